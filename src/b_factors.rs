@@ -0,0 +1,144 @@
+use crate::structs::*;
+
+/// The thresholds used by `validate_b_factors`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BFactorThresholds {
+    /// The number of standard deviations above the mean of its neighbourhood at which an Atom is an outlier
+    outlier_deviations: f64,
+    /// The number of Residues on either side of a Residue making up its neighbourhood
+    neighbourhood: usize,
+}
+
+impl BFactorThresholds {
+    /// Create new B-factor thresholds
+    /// ## Arguments
+    /// * `outlier_deviations` - the number of standard deviations above the mean B-factor of its neighbourhood at which an Atom is reported as outlier, for example 3.0
+    /// * `neighbourhood` - the number of Residues on either side of the Residue of an Atom that make up its neighbourhood, together with the Residue itself
+    /// ## Panics
+    /// It panics if `outlier_deviations` is not finite or not positive.
+    pub fn new(outlier_deviations: f64, neighbourhood: usize) -> BFactorThresholds {
+        assert!(
+            outlier_deviations.is_finite() && outlier_deviations > 0.0,
+            "The number of standard deviations is not finite or not positive"
+        );
+        BFactorThresholds {
+            outlier_deviations,
+            neighbourhood,
+        }
+    }
+
+    /// Get the number of standard deviations above the mean of its neighbourhood at which an Atom is an outlier
+    pub fn outlier_deviations(&self) -> f64 {
+        self.outlier_deviations
+    }
+
+    /// Get the number of Residues on either side of a Residue making up its neighbourhood
+    pub fn neighbourhood(&self) -> usize {
+        self.neighbourhood
+    }
+}
+
+impl Default for BFactorThresholds {
+    /// Outliers at three standard deviations, with a neighbourhood of two Residues on either side
+    fn default() -> Self {
+        BFactorThresholds::new(3.0, 2)
+    }
+}
+
+/// The distribution of the B-factors of a single Chain, see `b_factor_statistics`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BFactorStatistics {
+    /// The serial number of the Model
+    model_serial_number: usize,
+    /// The id of the Chain
+    chain_id: char,
+    /// The B-factors of all Atoms, in the order of the Chain
+    b_factors: Vec<f64>,
+    /// The mean B-factor
+    mean: f64,
+    /// The (population) standard deviation of the B-factors
+    standard_deviation: f64,
+}
+
+impl BFactorStatistics {
+    /// Calculate the statistics of the given Chain
+    pub(crate) fn of(model: &Model, chain: &Chain) -> BFactorStatistics {
+        let b_factors = chain.atoms().map(Atom::b_factor).collect::<Vec<f64>>();
+        let (mean, standard_deviation) = mean_and_deviation(&b_factors);
+        BFactorStatistics {
+            model_serial_number: model.serial_number(),
+            chain_id: chain.id(),
+            b_factors,
+            mean,
+            standard_deviation,
+        }
+    }
+
+    /// Get the serial number of the Model
+    pub fn model_serial_number(&self) -> usize {
+        self.model_serial_number
+    }
+
+    /// Get the id of the Chain
+    pub fn chain_id(&self) -> char {
+        self.chain_id
+    }
+
+    /// Get the B-factors of all Atoms of the Chain, in the order of the Chain, for example to plot
+    /// their distribution
+    pub fn b_factors(&self) -> &[f64] {
+        &self.b_factors
+    }
+
+    /// Get the number of Atoms
+    pub fn count(&self) -> usize {
+        self.b_factors.len()
+    }
+
+    /// Get the mean B-factor, zero for a Chain without Atoms
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Get the (population) standard deviation of the B-factors, zero for a Chain without Atoms
+    pub fn standard_deviation(&self) -> f64 {
+        self.standard_deviation
+    }
+
+    /// Get the lowest B-factor, if the Chain has any Atoms
+    pub fn min(&self) -> Option<f64> {
+        self.b_factors.iter().copied().reduce(f64::min)
+    }
+
+    /// Get the highest B-factor, if the Chain has any Atoms
+    pub fn max(&self) -> Option<f64> {
+        self.b_factors.iter().copied().reduce(f64::max)
+    }
+
+    /// Get if all B-factors are identical, which is only possible with at least two Atoms
+    pub fn all_identical(&self) -> bool {
+        self.count() > 1 && self.b_factors.iter().all(|b| *b == self.b_factors[0])
+    }
+}
+
+/// Get the distribution of the B-factors of every Chain (Normal and Hetero) in every Model, as
+/// used by `validate_b_factors`, for example to plot them
+pub fn b_factor_statistics(pdb: &PDB) -> Vec<BFactorStatistics> {
+    pdb.models()
+        .flat_map(|model| {
+            model
+                .all_chains()
+                .map(move |chain| BFactorStatistics::of(model, chain))
+        })
+        .collect()
+}
+
+/// Get the mean and (population) standard deviation of the given values, both zero without values
+pub(crate) fn mean_and_deviation(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    (mean, variance.sqrt())
+}
@@ -0,0 +1,90 @@
+use crate::error::*;
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// The number of lines read or written between two checks of a CancellationToken
+pub(crate) const CANCELLATION_INTERVAL: usize = 1000;
+
+/// The short description of the error given when reading or saving is cancelled, see
+/// `PDBError::is_cancellation`
+pub(crate) const CANCELLED: &str = "Cancelled";
+
+/// A token to cancel long running parsing or saving from another thread, for example from a GUI
+/// or a service. Clones share the same flag, so give a clone to the `ReadOptions` or `SaveOptions`
+/// and call `cancel` on the original. The flag is checked every thousand lines, when it is set
+/// the operation stops with a `BreakingError` for which `PDBError::is_cancellation` is `true`.
+///
+/// ```
+/// use pdbtbx::*;
+/// let token = CancellationToken::new();
+/// let mut options = ReadOptions::new();
+/// options.set_cancellation_token(Some(token.clone()));
+/// token.cancel();
+/// let error = parse_with_options("example-pdbs/1ubq.pdb", &options).unwrap_err();
+/// assert!(error.is_cancellation());
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    /// The flag set when cancelling
+    flag: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a new token that is not cancelled
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancel the operations using this token, or any of its clones
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Get if this token is cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+}
+
+impl From<Arc<AtomicBool>> for CancellationToken {
+    /// Use an existing flag as token, setting the flag cancels the operations using the token
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        CancellationToken { flag }
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CancellationToken({})", self.is_cancelled())
+    }
+}
+
+impl PartialEq for CancellationToken {
+    /// Tokens are equal if they share the same flag
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.flag, &other.flag)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+/// Get if the operation using the given token should stop before handling the given line (0 based)
+pub(crate) fn should_cancel(token: Option<&CancellationToken>, line: usize) -> bool {
+    line.is_multiple_of(CANCELLATION_INTERVAL) && token.is_some_and(|token| token.is_cancelled())
+}
+
+/// Create the error for a cancelled operation
+/// ## Arguments
+/// * `operation` - the operation that was cancelled, eg "Parsing"
+/// * `lines` - the number of lines handled before cancelling
+/// * `context` - the context, generally the file name
+pub(crate) fn cancelled_error(operation: &str, lines: usize, context: PDBContext) -> PDBError {
+    PDBError::new(
+        ErrorLevel::BreakingError,
+        CANCELLED,
+        &format!("{} was cancelled after {} lines.", operation, lines),
+        context,
+    )
+}
@@ -0,0 +1,83 @@
+use crate::read::Format;
+use std::fmt;
+
+/// The capabilities of the linked build of pdbtbx, to discover at runtime which optional features
+/// are enabled and which formats can be read, for example for the help text of a command line tool.
+/// Features that are not enabled are absent from the API, so using them is a compile error rather
+/// than a silent change in behaviour. Get the capabilities with `capabilities`.
+///
+/// ```
+/// use pdbtbx::*;
+/// let capabilities = capabilities();
+/// assert!(capabilities.can_read(Format::Pdb));
+/// println!("{}", capabilities);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// If the `serde` feature is enabled
+    serde: bool,
+}
+
+impl Capabilities {
+    /// Get the version of pdbtbx, as given in its manifest
+    pub fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    /// Get if the `serde` feature is enabled, deriving `Serialize` and `Deserialize` for the
+    /// structs of the PDB hierarchy
+    pub fn serde(&self) -> bool {
+        self.serde
+    }
+
+    /// Get the names of the enabled features, as used in the manifest
+    pub fn features(&self) -> Vec<&'static str> {
+        [("serde", self.serde)]
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    /// Get the formats that can be read with `open` and `open_with_format`, see `Format::is_readable`
+    pub fn readable_formats(&self) -> Vec<Format> {
+        Format::ALL
+            .iter()
+            .copied()
+            .filter(Format::is_readable)
+            .collect()
+    }
+
+    /// Get if files of the given format can be read, see `Format::is_readable`
+    pub fn can_read(&self, format: Format) -> bool {
+        format.is_readable()
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let features = self.features();
+        write!(
+            f,
+            "pdbtbx {}, features: {}, readable formats: {}",
+            self.version(),
+            if features.is_empty() {
+                "none".to_string()
+            } else {
+                features.join(", ")
+            },
+            self.readable_formats()
+                .iter()
+                .map(|format| format.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+/// Get the capabilities of the linked build of pdbtbx, see `Capabilities`
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        serde: cfg!(feature = "serde"),
+    }
+}
@@ -0,0 +1,76 @@
+use crate::structs::EntityType;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// The residue names registered at runtime with their classification
+static REGISTERED: RwLock<BTreeMap<String, EntityType>> = RwLock::new(BTreeMap::new());
+
+/// The classification of Residues by their name, see `EntityType::from_residue_name`. By default
+/// this uses the built in reference tables, which only know the names used by the PDB. Files
+/// from simulation packages often use other names, for example `SOL`, `TIP3` or `SPC` for water
+/// and `SOD` or `CLA` for ions in CHARMM. These names can be registered here, after which they are
+/// recognised everywhere Residues are classified, like `Residue::entity_type`, `Model::remove_waters`
+/// and `Model::deduplicate_waters`.
+///
+/// The registered names are shared by the whole program. A registered name takes precedence
+/// over the reference tables. Residue names are stored with at most three characters, as in the
+/// PDB format, so a longer name (like `TIP3`) is read as its first three characters (`TIP`) and
+/// is registered as such.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Classifier;
+
+impl Classifier {
+    /// Register a residue name with the given classification, replacing any previous registration
+    /// of this name. It returns the previous classification, if the name was registered before.
+    /// ## Fails
+    /// It fails if the name is empty, longer than four characters or contains characters that are not ASCII alphanumeric.
+    pub fn register(name: &str, entity: EntityType) -> Result<Option<EntityType>, String> {
+        let name = name.trim();
+        if name.is_empty()
+            || name.chars().count() > 4
+            || !name.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(format!(
+                "The residue name \"{}\" is invalid, it should be one to four ASCII alphanumeric characters",
+                name
+            ));
+        }
+        Ok(REGISTERED
+            .write()
+            .unwrap()
+            .insert(stored_name(name), entity))
+    }
+
+    /// Remove the registration of a residue name, it returns its classification if it was registered
+    pub fn unregister(name: &str) -> Option<EntityType> {
+        REGISTERED
+            .write()
+            .unwrap()
+            .remove(&stored_name(name.trim()))
+    }
+
+    /// Remove all registered residue names, so only the reference tables are used again
+    pub fn clear() {
+        REGISTERED.write().unwrap().clear();
+    }
+
+    /// Get all registered residue names, as they are matched, with their classification
+    pub fn registered() -> Vec<(String, EntityType)> {
+        REGISTERED
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, entity)| (name.clone(), *entity))
+            .collect()
+    }
+
+    /// Get the classification of the given residue name if it was registered
+    pub(crate) fn lookup(name: &str) -> Option<EntityType> {
+        REGISTERED.read().unwrap().get(name).copied()
+    }
+}
+
+/// The name as stored in a Residue, its first three characters
+fn stored_name(name: &str) -> String {
+    name.chars().take(3).collect()
+}
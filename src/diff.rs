@@ -0,0 +1,290 @@
+use crate::structs::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// The minimal change in B-factor or occupancy to be reported, to ignore floating point noise
+const VALUE_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The differences between two structures, as found by `diff`. Residues are identified by their
+/// chain id, serial number and insertion code (see `ResidueId`), so a shift in numbering shows up as removed and added Residues
+/// (or as renamed Residues). Atoms are identified by their AtomId, using the serial number of the
+/// Model of the structure they are found in (the first for removed and changed Atoms).
+pub struct StructureDiff {
+    /// The ids of the Chains only present in the second structure
+    added_chains: Vec<char>,
+    /// The ids of the Chains only present in the first structure
+    removed_chains: Vec<char>,
+    /// The Residues only present in the second structure, as (residue, name)
+    added_residues: Vec<(ResidueId, String)>,
+    /// The Residues only present in the first structure, as (residue, name)
+    removed_residues: Vec<(ResidueId, String)>,
+    /// The Residues with a different name at the same position, as (residue, name in first, name in second)
+    renamed_residues: Vec<(ResidueId, String, String)>,
+    /// The Atoms only present in the second structure
+    added_atoms: Vec<AtomId>,
+    /// The Atoms only present in the first structure
+    removed_atoms: Vec<AtomId>,
+    /// The Atoms that moved more than the tolerance, with their displacement in Å
+    moved_atoms: Vec<(AtomId, f64)>,
+    /// The Atoms with a changed B-factor, as (atom, first, second)
+    changed_b_factors: Vec<(AtomId, f64, f64)>,
+    /// The Atoms with a changed occupancy, as (atom, first, second)
+    changed_occupancies: Vec<(AtomId, f64, f64)>,
+    /// The number of Atoms present in both structures
+    matched_atoms: usize,
+    /// The maximal displacement of the Atoms present in both structures in Å
+    max_displacement: f64,
+    /// The root mean square displacement of the Atoms present in both structures in Å
+    rms_displacement: f64,
+}
+
+/// Find the differences between the first Model of two structures, for example to review what a
+/// preparation script changed. All Chains (Normal and Hetero) are compared. Atoms are matched on
+/// their name and alternate location within Residues with the same name at the same position.
+/// ## Arguments
+/// * `a` - the first (original) structure
+/// * `b` - the second (changed) structure
+/// * `tolerance` - the displacement in Å above which an Atom is reported as moved
+pub fn diff(a: &PDB, b: &PDB, tolerance: f64) -> StructureDiff {
+    let mut output = StructureDiff::default();
+    let (model_a, residues_a) = residue_map(a);
+    let (model_b, residues_b) = residue_map(b);
+    let atom_id = |model: usize, residue: ResidueId, atom: &Atom| {
+        AtomId::new(model, residue, &atom.name(), atom.alternate_location())
+    };
+
+    let chains_a = residues_a
+        .keys()
+        .map(ResidueId::chain_id)
+        .collect::<BTreeSet<char>>();
+    let chains_b = residues_b
+        .keys()
+        .map(ResidueId::chain_id)
+        .collect::<BTreeSet<char>>();
+    output.added_chains = chains_b.difference(&chains_a).copied().collect();
+    output.removed_chains = chains_a.difference(&chains_b).copied().collect();
+
+    let mut squared_sum = 0.0;
+    for (&id, residue_a) in &residues_a {
+        let residue_b = match residues_b.get(&id) {
+            Some(residue) => residue,
+            None => {
+                output.removed_residues.push((id, residue_a.id()));
+                continue;
+            }
+        };
+        if residue_a.id() != residue_b.id() {
+            output
+                .renamed_residues
+                .push((id, residue_a.id(), residue_b.id()));
+            continue;
+        }
+        for atom_a in residue_a.atoms() {
+            let atom_b = match residue_b.atoms().find(|atom| same_atom(atom_a, atom)) {
+                Some(atom) => atom,
+                None => {
+                    output.removed_atoms.push(atom_id(model_a, id, atom_a));
+                    continue;
+                }
+            };
+            let displacement = atom_a.distance(atom_b);
+            output.matched_atoms += 1;
+            squared_sum += displacement * displacement;
+            output.max_displacement = output.max_displacement.max(displacement);
+            if displacement > tolerance {
+                output
+                    .moved_atoms
+                    .push((atom_id(model_a, id, atom_a), displacement));
+            }
+            if (atom_a.b_factor() - atom_b.b_factor()).abs() > VALUE_TOLERANCE {
+                output.changed_b_factors.push((
+                    atom_id(model_a, id, atom_a),
+                    atom_a.b_factor(),
+                    atom_b.b_factor(),
+                ));
+            }
+            if (atom_a.occupancy() - atom_b.occupancy()).abs() > VALUE_TOLERANCE {
+                output.changed_occupancies.push((
+                    atom_id(model_a, id, atom_a),
+                    atom_a.occupancy(),
+                    atom_b.occupancy(),
+                ));
+            }
+        }
+        for atom_b in residue_b.atoms() {
+            if !residue_a.atoms().any(|atom| same_atom(atom, atom_b)) {
+                output.added_atoms.push(atom_id(model_b, id, atom_b));
+            }
+        }
+    }
+    for (&id, residue_b) in &residues_b {
+        if !residues_a.contains_key(&id) {
+            output.added_residues.push((id, residue_b.id()));
+        }
+    }
+    if output.matched_atoms > 0 {
+        output.rms_displacement = (squared_sum / output.matched_atoms as f64).sqrt();
+    }
+    output
+}
+
+/// Get the serial number of the first Model of the given PDB and its Residues (Normal and Hetero)
+/// by chain id, serial number and insertion code
+fn residue_map(pdb: &PDB) -> (usize, BTreeMap<ResidueId, &Residue>) {
+    let mut output = BTreeMap::new();
+    let mut serial_number = 0;
+    if let Some(model) = pdb.model(0) {
+        serial_number = model.serial_number();
+        for chain in model.all_chains() {
+            for residue in chain.residues() {
+                output
+                    .entry(ResidueId::from_hierarchy(chain, residue))
+                    .or_insert(residue);
+            }
+        }
+    }
+    (serial_number, output)
+}
+
+/// Format a Residue as its chain id directly followed by its serial number and insertion code,
+/// like `A100B`
+fn residue_label(residue: &ResidueId) -> String {
+    format!("{}{}", residue.chain_id(), residue.numbering_label())
+}
+
+/// Check if two Atoms are the same Atom, so have the same name and alternate location
+fn same_atom(a: &Atom, b: &Atom) -> bool {
+    a.name() == b.name() && a.alternate_location() == b.alternate_location()
+}
+
+impl StructureDiff {
+    /// Returns `true` if no differences were found
+    pub fn is_empty(&self) -> bool {
+        self.added_chains.is_empty()
+            && self.removed_chains.is_empty()
+            && self.added_residues.is_empty()
+            && self.removed_residues.is_empty()
+            && self.renamed_residues.is_empty()
+            && self.added_atoms.is_empty()
+            && self.removed_atoms.is_empty()
+            && self.moved_atoms.is_empty()
+            && self.changed_b_factors.is_empty()
+            && self.changed_occupancies.is_empty()
+    }
+
+    /// The ids of the Chains only present in the second structure
+    pub fn added_chains(&self) -> &[char] {
+        &self.added_chains
+    }
+
+    /// The ids of the Chains only present in the first structure
+    pub fn removed_chains(&self) -> &[char] {
+        &self.removed_chains
+    }
+
+    /// The Residues only present in the second structure, as (residue, name)
+    pub fn added_residues(&self) -> &[(ResidueId, String)] {
+        &self.added_residues
+    }
+
+    /// The Residues only present in the first structure, as (residue, name)
+    pub fn removed_residues(&self) -> &[(ResidueId, String)] {
+        &self.removed_residues
+    }
+
+    /// The Residues with a different name at the same position, as (residue, name in first, name in second)
+    pub fn renamed_residues(&self) -> &[(ResidueId, String, String)] {
+        &self.renamed_residues
+    }
+
+    /// The Atoms only present in the second structure
+    pub fn added_atoms(&self) -> &[AtomId] {
+        &self.added_atoms
+    }
+
+    /// The Atoms only present in the first structure
+    pub fn removed_atoms(&self) -> &[AtomId] {
+        &self.removed_atoms
+    }
+
+    /// The Atoms that moved more than the tolerance, with their displacement in Å
+    pub fn moved_atoms(&self) -> &[(AtomId, f64)] {
+        &self.moved_atoms
+    }
+
+    /// The Atoms with a changed B-factor, as (atom, first, second)
+    pub fn changed_b_factors(&self) -> &[(AtomId, f64, f64)] {
+        &self.changed_b_factors
+    }
+
+    /// The Atoms with a changed occupancy, as (atom, first, second)
+    pub fn changed_occupancies(&self) -> &[(AtomId, f64, f64)] {
+        &self.changed_occupancies
+    }
+
+    /// The number of Atoms present in both structures
+    pub fn matched_atoms(&self) -> usize {
+        self.matched_atoms
+    }
+
+    /// The maximal displacement of the Atoms present in both structures in Å, 0.0 if there are none
+    pub fn max_displacement(&self) -> f64 {
+        self.max_displacement
+    }
+
+    /// The root mean square displacement of the Atoms present in both structures in Å, 0.0 if there are none
+    pub fn rms_displacement(&self) -> f64 {
+        self.rms_displacement
+    }
+}
+
+impl fmt::Display for StructureDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            writeln!(f, "No differences")?;
+        }
+        for chain in &self.added_chains {
+            writeln!(f, "+ chain {}", chain)?;
+        }
+        for chain in &self.removed_chains {
+            writeln!(f, "- chain {}", chain)?;
+        }
+        for (residue, name) in &self.added_residues {
+            writeln!(f, "+ residue {} {}", residue_label(residue), name)?;
+        }
+        for (residue, name) in &self.removed_residues {
+            writeln!(f, "- residue {} {}", residue_label(residue), name)?;
+        }
+        for (residue, first, second) in &self.renamed_residues {
+            writeln!(
+                f,
+                "~ residue {} {} -> {}",
+                residue_label(residue),
+                first,
+                second
+            )?;
+        }
+        for atom in &self.added_atoms {
+            writeln!(f, "+ atom {}", atom)?;
+        }
+        for atom in &self.removed_atoms {
+            writeln!(f, "- atom {}", atom)?;
+        }
+        for (atom, displacement) in &self.moved_atoms {
+            writeln!(f, "~ atom {} moved {:.3} Å", atom, displacement)?;
+        }
+        for (atom, first, second) in &self.changed_b_factors {
+            writeln!(f, "~ atom {} B-factor {:.2} -> {:.2}", atom, first, second)?;
+        }
+        for (atom, first, second) in &self.changed_occupancies {
+            writeln!(f, "~ atom {} occupancy {:.2} -> {:.2}", atom, first, second)?;
+        }
+        write!(
+            f,
+            "{} atoms matched, max displacement {:.3} Å, RMS displacement {:.3} Å",
+            self.matched_atoms, self.max_displacement, self.rms_displacement
+        )
+    }
+}
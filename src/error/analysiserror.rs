@@ -0,0 +1,43 @@
+use crate::structs::ResidueId;
+use std::error;
+use std::fmt;
+
+/// An error surfacing while analysing a structure, for analyses that cannot give a meaningful
+/// result for the given input
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AnalysisError {
+    /// The Chain with the given id only contains CA atoms (see `Chain::is_ca_only`), while the
+    /// analysis needs the full backbone or all heavy atoms
+    CaOnlyChain(char),
+    /// The Residue with the given id could not be found
+    ResidueNotFound(ResidueId),
+    /// The Residues of the Chain with the given id are not ordered by serial number, the Residue
+    /// with the given serial number is numbered lower than the Residue before it (see
+    /// `Chain::first_unordered_residue`), while the analysis follows the stored order
+    UnorderedResidues(char, usize),
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::CaOnlyChain(chain_id) => write!(
+                f,
+                "Chain {} only contains CA atoms, this analysis needs all backbone and side chain atoms",
+                chain_id
+            ),
+            AnalysisError::ResidueNotFound(residue) => write!(
+                f,
+                "Residue {} of chain {} could not be found",
+                residue.numbering_label(),
+                residue.chain_id()
+            ),
+            AnalysisError::UnorderedResidues(chain_id, serial_number) => write!(
+                f,
+                "The Residues of chain {} are not ordered by serial number, residue {} is numbered lower than the residue before it",
+                chain_id, serial_number
+            ),
+        }
+    }
+}
+
+impl error::Error for AnalysisError {}
@@ -1,64 +1,130 @@
 use std::fmt;
 
-/// A struct to define the context of an error message
+/// The maximal number of characters of a line kept in a context, longer lines are cut to an
+/// excerpt of this length around the annotated position
+const MAX_EXCERPT_LENGTH: usize = 100;
+
+/// The marker shown where a line is cut to an excerpt
+const ELLIPSIS: &str = "...";
+
+/// A struct to define the context of an error message. Lines longer than 100 characters are not
+/// stored in full, only an excerpt around the annotated position is kept, so the many warnings
+/// of a large, sloppy file do not hold copies of all of its text.
 #[derive(Debug, Clone)]
-pub enum Context {
+pub enum PDBContext {
     /// When no context can be given
     None,
     /// When only a line (eg filename) can be shown
     Show { line: String },
     /// When a full line is faulty and no special position can be annotated
-    FullLine { linenumber: usize, line: String },
+    FullLine { linenumber: usize, line: Box<str> },
+    /// When only the number of the line is known, for example for an Atom after parsing
+    LineNumber { linenumber: usize },
     /// When a special position can be annotated on a line
+    /// The line is an excerpt for long lines, the offset is relative to the excerpt.
     Line {
         linenumber: usize,
-        line: String,
+        line: Box<str>,
         offset: usize,
         length: usize,
     },
 }
 
-impl Context {
+impl PDBContext {
     /// Creates a new context when no context can be given
-    pub fn none() -> Context {
-        Context::None
+    pub fn none() -> PDBContext {
+        PDBContext::None
     }
 
     /// Creates a new context when only a line (eg filename) can be shown
-    pub fn show(line: &str) -> Context {
-        Context::Show {
+    pub fn show(line: &str) -> PDBContext {
+        PDBContext::Show {
             line: line.to_string(),
         }
     }
 
-    /// Creates a new context when a full line is faulty and no special position can be annotated
-    pub fn full_line(linenumber: usize, line: &str) -> Context {
-        Context::FullLine {
-            linenumber,
-            line: line.to_owned(),
-        }
+    /// Creates a new context when a full line is faulty and no special position can be annotated.
+    /// Of a line longer than 100 characters only the start is kept.
+    pub fn full_line(linenumber: usize, line: &str) -> PDBContext {
+        let (line, _, _) = excerpt(line, 0, 0);
+        PDBContext::FullLine { linenumber, line }
     }
 
-    /// Creates a new context when a special position can be annotated on a line
-    pub fn line(linenumber: usize, line: &str, offset: usize, length: usize) -> Context {
-        Context::Line {
+    /// Creates a new context when a special position can be annotated on a line. Of a line longer
+    /// than 100 characters only an excerpt around the annotated position is kept.
+    pub fn line(linenumber: usize, line: &str, offset: usize, length: usize) -> PDBContext {
+        let (line, offset, length) = excerpt(line, offset, length);
+        PDBContext::Line {
             linenumber,
-            line: line.to_string(),
+            line,
             offset,
             length,
         }
     }
+
+    /// Creates a new context when only the number of the line is known
+    pub fn line_number(linenumber: usize) -> PDBContext {
+        PDBContext::LineNumber { linenumber }
+    }
+
+    /// Get the line of text this context refers to, if available
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            PDBContext::None => None,
+            PDBContext::Show { line } => Some(line),
+            PDBContext::LineNumber { .. } => None,
+            PDBContext::FullLine { line, .. } => Some(line),
+            PDBContext::Line { line, .. } => Some(line),
+        }
+    }
+
+    /// Get the line number this context refers to, if available
+    pub fn linenumber(&self) -> Option<usize> {
+        match self {
+            PDBContext::FullLine { linenumber, .. } => Some(*linenumber),
+            PDBContext::LineNumber { linenumber } => Some(*linenumber),
+            PDBContext::Line { linenumber, .. } => Some(*linenumber),
+            _ => None,
+        }
+    }
 }
 
-impl fmt::Display for Context {
+/// Cut the given line to an excerpt of at most `MAX_EXCERPT_LENGTH` characters (plus the markers
+/// where it is cut) around the annotated position, a line that is short enough is kept as is. It
+/// returns the excerpt with the offset and length of the annotated position in the excerpt.
+fn excerpt(line: &str, offset: usize, length: usize) -> (Box<str>, usize, usize) {
+    let total = line.chars().count();
+    if total <= MAX_EXCERPT_LENGTH {
+        return (line.into(), offset, length);
+    }
+    let length = length.min(MAX_EXCERPT_LENGTH);
+    let start = offset.saturating_sub((MAX_EXCERPT_LENGTH - length) / 2);
+    let end = (start + MAX_EXCERPT_LENGTH).min(total);
+    let start = end - MAX_EXCERPT_LENGTH;
+    let mut text = String::with_capacity(MAX_EXCERPT_LENGTH + 2 * ELLIPSIS.len());
+    if start > 0 {
+        text.push_str(ELLIPSIS);
+    }
+    let offset = offset - start + text.len();
+    text.extend(line.chars().skip(start).take(MAX_EXCERPT_LENGTH));
+    if end < total {
+        text.push_str(ELLIPSIS);
+    }
+    (text.into_boxed_str(), offset, length)
+}
+
+impl fmt::Display for PDBContext {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Context::None => write!(f, ""),
-            Context::Show { line } => write!(f, "\n     |\n     | {}\n     |\n", line),
-            Context::FullLine { linenumber, line } => {
+            PDBContext::None => write!(f, ""),
+            PDBContext::Show { line } => write!(f, "\n     |\n     | {}\n     |\n", line),
+            PDBContext::LineNumber { linenumber } => {
+                write!(f, "\n     |\n{:<4} |\n     |\n", linenumber)
+            }
+            PDBContext::FullLine { linenumber, line } => {
                 write!(f, "\n     |\n{:<4} | {}\n     |\n", linenumber, line)
             }
-            Context::Line {
+            PDBContext::Line {
                 linenumber,
                 line,
                 offset,
@@ -74,3 +140,43 @@ impl fmt::Display for Context {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_line() {
+        let line = "ATOM      1  N   MET A   1      27.340  24.430   2.614  1.00  9.67           N";
+        let context = PDBContext::line(3, line, 30, 8);
+        assert_eq!(context.text(), Some(line));
+        assert_eq!(
+            context.to_string(),
+            format!(
+                "\n     |\n3    | {}\n     | {}^^^^^^^^\n",
+                line,
+                " ".repeat(30)
+            )
+        );
+    }
+
+    #[test]
+    fn long_line() {
+        let line = format!("REMARK   2 {}ERROR{}", "A".repeat(500), "B".repeat(500));
+        let context = PDBContext::line(1, &line, 511, 5);
+        let text = context.text().unwrap();
+        assert_eq!(text.len(), MAX_EXCERPT_LENGTH + 2 * ELLIPSIS.len());
+        assert!(text.starts_with("...A") && text.ends_with("B..."));
+        if let PDBContext::Line { offset, length, .. } = context {
+            assert_eq!(&text[offset..offset + length], "ERROR");
+        } else {
+            panic!("Not a line context");
+        }
+
+        let context = PDBContext::line(1, &line, 1005, 10);
+        let text = context.text().unwrap();
+        assert!(text.starts_with("...") && text.ends_with("BBB"));
+        let context = PDBContext::full_line(1, &line);
+        assert!(context.text().unwrap().starts_with("REMARK   2 AAA"));
+    }
+}
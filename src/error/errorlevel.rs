@@ -1,7 +1,7 @@
 use std::fmt;
 
 /// This indicates the level of the error, to handle it differently based on the level of the raised error.
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ErrorLevel {
     /// An error that breaks the execution of the program.
     BreakingError,
@@ -30,6 +30,28 @@ impl ErrorLevel {
             ErrorLevel::GeneralWarning => "Warning",
         }
     }
+
+    /// Get the severity of this ErrorLevel, from 0 for a GeneralWarning up to 4 for a BreakingError
+    pub(crate) fn severity(&self) -> usize {
+        match self {
+            ErrorLevel::BreakingError => 4,
+            ErrorLevel::InvalidatingError => 3,
+            ErrorLevel::StrictWarning => 2,
+            ErrorLevel::LooseWarning => 1,
+            ErrorLevel::GeneralWarning => 0,
+        }
+    }
+
+    /// Get a unique lowercase name for this ErrorLevel, usable as an identifier in other programs.
+    pub fn name(&self) -> &str {
+        match self {
+            ErrorLevel::BreakingError => "breaking_error",
+            ErrorLevel::InvalidatingError => "invalidating_error",
+            ErrorLevel::StrictWarning => "strict_warning",
+            ErrorLevel::LooseWarning => "loose_warning",
+            ErrorLevel::GeneralWarning => "general_warning",
+        }
+    }
 }
 
 impl fmt::Display for ErrorLevel {
@@ -1,7 +1,9 @@
+mod analysiserror;
 mod context;
 mod errorlevel;
 mod parseerror;
 
-pub use context::Context;
+pub use analysiserror::AnalysisError;
+pub use context::PDBContext;
 pub use errorlevel::ErrorLevel;
 pub use parseerror::PDBError;
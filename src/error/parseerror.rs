@@ -1,5 +1,6 @@
-use super::Context;
 use super::ErrorLevel;
+use super::PDBContext;
+use crate::cancellation::CANCELLED;
 use std::error;
 use std::fmt;
 
@@ -13,7 +14,7 @@ pub struct PDBError {
     /// A longer description of the error, presented below the context to give more information and helpful feedback
     long_description: String,
     /// The context, in the most general sense this produces output which leads the user to the right place in the code or file
-    context: Context,
+    context: PDBContext,
 }
 
 impl PDBError {
@@ -28,7 +29,7 @@ impl PDBError {
         level: ErrorLevel,
         short_desc: &str,
         long_descr: &str,
-        context: Context,
+        context: PDBContext,
     ) -> PDBError {
         PDBError {
             level,
@@ -42,6 +43,27 @@ impl PDBError {
     pub fn level(&self) -> ErrorLevel {
         self.level
     }
+
+    /// A short description of the error, generally used as title line
+    pub fn short_description(&self) -> &str {
+        &self.short_description
+    }
+
+    /// A longer description of the error, presented below the context to give more information and helpful feedback
+    pub fn long_description(&self) -> &str {
+        &self.long_description
+    }
+
+    /// The context, in the most general sense this produces output which leads the user to the right place in the code or file
+    pub fn context(&self) -> &PDBContext {
+        &self.context
+    }
+
+    /// Get if this error was given because reading or saving was cancelled with a
+    /// `CancellationToken`. The long description gives the number of lines handled before.
+    pub fn is_cancellation(&self) -> bool {
+        self.level == ErrorLevel::BreakingError && self.short_description == CANCELLED
+    }
 }
 
 impl fmt::Display for PDBError {
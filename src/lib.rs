@@ -23,8 +23,8 @@
 //! to a file.
 //!
 //! ```
-//! use pdbtbx;
-//! let (mut pdb, _errors) = pdbtbx::parse("example-pdbs/1ubq.pdb").unwrap();
+//! use pdbtbx::prelude::*;
+//! let (mut pdb, _errors) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
 //! pdb.remove_atoms_by(|atom| atom.element() == "H"); // Remove all H atoms
 //!
 //! let mut avg_b_factor = 0.0;
@@ -34,20 +34,82 @@
 //! avg_b_factor /= pdb.atom_count() as f64;
 //!
 //! println!("The average B factor of the protein is: {}", avg_b_factor);
-//! pdbtbx::save(&pdb, "dump/1ubq.pdb");
+//! save_pdb(&pdb, "dump/1ubq.pdb");
 //! ```
+//!
+//! The `prelude` contains the commonly used types and functions, its names are unlikely to clash
+//! with the names of other crates. Everything else is available at the root of the crate.
 
+mod b_factors;
+mod cancellation;
+mod capabilities;
+mod classifier;
+mod diff;
 mod error;
+mod pipelines;
+mod pocket;
+pub mod prelude;
+mod pymol;
 mod read;
 mod reference_tables;
+mod residue_table;
+mod sasa;
 mod save;
+mod save_options;
+mod serial_overflow;
 mod structs;
 mod transformation;
 mod validate;
+mod validation_profile;
 
+pub use b_factors::{b_factor_statistics, BFactorStatistics, BFactorThresholds};
+pub use cancellation::CancellationToken;
+pub use capabilities::{capabilities, Capabilities};
+pub use classifier::Classifier;
+pub use diff::{diff, StructureDiff};
 pub use error::*;
-pub use read::parse;
-pub use save::save;
+pub use pipelines::{
+    clean_for_md, isolate_ligand_and_pocket, prepare_for_docking, DockingOptions, MdOptions,
+    PipelineAction, PipelineReport,
+};
+pub use pocket::{Pocket, PocketParameters};
+pub use read::{
+    metadata_remark_hook, open, open_with_format, parse_header, parse_with_metadata_hooks,
+    parse_with_options, read_pdb, vina_result_hook, Format, MetadataHook, ReadOptions,
+};
+pub use residue_table::ResidueTable;
+pub use sasa::SasaParameters;
+pub use save::{save_filtered, save_pdb, save_with_options, save_with_policy, PdbWriter};
+pub use save_options::{Dialect, SaveOptions, TerPolicy};
+pub use serial_overflow::SerialOverflowPolicy;
 pub use structs::*;
 pub use transformation::*;
-pub use validate::validate;
+pub use validate::{
+    validate_anisotropic_factors, validate_b_factors, validate_cis_peptides,
+    validate_conformer_occupancies, validate_hetatm_completeness, validate_hetatm_has_conect,
+    validate_incremental, validate_pdb, validate_residue_numbering, validate_solvent_numbering,
+    validate_with,
+};
+pub use validation_profile::{ValidationCheck, ValidationPreset, ValidationProfile};
+
+/// The context of an error message, renamed to `PDBContext`
+#[deprecated(since = "0.1.6", note = "renamed to `PDBContext`")]
+pub type Context = PDBContext;
+
+/// Parse the given filename into a PDB struct, renamed to `read_pdb`
+#[deprecated(since = "0.1.6", note = "renamed to `read_pdb`")]
+pub fn parse(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
+    read_pdb(filename)
+}
+
+/// Save the given PDB struct to the given file, renamed to `save_pdb`
+#[deprecated(since = "0.1.6", note = "renamed to `save_pdb`")]
+pub fn save(pdb: &PDB, filename: &str) -> Result<(), String> {
+    save_pdb(pdb, filename)
+}
+
+/// Validate a given PDB file, renamed to `validate_pdb`
+#[deprecated(since = "0.1.6", note = "renamed to `validate_pdb`")]
+pub fn validate(pdb: &PDB) -> Vec<PDBError> {
+    validate_pdb(pdb)
+}
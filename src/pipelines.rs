@@ -0,0 +1,497 @@
+use crate::save::guess_element;
+use crate::structs::*;
+use std::fmt;
+
+/// The length of the C-OXT bond in Å used when adding terminal oxygens
+const OXT_BOND_LENGTH: f64 = 1.25;
+
+/// The residue names used by force fields and simulation packages for specific protonation or
+/// bonding states, with the standard name of the Residue
+const RESIDUE_ALIASES: [(&str, &str); 13] = [
+    ("HID", "HIS"),
+    ("HIE", "HIS"),
+    ("HIP", "HIS"),
+    ("HSD", "HIS"),
+    ("HSE", "HIS"),
+    ("HSP", "HIS"),
+    ("CYX", "CYS"),
+    ("CYM", "CYS"),
+    ("ASH", "ASP"),
+    ("GLH", "GLU"),
+    ("LYN", "LYS"),
+    ("ARN", "ARG"),
+    ("TYM", "TYR"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The steps taken by `prepare_for_docking`, all steps are enabled by default
+pub struct DockingOptions {
+    /// If the water Residues should be removed
+    remove_waters: bool,
+    /// If only the conformer with the highest occupancy of every Residue should be kept
+    resolve_alternate_locations: bool,
+    /// If missing elements should be guessed
+    infer_elements: bool,
+    /// If the structure should be renumbered
+    renumber: bool,
+}
+
+impl Default for DockingOptions {
+    fn default() -> Self {
+        DockingOptions {
+            remove_waters: true,
+            resolve_alternate_locations: true,
+            infer_elements: true,
+            renumber: true,
+        }
+    }
+}
+
+impl DockingOptions {
+    /// Create new options with all steps enabled
+    pub fn new() -> DockingOptions {
+        DockingOptions::default()
+    }
+
+    /// Get if the water Residues are removed
+    pub fn remove_waters(&self) -> bool {
+        self.remove_waters
+    }
+
+    /// Set if the water Residues should be removed, see `PDB::remove_waters`
+    pub fn set_remove_waters(&mut self, remove: bool) {
+        self.remove_waters = remove;
+    }
+
+    /// Get if only the conformer with the highest occupancy of every Residue is kept
+    pub fn resolve_alternate_locations(&self) -> bool {
+        self.resolve_alternate_locations
+    }
+
+    /// Set if only the conformer with the highest occupancy of every Residue should be kept. The
+    /// kept Atoms lose their alternate location identifier.
+    pub fn set_resolve_alternate_locations(&mut self, resolve: bool) {
+        self.resolve_alternate_locations = resolve;
+    }
+
+    /// Get if missing elements are guessed
+    pub fn infer_elements(&self) -> bool {
+        self.infer_elements
+    }
+
+    /// Set if missing elements should be guessed, in the same way as when saving with
+    /// `Dialect::Gromacs`
+    pub fn set_infer_elements(&mut self, infer: bool) {
+        self.infer_elements = infer;
+    }
+
+    /// Get if the structure is renumbered
+    pub fn renumber(&self) -> bool {
+        self.renumber
+    }
+
+    /// Set if the structure should be renumbered as the last step, see `PDB::renumber`
+    pub fn set_renumber(&mut self, renumber: bool) {
+        self.renumber = renumber;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The steps taken by `clean_for_md`, all steps are enabled by default
+pub struct MdOptions {
+    /// If force field specific residue names should be replaced by the standard names
+    standardize_residue_names: bool,
+    /// If missing OXT atoms should be added to the C-terminal amino acids
+    add_terminal_oxygens: bool,
+    /// If the Chains should be sorted on their id
+    sort_chains: bool,
+}
+
+impl Default for MdOptions {
+    fn default() -> Self {
+        MdOptions {
+            standardize_residue_names: true,
+            add_terminal_oxygens: true,
+            sort_chains: true,
+        }
+    }
+}
+
+impl MdOptions {
+    /// Create new options with all steps enabled
+    pub fn new() -> MdOptions {
+        MdOptions::default()
+    }
+
+    /// Get if force field specific residue names are replaced by the standard names
+    pub fn standardize_residue_names(&self) -> bool {
+        self.standardize_residue_names
+    }
+
+    /// Set if force field specific residue names, like HIE or CYX, should be replaced by the
+    /// standard names, like HIS or CYS
+    pub fn set_standardize_residue_names(&mut self, standardize: bool) {
+        self.standardize_residue_names = standardize;
+    }
+
+    /// Get if missing OXT atoms are added to the C-terminal amino acids
+    pub fn add_terminal_oxygens(&self) -> bool {
+        self.add_terminal_oxygens
+    }
+
+    /// Set if missing OXT atoms should be added to the last amino acid of every Normal Chain. The
+    /// OXT atom is placed in the plane of the CA, C and O atoms, opposite of the other two bonds
+    /// of the C atom.
+    pub fn set_add_terminal_oxygens(&mut self, add: bool) {
+        self.add_terminal_oxygens = add;
+    }
+
+    /// Get if the Chains are sorted on their id
+    pub fn sort_chains(&self) -> bool {
+        self.sort_chains
+    }
+
+    /// Set if the Chains should be sorted on their id, see `Model::sort_chains`
+    pub fn set_sort_chains(&mut self, sort: bool) {
+        self.sort_chains = sort;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An action taken by one of the pipelines, the counts are summed over all Models
+pub enum PipelineAction {
+    /// The given number of water Residues was removed
+    RemovedWaters(usize),
+    /// Only the conformer with the highest occupancy was kept for the given number of Residues,
+    /// removing the given number of Atoms
+    ResolvedAlternateLocations {
+        /// The number of Residues with alternate locations
+        residues: usize,
+        /// The number of Atoms removed
+        removed_atoms: usize,
+    },
+    /// The element was guessed for the given number of Atoms
+    InferredElements(usize),
+    /// The structure was renumbered
+    Renumbered,
+    /// The given number of Residues with the ligand name was kept
+    KeptLigands(usize),
+    /// The given number of Residues close to the ligands was kept
+    KeptPocketResidues(usize),
+    /// The given number of Residues further away from the ligands was removed
+    RemovedResidues(usize),
+    /// The given number of Residues was renamed to their standard name
+    StandardizedResidueNames(usize),
+    /// An OXT atom was added to the given number of C-terminal amino acids
+    AddedTerminalOxygens(usize),
+    /// The Chains were sorted on their id
+    SortedChains,
+}
+
+impl fmt::Display for PipelineAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineAction::RemovedWaters(count) => write!(f, "Removed {} waters", count),
+            PipelineAction::ResolvedAlternateLocations {
+                residues,
+                removed_atoms,
+            } => write!(
+                f,
+                "Kept the conformer with the highest occupancy for {} residues, removing {} atoms",
+                residues, removed_atoms
+            ),
+            PipelineAction::InferredElements(count) => {
+                write!(f, "Guessed the element of {} atoms", count)
+            }
+            PipelineAction::Renumbered => write!(f, "Renumbered the structure"),
+            PipelineAction::KeptLigands(count) => write!(f, "Kept {} ligand residues", count),
+            PipelineAction::KeptPocketResidues(count) => {
+                write!(f, "Kept {} pocket residues", count)
+            }
+            PipelineAction::RemovedResidues(count) => write!(f, "Removed {} residues", count),
+            PipelineAction::StandardizedResidueNames(count) => {
+                write!(f, "Renamed {} residues to their standard name", count)
+            }
+            PipelineAction::AddedTerminalOxygens(count) => {
+                write!(f, "Added OXT atoms to {} C-terminal residues", count)
+            }
+            PipelineAction::SortedChains => write!(f, "Sorted the chains"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The actions taken by one of the pipelines, in the order they were taken. Steps that did not
+/// change anything are left out.
+pub struct PipelineReport {
+    /// The actions taken
+    actions: Vec<PipelineAction>,
+}
+
+impl PipelineReport {
+    /// Get the actions taken, in the order they were taken
+    pub fn actions(&self) -> impl DoubleEndedIterator<Item = &PipelineAction> + '_ {
+        self.actions.iter()
+    }
+
+    /// Returns `true` if the structure was not changed
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Add the action if it changed anything, so if the count is not zero
+    fn add(&mut self, count: usize, action: fn(usize) -> PipelineAction) {
+        if count > 0 {
+            self.actions.push(action(count));
+        }
+    }
+}
+
+impl fmt::Display for PipelineReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for action in &self.actions {
+            writeln!(f, "{}", action)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prepare a structure for docking: remove the waters, keep only the conformer with the highest
+/// occupancy of every Residue, guess missing elements and renumber, as far as enabled in the
+/// options. It returns the prepared structure and a report of the actions taken.
+///
+/// ## Arguments
+/// * `pdb` - the structure to prepare, it is not changed
+/// * `options` - the steps to take
+pub fn prepare_for_docking(pdb: &PDB, options: &DockingOptions) -> (PDB, PipelineReport) {
+    let mut pdb = pdb.clone();
+    let mut report = PipelineReport::default();
+    if options.remove_waters {
+        report.add(pdb.remove_waters(), PipelineAction::RemovedWaters);
+    }
+    if options.resolve_alternate_locations {
+        let (mut residues, mut removed_atoms) = (0, 0);
+        for residue in pdb.all_residues_mut() {
+            let preferred = match highest_occupancy_conformer(residue) {
+                Some(location) => location,
+                None => continue,
+            };
+            let atoms = residue.atom_count();
+            residue.remove_atoms_by(|atom| {
+                atom.alternate_location() != ' ' && atom.alternate_location() != preferred
+            });
+            residue.keep_conformer_name(preferred);
+            for atom in residue.atoms_mut() {
+                atom.set_alternate_location(' ').unwrap();
+            }
+            residues += 1;
+            removed_atoms += atoms - residue.atom_count();
+        }
+        if residues > 0 {
+            report
+                .actions
+                .push(PipelineAction::ResolvedAlternateLocations {
+                    residues,
+                    removed_atoms,
+                });
+        }
+    }
+    if options.infer_elements {
+        let mut inferred = 0;
+        for residue in pdb.all_residues_mut() {
+            let guesses = residue
+                .atoms()
+                .map(|atom| {
+                    if atom.element().is_empty() {
+                        Some(guess_element(atom, residue))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            for (atom, guess) in residue.atoms_mut().zip(guesses) {
+                if let Some(element) = guess {
+                    if atom.set_element(&element).is_ok() && !element.is_empty() {
+                        inferred += 1;
+                    }
+                }
+            }
+        }
+        report.add(inferred, PipelineAction::InferredElements);
+    }
+    if options.renumber {
+        pdb.renumber();
+        report.actions.push(PipelineAction::Renumbered);
+    }
+    (pdb, report)
+}
+
+/// Get the alternate location identifier of the conformer with the highest occupancy of the
+/// given Residue, the first in alphabetical order if multiple have the same occupancy. It
+/// returns `None` if the Residue has no alternate locations.
+fn highest_occupancy_conformer(residue: &Residue) -> Option<char> {
+    let mut best: Option<(char, f64)> = None;
+    for conformer in residue.conformers() {
+        let location = conformer.alternate_location();
+        let occupancy = conformer.occupancy().unwrap_or(0.0);
+        if location != ' ' && best.is_none_or(|(_, highest)| occupancy > highest) {
+            best = Some((location, occupancy));
+        }
+    }
+    best.map(|(location, _)| location)
+}
+
+/// Isolate a ligand and its pocket: keep only the Residues with the given name and the Residues
+/// with any Atom within the cutoff of an Atom of such a Residue in the same Model. Chains left
+/// without Residues are removed. It returns the isolated structure and a report of the actions
+/// taken.
+///
+/// It returns `None` if no Residue has the given name.
+/// ## Arguments
+/// * `pdb` - the structure to isolate the ligand from, it is not changed
+/// * `het_code` - the residue name of the ligand, like `HEM`
+/// * `cutoff` - the maximal distance in Å between an Atom of a pocket Residue and an Atom of the ligand
+pub fn isolate_ligand_and_pocket(
+    pdb: &PDB,
+    het_code: &str,
+    cutoff: f64,
+) -> Option<(PDB, PipelineReport)> {
+    let het_code = het_code.trim();
+    if !pdb.all_residues().any(|residue| residue.id() == het_code) {
+        return None;
+    }
+    let mut pdb = pdb.clone();
+    let (mut ligands, mut pocket, mut removed) = (0, 0, 0);
+    for model in pdb.models_mut() {
+        let ligand_atoms = model
+            .all_residues()
+            .filter(|residue| residue.id() == het_code)
+            .flat_map(|residue| residue.atoms())
+            .cloned()
+            .collect::<Vec<Atom>>();
+        for residue in model.all_residues() {
+            if residue.id() == het_code {
+                ligands += 1;
+            } else if residue
+                .atoms()
+                .any(|atom| ligand_atoms.iter().any(|l| atom.distance(l) <= cutoff))
+            {
+                pocket += 1;
+            } else {
+                removed += 1;
+            }
+        }
+        model.remove_residues_by(|residue| {
+            residue.id() != het_code
+                && !residue
+                    .atoms()
+                    .any(|atom| ligand_atoms.iter().any(|l| atom.distance(l) <= cutoff))
+        });
+        model.remove_chains_by(|chain| chain.residue_count() == 0);
+    }
+    let mut report = PipelineReport::default();
+    report.add(ligands, PipelineAction::KeptLigands);
+    report.add(pocket, PipelineAction::KeptPocketResidues);
+    report.add(removed, PipelineAction::RemovedResidues);
+    Some((pdb, report))
+}
+
+/// Clean a structure for molecular dynamics: replace force field specific residue names by the
+/// standard names, add missing OXT atoms to the C-terminal amino acids and sort the Chains, as
+/// far as enabled in the options. It returns the cleaned structure and a report of the actions
+/// taken.
+///
+/// ## Arguments
+/// * `pdb` - the structure to clean, it is not changed
+/// * `options` - the steps to take
+pub fn clean_for_md(pdb: &PDB, options: &MdOptions) -> (PDB, PipelineReport) {
+    let mut pdb = pdb.clone();
+    let mut report = PipelineReport::default();
+    if options.standardize_residue_names {
+        let mut renamed = 0;
+        for residue in pdb.all_residues_mut() {
+            if let Some((_, standard)) = RESIDUE_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == residue.id())
+            {
+                residue.set_id(standard).unwrap();
+                renamed += 1;
+            }
+        }
+        report.add(renamed, PipelineAction::StandardizedResidueNames);
+    }
+    if options.add_terminal_oxygens {
+        let mut added = 0;
+        for model in pdb.models_mut() {
+            let mut serial_number = model
+                .all_atoms()
+                .map(|atom| atom.serial_number())
+                .max()
+                .unwrap_or(0);
+            for chain in model.chains_mut() {
+                if let Some(residue) = chain.residues_mut().next_back() {
+                    if let Some(oxt) = terminal_oxygen(residue, serial_number + 1) {
+                        residue.add_atom(oxt);
+                        serial_number += 1;
+                        added += 1;
+                    }
+                }
+            }
+        }
+        report.add(added, PipelineAction::AddedTerminalOxygens);
+    }
+    if options.sort_chains {
+        let sorted = pdb.models().all(|model| {
+            let ids = model
+                .all_chains()
+                .map(|chain| chain.id())
+                .collect::<Vec<_>>();
+            let normal = model.chain_count();
+            ids[..normal].windows(2).all(|w| w[0] <= w[1])
+                && ids[normal..].windows(2).all(|w| w[0] <= w[1])
+        });
+        if !sorted {
+            for model in pdb.models_mut() {
+                model.sort_chains();
+            }
+            report.actions.push(PipelineAction::SortedChains);
+        }
+    }
+    (pdb, report)
+}
+
+/// Create the OXT atom for the given Residue if it is an amino acid with CA, C and O atoms but
+/// without an OXT atom. It is placed in the plane of the CA, C and O atoms, opposite of the other
+/// two bonds of the C atom, with the occupancy and B-factor of the O atom.
+fn terminal_oxygen(residue: &Residue, serial_number: usize) -> Option<Atom> {
+    if !residue.amino_acid() || residue.atoms().any(|atom| atom.name() == "OXT") {
+        return None;
+    }
+    let find = |name: &str| residue.atoms().find(|atom| atom.name() == name);
+    let (ca, c, o) = (find("CA")?, find("C")?, find("O")?);
+    let unit = |from: &Atom| {
+        let (x, y, z) = (c.x() - from.x(), c.y() - from.y(), c.z() - from.z());
+        let length = (x * x + y * y + z * z).sqrt();
+        (x / length, y / length, z / length)
+    };
+    let (a, b) = (unit(ca), unit(o));
+    let (x, y, z) = (a.0 + b.0, a.1 + b.1, a.2 + b.2);
+    let length = (x * x + y * y + z * z).sqrt();
+    if !length.is_normal() {
+        return None;
+    }
+    let scale = OXT_BOND_LENGTH / length;
+    let mut atom = Atom::new(
+        serial_number,
+        [' ', 'O', 'X', 'T'],
+        c.x() + x * scale,
+        c.y() + y * scale,
+        c.z() + z * scale,
+        o.occupancy(),
+        o.b_factor(),
+        [' ', 'O'],
+        0,
+    )?;
+    atom.set_alternate_location(o.alternate_location()).ok()?;
+    Some(atom)
+}
@@ -0,0 +1,425 @@
+use crate::error::*;
+use crate::structs::*;
+use std::collections::HashSet;
+
+/// The radius in Å used for atoms without a known van der Waals radius
+const DEFAULT_RADIUS: f64 = 1.8;
+
+/// The maximal distance in Å scanned from a grid point to find the protein in every direction
+const SCAN_DISTANCE: f64 = 10.0;
+
+/// The directions scanned to determine the buriedness of a grid point: the three axes and the
+/// four body diagonals of the grid, every direction is scanned both ways
+const DIRECTIONS: [(i64, i64, i64); 7] = [
+    (1, 0, 0),
+    (0, 1, 0),
+    (0, 0, 1),
+    (1, 1, 1),
+    (1, 1, -1),
+    (1, -1, 1),
+    (-1, 1, 1),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+/// The parameters for the detection of pockets, see `Model::detect_pockets`
+pub struct PocketParameters {
+    /// The distance between grid points in Å
+    grid_spacing: f64,
+    /// The radius of the probe in Å
+    probe_radius: f64,
+    /// The minimal number of directions (out of 7) a grid point has to be enclosed in
+    min_buriedness: usize,
+    /// The minimal volume of a pocket in Å³
+    min_volume: f64,
+    /// The maximal volume of a pocket in Å³
+    max_volume: f64,
+}
+
+impl PocketParameters {
+    /// Create new pocket detection parameters
+    /// ## Arguments
+    /// * `grid_spacing` - the distance between grid points in Å, 1 Å is a good balance between speed and detail
+    /// * `probe_radius` - the radius of the probe in Å, grid points closer to an atom than its van der Waals radius plus this radius are not part of a pocket
+    /// * `min_buriedness` - the minimal number of the 7 scanned directions in which a grid point has to be enclosed by the protein on both sides
+    /// * `min_volume` - the minimal volume of a pocket in Å³, smaller pockets are left out
+    /// * `max_volume` - the maximal volume of a pocket in Å³, bigger pockets are split by only keeping their more buried points
+    /// ## Panics
+    /// It panics if `grid_spacing` is not finite and positive, if `probe_radius` or `min_volume`
+    /// is not finite or negative, if `max_volume` is smaller than `min_volume`, or if
+    /// `min_buriedness` is not in the range 1 to 7.
+    pub fn new(
+        grid_spacing: f64,
+        probe_radius: f64,
+        min_buriedness: usize,
+        min_volume: f64,
+        max_volume: f64,
+    ) -> PocketParameters {
+        assert!(
+            grid_spacing.is_finite() && grid_spacing > 0.0,
+            "The grid spacing is not finite or not positive"
+        );
+        assert!(
+            probe_radius.is_finite() && probe_radius >= 0.0,
+            "The probe radius is not finite or negative"
+        );
+        assert!(
+            (1..=DIRECTIONS.len()).contains(&min_buriedness),
+            "The minimal buriedness has to be in the range 1 to 7"
+        );
+        assert!(
+            min_volume.is_finite() && min_volume >= 0.0,
+            "The minimal volume is not finite or negative"
+        );
+        assert!(
+            max_volume >= min_volume,
+            "The maximal volume is smaller than the minimal volume"
+        );
+        PocketParameters {
+            grid_spacing,
+            probe_radius,
+            min_buriedness,
+            min_volume,
+            max_volume,
+        }
+    }
+
+    /// Get the distance between grid points in Å
+    pub fn grid_spacing(&self) -> f64 {
+        self.grid_spacing
+    }
+
+    /// Get the radius of the probe in Å
+    pub fn probe_radius(&self) -> f64 {
+        self.probe_radius
+    }
+
+    /// Get the minimal number of directions (out of 7) a grid point has to be enclosed in
+    pub fn min_buriedness(&self) -> usize {
+        self.min_buriedness
+    }
+
+    /// Get the minimal volume of a pocket in Å³
+    pub fn min_volume(&self) -> f64 {
+        self.min_volume
+    }
+
+    /// Get the maximal volume of a pocket in Å³
+    pub fn max_volume(&self) -> f64 {
+        self.max_volume
+    }
+}
+
+impl Default for PocketParameters {
+    /// A 1 Å grid with a 1 Å probe, points enclosed in at least 3 directions and pockets of 20
+    /// to 1000 Å³
+    fn default() -> Self {
+        PocketParameters::new(1.0, 1.0, 3, 20.0, 1000.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A candidate ligand binding pocket found by `Model::detect_pockets`
+pub struct Pocket {
+    /// The grid points making up the pocket
+    points: Vec<(f64, f64, f64)>,
+    /// The volume in Å³
+    volume: f64,
+    /// The mean buriedness of the grid points
+    buriedness: f64,
+    /// The Residues lining the pocket, as chain id and residue serial number
+    residues: Vec<(char, usize)>,
+}
+
+impl Pocket {
+    /// Get the centre of the pocket, the mean position of its grid points
+    pub fn centroid(&self) -> (f64, f64, f64) {
+        let n = self.points.len() as f64;
+        let sum = self.points.iter().fold((0.0, 0.0, 0.0), |sum, point| {
+            (sum.0 + point.0, sum.1 + point.1, sum.2 + point.2)
+        });
+        (sum.0 / n, sum.1 / n, sum.2 / n)
+    }
+
+    /// Get the approximate volume of the pocket in Å³, the number of grid points times the volume of a grid cell
+    pub fn volume(&self) -> f64 {
+        self.volume
+    }
+
+    /// Get the mean buriedness of the grid points of the pocket, the number of scanned directions
+    /// (out of 7) in which a point is enclosed by the protein on both sides
+    pub fn buriedness(&self) -> f64 {
+        self.buriedness
+    }
+
+    /// Get the Residues lining the pocket, as chain id and residue serial number in the order of
+    /// the Model. A Residue lines the pocket if any of its Atoms is within its van der Waals
+    /// radius plus the probe radius and the grid spacing of a grid point of the pocket.
+    pub fn residues(&self) -> &[(char, usize)] {
+        &self.residues
+    }
+
+    /// Get the positions of the grid points making up the pocket
+    pub fn points(&self) -> &[(f64, f64, f64)] {
+        &self.points
+    }
+}
+
+impl Model {
+    /// Detect candidate ligand binding pockets in this Model without a ligand, with a grid based
+    /// method in the style of LIGSITE. A grid is placed over the bounding box of the Atoms of the
+    /// Normal Chains, so bound ligands and waters are ignored. Grid points that are not within
+    /// reach of any Atom (its van der Waals radius plus the probe radius) are scanned in 7
+    /// directions, the points enclosed by the protein on both sides in at least the minimal
+    /// number of directions are buried. Connected buried points form a pocket, a pocket bigger
+    /// than the maximal volume is split into the connected points enclosed in one more direction,
+    /// until it is small enough. The pockets are ranked by their size and buriedness (the sum of
+    /// the buriedness of their points), the most likely binding site first.
+    /// ## Arguments
+    /// * `parameters` - the grid spacing, probe radius, minimal buriedness and volume range to use
+    /// ## Fails
+    /// It fails with `AnalysisError::CaOnlyChain` if any of the Chains is CA-only (see
+    /// `Chain::is_ca_only`), as the surface cannot be found without the side chain atoms.
+    pub fn detect_pockets(
+        &self,
+        parameters: &PocketParameters,
+    ) -> Result<Vec<Pocket>, AnalysisError> {
+        self.check_full_atoms()?;
+        let atoms = self
+            .atoms()
+            .map(|atom| {
+                (
+                    atom.pos(),
+                    atom.vanderwaals_radius().unwrap_or(DEFAULT_RADIUS),
+                )
+            })
+            .collect::<Vec<((f64, f64, f64), f64)>>();
+        if atoms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let spacing = parameters.grid_spacing;
+        let probe = parameters.probe_radius;
+
+        // The grid over the bounding box, with a margin to reach the outside of the surface
+        let margin = 2.0 * spacing;
+        let min = atoms.iter().fold((f64::MAX, f64::MAX, f64::MAX), |m, a| {
+            (m.0.min(a.0 .0), m.1.min(a.0 .1), m.2.min(a.0 .2))
+        });
+        let max = atoms.iter().fold((f64::MIN, f64::MIN, f64::MIN), |m, a| {
+            (m.0.max(a.0 .0), m.1.max(a.0 .1), m.2.max(a.0 .2))
+        });
+        let origin = (min.0 - margin, min.1 - margin, min.2 - margin);
+        let size = |low: f64, high: f64| ((high - low + 2.0 * margin) / spacing).ceil() as i64 + 1;
+        let grid = Grid {
+            size: (size(min.0, max.0), size(min.1, max.1), size(min.2, max.2)),
+        };
+        let position = |(x, y, z): (i64, i64, i64)| {
+            (
+                origin.0 + x as f64 * spacing,
+                origin.1 + y as f64 * spacing,
+                origin.2 + z as f64 * spacing,
+            )
+        };
+
+        // Mark the points inside the atoms (protein) and within reach of the probe (excluded)
+        let mut protein = vec![false; grid.len()];
+        let mut excluded = vec![false; grid.len()];
+        for &(pos, radius) in &atoms {
+            let reach = radius + probe;
+            let low = grid.index_of(
+                origin,
+                spacing,
+                (pos.0 - reach, pos.1 - reach, pos.2 - reach),
+            );
+            let high = grid.index_of(
+                origin,
+                spacing,
+                (pos.0 + reach, pos.1 + reach, pos.2 + reach),
+            );
+            for x in low.0..=high.0 + 1 {
+                for y in low.1..=high.1 + 1 {
+                    for z in low.2..=high.2 + 1 {
+                        if let Some(index) = grid.index((x, y, z)) {
+                            let distance = squared_distance(pos, position((x, y, z)));
+                            if distance < radius * radius {
+                                protein[index] = true;
+                            }
+                            if distance < reach * reach {
+                                excluded[index] = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Determine the buriedness of every free point
+        let steps = (SCAN_DISTANCE / spacing).ceil() as i64;
+        let enclosed = |point: (i64, i64, i64), direction: (i64, i64, i64)| {
+            (1..=steps).any(|step| {
+                grid.index((
+                    point.0 + step * direction.0,
+                    point.1 + step * direction.1,
+                    point.2 + step * direction.2,
+                ))
+                .is_some_and(|index| protein[index])
+            })
+        };
+        let mut buriedness = vec![0; grid.len()];
+        for index in 0..grid.len() {
+            if excluded[index] {
+                continue;
+            }
+            let point = grid.point(index);
+            buriedness[index] = DIRECTIONS
+                .iter()
+                .filter(|d| enclosed(point, **d) && enclosed(point, (-d.0, -d.1, -d.2)))
+                .count();
+        }
+
+        // Group the connected buried points into pockets, pockets that are too big are split by
+        // only keeping their more buried points
+        let cell_volume = spacing.powi(3);
+        let mut pockets = Vec::new();
+        let mut regions = vec![(
+            (0..grid.len())
+                .filter(|index| buriedness[*index] >= parameters.min_buriedness)
+                .collect::<Vec<usize>>(),
+            parameters.min_buriedness,
+        )];
+        let mut region_of = vec![0; grid.len()];
+        let mut id = 0;
+        while let Some((region, threshold)) = regions.pop() {
+            id += 1;
+            for index in &region {
+                region_of[*index] = id;
+            }
+            for start in region {
+                if region_of[start] != id || buriedness[start] < threshold {
+                    continue;
+                }
+                region_of[start] = 0;
+                let mut members = vec![start];
+                let mut next = 0;
+                while next < members.len() {
+                    let point = grid.point(members[next]);
+                    next += 1;
+                    for dx in -1..=1 {
+                        for dy in -1..=1 {
+                            for dz in -1..=1 {
+                                if let Some(index) =
+                                    grid.index((point.0 + dx, point.1 + dy, point.2 + dz))
+                                {
+                                    if region_of[index] == id && buriedness[index] >= threshold {
+                                        region_of[index] = 0;
+                                        members.push(index);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                let volume = members.len() as f64 * cell_volume;
+                if volume > parameters.max_volume && threshold < DIRECTIONS.len() {
+                    regions.push((members, threshold + 1));
+                    continue;
+                }
+                if volume < parameters.min_volume {
+                    continue;
+                }
+                let score = members
+                    .iter()
+                    .map(|index| buriedness[*index])
+                    .sum::<usize>();
+                let points = members
+                    .iter()
+                    .map(|index| position(grid.point(*index)))
+                    .collect::<Vec<(f64, f64, f64)>>();
+                pockets.push((
+                    score,
+                    Pocket {
+                        residues: self.lining_residues(&points, probe + spacing),
+                        buriedness: score as f64 / members.len() as f64,
+                        volume,
+                        points,
+                    },
+                ));
+            }
+        }
+        pockets.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        Ok(pockets.into_iter().map(|(_, pocket)| pocket).collect())
+    }
+
+    /// Get the Residues of the Normal Chains with an Atom within its van der Waals radius plus
+    /// the given distance of any of the given points, as chain id and residue serial number
+    fn lining_residues(&self, points: &[(f64, f64, f64)], distance: f64) -> Vec<(char, usize)> {
+        let mut found = HashSet::new();
+        let mut residues = Vec::new();
+        for chain in self.chains() {
+            for residue in chain.residues() {
+                let lining = residue.atoms().any(|atom| {
+                    let reach = atom.vanderwaals_radius().unwrap_or(DEFAULT_RADIUS) + distance;
+                    points
+                        .iter()
+                        .any(|point| squared_distance(atom.pos(), *point) < reach * reach)
+                });
+                if lining && found.insert((chain.id(), residue.serial_number())) {
+                    residues.push((chain.id(), residue.serial_number()));
+                }
+            }
+        }
+        residues
+    }
+}
+
+/// The dimensions of a grid of points, stored in a flat list with the z index changing fastest
+struct Grid {
+    /// The number of points along x, y and z
+    size: (i64, i64, i64),
+}
+
+impl Grid {
+    /// The number of points in the grid
+    fn len(&self) -> usize {
+        (self.size.0 * self.size.1 * self.size.2) as usize
+    }
+
+    /// The index in the flat list of the given point, if it is inside the grid
+    fn index(&self, (x, y, z): (i64, i64, i64)) -> Option<usize> {
+        if (0..self.size.0).contains(&x)
+            && (0..self.size.1).contains(&y)
+            && (0..self.size.2).contains(&z)
+        {
+            Some(((x * self.size.1 + y) * self.size.2 + z) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// The point at the given index in the flat list
+    fn point(&self, index: usize) -> (i64, i64, i64) {
+        let index = index as i64;
+        (
+            index / (self.size.1 * self.size.2),
+            index / self.size.2 % self.size.1,
+            index % self.size.2,
+        )
+    }
+
+    /// The point at or below the given position, which can be outside the grid
+    fn index_of(
+        &self,
+        origin: (f64, f64, f64),
+        spacing: f64,
+        pos: (f64, f64, f64),
+    ) -> (i64, i64, i64) {
+        (
+            ((pos.0 - origin.0) / spacing).floor() as i64,
+            ((pos.1 - origin.1) / spacing).floor() as i64,
+            ((pos.2 - origin.2) / spacing).floor() as i64,
+        )
+    }
+}
+
+fn squared_distance(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)
+}
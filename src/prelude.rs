@@ -0,0 +1,8 @@
+//! The commonly used types and functions of pdbtbx, to be imported with `use pdbtbx::prelude::*;`.
+//! It leaves out names that are likely to clash with other crates, like `PDBContext`, so those
+//! have to be imported from the root of the crate when needed.
+
+pub use crate::{
+    open, read_pdb, save_pdb, save_with_options, validate_pdb, Atom, Chain, Conformer, EntityType,
+    ErrorLevel, Format, Model, PDBError, ReadOptions, Residue, SaveOptions, Selection, PDB,
+};
@@ -0,0 +1,192 @@
+use crate::error::*;
+use crate::structs::*;
+
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufWriter;
+
+impl PDB {
+    /// Write a PyMOL macro (`.pml`) to visualise the atoms flagged by the given errors.
+    /// The script loads the source PDB file, creates a selection per ErrorLevel (named after
+    /// `ErrorLevel::name()`) containing all flagged atoms and shows these selections as sticks.
+    ///
+    /// The flagged atoms are found by parsing the context of each error, only errors with a context
+    /// containing an ATOM, HETATM or ANISOU line can be visualised. Atoms that are not present in
+    /// this PDB are not selected.
+    ///
+    /// ## Arguments
+    /// * `source_filename` - the filename of the PDB file to load in PyMOL
+    /// * `errors` - the errors to visualise
+    /// * `output_filename` - the filename of the macro to write
+    ///
+    /// ## Fails
+    /// It fails if the output file could not be written.
+    pub fn write_pymol_selection_macro(
+        &self,
+        source_filename: &str,
+        errors: &[PDBError],
+        output_filename: &str,
+    ) -> Result<(), PDBError> {
+        let file_error = || {
+            PDBError::new(
+                ErrorLevel::BreakingError,
+                "Could not write file",
+                "Could not write the PyMOL macro, make sure the path is correct and you have permission.",
+                PDBContext::show(output_filename),
+            )
+        };
+        let file = File::create(output_filename).map_err(|_| file_error())?;
+        let mut writer = BufWriter::new(file);
+
+        writeln!(writer, "load {}", source_filename).map_err(|_| file_error())?;
+        for level in &[
+            ErrorLevel::BreakingError,
+            ErrorLevel::InvalidatingError,
+            ErrorLevel::StrictWarning,
+            ErrorLevel::LooseWarning,
+            ErrorLevel::GeneralWarning,
+        ] {
+            let mut selections: Vec<String> = Vec::new();
+            for error in errors.iter().filter(|e| e.level() == *level) {
+                if let Some(selection) = self.pymol_selection(error.context()) {
+                    if !selections.contains(&selection) {
+                        selections.push(selection);
+                    }
+                }
+            }
+            if !selections.is_empty() {
+                writeln!(
+                    writer,
+                    "select {}, {}",
+                    level.name(),
+                    selections
+                        .iter()
+                        .map(|s| format!("({})", s))
+                        .collect::<Vec<String>>()
+                        .join(" or ")
+                )
+                .map_err(|_| file_error())?;
+                writeln!(writer, "show sticks, {}", level.name()).map_err(|_| file_error())?;
+            }
+        }
+
+        writer.flush().map_err(|_| file_error())?;
+        Ok(())
+    }
+
+    /// Create a PyMOL selection for the atom described by the given context, if the context
+    /// contains an ATOM, HETATM or ANISOU line describing an atom present in this PDB.
+    fn pymol_selection(&self, context: &PDBContext) -> Option<String> {
+        let line = context.text()?;
+        if !(line.starts_with("ATOM") || line.starts_with("HETATM") || line.starts_with("ANISOU"))
+            || line.len() < 26
+        {
+            return None;
+        }
+        let name = line.get(12..16)?.trim();
+        let chain_id = line.chars().nth(21)?;
+        let residue_serial_number = line.get(22..26)?.trim().parse::<usize>().ok()?;
+        let insertion_code = line.chars().nth(26).filter(|c| *c != ' ');
+
+        let present = self.all_chains().any(|chain| {
+            chain.id() == chain_id
+                && chain.residues().any(|residue| {
+                    residue.numbering() == (residue_serial_number, insertion_code)
+                        && (name.is_empty() || residue.atom_by_name(name).is_some())
+                })
+        });
+        if !present {
+            return None;
+        }
+
+        let mut selection = format!(
+            "chain {} and resi {}{}",
+            chain_id,
+            residue_serial_number,
+            insertion_code.map_or_else(String::new, String::from)
+        );
+        if !name.is_empty() {
+            selection.push_str(&format!(" and name {}", name));
+        }
+        Some(selection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::*;
+    use crate::structs::*;
+    use std::fs;
+
+    #[test]
+    fn write_macro() {
+        let mut pdb = PDB::new();
+        let mut model = Model::new(0);
+        let atom = Atom::new(
+            1,
+            [' ', 'C', 'A', ' '],
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0,
+            [' ', 'C'],
+            0,
+        );
+        model.add_atom(atom.unwrap(), 'A', 12, None, ['A', 'L', 'A']);
+        let atom = Atom::new(
+            2,
+            [' ', 'Z', 'N', ' '],
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0,
+            ['Z', 'N'],
+            0,
+        );
+        model.add_hetero_atom(atom.unwrap(), 'B', 101, None, [' ', 'Z', 'N']);
+        pdb.add_model(model);
+
+        let errors = vec![
+            PDBError::new(
+                ErrorLevel::StrictWarning,
+                "Test",
+                "",
+                PDBContext::full_line(
+                    1,
+                    "ATOM      1  CA  ALA A  12       0.000   0.000   0.000  1.00  0.00           C",
+                ),
+            ),
+            PDBError::new(
+                ErrorLevel::StrictWarning,
+                "Test",
+                "",
+                PDBContext::line(
+                    2,
+                    "HETATM    2 ZN    ZN B 101       0.000   0.000   0.000  1.00  0.00          ZN",
+                    30,
+                    8,
+                ),
+            ),
+            PDBError::new(
+                ErrorLevel::GeneralWarning,
+                "Not present",
+                "",
+                PDBContext::full_line(
+                    3,
+                    "ATOM      3  CB  ALA A  12       0.000   0.000   0.000  1.00  0.00           C",
+                ),
+            ),
+            PDBError::new(ErrorLevel::LooseWarning, "No context", "", PDBContext::none()),
+        ];
+        let _ = fs::create_dir("dump");
+        pdb.write_pymol_selection_macro("example.pdb", &errors, "dump/errors.pml")
+            .unwrap();
+        let output = fs::read_to_string("dump/errors.pml").unwrap();
+        assert_eq!(
+            output,
+            "load example.pdb\nselect strict_warning, (chain A and resi 12 and name CA) or (chain B and resi 101 and name ZN)\nshow sticks, strict_warning\n"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use super::lexitem::*;
+use super::parser::lex_atom;
+use crate::error::*;
+use crate::structs::*;
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+/// The maximal number of differences listed in the error when the atoms do not match
+const MAX_LISTED_DIFFERENCES: usize = 5;
+
+impl PDB {
+    /// Append the coordinates in the given file as a new Model, for example to load a trajectory
+    /// saved as separate PDB files of the same system. See `append_model_from_reader` for the details.
+    /// ## Arguments
+    /// * `filename` - the file to read the coordinates from
+    /// ## Fails
+    /// It fails if the file could not be read, see `append_model_from_reader` for the other cases.
+    pub fn append_model_from(&mut self, filename: &str) -> Result<usize, PDBError> {
+        let file = if let Ok(f) = File::open(filename) {
+            f
+        } else {
+            return Err(PDBError::new(ErrorLevel::BreakingError, "Could not open file", "Could not open the specified file, make sure the path is correct, you have permission, and that it is not open in another program.", PDBContext::show(filename)));
+        };
+        self.append_model_from_reader(BufReader::new(file))
+    }
+
+    /// Append the coordinates read from the given reader as a new Model. Only the ATOM and HETATM
+    /// records are parsed, up to the first ENDMDL or END record, all other records are skipped.
+    /// The atoms have to be the same, and in the same order, as the atoms of the first Model
+    /// (`all_atoms()`), so the new Model is a copy of the first Model with the position, occupancy
+    /// and B-factor of every atom taken from the read records. The new Model gets the serial number
+    /// following the highest serial number in use, which is returned.
+    /// ## Arguments
+    /// * `reader` - the reader to read the coordinates from
+    /// ## Fails
+    /// It fails if this PDB has no Models, if a line could not be read or lexed, if the atoms
+    /// read do not match the atoms of the first Model (the error then summarises the differences),
+    /// or if a position, occupancy or B-factor read is not valid for an Atom.
+    pub fn append_model_from_reader<R: BufRead>(&mut self, reader: R) -> Result<usize, PDBError> {
+        let reference = if let Some(model) = self.model(0) {
+            model
+        } else {
+            return Err(PDBError::new(
+                ErrorLevel::BreakingError,
+                "No Model to append to",
+                "This PDB does not contain any Models, so there is no topology to append the coordinates to.",
+                PDBContext::none(),
+            ));
+        };
+        let expected = reference
+            .all_chains()
+            .flat_map(|chain| {
+                chain.residues().flat_map(move |residue| {
+                    residue.atoms().map(move |atom| {
+                        (
+                            chain.id(),
+                            residue.serial_number(),
+                            residue.id(),
+                            atom.name(),
+                        )
+                    })
+                })
+            })
+            .collect::<Vec<(char, usize, String, String)>>();
+
+        let mut coordinates = Vec::with_capacity(expected.len());
+        let mut differences = Vec::new();
+        let mut first_difference = None;
+        for (mut linenumber, read_line) in reader.lines().enumerate() {
+            linenumber += 1; // 1 based indexing in files
+            let line = read_line.map_err(|_| {
+                PDBError::new(
+                    ErrorLevel::BreakingError,
+                    "Could read line",
+                    &format!(
+                        "Could not read line {} while parsing the coordinates.",
+                        linenumber
+                    ),
+                    PDBContext::none(),
+                )
+            })?;
+            if line.starts_with("ENDMDL") || line.trim_end() == "END" {
+                break;
+            }
+            let hetero = line.starts_with("HETATM");
+            if !(hetero || line.starts_with("ATOM  ")) {
+                continue;
+            }
+            let context = PDBContext::full_line(linenumber, &line);
+            if let LexItem::Atom(
+                _,
+                _,
+                name,
+                _,
+                residue_name,
+                chain_id,
+                residue_serial_number,
+                _,
+                x,
+                y,
+                z,
+                occupancy,
+                b_factor,
+                _,
+                _,
+                _,
+            ) = lex_atom(linenumber, &line, hetero)?
+            {
+                let found = (
+                    chain_id,
+                    residue_serial_number,
+                    residue_name
+                        .iter()
+                        .collect::<String>()
+                        .split_whitespace()
+                        .collect::<String>(),
+                    name.iter()
+                        .collect::<String>()
+                        .split_whitespace()
+                        .collect::<String>(),
+                );
+                if let Some(expected) = expected.get(coordinates.len()) {
+                    if *expected != found {
+                        if differences.len() < MAX_LISTED_DIFFERENCES {
+                            differences.push(format!(
+                                "atom {}: expected {} {} {} {}, found {} {} {} {}",
+                                coordinates.len() + 1,
+                                expected.0,
+                                expected.1,
+                                expected.2,
+                                expected.3,
+                                found.0,
+                                found.1,
+                                found.2,
+                                found.3
+                            ));
+                        }
+                        first_difference.get_or_insert_with(|| context.clone());
+                    }
+                }
+                coordinates.push(((x, y, z), occupancy, b_factor, context));
+            }
+        }
+
+        if coordinates.len() != expected.len() || first_difference.is_some() {
+            let mut summary = format!(
+                "The coordinates do not match the first Model, found {} atoms where {} were expected.",
+                coordinates.len(),
+                expected.len()
+            );
+            if !differences.is_empty() {
+                summary.push_str(&format!(" Differences: {}.", differences.join("; ")));
+            }
+            return Err(PDBError::new(
+                ErrorLevel::BreakingError,
+                "Atoms do not match",
+                &summary,
+                first_difference.unwrap_or_else(PDBContext::none),
+            ));
+        }
+
+        let serial_number = self.models().map(|m| m.serial_number()).max().unwrap_or(0) + 1;
+        let mut model = reference.clone();
+        model.set_serial_number(serial_number);
+        for (atom, (pos, occupancy, b_factor, context)) in model.all_atoms_mut().zip(coordinates) {
+            atom.set_pos(pos)
+                .and_then(|_| atom.set_occupancy(occupancy))
+                .and_then(|_| atom.set_b_factor(b_factor))
+                .map_err(|message| {
+                    PDBError::new(
+                        ErrorLevel::BreakingError,
+                        "Invalid coordinates",
+                        &format!("{}, so the Model cannot be appended.", message),
+                        context,
+                    )
+                })?;
+        }
+        self.add_model(model);
+        Ok(serial_number)
+    }
+}
@@ -1,9 +1,35 @@
+#![allow(dead_code)]
 /// A definition of all lines that a PDB file can contain (and can be parsed by this program)
 /// with all properties saved as primitive data types.
 ///
 /// See wwPDB v3.30 for detailed explanation of the meaning of all fields
 #[derive(Debug)]
 pub enum LexItem {
+    /// A HEADER, containing the classification, deposition date, and id code (read up to the end of the line)
+    Header(String, String, String),
+    /// An OBSLTE, containing the continuation number, the date of replacement, the id code of this entry, and the id
+    /// codes of the replacing entries
+    Obsolete(usize, String, String, Vec<String>),
+    /// An SPRSDE, containing the continuation number, the date of superseding, the id code of this entry, and the id
+    /// codes of the superseded entries
+    Supersedes(usize, String, String, Vec<String>),
+    /// A SPLIT, containing the continuation number and the id codes of the entries
+    Split(usize, Vec<String>),
+    /// A CAVEAT, containing the continuation number, the id code of this entry, and the text (trimmed)
+    Caveat(usize, String, String),
+    /// A TITLE, containing the continuation number (1 for the first line) and the text, with the
+    /// trailing spaces and the separating space of continuation lines removed
+    Title(usize, String),
+    /// A COMPND, containing the continuation number and the text, as for TITLE
+    Compound(usize, String),
+    /// A SOURCE, containing the continuation number and the text, as for TITLE
+    Source(usize, String),
+    /// An EXPDTA, containing the continuation number and the text, as for TITLE
+    ExperimentalData(usize, String),
+    /// An MDLTYP, containing the continuation number and the text, as for TITLE
+    ModelType(usize, String),
+    /// A NUMMDL, containing the declared number of Models
+    ModelCount(usize),
     /// A REMARK saved as the remark-type-number and the remark line itself
     Remark(usize, String),
     /// An Atom with all its information, including the deprecated and rarely used fields.
@@ -50,7 +76,7 @@ pub enum LexItem {
     /// * chain id
     /// * residue serial number
     /// * insertion
-    /// * temperature factors
+    /// * temperature factors (U11, U22, U33, U12, U13, U23)
     /// * segment id
     /// * element
     /// * charge
@@ -62,19 +88,107 @@ pub enum LexItem {
         char,
         usize,
         char,
-        [[f64; 3]; 2],
+        [f64; 6],
         [char; 4],
         [char; 2],
         [char; 2],
     ),
+    /// A HET line, containing: het id, chain id, residue serial number, insertion code, number of HETATM records, and text
+    Het([char; 3], char, usize, char, usize, String),
+    /// A HETNAM line, containing: continuation number, het id, and text
+    HeterogenName(usize, String, String),
+    /// A HETSYN line, containing: continuation number, het id, and text
+    HeterogenSynonym(usize, String, String),
+    /// A FORMUL line, containing: component number if given, het id, continuation number, and text
+    Formula(Option<usize>, String, usize, String),
+    /// A DBREF line, containing: chain id, first and last residue serial number and insertion code in the Chain,
+    /// database name, accession code, database id code, and first and last residue serial number and insertion code
+    /// in the database
+    DatabaseReference(
+        char,
+        [(usize, char); 2],
+        String,
+        String,
+        String,
+        [(usize, char); 2],
+    ),
+    /// A DBREF1 line, containing: chain id, first and last residue serial number and insertion code in the Chain,
+    /// database name, and database id code
+    DatabaseReference1(char, [(usize, char); 2], String, String),
+    /// A DBREF2 line, containing: chain id, accession code, and first and last residue serial number in the database
+    DatabaseReference2(char, String, [usize; 2]),
+    /// A SEQADV line, containing: residue name, chain id, residue serial number and insertion code if given,
+    /// database name, accession code, database residue name, database residue serial number if given, and comment
+    SequenceDifference(
+        String,
+        char,
+        Option<(usize, char)>,
+        String,
+        String,
+        String,
+        Option<usize>,
+        String,
+    ),
+    /// A LINK line, containing: atom names, alternate locations, residue names, chain ids, residue serial numbers,
+    /// insertion codes, symmetry operators, and bond length if given
+    Link(
+        [String; 2],
+        [char; 2],
+        [String; 2],
+        [char; 2],
+        [usize; 2],
+        [char; 2],
+        [usize; 2],
+        Option<f64>,
+    ),
+    /// A CISPEP line, containing: serial number, residue names, chain ids, residue serial numbers, insertion codes,
+    /// model number, and omega angle if given
+    CisPeptide(
+        usize,
+        [String; 2],
+        [char; 2],
+        [usize; 2],
+        [char; 2],
+        usize,
+        Option<f64>,
+    ),
+    /// A SITE line, containing: sequence number, site name, number of residues in the site, and the residues on this
+    /// line as residue name, chain id, residue serial number, and insertion code
+    Site(usize, String, usize, Vec<(String, char, usize, char)>),
+    /// An SSBOND line, containing: serial number, chain ids, residue serial numbers, insertion codes, symmetry operators, and bond length if given
+    SSBond(
+        usize,
+        [char; 2],
+        [usize; 2],
+        [char; 2],
+        [usize; 2],
+        Option<f64>,
+    ),
+    /// A HELIX line, containing: serial number, helix id, residue names, chain ids, residue serial numbers, insertion codes, helix class, comment, and length if given
+    Helix(
+        usize,
+        String,
+        [[char; 3]; 2],
+        [char; 2],
+        [usize; 2],
+        [char; 2],
+        usize,
+        String,
+        Option<usize>,
+    ),
+    /// A CONECT line, containing: the serial number of the Atom and the serial numbers of the (1 to 4) Atoms bonded to it
+    Conect(usize, Vec<usize>),
+    /// A SEQRES line, containing: serial number, chain id, number of residues in the chain, and the residue names
+    Seqres(usize, char, usize, Vec<String>),
     /// A SCALEn line, as the row (1/2/3) and data
     Scale(usize, [f64; 4]),
     /// A ORIGXn line, as the row (1/2/3) and data
     OrigX(usize, [f64; 4]),
     /// A MTRIXn line, as the row (1/2/3), serial number, data, and contained fields
     MtriX(usize, usize, [f64; 4], bool),
-    /// A CRYST1 line, containing: a, b, c, alpha, beta, gamma, space group character, and space group symbols as numbers
-    Crystal(f64, f64, f64, f64, f64, f64, String, usize),
+    /// A CRYST1 line, containing: a, b, c, alpha, beta, gamma, space group character, and space group symbols as numbers,
+    /// and the Z value if given
+    Crystal(f64, f64, f64, f64, f64, f64, String, Option<usize>),
     /// A MODEL with its serial number
     Model(usize),
     /// The Master record, having a checksum of the number of selected record types, used for verification
@@ -94,8 +208,8 @@ pub enum LexItem {
     ),
     /// ENDMODEL, end of the current model
     EndModel(),
-    /// TER =, termination of ATOM lines to allow for HETATMs to be defined
-    TER(),
+    /// TER =, termination of ATOM lines to allow for HETATMs to be defined, with its serial number if given
+    Ter(Option<usize>),
     /// END, end of the whole file
     End(),
     /// Empty line, just ignore
@@ -0,0 +1,63 @@
+use crate::structs::MetadataValue;
+
+/// A hook to recognise metadata of a Model in REMARK lines while parsing, see `parse_with_metadata_hooks`.
+/// It gets the full line and returns the (key, value) pairs found, or an empty Vec if it does
+/// not recognise the line.
+pub type MetadataHook = fn(&str) -> Vec<(String, MetadataValue)>;
+
+/// Recognise the results written by AutoDock Vina in every pose (`REMARK VINA RESULT:  -7.3  0.000  0.000`).
+/// It gives the keys `vina_affinity` (in kcal/mol), `vina_rmsd_lb` and `vina_rmsd_ub`.
+pub fn vina_result_hook(line: &str) -> Vec<(String, MetadataValue)> {
+    let text = match line.strip_prefix("REMARK VINA RESULT:") {
+        Some(text) => text,
+        None => return Vec::new(),
+    };
+    let values = text
+        .split_whitespace()
+        .map(|value| value.parse::<f64>())
+        .collect::<Result<Vec<f64>, _>>();
+    match values {
+        Ok(values) if values.len() == 3 => ["vina_affinity", "vina_rmsd_lb", "vina_rmsd_ub"]
+            .iter()
+            .zip(values)
+            .map(|(key, value)| (key.to_string(), MetadataValue::Number(value)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Recognise the metadata as written by `save_pdb` (`REMARK METADATA key = value`).
+pub fn metadata_remark_hook(line: &str) -> Vec<(String, MetadataValue)> {
+    line.strip_prefix("REMARK METADATA ")
+        .and_then(|text| text.split_once(" = "))
+        .map(|(key, value)| vec![(key.trim().to_string(), MetadataValue::parse(value))])
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hooks() {
+        assert_eq!(
+            vina_result_hook("REMARK VINA RESULT:    -7.3      1.250      2.000"),
+            vec![
+                ("vina_affinity".to_string(), MetadataValue::Number(-7.3)),
+                ("vina_rmsd_lb".to_string(), MetadataValue::Number(1.25)),
+                ("vina_rmsd_ub".to_string(), MetadataValue::Number(2.0)),
+            ]
+        );
+        assert!(vina_result_hook("REMARK VINA RESULT: -7.3").is_empty());
+        assert!(vina_result_hook("REMARK   2 RESOLUTION. 1.80 ANGSTROMS.").is_empty());
+        assert_eq!(
+            metadata_remark_hook("REMARK METADATA time = 12.5"),
+            vec![("time".to_string(), MetadataValue::Number(12.5))]
+        );
+        assert_eq!(
+            metadata_remark_hook("REMARK METADATA name = pose a"),
+            vec![("name".to_string(), MetadataValue::from("pose a"))]
+        );
+        assert!(metadata_remark_hook("REMARK METADATA").is_empty());
+    }
+}
@@ -1,4 +1,11 @@
+mod append;
 mod lexitem;
+mod metadata;
+mod open;
+mod options;
 mod parser;
 
+pub use metadata::*;
+pub use open::{open, open_with_format, Format};
+pub use options::ReadOptions;
 pub use parser::*;
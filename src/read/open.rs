@@ -0,0 +1,160 @@
+use super::parser::read_pdb;
+use crate::error::*;
+use crate::structs::PDB;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// The number of bytes read from the start of a file to detect its format
+const SNIFF_LENGTH: usize = 1024;
+
+/// The record types that can start a PDB file
+const PDB_RECORDS: &[&str] = &[
+    "HEADER", "TITLE ", "COMPND", "SOURCE", "KEYWDS", "EXPDTA", "AUTHOR", "REVDAT", "REMARK",
+    "SEQRES", "HET   ", "CRYST1", "SCALE1", "ORIGX1", "MODEL ", "ATOM  ", "HETATM",
+];
+
+/// The format of a structure file, see `open` and `open_with_format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// A PDB file, extensions `.pdb`, `.ent` and `.brk`
+    Pdb,
+    /// A PDBx/mmCIF file, extensions `.cif` and `.mmcif`
+    Mmcif,
+    /// A PQR file (PDB with charges and radii), extension `.pqr`
+    Pqr,
+    /// A GROMACS coordinate file, extension `.gro`
+    Gro,
+    /// A gzip compressed file, extension `.gz`
+    Gzip,
+}
+
+impl Format {
+    /// All formats, in the order they are listed
+    pub const ALL: [Format; 5] = [
+        Format::Pdb,
+        Format::Mmcif,
+        Format::Pqr,
+        Format::Gro,
+        Format::Gzip,
+    ];
+
+    /// Get if files of this format can be parsed by `open` and `open_with_format` in this build,
+    /// see `capabilities`
+    pub fn is_readable(&self) -> bool {
+        matches!(self, Format::Pdb)
+    }
+
+    /// Get the format belonging to the extension of the given path, if the extension is known
+    pub fn from_extension(path: &str) -> Option<Format> {
+        let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "pdb" | "ent" | "brk" => Some(Format::Pdb),
+            "cif" | "mmcif" => Some(Format::Mmcif),
+            "pqr" => Some(Format::Pqr),
+            "gro" => Some(Format::Gro),
+            "gz" => Some(Format::Gzip),
+            _ => None,
+        }
+    }
+
+    /// Get the format of a file from its first bytes: the gzip magic number, `data_` for mmCIF,
+    /// a PDB record type, or a title followed by the number of atoms for GRO
+    pub fn from_content(start: &[u8]) -> Option<Format> {
+        if start.starts_with(&[0x1f, 0x8b]) {
+            return Some(Format::Gzip);
+        }
+        let text = String::from_utf8_lossy(start);
+        let mut lines = text
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'));
+        let first = lines.next()?;
+        if first.starts_with("data_") {
+            Some(Format::Mmcif)
+        } else if PDB_RECORDS
+            .iter()
+            .any(|record| format!("{:6}", first).starts_with(record))
+        {
+            Some(Format::Pdb)
+        } else if lines
+            .next()
+            .is_some_and(|line| line.trim().parse::<usize>().is_ok())
+        {
+            Some(Format::Gro)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Format::Pdb => "PDB",
+            Format::Mmcif => "mmCIF",
+            Format::Pqr => "PQR",
+            Format::Gro => "GRO",
+            Format::Gzip => "gzip",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Open the given file, detecting its format from the extension or, if the extension is not
+/// known, from the first bytes of the file (see `Format`). Use `open_with_format` for files with
+/// a misleading extension. Only PDB files can be parsed for now, other formats give an error
+/// naming the detected format.
+/// Returns an PDBError when it found a BreakingError. Otherwise it returns the PDB with all errors/warnings found while parsing it.
+pub fn open(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
+    if let Some(format) = Format::from_extension(filename) {
+        return open_detected(
+            filename,
+            format,
+            &format!("from the extension of the file name \"{}\"", filename),
+        );
+    }
+    let mut start = Vec::with_capacity(SNIFF_LENGTH);
+    let read = File::open(filename)
+        .and_then(|file| file.take(SNIFF_LENGTH as u64).read_to_end(&mut start));
+    if read.is_err() {
+        return Err(PDBError::new(ErrorLevel::BreakingError, "Could not open file", "Could not open the specified file, make sure the path is correct, you have permission, and that it is not open in another program.", PDBContext::show(filename)));
+    }
+    match Format::from_content(&start) {
+        Some(format) => open_detected(filename, format, "from the start of the file"),
+        None => Err(PDBError::new(
+            ErrorLevel::BreakingError,
+            "Unknown file format",
+            "The format could not be detected: the extension is not recognised and the file does not start with a PDB record, `data_` (mmCIF), a GRO header or the gzip magic number. Use `open_with_format` to give the format.",
+            PDBContext::show(filename),
+        )),
+    }
+}
+
+/// Open the given file as the given format, regardless of its extension or content.
+/// Only PDB files can be parsed for now, other formats give an error.
+/// Returns an PDBError when it found a BreakingError. Otherwise it returns the PDB with all errors/warnings found while parsing it.
+pub fn open_with_format(filename: &str, format: Format) -> Result<(PDB, Vec<PDBError>), PDBError> {
+    open_detected(filename, format, "as given")
+}
+
+/// Open the given file with the parser for the given format, the reason describes how the
+/// format was found for the error of unsupported formats
+fn open_detected(
+    filename: &str,
+    format: Format,
+    reason: &str,
+) -> Result<(PDB, Vec<PDBError>), PDBError> {
+    match format {
+        Format::Pdb => read_pdb(filename),
+        _ => Err(PDBError::new(
+            ErrorLevel::BreakingError,
+            "Unsupported file format",
+            &format!(
+                "The file was detected as {} ({}), but only PDB files can be parsed. Use `open_with_format` if the format was detected wrongly.",
+                format, reason
+            ),
+            PDBContext::show(filename),
+        )),
+    }
+}
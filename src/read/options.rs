@@ -0,0 +1,157 @@
+use super::metadata::MetadataHook;
+use crate::cancellation::CancellationToken;
+use crate::validation_profile::ValidationProfile;
+
+#[derive(Debug, Clone, Default)]
+/// The options to use when parsing a PDB file, see `parse_with_options`
+pub struct ReadOptions {
+    /// The hooks to recognise metadata of the Models in REMARK lines
+    metadata_hooks: Vec<MetadataHook>,
+    /// If the line number of every Atom in the file should be kept
+    keep_source_lines: bool,
+    /// If the original text of the numeric fields of every Atom should be kept
+    keep_source_text: bool,
+    /// If only the header, all records before the first Atom, should be read
+    header_only: bool,
+    /// If missing END, TER and MASTER records should give warnings
+    warn_missing_records: bool,
+    /// If hydrogen atoms should be left out
+    discard_hydrogens: bool,
+    /// If atoms with their coordinates shifted out of the defined columns should be recovered
+    recover_misaligned_columns: bool,
+    /// The profile to validate with, instead of the checks of `validate_pdb`
+    validation_profile: Option<ValidationProfile>,
+    /// The token to cancel parsing with
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl ReadOptions {
+    /// Create new options, without any metadata hooks, without keeping the source lines or text, reading
+    /// the whole file including hydrogen atoms, without warnings for missing END, TER and MASTER
+    /// records, without recovering misaligned coordinate columns and validating as `validate_pdb` does
+    pub fn new() -> ReadOptions {
+        ReadOptions::default()
+    }
+
+    /// Get the hooks used to recognise metadata of the Models in REMARK lines
+    pub fn metadata_hooks(&self) -> &[MetadataHook] {
+        &self.metadata_hooks
+    }
+
+    /// Set the hooks used to recognise metadata of the Models in REMARK lines, see `parse_with_metadata_hooks`
+    pub fn set_metadata_hooks(&mut self, hooks: &[MetadataHook]) {
+        self.metadata_hooks = hooks.to_vec();
+    }
+
+    /// Get if the line number of every Atom in the file is kept
+    pub fn keep_source_lines(&self) -> bool {
+        self.keep_source_lines
+    }
+
+    /// Set if the line number of every Atom in the file should be kept (see `Atom::source_line`).
+    /// The validation errors found after parsing then point at the lines of the Atoms involved.
+    /// This is off by default to keep the memory use down.
+    pub fn set_keep_source_lines(&mut self, keep: bool) {
+        self.keep_source_lines = keep;
+    }
+
+    /// Get if the original text of the coordinates, occupancy and B-factor of every Atom is kept
+    pub fn keep_source_text(&self) -> bool {
+        self.keep_source_text
+    }
+
+    /// Set if the original text of the coordinates, occupancy and B-factor fields of every Atom
+    /// should be kept (see `Atom::source_text`), to write these fields back exactly as they were
+    /// read, including trailing zeros and the rounding of the author (see
+    /// `SaveOptions::set_prefer_source_text`). This is off by default, as it adds about a hundred
+    /// bytes to every Atom.
+    pub fn set_keep_source_text(&mut self, keep: bool) {
+        self.keep_source_text = keep;
+    }
+
+    /// Get if only the header of the file is read
+    pub fn header_only(&self) -> bool {
+        self.header_only
+    }
+
+    /// Set if only the header of the file should be read, see `parse_header`. Reading stops at the
+    /// first ATOM, HETATM or MODEL record, so the resulting PDB has all metadata given before the
+    /// coordinates (remarks, CRYST1, SCALEn, ORIGXn, MTRIXn, HET and SEQRES records) but no Models.
+    pub fn set_header_only(&mut self, header_only: bool) {
+        self.header_only = header_only;
+    }
+
+    /// Get if missing END, TER and MASTER records give warnings
+    pub fn warn_missing_records(&self) -> bool {
+        self.warn_missing_records
+    }
+
+    /// Set if a missing END record, a missing MASTER record or the absence of any TER record with
+    /// Atoms present should each give a `LooseWarning`. This is off by default, as files written
+    /// by scripts often leave these records out. Whether the records were found is always available
+    /// with `PDB::saw_end`, `PDB::saw_ter` and `PDB::saw_master`. When only the header is read
+    /// (see `set_header_only`) these warnings are never given.
+    pub fn set_warn_missing_records(&mut self, warn: bool) {
+        self.warn_missing_records = warn;
+    }
+
+    /// Get if hydrogen atoms are left out
+    pub fn discard_hydrogens(&self) -> bool {
+        self.discard_hydrogens
+    }
+
+    /// Set if hydrogen (and deuterium) atoms should be left out, to save the time and memory of
+    /// parsing atoms that will be removed anyway, for example for protonated MD snapshots. The
+    /// ATOM and HETATM records of hydrogens, and the ANISOU records directly following them, are
+    /// skipped before they are parsed. An atom is a hydrogen if its element column is H or D, or if
+    /// the element column is empty and its atom name has H or D as element (in the second column
+    /// of the name if the first column is a space or a digit, or in the first column for ATOM
+    /// records). The MASTER check on the number of atoms takes the skipped atoms into account.
+    pub fn set_discard_hydrogens(&mut self, discard: bool) {
+        self.discard_hydrogens = discard;
+    }
+
+    /// Get if atoms with their coordinates shifted out of the defined columns are recovered
+    pub fn recover_misaligned_columns(&self) -> bool {
+        self.recover_misaligned_columns
+    }
+
+    /// Set if ATOM and HETATM records with their coordinates shifted out of the defined columns
+    /// should be recovered, as written by some programs with an off by one error in their format.
+    /// When the coordinates cannot be read from their columns, the text after the insertion code
+    /// (column 27) is split on whitespace instead. If this gives exactly the expected number of numbers (x, y and z,
+    /// followed by the occupancy and B-factor if the line is long enough to hold them) the record
+    /// is read with these numbers and the fields after them shifted by the same amount, and a
+    /// `StrictWarning` describes the misalignment. Otherwise the record gives the same error as
+    /// without recovery. This is off by default.
+    pub fn set_recover_misaligned_columns(&mut self, recover: bool) {
+        self.recover_misaligned_columns = recover;
+    }
+
+    /// Get the profile used to validate, if set
+    pub fn validation_profile(&self) -> Option<&ValidationProfile> {
+        self.validation_profile.as_ref()
+    }
+
+    /// Set the profile to validate with, or `None` to validate as `validate_pdb` does (the default).
+    /// With a profile the PDB is validated with `validate_with` after parsing, the errors of the
+    /// checks done while parsing (see `ValidationCheck`) are only given if the check is in the
+    /// profile, and at the level set in the profile. The missing END, MASTER and TER records are
+    /// also reported if the profile has `ValidationCheck::MissingRecords`, even if
+    /// `set_warn_missing_records` is off.
+    pub fn set_validation_profile(&mut self, profile: Option<ValidationProfile>) {
+        self.validation_profile = profile;
+    }
+
+    /// Get the token used to cancel parsing, if set
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+
+    /// Set the token to cancel parsing with, see `CancellationToken`. When the token is cancelled
+    /// parsing stops within a thousand lines and fails with an error for which
+    /// `PDBError::is_cancellation` is `true`.
+    pub fn set_cancellation_token(&mut self, token: Option<CancellationToken>) {
+        self.cancellation_token = token;
+    }
+}
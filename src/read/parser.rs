@@ -1,32 +1,114 @@
 use super::lexitem::*;
+use super::metadata::MetadataHook;
+use super::options::ReadOptions;
+use crate::cancellation::*;
 use crate::error::*;
 use crate::reference_tables;
+use crate::serial_overflow::*;
 use crate::structs::*;
 use crate::validate::*;
+use crate::validation_profile::*;
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::str::FromStr;
 
 /// Parse the given filename into a PDB struct.
 /// Returns an PDBError when it found a BreakingError. Otherwise it returns the PDB with all errors/warnings found while parsing it.
-pub fn parse(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
+pub fn read_pdb(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
+    parse_with_metadata_hooks(filename, &[])
+}
+
+/// Parse the given filename into a PDB struct, recognising metadata of the Models in REMARK lines
+/// with the given hooks (see `MetadataHook`). When a hook recognises a REMARK line the found
+/// (key, value) pairs are set as metadata on the current Model and the line is not stored as a
+/// remark. Metadata found before a MODEL record is given to that Model.
+/// Returns an PDBError when it found a BreakingError. Otherwise it returns the PDB with all errors/warnings found while parsing it.
+/// ## Arguments
+/// * `filename` - the file to parse
+/// * `hooks` - the hooks to use, for example `vina_result_hook` or `metadata_remark_hook`
+pub fn parse_with_metadata_hooks(
+    filename: &str,
+    hooks: &[MetadataHook],
+) -> Result<(PDB, Vec<PDBError>), PDBError> {
+    let mut options = ReadOptions::new();
+    options.set_metadata_hooks(hooks);
+    parse_with_options(filename, &options)
+}
+
+/// Parse only the header of the given filename into a PDB struct, to quickly scan files for their
+/// metadata. Reading stops at the first ATOM, HETATM or MODEL record, so the PDB contains the
+/// remarks, unit cell, symmetry, transformations, HET and SEQRES records but no Models.
+/// Returns an PDBError when it found a BreakingError. Otherwise it returns the PDB with all errors/warnings found while parsing it.
+pub fn parse_header(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
+    let mut options = ReadOptions::new();
+    options.set_header_only(true);
+    parse_with_options(filename, &options)
+}
+
+/// Parse the given filename into a PDB struct using the given options, see `ReadOptions`.
+/// Returns an PDBError when it found a BreakingError. Otherwise it returns the PDB with all errors/warnings found while parsing it.
+pub fn parse_with_options(
+    filename: &str,
+    options: &ReadOptions,
+) -> Result<(PDB, Vec<PDBError>), PDBError> {
     // Open a file a use a buffered reader to minimise memory use while immediately lexing the line followed by adding it to the current PDB
     let mut errors = Vec::new();
     let file = if let Ok(f) = File::open(filename) {
         f
     } else {
-        return Err(PDBError::new(ErrorLevel::BreakingError, "Could not open file", "Could not open the specified file, make sure the path is correct, you have permission, and that it is not open in another program.", Context::show(filename)));
+        return Err(PDBError::new(ErrorLevel::BreakingError, "Could not open file", "Could not open the specified file, make sure the path is correct, you have permission, and that it is not open in another program.", PDBContext::show(filename)));
     };
     let reader = BufReader::new(file);
 
     let mut pdb = PDB::new();
     let mut current_model = Model::new(0);
+    let mut seqres_lengths = Vec::new();
+    let mut previous_serial = None;
+    let mut after_ter = false;
+    let mut serial_gaps = None;
+    let (mut saw_end, mut saw_ter, mut saw_master) = (false, false, false);
+    let mut discarded_atoms = 0;
+    let mut discarded_serial = None;
+    let mut discarded_serials = HashSet::new();
+    let mut conect_records = Vec::new();
+    let mut ssbond_contexts = Vec::new();
+    let mut title_lines = Vec::new();
+    let (mut obsolete_lines, mut supersedes_lines) = (None, None);
+    let mut split_lines = Vec::new();
+    let mut caveat_lines = None;
+    let mut title_context = None;
+    let (mut compound_lines, mut source_lines) = (Vec::new(), Vec::new());
+    let (mut compound_context, mut source_context) = (None, None);
+    let mut method_lines = Vec::new();
+    let mut method_context = None;
+    let mut model_type_lines = Vec::new();
+    let mut model_type_context = None;
+    let mut after_dbref1 = false;
+    let mut heterogen_lines: BTreeMap<String, [Vec<(usize, String)>; 3]> = BTreeMap::new();
+    let mut component_numbers = HashMap::new();
+    let mut heterogen_context = None;
+    let mut site_lines: Vec<(String, usize, Vec<_>, PDBContext)> = Vec::new();
+    let mut record_counts: HashMap<[u8; 6], usize> = HashMap::new();
+    let mut matrix_rows = BTreeMap::new();
 
+    let mut read_lines = 0;
     for (mut linenumber, read_line) in reader.lines().enumerate() {
+        if should_cancel(options.cancellation_token(), linenumber) {
+            return Err(cancelled_error(
+                "Parsing",
+                linenumber,
+                PDBContext::show(filename),
+            ));
+        }
         linenumber += 1; // 1 based indexing in files
+        read_lines = linenumber;
 
-        let line = if let Ok(l) = read_line {
+        // Remove trailing whitespace, like the `\r` of Windows line endings, so it does not end
+        // up in the last field of the line
+        let line = if let Ok(mut l) = read_line {
+            l.truncate(l.trim_end().len());
             l
         } else {
             return Err(PDBError::new(
@@ -36,90 +118,313 @@ pub fn parse(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
                     "Could not read line {} while parsing the input file.",
                     linenumber
                 ),
-                Context::show(filename),
+                PDBContext::show(filename),
             ));
         };
+        if options.header_only()
+            && (line.starts_with("ATOM") || line.starts_with("HETATM") || line.starts_with("MODEL"))
+        {
+            break;
+        }
+        if !line.is_empty() {
+            *record_counts.entry(record_type(&line)).or_insert(0) += 1;
+        }
+        if options.discard_hydrogens() {
+            let serial_number = || {
+                parse_serial(
+                    PDBContext::line(linenumber, &line, 6, 5),
+                    &line_chars(&line, 6, 11),
+                )
+                .ok()
+            };
+            if is_hydrogen_record(&line) {
+                // Only Normal Atoms as the MASTER check compares with `PDB::total_atom_count`
+                if line.starts_with("ATOM") {
+                    discarded_atoms += 1;
+                }
+                discarded_serial = serial_number();
+                if let Some(serial) = discarded_serial {
+                    discarded_serials.insert(serial);
+                }
+                if discarded_serial.is_some() {
+                    previous_serial = discarded_serial;
+                    after_ter = false;
+                }
+                continue;
+            }
+            // The ANISOU record of a hydrogen directly follows its ATOM or HETATM record
+            if line.starts_with("ANISOU")
+                && discarded_serial.is_some()
+                && serial_number() == discarded_serial
+            {
+                continue;
+            }
+            discarded_serial = None;
+        }
+        if line.starts_with("REMARK") {
+            let metadata = options
+                .metadata_hooks()
+                .iter()
+                .flat_map(|hook| hook(&line))
+                .collect::<Vec<_>>();
+            if !metadata.is_empty() {
+                for (key, value) in metadata {
+                    if let Err(message) = current_model.set_metadata(&key, value) {
+                        errors.push(PDBError::new(
+                            ErrorLevel::LooseWarning,
+                            "Invalid metadata",
+                            &message,
+                            PDBContext::full_line(linenumber, &line),
+                        ));
+                    }
+                }
+                continue;
+            }
+        }
+
         let lineresult = if line.len() > 6 {
             match &line[..6] {
-                "REMARK" => lex_remark(linenumber, line),
-                "ATOM  " => lex_atom(linenumber, line, false),
-                "ANISOU" => lex_anisou(linenumber, line),
-                "HETATM" => lex_atom(linenumber, line, true),
-                "CRYST1" => lex_cryst(linenumber, line),
-                "SCALE1" => lex_scale(linenumber, line, 0),
-                "SCALE2" => lex_scale(linenumber, line, 1),
-                "SCALE3" => lex_scale(linenumber, line, 2),
-                "ORIGX1" => lex_origx(linenumber, line, 0),
-                "ORIGX2" => lex_origx(linenumber, line, 1),
-                "ORIGX3" => lex_origx(linenumber, line, 2),
-                "MTRIX1" => lex_mtrix(linenumber, line, 0),
-                "MTRIX2" => lex_mtrix(linenumber, line, 1),
-                "MTRIX3" => lex_mtrix(linenumber, line, 2),
-                "MODEL " => lex_model(linenumber, line),
-                "MASTER" => lex_master(linenumber, line),
+                "REMARK" => lex_remark(linenumber, &line),
+                "ATOM  " | "HETATM" => {
+                    let hetero = line.starts_with("HETATM");
+                    match lex_atom(linenumber, &line, hetero) {
+                        Err(error) if options.recover_misaligned_columns() => {
+                            match lex_misaligned_atom(linenumber, &line, hetero) {
+                                Some((item, warning)) => {
+                                    errors.push(warning);
+                                    Ok(item)
+                                }
+                                None => Err(error),
+                            }
+                        }
+                        result => result,
+                    }
+                }
+                "ANISOU" => lex_anisou(linenumber, &line),
+                "HET   " => lex_het(linenumber, &line),
+                "HETNAM" => lex_heterogen_text(linenumber, &line)
+                    .map(|(continuation, id, text)| LexItem::HeterogenName(continuation, id, text)),
+                "HETSYN" => lex_heterogen_text(linenumber, &line).map(|(continuation, id, text)| {
+                    LexItem::HeterogenSynonym(continuation, id, text)
+                }),
+                "FORMUL" => lex_formul(linenumber, &line),
+                "SEQRES" => lex_seqres(linenumber, &line),
+                "CRYST1" => lex_cryst(linenumber, &line),
+                "SCALE1" => lex_scale(linenumber, &line, 0),
+                "SCALE2" => lex_scale(linenumber, &line, 1),
+                "SCALE3" => lex_scale(linenumber, &line, 2),
+                "ORIGX1" => lex_origx(linenumber, &line, 0),
+                "ORIGX2" => lex_origx(linenumber, &line, 1),
+                "ORIGX3" => lex_origx(linenumber, &line, 2),
+                "MTRIX1" => lex_mtrix(linenumber, &line, 0),
+                "MTRIX2" => lex_mtrix(linenumber, &line, 1),
+                "MTRIX3" => lex_mtrix(linenumber, &line, 2),
+                "MODEL " => lex_model(linenumber, &line),
+                "MASTER" => lex_master(linenumber, &line),
                 "ENDMDL" => Ok(LexItem::EndModel()),
-                "TER   " => Ok(LexItem::TER()),
+                "TER   " => lex_ter(linenumber, &line),
+                "CONECT" => lex_conect(linenumber, &line),
+                "SSBOND" => lex_ssbond(linenumber, &line),
+                "LINK  " => lex_link(linenumber, &line),
+                "CISPEP" => lex_cispep(linenumber, &line),
+                "SITE  " => lex_site(linenumber, &line),
+                "DBREF " => lex_dbref(linenumber, &line),
+                "DBREF1" => lex_dbref1(linenumber, &line),
+                "DBREF2" => lex_dbref2(linenumber, &line),
+                "SEQADV" => lex_seqadv(linenumber, &line),
+                "HEADER" => Ok(lex_header(&line)),
+                "OBSLTE" => lex_entry_replacement(linenumber, &line).map(
+                    |(continuation, date, id_code, entries)| {
+                        LexItem::Obsolete(continuation, date, id_code, entries)
+                    },
+                ),
+                "SPRSDE" => lex_entry_replacement(linenumber, &line).map(
+                    |(continuation, date, id_code, entries)| {
+                        LexItem::Supersedes(continuation, date, id_code, entries)
+                    },
+                ),
+                "SPLIT " => lex_split(linenumber, &line),
+                "CAVEAT" => lex_caveat(linenumber, &line),
+                "TITLE " => lex_continued(linenumber, &line)
+                    .map(|(continuation, text)| LexItem::Title(continuation, text)),
+                "COMPND" => lex_continued(linenumber, &line)
+                    .map(|(continuation, text)| LexItem::Compound(continuation, text)),
+                "SOURCE" => lex_continued(linenumber, &line)
+                    .map(|(continuation, text)| LexItem::Source(continuation, text)),
+                "EXPDTA" => lex_continued(linenumber, &line)
+                    .map(|(continuation, text)| LexItem::ExperimentalData(continuation, text)),
+                "MDLTYP" => lex_continued(linenumber, &line)
+                    .map(|(continuation, text)| LexItem::ModelType(continuation, text)),
+                "NUMMDL" => parse_number(
+                    PDBContext::line(linenumber, &line, 10, 4),
+                    &line_chars(&line, 10, 14),
+                )
+                .map(LexItem::ModelCount),
+                "HELIX " => lex_helix(linenumber, &line),
                 "END   " => Ok(LexItem::End()),
-                _ => Err(PDBError::new(ErrorLevel::GeneralWarning, "Could not recognise tag.", "Could not parse the tag above, it is possible that it is valid PDB but just not supported right now.",Context::full_line(linenumber, &line))),
+                _ => Err(PDBError::new(ErrorLevel::GeneralWarning, "Could not recognise tag.", "Could not parse the tag above, it is possible that it is valid PDB but just not supported right now.",PDBContext::full_line(linenumber, &line))),
             }
         } else if line.len() > 2 {
             match &line[..3] {
-                "TER" => Ok(LexItem::TER()),
+                "TER" => Ok(LexItem::Ter(None)),
+                "END" if line.starts_with("ENDMDL") => Ok(LexItem::EndModel()),
                 "END" => Ok(LexItem::End()),
-                _ => Err(PDBError::new(ErrorLevel::GeneralWarning, "Could not recognise tag.", "Could not parse the tag above, it is possible that it is valid PDB but just not supported right now.",Context::full_line(linenumber, &line))),
+                _ => Err(PDBError::new(ErrorLevel::GeneralWarning, "Could not recognise tag.", "Could not parse the tag above, it is possible that it is valid PDB but just not supported right now.",PDBContext::full_line(linenumber, &line))),
             }
         } else if !line.is_empty() {
-            Err(PDBError::new(ErrorLevel::GeneralWarning, "Could not recognise tag.", "Could not parse the tag above, it is possible that it is valid PDB but just not supported right now.",Context::full_line(linenumber, &line)))
+            Err(PDBError::new(ErrorLevel::GeneralWarning, "Could not recognise tag.", "Could not parse the tag above, it is possible that it is valid PDB but just not supported right now.",PDBContext::full_line(linenumber, &line)))
         } else {
             Ok(LexItem::Empty())
         };
 
         // Then immediately add this lines information to the final PDB struct
+        let follows_dbref1 = std::mem::replace(&mut after_dbref1, false);
         if let Ok(result) = lineresult {
             match result {
-                LexItem::Remark(num, text) => pdb.add_remark(num, text.to_string()),
+                LexItem::Remark(num, text) => {
+                    if num == 2 && text.trim_start().starts_with("RESOLUTION.") {
+                        if let Err(message) = remark_resolution(&text) {
+                            errors.push(PDBError::new(
+                                ErrorLevel::LooseWarning,
+                                "Invalid resolution",
+                                &format!("{}, so it is ignored.", message),
+                                PDBContext::full_line(linenumber, &line),
+                            ));
+                        }
+                    }
+                    pdb.add_remark(num, text.to_string())
+                }
                 LexItem::Atom(
                     hetero,
                     serial_number,
                     name,
-                    _,
+                    alternate_location,
                     residue_name,
                     chain_id,
                     residue_serial_number,
-                    _,
+                    insertion_code,
                     x,
                     y,
                     z,
                     occ,
                     b,
-                    _,
+                    segment_id,
                     element,
                     charge,
                 ) => {
-                    let atom = Atom::new(serial_number, name, x, y, z, occ, b, element, charge)
+                    let mut atom = Atom::new(serial_number, name, x, y, z, occ, b, element, charge)
+                        .expect("Invalid characters in atom creation");
+                    atom.set_alternate_location(alternate_location)
                         .expect("Invalid characters in atom creation");
+                    atom.set_segment_id(&segment_id.iter().collect::<String>())
+                        .expect("Invalid characters in atom creation");
+                    atom.set_hetero(hetero);
+                    if options.keep_source_lines() {
+                        atom.set_source_line(Some(linenumber));
+                    }
+                    if options.keep_source_text() {
+                        let field = |start: usize, end: usize| {
+                            format!(
+                                "{:<width$}",
+                                line_chars(&line, start, end).iter().collect::<String>(),
+                                width = end - start
+                            )
+                        };
+                        let text = [
+                            field(30, 38),
+                            field(38, 46),
+                            field(46, 54),
+                            field(54, 60),
+                            field(60, 66),
+                        ];
+                        // Text with invalid characters (like tabs) is not kept
+                        let _ = atom.set_source_text(Some([
+                            &text[0], &text[1], &text[2], &text[3], &text[4],
+                        ]));
+                    }
+
+                    check_serial_order(
+                        &mut errors,
+                        &mut serial_gaps,
+                        (previous_serial, after_ter, serial_number),
+                        "Atom serial number out of order",
+                        PDBContext::line(linenumber, &line, 6, 5),
+                    );
+                    previous_serial = Some(serial_number);
+                    after_ter = false;
 
+                    let insertion_code = Some(insertion_code).filter(|code| *code != ' ');
                     if hetero {
                         current_model.add_hetero_atom(
                             atom,
                             chain_id,
                             residue_serial_number,
+                            insertion_code,
                             residue_name,
                         );
                     } else {
-                        current_model.add_atom(atom, chain_id, residue_serial_number, residue_name);
+                        current_model.add_atom(
+                            atom,
+                            chain_id,
+                            residue_serial_number,
+                            insertion_code,
+                            residue_name,
+                        );
                     }
                 }
-                LexItem::Anisou(s, n, _, _r, _c, _rs, _, factors, _, _e, _ch) => {
-                    let mut found = false;
-                    for atom in current_model.all_atoms_mut().rev() {
-                        if atom.serial_number() == s {
-                            atom.set_anisotropic_temperature_factors(factors);
-                            found = true;
-                            break;
+                LexItem::Anisou(s, n, a, r, c, rs, _, factors, sg, e, _ch) => {
+                    let found = current_model.all_chains_mut().rev().find_map(|chain| {
+                        let chain_id = chain.id();
+                        chain.residues_mut().rev().find_map(|residue| {
+                            let index =
+                                residue.atoms().position(|atom| atom.serial_number() == s)?;
+                            let name =
+                                residue.conformer_name(residue.atom(index)?.alternate_location());
+                            let serial_number = residue.serial_number();
+                            Some((chain_id, serial_number, name, residue.atom_mut(index)?))
+                        })
+                    });
+                    if let Some((chain_id, serial_number, residue_name, atom)) = found {
+                        atom.set_anisotropic_u(factors);
+                        let anisou_fields = [
+                            n.iter().collect::<String>().trim().to_string(),
+                            a.to_string(),
+                            r.iter().collect::<String>().trim().to_string(),
+                            rs.to_string(),
+                            c.to_string(),
+                            sg.iter().collect::<String>().trim().to_string(),
+                            e.iter().collect::<String>().trim().to_string(),
+                        ];
+                        let atom_fields = [
+                            atom.name(),
+                            atom.alternate_location().to_string(),
+                            residue_name,
+                            serial_number.to_string(),
+                            chain_id.to_string(),
+                            atom.segment_id().to_string(),
+                            atom.element(),
+                        ];
+                        for (index, (field, offset, length)) in ANISOU_FIELDS.iter().enumerate() {
+                            let (anisou, atom) = (&anisou_fields[index], &atom_fields[index]);
+                            // An element or segment id is only compared if given in both records
+                            if (*field == "element" || *field == "segment id")
+                                && (anisou.is_empty() || atom.is_empty())
+                            {
+                                continue;
+                            }
+                            if !anisou.eq_ignore_ascii_case(atom) {
+                                errors.push(PDBError::new(
+                                    ErrorLevel::StrictWarning,
+                                    "ANISOU does not match atom",
+                                    &format!(
+                                        "The {} of the ANISOU record (\"{}\") differs from the {} of atom {} (\"{}\") it belongs to.",
+                                        field, anisou, field, s, atom
+                                    ),
+                                    PDBContext::line(linenumber, &line, *offset, *length),
+                                ));
+                            }
                         }
-                    }
-                    if !found {
+                    } else {
                         println!(
                             "Could not find atom for temperature factors, coupled to atom {} {}",
                             s,
@@ -127,26 +432,495 @@ pub fn parse(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
                         )
                     }
                 }
+                LexItem::Ter(serial_number) => {
+                    saw_ter = true;
+                    if let Some(serial_number) = serial_number {
+                        check_serial_order(
+                            &mut errors,
+                            &mut serial_gaps,
+                            (previous_serial, after_ter, serial_number),
+                            "TER serial number out of order",
+                            PDBContext::line(linenumber, &line, 6, 5),
+                        );
+                        previous_serial = Some(serial_number);
+                    } else {
+                        after_ter = true;
+                    }
+                }
                 LexItem::Model(number) => {
-                    if current_model.atom_count() > 0 {
+                    previous_serial = None;
+                    after_ter = false;
+                    let mut model = Model::new(number);
+                    if current_model.total_atom_count() > 0 {
                         pdb.add_model(current_model)
+                    } else {
+                        // Keep the metadata found before the MODEL record
+                        for (key, value) in current_model.all_metadata() {
+                            model
+                                .set_metadata(key, value.clone())
+                                .expect("Invalid metadata key");
+                        }
                     }
 
-                    current_model = Model::new(number);
+                    current_model = model;
+                }
+                LexItem::Het(id, chain_id, residue_serial_number, insertion_code, atoms, text) => {
+                    match Het::new(
+                        id,
+                        chain_id,
+                        residue_serial_number,
+                        insertion_code,
+                        atoms,
+                        &text,
+                    ) {
+                        Some(het) => pdb.add_het(het),
+                        None => errors.push(PDBError::new(
+                            ErrorLevel::LooseWarning,
+                            "Invalid HET",
+                            "This HET record contains invalid characters, so it is ignored.",
+                            PDBContext::full_line(linenumber, &line),
+                        )),
+                    }
+                }
+                LexItem::HeterogenName(continuation, id, text) => {
+                    heterogen_lines.entry(id).or_default()[0].push((continuation, text));
+                    heterogen_context
+                        .get_or_insert_with(|| PDBContext::full_line(linenumber, &line));
+                }
+                LexItem::HeterogenSynonym(continuation, id, text) => {
+                    heterogen_lines.entry(id).or_default()[1].push((continuation, text));
+                    heterogen_context
+                        .get_or_insert_with(|| PDBContext::full_line(linenumber, &line));
+                }
+                LexItem::Formula(component_number, id, continuation, text) => {
+                    if let Some(number) = component_number {
+                        component_numbers.entry(id.clone()).or_insert(number);
+                    }
+                    heterogen_lines.entry(id).or_default()[2].push((continuation, text));
+                    heterogen_context
+                        .get_or_insert_with(|| PDBContext::full_line(linenumber, &line));
+                }
+                LexItem::SSBond(
+                    serial_number,
+                    chain_ids,
+                    residue_serial_numbers,
+                    insertion_codes,
+                    symmetry_operators,
+                    length,
+                ) => {
+                    let mut ssbond = match SSBond::new(
+                        serial_number,
+                        chain_ids,
+                        residue_serial_numbers,
+                        insertion_codes,
+                    ) {
+                        Some(ssbond) => ssbond,
+                        None => {
+                            errors.push(PDBError::new(
+                                ErrorLevel::LooseWarning,
+                                "Invalid SSBOND",
+                                "The chain ids or insertion codes of this SSBOND record contain invalid characters, so it is ignored.",
+                                PDBContext::full_line(linenumber, &line),
+                            ));
+                            continue;
+                        }
+                    };
+                    ssbond.set_symmetry_operators(symmetry_operators);
+                    ssbond.set_length(length);
+                    pdb.add_ssbond(ssbond);
+                    ssbond_contexts.push(PDBContext::full_line(linenumber, &line));
+                }
+                LexItem::Link(
+                    atom_names,
+                    alternate_locations,
+                    residue_names,
+                    chain_ids,
+                    residue_serial_numbers,
+                    insertion_codes,
+                    symmetry_operators,
+                    length,
+                ) => {
+                    let mut link = match Link::new(
+                        [&atom_names[0], &atom_names[1]],
+                        alternate_locations,
+                        [&residue_names[0], &residue_names[1]],
+                        chain_ids,
+                        residue_serial_numbers,
+                        insertion_codes,
+                    ) {
+                        Some(link) => link,
+                        None => {
+                            errors.push(PDBError::new(
+                                ErrorLevel::LooseWarning,
+                                "Invalid LINK",
+                                "This LINK record contains invalid characters, so it is ignored.",
+                                PDBContext::full_line(linenumber, &line),
+                            ));
+                            continue;
+                        }
+                    };
+                    link.set_symmetry_operators(symmetry_operators);
+                    link.set_length(length);
+                    pdb.add_link(link);
+                }
+                LexItem::CisPeptide(
+                    serial_number,
+                    residue_names,
+                    chain_ids,
+                    residue_serial_numbers,
+                    insertion_codes,
+                    model_number,
+                    angle,
+                ) => {
+                    let mut cis_peptide = match CisPeptide::new(
+                        serial_number,
+                        [&residue_names[0], &residue_names[1]],
+                        chain_ids,
+                        residue_serial_numbers,
+                        insertion_codes,
+                        model_number,
+                    ) {
+                        Some(cis_peptide) => cis_peptide,
+                        None => {
+                            errors.push(PDBError::new(
+                                ErrorLevel::LooseWarning,
+                                "Invalid CISPEP",
+                                "This CISPEP record contains invalid characters, so it is ignored.",
+                                PDBContext::full_line(linenumber, &line),
+                            ));
+                            continue;
+                        }
+                    };
+                    cis_peptide.set_angle(angle);
+                    pdb.add_cis_peptide(cis_peptide);
+                }
+                LexItem::Site(sequence_number, id, residue_count, residues) => {
+                    match site_lines.iter_mut().find(|(site, _, _, _)| *site == id) {
+                        Some((_, _, lines, _)) => lines.push((sequence_number, residues)),
+                        None => site_lines.push((
+                            id,
+                            residue_count,
+                            vec![(sequence_number, residues)],
+                            PDBContext::full_line(linenumber, &line),
+                        )),
+                    }
+                }
+                LexItem::DatabaseReference(
+                    chain_id,
+                    pdb_range,
+                    database,
+                    accession,
+                    database_id_code,
+                    database_range,
+                ) => {
+                    match DatabaseReference::new(
+                        chain_id,
+                        &database,
+                        &accession,
+                        &database_id_code,
+                        pdb_range,
+                        database_range,
+                    ) {
+                        Some(reference) => pdb.add_database_reference(reference),
+                        None => errors.push(PDBError::new(
+                            ErrorLevel::LooseWarning,
+                            "Invalid DBREF",
+                            "This DBREF record contains invalid characters, so it is ignored.",
+                            PDBContext::full_line(linenumber, &line),
+                        )),
+                    }
+                }
+                LexItem::DatabaseReference1(chain_id, pdb_range, database, database_id_code) => {
+                    // The accession code and database range are given in the following DBREF2
+                    match DatabaseReference::new(
+                        chain_id,
+                        &database,
+                        "",
+                        &database_id_code,
+                        pdb_range,
+                        [(0, ' '); 2],
+                    ) {
+                        Some(reference) => {
+                            pdb.add_database_reference(reference);
+                            after_dbref1 = true;
+                        }
+                        None => errors.push(PDBError::new(
+                            ErrorLevel::LooseWarning,
+                            "Invalid DBREF1",
+                            "This DBREF1 record contains invalid characters, so it is ignored.",
+                            PDBContext::full_line(linenumber, &line),
+                        )),
+                    }
+                }
+                LexItem::DatabaseReference2(chain_id, accession, database_range) => {
+                    match pdb
+                        .database_references_mut()
+                        .next_back()
+                        .filter(|reference| follows_dbref1 && reference.chain_id() == chain_id)
+                    {
+                        Some(reference) => {
+                            if let Err(message) = reference.set_database_entry(
+                                &accession,
+                                [(database_range[0], ' '), (database_range[1], ' ')],
+                            ) {
+                                errors.push(PDBError::new(
+                                    ErrorLevel::LooseWarning,
+                                    "Invalid DBREF2",
+                                    &format!("{}, so it is ignored.", message),
+                                    PDBContext::full_line(linenumber, &line),
+                                ));
+                            }
+                        }
+                        None => errors.push(PDBError::new(
+                            ErrorLevel::LooseWarning,
+                            "DBREF2 without DBREF1",
+                            &format!("This DBREF2 record of chain {} does not directly follow a DBREF1 record of the same chain, so it is ignored.", chain_id),
+                            PDBContext::full_line(linenumber, &line),
+                        )),
+                    }
+                }
+                LexItem::SequenceDifference(
+                    residue_name,
+                    chain_id,
+                    residue,
+                    database,
+                    accession,
+                    database_residue_name,
+                    database_serial_number,
+                    comment,
+                ) => {
+                    // SEQADV records only hold the first 4 characters of the database name and 9 of the accession code
+                    match pdb.database_references_mut().find(|reference| {
+                        reference.chain_id() == chain_id
+                            && reference.database().starts_with(&database)
+                            && reference.accession().starts_with(&accession)
+                    }) {
+                        Some(reference) => match SequenceDifference::new(
+                            &residue_name,
+                            residue,
+                            &database_residue_name,
+                            database_serial_number,
+                            &comment,
+                        ) {
+                            Some(difference) => reference.add_sequence_difference(difference),
+                            None => errors.push(PDBError::new(
+                                ErrorLevel::LooseWarning,
+                                "Invalid SEQADV",
+                                "This SEQADV record contains invalid characters, so it is ignored.",
+                                PDBContext::full_line(linenumber, &line),
+                            )),
+                        },
+                        None => errors.push(PDBError::new(
+                            ErrorLevel::LooseWarning,
+                            "SEQADV without DBREF",
+                            &format!("This SEQADV record refers to database entry {} {} of chain {}, which is not given in a DBREF record before it, so it is ignored.", database, accession, chain_id),
+                            PDBContext::full_line(linenumber, &line),
+                        )),
+                    }
+                }
+                LexItem::Obsolete(continuation, date, id_code, entries) => {
+                    obsolete_lines
+                        .get_or_insert_with(|| {
+                            (
+                                date,
+                                id_code,
+                                Vec::new(),
+                                PDBContext::full_line(linenumber, &line),
+                            )
+                        })
+                        .2
+                        .push((continuation, entries));
+                }
+                LexItem::Supersedes(continuation, date, id_code, entries) => {
+                    supersedes_lines
+                        .get_or_insert_with(|| {
+                            (
+                                date,
+                                id_code,
+                                Vec::new(),
+                                PDBContext::full_line(linenumber, &line),
+                            )
+                        })
+                        .2
+                        .push((continuation, entries));
+                }
+                LexItem::Split(continuation, entries) => {
+                    split_lines.push((
+                        continuation,
+                        entries,
+                        PDBContext::full_line(linenumber, &line),
+                    ));
+                }
+                LexItem::Caveat(continuation, id_code, text) => {
+                    caveat_lines
+                        .get_or_insert_with(|| {
+                            (
+                                id_code,
+                                Vec::new(),
+                                PDBContext::full_line(linenumber, &line),
+                            )
+                        })
+                        .1
+                        .push((continuation, text));
+                }
+                LexItem::Title(continuation, text) => {
+                    title_lines.push((continuation, text));
+                    title_context.get_or_insert_with(|| PDBContext::full_line(linenumber, &line));
+                }
+                LexItem::Compound(continuation, text) => {
+                    compound_lines.push((continuation, text));
+                    compound_context
+                        .get_or_insert_with(|| PDBContext::full_line(linenumber, &line));
+                }
+                LexItem::Source(continuation, text) => {
+                    source_lines.push((continuation, text));
+                    source_context.get_or_insert_with(|| PDBContext::full_line(linenumber, &line));
+                }
+                LexItem::ExperimentalData(continuation, text) => {
+                    method_lines.push((continuation, text));
+                    method_context.get_or_insert_with(|| PDBContext::full_line(linenumber, &line));
+                }
+                LexItem::ModelType(continuation, text) => {
+                    model_type_lines.push((continuation, text));
+                    model_type_context
+                        .get_or_insert_with(|| PDBContext::full_line(linenumber, &line));
+                }
+                LexItem::ModelCount(count) => pdb.set_declared_model_count(Some(count)),
+                LexItem::Header(classification, date, id_code) => {
+                    if let Err(message) = pdb.set_classification(&classification) {
+                        errors.push(PDBError::new(
+                            ErrorLevel::LooseWarning,
+                            "Invalid classification",
+                            &format!("{}, so it is ignored.", message),
+                            PDBContext::line(linenumber, &line, 10, 40),
+                        ));
+                    }
+                    if !date.is_empty() {
+                        match Date::from_pdb(&date) {
+                            Some(date) => pdb.set_deposition_date(Some(date)),
+                            None => errors.push(PDBError::new(
+                                ErrorLevel::LooseWarning,
+                                "Invalid deposition date",
+                                "The deposition date of the HEADER record is not a valid date in the format DD-MMM-YY (like 26-OCT-07), so it is ignored.",
+                                PDBContext::line(linenumber, &line, 50, 9),
+                            )),
+                        }
+                    }
+                    // Longer names are not PDB id codes, see `lex_header`
+                    if id_code.len() == 4 {
+                        let _ = pdb.set_identifier(Some(&id_code));
+                    }
+                    if let Err(message) = pdb.set_name(&id_code) {
+                        errors.push(PDBError::new(
+                            ErrorLevel::GeneralWarning,
+                            "Invalid id code",
+                            &format!("{}, so the file name is used as name instead.", message),
+                            PDBContext::line(linenumber, &line, 62, id_code.len()),
+                        ));
+                    }
+                }
+                LexItem::Helix(
+                    serial_number,
+                    id,
+                    residue_names,
+                    chain_ids,
+                    residue_serial_numbers,
+                    insertion_codes,
+                    class,
+                    comment,
+                    length,
+                ) => {
+                    let mut helix = match Helix::new(
+                        serial_number,
+                        &id,
+                        residue_names,
+                        chain_ids,
+                        residue_serial_numbers,
+                        insertion_codes,
+                    ) {
+                        Some(helix) => helix,
+                        None => {
+                            errors.push(PDBError::new(
+                                ErrorLevel::LooseWarning,
+                                "Invalid HELIX",
+                                "This HELIX record contains invalid characters, so it is ignored.",
+                                PDBContext::full_line(linenumber, &line),
+                            ));
+                            continue;
+                        }
+                    };
+                    if let Err(message) = helix.set_class(class) {
+                        errors.push(PDBError::new(
+                            ErrorLevel::LooseWarning,
+                            "Invalid helix class",
+                            &format!(
+                                "{}, the helix is read as a right-handed alpha helix.",
+                                message
+                            ),
+                            PDBContext::line(linenumber, &line, 38, 2),
+                        ));
+                    }
+                    if let Err(message) = helix.set_comment(&comment) {
+                        errors.push(PDBError::new(
+                            ErrorLevel::LooseWarning,
+                            "Invalid HELIX",
+                            &format!("{}, so the record is ignored.", message),
+                            PDBContext::full_line(linenumber, &line),
+                        ));
+                        continue;
+                    }
+                    if let Some(length) = length {
+                        helix.set_length(length);
+                    }
+                    pdb.add_helix(helix);
+                }
+                LexItem::Conect(serial_number, bonded) => {
+                    conect_records.push((
+                        PDBContext::full_line(linenumber, &line),
+                        serial_number,
+                        bonded,
+                    ));
+                }
+                LexItem::Seqres(_, chain_id, length, residues) => {
+                    if !seqres_lengths.iter().any(|(id, _)| *id == chain_id) {
+                        seqres_lengths.push((chain_id, length));
+                    }
+                    pdb.add_seqres(chain_id, residues);
                 }
                 LexItem::Scale(n, row) => {
+                    let context = PDBContext::full_line(linenumber, &line);
+                    if let Some(warning) =
+                        duplicate_matrix_row(&mut matrix_rows, ("SCALE", 0), n, context)
+                    {
+                        errors.push(warning);
+                        continue;
+                    }
                     if !pdb.has_scale() {
                         pdb.set_scale(Scale::new());
                     }
                     pdb.scale_mut().set_row(n, row);
                 }
                 LexItem::OrigX(n, row) => {
+                    let context = PDBContext::full_line(linenumber, &line);
+                    if let Some(warning) =
+                        duplicate_matrix_row(&mut matrix_rows, ("ORIGX", 0), n, context)
+                    {
+                        errors.push(warning);
+                        continue;
+                    }
                     if !pdb.has_origx() {
                         pdb.set_origx(OrigX::new());
                     }
                     pdb.origx_mut().set_row(n, row);
                 }
                 LexItem::MtriX(n, ser, row, given) => {
+                    let context = PDBContext::full_line(linenumber, &line);
+                    if let Some(warning) =
+                        duplicate_matrix_row(&mut matrix_rows, ("MTRIX", ser), n, context)
+                    {
+                        errors.push(warning);
+                        continue;
+                    }
                     let mut found = false;
                     for mtrix in pdb.mtrix_mut() {
                         if mtrix.serial_number == ser {
@@ -164,8 +938,10 @@ pub fn parse(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
                         pdb.add_mtrix(mtrix);
                     }
                 }
-                LexItem::Crystal(a, b, c, alpha, beta, gamma, spacegroup, _z) => {
-                    pdb.set_unit_cell(UnitCell::new(a, b, c, alpha, beta, gamma));
+                LexItem::Crystal(a, b, c, alpha, beta, gamma, spacegroup, z) => {
+                    let mut unit_cell = UnitCell::new(a, b, c, alpha, beta, gamma);
+                    unit_cell.set_z(z);
+                    pdb.set_unit_cell(unit_cell);
                     pdb.set_symmetry(
                         Symmetry::new(&spacegroup)
                             .unwrap_or_else(|| panic!("Invalid space group: \"{}\"", spacegroup)),
@@ -174,17 +950,18 @@ pub fn parse(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
                 LexItem::Master(
                     num_remark,
                     num_empty,
-                    _num_het,
-                    _num_helix,
+                    num_het,
+                    num_helix,
                     _num_sheet,
                     _num_turn,
-                    _num_site,
+                    num_site,
                     num_xform,
                     num_coord,
                     _num_ter,
-                    _num_connect,
-                    _num_seq,
+                    num_connect,
+                    num_seq,
                 ) => {
+                    saw_master = true;
                     // This has to be one of the last lines so push the current model
                     if current_model.total_atom_count() > 0 {
                         pdb.add_model(current_model);
@@ -197,7 +974,7 @@ pub fn parse(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
                                 ErrorLevel::StrictWarning,
                                 "MASTER checksum failed",
                                 &format!("The number of REMARKS ({}) is different then posed in the MASTER Record ({})", pdb.remark_count(), num_remark),
-                                Context::show(filename)
+                                PDBContext::show(filename)
                             )
                         );
                     }
@@ -207,7 +984,38 @@ pub fn parse(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
                                 ErrorLevel::LooseWarning,
                                 "MASTER checksum failed",
                                 &format!("The empty checksum number is not empty (value: {}) while it is defined to be empty.", num_empty),
-                                Context::show(filename)
+                                PDBContext::show(filename)
+                            )
+                        );
+                    }
+                    if num_het != pdb.het_count() {
+                        errors.push(
+                            PDBError::new(
+                                ErrorLevel::StrictWarning,
+                                "MASTER checksum failed",
+                                &format!("The number of HET records ({}) is different then posed in the MASTER Record ({})", pdb.het_count(), num_het),
+                                PDBContext::show(filename)
+                            )
+                        );
+                    }
+                    if num_helix != pdb.helix_count() {
+                        errors.push(
+                            PDBError::new(
+                                ErrorLevel::StrictWarning,
+                                "MASTER checksum failed",
+                                &format!("The number of HELIX records ({}) is different then posed in the MASTER Record ({})", pdb.helix_count(), num_helix),
+                                PDBContext::show(filename)
+                            )
+                        );
+                    }
+                    let site_count = record_counts.get(b"SITE  ").copied().unwrap_or(0);
+                    if num_site != site_count {
+                        errors.push(
+                            PDBError::new(
+                                ErrorLevel::StrictWarning,
+                                "MASTER checksum failed",
+                                &format!("The number of SITE records ({}) is different then posed in the MASTER Record ({})", site_count, num_site),
+                                PDBContext::show(filename)
                             )
                         );
                     }
@@ -229,131 +1037,677 @@ pub fn parse(filename: &str) -> Result<(PDB, Vec<PDBError>), PDBError> {
                                 ErrorLevel::StrictWarning,
                                 "MASTER checksum failed",
                                 &format!("The number of coordinate transformation records ({}) is different then posed in the MASTER Record ({})", xform, num_xform),
-                                Context::show(filename)
+                                PDBContext::show(filename)
+                            )
+                        );
+                    }
+                    if num_coord != pdb.total_atom_count() + discarded_atoms {
+                        errors.push(
+                            PDBError::new(
+                                ErrorLevel::StrictWarning,
+                                "MASTER checksum failed",
+                                &format!("The number of Atoms (Normal + Hetero) ({}) is different then posed in the MASTER Record ({})", pdb.total_atom_count() + discarded_atoms, num_coord),
+                                PDBContext::show(filename)
+                            )
+                        );
+                    }
+                    let conect_count = record_counts.get(b"CONECT").copied().unwrap_or(0);
+                    if num_connect != conect_count {
+                        errors.push(
+                            PDBError::new(
+                                ErrorLevel::StrictWarning,
+                                "MASTER checksum failed",
+                                &format!("The number of CONECT records ({}) is different then posed in the MASTER Record ({})", conect_count, num_connect),
+                                PDBContext::show(filename)
                             )
                         );
                     }
-                    if num_coord != pdb.total_atom_count() {
+                    if num_seq != pdb.seqres_line_count() {
                         errors.push(
                             PDBError::new(
                                 ErrorLevel::StrictWarning,
                                 "MASTER checksum failed",
-                                &format!("The number of Atoms (Normal + Hetero) ({}) is different then posed in the MASTER Record ({})", pdb.total_atom_count(), num_coord),
-                                Context::show(filename)
+                                &format!("The number of SEQRES records ({}) is different then posed in the MASTER Record ({})", pdb.seqres_line_count(), num_seq),
+                                PDBContext::show(filename)
                             )
                         );
                     }
                 }
+                LexItem::End() => saw_end = true,
                 _ => (),
             }
         } else {
             errors.push(lineresult.unwrap_err())
         }
     }
-    if current_model.total_atom_count() > 0 {
-        pdb.add_model(current_model);
+    if let Some(gaps) = serial_gaps {
+        errors.push(gaps.into_error());
     }
-    errors.extend(validate(&pdb));
-
-    Ok((pdb, errors))
-}
-
-/// Lex a REMARK
-/// ## Fails
-/// It fails on incorrect numbers for the remark-type-number
-fn lex_remark(linenumber: usize, line: String) -> Result<LexItem, PDBError> {
-    let number = parse_number(
-        Context::line(linenumber, &line, 7, 3),
-        &line.chars().collect::<Vec<char>>()[7..10],
-    )?;
-    if !reference_tables::valid_remark_type_number(number) {
-        return Err(PDBError::new(
-            ErrorLevel::StrictWarning,
-            "Remark type number invalid",
-            "The remark-type-number is not valid, see wwPDB v3.30 for all valid numbers.",
-            Context::line(linenumber, &line, 7, 3),
-        ));
+    for ((record, serial), (seen, context)) in matrix_rows {
+        let missing = (0..3)
+            .filter(|row| !seen[*row])
+            .map(|row| format!("{}{}", record, row + 1))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            let name = if record == "MTRIX" {
+                format!("{}n Serial number {}", record, serial)
+            } else {
+                format!("{}n", record)
+            };
+            errors.push(PDBError::new(
+                ErrorLevel::StrictWarning,
+                "Incomplete matrix",
+                &format!(
+                    "The {} records are missing for {}, so the matrix is not valid.",
+                    missing.join(", "),
+                    name
+                ),
+                context,
+            ));
+        }
     }
-    Ok(LexItem::Remark(
-        number,
-        if line.len() > 11 {
-            if line.len() - 11 > 70 {
-                return Err(PDBError::new(
+    for (record, lines) in vec![("OBSLTE", obsolete_lines), ("SPRSDE", supersedes_lines)]
+        .into_iter()
+        .filter_map(|(record, lines)| Some((record, lines?)))
+    {
+        let (date, id_code, mut entries, context) = lines;
+        let date = if date.is_empty() {
+            None
+        } else {
+            let parsed = Date::from_pdb(&date);
+            if parsed.is_none() {
+                errors.push(PDBError::new(
                     ErrorLevel::LooseWarning,
-                    "Remark too long",
-                    "The REMARK is too long, the max is 70 characters.",
-                    Context::line(linenumber, &line, 11, line.len() - 11),
+                    "Invalid date",
+                    &format!(
+                        "The date of the {} records is not a valid date in the format DD-MMM-YY (like 26-OCT-07), so it is ignored.",
+                        record
+                    ),
+                    context.clone(),
                 ));
             }
-            line[11..].to_string()
+            parsed
+        };
+        let mut replacement = match EntryReplacement::new(&id_code, date) {
+            Some(replacement) => replacement,
+            None => {
+                errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Invalid id code",
+                    &format!(
+                        "The id code \"{}\" of the {} records is not a valid PDB id code, so its records are ignored.",
+                        id_code, record
+                    ),
+                    context,
+                ));
+                continue;
+            }
+        };
+        entries.sort_by_key(|(continuation, _)| *continuation);
+        for entry in entries.into_iter().flat_map(|(_, entries)| entries) {
+            if let Err(message) = replacement.add_entry(&entry) {
+                errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Invalid id code",
+                    &format!("{}, so it is ignored.", message),
+                    context.clone(),
+                ));
+            }
+        }
+        if record == "OBSLTE" {
+            pdb.set_obsolete(Some(replacement));
         } else {
-            "".to_string()
-        },
-    ))
-}
-
-/// Lex a MODEL
-/// ## Fails
-/// It fails on incorrect numbers for the serial number
-fn lex_model(linenumber: usize, line: String) -> Result<LexItem, PDBError> {
-    Ok(LexItem::Model(parse_number(
-        Context::line(linenumber, &line, 6, line.len() - 6),
-        &line[6..]
-            .split_whitespace()
-            .collect::<String>()
-            .chars()
-            .collect::<Vec<char>>()[..],
-    )?))
-}
-
-/// Lex an ATOM
-/// ## Fails
-/// It fails on incorrect numbers in the line
-fn lex_atom(linenumber: usize, line: String, hetero: bool) -> Result<LexItem, PDBError> {
-    let chars: Vec<char> = line.chars().collect();
-    if chars.len() < 54 {
-        return Err(PDBError::new(
-            ErrorLevel::BreakingError,
-            "Atom line too short",
-            "This line is too short to contain all necessary elements (up to `z` at least).",
-            Context::full_line(linenumber, &line),
-        ));
+            pdb.set_supersedes(Some(replacement));
+        }
     }
-    let serial_number = parse_number(Context::line(linenumber, &line, 7, 4), &chars[7..11])?;
-    let atom_name = [chars[12], chars[13], chars[14], chars[15]];
-    let alternate_location = chars[16];
-    let residue_name = [chars[17], chars[18], chars[19]];
-    let chain_id = chars[21];
-    let residue_serial_number =
-        parse_number(Context::line(linenumber, &line, 22, 4), &chars[22..26])?;
-    let insertion = chars[26];
-    let x = parse_number(Context::line(linenumber, &line, 30, 8), &chars[30..38])?;
-    let y = parse_number(Context::line(linenumber, &line, 38, 8), &chars[38..46])?;
-    let z = parse_number(Context::line(linenumber, &line, 46, 8), &chars[46..54])?;
-    let mut occupancy = 1.0;
-    if chars.len() >= 60 {
-        occupancy = parse_number(Context::line(linenumber, &line, 54, 6), &chars[54..60])?;
+    split_lines.sort_by_key(|(continuation, _, _)| *continuation);
+    for (_, entries, context) in split_lines {
+        for entry in entries {
+            if let Err(message) = pdb.add_split_entry(&entry) {
+                errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Invalid id code",
+                    &format!("{}, so it is ignored.", message),
+                    context.clone(),
+                ));
+            }
+        }
     }
-    let mut b_factor = 0.0;
-    if chars.len() >= 66 {
-        b_factor = parse_number(Context::line(linenumber, &line, 60, 6), &chars[60..66])?;
+    if let Some((id_code, lines, context)) = caveat_lines {
+        let text = join_continued(lines);
+        errors.push(PDBError::new(
+            ErrorLevel::LooseWarning,
+            "Caveat",
+            &format!("The entry has a CAVEAT record: {}", text),
+            context.clone(),
+        ));
+        match Caveat::new(&id_code, &text) {
+            Some(caveat) => pdb.set_caveat(Some(caveat)),
+            None => errors.push(PDBError::new(
+                ErrorLevel::LooseWarning,
+                "Invalid caveat",
+                "The id code or text of the CAVEAT records is not valid, so it is ignored.",
+                context,
+            )),
+        }
     }
-    let mut segment_id = [' ', ' ', ' ', ' '];
-    if chars.len() >= 75 {
-        segment_id = [chars[72], chars[73], chars[74], chars[75]];
+    if let Some(context) = title_context {
+        if let Err(message) = pdb.set_title(&join_continued(title_lines)) {
+            errors.push(PDBError::new(
+                ErrorLevel::LooseWarning,
+                "Invalid title",
+                &format!("{}, so it is ignored.", message),
+                context,
+            ));
+        }
     }
-    let mut element = [' ', ' '];
-    if chars.len() >= 77 {
-        element = [chars[76], chars[77]];
+    if let Some(context) = method_context {
+        let mut methods = Vec::new();
+        for name in join_continued(method_lines)
+            .split(';')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            match ExperimentalMethod::from_pdb(name) {
+                Some(method) => methods.push(method),
+                None => errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Invalid experimental method",
+                    &format!(
+                        "The experimental method \"{}\" of the EXPDTA record is not known, so it is ignored.",
+                        name
+                    ),
+                    context.clone(),
+                )),
+            }
+        }
+        pdb.set_experimental_methods(methods);
+    }
+    if let Some(context) = model_type_context {
+        for annotation in join_continued(model_type_lines)
+            .split(';')
+            .map(str::trim)
+            .filter(|annotation| !annotation.is_empty())
+        {
+            if let Err(message) = pdb.add_model_type(annotation) {
+                errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Invalid model type",
+                    &format!("{}, so it is ignored.", message),
+                    context.clone(),
+                ));
+            }
+        }
+    }
+    if let Some(context) = heterogen_context {
+        for (id, [names, synonyms, formulas]) in heterogen_lines {
+            let mut heterogen = match Heterogen::new(&id) {
+                Some(heterogen) => heterogen,
+                None => {
+                    errors.push(PDBError::new(
+                        ErrorLevel::LooseWarning,
+                        "Invalid heterogen",
+                        &format!("The hetero group id \"{}\" of the HETNAM, HETSYN or FORMUL records is not valid, so its records are ignored.", id),
+                        context.clone(),
+                    ));
+                    continue;
+                }
+            };
+            heterogen.set_component_number(component_numbers.get(&id).copied());
+            let mut results = vec![
+                heterogen.set_name(&join_continued(names)),
+                heterogen.set_formula(&join_continued(formulas)),
+            ];
+            for synonym in join_continued(synonyms)
+                .split(';')
+                .map(str::trim)
+                .filter(|synonym| !synonym.is_empty())
+            {
+                results.push(heterogen.add_synonym(synonym));
+            }
+            for message in results.into_iter().filter_map(Result::err) {
+                errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Invalid heterogen",
+                    &format!("{}, so it is ignored.", message),
+                    context.clone(),
+                ));
+            }
+            pdb.add_heterogen(heterogen);
+        }
+    }
+    for (id, residue_count, mut lines, context) in site_lines {
+        let mut site = match Site::new(&id) {
+            Some(site) => site,
+            None => {
+                errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Invalid site",
+                    &format!(
+                        "The site name \"{}\" of the SITE records is not valid, so its records are ignored.",
+                        id
+                    ),
+                    context,
+                ));
+                continue;
+            }
+        };
+        lines.sort_by_key(|(sequence_number, _)| *sequence_number);
+        for (name, chain_id, serial_number, insertion_code) in
+            lines.into_iter().flat_map(|(_, residues)| residues)
+        {
+            if let Err(message) = site.add_residue(&name, chain_id, serial_number, insertion_code) {
+                errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Invalid site",
+                    &format!("{}, so it is ignored.", message),
+                    context.clone(),
+                ));
+            }
+        }
+        if site.residue_count() != residue_count {
+            errors.push(PDBError::new(
+                ErrorLevel::StrictWarning,
+                "Site residue count",
+                &format!(
+                    "The SITE records of site {} list {} Residues while the number of Residues is given as {}.",
+                    site.id(),
+                    site.residue_count(),
+                    residue_count
+                ),
+                context,
+            ));
+        }
+        pdb.add_site(site);
+    }
+    for (record, lines, context) in vec![
+        ("COMPND", compound_lines, compound_context),
+        ("SOURCE", source_lines, source_context),
+    ]
+    .into_iter()
+    .filter_map(|(record, lines, context)| Some((record, lines, context?)))
+    {
+        let (molecules, invalid) = parse_specification_list(&join_continued(lines));
+        for specification in invalid {
+            errors.push(PDBError::new(
+                ErrorLevel::LooseWarning,
+                "Invalid specification",
+                &format!(
+                    "The specification \"{}\" of the {} records is not in the format `TOKEN: value`, so it is ignored.",
+                    specification, record
+                ),
+                context.clone(),
+            ));
+        }
+        for molecule in molecules {
+            if record == "COMPND" {
+                pdb.add_compound(molecule);
+            } else {
+                pdb.add_source(molecule);
+            }
+        }
+    }
+    if pdb.name().is_empty() {
+        pdb.set_name(&block_name_from_path(filename))
+            .expect("Names from paths are always valid");
+    }
+    pdb.set_records_seen(saw_end, saw_ter, saw_master);
+    pdb.set_source_record_counts(
+        record_counts
+            .into_iter()
+            .map(|(tag, count)| (String::from_utf8_lossy(&tag).trim_end().to_string(), count))
+            .collect(),
+    );
+    let warn_missing_records = options.warn_missing_records()
+        || options
+            .validation_profile()
+            .is_some_and(|profile| profile.runs(ValidationCheck::MissingRecords));
+    if warn_missing_records && !options.header_only() {
+        let missing = |record: &str, description: &str| {
+            PDBError::new(
+                ErrorLevel::LooseWarning,
+                &format!("Missing {} record", record),
+                description,
+                PDBContext::show(filename),
+            )
+        };
+        if !saw_end {
+            errors.push(missing(
+                "END",
+                "The file does not contain an END record, it is possibly truncated.",
+            ));
+        }
+        if !saw_ter && current_model.total_atom_count() + pdb.total_atom_count() > 0 {
+            errors.push(missing(
+                "TER",
+                "The file does not contain any TER record, so the ends of the Chains are not marked.",
+            ));
+        }
+        if !saw_master {
+            errors.push(missing(
+                "MASTER",
+                "The file does not contain a MASTER record, so its record counts cannot be checked.",
+            ));
+        }
+    }
+    if current_model.total_atom_count() > 0 {
+        pdb.add_model(current_model);
+    }
+    if !options.header_only() {
+        if let Some(model) = pdb.models().next() {
+            for (ssbond, context) in pdb.ssbonds().zip(ssbond_contexts) {
+                for index in 0..2 {
+                    let (chain_id, residue_serial_number) = (
+                        ssbond.chain_ids()[index],
+                        ssbond.residue_serial_numbers()[index],
+                    );
+                    let found = model.all_chains().any(|chain| {
+                        chain.id() == chain_id
+                            && chain
+                                .residues()
+                                .any(|r| r.serial_number() == residue_serial_number)
+                    });
+                    if !found {
+                        errors.push(PDBError::new(
+                            ErrorLevel::StrictWarning,
+                            "SSBOND residue not found",
+                            &format!(
+                                "The SSBOND record refers to Residue {}{}, which is not present in the coordinates.",
+                                chain_id, residue_serial_number
+                            ),
+                            context.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    if !conect_records.is_empty() {
+        let serial_numbers = pdb
+            .models()
+            .flat_map(|model| model.all_atoms())
+            .map(|atom| atom.serial_number())
+            .collect::<HashSet<usize>>();
+        for (context, serial_number, bonded) in conect_records {
+            for serial in std::iter::once(serial_number).chain(bonded.iter().copied()) {
+                if !serial_numbers.contains(&serial) && !discarded_serials.contains(&serial) {
+                    errors.push(PDBError::new(
+                        ErrorLevel::StrictWarning,
+                        "CONECT atom not found",
+                        &format!(
+                            "The CONECT record refers to Atom {}, which is not present in the file, so its bond is ignored.",
+                            serial
+                        ),
+                        context.clone(),
+                    ));
+                }
+            }
+            for other in bonded {
+                if serial_numbers.contains(&serial_number)
+                    && serial_numbers.contains(&other)
+                    && pdb.add_connection(serial_number, other).is_err()
+                {
+                    errors.push(PDBError::new(
+                        ErrorLevel::StrictWarning,
+                        "Invalid CONECT record",
+                        &format!("The CONECT record bonds Atom {} to itself.", other),
+                        context.clone(),
+                    ));
+                }
+            }
+        }
+    }
+    for (chain_id, length) in seqres_lengths {
+        let found = pdb
+            .chain_seqres(chain_id)
+            .map_or(0, |residues| residues.len());
+        if found != length {
+            errors.push(PDBError::new(
+                ErrorLevel::LooseWarning,
+                "SEQRES length incorrect",
+                &format!(
+                    "The number of residues in the SEQRES records of chain {} ({}) is different then posed in its first SEQRES record ({})",
+                    chain_id, found, length
+                ),
+                PDBContext::show(filename),
+            ));
+        }
+    }
+    // Validating a big file takes a while, so check once more before starting
+    if should_cancel(options.cancellation_token(), 0) {
+        return Err(cancelled_error(
+            "Parsing",
+            read_lines,
+            PDBContext::show(filename),
+        ));
+    }
+    let validation = match options.validation_profile() {
+        Some(profile) => {
+            errors = profile.apply(errors);
+            validate_with(&pdb, profile)
+        }
+        None => validate_pdb(&pdb),
+    };
+    if options.keep_source_lines() {
+        errors.extend(add_source_text(filename, validation));
+    } else {
+        errors.extend(validation);
+    }
+
+    Ok((pdb, errors))
+}
+
+/// Replace the contexts of the given errors that only give a line number (see `PDBContext::line_number`)
+/// with the full line read from the given file. The file is only read if any error needs it.
+fn add_source_text(filename: &str, errors: Vec<PDBError>) -> Vec<PDBError> {
+    let mut needed = errors
+        .iter()
+        .filter(|e| matches!(e.context(), PDBContext::LineNumber { .. }))
+        .filter_map(|e| e.context().linenumber())
+        .collect::<Vec<usize>>();
+    if needed.is_empty() {
+        return errors;
+    }
+    needed.sort_unstable();
+    needed.dedup();
+    let lines = match File::open(filename) {
+        Ok(file) => BufReader::new(file)
+            .lines()
+            .enumerate()
+            .filter(|(index, _)| needed.binary_search(&(index + 1)).is_ok())
+            .filter_map(|(index, line)| Some((index + 1, line.ok()?)))
+            .collect::<Vec<(usize, String)>>(),
+        Err(_) => return errors,
+    };
+    errors
+        .into_iter()
+        .map(|error| match error.context() {
+            PDBContext::LineNumber { linenumber } => {
+                match lines.iter().find(|(number, _)| number == linenumber) {
+                    Some((number, line)) => PDBError::new(
+                        error.level(),
+                        error.short_description(),
+                        error.long_description(),
+                        PDBContext::full_line(*number, line),
+                    ),
+                    None => error,
+                }
+            }
+            _ => error,
+        })
+        .collect()
+}
+
+/// Lex a REMARK
+/// ## Fails
+/// It fails on incorrect numbers for the remark-type-number
+fn lex_remark(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    let number = parse_number(
+        PDBContext::line(linenumber, line, 7, 3),
+        &line.chars().collect::<Vec<char>>()[7..10],
+    )?;
+    if !reference_tables::valid_remark_type_number(number) {
+        return Err(PDBError::new(
+            ErrorLevel::StrictWarning,
+            "Remark type number invalid",
+            "The remark-type-number is not valid, see wwPDB v3.30 for all valid numbers.",
+            PDBContext::line(linenumber, line, 7, 3),
+        ));
+    }
+    Ok(LexItem::Remark(
+        number,
+        if line.len() > 11 {
+            if line.len() - 11 > 70 {
+                return Err(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Remark too long",
+                    "The REMARK is too long, the max is 70 characters.",
+                    PDBContext::line(linenumber, line, 11, line.len() - 11),
+                ));
+            }
+            line[11..].to_string()
+        } else {
+            "".to_string()
+        },
+    ))
+}
+
+/// Lex a MODEL
+/// ## Fails
+/// It fails on incorrect numbers for the serial number
+fn lex_model(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    Ok(LexItem::Model(parse_number(
+        PDBContext::line(linenumber, line, 6, line.len() - 6),
+        &line[6..]
+            .split_whitespace()
+            .collect::<String>()
+            .chars()
+            .collect::<Vec<char>>()[..],
+    )?))
+}
+
+/// Get the record type of the given line, the first six characters padded with spaces
+fn record_type(line: &str) -> [u8; 6] {
+    let mut tag = [b' '; 6];
+    for (target, byte) in tag.iter_mut().zip(line.bytes()) {
+        *target = byte;
+    }
+    tag
+}
+
+/// Check if the given line is an ATOM or HETATM record of a hydrogen (or deuterium) atom, based on
+/// the element column or, if that is empty, the atom name, see `ReadOptions::set_discard_hydrogens`
+fn is_hydrogen_record(line: &str) -> bool {
+    let atom = line.starts_with("ATOM  ");
+    if !atom && !line.starts_with("HETATM") {
+        return false;
+    }
+    let is_hydrogen = |c: char| c == 'H' || c == 'D';
+    let element = line_chars(line, 76, 78)
+        .into_iter()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    if !element.is_empty() {
+        return element.len() == 1 && is_hydrogen(element[0].to_ascii_uppercase());
+    }
+    match line_chars(line, 12, 14)[..] {
+        [first, second] if first == ' ' || first.is_ascii_digit() => is_hydrogen(second),
+        [first, _] => atom && is_hydrogen(first),
+        _ => false,
+    }
+}
+
+/// Get the characters of the given line in the given range of columns, as far as the line reaches
+fn line_chars(line: &str, start: usize, end: usize) -> Vec<char> {
+    line.chars().skip(start).take(end - start).collect()
+}
+
+/// Get the character in the given column of the line, a space if the line does not reach it
+fn line_char(line: &str, column: usize) -> char {
+    line.chars().nth(column).unwrap_or(' ')
+}
+
+/// Get the text of the given line in the given range of columns, as far as the line reaches, trimmed
+fn line_field(line: &str, start: usize, end: usize) -> String {
+    line_chars(line, start, end)
+        .iter()
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Lex an ATOM
+/// ## Fails
+/// It fails on incorrect numbers in the line
+pub(super) fn lex_atom(linenumber: usize, line: &str, hetero: bool) -> Result<LexItem, PDBError> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() < 54 {
+        return Err(PDBError::new(
+            ErrorLevel::BreakingError,
+            "Atom line too short",
+            "This line is too short to contain all necessary elements (up to `z` at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let serial_number = parse_serial(PDBContext::line(linenumber, line, 6, 5), &chars[6..11])?;
+    let atom_name = [chars[12], chars[13], chars[14], chars[15]];
+    let alternate_location = chars[16];
+    let residue_name = [chars[17], chars[18], chars[19]];
+    let chain_id = chars[21];
+    let residue_serial_number =
+        parse_serial(PDBContext::line(linenumber, line, 22, 4), &chars[22..26])?;
+    let insertion = chars[26];
+    let x = parse_number(PDBContext::line(linenumber, line, 30, 8), &chars[30..38])?;
+    let y = parse_number(PDBContext::line(linenumber, line, 38, 8), &chars[38..46])?;
+    let z = parse_number(PDBContext::line(linenumber, line, 46, 8), &chars[46..54])?;
+    let mut occupancy = 1.0;
+    if chars.len() >= 60 {
+        occupancy = parse_number(PDBContext::line(linenumber, line, 54, 6), &chars[54..60])?;
+    }
+    let mut b_factor = 0.0;
+    if chars.len() >= 66 {
+        b_factor = parse_number(PDBContext::line(linenumber, line, 60, 6), &chars[60..66])?;
+    }
+    let segment_id = [
+        line_char(line, 72),
+        line_char(line, 73),
+        line_char(line, 74),
+        line_char(line, 75),
+    ];
+    let mut element = [' ', ' '];
+    if chars.len() >= 78 {
+        element = [chars[76], chars[77]];
+    }
+    for (field, offset, length, valid) in &[
+        ("atom name", 12, 4, check_char as fn(char) -> bool),
+        ("alternate location", 16, 1, check_char),
+        ("residue name", 17, 3, check_char),
+        ("chain id", 21, 1, check_chain_id),
+        ("insertion code", 26, 1, check_char),
+        ("segment id", 72, 4, check_char),
+        ("element", 76, 2, check_char),
+    ] {
+        let text = chars.iter().skip(*offset).take(*length).collect::<String>();
+        if let Some(invalid) = describe_invalid_chars(&text, *valid) {
+            return Err(PDBError::new(
+                ErrorLevel::BreakingError,
+                "Invalid characters in atom",
+                &format!(
+                    "The {} contains the invalid character(s) {}. Chain ids can only contain alphanumeric ASCII characters or a space, the other fields printable ASCII characters.",
+                    field, invalid
+                ),
+                PDBContext::line(linenumber, line, *offset, *length),
+            ));
+        }
     }
     let mut charge = 0;
-    if chars.len() >= 79 && !(chars[78] == ' ' && chars[79] == ' ') {
+    if chars.len() >= 80 && !(chars[78] == ' ' && chars[79] == ' ') {
         if !chars[78].is_ascii_digit() {
             return Err(PDBError::new(
                 ErrorLevel::BreakingError,
                 "Atom charge is not correct",
                 "The charge is not numeric, it is defined to be [0-9][+-], so two characters in total.",
-                Context::line(linenumber, &line, 78, 1),
+                PDBContext::line(linenumber, line, 78, 1),
             ));
         }
         if chars[79] != '-' && chars[79] != '+' {
@@ -361,7 +1715,7 @@ fn lex_atom(linenumber: usize, line: String, hetero: bool) -> Result<LexItem, PD
                 ErrorLevel::BreakingError,
                 "Atom charge is not correct",
                 "The charge is not properly signed, it is defined to be [0-9][+-], so two characters in total.",
-                Context::line(linenumber, &line, 79, 1),
+                PDBContext::line(linenumber, line, 79, 1),
             ));
         }
         charge = chars[78].to_digit(10).unwrap() as isize;
@@ -390,42 +1744,124 @@ fn lex_atom(linenumber: usize, line: String, hetero: bool) -> Result<LexItem, PD
     ))
 }
 
+/// Lex an ATOM or HETATM with its coordinates shifted out of the defined columns, see
+/// `ReadOptions::set_recover_misaligned_columns`. The numbers from column 28 on are found by
+/// splitting on whitespace and the line is rebuilt with these numbers in their defined columns and
+/// the rest of the line directly following them, after which it is lexed as usual. It returns the
+/// lexed Atom and a warning describing the misalignment.
+/// ## Fails
+/// It fails if the expected number of numbers is not found or the rebuilt line cannot be lexed.
+fn lex_misaligned_atom(linenumber: usize, line: &str, hetero: bool) -> Option<(LexItem, PDBError)> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() <= 27 {
+        return None;
+    }
+    // The numbers as (text, end column), anything starting near the segment id (column 73) is
+    // not taken as a number, even if the fields are shifted
+    let mut numbers = Vec::new();
+    // Start after the insertion code, as a number shifted to the left can start before column 31
+    let mut column = 27;
+    while column < chars.len() && numbers.len() < 5 {
+        if chars[column] == ' ' {
+            column += 1;
+            continue;
+        }
+        let start = column;
+        while column < chars.len() && chars[column] != ' ' {
+            column += 1;
+        }
+        let text = chars[start..column].iter().collect::<String>();
+        if start > 70 || text.parse::<f64>().is_err() {
+            break;
+        }
+        numbers.push((text, column));
+    }
+    // Occupancy and B-factor are expected if the line extends past the z coordinate
+    let expected = if chars.len() > 55 { 5 } else { 3 };
+    if numbers.len() != expected {
+        return None;
+    }
+    let mut rebuilt = chars[..27].iter().collect::<String>();
+    rebuilt.push_str("   ");
+    for (index, (text, _)) in numbers.iter().enumerate() {
+        let width = if index < 3 { 8 } else { 6 };
+        if text.len() > width {
+            return None;
+        }
+        rebuilt.push_str(&format!("{:>width$}", text, width = width));
+    }
+    let end = numbers.last().map_or(27, |number| number.1);
+    rebuilt.extend(&chars[end..]);
+    let shift = end as isize - (30 + 8 * 3 + 6 * (expected as isize - 3));
+    let item = lex_atom(linenumber, &rebuilt, hetero).ok()?;
+    let warning = PDBError::new(
+        ErrorLevel::StrictWarning,
+        "Misaligned atom columns",
+        &format!(
+            "The coordinates of this atom are not in their defined columns, so they were read by splitting on whitespace. The fields after them are shifted by {} column(s).",
+            shift
+        ),
+        PDBContext::full_line(linenumber, line),
+    );
+    Some((item, warning))
+}
+
+/// The fields of an ANISOU record that repeat the fields of the ATOM or HETATM record it belongs
+/// to, as the name of the field and the columns (offset and length) in the ANISOU line
+const ANISOU_FIELDS: [(&str, usize, usize); 7] = [
+    ("atom name", 12, 4),
+    ("alternate location", 16, 1),
+    ("residue name", 17, 3),
+    ("residue serial number", 22, 4),
+    ("chain id", 21, 1),
+    ("segment id", 72, 4),
+    ("element", 76, 2),
+];
+
 /// Lex an ANISOU
 /// ## Fails
-/// It fails on incorrect numbers in the line
-fn lex_anisou(linenumber: usize, line: String) -> Result<LexItem, PDBError> {
+/// It fails if the line is too short or on incorrect numbers in the line
+fn lex_anisou(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
     let chars: Vec<char> = line.chars().collect();
-    let serial_number = parse_number(Context::line(linenumber, &line, 7, 4), &chars[7..11])?;
+    if chars.len() < 70 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "ANISOU line too short",
+            "This line is too short to contain all necessary elements (up to U23 at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let serial_number = parse_serial(PDBContext::line(linenumber, line, 6, 5), &chars[6..11])?;
     let atom_name = [chars[12], chars[13], chars[14], chars[15]];
     let alternate_location = chars[16];
     let residue_name = [chars[17], chars[18], chars[19]];
     let chain_id = chars[21];
     let residue_serial_number =
-        parse_number(Context::line(linenumber, &line, 10, 10), &chars[22..26])?;
+        parse_serial(PDBContext::line(linenumber, line, 22, 4), &chars[22..26])?;
     let insertion = chars[26];
-    let ai: isize = parse_number(Context::line(linenumber, &line, 28, 7), &chars[28..35])?;
-    let bi: isize = parse_number(Context::line(linenumber, &line, 35, 7), &chars[35..42])?;
-    let ci: isize = parse_number(Context::line(linenumber, &line, 42, 7), &chars[42..49])?;
-    let di: isize = parse_number(Context::line(linenumber, &line, 49, 7), &chars[49..56])?;
-    let ei: isize = parse_number(Context::line(linenumber, &line, 56, 7), &chars[56..63])?;
-    let fi: isize = parse_number(Context::line(linenumber, &line, 63, 7), &chars[63..70])?;
-    let factors = [
-        [
-            (ai as f64) / 10000.0,
-            (bi as f64) / 10000.0,
-            (ci as f64) / 10000.0,
-        ],
-        [
-            (di as f64) / 10000.0,
-            (ei as f64) / 10000.0,
-            (fi as f64) / 10000.0,
-        ],
+    // U11, U22, U33, U12, U13, U23 in 7 columns each, in units of 10⁻⁴ Å²
+    let mut factors = [0.0; 6];
+    for (index, factor) in factors.iter_mut().enumerate() {
+        let start = 28 + 7 * index;
+        let value: isize = parse_number(
+            PDBContext::line(linenumber, line, start, 7),
+            &line_chars(line, start, start + 7),
+        )?;
+        *factor = value as f64 / 10000.0;
+    }
+    let segment_id = [
+        line_char(line, 72),
+        line_char(line, 73),
+        line_char(line, 74),
+        line_char(line, 75),
     ];
-    let segment_id = [chars[72], chars[73], chars[74], chars[75]];
-    let element = [chars[76], chars[77]];
+    let mut element = [' ', ' '];
+    if chars.len() >= 78 {
+        element = [chars[76], chars[77]];
+    }
     let mut charge = [' ', ' '];
-    if chars.len() == 80 {
-        charge = [chars[79], chars[80]];
+    if chars.len() >= 80 {
+        charge = [chars[78], chars[79]];
     }
 
     Ok(LexItem::Anisou(
@@ -443,26 +1879,594 @@ fn lex_anisou(linenumber: usize, line: String) -> Result<LexItem, PDBError> {
     ))
 }
 
+/// Lex a HET
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_het(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() < 25 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "HET line too short",
+            "This line is too short to contain all necessary elements (up to the number of HETATM records at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let id = [chars[7], chars[8], chars[9]];
+    let chain_id = chars[12];
+    let residue_serial_number =
+        parse_number(PDBContext::line(linenumber, line, 13, 4), &chars[13..17])?;
+    let insertion_code = chars[17];
+    let atoms = parse_number(PDBContext::line(linenumber, line, 20, 5), &chars[20..25])?;
+    let text = if chars.len() > 30 {
+        chars[30..].iter().collect::<String>()
+    } else {
+        String::new()
+    };
+
+    Ok(LexItem::Het(
+        id,
+        chain_id,
+        residue_serial_number,
+        insertion_code,
+        atoms,
+        text,
+    ))
+}
+
+/// Lex a HETNAM or HETSYN, returning the continuation number, het id, and text. The text of
+/// continuation lines starts with a space, as for TITLE.
+/// ## Fails
+/// It fails on an incorrect continuation number
+fn lex_heterogen_text(linenumber: usize, line: &str) -> Result<(usize, String, String), PDBError> {
+    let continuation = parse_optional_number(linenumber, line, 7, 10)?.unwrap_or(1);
+    let id = line_field(line, 11, 14);
+    let text = line_chars(line, 15, 80).iter().collect::<String>();
+    let text = text.trim_end();
+    let text = if continuation > 1 {
+        text.strip_prefix(' ').unwrap_or(text)
+    } else {
+        text
+    };
+    Ok((continuation, id, text.to_string()))
+}
+
+/// Lex a FORMUL
+/// ## Fails
+/// It fails on an incorrect component or continuation number
+fn lex_formul(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    let component_number = parse_optional_number(linenumber, line, 8, 10)?;
+    let id = line_field(line, 12, 15);
+    let continuation = parse_optional_number(linenumber, line, 16, 18)?.unwrap_or(1);
+    // Column 19 holds an asterisk for water, the formula follows it
+    let text = line_field(line, 19, 80);
+    Ok(LexItem::Formula(component_number, id, continuation, text))
+}
+
+/// Lex a SEQRES
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_seqres(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() < 17 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "SEQRES line too short",
+            "This line is too short to contain all necessary elements (up to the number of residues at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let serial_number = parse_number(PDBContext::line(linenumber, line, 7, 3), &chars[7..10])?;
+    let chain_id = chars[11];
+    let length = parse_number(PDBContext::line(linenumber, line, 13, 4), &chars[13..17])?;
+    let residues = chars[17..]
+        .iter()
+        .collect::<String>()
+        .split_whitespace()
+        .map(|name| name.to_string())
+        .collect();
+
+    Ok(LexItem::Seqres(serial_number, chain_id, length, residues))
+}
+
+/// Lex a TER, the serial number is optional
+/// ## Fails
+/// It fails on an incorrect serial number
+fn lex_ter(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    let chars: Vec<char> = line.chars().take(11).collect();
+    if chars.len() < 7 || chars[6..].iter().all(|c| c.is_whitespace()) {
+        return Ok(LexItem::Ter(None));
+    }
+    Ok(LexItem::Ter(Some(parse_serial(
+        PDBContext::line(linenumber, line, 6, chars.len() - 6),
+        &chars[6..],
+    )?)))
+}
+
+/// Lex an SSBOND, the symmetry operators (default `1555`) and bond length are optional
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_ssbond(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() < 35 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "SSBOND line too short",
+            "This line is too short to contain all necessary elements (up to the residue serial number of the second cysteine at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let serial_number = parse_number(PDBContext::line(linenumber, line, 7, 3), &chars[7..10])?;
+    let first = parse_serial(PDBContext::line(linenumber, line, 17, 4), &chars[17..21])?;
+    let second = parse_serial(PDBContext::line(linenumber, line, 31, 4), &chars[31..35])?;
+    let symmetry_operators = [
+        parse_optional_number(linenumber, line, 59, 65)?.unwrap_or(1555),
+        parse_optional_number(linenumber, line, 66, 72)?.unwrap_or(1555),
+    ];
+    Ok(LexItem::SSBond(
+        serial_number,
+        [chars[15], chars[29]],
+        [first, second],
+        [
+            chars.get(21).copied().unwrap_or(' '),
+            chars.get(35).copied().unwrap_or(' '),
+        ],
+        symmetry_operators,
+        parse_optional_number(linenumber, line, 73, 78)?,
+    ))
+}
+
+/// Lex a LINK, the symmetry operators and the length are optional as older files leave them out
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_link(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    if line.chars().count() < 56 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "LINK line too short",
+            "This line is too short to contain all necessary elements (up to the residue serial number of the second Atom at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let serial = |start: usize| {
+        parse_serial(
+            PDBContext::line(linenumber, line, start, 4),
+            &line_chars(line, start, start + 4),
+        )
+    };
+    Ok(LexItem::Link(
+        [line_field(line, 12, 16), line_field(line, 42, 46)],
+        [line_char(line, 16), line_char(line, 46)],
+        [line_field(line, 17, 20), line_field(line, 47, 50)],
+        [line_char(line, 21), line_char(line, 51)],
+        [serial(22)?, serial(52)?],
+        [line_char(line, 26), line_char(line, 56)],
+        [
+            parse_optional_number(linenumber, line, 59, 65)?.unwrap_or(1555),
+            parse_optional_number(linenumber, line, 66, 72)?.unwrap_or(1555),
+        ],
+        parse_optional_number(linenumber, line, 73, 78)?,
+    ))
+}
+
+/// Lex a CISPEP, the model number and the angle are optional
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_cispep(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    if line.chars().count() < 35 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "CISPEP line too short",
+            "This line is too short to contain all necessary elements (up to the residue serial number of the second Residue at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let serial = |start: usize| {
+        parse_serial(
+            PDBContext::line(linenumber, line, start, 4),
+            &line_chars(line, start, start + 4),
+        )
+    };
+    Ok(LexItem::CisPeptide(
+        parse_optional_number(linenumber, line, 7, 10)?.unwrap_or(0),
+        [line_field(line, 11, 14), line_field(line, 25, 28)],
+        [line_char(line, 15), line_char(line, 29)],
+        [serial(17)?, serial(31)?],
+        [line_char(line, 21), line_char(line, 35)],
+        parse_optional_number(linenumber, line, 43, 46)?.unwrap_or(0),
+        parse_optional_number(linenumber, line, 53, 59)?,
+    ))
+}
+
+/// Lex a SITE, with up to four Residues per line
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_site(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    if line.chars().count() < 17 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "SITE line too short",
+            "This line is too short to contain all necessary elements (up to the number of residues in the site at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let mut residues = Vec::new();
+    for start in [18, 29, 40, 51] {
+        let name = line_field(line, start, start + 3);
+        if name.is_empty() {
+            continue;
+        }
+        residues.push((
+            name,
+            line_char(line, start + 4),
+            parse_serial(
+                PDBContext::line(linenumber, line, start + 5, 4),
+                &line_chars(line, start + 5, start + 9),
+            )?,
+            line_char(line, start + 9),
+        ));
+    }
+    Ok(LexItem::Site(
+        parse_optional_number(linenumber, line, 7, 10)?.unwrap_or(0),
+        line_field(line, 11, 14),
+        parse_number(
+            PDBContext::line(linenumber, line, 15, 2),
+            &line_chars(line, 15, 17),
+        )?,
+        residues,
+    ))
+}
+
+/// Lex a DBREF, the database id code and insertion codes are optional
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_dbref(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    if line.chars().count() < 67 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "DBREF line too short",
+            "This line is too short to contain all necessary elements (up to the last residue serial number in the database at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    Ok(LexItem::DatabaseReference(
+        line_char(line, 12),
+        lex_pdb_range(linenumber, line)?,
+        line_field(line, 26, 32),
+        line_field(line, 33, 41),
+        line_field(line, 42, 54),
+        [
+            (
+                parse_number(
+                    PDBContext::line(linenumber, line, 55, 5),
+                    &line_chars(line, 55, 60),
+                )?,
+                line_char(line, 60),
+            ),
+            (
+                parse_number(
+                    PDBContext::line(linenumber, line, 62, 5),
+                    &line_chars(line, 62, 67),
+                )?,
+                line_char(line, 67),
+            ),
+        ],
+    ))
+}
+
+/// Lex a DBREF1, the first of the two lines used instead of DBREF for database entries with long
+/// accession codes or residue serial numbers, the database id code is optional
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_dbref1(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    if line.chars().count() < 32 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "DBREF1 line too short",
+            "This line is too short to contain all necessary elements (up to the database name at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    Ok(LexItem::DatabaseReference1(
+        line_char(line, 12),
+        lex_pdb_range(linenumber, line)?,
+        line_field(line, 26, 32),
+        line_field(line, 47, 67),
+    ))
+}
+
+/// Lex a DBREF2, the second of the two lines following a DBREF1
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_dbref2(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    if line.chars().count() < 67 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "DBREF2 line too short",
+            "This line is too short to contain all necessary elements (up to the last residue serial number in the database at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    Ok(LexItem::DatabaseReference2(
+        line_char(line, 12),
+        line_field(line, 18, 40),
+        [
+            parse_number(
+                PDBContext::line(linenumber, line, 45, 10),
+                &line_chars(line, 45, 55),
+            )?,
+            parse_number(
+                PDBContext::line(linenumber, line, 57, 10),
+                &line_chars(line, 57, 67),
+            )?,
+        ],
+    ))
+}
+
+/// Lex the first and last residue serial number and insertion code in the Chain of a DBREF or DBREF1
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_pdb_range(linenumber: usize, line: &str) -> Result<[(usize, char); 2], PDBError> {
+    Ok([
+        (
+            parse_serial(
+                PDBContext::line(linenumber, line, 14, 4),
+                &line_chars(line, 14, 18),
+            )?,
+            line_char(line, 18),
+        ),
+        (
+            parse_serial(
+                PDBContext::line(linenumber, line, 20, 4),
+                &line_chars(line, 20, 24),
+            )?,
+            line_char(line, 24),
+        ),
+    ])
+}
+
+/// Lex a SEQADV, the residue in the Chain, the residue in the database and the comment are optional
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_seqadv(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    if line.chars().count() < 38 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "SEQADV line too short",
+            "This line is too short to contain all necessary elements (up to the accession code at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let residue = if line_chars(line, 18, 22).iter().all(|c| c.is_whitespace()) {
+        None
+    } else {
+        Some((
+            parse_serial(
+                PDBContext::line(linenumber, line, 18, 4),
+                &line_chars(line, 18, 22),
+            )?,
+            line_char(line, 22),
+        ))
+    };
+    Ok(LexItem::SequenceDifference(
+        line_field(line, 12, 15),
+        line_char(line, 16),
+        residue,
+        line_field(line, 24, 28),
+        line_field(line, 29, 38),
+        line_field(line, 39, 42),
+        parse_optional_number(linenumber, line, 43, 48)?,
+        line_field(line, 49, 70),
+    ))
+}
+
+/// Lex a HEADER, with the classification (columns 11-50), deposition date (51-59) and id code.
+/// The id code is read up to the end of the line (instead of only columns 63-66) to allow longer
+/// names, see `PDB::name`. All fields are trimmed and empty if not given.
+fn lex_header(line: &str) -> LexItem {
+    LexItem::Header(
+        line_field(line, 10, 50),
+        line_field(line, 50, 59),
+        line_field(line, 62, 80),
+    )
+}
+
+/// Lex an OBSLTE or SPRSDE into the continuation number (columns 9-10, 1 if not given), the date
+/// (columns 12-20), the id code of this entry (columns 22-25) and the id codes of the replacing
+/// or replaced entries (up to nine from column 32 on). All fields are trimmed.
+/// ## Fails
+/// It fails on an invalid continuation number.
+fn lex_entry_replacement(
+    linenumber: usize,
+    line: &str,
+) -> Result<(usize, String, String, Vec<String>), PDBError> {
+    Ok((
+        parse_optional_number(linenumber, line, 8, 10)?.unwrap_or(1),
+        line_field(line, 11, 20),
+        line_field(line, 21, 25),
+        (31..76)
+            .step_by(5)
+            .map(|start| line_field(line, start, start + 4))
+            .filter(|id_code| !id_code.is_empty())
+            .collect(),
+    ))
+}
+
+/// Lex a SPLIT into the continuation number (columns 9-10, 1 if not given) and the id codes of
+/// the entries (up to fourteen from column 12 on)
+/// ## Fails
+/// It fails on an invalid continuation number.
+fn lex_split(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    Ok(LexItem::Split(
+        parse_optional_number(linenumber, line, 8, 10)?.unwrap_or(1),
+        (11..80)
+            .step_by(5)
+            .map(|start| line_field(line, start, start + 4))
+            .filter(|id_code| !id_code.is_empty())
+            .collect(),
+    ))
+}
+
+/// Lex a CAVEAT into the continuation number (columns 9-10, 1 if not given), the id code
+/// (columns 12-15) and the text (columns 20-79)
+/// ## Fails
+/// It fails on an invalid continuation number.
+fn lex_caveat(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    Ok(LexItem::Caveat(
+        parse_optional_number(linenumber, line, 8, 10)?.unwrap_or(1),
+        line_field(line, 11, 15),
+        line_field(line, 19, 79),
+    ))
+}
+
+/// Lex a record continued over multiple lines, like TITLE, COMPND and SOURCE, into the
+/// continuation number (columns 8-10) and the text (columns 11-80). The continuation number is 1
+/// if it is not given. The text is trimmed at the end, and continuation lines lose the space in
+/// column 11 that separates them from the previous line.
+/// ## Fails
+/// It fails on an invalid continuation number.
+fn lex_continued(linenumber: usize, line: &str) -> Result<(usize, String), PDBError> {
+    let continuation = parse_optional_number(linenumber, line, 7, 10)?.unwrap_or(1);
+    let text = line_chars(line, 10, 80).iter().collect::<String>();
+    let text = text.trim_end();
+    Ok((
+        continuation,
+        if continuation > 1 {
+            text.strip_prefix(' ').unwrap_or(text)
+        } else {
+            text
+        }
+        .to_string(),
+    ))
+}
+
+/// Join the lines of a record continued over multiple lines in the order of their continuation
+/// numbers, separated by a space unless the previous line ends with a hyphen (a word split over
+/// two lines).
+fn join_continued(mut lines: Vec<(usize, String)>) -> String {
+    lines.sort_by_key(|(continuation, _)| *continuation);
+    let mut text = String::new();
+    for (_, line) in lines {
+        if !text.is_empty() && !text.ends_with('-') {
+            text.push(' ');
+        }
+        text.push_str(&line);
+    }
+    text
+}
+
+/// The molecules described by a specification list and the specifications that could not be read
+type SpecificationList = (Vec<Vec<(String, String)>>, Vec<String>);
+
+/// Read the specification list of the COMPND or SOURCE records (`MOL_ID: 1; MOLECULE: ...;`) into
+/// the tokens and values per molecule, a new molecule starts at every MOL_ID token. The
+/// specifications without a token are returned separately.
+fn parse_specification_list(text: &str) -> SpecificationList {
+    let mut molecules: Vec<Vec<(String, String)>> = Vec::new();
+    let mut invalid = Vec::new();
+    for specification in text.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+        match specification.split_once(':') {
+            Some((token, value)) => {
+                let token = token.trim().to_string();
+                if token == "MOL_ID" || molecules.is_empty() {
+                    molecules.push(Vec::new());
+                }
+                molecules
+                    .last_mut()
+                    .unwrap()
+                    .push((token, value.trim().to_string()));
+            }
+            None => invalid.push(specification.to_string()),
+        }
+    }
+    (molecules, invalid)
+}
+
+/// Lex a HELIX, the helix class (default 1, right-handed alpha), comment and length are optional
+/// ## Fails
+/// It fails on incorrect numbers in the line
+fn lex_helix(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() < 37 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "HELIX line too short",
+            "This line is too short to contain all necessary elements (up to the residue serial number of the end residue at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let serial_number = parse_number(PDBContext::line(linenumber, line, 7, 3), &chars[7..10])?;
+    let start = parse_serial(PDBContext::line(linenumber, line, 21, 4), &chars[21..25])?;
+    let end = parse_serial(PDBContext::line(linenumber, line, 33, 4), &chars[33..37])?;
+    Ok(LexItem::Helix(
+        serial_number,
+        chars[11..14].iter().collect(),
+        [
+            [chars[15], chars[16], chars[17]],
+            [chars[27], chars[28], chars[29]],
+        ],
+        [chars[19], chars[31]],
+        [start, end],
+        [
+            chars.get(25).copied().unwrap_or(' '),
+            chars.get(37).copied().unwrap_or(' '),
+        ],
+        parse_optional_number(linenumber, line, 38, 40)?.unwrap_or(1),
+        line_chars(line, 40, 70).iter().collect(),
+        parse_optional_number(linenumber, line, 71, 76)?,
+    ))
+}
+
+/// Lex a CONECT, the serial number of the Atom followed by the serial numbers of the up to four
+/// Atoms bonded to it, blank fields are skipped. The deprecated hydrogen bond and salt bridge
+/// fields are ignored.
+/// ## Fails
+/// It fails on incorrect serial numbers in the line
+fn lex_conect(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
+    let chars: Vec<char> = line.chars().take(31).collect();
+    if chars.len() < 16 {
+        return Err(PDBError::new(
+            ErrorLevel::InvalidatingError,
+            "CONECT line too short",
+            "This line is too short to contain all necessary elements (up to the first bonded atom at least).",
+            PDBContext::full_line(linenumber, line),
+        ));
+    }
+    let serial_number = parse_serial(PDBContext::line(linenumber, line, 6, 5), &chars[6..11])?;
+    let mut bonded = Vec::new();
+    for start in (11..chars.len()).step_by(5) {
+        let field = &chars[start..(start + 5).min(chars.len())];
+        if field.iter().all(|c| c.is_whitespace()) {
+            continue;
+        }
+        bonded.push(parse_serial(
+            PDBContext::line(linenumber, line, start, field.len()),
+            field,
+        )?);
+    }
+    Ok(LexItem::Conect(serial_number, bonded))
+}
+
 /// Lex a CRYST1
 /// ## Fails
 /// It fails on incorrect numbers in the line
-fn lex_cryst(linenumber: usize, line: String) -> Result<LexItem, PDBError> {
+fn lex_cryst(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
     let chars: Vec<char> = line.chars().collect();
-    let a = parse_number(Context::line(linenumber, &line, 6, 9), &chars[6..15])?;
-    let b = parse_number(Context::line(linenumber, &line, 15, 9), &chars[15..24])?;
-    let c = parse_number(Context::line(linenumber, &line, 24, 9), &chars[24..33])?;
-    let alpha = parse_number(Context::line(linenumber, &line, 33, 7), &chars[33..40])?;
-    let beta = parse_number(Context::line(linenumber, &line, 40, 7), &chars[40..47])?;
-    let gamma = parse_number(Context::line(linenumber, &line, 47, 7), &chars[47..54])?;
-    let spacegroup = chars[55..std::cmp::min(66, chars.len())]
+    let a = parse_number(PDBContext::line(linenumber, line, 6, 9), &chars[6..15])?;
+    let b = parse_number(PDBContext::line(linenumber, line, 15, 9), &chars[15..24])?;
+    let c = parse_number(PDBContext::line(linenumber, line, 24, 9), &chars[24..33])?;
+    let alpha = parse_number(PDBContext::line(linenumber, line, 33, 7), &chars[33..40])?;
+    let beta = parse_number(PDBContext::line(linenumber, line, 40, 7), &chars[40..47])?;
+    let gamma = parse_number(PDBContext::line(linenumber, line, 47, 7), &chars[47..54])?;
+    let spacegroup = chars[std::cmp::min(55, chars.len())..std::cmp::min(66, chars.len())]
         .iter()
         .collect::<String>();
-    let mut z = 1;
-    if chars.len() > 66 {
-        z = parse_number(
-            Context::line(linenumber, &line, 66, line.len() - 66),
-            &chars[66..],
-        )?;
+    let mut z = None;
+    if chars.len() > 66 && chars[66..].iter().any(|c| !c.is_whitespace()) {
+        z = Some(parse_number(
+            PDBContext::line(linenumber, line, 66, chars.len() - 66),
+            &chars[66..std::cmp::min(70, chars.len())],
+        )?);
     }
 
     Ok(LexItem::Crystal(a, b, c, alpha, beta, gamma, spacegroup, z))
@@ -471,12 +2475,12 @@ fn lex_cryst(linenumber: usize, line: String) -> Result<LexItem, PDBError> {
 /// Lex an SCALEn (where `n` is given)
 /// ## Fails
 /// It fails on incorrect numbers in the line
-fn lex_scale(linenumber: usize, line: String, row: usize) -> Result<LexItem, PDBError> {
+fn lex_scale(linenumber: usize, line: &str, row: usize) -> Result<LexItem, PDBError> {
     let chars: Vec<char> = line.chars().collect();
-    let a = parse_number(Context::line(linenumber, &line, 10, 10), &chars[10..20])?;
-    let b = parse_number(Context::line(linenumber, &line, 20, 10), &chars[20..30])?;
-    let c = parse_number(Context::line(linenumber, &line, 30, 10), &chars[30..40])?;
-    let d = parse_number(Context::line(linenumber, &line, 45, 10), &chars[45..55])?;
+    let a = parse_number(PDBContext::line(linenumber, line, 10, 10), &chars[10..20])?;
+    let b = parse_number(PDBContext::line(linenumber, line, 20, 10), &chars[20..30])?;
+    let c = parse_number(PDBContext::line(linenumber, line, 30, 10), &chars[30..40])?;
+    let d = parse_number(PDBContext::line(linenumber, line, 45, 10), &chars[45..55])?;
 
     Ok(LexItem::Scale(row, [a, b, c, d]))
 }
@@ -484,12 +2488,12 @@ fn lex_scale(linenumber: usize, line: String, row: usize) -> Result<LexItem, PDB
 /// Lex an ORIGXn (where `n` is given)
 /// ## Fails
 /// It fails on incorrect numbers in the line
-fn lex_origx(linenumber: usize, line: String, row: usize) -> Result<LexItem, PDBError> {
+fn lex_origx(linenumber: usize, line: &str, row: usize) -> Result<LexItem, PDBError> {
     let chars: Vec<char> = line.chars().collect();
-    let a = parse_number(Context::line(linenumber, &line, 10, 10), &chars[10..20])?;
-    let b = parse_number(Context::line(linenumber, &line, 20, 10), &chars[20..30])?;
-    let c = parse_number(Context::line(linenumber, &line, 30, 10), &chars[30..40])?;
-    let d = parse_number(Context::line(linenumber, &line, 45, 10), &chars[45..55])?;
+    let a = parse_number(PDBContext::line(linenumber, line, 10, 10), &chars[10..20])?;
+    let b = parse_number(PDBContext::line(linenumber, line, 20, 10), &chars[20..30])?;
+    let c = parse_number(PDBContext::line(linenumber, line, 30, 10), &chars[30..40])?;
+    let d = parse_number(PDBContext::line(linenumber, line, 45, 10), &chars[45..55])?;
 
     Ok(LexItem::OrigX(row, [a, b, c, d]))
 }
@@ -497,13 +2501,13 @@ fn lex_origx(linenumber: usize, line: String, row: usize) -> Result<LexItem, PDB
 /// Lex an MTRIXn (where `n` is given)
 /// ## Fails
 /// It fails on incorrect numbers in the line
-fn lex_mtrix(linenumber: usize, line: String, row: usize) -> Result<LexItem, PDBError> {
+fn lex_mtrix(linenumber: usize, line: &str, row: usize) -> Result<LexItem, PDBError> {
     let chars: Vec<char> = line.chars().collect();
-    let ser = parse_number(Context::line(linenumber, &line, 7, 4), &chars[7..10])?;
-    let a = parse_number(Context::line(linenumber, &line, 10, 10), &chars[10..20])?;
-    let b = parse_number(Context::line(linenumber, &line, 20, 10), &chars[20..30])?;
-    let c = parse_number(Context::line(linenumber, &line, 30, 10), &chars[30..40])?;
-    let d = parse_number(Context::line(linenumber, &line, 45, 10), &chars[45..55])?;
+    let ser = parse_number(PDBContext::line(linenumber, line, 7, 4), &chars[7..10])?;
+    let a = parse_number(PDBContext::line(linenumber, line, 10, 10), &chars[10..20])?;
+    let b = parse_number(PDBContext::line(linenumber, line, 20, 10), &chars[20..30])?;
+    let c = parse_number(PDBContext::line(linenumber, line, 30, 10), &chars[30..40])?;
+    let d = parse_number(PDBContext::line(linenumber, line, 45, 10), &chars[45..55])?;
     let mut given = false;
     if chars.len() >= 60 {
         given = chars[59] == '1';
@@ -515,20 +2519,20 @@ fn lex_mtrix(linenumber: usize, line: String, row: usize) -> Result<LexItem, PDB
 /// Lex a MASTER
 /// ## Fails
 /// It fails on incorrect numbers in the line
-fn lex_master(linenumber: usize, line: String) -> Result<LexItem, PDBError> {
+fn lex_master(linenumber: usize, line: &str) -> Result<LexItem, PDBError> {
     let chars: Vec<char> = line.chars().collect();
-    let num_remark = parse_number(Context::line(linenumber, &line, 10, 5), &chars[10..15])?;
-    let num_empty = parse_number(Context::line(linenumber, &line, 15, 5), &chars[15..20])?;
-    let num_het = parse_number(Context::line(linenumber, &line, 20, 5), &chars[20..25])?;
-    let num_helix = parse_number(Context::line(linenumber, &line, 25, 5), &chars[25..30])?;
-    let num_sheet = parse_number(Context::line(linenumber, &line, 30, 5), &chars[30..35])?;
-    let num_turn = parse_number(Context::line(linenumber, &line, 35, 5), &chars[35..40])?;
-    let num_site = parse_number(Context::line(linenumber, &line, 40, 5), &chars[40..45])?;
-    let num_xform = parse_number(Context::line(linenumber, &line, 45, 5), &chars[45..50])?;
-    let num_coord = parse_number(Context::line(linenumber, &line, 50, 5), &chars[50..55])?;
-    let num_ter = parse_number(Context::line(linenumber, &line, 55, 5), &chars[55..60])?;
-    let num_connect = parse_number(Context::line(linenumber, &line, 60, 5), &chars[60..65])?;
-    let num_seq = parse_number(Context::line(linenumber, &line, 65, 5), &chars[65..70])?;
+    let num_remark = parse_number(PDBContext::line(linenumber, line, 10, 5), &chars[10..15])?;
+    let num_empty = parse_number(PDBContext::line(linenumber, line, 15, 5), &chars[15..20])?;
+    let num_het = parse_number(PDBContext::line(linenumber, line, 20, 5), &chars[20..25])?;
+    let num_helix = parse_number(PDBContext::line(linenumber, line, 25, 5), &chars[25..30])?;
+    let num_sheet = parse_number(PDBContext::line(linenumber, line, 30, 5), &chars[30..35])?;
+    let num_turn = parse_number(PDBContext::line(linenumber, line, 35, 5), &chars[35..40])?;
+    let num_site = parse_number(PDBContext::line(linenumber, line, 40, 5), &chars[40..45])?;
+    let num_xform = parse_number(PDBContext::line(linenumber, line, 45, 5), &chars[45..50])?;
+    let num_coord = parse_number(PDBContext::line(linenumber, line, 50, 5), &chars[50..55])?;
+    let num_ter = parse_number(PDBContext::line(linenumber, line, 55, 5), &chars[55..60])?;
+    let num_connect = parse_number(PDBContext::line(linenumber, line, 60, 5), &chars[60..65])?;
+    let num_seq = parse_number(PDBContext::line(linenumber, line, 65, 5), &chars[65..70])?;
 
     Ok(LexItem::Master(
         num_remark,
@@ -546,14 +2550,165 @@ fn lex_master(linenumber: usize, line: String) -> Result<LexItem, PDBError> {
     ))
 }
 
+/// Register a row of a matrix record (SCALEn, ORIGXn or MTRIXn), identified by the record name
+/// and the serial number (0 for SCALEn and ORIGXn). The context of the first row is kept to
+/// report missing rows after parsing.
+/// ## Fails
+/// It returns a warning if the row was already given, the first row is kept.
+fn duplicate_matrix_row(
+    rows: &mut BTreeMap<(&'static str, usize), ([bool; 3], PDBContext)>,
+    matrix: (&'static str, usize),
+    row: usize,
+    context: PDBContext,
+) -> Option<PDBError> {
+    let (seen, _) = rows.entry(matrix).or_insert(([false; 3], context.clone()));
+    if seen[row] {
+        return Some(PDBError::new(
+            ErrorLevel::StrictWarning,
+            "Duplicate matrix row",
+            &format!(
+                "The {}{} record is given more than once, only the first one is used.",
+                matrix.0,
+                row + 1
+            ),
+            context,
+        ));
+    }
+    seen[row] = true;
+    None
+}
+
+/// Check the serial number of an Atom or TER record against the serial number of the previous
+/// record in the same Model, in file order. The serial numbers should increase by one, except
+/// after a TER record without serial number which implicitly consumes one serial number.
+/// A duplicate or lower serial number is reported directly, while consecutive records leaving a
+/// gap are collected in `gaps` and reported once as soon as the run ends.
+fn check_serial_order(
+    errors: &mut Vec<PDBError>,
+    gaps: &mut Option<SerialGaps>,
+    (previous, after_ter, serial): (Option<usize>, bool, usize),
+    short_description: &'static str,
+    context: PDBContext,
+) {
+    let previous = match previous {
+        Some(previous) if serial > previous + 1 && !(after_ter && serial == previous + 2) => {
+            match gaps {
+                Some(gaps) => {
+                    gaps.last = serial;
+                    gaps.count += 1;
+                }
+                None => {
+                    *gaps = Some(SerialGaps {
+                        short_description,
+                        context,
+                        first: serial,
+                        previous,
+                        last: serial,
+                        count: 1,
+                    })
+                }
+            }
+            return;
+        }
+        previous => previous,
+    };
+    if let Some(gaps) = gaps.take() {
+        errors.push(gaps.into_error());
+    }
+    let message = match previous {
+        Some(previous) if serial == previous => format!(
+            "The serial number {} is the same as the serial number of the previous record.",
+            serial
+        ),
+        Some(previous) if serial < previous => format!(
+            "The serial number {} is lower than the serial number of the previous record ({}).",
+            serial, previous
+        ),
+        _ => return,
+    };
+    errors.push(PDBError::new(
+        ErrorLevel::LooseWarning,
+        short_description,
+        &message,
+        context,
+    ));
+}
+
+/// A run of consecutive records that each leave a gap in the serial numbers not explained by a
+/// TER record, reported as a single warning so a file with sparse numbering (like an extracted
+/// CA-only structure) does not give a warning for every line
+struct SerialGaps {
+    /// The short description of the first record with a gap
+    short_description: &'static str,
+    /// The context of the first record with a gap
+    context: PDBContext,
+    /// The serial number of the first record with a gap
+    first: usize,
+    /// The serial number of the record before the first gap
+    previous: usize,
+    /// The serial number of the last record with a gap
+    last: usize,
+    /// The number of records with a gap
+    count: usize,
+}
+
+impl SerialGaps {
+    /// The warning describing this run of gaps
+    fn into_error(self) -> PDBError {
+        let mut message = format!(
+            "The serial number {} leaves a gap after the serial number of the previous record ({}) which is not explained by a TER record.",
+            self.first, self.previous
+        );
+        if self.count > 1 {
+            message.push_str(&format!(
+                " The {} records following it up to serial number {} leave gaps as well.",
+                self.count - 1,
+                self.last
+            ));
+        }
+        PDBError::new(
+            ErrorLevel::LooseWarning,
+            self.short_description,
+            &message,
+            self.context,
+        )
+    }
+}
+
+/// Parse a serial number, which can be written in decimal or in hybrid-36 (see `SerialOverflowPolicy`)
+/// ## Fails
+/// It fails if the text is not a valid serial number
+fn parse_serial(context: PDBContext, input: &[char]) -> Result<usize, PDBError> {
+    match hybrid36_decode(&input.iter().collect::<String>(), input.len()) {
+        Some(v) => Ok(v),
+        None => Err(PDBError::new(
+            ErrorLevel::BreakingError,
+            "Not a serial number",
+            "The text presented is not a valid serial number, it should be a decimal or hybrid-36 number.",
+            context,
+        )),
+    }
+}
+
+/// Parse the number in the given columns of the line, `None` if the columns are blank or beyond the end of the line
+fn parse_optional_number<T: FromStr>(
+    linenumber: usize,
+    line: &str,
+    start: usize,
+    end: usize,
+) -> Result<Option<T>, PDBError> {
+    let text = line_chars(line, start, end);
+    if text.iter().all(|c| c.is_whitespace()) {
+        Ok(None)
+    } else {
+        parse_number(PDBContext::line(linenumber, line, start, text.len()), &text).map(Some)
+    }
+}
+
 /// Parse a number, generic for anything that can be parsed using FromStr
-fn parse_number<T: FromStr>(context: Context, input: &[char]) -> Result<T, PDBError> {
-    let string = input
-        .iter()
-        .collect::<String>()
-        .split_whitespace()
-        .collect::<String>();
-    match string.parse::<T>() {
+fn parse_number<T: FromStr>(context: PDBContext, input: &[char]) -> Result<T, PDBError> {
+    let string = input.iter().collect::<String>();
+    match string.trim().parse::<T>() {
         Ok(v) => Ok(v),
         Err(_) => Err(PDBError::new(
             ErrorLevel::BreakingError,
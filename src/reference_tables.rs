@@ -1,65 +1,109 @@
 #![allow(dead_code)]
+use crate::classifier::Classifier;
+use crate::structs::EntityType;
 
 /// Gets the index (into Int. Crys. Handbook Vol A 2016) for the given Herman Mauguin symbol
 pub fn get_index_for_symbol(symbol: &str) -> Option<usize> {
-    let mut counter = 1;
-    for item in HERMANN_MAUGUIN_SYMBOL {
-        if *item == symbol {
-            return Some(counter);
-        }
-        counter += 1;
-    }
-    None
+    HERMANN_MAUGUIN_SYMBOL
+        .iter()
+        .position(|item| *item == symbol)
+        .map(|index| index + 1)
 }
 
 /// Gets the Herman Mauguin symbol for the given index (into Int. Crys. Handbook Vol A 2016)
 pub fn get_symbol_for_index(index: usize) -> Option<&'static str> {
-    match HERMANN_MAUGUIN_SYMBOL.get(index - 1) {
-        Some(n) => Some(*n),
-        None => None,
-    }
+    HERMANN_MAUGUIN_SYMBOL.get(index - 1).copied()
 }
 
 /// Gets the transformations given an index (into Int. Crys. Handbook Vol A 2016) for the given space group
 pub fn get_transformation(index: usize) -> Option<&'static [[[f64; 4]; 3]]> {
-    match SYMBOL_TRANSFORMATION.get(index - 1) {
-        Some(n) => Some(*n),
-        None => None,
-    }
+    SYMBOL_TRANSFORMATION.get(index - 1).copied()
 }
 
 /// Gets the atomic number for the given element. It is case insensitive for the element name.
 pub fn get_atomic_number(element: &str) -> Option<usize> {
-    let mut counter = 1;
     let element = element.to_lowercase();
-    for item in ELEMENT_SYMBOLS {
-        if (*item).to_lowercase() == element {
-            return Some(counter);
-        }
-        counter += 1;
-    }
-    None
+    ELEMENT_SYMBOLS
+        .iter()
+        .position(|item| (*item).to_lowercase() == element)
+        .map(|index| index + 1)
 }
 
 /// Gets the atomic radius for the given atomic number (defined up until 'Cm').
 /// Source: Martin Rahm, Roald Hoffmann, and N. W. Ashcroft. Atomic and Ionic Radii of Elements 1-96. Chemistry - A European Journal, 22(41):14625–14632, oct 2016. URL: http://doi.wiley.com/10.1002/chem.201602949, doi:10.1002/chem.201602949.
 pub fn get_atomic_radius(atomic_number: usize) -> Option<f64> {
-    match ELEMENT_RADII.get(atomic_number) {
-        Some(n) => Some(*n),
-        None => None,
-    }
+    ELEMENT_RADII.get(atomic_number).copied()
+}
+
+/// Gets the van der Waals radius for the given atomic number, only defined for the elements listed by Bondi.
+/// Source: A. Bondi. van der Waals Volumes and Radii. The Journal of Physical Chemistry, 68(3):441–451, 1964. doi:10.1021/j100785a001.
+pub fn get_vanderwaals_radius(atomic_number: usize) -> Option<f64> {
+    VANDERWAALS_RADII
+        .iter()
+        .find(|(number, _)| *number == atomic_number)
+        .map(|(_, radius)| *radius)
 }
 
 /// Gets the amino acid number into the table, effectively providing the recognition of it being an amino acid or not
 pub fn get_amino_acid_number(aa: &str) -> Option<usize> {
-    let mut counter = 1;
-    for item in AMINO_ACIDS {
-        if *item == aa {
-            return Some(counter);
-        }
-        counter += 1;
-    }
-    None
+    AMINO_ACIDS
+        .iter()
+        .position(|item| *item == aa)
+        .map(|index| index + 1)
+}
+
+/// Gets the one letter code of the given amino acid, for the 20 standard amino acids only
+pub fn get_one_letter_code(aa: &str) -> Option<char> {
+    get_amino_acid_number(aa).and_then(|number| AMINO_ACID_CODES.chars().nth(number - 1))
+}
+
+/// Returns if the given residue name is the unknown amino acid (UNK), a part of a polymer with an
+/// unknown sequence, which has `X` as its one letter code
+pub fn is_unknown_amino_acid(name: &str) -> bool {
+    name == UNKNOWN_AMINO_ACID
+}
+
+/// Returns if the given residue name is an unknown ligand (UNL) or an unknown atom or ion (UNX),
+/// which have no known formula or elements
+pub fn is_unknown_ligand(name: &str) -> bool {
+    UNKNOWN_LIGANDS.contains(&name)
+}
+
+/// Returns if the given residue name is a standard residue, so one of the 20 standard amino acids or
+/// one of the common (deoxy)ribonucleotides
+pub fn is_standard_residue(name: &str) -> bool {
+    get_amino_acid_number(name).is_some() || is_dna_residue(name) || is_rna_residue(name)
+}
+
+/// Returns if the given residue name is a deoxyribonucleotide
+pub fn is_dna_residue(name: &str) -> bool {
+    DNA_RESIDUES.contains(&name)
+}
+
+/// Returns if the given residue name is a ribonucleotide
+pub fn is_rna_residue(name: &str) -> bool {
+    RNA_RESIDUES.contains(&name)
+}
+
+/// Returns if the given residue name is a common saccharide (monosaccharide building block)
+pub fn is_saccharide_residue(name: &str) -> bool {
+    SACCHARIDE_RESIDUES.contains(&name)
+}
+
+/// Returns if the given residue name is a water molecule, including the names registered as water (see `Classifier`)
+pub fn is_water_residue(name: &str) -> bool {
+    Classifier::lookup(name).map_or_else(
+        || WATER_RESIDUES.contains(&name),
+        |entity| entity == EntityType::Water,
+    )
+}
+
+/// Returns if the given residue name is a (monoatomic) ion, including the names registered as ions (see `Classifier`)
+pub fn is_ion_residue(name: &str) -> bool {
+    Classifier::lookup(name).map_or_else(
+        || ION_RESIDUES.contains(&name),
+        |entity| entity == EntityType::Ion,
+    )
 }
 
 /// Returns if the given number is a valid remark-type-number (according to wwPDB v 3.30)
@@ -21519,10 +21563,65 @@ const ELEMENT_RADII: &[f64] = &[
     2.76, 2.75, 2.73, 2.72, 2.71, 2.77, 2.70, 2.64, 2.58, 2.53, 2.49, 2.44, 2.33, 2.30, 2.26, 2.29,
     2.42, 2.49, 2.50, 2.50, 2.47, 2.43, 2.58, 2.92, 2.93, 2.89, 2.85, 2.83, 2.80, 2.78, 2.76, 2.64,
 ];
+const VANDERWAALS_RADII: &[(usize, f64)] = &[
+    (1, 1.20),
+    (2, 1.40),
+    (3, 1.82),
+    (6, 1.70),
+    (7, 1.55),
+    (8, 1.52),
+    (9, 1.47),
+    (10, 1.54),
+    (11, 2.27),
+    (12, 1.73),
+    (14, 2.10),
+    (15, 1.80),
+    (16, 1.80),
+    (17, 1.75),
+    (18, 1.88),
+    (19, 2.75),
+    (28, 1.63),
+    (29, 1.40),
+    (30, 1.39),
+    (31, 1.87),
+    (33, 1.85),
+    (34, 1.90),
+    (35, 1.85),
+    (36, 2.02),
+    (46, 1.63),
+    (47, 1.72),
+    (48, 1.58),
+    (49, 1.93),
+    (50, 2.17),
+    (52, 2.06),
+    (53, 1.98),
+    (54, 2.16),
+    (78, 1.72),
+    (79, 1.66),
+    (80, 1.55),
+    (81, 1.96),
+    (82, 2.02),
+    (92, 1.86),
+];
 const AMINO_ACIDS: &[&str] = &[
     "ALA", "ARG", "ASN", "ASP", "CYS", "GLN", "GLU", "GLY", "HIS", "ILE", "LEU", "LYS", "MET",
     "PHE", "PRO", "SER", "THR", "TRP", "TYR", "VAL",
 ];
+/// The one letter codes of the amino acids, in the same order as `AMINO_ACIDS`
+const AMINO_ACID_CODES: &str = "ARNDCQEGHILKMFPSTWYV";
+const UNKNOWN_AMINO_ACID: &str = "UNK";
+const UNKNOWN_LIGANDS: &[&str] = &["UNL", "UNX"];
+const DNA_RESIDUES: &[&str] = &["DA", "DC", "DG", "DT", "DI", "DU"];
+const RNA_RESIDUES: &[&str] = &["A", "C", "G", "U", "I"];
+const SACCHARIDE_RESIDUES: &[&str] = &[
+    "NAG", "NDG", "MAN", "BMA", "GLC", "BGC", "GAL", "GLA", "FUC", "FUL", "SIA", "XYS", "XYP",
+    "FRU", "GCU", "IDR", "A2G", "NGA",
+];
+const WATER_RESIDUES: &[&str] = &["HOH", "WAT", "DOD", "H2O"];
+const ION_RESIDUES: &[&str] = &[
+    "LI", "NA", "K", "RB", "CS", "MG", "CA", "SR", "BA", "MN", "FE", "FE2", "CO", "NI", "CU",
+    "CU1", "ZN", "CD", "HG", "F", "CL", "BR", "IOD", "AL", "PB",
+];
 
 const HERMANN_MAUGUIN_SYMBOL: &[&str] = &[
     "P 1",
@@ -0,0 +1,193 @@
+use crate::sasa::SasaParameters;
+use crate::structs::*;
+
+use std::io;
+use std::io::prelude::*;
+
+/// A builder for a table with one row per Residue, to export per residue data to CSV or TSV.
+/// Create it with `PDB::residue_table()` and select the columns with the `with_*` functions.
+///
+/// Every row starts with the serial number of the Model and the residue id (`chain:serial:name`,
+/// for example `A:12:ALA`) followed by the selected columns. The order of the columns is
+/// always the same, regardless of the order in which they were selected. Missing values (for
+/// example the phi angle of the first Residue in a Chain) are written as empty cells.
+#[derive(Debug, Clone)]
+pub struct ResidueTable<'a> {
+    /// The PDB to get the data from
+    pdb: &'a PDB,
+    /// Add the mean B-factor
+    b_factor: bool,
+    /// Add the SASA, calculated with these parameters
+    sasa: Option<SasaParameters>,
+    /// Add the backbone torsion angles
+    torsions: bool,
+}
+
+impl PDB {
+    /// Create a table with one row per Residue (Normal and Hetero) for all Models in this PDB,
+    /// see `ResidueTable` for the details.
+    pub fn residue_table(&self) -> ResidueTable<'_> {
+        ResidueTable {
+            pdb: self,
+            b_factor: false,
+            sasa: None,
+            torsions: false,
+        }
+    }
+}
+
+impl<'a> ResidueTable<'a> {
+    /// Add a column `b_factor` with the mean B-factor of the atoms of the Residue
+    pub fn with_b_factor(mut self) -> Self {
+        self.b_factor = true;
+        self
+    }
+
+    /// Add a column `sasa` with the solvent accessible surface area of the Residue in Å²,
+    /// calculated per Model (see `Model::residue_sasa`). The values are left empty for Models with
+    /// CA-only Chains (see `Chain::is_ca_only`).
+    /// ## Arguments
+    /// * `parameters` - the parameters for the SASA calculation
+    pub fn with_sasa(mut self, parameters: SasaParameters) -> Self {
+        self.sasa = Some(parameters);
+        self
+    }
+
+    /// Add the columns `phi`, `psi` and `omega` with the backbone torsion angles of the Residue in
+    /// degrees, left empty if any of the needed atoms is missing (as in CA-only Chains)
+    pub fn with_torsions(mut self) -> Self {
+        self.torsions = true;
+        self
+    }
+
+    /// Get the names of the columns of this table
+    pub fn header(&self) -> Vec<&'static str> {
+        let mut header = vec!["model", "residue_id"];
+        if self.b_factor {
+            header.push("b_factor");
+        }
+        if self.sasa.is_some() {
+            header.push("sasa");
+        }
+        if self.torsions {
+            header.extend(["phi", "psi", "omega"]);
+        }
+        header
+    }
+
+    /// Get the rows of this table, with all values formatted as text and missing values as empty strings
+    pub fn rows(&self) -> Vec<Vec<String>> {
+        let optional = |value: Option<f64>| value.map_or_else(String::new, |v| format!("{:.3}", v));
+        let mut rows = Vec::new();
+        for model in self.pdb.models() {
+            let sasa = self
+                .sasa
+                .as_ref()
+                .map(|parameters| model.residue_sasa(parameters).unwrap_or_default());
+            let mut index = 0;
+            for chain in model.all_chains() {
+                let torsions = if self.torsions {
+                    chain.backbone_dihedrals()
+                } else {
+                    Vec::new()
+                };
+                for (residue_index, residue) in chain.residues().enumerate() {
+                    let mut row = vec![
+                        model.serial_number().to_string(),
+                        format!(
+                            "{}:{}:{}",
+                            chain.id(),
+                            residue.serial_number(),
+                            residue.id()
+                        ),
+                    ];
+                    if self.b_factor {
+                        row.push(optional(residue.average_b_factor()));
+                    }
+                    if let Some(sasa) = &sasa {
+                        row.push(optional(sasa.get(index).copied()));
+                    }
+                    if self.torsions {
+                        let (phi, psi, omega) = torsions[residue_index];
+                        row.extend([optional(phi), optional(psi), optional(omega)]);
+                    }
+                    rows.push(row);
+                    index += 1;
+                }
+            }
+        }
+        rows
+    }
+
+    /// Write this table as comma separated values, including a header line
+    /// ## Fails
+    /// It fails if the writer fails.
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.write_separated(writer, ',')
+    }
+
+    /// Write this table as tab separated values, including a header line
+    /// ## Fails
+    /// It fails if the writer fails.
+    pub fn write_tsv<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.write_separated(writer, '\t')
+    }
+
+    fn write_separated<W: Write>(&self, mut writer: W, separator: char) -> io::Result<()> {
+        let separator = separator.to_string();
+        writeln!(writer, "{}", self.header().join(&separator))?;
+        for row in self.rows() {
+            writeln!(writer, "{}", row.join(&separator))?;
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sasa::SasaParameters;
+    use crate::structs::*;
+
+    #[test]
+    fn write_csv() {
+        let mut pdb = PDB::new();
+        let mut model = Model::new(1);
+        let positions = [
+            ("N", (0.0, 1.4, 0.0), 10.0),
+            ("CA", (0.0, 0.0, 0.0), 20.0),
+            ("C", (1.5, 0.0, 0.0), 30.0),
+        ];
+        for (serial, (name, pos, b)) in positions.iter().enumerate() {
+            let chars = format!("{:^4}", name).chars().collect::<Vec<char>>();
+            let atom = Atom::new(
+                serial,
+                [chars[0], chars[1], chars[2], chars[3]],
+                pos.0,
+                pos.1,
+                pos.2,
+                1.0,
+                *b,
+                [' ', name.chars().next().unwrap()],
+                0,
+            )
+            .unwrap();
+            model.add_atom(atom, 'A', 7, None, ['G', 'L', 'Y']);
+        }
+        pdb.add_model(model);
+
+        let mut output = Vec::new();
+        pdb.residue_table()
+            .with_torsions()
+            .with_b_factor()
+            .write_csv(&mut output)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "model,residue_id,b_factor,phi,psi,omega\n1,A:7:GLY,20.000,,,\n"
+        );
+
+        let table = pdb.residue_table().with_sasa(SasaParameters::default());
+        assert_eq!(table.header(), vec!["model", "residue_id", "sasa"]);
+        assert!(table.rows()[0][2].parse::<f64>().unwrap() > 0.0);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::error::*;
+use crate::structs::*;
+use std::collections::HashMap;
+
+/// The radius in Å used for atoms without a known van der Waals radius
+const DEFAULT_RADIUS: f64 = 1.8;
+
+#[derive(Debug, Clone, PartialEq)]
+/// The parameters for the calculation of the solvent accessible surface area (SASA)
+pub struct SasaParameters {
+    /// The radius of the solvent probe in Å
+    probe_radius: f64,
+    /// The number of test points on the sphere around every atom
+    points: usize,
+}
+
+impl SasaParameters {
+    /// Create new SASA parameters
+    /// ## Arguments
+    /// * `probe_radius` - the radius of the solvent probe in Å, 1.4 Å for water
+    /// * `points` - the number of test points on the sphere around every atom, more points give a more accurate result
+    /// ## Panics
+    /// It panics if `probe_radius` is not finite or negative, or if `points` is zero.
+    pub fn new(probe_radius: f64, points: usize) -> SasaParameters {
+        assert!(
+            probe_radius.is_finite() && probe_radius >= 0.0,
+            "The probe radius is not finite or negative"
+        );
+        assert!(points > 0, "The number of points has to be at least one");
+        SasaParameters {
+            probe_radius,
+            points,
+        }
+    }
+
+    /// Get the radius of the solvent probe in Å
+    pub fn probe_radius(&self) -> f64 {
+        self.probe_radius
+    }
+
+    /// Get the number of test points on the sphere around every atom
+    pub fn points(&self) -> usize {
+        self.points
+    }
+}
+
+impl Default for SasaParameters {
+    /// A water probe (1.4 Å) with 100 test points per atom
+    fn default() -> Self {
+        SasaParameters::new(1.4, 100)
+    }
+}
+
+impl Model {
+    /// Calculate the solvent accessible surface area of every atom (Normal and Hetero) in this Model
+    /// in Å², in the same order as `all_atoms()`. It uses the Shrake-Rupley algorithm with the van
+    /// der Waals radii of the atoms (see `Atom::vanderwaals_radius`), atoms without a known radius
+    /// get a radius of 1.8 Å.
+    /// ## Arguments
+    /// * `parameters` - the probe radius and number of test points to use
+    /// ## Fails
+    /// It fails with `AnalysisError::CaOnlyChain` if any of the Chains is CA-only (see
+    /// `Chain::is_ca_only`), as the surface of the missing atoms would be attributed to the CA atoms.
+    pub fn atomic_sasa(&self, parameters: &SasaParameters) -> Result<Vec<f64>, AnalysisError> {
+        self.check_full_atoms()?;
+        let spheres = self
+            .all_atoms()
+            .map(|atom| {
+                (
+                    atom.pos(),
+                    atom.vanderwaals_radius().unwrap_or(DEFAULT_RADIUS) + parameters.probe_radius,
+                )
+            })
+            .collect::<Vec<((f64, f64, f64), f64)>>();
+        if spheres.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Put all spheres in a grid with cells big enough to only have to look at neighbouring cells
+        let cell_size = 2.0 * spheres.iter().map(|s| s.1).fold(0.0, f64::max);
+        let cell = |pos: (f64, f64, f64)| {
+            (
+                (pos.0 / cell_size).floor() as i64,
+                (pos.1 / cell_size).floor() as i64,
+                (pos.2 / cell_size).floor() as i64,
+            )
+        };
+        let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+        for (index, sphere) in spheres.iter().enumerate() {
+            grid.entry(cell(sphere.0)).or_default().push(index);
+        }
+
+        let points = sphere_points(parameters.points);
+        Ok(spheres
+            .iter()
+            .enumerate()
+            .map(|(index, &(pos, radius))| {
+                let (x, y, z) = cell(pos);
+                let mut neighbours = Vec::new();
+                for dx in -1..=1 {
+                    for dy in -1..=1 {
+                        for dz in -1..=1 {
+                            for other in grid.get(&(x + dx, y + dy, z + dz)).into_iter().flatten() {
+                                let (other_pos, other_radius) = spheres[*other];
+                                if *other != index
+                                    && squared_distance(pos, other_pos)
+                                        < (radius + other_radius).powi(2)
+                                {
+                                    neighbours.push(spheres[*other]);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Check every test point, starting with the last neighbour that buried a point
+                let mut last = 0;
+                let accessible = points
+                    .iter()
+                    .filter(|point| {
+                        let test = (
+                            pos.0 + point.0 * radius,
+                            pos.1 + point.1 * radius,
+                            pos.2 + point.2 * radius,
+                        );
+                        let buried =
+                            |n: &((f64, f64, f64), f64)| squared_distance(test, n.0) < n.1 * n.1;
+                        if neighbours.get(last).is_some_and(buried) {
+                            return false;
+                        }
+                        match neighbours.iter().position(buried) {
+                            Some(found) => {
+                                last = found;
+                                false
+                            }
+                            None => true,
+                        }
+                    })
+                    .count();
+                4.0 * std::f64::consts::PI * radius * radius * accessible as f64
+                    / points.len() as f64
+            })
+            .collect())
+    }
+
+    /// Calculate the solvent accessible surface area of every Residue (Normal and Hetero) in this
+    /// Model in Å², in the same order as `all_residues()`. See `atomic_sasa` for the details.
+    /// ## Arguments
+    /// * `parameters` - the probe radius and number of test points to use
+    /// ## Fails
+    /// It fails with `AnalysisError::CaOnlyChain` if any of the Chains is CA-only, see `atomic_sasa`.
+    pub fn residue_sasa(&self, parameters: &SasaParameters) -> Result<Vec<f64>, AnalysisError> {
+        let mut atomic = self.atomic_sasa(parameters)?.into_iter();
+        Ok(self
+            .all_residues()
+            .map(|residue| atomic.by_ref().take(residue.atom_count()).sum())
+            .collect())
+    }
+}
+
+fn squared_distance(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)
+}
+
+/// Generate (nearly) evenly distributed points on the unit sphere using the golden section spiral
+fn sphere_points(n: usize) -> Vec<(f64, f64, f64)> {
+    let increment = std::f64::consts::PI * (3.0 - 5.0_f64.sqrt());
+    (0..n)
+        .map(|k| {
+            let y = 1.0 - (2.0 * k as f64 + 1.0) / n as f64;
+            let r = (1.0 - y * y).sqrt();
+            let (s, c) = (k as f64 * increment).sin_cos();
+            (c * r, y, s * r)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SasaParameters;
+    use crate::structs::*;
+
+    fn carbon(serial_number: usize, x: f64) -> Atom {
+        Atom::new(
+            serial_number,
+            [' ', 'C', ' ', ' '],
+            x,
+            0.0,
+            0.0,
+            1.0,
+            0.0,
+            [' ', 'C'],
+            0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn isolated_and_buried_atoms() {
+        let parameters = SasaParameters::new(1.4, 960);
+        let mut model = Model::new(0);
+        model.add_hetero_atom(carbon(1, 0.0), 'A', 1, None, ['C', 'H', '4']);
+        let full = 4.0 * std::f64::consts::PI * 3.1 * 3.1;
+        assert!((model.atomic_sasa(&parameters).unwrap()[0] - full).abs() < 1e-6);
+
+        // A second atom at 3.1 Å covers exactly a quarter of the sphere of the first
+        model.add_hetero_atom(carbon(2, 3.1), 'A', 1, None, ['C', 'H', '4']);
+        let sasa = model.atomic_sasa(&parameters).unwrap();
+        assert!((sasa[0] - 0.75 * full).abs() < 0.01 * full);
+        assert!((sasa[0] - sasa[1]).abs() < 0.01 * full);
+        assert_eq!(model.residue_sasa(&parameters), Ok(vec![sasa[0] + sasa[1]]));
+    }
+}
@@ -1,45 +1,960 @@
+use crate::cancellation::*;
+use crate::error::*;
+use crate::read::read_pdb;
+use crate::reference_tables;
+use crate::save_options::*;
+use crate::serial_overflow::*;
 use crate::structs::*;
 
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufWriter;
 
 /// Save the given PDB struct to the given file.
 /// It does not validate or renumber the PDB, so if that is needed that needs to be done in preparation.
-pub fn save(pdb: &PDB, filename: &str) -> Result<(), String> {
+/// The HEADER record is written as the first line, with the classification, deposition date and
+/// identifier of the PDB. If the PDB has no identifier its name (see `PDB::name`) is written as
+/// id code, if that is empty the file name without extension is used instead. Only a valid PDB id
+/// code (four alphanumeric characters) is written, other names are left out. The title is wrapped over
+/// as many TITLE records as needed.
+/// Serial numbers that do not fit in the PDB format are written using hybrid-36, see `save_with_policy`
+/// to choose another SerialOverflowPolicy. The metadata of the Models is written as
+/// `REMARK METADATA key = value` lines, which can be read with `metadata_remark_hook`.
+pub fn save_pdb(pdb: &PDB, filename: &str) -> Result<(), String> {
+    save_with_policy(pdb, filename, SerialOverflowPolicy::Hybrid36)
+}
+
+/// Save only the Atoms of the given PDB struct that are selected by the given Selection to the
+/// given file, see `PDB::keep_selection`. The PDB itself is not changed.
+/// ## Fails
+/// It fails if the file could not be created or if a serial number cannot be written.
+pub fn save_filtered(pdb: &PDB, filename: &str, selection: &Selection) -> Result<(), String> {
+    let mut filtered = pdb.clone();
+    filtered.keep_selection(selection);
+    save_pdb(&filtered, filename)
+}
+
+/// Save the given PDB struct to the given file, writing Atom and Residue serial numbers that do not
+/// fit in the PDB format according to the given SerialOverflowPolicy.
+/// It does not validate or renumber the PDB, so if that is needed that needs to be done in preparation.
+/// ## Fails
+/// It fails if the file could not be created or if a serial number cannot be written with the given policy.
+pub fn save_with_policy(
+    pdb: &PDB,
+    filename: &str,
+    policy: SerialOverflowPolicy,
+) -> Result<(), String> {
+    let mut options = SaveOptions::new();
+    options.set_serial_overflow_policy(policy);
+    save_with_options(pdb, filename, &options)
+}
+
+/// Save the given PDB struct to the given file using the given SaveOptions, for example to write
+/// the Chains in canonical order without changing the structure itself.
+/// It does not validate or renumber the PDB, so if that is needed that needs to be done in preparation.
+/// The name of the PDB is written in the HEADER record as described at `save_pdb`.
+/// ## Fails
+/// It fails if the file could not be created or if a serial number cannot be written with the
+/// SerialOverflowPolicy of the options. When asked to verify the saved file (see
+/// `SaveOptions::set_verify`) it also fails if the file does not read back cleanly, describing
+/// the first problems found. It also fails if saving is cancelled with the cancellation token of
+/// the options (see `SaveOptions::set_cancellation_token`).
+pub fn save_with_options(pdb: &PDB, filename: &str, options: &SaveOptions) -> Result<(), String> {
+    let policy = options.serial_overflow_policy();
+    check_serial_numbers(pdb.all_atoms(), pdb.all_residues(), policy)?;
+    if let Some(serial) = pdb
+        .ssbonds()
+        .flat_map(|ssbond| ssbond.residue_serial_numbers())
+        .find(|serial| policy.format(*serial, 4).is_none())
+    {
+        return Err(format!(
+            "The residue serial number {} in an SSBOND record cannot be saved with the {:?} SerialOverflowPolicy",
+            serial, policy
+        ));
+    }
+    if let Some(serial) = pdb
+        .links()
+        .flat_map(|link| link.residue_serial_numbers())
+        .find(|serial| policy.format(*serial, 4).is_none())
+    {
+        return Err(format!(
+            "The residue serial number {} in a LINK record cannot be saved with the {:?} SerialOverflowPolicy",
+            serial, policy
+        ));
+    }
+    if let Some(serial) = pdb
+        .cis_peptides()
+        .flat_map(|cis_peptide| cis_peptide.residue_serial_numbers())
+        .find(|serial| policy.format(*serial, 4).is_none())
+    {
+        return Err(format!(
+            "The residue serial number {} in a CISPEP record cannot be saved with the {:?} SerialOverflowPolicy",
+            serial, policy
+        ));
+    }
+    if let Some(serial) = pdb
+        .sites()
+        .flat_map(|site| site.residues())
+        .map(|(_, _, serial, _)| serial)
+        .find(|serial| policy.format(*serial, 4).is_none())
+    {
+        return Err(format!(
+            "The residue serial number {} in a SITE record cannot be saved with the {:?} SerialOverflowPolicy",
+            serial, policy
+        ));
+    }
+    if let Some(serial) = pdb
+        .database_references()
+        .flat_map(|reference| {
+            reference
+                .pdb_range()
+                .iter()
+                .map(|(serial, _)| *serial)
+                .chain(
+                    reference
+                        .sequence_differences()
+                        .filter_map(|difference| difference.residue())
+                        .map(|(serial, _)| serial),
+                )
+                .collect::<Vec<usize>>()
+        })
+        .find(|serial| policy.format(*serial, 4).is_none())
+    {
+        return Err(format!(
+            "The residue serial number {} in a DBREF or SEQADV record cannot be saved with the {:?} SerialOverflowPolicy",
+            serial, policy
+        ));
+    }
+    if let Some(serial) = pdb
+        .helices()
+        .flat_map(|helix| helix.residue_serial_numbers())
+        .find(|serial| policy.format(*serial, 4).is_none())
+    {
+        return Err(format!(
+            "The residue serial number {} in a HELIX record cannot be saved with the {:?} SerialOverflowPolicy",
+            serial, policy
+        ));
+    }
+
     let file = match File::create(filename) {
         Ok(f) => f,
         Err(e) => return Err(e.to_string()),
     };
-    let mut writer = BufWriter::new(file);
+    let mut writer = FormattedLines::new(BufWriter::new(file), options);
 
-    // Remarks
-    for line in pdb.remarks() {
+    let conect = conect_lines(pdb, policy)?;
+    let hets = pdb.calculate_het_records();
+    let name = if pdb.name().is_empty() {
+        block_name_from_path(filename)
+    } else {
+        pdb.name().to_string()
+    };
+    write_header(&mut writer, pdb, &name, &hets, options).map_err(|e| e.to_string())?;
+
+    // Models
+    let multiple_models = pdb.models().size_hint().0 > 1;
+    for model in pdb.models() {
+        if multiple_models {
+            writer
+                .write_fmt(format_args!("MODEL        {}\n", model.serial_number()))
+                .map_err(|e| e.to_string())?;
+        }
+        write_model(&mut writer, model, options).map_err(|e| e.to_string())?;
+    }
+
+    for line in &conect {
         writer
-            .write_fmt(format_args!("REMARK {:3} {}\n", line.0, line.1))
-            .unwrap();
+            .write_fmt(format_args!("{}\n", line))
+            .map_err(|e| e.to_string())?;
+    }
+
+    let mut xform = 0;
+    if pdb.has_origx() && pdb.origx().valid() {
+        xform += 3;
+    }
+    if pdb.has_scale() && pdb.scale().valid() {
+        xform += 3;
+    }
+    for mtrix in pdb.mtrix() {
+        if mtrix.valid() {
+            xform += 3;
+        }
+    }
+    writer
+        .write_fmt(format_args!(
+            "MASTER    {:5}{:5}{:5}{:5}{:5}{:5}{:5}{:5}{:5}{:5}{:5}{:5}\n",
+            remark_lines(pdb).len(),
+            0, //defined to be empty
+            hets.len(),
+            pdb.helix_count(),
+            0, //numSheet
+            0, //numTurn (deprecated)
+            pdb.sites()
+                .map(|site| site.residue_count().div_ceil(4))
+                .sum::<usize>(),
+            xform,
+            pdb.total_atom_count(),
+            pdb.model_count(),
+            conect.len(),
+            pdb.seqres_line_count(),
+        ))
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_fmt(format_args!("END\n"))
+        .map_err(|e| e.to_string())?;
+
+    writer.flush().map_err(|e| e.to_string())?;
+    drop(writer);
+    if options.verify() {
+        verify_saved(pdb, filename, options.verify_level())?;
+    }
+    Ok(())
+}
+
+/// The highest residue serial number in the database that fits in a DBREF record, higher numbers are written in DBREF2
+const MAX_DBREF_SERIAL_NUMBER: usize = 99999;
+
+/// The maximal number of problems described when a saved file does not verify
+const MAX_VERIFY_PROBLEMS: usize = 5;
+
+/// Parse the saved file again and check that it contains the same number of Models and Atoms
+/// as the saved PDB and that parsing gives no errors at or above the given level.
+/// ## Fails
+/// It fails with a description of the first problems found.
+fn verify_saved(pdb: &PDB, filename: &str, level: ErrorLevel) -> Result<(), String> {
+    let (reread, errors) = read_pdb(filename).map_err(|error| {
+        format!(
+            "The saved file \"{}\" could not be parsed again:\n{}",
+            filename, error
+        )
+    })?;
+    let mut problems = Vec::new();
+    if reread.model_count() != pdb.model_count() {
+        problems.push(format!(
+            "The saved file contains {} Models instead of {}.\n",
+            reread.model_count(),
+            pdb.model_count()
+        ));
+    }
+    let (saved_atoms, read_atoms) = (pdb.all_atoms().count(), reread.all_atoms().count());
+    if read_atoms != saved_atoms {
+        problems.push(format!(
+            "The saved file contains {} Atoms instead of {}.\n",
+            read_atoms, saved_atoms
+        ));
+    }
+    problems.extend(
+        errors
+            .iter()
+            .filter(|error| error.level().severity() >= level.severity())
+            .map(|error| error.to_string()),
+    );
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "The saved file \"{}\" does not read back cleanly, found {} problem(s):\n{}",
+            filename,
+            problems.len(),
+            problems
+                .iter()
+                .take(MAX_VERIFY_PROBLEMS)
+                .cloned()
+                .collect::<String>()
+        ))
+    }
+}
+
+/// A writer to save a PDB file one Model at a time, for example to write a trajectory or an NMR
+/// ensemble during a long simulation without keeping all Models in memory. The header records
+/// (HEADER, REMARK, SEQRES, HET, CRYST1, SCALE, ORIGX and MTRIX) are written once when creating the writer,
+/// every Model is written as a MODEL/ENDMDL block and `finish` writes the END record. As the
+/// number of Models and Atoms is not known beforehand no MASTER record is written.
+///
+/// ```
+/// use pdbtbx::*;
+/// let (pdb, _errors) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+/// let mut output = Vec::new();
+/// let mut writer = PdbWriter::new(&mut output, &pdb).unwrap();
+/// for model in pdb.models() {
+///     writer.write_model(model).unwrap();
+/// }
+/// writer.finish().unwrap();
+/// ```
+pub struct PdbWriter<W: Write> {
+    /// The output the records are written to
+    writer: FormattedLines<W>,
+    /// The options used to write the Models
+    options: SaveOptions,
+    /// The number of Models written so far
+    models: usize,
+}
+
+impl<W: Write> PdbWriter<W> {
+    /// Create a new PdbWriter writing to the given output and immediately write the header records
+    /// of the given PDB. The Models of the given PDB are not written. Serial numbers that do not
+    /// fit in the PDB format are written using hybrid-36, see `with_policy` to choose another policy.
+    /// ## Fails
+    /// It fails if the header records could not be written.
+    pub fn new(writer: W, pdb_metadata: &PDB) -> Result<Self, PDBError> {
+        Self::with_policy(writer, pdb_metadata, SerialOverflowPolicy::Hybrid36)
+    }
+
+    /// Create a new PdbWriter writing to the given output using the given SerialOverflowPolicy,
+    /// and immediately write the header records of the given PDB, see `new`.
+    /// ## Fails
+    /// It fails if the header records could not be written or if a HET record has a residue serial
+    /// number that cannot be written with the given policy.
+    pub fn with_policy(
+        writer: W,
+        pdb_metadata: &PDB,
+        policy: SerialOverflowPolicy,
+    ) -> Result<Self, PDBError> {
+        let mut options = SaveOptions::new();
+        options.set_serial_overflow_policy(policy);
+        Self::with_options(writer, pdb_metadata, &options)
+    }
+
+    /// Create a new PdbWriter writing to the given output using the given SaveOptions,
+    /// and immediately write the header records of the given PDB, see `new`.
+    /// ## Fails
+    /// It fails if the header records could not be written or if a HET record has a residue serial
+    /// number that cannot be written with the SerialOverflowPolicy of the options.
+    pub fn with_options(
+        writer: W,
+        pdb_metadata: &PDB,
+        options: &SaveOptions,
+    ) -> Result<Self, PDBError> {
+        let policy = options.serial_overflow_policy();
+        let hets = pdb_metadata.calculate_het_records();
+        if let Some(het) = hets
+            .iter()
+            .find(|h| policy.format(h.residue_serial_number(), 4).is_none())
+        {
+            return Err(PDBError::new(
+                ErrorLevel::BreakingError,
+                "Serial number too big",
+                &format!(
+                    "The residue serial number {} cannot be saved with the {:?} SerialOverflowPolicy",
+                    het.residue_serial_number(),
+                    policy
+                ),
+                PDBContext::none(),
+            ));
+        }
+        let mut writer = FormattedLines::new(writer, options);
+        write_header(
+            &mut writer,
+            pdb_metadata,
+            pdb_metadata.name(),
+            &hets,
+            options,
+        )
+        .map_err(write_error)?;
+        Ok(PdbWriter {
+            writer,
+            options: options.clone(),
+            models: 0,
+        })
+    }
+
+    /// Get the number of Models written so far
+    pub fn model_count(&self) -> usize {
+        self.models
+    }
+
+    /// Append the given Model as a MODEL/ENDMDL block. The serial number of the Model is used
+    /// for the MODEL record, or the number of this Model in the file if the serial number is 0.
+    /// ## Fails
+    /// It fails if a serial number cannot be written with the options of this writer, if the
+    /// records could not be written or if writing is cancelled with the cancellation token of the
+    /// options (see `SaveOptions::set_cancellation_token`).
+    pub fn write_model(&mut self, model: &Model) -> Result<(), PDBError> {
+        if let Err(message) = check_serial_numbers(
+            model.all_atoms(),
+            model.all_residues(),
+            self.options.serial_overflow_policy(),
+        ) {
+            return Err(PDBError::new(
+                ErrorLevel::BreakingError,
+                "Serial number too big",
+                &message,
+                PDBContext::none(),
+            ));
+        }
+        self.models += 1;
+        let serial_number = if model.serial_number() == 0 {
+            self.models
+        } else {
+            model.serial_number()
+        };
+        self.writer
+            .write_fmt(format_args!("MODEL     {:>4}\n", serial_number))
+            .map_err(write_error)?;
+        write_model(&mut self.writer, model, &self.options).map_err(write_error)?;
+        self.writer
+            .write_fmt(format_args!("ENDMDL\n"))
+            .map_err(write_error)
+    }
+
+    /// Write the END record and flush the output
+    /// ## Fails
+    /// It fails if the record could not be written or the output could not be flushed.
+    pub fn finish(mut self) -> Result<(), PDBError> {
+        self.writer
+            .write_fmt(format_args!("END\n"))
+            .map_err(write_error)?;
+        self.writer.flush().map_err(write_error)
+    }
+}
+
+/// Create the error for a failed write, or give the error of a cancelled write
+fn write_error(error: std::io::Error) -> PDBError {
+    if let Some(cancelled) = error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<PDBError>())
+    {
+        return cancelled.clone();
+    }
+    PDBError::new(
+        ErrorLevel::BreakingError,
+        "Could not write",
+        &format!("Could not write the PDB records: {}", error),
+        PDBContext::none(),
+    )
+}
+
+/// Check that the serial numbers of all given Atoms and Residues can be written with the given policy
+/// ## Fails
+/// It fails with a description of the first serial number that cannot be written.
+fn check_serial_numbers<'a>(
+    mut atoms: impl Iterator<Item = &'a Atom>,
+    mut residues: impl Iterator<Item = &'a Residue>,
+    policy: SerialOverflowPolicy,
+) -> Result<(), String> {
+    if let Some(atom) = atoms.find(|a| policy.format(a.serial_number(), 5).is_none()) {
+        return Err(format!(
+            "The atom serial number {} cannot be saved with the {:?} SerialOverflowPolicy",
+            atom.serial_number(),
+            policy
+        ));
+    }
+    if let Some(residue) = residues.find(|r| policy.format(r.serial_number(), 4).is_none()) {
+        return Err(format!(
+            "The residue serial number {} cannot be saved with the {:?} SerialOverflowPolicy",
+            residue.serial_number(),
+            policy
+        ));
+    }
+    Ok(())
+}
+
+/// Get the CONECT records for the bonds of the given PDB (see `PDB::connections`). As defined in
+/// the specification every bond is listed for both of its Atoms, with up to four bonded Atoms per record.
+/// ## Fails
+/// It fails with a description of the first serial number that cannot be written with the given policy.
+fn conect_lines(pdb: &PDB, policy: SerialOverflowPolicy) -> Result<Vec<String>, String> {
+    let mut bonded: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (a, b) in pdb.connections() {
+        bonded.entry(a).or_default().push(b);
+        bonded.entry(b).or_default().push(a);
+    }
+    let format = |serial: usize| {
+        policy.format(serial, 5).ok_or_else(|| {
+            format!(
+                "The atom serial number {} in a CONECT record cannot be saved with the {:?} SerialOverflowPolicy",
+                serial, policy
+            )
+        })
+    };
+    let mut lines = Vec::new();
+    for (serial, mut others) in bonded {
+        others.sort_unstable();
+        for chunk in others.chunks(4) {
+            let mut line = format!("CONECT{}", format(serial)?);
+            for other in chunk {
+                line.push_str(&format(*other)?);
+            }
+            lines.push(line);
+        }
+    }
+    Ok(lines)
+}
+
+/// Write the records preceding the coordinates: HEADER, OBSLTE, TITLE, SPLIT, CAVEAT, COMPND, SOURCE, EXPDTA, NUMMDL, MDLTYP, SPRSDE, REMARK, DBREF, SEQADV, SEQRES, HET, HETNAM, HETSYN, FORMUL, HELIX, SSBOND, LINK, CISPEP, SITE, CRYST1, SCALE, ORIGX and MTRIX.
+/// The id code of the HEADER record is the identifier of the PDB or otherwise the given name, if it is a valid
+/// PDB id code. The classification is cut off at 40 characters. The HEADER record is left out if all its fields are empty.
+/// The residue serial numbers of the HET records have to be checked to fit with the policy of the given options.
+fn write_header(
+    writer: &mut impl Write,
+    pdb: &PDB,
+    name: &str,
+    hets: &[Het],
+    options: &SaveOptions,
+) -> std::io::Result<()> {
+    let policy = options.serial_overflow_policy();
+    let decimals = options.coordinate_decimals();
+    let residue_serial = |number: usize| policy.format(number, 4).unwrap();
+
+    // Header
+    let id_code = pdb
+        .identifier()
+        .unwrap_or(if check_id_code(name) { name } else { "" });
+    let date = pdb
+        .deposition_date()
+        .map_or_else(String::new, |date| date.to_string());
+    if !pdb.classification().is_empty() || !date.is_empty() || !id_code.is_empty() {
+        let line = format!(
+            "HEADER    {:40.40}{:9}   {}",
+            pdb.classification(),
+            date,
+            id_code
+        );
+        writer.write_fmt(format_args!("{}\n", line.trim_end()))?;
+    }
+
+    // Obsolete
+    if let Some(obsolete) = pdb.obsolete() {
+        write_entry_replacement(writer, "OBSLTE", obsolete)?;
+    }
+
+    // Title
+    for (index, line) in wrap_continued(pdb.title(), CONTINUED_WIDTH, CONTINUED_WIDTH - 1)
+        .iter()
+        .enumerate()
+    {
+        if index == 0 {
+            writer.write_fmt(format_args!("TITLE     {}\n", line))?;
+        } else {
+            writer.write_fmt(format_args!("TITLE   {:2} {}\n", index + 1, line))?;
+        }
+    }
+
+    // Split
+    let split_entries = pdb.split_entries().collect::<Vec<&str>>();
+    for (index, entries) in split_entries.chunks(14).enumerate() {
+        writer.write_fmt(format_args!(
+            "SPLIT   {:>2} {}\n",
+            continuation(index),
+            entries.join(" ")
+        ))?;
+    }
+
+    // Caveat
+    if let Some(caveat) = pdb.caveat() {
+        for (index, line) in wrap_continued(caveat.text(), CAVEAT_WIDTH, CAVEAT_WIDTH)
+            .iter()
+            .enumerate()
+        {
+            writer.write_fmt(format_args!(
+                "CAVEAT  {:>2} {:4}    {}\n",
+                continuation(index),
+                caveat.id_code(),
+                line
+            ))?;
+        }
+    }
+
+    // Compound and source
+    for (record, lines) in [
+        ("COMPND", specification_lines(pdb.compounds())),
+        ("SOURCE", specification_lines(pdb.sources())),
+    ]
+    .iter()
+    {
+        for (index, line) in lines.iter().enumerate() {
+            if index == 0 {
+                writer.write_fmt(format_args!("{}    {}\n", record, line))?;
+            } else {
+                writer.write_fmt(format_args!("{} {:3} {}\n", record, index + 1, line))?;
+            }
+        }
+    }
+
+    // Experimental methods
+    let methods = pdb
+        .experimental_methods()
+        .map(|method| method.to_string())
+        .collect::<Vec<String>>()
+        .join("; ");
+    for (index, line) in wrap_continued(&methods, CONTINUED_WIDTH, CONTINUED_WIDTH - 1)
+        .iter()
+        .enumerate()
+    {
+        if index == 0 {
+            writer.write_fmt(format_args!("EXPDTA    {}\n", line))?;
+        } else {
+            writer.write_fmt(format_args!("EXPDTA  {:2} {}\n", index + 1, line))?;
+        }
+    }
+
+    // Number of models
+    if let Some(count) = pdb.declared_model_count() {
+        writer.write_fmt(format_args!("NUMMDL    {}\n", count))?;
+    }
+
+    // Model types
+    let model_types = pdb.model_types().collect::<Vec<&str>>().join("; ");
+    for (index, line) in wrap_continued(&model_types, CONTINUED_WIDTH, CONTINUED_WIDTH - 1)
+        .iter()
+        .enumerate()
+    {
+        if index == 0 {
+            writer.write_fmt(format_args!("MDLTYP    {}\n", line))?;
+        } else {
+            writer.write_fmt(format_args!("MDLTYP  {:2} {}\n", index + 1, line))?;
+        }
+    }
+
+    // Superseded entries
+    if let Some(supersedes) = pdb.supersedes() {
+        write_entry_replacement(writer, "SPRSDE", supersedes)?;
+    }
+
+    // Remarks
+    for (remark_type, text) in remark_lines(pdb) {
+        writer.write_fmt(format_args!("REMARK {:3} {}\n", remark_type, text))?;
+    }
+
+    // Database references, using DBREF1 and DBREF2 if the database entry does not fit in DBREF
+    let id_code = pdb.identifier().unwrap_or("");
+    for reference in pdb.database_references() {
+        let [first, last] = reference.pdb_range();
+        let [database_first, database_last] = reference.database_range();
+        if reference.accession().len() <= 8
+            && reference.database_id_code().len() <= 12
+            && database_last.0 <= MAX_DBREF_SERIAL_NUMBER
+        {
+            writer.write_fmt(format_args!(
+                "DBREF  {:4} {} {}{} {}{} {:6} {:8} {:12} {:>5}{} {:>5}{}
+",
+                id_code,
+                reference.chain_id(),
+                residue_serial(first.0),
+                first.1,
+                residue_serial(last.0),
+                last.1,
+                reference.database(),
+                reference.accession(),
+                reference.database_id_code(),
+                database_first.0,
+                database_first.1,
+                database_last.0,
+                database_last.1,
+            ))?;
+        } else {
+            writer.write_fmt(format_args!(
+                "DBREF1 {:4} {} {}{} {}{} {:6}               {}\n",
+                id_code,
+                reference.chain_id(),
+                residue_serial(first.0),
+                first.1,
+                residue_serial(last.0),
+                last.1,
+                reference.database(),
+                reference.database_id_code(),
+            ))?;
+            writer.write_fmt(format_args!(
+                "DBREF2 {:4} {}     {:22}     {:>10}  {:>10}\n",
+                id_code,
+                reference.chain_id(),
+                reference.accession(),
+                database_first.0,
+                database_last.0,
+            ))?;
+        }
+    }
+    for reference in pdb.database_references() {
+        for difference in reference.sequence_differences() {
+            let (serial, insertion_code) = difference
+                .residue()
+                .map_or((String::new(), ' '), |(serial, insertion_code)| {
+                    (residue_serial(serial), insertion_code)
+                });
+            writer.write_fmt(format_args!(
+                "SEQADV {:4} {:>3} {} {:>4}{} {:4.4} {:9.9} {:>3} {:>5} {}\n",
+                id_code,
+                difference.residue_name(),
+                reference.chain_id(),
+                serial,
+                insertion_code,
+                reference.database(),
+                reference.accession(),
+                difference.database_residue_name(),
+                difference
+                    .database_serial_number()
+                    .map_or_else(String::new, |serial| serial.to_string()),
+                difference.comment(),
+            ))?;
+        }
+    }
+
+    // Seqres
+    for (chain_id, residues) in pdb.seqres() {
+        for (index, line) in residues.chunks(13).enumerate() {
+            writer.write_fmt(format_args!(
+                "SEQRES {:3} {} {:4}  {}\n",
+                index + 1,
+                chain_id,
+                residues.len(),
+                line.iter()
+                    .map(|name| format!("{:>3}", name))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+            ))?;
+        }
+    }
+
+    // Het
+    for het in hets {
+        writer.write_fmt(format_args!(
+            "HET    {:>3}  {}{}{}  {:5}     {}\n",
+            het.id(),
+            het.chain_id(),
+            residue_serial(het.residue_serial_number()),
+            het.insertion_code(),
+            het.atom_count(),
+            het.text(),
+        ))?;
+    }
+
+    // Heterogen names, synonyms and formulas
+    for heterogen in pdb.heterogens() {
+        for (index, line) in wrap_continued(heterogen.name(), HETEROGEN_WIDTH, HETEROGEN_WIDTH - 1)
+            .iter()
+            .enumerate()
+        {
+            if index == 0 {
+                writer.write_fmt(format_args!("HETNAM     {:>3} {}\n", heterogen.id(), line))?;
+            } else {
+                writer.write_fmt(format_args!(
+                    "HETNAM  {:2} {:>3}  {}\n",
+                    index + 1,
+                    heterogen.id(),
+                    line
+                ))?;
+            }
+        }
+    }
+    for heterogen in pdb.heterogens() {
+        let synonyms = heterogen.synonyms().collect::<Vec<&str>>().join("; ");
+        for (index, line) in wrap_continued(&synonyms, HETEROGEN_WIDTH, HETEROGEN_WIDTH - 1)
+            .iter()
+            .enumerate()
+        {
+            if index == 0 {
+                writer.write_fmt(format_args!("HETSYN     {:>3} {}\n", heterogen.id(), line))?;
+            } else {
+                writer.write_fmt(format_args!(
+                    "HETSYN  {:2} {:>3}  {}\n",
+                    index + 1,
+                    heterogen.id(),
+                    line
+                ))?;
+            }
+        }
+    }
+    // Heterogens without a component number follow the Chains and the numbered components
+    let mut next_component = pdb
+        .heterogens()
+        .filter_map(|heterogen| heterogen.component_number())
+        .chain(pdb.model(0).map(|model| model.chain_count()))
+        .max()
+        .unwrap_or(0);
+    let mut formulas = pdb
+        .heterogens()
+        .filter(|heterogen| !heterogen.formula().is_empty())
+        .map(|heterogen| {
+            let number = heterogen.component_number().unwrap_or_else(|| {
+                next_component += 1;
+                next_component
+            });
+            (number, heterogen)
+        })
+        .collect::<Vec<_>>();
+    formulas.sort_by_key(|(number, _)| *number);
+    for (number, heterogen) in formulas {
+        let water = if reference_tables::is_water_residue(heterogen.id()) {
+            '*'
+        } else {
+            ' '
+        };
+        for (index, line) in wrap_continued(heterogen.formula(), FORMULA_WIDTH, FORMULA_WIDTH)
+            .iter()
+            .enumerate()
+        {
+            let continuation = if index == 0 {
+                String::new()
+            } else {
+                (index + 1).to_string()
+            };
+            writer.write_fmt(format_args!(
+                "FORMUL  {:2}  {:>3} {:>2}{}{}\n",
+                number,
+                heterogen.id(),
+                continuation,
+                water,
+                line
+            ))?;
+        }
+    }
+
+    // Helix
+    for helix in pdb.helices() {
+        let [name1, name2] = helix.residue_names();
+        let [chain1, chain2] = helix.chain_ids();
+        let [serial1, serial2] = helix.residue_serial_numbers();
+        let [insertion1, insertion2] = helix.insertion_codes();
+        writer.write_fmt(format_args!(
+            "HELIX  {:3} {:>3} {:>3} {} {}{} {:>3} {} {}{}{:2}{:30} {:5}\n",
+            helix.serial_number(),
+            helix.id(),
+            name1,
+            chain1,
+            residue_serial(serial1),
+            insertion1,
+            name2,
+            chain2,
+            residue_serial(serial2),
+            insertion2,
+            helix.class(),
+            helix.comment(),
+            helix.length(),
+        ))?;
+    }
+
+    // SSBond
+    for ssbond in pdb.ssbonds() {
+        let [chain1, chain2] = ssbond.chain_ids();
+        let [serial1, serial2] = ssbond.residue_serial_numbers();
+        let [insertion1, insertion2] = ssbond.insertion_codes();
+        let [symmetry1, symmetry2] = ssbond.symmetry_operators();
+        writer.write_fmt(format_args!(
+            "SSBOND {:3} CYS {} {}{}   CYS {} {}{}{}{:>6} {:>6}{}\n",
+            ssbond.serial_number(),
+            chain1,
+            residue_serial(serial1),
+            insertion1,
+            chain2,
+            residue_serial(serial2),
+            insertion2,
+            " ".repeat(23),
+            symmetry1,
+            symmetry2,
+            ssbond
+                .length()
+                .map_or_else(String::new, |length| format!(" {:5.2}", length)),
+        ))?;
+    }
+
+    // Link
+    for link in pdb.links() {
+        let [name1, name2] = link.atom_names();
+        let [altloc1, altloc2] = link.alternate_locations();
+        let [residue1, residue2] = link.residue_names();
+        let [chain1, chain2] = link.chain_ids();
+        let [serial1, serial2] = link.residue_serial_numbers();
+        let [insertion1, insertion2] = link.insertion_codes();
+        let [symmetry1, symmetry2] = link.symmetry_operators();
+        writer.write_fmt(format_args!(
+            "LINK        {:^4}{}{:>3} {}{}{}{}{:^4}{}{:>3} {}{}{}  {:>6} {:>6}{}\n",
+            name1,
+            altloc1,
+            residue1,
+            chain1,
+            residue_serial(serial1),
+            insertion1,
+            " ".repeat(15),
+            name2,
+            altloc2,
+            residue2,
+            chain2,
+            residue_serial(serial2),
+            insertion2,
+            symmetry1,
+            symmetry2,
+            link.length()
+                .map_or_else(String::new, |length| format!(" {:5.2}", length)),
+        ))?;
+    }
+
+    // Cis peptides
+    for cis_peptide in pdb.cis_peptides() {
+        let [name1, name2] = cis_peptide.residue_names();
+        let [chain1, chain2] = cis_peptide.chain_ids();
+        let [serial1, serial2] = cis_peptide.residue_serial_numbers();
+        let [insertion1, insertion2] = cis_peptide.insertion_codes();
+        writer.write_fmt(format_args!(
+            "CISPEP {:3} {:>3} {} {}{}   {:>3} {} {}{}       {:3}       {}\n",
+            cis_peptide.serial_number(),
+            name1,
+            chain1,
+            residue_serial(serial1),
+            insertion1,
+            name2,
+            chain2,
+            residue_serial(serial2),
+            insertion2,
+            cis_peptide.model_number(),
+            cis_peptide
+                .angle()
+                .map_or_else(String::new, |angle| format!("{:6.2}", angle)),
+        ))?;
+    }
+
+    // Sites
+    for site in pdb.sites() {
+        let residues = site.residues().collect::<Vec<_>>();
+        for (index, line) in residues.chunks(4).enumerate() {
+            let text = line
+                .iter()
+                .map(|(name, chain_id, serial_number, insertion_code)| {
+                    format!(
+                        "{:>3} {}{}{}",
+                        name,
+                        chain_id,
+                        residue_serial(*serial_number),
+                        insertion_code
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            writer.write_fmt(format_args!(
+                "SITE   {:3} {:>3} {:2} {}\n",
+                index + 1,
+                site.id(),
+                residues.len(),
+                text
+            ))?;
+        }
     }
 
     // Cryst
     if pdb.has_unit_cell() {
         let unit_cell = pdb.unit_cell();
-        let sym = if pdb.has_symmetry() {
-            format!("{:10}{:3}", pdb.symmetry().symbol(), pdb.symmetry().z(),)
+        let z = unit_cell
+            .z()
+            .or_else(|| pdb.has_symmetry().then(|| pdb.symmetry().z()));
+        let symbol = if pdb.has_symmetry() {
+            pdb.symmetry().symbol()
         } else {
-            "P 1".to_string()
+            "P 1"
         };
-        writer
-            .write_fmt(format_args!(
-                "CRYST1{:9.3}{:9.3}{:9.3}{:7.2}{:7.2}{:7.2} {}\n",
-                unit_cell.a(),
-                unit_cell.b(),
-                unit_cell.c(),
-                unit_cell.alpha(),
-                unit_cell.beta(),
-                unit_cell.gamma(),
-                sym
-            ))
-            .unwrap();
+        let sym = match z {
+            Some(z) => format!("{:11}{:>4}", symbol, z),
+            None => symbol.to_string(),
+        };
+        writer.write_fmt(format_args!(
+            "CRYST1{}{}{}{}{}{} {}\n",
+            format_fixed(unit_cell.a(), 9, decimals),
+            format_fixed(unit_cell.b(), 9, decimals),
+            format_fixed(unit_cell.c(), 9, decimals),
+            format_fixed(unit_cell.alpha(), 7, 2),
+            format_fixed(unit_cell.beta(), 7, 2),
+            format_fixed(unit_cell.gamma(), 7, 2),
+            sym
+        ))?;
     }
 
     // Scale
@@ -59,7 +974,7 @@ pub fn save(pdb: &PDB, filename: &str) -> Result<(), String> {
             m[2][1],
             m[2][2],
             m[2][3],
-        )).unwrap();
+        ))?;
     }
 
     // OrigX
@@ -79,7 +994,7 @@ pub fn save(pdb: &PDB, filename: &str) -> Result<(), String> {
             m[2][1],
             m[2][2],
             m[2][3],
-        )).unwrap();
+        ))?;
     }
 
     // MtriX
@@ -105,148 +1020,619 @@ pub fn save(pdb: &PDB, filename: &str) -> Result<(), String> {
             m[2][2],
             m[2][3],
             if mtrix.contained {'1'} else {' '},
-        )).unwrap();
+        ))?;
     }
+    Ok(())
+}
 
-    // Models
-    let multiple_models = pdb.models().size_hint().0 > 1;
-    for model in pdb.models() {
-        if multiple_models {
-            writer
-                .write_fmt(format_args!("MODEL        {}\n", model.serial_number()))
-                .unwrap();
-        }
-
-        for chain in model.chains() {
-            for residue in chain.residues() {
-                for atom in residue.atoms() {
-                    writer
-                .write_fmt(format_args!(
-                    "ATOM  {:5} {:^4} {:4}{}{:4}    {:8.3}{:8.3}{:8.3}{:6.2}{:6.2}          {:>2}{}\n",
-                    atom.serial_number(),
-                    atom.name(),
-                    residue.id(),
-                    chain.id(),
-                    residue.serial_number(),
-                    atom.pos().0,
-                    atom.pos().1,
-                    atom.pos().2,
-                    atom.occupancy(),
-                    atom.b_factor(),
-                    atom.element(),
-                    atom.pdb_charge(),
-                ))
-                .unwrap();
-                    if atom.anisotropic_temperature_factors().is_some() {
-                        writer
-                            .write_fmt(format_args!(
-                        "ANSIOU{:5} {:^4} {:4}{}{:4}  {:7}{:7}{:7}{:7}{:7}{:7}      {:>2}{}\n",
-                        atom.serial_number(),
-                        atom.name(),
-                        residue.id(),
-                        chain.id(),
-                        residue.serial_number(),
-                        (atom.anisotropic_temperature_factors().unwrap()[0][0] * 10000.0) as isize,
-                        (atom.anisotropic_temperature_factors().unwrap()[0][1] * 10000.0) as isize,
-                        (atom.anisotropic_temperature_factors().unwrap()[0][2] * 10000.0) as isize,
-                        (atom.anisotropic_temperature_factors().unwrap()[1][0] * 10000.0) as isize,
-                        (atom.anisotropic_temperature_factors().unwrap()[1][1] * 10000.0) as isize,
-                        (atom.anisotropic_temperature_factors().unwrap()[1][2] * 10000.0) as isize,
-                        atom.element(),
-                        atom.pdb_charge(),
-                    ))
-                            .unwrap();
-                    }
+/// The number of characters of text on the first line of a record continued over multiple lines,
+/// like TITLE (columns 11-80), continuation lines have one less as column 11 is left blank
+const CONTINUED_WIDTH: usize = 70;
+
+/// The number of characters of text on a HETNAM or HETSYN line (columns 16-70), continuation
+/// lines have one less as column 16 is left blank
+const HETEROGEN_WIDTH: usize = 55;
+
+/// The number of characters of the formula on a FORMUL line (columns 20-70)
+const FORMULA_WIDTH: usize = 51;
+
+/// The number of characters of text on a CAVEAT line (columns 20-79)
+const CAVEAT_WIDTH: usize = 60;
+
+/// Wrap the text of a record continued over multiple lines, the first line has the given width
+/// and the others have the given width of continuation lines. Lines are broken at a space, which
+/// is left out, or after a hyphen, so the lines are joined to the same text when parsing. Only
+/// words that are too long for a whole line are broken elsewhere.
+fn wrap_continued(text: &str, first_width: usize, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut rest = text.chars().collect::<Vec<char>>();
+    while !rest.is_empty() {
+        let width = if lines.is_empty() { first_width } else { width };
+        if rest.len() <= width {
+            lines.push(rest.iter().collect());
+            break;
+        }
+        let (end, next) = (1..=width)
+            .rev()
+            .find_map(|index| {
+                if rest[index] == ' ' && rest[index - 1] != ' ' && rest[index - 1] != '-' {
+                    Some((index, index + 1))
+                } else if rest[index - 1] == '-' {
+                    Some((index, index))
+                } else {
+                    None
                 }
+            })
+            .unwrap_or((width, width));
+        lines.push(rest[..end].iter().collect());
+        rest.drain(..next);
+    }
+    lines
+}
+
+/// The continuation number of the line with the given index, empty for the first line
+fn continuation(index: usize) -> String {
+    if index == 0 {
+        String::new()
+    } else {
+        (index + 1).to_string()
+    }
+}
+
+/// Write the OBSLTE or SPRSDE records of the given replacement, with up to nine entries per line
+fn write_entry_replacement(
+    writer: &mut impl Write,
+    record: &str,
+    replacement: &EntryReplacement,
+) -> std::io::Result<()> {
+    let date = replacement
+        .date()
+        .map_or_else(String::new, |date| date.to_string());
+    let entries = replacement.entries().collect::<Vec<&str>>();
+    // A withdrawn entry is obsolete without being replaced, so it still gets a line
+    let lines = if entries.is_empty() {
+        vec![&entries[..]]
+    } else {
+        entries.chunks(9).collect()
+    };
+    for (index, entries) in lines.into_iter().enumerate() {
+        let line = format!(
+            "{}  {:>2} {:9} {:4}      {}",
+            record,
+            continuation(index),
+            date,
+            replacement.id_code(),
+            entries.join(" ")
+        );
+        writer.write_fmt(format_args!("{}\n", line.trim_end()))?;
+    }
+    Ok(())
+}
+
+/// Get the lines of the COMPND or SOURCE records for the given specification lists: every
+/// specification (`TOKEN: value;`) starts on a new line and is wrapped over as many lines as
+/// needed, the last specification has no semicolon.
+fn specification_lines<'a>(
+    molecules: impl Iterator<Item = &'a Vec<(String, String)>>,
+) -> Vec<String> {
+    let specifications = molecules.flatten().collect::<Vec<_>>();
+    let mut lines = Vec::new();
+    for (index, (token, value)) in specifications.iter().enumerate() {
+        let text = format!(
+            "{}: {}{}",
+            token,
+            value,
+            if index + 1 < specifications.len() {
+                ";"
+            } else {
+                ""
             }
-            let last_atom = chain.atoms().nth_back(0).unwrap();
-            let last_residue = chain.residues().nth_back(0).unwrap();
-            writer
-                .write_fmt(format_args!(
-                    "TER{:5}      {:3} {}{:4} \n",
-                    last_atom.serial_number(),
-                    last_residue.id(),
-                    chain.id(),
-                    last_residue.serial_number()
-                ))
-                .unwrap();
-        }
-        for chain in model.hetero_chains() {
-            for residue in chain.residues() {
-                for atom in residue.atoms() {
-                    writer
-                .write_fmt(format_args!(
-                    "HETATM{:5} {:^4} {:4}{}{:4}    {:8.3}{:8.3}{:8.3}{:6.2}{:6.2}          {:>2}{}\n",
-                    atom.serial_number(),
-                    atom.name(),
-                    residue.id(),
-                    chain.id(),
-                    residue.serial_number(),
-                    atom.pos().0,
-                    atom.pos().1,
-                    atom.pos().2,
-                    atom.occupancy(),
-                    atom.b_factor(),
-                    atom.element(),
-                    atom.pdb_charge()
-                ))
-                .unwrap();
-                    if atom.anisotropic_temperature_factors().is_some() {
-                        writer
-                            .write_fmt(format_args!(
-                        "ANSIOU{:5} {:^4} {:4}{}{:4}  {:7}{:7}{:7}{:7}{:7}{:7}      {:>2}{}\n",
-                        atom.serial_number(),
-                        atom.name(),
-                        residue.id(),
-                        chain.id(),
-                        residue.serial_number(),
-                        (atom.anisotropic_temperature_factors().unwrap()[0][0] * 10000.0) as isize,
-                        (atom.anisotropic_temperature_factors().unwrap()[0][1] * 10000.0) as isize,
-                        (atom.anisotropic_temperature_factors().unwrap()[0][2] * 10000.0) as isize,
-                        (atom.anisotropic_temperature_factors().unwrap()[1][0] * 10000.0) as isize,
-                        (atom.anisotropic_temperature_factors().unwrap()[1][1] * 10000.0) as isize,
-                        (atom.anisotropic_temperature_factors().unwrap()[1][2] * 10000.0) as isize,
-                        atom.element(),
-                        atom.pdb_charge()
-                    ))
-                            .unwrap();
-                    }
-                }
+        );
+        let width = if lines.is_empty() {
+            CONTINUED_WIDTH
+        } else {
+            CONTINUED_WIDTH - 1
+        };
+        lines.extend(wrap_continued(&text, width, CONTINUED_WIDTH - 1));
+    }
+    lines
+}
+
+/// The number of chain ids on a single APPLY THE FOLLOWING TO CHAINS line of REMARK 350
+const CHAINS_PER_LINE: usize = 12;
+
+/// Get the remarks to write: the remarks of the PDB, with the assemblies (if any) written as
+/// REMARK 350 in place of the REMARK 350 of the PDB, in the order of the remark-type-numbers.
+fn remark_lines(pdb: &PDB) -> Vec<(usize, String)> {
+    let assemblies = pdb.assemblies().collect::<Vec<&Assembly>>();
+    if assemblies.is_empty() {
+        return pdb.remarks().cloned().collect();
+    }
+    let mut biomolecules = vec![
+        String::new(),
+        "COORDINATES FOR A COMPLETE MULTIMER REPRESENTING THE KNOWN".to_string(),
+        "BIOLOGICALLY SIGNIFICANT OLIGOMERIZATION STATE OF THE".to_string(),
+        "MOLECULE CAN BE GENERATED BY APPLYING BIOMT TRANSFORMATIONS".to_string(),
+        "GIVEN BELOW.  BOTH NON-CRYSTALLOGRAPHIC AND".to_string(),
+        "CRYSTALLOGRAPHIC OPERATIONS ARE GIVEN.".to_string(),
+    ];
+    for assembly in assemblies {
+        biomolecules.push(String::new());
+        biomolecules.push(format!("BIOMOLECULE: {}", assembly.serial_number()));
+        for (index, chains) in assembly.chains().chunks(CHAINS_PER_LINE).enumerate() {
+            let last = (index + 1) * CHAINS_PER_LINE >= assembly.chains().len();
+            biomolecules.push(format!(
+                "{} {}{}",
+                if index == 0 {
+                    "APPLY THE FOLLOWING TO CHAINS:"
+                } else {
+                    "                   AND CHAINS:"
+                },
+                chains
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                if last { "" } else { "," }
+            ));
+        }
+        for (index, transformation) in assembly.transformations().iter().enumerate() {
+            for (row_index, row) in transformation.matrix().iter().enumerate() {
+                biomolecules.push(format!(
+                    "  BIOMT{} {:3}{:10.6}{:10.6}{:10.6}{:15.5}",
+                    row_index + 1,
+                    index + 1,
+                    row[0],
+                    row[1],
+                    row[2],
+                    row[3]
+                ));
             }
         }
     }
 
-    let mut xform = 0;
-    if pdb.has_origx() && pdb.origx().valid() {
-        xform += 3;
+    let mut lines = Vec::new();
+    let mut biomolecules = Some(biomolecules);
+    for remark in pdb.remarks() {
+        if remark.0 == 350 {
+            continue;
+        }
+        if remark.0 > 350 {
+            if let Some(biomolecules) = biomolecules.take() {
+                lines.extend(biomolecules.into_iter().map(|text| (350, text)));
+            }
+        }
+        lines.push(remark.clone());
     }
-    if pdb.has_scale() && pdb.scale().valid() {
-        xform += 3;
+    if let Some(biomolecules) = biomolecules {
+        lines.extend(biomolecules.into_iter().map(|text| (350, text)));
     }
-    for mtrix in pdb.mtrix() {
-        if mtrix.valid() {
-            xform += 3;
+    lines
+}
+
+/// Write the metadata and the coordinates (ATOM, ANISOU, TER and HETATM records) of the given Model.
+/// The serial numbers have to be checked to fit with the policy of the given options.
+fn write_model(
+    writer: &mut impl Write,
+    model: &Model,
+    options: &SaveOptions,
+) -> std::io::Result<()> {
+    for (key, value) in model.all_metadata() {
+        writer.write_fmt(format_args!("REMARK METADATA {} = {}\n", key, value))?;
+    }
+
+    let (chains, hetero_residues) = chain_order(model, options.canonical_chain_order());
+    let mut written = vec![false; hetero_residues.len()];
+    for chain in chains {
+        for residue in chain.residues() {
+            for atom in residue.atoms() {
+                write_atom(writer, chain, residue, atom, options)?;
+            }
+        }
+        write_ter(writer, chain, options)?;
+        if options.interleave_hetero() {
+            for (index, (hetero_chain, residue)) in hetero_residues.iter().enumerate() {
+                if hetero_chain.id() == chain.id() {
+                    write_hetero_residue(writer, hetero_chain, residue, options)?;
+                    written[index] = true;
+                }
+            }
         }
     }
-    writer
-        .write_fmt(format_args!(
-            "MASTER    {:5}{:5}{:5}{:5}{:5}{:5}{:5}{:5}{:5}{:5}{:5}{:5}\n",
-            pdb.remark_count(),
-            0, //defined to be empty
-            0, //numHet
-            0, //numHelix
-            0, //numSheet
-            0, //numTurn (deprecated)
-            0, //numSite
-            xform,
-            pdb.total_atom_count(),
-            pdb.model_count(),
-            0, //numConnect
-            0, //numSeq
-        ))
-        .unwrap();
-    writer.write_fmt(format_args!("END\n")).unwrap();
+    for ((chain, residue), _) in hetero_residues
+        .iter()
+        .zip(written)
+        .filter(|(_, written)| !written)
+    {
+        write_hetero_residue(writer, chain, residue, options)?;
+    }
+    Ok(())
+}
 
-    writer.flush().unwrap();
+/// Write the HETATM (and ANISOU) records of the given Hetero Residue, followed by a TER record
+/// if the TerPolicy of the options asks for it.
+fn write_hetero_residue(
+    writer: &mut impl Write,
+    chain: &Chain,
+    residue: &Residue,
+    options: &SaveOptions,
+) -> std::io::Result<()> {
+    for atom in residue.atoms() {
+        write_atom(writer, chain, residue, atom, options)?;
+    }
+    if options.ter_policy() == TerPolicy::AfterMolecules && residue.atom_count() > 0 {
+        let last_atom = residue.atoms().nth_back(0).unwrap();
+        write_ter_record(writer, chain, residue, last_atom, options)?;
+    }
     Ok(())
 }
+
+/// Write the ATOM or HETATM record of the given Atom, depending on `Atom::hetero`, followed by its
+/// ANISOU record if it has anisotropic temperature factors.
+fn write_atom(
+    writer: &mut impl Write,
+    chain: &Chain,
+    residue: &Residue,
+    atom: &Atom,
+    options: &SaveOptions,
+) -> std::io::Result<()> {
+    let policy = options.serial_overflow_policy();
+    let decimals = options.coordinate_decimals();
+    let atom_serial = policy.format(atom.serial_number(), 5).unwrap();
+    let residue_serial = policy.format(residue.serial_number(), 4).unwrap();
+    let residue_name = residue.conformer_name(atom.alternate_location());
+    let insertion_code = residue.insertion_code().unwrap_or(' ');
+    // The element and charge columns, left out completely if neither is written
+    let (element, charge) = (
+        if options.write_elements() {
+            element(atom, residue, options.guess_missing_elements())
+        } else {
+            String::new()
+        },
+        if options.write_charges() {
+            atom.pdb_charge()
+        } else {
+            String::new()
+        },
+    );
+    // The numeric fields, using the original text for unchanged values if preferred
+    let source_text = atom.source_text().filter(|_| options.prefer_source_text());
+    let field = |index: usize, value: f64, width: usize, precision: usize| {
+        source_text
+            .map(|text| text[index])
+            .filter(|text| text.trim().parse::<f64>() == Ok(value))
+            .map_or_else(|| format_fixed(value, width, precision), str::to_string)
+    };
+    let segment_id = if options.write_segment_ids() {
+        atom.segment_id()
+    } else {
+        ""
+    };
+    // The columns after the last numeric field, starting with the given number of spaces before
+    // the segment id, trailing spaces are removed when writing
+    let tail = |spaces: usize| {
+        if options.write_elements() || options.write_charges() || !segment_id.is_empty() {
+            format!(
+                "{}{:<4}{:>2}{}",
+                " ".repeat(spaces),
+                segment_id,
+                element,
+                charge
+            )
+        } else {
+            String::new()
+        }
+    };
+
+    writer.write_fmt(format_args!(
+        "{}{} {:^4}{}{:4}{}{}{}   {}{}{}{}{}{}\n",
+        if atom.hetero() { "HETATM" } else { "ATOM  " },
+        atom_serial,
+        atom.name(),
+        atom.alternate_location(),
+        residue_name,
+        chain.id(),
+        residue_serial,
+        insertion_code,
+        field(0, atom.x(), 8, decimals),
+        field(1, atom.y(), 8, decimals),
+        field(2, atom.z(), 8, decimals),
+        field(3, atom.occupancy(), 6, 2),
+        field(4, atom.b_factor(), 6, 2),
+        tail(6),
+    ))?;
+    if let Some(u) = atom.anisotropic_u() {
+        let factor = |index: usize| (u[index] * 10000.0).round() as isize;
+        writer.write_fmt(format_args!(
+            "ANISOU{} {:^4}{}{:4}{}{}{} {:7}{:7}{:7}{:7}{:7}{:7}{}\n",
+            atom_serial,
+            atom.name(),
+            atom.alternate_location(),
+            residue_name,
+            chain.id(),
+            residue_serial,
+            insertion_code,
+            factor(0),
+            factor(1),
+            factor(2),
+            factor(3),
+            factor(4),
+            factor(5),
+            tail(2),
+        ))?;
+    }
+    Ok(())
+}
+
+/// Get the element to write for the given Atom, guessing it if it is missing and asked to do so,
+/// see `guess_element`
+fn element(atom: &Atom, residue: &Residue, guess: bool) -> String {
+    let element = atom.element();
+    if !element.is_empty() || !guess {
+        element
+    } else {
+        guess_element(atom, residue)
+    }
+}
+
+/// Guess the element of the given Atom. The element of an Atom in an ion Residue is its residue
+/// name, of an Atom in an unknown ligand (UNL or UNX) it is `X`, otherwise it is the first letter
+/// of its name.
+pub(crate) fn guess_element(atom: &Atom, residue: &Residue) -> String {
+    if reference_tables::is_unknown_ligand(&residue.id()) {
+        "X".to_string()
+    } else if reference_tables::is_ion_residue(&residue.id()) && residue.id().len() <= 2 {
+        residue.id()
+    } else {
+        atom.name()
+            .chars()
+            .find(|c| c.is_ascii_alphabetic())
+            .map_or_else(String::new, |c| c.to_ascii_uppercase().to_string())
+    }
+}
+
+/// Write the TER record terminating the given Normal Chain
+fn write_ter(writer: &mut impl Write, chain: &Chain, options: &SaveOptions) -> std::io::Result<()> {
+    let last_atom = chain.atoms().nth_back(0).unwrap();
+    let last_residue = chain.residues().nth_back(0).unwrap();
+    write_ter_record(writer, chain, last_residue, last_atom, options)
+}
+
+/// Write a TER record after the given Atom, the last of the given Residue.
+/// The TER record consumes the serial number following the last Atom, if that cannot be written
+/// the serial number is left blank.
+fn write_ter_record(
+    writer: &mut impl Write,
+    chain: &Chain,
+    residue: &Residue,
+    last_atom: &Atom,
+    options: &SaveOptions,
+) -> std::io::Result<()> {
+    let policy = options.serial_overflow_policy();
+    writer.write_fmt(format_args!(
+        "TER   {}      {:3} {}{}{}\n",
+        policy
+            .format(last_atom.serial_number() + 1, 5)
+            .unwrap_or_else(|| " ".repeat(5)),
+        residue.id(),
+        chain.id(),
+        policy.format(residue.serial_number(), 4).unwrap(),
+        residue.insertion_code().unwrap_or(' ')
+    ))
+}
+
+/// A writer that formats the lines written to it as asked by the `SaveOptions`: trailing
+/// whitespace is removed, the lines are padded with spaces if `SaveOptions::pad_lines` is set and
+/// ended with `\r\n` if `SaveOptions::crlf_line_endings` is set. Lines are only written to the
+/// inner writer once they are complete.
+struct FormattedLines<W: Write> {
+    /// The output the formatted lines are written to
+    inner: W,
+    /// The width to pad the lines to, no padding if `None`
+    width: Option<usize>,
+    /// The line ending to use
+    line_ending: &'static [u8],
+    /// The current line, not yet written
+    line: Vec<u8>,
+    /// The number of lines written so far
+    lines: usize,
+    /// The token to cancel writing with
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl<W: Write> FormattedLines<W> {
+    /// Create a new writer formatting the lines as asked by the options
+    fn new(inner: W, options: &SaveOptions) -> Self {
+        FormattedLines {
+            inner,
+            width: if options.pad_lines() { Some(80) } else { None },
+            line_ending: if options.crlf_line_endings() {
+                b"\r\n"
+            } else {
+                b"\n"
+            },
+            line: Vec::with_capacity(82),
+            lines: 0,
+            cancellation_token: options.cancellation_token().cloned(),
+        }
+    }
+
+    /// Write the current line to the inner writer.
+    /// ## Fails
+    /// It fails if the cancellation token is cancelled, the error then wraps the `PDBError` to give.
+    fn write_line(&mut self) -> std::io::Result<()> {
+        if should_cancel(self.cancellation_token.as_ref(), self.lines) {
+            return Err(std::io::Error::other(cancelled_error(
+                "Saving",
+                self.lines,
+                PDBContext::none(),
+            )));
+        }
+        self.lines += 1;
+        let length = self
+            .line
+            .iter()
+            .rposition(|byte| !byte.is_ascii_whitespace())
+            .map_or(0, |index| index + 1);
+        self.inner.write_all(&self.line[..length])?;
+        if let Some(width) = self.width {
+            // Count characters, not the continuation bytes of multi byte characters
+            let column = self.line[..length]
+                .iter()
+                .filter(|byte| **byte & 0xC0 != 0x80)
+                .count();
+            self.inner
+                .write_all(" ".repeat(width.saturating_sub(column)).as_bytes())?;
+        }
+        self.inner.write_all(self.line_ending)?;
+        self.line.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for FormattedLines<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for (index, part) in buf.split(|byte| *byte == b'\n').enumerate() {
+            if index > 0 {
+                self.write_line()?;
+            }
+            self.line.extend_from_slice(part);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Get the Normal Chains and the Hetero Residues (with their Chain) of the given Model in the order
+/// they should be written. In canonical order the Normal Chains are sorted alphabetically, the
+/// Hetero Chains follow the Normal Chain with the same id (the others come last, alphabetically)
+/// and all ligands are written before all waters. Otherwise the order of the Model is kept.
+fn chain_order(model: &Model, canonical: bool) -> (Vec<&Chain>, Vec<(&Chain, &Residue)>) {
+    let mut chains = model.chains().collect::<Vec<_>>();
+    let mut hetero_chains = model.hetero_chains().collect::<Vec<_>>();
+    if canonical {
+        chains.sort_by_key(|chain| chain.id());
+        hetero_chains.sort_by_key(|hetero| {
+            (
+                chains
+                    .iter()
+                    .position(|chain| chain.id() == hetero.id())
+                    .unwrap_or(chains.len()),
+                hetero.id(),
+            )
+        });
+    }
+    let hetero_residues = hetero_chains
+        .iter()
+        .flat_map(|chain| chain.residues().map(move |residue| (*chain, residue)));
+    let hetero_residues = if canonical {
+        let (waters, ligands): (Vec<_>, Vec<_>) = hetero_residues
+            .partition(|(_, residue)| reference_tables::is_water_residue(&residue.id()));
+        ligands.into_iter().chain(waters).collect()
+    } else {
+        hetero_residues.collect()
+    };
+    (chains, hetero_residues)
+}
+
+/// Format a number in a fixed width field with the given number of decimals, as done by the
+/// conventional (Fortran based) PDB writers. The number is rounded half away from zero based on
+/// its shortest decimal representation (so 0.0005 rounds up to 0.001, while the binary value is
+/// slightly below 0.0005), a negative number rounding to zero is written without sign, and the
+/// result is right aligned in the given width. Numbers not fitting in the width are not truncated.
+/// This is also used to round in memory (see `PDB::round_coordinates`) so both give the same values.
+pub(crate) fn format_fixed(value: f64, width: usize, precision: usize) -> String {
+    if !value.is_finite() {
+        return format!("{:>width$}", value, width = width);
+    }
+    // The Display implementation of f64 gives the shortest representation without exponent
+    let text = value.abs().to_string();
+    let (integer, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    let mut digits = integer
+        .bytes()
+        .chain(
+            fraction
+                .bytes()
+                .chain(std::iter::repeat(b'0'))
+                .take(precision),
+        )
+        .collect::<Vec<u8>>();
+    if fraction
+        .as_bytes()
+        .get(precision)
+        .is_some_and(|d| *d >= b'5')
+    {
+        // Round up, propagating the carry
+        let mut index = digits.len();
+        loop {
+            if index == 0 {
+                digits.insert(0, b'1');
+                break;
+            }
+            index -= 1;
+            if digits[index] == b'9' {
+                digits[index] = b'0';
+            } else {
+                digits[index] += 1;
+                break;
+            }
+        }
+    }
+    let negative = value < 0.0 && digits.iter().any(|d| *d != b'0');
+    let split = digits.len() - precision;
+    let mut output = String::with_capacity(width);
+    if negative {
+        output.push('-');
+    }
+    output.push_str(std::str::from_utf8(&digits[..split]).unwrap());
+    if precision > 0 {
+        output.push('.');
+        output.push_str(std::str::from_utf8(&digits[split..]).unwrap());
+    }
+    format!("{:>width$}", output, width = width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_fixed;
+
+    #[test]
+    fn fixed_width_numbers() {
+        let cases: &[(f64, usize, usize, &str)] = &[
+            (0.0, 8, 3, "   0.000"),
+            (-0.0, 8, 3, "   0.000"),
+            (-0.0004, 8, 3, "   0.000"),
+            (-0.0005, 8, 3, "  -0.001"),
+            (0.0005, 8, 3, "   0.001"),
+            (1.2345, 8, 3, "   1.235"),
+            (-1.2345, 8, 3, "  -1.235"),
+            (2.675, 6, 2, "  2.68"),
+            (999.9995, 8, 3, "1000.000"),
+            (999.9994, 8, 3, " 999.999"),
+            (-999.9994, 8, 3, "-999.999"),
+            (-999.9995, 8, 3, "-1000.000"),
+            (9999.999, 8, 3, "9999.999"),
+            (12.5, 6, 2, " 12.50"),
+            (100.0, 6, 2, "100.00"),
+            (0.995, 6, 2, "  1.00"),
+            (-0.004, 6, 2, "  0.00"),
+            (1e-7, 8, 3, "   0.000"),
+            (45.6789, 7, 2, "  45.68"),
+            (1234.5, 9, 3, " 1234.500"),
+            (7.5, 4, 0, "   8"),
+        ];
+        for (value, width, precision, expected) in cases {
+            assert_eq!(
+                format_fixed(*value, *width, *precision),
+                *expected,
+                "{} {}.{}",
+                value,
+                width,
+                precision
+            );
+        }
+    }
+}
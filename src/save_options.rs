@@ -0,0 +1,309 @@
+use crate::cancellation::CancellationToken;
+use crate::error::ErrorLevel;
+use crate::serial_overflow::SerialOverflowPolicy;
+
+/// Where TER records are written when saving, see `SaveOptions::set_ter_policy`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TerPolicy {
+    /// After every Normal Chain, as defined by the PDB format
+    AfterChains,
+    /// After every Normal Chain and after every Hetero Residue (ligands, ions and waters), so every
+    /// molecule is terminated, as expected by AMBER tleap
+    AfterMolecules,
+}
+
+/// The flavour of PDB file expected by a specific kind of downstream program, used to configure
+/// `SaveOptions` in one call with `SaveOptions::preset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// As distributed by the wwPDB: all lines padded to 80 characters, with element and charge columns
+    Wwpdb,
+    /// For AMBER tleap: a TER record after every molecule, including every water, and no charge
+    /// columns as the charges are assigned by the force field
+    Amber,
+    /// For GROMACS pdb2gmx: the element column filled for every Atom (guessed if missing), the
+    /// Hetero Residues written directly after the Normal Chain with the same id so every chain id
+    /// is used in one block, and no charge columns
+    Gromacs,
+    /// Only the columns up to and including the B-factor, for old programs that cannot handle the
+    /// segment id, element and charge columns
+    Plain,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The options to use when saving a PDB file, see `save_with_options`
+pub struct SaveOptions {
+    /// The policy used to write serial numbers that do not fit in the PDB format
+    serial_overflow_policy: SerialOverflowPolicy,
+    /// If the Chains should be written in canonical order
+    canonical_chain_order: bool,
+    /// The number of decimals of the coordinates
+    coordinate_decimals: usize,
+    /// If the original text of unchanged numeric fields of Atoms should be written
+    prefer_source_text: bool,
+    /// If all lines should be padded with spaces to 80 characters
+    pad_lines: bool,
+    /// Where TER records are written
+    ter_policy: TerPolicy,
+    /// If the element column should be written
+    write_elements: bool,
+    /// If missing elements should be guessed when writing the element column
+    guess_missing_elements: bool,
+    /// If the charge column should be written
+    write_charges: bool,
+    /// If the segment id column should be written
+    write_segment_ids: bool,
+    /// If the Hetero Residues should be written directly after the Normal Chain with the same id
+    interleave_hetero: bool,
+    /// If the lines should end with `\r\n` instead of `\n`
+    crlf_line_endings: bool,
+    /// If the saved file should be parsed again to check that it reads back cleanly
+    verify: bool,
+    /// The lowest level of the errors found when verifying that makes saving fail
+    verify_level: ErrorLevel,
+    /// The token to cancel saving with
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        SaveOptions {
+            serial_overflow_policy: SerialOverflowPolicy::Hybrid36,
+            canonical_chain_order: false,
+            coordinate_decimals: 3,
+            prefer_source_text: false,
+            pad_lines: false,
+            ter_policy: TerPolicy::AfterChains,
+            write_elements: true,
+            guess_missing_elements: false,
+            write_charges: true,
+            write_segment_ids: true,
+            interleave_hetero: false,
+            crlf_line_endings: false,
+            verify: false,
+            verify_level: ErrorLevel::StrictWarning,
+            cancellation_token: None,
+        }
+    }
+}
+
+impl SaveOptions {
+    /// Create new options, writing serial numbers using hybrid-36, the Chains in their current order,
+    /// the coordinates with 3 decimals, TER records after every Normal Chain, the segment id, element
+    /// and charge columns as they are, all Hetero Residues after the Normal Chains and lines without padding,
+    /// ending with `\n`, without verifying the saved file
+    pub fn new() -> SaveOptions {
+        SaveOptions::default()
+    }
+
+    /// Create new options for the given dialect, see `Dialect` for what each dialect changes.
+    /// Everything else is as in `new`.
+    pub fn preset(dialect: Dialect) -> SaveOptions {
+        let mut options = SaveOptions::default();
+        match dialect {
+            Dialect::Wwpdb => {
+                options.pad_lines = true;
+            }
+            Dialect::Amber => {
+                options.ter_policy = TerPolicy::AfterMolecules;
+                options.write_charges = false;
+            }
+            Dialect::Gromacs => {
+                options.guess_missing_elements = true;
+                options.write_charges = false;
+                options.interleave_hetero = true;
+            }
+            Dialect::Plain => {
+                options.write_elements = false;
+                options.write_charges = false;
+                options.write_segment_ids = false;
+            }
+        }
+        options
+    }
+
+    /// Get the policy used to write serial numbers that do not fit in the PDB format
+    pub fn serial_overflow_policy(&self) -> SerialOverflowPolicy {
+        self.serial_overflow_policy
+    }
+
+    /// Set the policy used to write serial numbers that do not fit in the PDB format
+    pub fn set_serial_overflow_policy(&mut self, policy: SerialOverflowPolicy) {
+        self.serial_overflow_policy = policy;
+    }
+
+    /// Get if the Chains are written in canonical order
+    pub fn canonical_chain_order(&self) -> bool {
+        self.canonical_chain_order
+    }
+
+    /// Set if the Chains should be written in canonical order, as used by deposited files: the
+    /// Normal Chains alphabetically, then the ligands of the Hetero Chains and finally their waters.
+    /// The Hetero Chains follow the order of the Normal Chains with the same id, the Hetero Chains
+    /// without such a Normal Chain come last, alphabetically. The structure itself is not changed,
+    /// see `Model::sort_chains` to do that.
+    pub fn set_canonical_chain_order(&mut self, canonical: bool) {
+        self.canonical_chain_order = canonical;
+    }
+
+    /// Get the number of decimals the coordinates are written with
+    pub fn coordinate_decimals(&self) -> usize {
+        self.coordinate_decimals
+    }
+
+    /// Set the number of decimals the coordinates are written with, the PDB format uses 3. This is
+    /// used for the positions in ATOM and HETATM records and for the cell lengths in the CRYST1
+    /// record, the fields with a precision of 3 decimals. The other floating point fields have a
+    /// different precision in the format and are not changed. See `PDB::round_coordinates` to
+    /// round the positions in memory in the same way, for example to make files diff-stable.
+    /// ## Fails
+    /// It fails if the number of decimals is not 1, 2 or 3.
+    pub fn set_coordinate_decimals(&mut self, decimals: usize) -> Result<(), String> {
+        if (1..=3).contains(&decimals) {
+            self.coordinate_decimals = decimals;
+            Ok(())
+        } else {
+            Err(format!(
+                "The coordinates can be written with 1 to 3 decimals, not {}",
+                decimals
+            ))
+        }
+    }
+
+    /// Get if the original text of unchanged numeric fields of Atoms is written
+    pub fn prefer_source_text(&self) -> bool {
+        self.prefer_source_text
+    }
+
+    /// Set if the original text of the coordinates, occupancy and B-factor of Atoms should be
+    /// written instead of their value, for the fields with a value that is unchanged since parsing
+    /// (see `Atom::source_text`, only kept with `ReadOptions::set_keep_source_text`). This gives
+    /// byte-identical fields for untouched Atoms, including the number of decimals of the
+    /// original, while changed fields are formatted as usual. This is off by default.
+    pub fn set_prefer_source_text(&mut self, prefer: bool) {
+        self.prefer_source_text = prefer;
+    }
+
+    /// Get if all lines are padded with spaces to 80 characters
+    pub fn pad_lines(&self) -> bool {
+        self.pad_lines
+    }
+
+    /// Set if all lines should be padded with spaces to 80 characters, as some older programs
+    /// read the records as fixed length lines. Otherwise no line ends in whitespace.
+    pub fn set_pad_lines(&mut self, pad: bool) {
+        self.pad_lines = pad;
+    }
+
+    /// Get where TER records are written
+    pub fn ter_policy(&self) -> TerPolicy {
+        self.ter_policy
+    }
+
+    /// Set where TER records should be written, see `TerPolicy`
+    pub fn set_ter_policy(&mut self, policy: TerPolicy) {
+        self.ter_policy = policy;
+    }
+
+    /// Get if the element column is written
+    pub fn write_elements(&self) -> bool {
+        self.write_elements
+    }
+
+    /// Set if the element column should be written
+    pub fn set_write_elements(&mut self, write: bool) {
+        self.write_elements = write;
+    }
+
+    /// Get if missing elements are guessed when writing the element column
+    pub fn guess_missing_elements(&self) -> bool {
+        self.guess_missing_elements
+    }
+
+    /// Set if missing elements should be guessed when writing the element column. The element of
+    /// an Atom in an ion Residue (see `EntityType::Ion`) is taken from the residue name, of an Atom
+    /// in an unknown ligand (UNL or UNX) it is `X`, otherwise it is the first letter of the atom
+    /// name. The structure itself is not changed.
+    pub fn set_guess_missing_elements(&mut self, guess: bool) {
+        self.guess_missing_elements = guess;
+    }
+
+    /// Get if the charge column is written
+    pub fn write_charges(&self) -> bool {
+        self.write_charges
+    }
+
+    /// Set if the charge column should be written
+    pub fn set_write_charges(&mut self, write: bool) {
+        self.write_charges = write;
+    }
+
+    /// Get if the segment id column is written
+    pub fn write_segment_ids(&self) -> bool {
+        self.write_segment_ids
+    }
+
+    /// Set if the segment id column (columns 73-76) should be written, see `Atom::segment_id`
+    pub fn set_write_segment_ids(&mut self, write: bool) {
+        self.write_segment_ids = write;
+    }
+
+    /// Get if the Hetero Residues are written directly after the Normal Chain with the same id
+    pub fn interleave_hetero(&self) -> bool {
+        self.interleave_hetero
+    }
+
+    /// Set if the Hetero Residues should be written directly after the Normal Chain with the same
+    /// id, instead of after all Normal Chains. Hetero Residues without such a Normal Chain are
+    /// still written after all Normal Chains.
+    pub fn set_interleave_hetero(&mut self, interleave: bool) {
+        self.interleave_hetero = interleave;
+    }
+
+    /// Get if the lines end with `\r\n` instead of `\n`
+    pub fn crlf_line_endings(&self) -> bool {
+        self.crlf_line_endings
+    }
+
+    /// Set if the lines should end with `\r\n` (Windows line endings) instead of `\n`
+    pub fn set_crlf_line_endings(&mut self, crlf: bool) {
+        self.crlf_line_endings = crlf;
+    }
+    /// Get if the saved file is parsed again to check that it reads back cleanly
+    pub fn verify(&self) -> bool {
+        self.verify
+    }
+
+    /// Set if the saved file should be parsed again to check that it reads back cleanly. Saving
+    /// then fails if the file cannot be parsed, if it does not contain the same number of Models
+    /// and Atoms, or if parsing gives errors at or above the verify level (see
+    /// `set_verify_level`). This catches problems of the writer, like overflowing fields, when
+    /// writing instead of when the file is used. It is meant for debugging as it doubles the time
+    /// needed to save.
+    pub fn set_verify(&mut self, verify: bool) {
+        self.verify = verify;
+    }
+
+    /// Get the lowest level of the errors found when verifying that makes saving fail
+    pub fn verify_level(&self) -> ErrorLevel {
+        self.verify_level
+    }
+
+    /// Set the lowest level of the errors found when verifying that makes saving fail, the
+    /// default is `ErrorLevel::StrictWarning`. See `set_verify`.
+    pub fn set_verify_level(&mut self, level: ErrorLevel) {
+        self.verify_level = level;
+    }
+
+    /// Get the token used to cancel saving, if set
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+
+    /// Set the token to cancel saving with, see `CancellationToken`. When the token is cancelled
+    /// saving stops within a thousand lines and fails, the partially written file is left as is.
+    /// `PdbWriter` fails with an error for which `PDBError::is_cancellation` is `true`.
+    pub fn set_cancellation_token(&mut self, token: Option<CancellationToken>) {
+        self.cancellation_token = token;
+    }
+}
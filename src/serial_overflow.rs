@@ -0,0 +1,145 @@
+/// The policy defining what happens when serial numbers do not fit in the fixed width columns of
+/// the PDB format anymore. Atom serial numbers have five columns (max 99,999) and Residue serial
+/// numbers four columns (max 9,999). This mostly happens when expanding (assembly) structures.
+///
+/// The policy is used by the operations assigning serial numbers (`PDB::renumber_with_policy`,
+/// `PDB::join_with_policy`, `PDB::expand_mtrix` and `PDB::build_assembly`) and by `save_with_policy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SerialOverflowPolicy {
+    /// Fail with an error identifying the operation and the serial number reached
+    Error,
+    /// Write the serial numbers using the hybrid-36 encoding, which extends the range of atom
+    /// serial numbers to 87,440,031 and of Residue serial numbers to 2,436,111
+    Hybrid36,
+    /// Wrap the serial numbers back to 1 after the maximum, and warn about this. Serial numbers
+    /// stay unique within each Model, operations that would create more Atoms in a single Model
+    /// than can be numbered uniquely place the extra Atoms in new Models or fail.
+    WrapWithWarning,
+}
+
+impl SerialOverflowPolicy {
+    /// Format the given serial number in the given number of columns according to this policy.
+    /// Returns `None` if the number cannot be formatted, which is always the case for the
+    /// `Error` policy when the number is too big.
+    pub(crate) fn format(&self, number: usize, width: usize) -> Option<String> {
+        match self {
+            SerialOverflowPolicy::Error => {
+                if number <= max_decimal(width) {
+                    Some(format!("{:>width$}", number, width = width))
+                } else {
+                    None
+                }
+            }
+            SerialOverflowPolicy::Hybrid36 => hybrid36_encode(number, width),
+            SerialOverflowPolicy::WrapWithWarning => {
+                Some(format!("{:>width$}", wrap(number, width), width = width))
+            }
+        }
+    }
+}
+
+/// The highest number that can be written in decimal in the given number of columns
+pub(crate) fn max_decimal(width: usize) -> usize {
+    10_usize.pow(width as u32) - 1
+}
+
+/// Wrap the number into the range 1..=max_decimal(width)
+pub(crate) fn wrap(number: usize, width: usize) -> usize {
+    if number == 0 {
+        0
+    } else {
+        (number - 1) % max_decimal(width) + 1
+    }
+}
+
+/// Encode the number in hybrid-36 in the given number of columns. Returns `None` if the number is too big.
+/// See Grosse-Kunstleve, Adams, Sauter. 'Hybrid 36' encoding of PDB serial numbers.
+pub(crate) fn hybrid36_encode(number: usize, width: usize) -> Option<String> {
+    if number <= max_decimal(width) {
+        return Some(format!("{:>width$}", number, width = width));
+    }
+    let block = 26 * 36_usize.pow(width as u32 - 1);
+    let offset = 10 * 36_usize.pow(width as u32 - 1);
+    let number = number - max_decimal(width) - 1;
+    if number < block {
+        Some(base36(number + offset, width, b'A'))
+    } else if number < 2 * block {
+        Some(base36(number - block + offset, width, b'a'))
+    } else {
+        None
+    }
+}
+
+/// Decode a number in hybrid-36 in the given number of columns. Returns `None` if the text is not valid.
+pub(crate) fn hybrid36_decode(text: &str, width: usize) -> Option<usize> {
+    let trimmed = text.trim();
+    let first = trimmed.chars().next()?;
+    if first.is_ascii_digit() {
+        return trimmed.parse().ok();
+    }
+    if text.chars().count() != width || !text.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    let upper = first.is_ascii_uppercase();
+    if text
+        .chars()
+        .any(|c| c.is_ascii_alphabetic() && c.is_ascii_uppercase() != upper)
+    {
+        return None;
+    }
+    let value = usize::from_str_radix(&text.to_ascii_lowercase(), 36).ok()?;
+    let block = 26 * 36_usize.pow(width as u32 - 1);
+    let offset = 10 * 36_usize.pow(width as u32 - 1);
+    Some(value - offset + max_decimal(width) + 1 + if upper { 0 } else { block })
+}
+
+fn base36(mut number: usize, width: usize, letter_start: u8) -> String {
+    let mut digits = vec![b'0'; width];
+    for digit in digits.iter_mut().rev() {
+        let value = (number % 36) as u8;
+        *digit = if value < 10 {
+            b'0' + value
+        } else {
+            letter_start + value - 10
+        };
+        number /= 36;
+    }
+    String::from_utf8(digits).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hybrid36() {
+        assert_eq!(hybrid36_encode(99_999, 5).unwrap(), "99999");
+        assert_eq!(hybrid36_encode(100_000, 5).unwrap(), "A0000");
+        assert_eq!(hybrid36_encode(10_000, 4).unwrap(), "A000");
+        assert_eq!(hybrid36_encode(87_440_031, 5).unwrap(), "zzzzz");
+        assert!(hybrid36_encode(87_440_032, 5).is_none());
+        for number in &[1, 9_999, 10_000, 43_670_015, 43_670_016, 87_440_031] {
+            let encoded = hybrid36_encode(*number, 5).unwrap();
+            assert_eq!(hybrid36_decode(&encoded, 5), Some(*number));
+        }
+        assert_eq!(hybrid36_decode("   42", 5), Some(42));
+        assert_eq!(hybrid36_decode("A0a00", 5), None);
+    }
+
+    #[test]
+    fn policies() {
+        assert_eq!(SerialOverflowPolicy::Error.format(100_000, 5), None);
+        assert_eq!(
+            SerialOverflowPolicy::Error.format(12, 5),
+            Some("   12".to_string())
+        );
+        assert_eq!(
+            SerialOverflowPolicy::WrapWithWarning.format(100_001, 5),
+            Some("    2".to_string())
+        );
+        assert_eq!(
+            SerialOverflowPolicy::Hybrid36.format(100_001, 5),
+            Some("A0001".to_string())
+        );
+    }
+}
@@ -0,0 +1,69 @@
+#![allow(dead_code)]
+use crate::structs::*;
+use crate::transformation::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A biological assembly (biomolecule) as defined in REMARK 350: the transformations that have to
+/// be applied to the given Chains to generate the biologically relevant molecule. Add it to a PDB
+/// with `PDB::add_assembly` to write it when saving, and build it with `PDB::build_assembly`.
+pub struct Assembly {
+    /// The serial number of the biomolecule
+    serial_number: usize,
+    /// The ids of the Chains the transformations are applied to
+    chains: Vec<char>,
+    /// The transformations (BIOMT), the identity transformation included
+    transformations: Vec<TransformationMatrix>,
+}
+
+impl Assembly {
+    /// Create a new Assembly
+    ///
+    /// ## Arguments
+    /// * `serial_number` - the serial number of the biomolecule
+    /// * `chains` - the ids of the Chains the transformations are applied to
+    /// * `transformations` - the transformations, include the identity transformation to keep the Chains themselves
+    ///
+    /// ## Fails
+    /// It fails if there are no Chains or no transformations, or if a chain id is an invalid
+    /// character (only ASCII graphic and space is allowed).
+    pub fn new(
+        serial_number: usize,
+        chains: &[char],
+        transformations: Vec<TransformationMatrix>,
+    ) -> Option<Assembly> {
+        if chains.is_empty() || transformations.is_empty() || !chains.iter().all(|c| check_char(*c))
+        {
+            return None;
+        }
+        Some(Assembly {
+            serial_number,
+            chains: chains.to_vec(),
+            transformations,
+        })
+    }
+
+    /// The serial number of the biomolecule
+    pub fn serial_number(&self) -> usize {
+        self.serial_number
+    }
+
+    /// Set the serial number of the biomolecule
+    pub fn set_serial_number(&mut self, new_number: usize) {
+        self.serial_number = new_number;
+    }
+
+    /// The ids of the Chains the transformations are applied to
+    pub fn chains(&self) -> &[char] {
+        &self.chains
+    }
+
+    /// The transformations, in the order of the BIOMT records
+    pub fn transformations(&self) -> &[TransformationMatrix] {
+        &self.transformations
+    }
+
+    /// Add a transformation
+    pub fn add_transformation(&mut self, transformation: TransformationMatrix) {
+        self.transformations.push(transformation);
+    }
+}
@@ -4,13 +4,13 @@ use crate::structs::*;
 use crate::transformation::*;
 use std::fmt;
 
-/// A struct to represent a single Atom in a protein
+/// A struct to represent a single Atom in a protein. The text fields are stored as ASCII bytes and
+/// the rarely used fields (the anisotropic temperature factors and the source line) are boxed, so
+/// an Atom takes 64 bytes instead of 160, which matters when holding many large structures.
 #[derive(Debug)]
 pub struct Atom {
     /// The serial number of the Atom, should be unique within its model
     serial_number: usize,
-    /// The name of the Atom, can only be four chars, can only use the standard allowed characters
-    name: [char; 4],
     /// The X position of the Atom (Å)
     x: f64,
     /// The Y position of the Atom (Å)
@@ -21,16 +21,45 @@ pub struct Atom {
     occupancy: f64,
     /// The B-factor (or temperature factor) of the Atom
     b_factor: f64,
+    /// The rarely used fields, `None` if none of them are set
+    details: Option<Box<AtomDetails>>,
+    /// The name of the Atom, can only be four chars, can only use the standard allowed characters
+    name: [u8; 4],
     /// The element of the Atom, can only be two chars, can only use the standard allowed characters
-    element: [char; 2],
+    element: [u8; 2],
     /// The charge of the Atom
-    charge: isize,
-    /// The anisotropic temperature factors, if applicable
-    atf: Option<[[f64; 3]; 2]>,
+    charge: i8,
+    /// The alternate location identifier of the Atom, ' ' if there are no alternate locations. As
+    /// the identifier is ASCII its highest bit is free and marks an Atom from a HETATM record (see
+    /// `HETERO_FLAG`), keeping the Atom at 64 bytes.
+    alternate_location: u8,
+}
+
+/// The bit of the alternate location byte of an Atom set for an Atom from a HETATM record
+const HETERO_FLAG: u8 = 0x80;
+
+/// The original text of the x, y and z fields (8 columns each) and the occupancy and B-factor
+/// fields (6 columns each) of the record an Atom was parsed from
+type SourceText = ([[u8; 8]; 3], [[u8; 6]; 2]);
+
+/// The rarely used fields of an Atom, boxed to keep the Atom small
+#[derive(Debug, Clone, Default, PartialEq)]
+struct AtomDetails {
+    /// The anisotropic temperature factors as U11, U22, U33, U12, U13, U23, if applicable
+    anisotropic_u: Option<[f64; 6]>,
+    /// The line number of the record this Atom was parsed from, if kept while parsing
+    source_line: Option<usize>,
+    /// The original text of the numeric fields of the record this Atom was parsed from, if kept while parsing
+    source_text: Option<SourceText>,
+    /// The segment identifier (left justified, padded with spaces), if set
+    segment_id: Option<[u8; 4]>,
 }
 
 impl Atom {
     /// Create a new Atom
+    /// ## Fails
+    /// It fails if the name or element contains invalid characters (only ASCII graphic and space is
+    /// allowed) or if the charge is not in -9..=9.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         serial_number: usize,
@@ -43,23 +72,22 @@ impl Atom {
         element: [char; 2],
         charge: isize,
     ) -> Option<Atom> {
-        let atom = Atom {
-            serial_number,
-            name: atom_name,
-            x,
-            y,
-            z,
-            occupancy,
-            b_factor,
-            element,
-            charge,
-            atf: None,
-        };
-
-        if !check_char4(atom_name) || !check_char2(element) {
+        if !check_char4(atom_name) || !check_char2(element) || !(-9..=9).contains(&charge) {
             None
         } else {
-            Some(atom)
+            Some(Atom {
+                serial_number,
+                x,
+                y,
+                z,
+                occupancy,
+                b_factor,
+                details: None,
+                name: atom_name.map(|c| c as u8),
+                element: element.map(|c| c as u8),
+                charge: charge as i8,
+                alternate_location: b' ',
+            })
         }
     }
 
@@ -166,6 +194,7 @@ impl Atom {
     pub fn name(&self) -> String {
         self.name
             .iter()
+            .map(|c| *c as char)
             .collect::<String>()
             .split_whitespace()
             .collect::<String>()
@@ -180,14 +209,15 @@ impl Atom {
         let new_name = format!("{:^4}", new_name);
         let chars = new_name.to_ascii_uppercase().chars().collect::<Vec<char>>();
         if chars.len() < 5 {
-            if check_chars(new_name.to_string()) {
-                self.name = [chars[0], chars[1], chars[2], chars[3]];
-                Ok(())
-            } else {
-                Err(format!(
-                    "New name has invalid characters for atom {} name {}",
-                    self.serial_number, new_name
-                ))
+            match describe_invalid_chars(&new_name, check_char) {
+                None => {
+                    self.name = [chars[0], chars[1], chars[2], chars[3]].map(|c| c as u8);
+                    Ok(())
+                }
+                Some(invalid) => Err(format!(
+                    "New name has invalid characters for atom {} name {}: {}",
+                    self.serial_number, new_name, invalid
+                )),
             }
         } else {
             Err(format!(
@@ -241,6 +271,7 @@ impl Atom {
     pub fn element(&self) -> String {
         self.element
             .iter()
+            .map(|c| *c as char)
             .collect::<String>()
             .split_whitespace()
             .collect::<String>()
@@ -250,7 +281,7 @@ impl Atom {
     /// ## Fails
     /// It fails when the element() or name() is not a valid element name.
     pub fn atomic_number(&self) -> Option<usize> {
-        if self.element != [' ', ' '] {
+        if self.element != [b' ', b' '] {
             reference_tables::get_atomic_number(&self.element())
         } else {
             reference_tables::get_atomic_number(&self.name())
@@ -260,17 +291,180 @@ impl Atom {
     /// Get the atomic radius of this Atom in Å. The radius is defined up to Cm.
     /// Source: Martin Rahm, Roald Hoffmann, and N. W. Ashcroft. Atomic and Ionic Radii of Elements 1-96.
     /// Chemistry - A European Journal, 22(41):14625–14632, oct 2016. URL:
-    /// http://doi.wiley.com/10.1002/chem.201602949, doi:10.1002/chem.201602949.
+    /// <http://doi.wiley.com/10.1002/chem.201602949>, doi:10.1002/chem.201602949.
     /// ## Fails
     /// It fails if the element name if this Atom is not defined (see `self.atomic_number()`).
     /// It also fails when the atomic radius is not defined for the given atomic number, so if the atomic
     /// number is higher than 96.
     pub fn atomic_radius(&self) -> Option<f64> {
-        if let Some(s) = self.atomic_number() {
-            reference_tables::get_atomic_radius(s)
+        self.atomic_number()
+            .and_then(reference_tables::get_atomic_radius)
+    }
+
+    /// Get the van der Waals radius of this Atom in Å, as defined by Bondi.
+    /// Source: A. Bondi. van der Waals Volumes and Radii. The Journal of Physical Chemistry,
+    /// 68(3):441–451, 1964. doi:10.1021/j100785a001.
+    /// ## Fails
+    /// It fails if the element name if this Atom is not defined (see `self.atomic_number()`).
+    /// It also fails when Bondi does not define a radius for this element.
+    pub fn vanderwaals_radius(&self) -> Option<f64> {
+        self.atomic_number()
+            .and_then(reference_tables::get_vanderwaals_radius)
+    }
+
+    /// Get the alternate location identifier of this Atom, ' ' if this Atom has no alternate locations
+    pub fn alternate_location(&self) -> char {
+        (self.alternate_location & !HETERO_FLAG) as char
+    }
+
+    /// Get if this Atom is a hetero atom, written as a HETATM record instead of an ATOM record.
+    /// It is set when parsing and by `Model::add_hetero_atom`, and stays with the Atom when it
+    /// is moved to another Chain.
+    pub fn hetero(&self) -> bool {
+        self.alternate_location & HETERO_FLAG != 0
+    }
+
+    /// Set if this Atom is a hetero atom, written as a HETATM record instead of an ATOM record
+    pub fn set_hetero(&mut self, hetero: bool) {
+        if hetero {
+            self.alternate_location |= HETERO_FLAG;
+        } else {
+            self.alternate_location &= !HETERO_FLAG;
+        }
+    }
+
+    /// Set the alternate location identifier of this Atom, use ' ' for no alternate location
+    /// ## Fails
+    /// It fails if the identifier is an invalid character (only ASCII graphic and space is allowed).
+    pub fn set_alternate_location(&mut self, new_location: char) -> Result<(), String> {
+        if check_char(new_location) {
+            self.alternate_location = new_location as u8 | (self.alternate_location & HETERO_FLAG);
+            Ok(())
         } else {
+            Err(format!(
+                "New alternate location has invalid characters for atom {} location {}",
+                self.serial_number, new_location
+            ))
+        }
+    }
+
+    /// Get the line number of the record this Atom was parsed from. This is only kept if asked for
+    /// while parsing (see `ReadOptions::set_keep_source_lines`), and is `None` for Atoms created otherwise.
+    pub fn source_line(&self) -> Option<usize> {
+        self.details
+            .as_ref()
+            .and_then(|details| details.source_line)
+    }
+
+    /// Set the line number of the record this Atom was parsed from
+    pub fn set_source_line(&mut self, line: Option<usize>) {
+        self.details_mut().source_line = line;
+        if self.details.as_deref() == Some(&AtomDetails::default()) {
+            self.details = None;
+        }
+    }
+
+    /// Get the original text of the x, y, z, occupancy and B-factor fields of the record this Atom
+    /// was parsed from, including the padding, like `[" -12.030", "   4.500", "  10.000", "  1.00", " 20.50"]`.
+    /// This is only kept if asked for while parsing (see `ReadOptions::set_keep_source_text`), and
+    /// is `None` for Atoms created otherwise. When saving, the text of a field is written instead
+    /// of the value if the value is unchanged (see `SaveOptions::set_prefer_source_text`).
+    pub fn source_text(&self) -> Option<[&str; 5]> {
+        fn text(bytes: &[u8]) -> &str {
+            std::str::from_utf8(bytes).expect("Source text is ASCII")
+        }
+        let (coordinates, factors) = self.details.as_ref()?.source_text.as_ref()?;
+        Some([
+            text(&coordinates[0]),
+            text(&coordinates[1]),
+            text(&coordinates[2]),
+            text(&factors[0]),
+            text(&factors[1]),
+        ])
+    }
+
+    /// Set the original text of the x, y, z, occupancy and B-factor fields, see `source_text`.
+    /// Shorter texts are padded with spaces on the left.
+    /// ## Fails
+    /// It fails if any of the texts contains invalid characters (only ASCII graphic and space is
+    /// allowed) or is too long, the max length is 8 characters for the coordinates and 6 for the
+    /// occupancy and B-factor.
+    pub fn set_source_text(&mut self, text: Option<[&str; 5]>) -> Result<(), String> {
+        let source_text = match text {
+            Some(text) => {
+                for (index, field) in text.iter().enumerate() {
+                    let width = if index < 3 { 8 } else { 6 };
+                    if field.len() > width || !check_chars(field.to_string()) {
+                        return Err(format!(
+                            "The source text \"{}\" is not valid for a field of {} characters for atom {}",
+                            field, width, self.serial_number
+                        ));
+                    }
+                }
+                let mut coordinates = [[b' '; 8]; 3];
+                let mut factors = [[b' '; 6]; 2];
+                for (index, field) in text.iter().enumerate() {
+                    let target: &mut [u8] = if index < 3 {
+                        &mut coordinates[index]
+                    } else {
+                        &mut factors[index - 3]
+                    };
+                    let start = target.len() - field.len();
+                    target[start..].copy_from_slice(field.as_bytes());
+                }
+                Some((coordinates, factors))
+            }
+            None => None,
+        };
+        self.details_mut().source_text = source_text;
+        if self.details.as_deref() == Some(&AtomDetails::default()) {
+            self.details = None;
+        }
+        Ok(())
+    }
+
+    /// Get the segment identifier of the Atom, used by CHARMM and NAMD to group Atoms into
+    /// segments (like a protein chain, a membrane or the solvent). The identifier is max 4
+    /// characters and is trimmed, it is empty if not set.
+    pub fn segment_id(&self) -> &str {
+        self.details
+            .as_ref()
+            .and_then(|details| details.segment_id.as_ref())
+            .map_or("", |id| {
+                std::str::from_utf8(id)
+                    .expect("Segment ids are ASCII")
+                    .trim()
+            })
+    }
+
+    /// Set the segment identifier of the Atom, see `segment_id`. An empty identifier removes it.
+    /// ## Fails
+    /// It fails if the identifier contains invalid characters (only ASCII graphic and space is
+    /// allowed) or is too long, the max length is 4 characters.
+    pub fn set_segment_id(&mut self, new_segment_id: &str) -> Result<(), String> {
+        let new_segment_id = new_segment_id.trim();
+        if new_segment_id.len() > 4 || !check_chars(new_segment_id.to_string()) {
+            return Err(format!(
+                "The segment id \"{}\" is not valid for atom {}, it should be at most 4 ASCII characters",
+                new_segment_id, self.serial_number
+            ));
+        }
+        self.details_mut().segment_id = if new_segment_id.is_empty() {
             None
+        } else {
+            let mut id = [b' '; 4];
+            id[..new_segment_id.len()].copy_from_slice(new_segment_id.as_bytes());
+            Some(id)
+        };
+        if self.details.as_deref() == Some(&AtomDetails::default()) {
+            self.details = None;
         }
+        Ok(())
+    }
+
+    /// Get the rarely used fields to change them, allocating them if needed
+    fn details_mut(&mut self) -> &mut AtomDetails {
+        self.details.get_or_insert_with(Box::default)
     }
 
     /// Set the element of this atom
@@ -285,7 +479,7 @@ impl Atom {
             .collect::<Vec<char>>();
         if chars.len() <= 2 {
             if check_chars(new_element.to_string()) {
-                self.element = [chars[0], chars[1]];
+                self.element = [chars[0] as u8, chars[1] as u8];
                 Ok(())
             } else {
                 Err(format!(
@@ -303,16 +497,16 @@ impl Atom {
 
     /// Get the charge of the atom
     pub fn charge(&self) -> isize {
-        self.charge
+        self.charge as isize
     }
 
-    /// Get the charge in the PDB format [0-9][-+]
+    /// Get the charge in the PDB format `[0-9][-+]`
     pub fn pdb_charge(&self) -> String {
         if self.charge == 0 {
             String::new()
         } else {
             let mut sign = '+';
-            let charge = (48 + self.charge.abs() as u8) as char;
+            let charge = (48 + self.charge.unsigned_abs()) as char;
             if self.charge < 0 {
                 sign = '-';
             }
@@ -328,36 +522,90 @@ impl Atom {
     /// It fails if the charge contains invalid characters (only ASCII graphic and space is allowed).
     /// It also fails if the string is too ling, the max length is 2 characters.
     pub fn set_charge(&mut self, new_charge: isize) -> Result<(), String> {
-        if new_charge < -9 || new_charge > 9 {
+        if !(-9..=9).contains(&new_charge) {
             Err(format!(
                 "New charge is out of bounds, for Atom {}, with new charge {}",
                 self.serial_number, new_charge
             ))
         } else {
-            self.charge = new_charge;
+            self.charge = new_charge as i8;
             Ok(())
         }
     }
 
-    /// Get the anisotropic temperature factors, if available
+    /// Get the anisotropic temperature factors, if available, as `[[U11, U22, U33], [U12, U13, U23]]`
+    /// in Å². See `anisotropic_u` for the same values in a flat array.
     pub fn anisotropic_temperature_factors(&self) -> Option<[[f64; 3]; 2]> {
-        self.atf
+        self.anisotropic_u()
+            .map(|[u11, u22, u33, u12, u13, u23]| [[u11, u22, u33], [u12, u13, u23]])
     }
 
-    /// Set the anisotropic temperature factors
+    /// Set the anisotropic temperature factors, given as `[[U11, U22, U33], [U12, U13, U23]]` in Å²
     pub fn set_anisotropic_temperature_factors(&mut self, factors: [[f64; 3]; 2]) {
-        self.atf = Some(factors);
+        let [[u11, u22, u33], [u12, u13, u23]] = factors;
+        self.set_anisotropic_u([u11, u22, u33, u12, u13, u23]);
+    }
+
+    /// Get the anisotropic temperature factors, if available, as the six independent elements of
+    /// the symmetric U tensor in Å², in the order of the ANISOU record: U11, U22, U33, U12, U13, U23
+    pub fn anisotropic_u(&self) -> Option<[f64; 6]> {
+        self.details
+            .as_ref()
+            .and_then(|details| details.anisotropic_u)
+    }
+
+    /// Set the anisotropic temperature factors, given as the six independent elements of the
+    /// symmetric U tensor in Å², in the order U11, U22, U33, U12, U13, U23
+    pub fn set_anisotropic_u(&mut self, u: [f64; 6]) {
+        self.details_mut().anisotropic_u = Some(u);
+    }
+
+    /// Get the anisotropic temperature factors, if available, as the full symmetric 3x3 U tensor
+    /// in Å², so `[[U11, U12, U13], [U12, U22, U23], [U13, U23, U33]]`
+    pub fn anisotropic_u_matrix(&self) -> Option<[[f64; 3]; 3]> {
+        self.anisotropic_u().map(|[u11, u22, u33, u12, u13, u23]| {
+            [[u11, u12, u13], [u12, u22, u23], [u13, u23, u33]]
+        })
+    }
+
+    /// Set the anisotropic temperature factors from the full 3x3 U tensor in Å². The tensor is made
+    /// symmetric by taking the mean of every pair of off-diagonal elements.
+    pub fn set_anisotropic_u_matrix(&mut self, u: [[f64; 3]; 3]) {
+        self.set_anisotropic_u([
+            u[0][0],
+            u[1][1],
+            u[2][2],
+            (u[0][1] + u[1][0]) / 2.0,
+            (u[0][2] + u[2][0]) / 2.0,
+            (u[1][2] + u[2][1]) / 2.0,
+        ]);
     }
 
     /// Get if this atom is likely to be a part of the backbone of a protein
     pub fn backbone(&self) -> bool {
-        let backbone_names = vec!["N", "CA", "C", "O"];
+        let backbone_names = ["N", "CA", "C", "O"];
         backbone_names.contains(&self.name().as_str())
     }
 
     /// Apply a transformation to the position of this atom, the new position is immediately set.
+    /// The anisotropic temperature factors, if available, are rotated along as R U Rᵀ, with R the
+    /// rotation (and scaling) part of the transformation.
     pub fn apply_transformation(&mut self, transformation: &TransformationMatrix) {
         self.set_pos(transformation.apply(self.pos())).unwrap();
+        if let Some(u) = self.anisotropic_u_matrix() {
+            let r = transformation.matrix();
+            let mut rotated = [[0.0; 3]; 3];
+            for (i, row) in rotated.iter_mut().enumerate() {
+                for (j, value) in row.iter_mut().enumerate() {
+                    for k in 0..3 {
+                        for l in 0..3 {
+                            *value += r[i][k] * u[k][l] * r[j][l];
+                        }
+                    }
+                }
+            }
+            self.set_anisotropic_u_matrix(rotated);
+        }
     }
 
     /// See if the `other` Atom corresponds with this Atom.
@@ -368,8 +616,50 @@ impl Atom {
             && self.name() == other.name()
             && self.element() == other.element()
             && self.charge() == other.charge()
-            && ((self.atf.is_none() && other.atf.is_none())
-                || (self.atf.is_some() && other.atf.is_some()))
+            && self.anisotropic_temperature_factors().is_some()
+                == other.anisotropic_temperature_factors().is_some()
+    }
+
+    /// Gives the distance between the centers of two atoms in Å.
+    pub fn distance(&self, other: &Atom) -> f64 {
+        ((other.x - self.x).powi(2) + (other.y - self.y).powi(2) + (other.z - self.z).powi(2))
+            .sqrt()
+    }
+
+    /// Gives the dihedral (torsion) angle in degrees defined by this Atom and the three given Atoms,
+    /// in the order self-second-third-fourth. The result is in the range (-180, 180].
+    pub fn dihedral(&self, second: &Atom, third: &Atom, fourth: &Atom) -> f64 {
+        let sub = |a: &Atom, b: &Atom| [a.x - b.x, a.y - b.y, a.z - b.z];
+        let cross = |a: [f64; 3], b: [f64; 3]| {
+            [
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ]
+        };
+        let dot = |a: [f64; 3], b: [f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+
+        let b1 = sub(second, self);
+        let b2 = sub(third, second);
+        let b3 = sub(fourth, third);
+        let n1 = cross(b1, b2);
+        let n2 = cross(b2, b3);
+        let b2_length = dot(b2, b2).sqrt();
+        let m1 = cross(
+            n1,
+            [b2[0] / b2_length, b2[1] / b2_length, b2[2] / b2_length],
+        );
+        -dot(m1, n2).atan2(dot(n1, n2)).to_degrees()
+    }
+
+    /// Gives the bond angle in degrees defined by this Atom and the two given Atoms, with the second
+    /// Atom at the vertex. The result is in the range [0, 180].
+    pub fn angle(&self, second: &Atom, third: &Atom) -> f64 {
+        let a = [self.x - second.x, self.y - second.y, self.z - second.z];
+        let b = [third.x - second.x, third.y - second.y, third.z - second.z];
+        let dot = a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+        let length = self.distance(second) * third.distance(second);
+        (dot / length).clamp(-1.0, 1.0).acos().to_degrees()
     }
 
     /// Checks if this Atom overlaps with the given atom. It overlaps if the sphere defined as sitting at
@@ -378,22 +668,15 @@ impl Atom {
     /// ## Fails
     /// It fails if any one of the two radii are not defined.
     pub fn overlaps(&self, other: &Atom) -> Option<bool> {
-        if let Some(self_rad) = self.atomic_radius() {
-            if let Some(other_rad) = other.atomic_radius() {
-                Some(
-                    self.x() + self_rad > other.x() - other_rad
-                        && self.x() - self_rad < other.x() + other_rad
-                        && self.y() + self_rad > other.y() - other_rad
-                        && self.y() - self_rad < other.y() + other_rad
-                        && self.z() + self_rad > other.z() - other_rad
-                        && self.z() - self_rad < other.z() + other_rad,
-                )
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+        let self_rad = self.atomic_radius()?;
+        other.atomic_radius().map(|other_rad| {
+            self.x() + self_rad > other.x() - other_rad
+                && self.x() - self_rad < other.x() + other_rad
+                && self.y() + self_rad > other.y() - other_rad
+                && self.y() - self_rad < other.y() + other_rad
+                && self.z() + self_rad > other.z() - other_rad
+                && self.z() - self_rad < other.z() + other_rad
+        })
     }
 }
 
@@ -410,29 +693,26 @@ impl fmt::Display for Atom {
             self.z(),
             self.occupancy(),
             self.b_factor(),
-            self.atf.is_some()
+            self.anisotropic_temperature_factors().is_some()
         )
     }
 }
 
 impl Clone for Atom {
     fn clone(&self) -> Self {
-        let mut atom = Atom::new(
-            self.serial_number,
-            self.name,
-            self.x,
-            self.y,
-            self.z,
-            self.occupancy,
-            self.b_factor,
-            self.element,
-            self.charge,
-        )
-        .unwrap();
-
-        atom.atf = self.atf;
-
-        atom
+        Atom {
+            serial_number: self.serial_number,
+            x: self.x,
+            y: self.y,
+            z: self.z,
+            occupancy: self.occupancy,
+            b_factor: self.b_factor,
+            details: self.details.clone(),
+            name: self.name,
+            element: self.element,
+            charge: self.charge,
+            alternate_location: self.alternate_location,
+        }
     }
 }
 
@@ -442,10 +722,12 @@ impl PartialEq for Atom {
             && self.name() == other.name()
             && self.element() == other.element()
             && self.charge() == other.charge()
-            && self.atf == other.atf
+            && self.anisotropic_temperature_factors() == other.anisotropic_temperature_factors()
             && self.pos() == other.pos()
             && self.occupancy == other.occupancy
             && self.b_factor == other.b_factor
+            && self.alternate_location == other.alternate_location
+            && self.segment_id() == other.segment_id()
     }
 }
 
@@ -453,6 +735,11 @@ impl PartialEq for Atom {
 mod tests {
     use super::Atom;
 
+    #[test]
+    fn size() {
+        assert!(std::mem::size_of::<Atom>() <= 64);
+    }
+
     #[test]
     fn set_name() {
         let mut a = Atom::new(
@@ -496,4 +783,20 @@ mod tests {
         a.set_element("R").unwrap();
         a.set_element("").unwrap();
     }
+
+    #[test]
+    fn dihedral() {
+        let atom =
+            |x, y, z| Atom::new(0, [' ', ' ', ' ', ' '], x, y, z, 0.0, 0.0, [' ', ' '], 0).unwrap();
+        let a = atom(0.0, 1.0, 0.0);
+        let b = atom(0.0, 0.0, 0.0);
+        let c = atom(1.0, 0.0, 0.0);
+        assert!((a.dihedral(&b, &c, &atom(1.0, 1.0, 0.0))).abs() < 1e-9);
+        assert!((a.dihedral(&b, &c, &atom(1.0, -1.0, 0.0)).abs() - 180.0).abs() < 1e-9);
+        assert!((a.dihedral(&b, &c, &atom(1.0, 0.0, 1.0)) - 90.0).abs() < 1e-9);
+        assert!((b.distance(&c) - 1.0).abs() < 1e-9);
+        assert!((a.angle(&b, &c) - 90.0).abs() < 1e-9);
+        assert!((c.angle(&b, &atom(-1.0, 0.0, 0.0)) - 180.0).abs() < 1e-9);
+        assert!((c.angle(&b, &atom(1.0, 1.0, 0.0)) - 45.0).abs() < 1e-9);
+    }
 }
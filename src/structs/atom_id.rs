@@ -0,0 +1,130 @@
+#![allow(dead_code)]
+use crate::structs::*;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An identifier of an Atom by its place in the hierarchy instead of by its values, so it can be
+/// used as a key in a HashMap, HashSet or BTreeMap. It consists of the serial number of the Model,
+/// the Residue (see `ResidueId`, so the chain id, serial number and insertion code), the name of
+/// the Atom and its alternate location. Identifiers are ordered in this same order, so sorting
+/// them gives the order of a PDB file.
+pub struct AtomId {
+    /// The serial number of the Model
+    model_serial_number: usize,
+    /// The Residue
+    residue: ResidueId,
+    /// The name of the Atom
+    name: String,
+    /// The alternate location of the Atom
+    alternate_location: char,
+}
+
+impl AtomId {
+    /// Create a new AtomId
+    ///
+    /// ## Arguments
+    /// * `model_serial_number` - the serial number of the Model
+    /// * `residue` - the Residue, with the id of its Chain
+    /// * `name` - the name of the Atom, surrounding whitespace is ignored
+    /// * `alternate_location` - the alternate location of the Atom, a space if there is none
+    pub fn new(
+        model_serial_number: usize,
+        residue: ResidueId,
+        name: &str,
+        alternate_location: char,
+    ) -> AtomId {
+        AtomId {
+            model_serial_number,
+            residue,
+            name: name.trim().to_string(),
+            alternate_location,
+        }
+    }
+
+    /// Create the AtomId of the given Atom, with the given Model, Chain and Residue containing it
+    pub fn from_hierarchy(model: &Model, chain: &Chain, residue: &Residue, atom: &Atom) -> AtomId {
+        AtomId::new(
+            model.serial_number(),
+            ResidueId::from_hierarchy(chain, residue),
+            &atom.name(),
+            atom.alternate_location(),
+        )
+    }
+
+    /// The serial number of the Model
+    pub fn model_serial_number(&self) -> usize {
+        self.model_serial_number
+    }
+
+    /// The Residue, with the id of its Chain
+    pub fn residue(&self) -> ResidueId {
+        self.residue
+    }
+
+    /// The id of the Chain
+    pub fn chain_id(&self) -> char {
+        self.residue.chain_id()
+    }
+
+    /// The serial number of the Residue
+    pub fn residue_serial_number(&self) -> usize {
+        self.residue.serial_number()
+    }
+
+    /// The insertion code of the Residue, if any
+    pub fn insertion_code(&self) -> Option<char> {
+        self.residue.insertion_code()
+    }
+
+    /// The name of the Atom
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The alternate location of the Atom, a space if there is none
+    pub fn alternate_location(&self) -> char {
+        self.alternate_location
+    }
+}
+
+impl fmt::Display for AtomId {
+    /// Formats as `model/chain/residue/name`, with the insertion code directly following the
+    /// residue serial number, followed by `` `altloc`` if there is an alternate location
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}",
+            self.model_serial_number, self.residue, self.name
+        )?;
+        if self.alternate_location != ' ' {
+            write!(f, "`{}", self.alternate_location)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AtomId;
+    use crate::structs::ResidueId;
+    use std::collections::HashSet;
+
+    #[test]
+    fn ordering_and_display() {
+        let a = AtomId::new(1, ('A', 10).into(), " CA ", ' ');
+        let b = AtomId::new(1, ('A', 10).into(), "CA", 'B');
+        let c = AtomId::new(1, ResidueId::new('A', 10, Some('A')), "N", ' ');
+        let d = AtomId::new(1, ('B', 2).into(), "N", ' ');
+        assert_eq!(a.name(), "CA");
+        assert!(a < b && b < c && c < d);
+        assert_eq!(format!("{}", a), "1/A/10/CA");
+        assert_eq!(format!("{}", b), "1/A/10/CA`B");
+        assert_eq!(format!("{}", c), "1/A/10A/N");
+        let set = [a.clone(), b, c, d, a]
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>();
+        assert_eq!(set.len(), 4);
+    }
+}
@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A warning about severe errors in an entry, given in the CAVEAT records. The text of all
+/// continuation lines is joined into one line.
+pub struct Caveat {
+    /// The PDB id code of the entry
+    id_code: String,
+    /// The description of the errors
+    text: String,
+}
+
+impl Caveat {
+    /// Create a new Caveat
+    ///
+    /// ## Arguments
+    /// * `id_code` - the PDB id code of the entry
+    /// * `text` - the description of the errors
+    ///
+    /// ## Fails
+    /// It fails if the id code is not four alphanumeric ASCII characters or if the text contains
+    /// invalid characters.
+    pub fn new(id_code: &str, text: &str) -> Option<Caveat> {
+        let id_code = id_code.trim();
+        if !check_id_code(id_code) || !check_chars(text.to_string()) {
+            return None;
+        }
+        Some(Caveat {
+            id_code: id_code.to_string(),
+            text: text.trim().to_string(),
+        })
+    }
+
+    /// The PDB id code of the entry
+    pub fn id_code(&self) -> &str {
+        &self.id_code
+    }
+
+    /// The description of the errors, like `THE CHIRALITY OF RESIDUE A 23 IS INCORRECT`
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
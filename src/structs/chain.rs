@@ -1,9 +1,16 @@
 #![allow(dead_code)]
+use crate::error::*;
+use crate::reference_tables;
+use crate::structs::local_geometry;
 use crate::structs::*;
 use crate::transformation::*;
 
+/// The backbone dihedral angles phi and psi in degrees with the residue name, see `Chain::phi_psi_pairs`
+type PhiPsiPair = (Option<f64>, Option<f64>, [char; 3]);
+
 #[derive(Debug)]
-/// A Chain containing multiple Residues
+/// A Chain containing multiple Residues, iterated in the order they are stored, see `Model` for
+/// the ordering of the Atoms
 pub struct Chain {
     /// The identifier of this Chain
     id: char,
@@ -18,9 +25,9 @@ impl Chain {
     /// * `id` - the identifier
     ///
     /// ## Fails
-    /// It fails if the identifier is an invalid character.
+    /// It fails if the identifier is an invalid character, only alphanumeric ASCII characters and space are allowed.
     pub fn new(id: char) -> Option<Chain> {
-        if !check_char(id) {
+        if !check_chain_id(id) {
             return None;
         }
         Some(Chain {
@@ -34,13 +41,19 @@ impl Chain {
         self.id
     }
 
-    /// Set the ID of the Chain, returns `false` if the new id is an invalid character.
-    pub fn set_id(&mut self, new_id: char) -> bool {
-        if check_char(new_id) {
-            self.id = new_id;
-            true
-        } else {
-            false
+    /// Set the ID of the Chain
+    /// ## Fails
+    /// It fails if the new id is an invalid character, only alphanumeric ASCII characters and space are allowed.
+    pub fn set_id(&mut self, new_id: char) -> Result<(), String> {
+        match describe_invalid_chars(&new_id.to_string(), check_chain_id) {
+            None => {
+                self.id = new_id;
+                Ok(())
+            }
+            Some(invalid) => Err(format!(
+                "New id for chain {} has the invalid character {}, only alphanumeric ASCII characters and space are allowed",
+                self.id, invalid
+            )),
         }
     }
 
@@ -122,27 +135,450 @@ impl Chain {
         self.residues.iter_mut().flat_map(|a| a.atoms_mut())
     }
 
-    /// Add a new Atom to this Chain. It finds if there already is a Residue with the given serial number if there is it will add this atom to that Residue, otherwise it will create a new Residue and add that to the list of Residues making up this Chain.
+    /// Get the type of molecular entity this Chain represents. This is the type of the majority of
+    /// its Residues (see `Residue::entity_type()`). Ties are broken by the order in which the variants
+    /// of `EntityType` are declared, so a chain with as many amino acids as waters is a Protein.
+    /// A small peptide ligand in a hetero chain is therefore also classified as a Protein.
+    /// An empty Chain is classified as `EntityType::Other`.
+    /// Use `entity_composition()` to get the full breakdown for mixed chains.
+    pub fn entity_type(&self) -> EntityType {
+        self.entity_composition()
+            .first()
+            .map_or(EntityType::Other, |(entity, _)| *entity)
+    }
+
+    /// Get the breakdown of the types of molecular entities of the Residues making up this Chain.
+    /// It returns the amount of Residues per type, sorted from most to least common, with ties
+    /// sorted in the order in which the variants of `EntityType` are declared.
+    pub fn entity_composition(&self) -> Vec<(EntityType, usize)> {
+        let mut composition: Vec<(EntityType, usize)> = Vec::new();
+        for residue in self.residues() {
+            let entity = residue.entity_type();
+            match composition.iter_mut().find(|(e, _)| *e == entity) {
+                Some((_, count)) => *count += 1,
+                None => composition.push((entity, 1)),
+            }
+        }
+        composition.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        composition
+    }
+
+    /// Returns `true` if this Chain is a CA-only model (a C-alpha trace), as is common for large
+    /// cryo-EM and predicted structures: it contains amino acids and all their Atoms are CA atoms.
+    /// Analyses that need the full backbone or all heavy atoms (like the backbone dihedrals) give
+    /// `AnalysisError::CaOnlyChain` for these Chains. See `PDB::ca_only_chains` to also include the
+    /// Chains marked as CA-only in the MDLTYP record.
+    pub fn is_ca_only(&self) -> bool {
+        let mut amino_acids = self
+            .residues()
+            .filter(|residue| residue.amino_acid())
+            .peekable();
+        amino_acids.peek().is_some()
+            && amino_acids.all(|residue| residue.atoms().all(|atom| atom.name() == "CA"))
+    }
+
+    /// Check that this Chain has more than CA atoms, for analyses that need the full backbone
+    /// ## Fails
+    /// It fails with `AnalysisError::CaOnlyChain` if this Chain is CA-only, see `is_ca_only`.
+    pub(crate) fn check_full_atoms(&self) -> Result<(), AnalysisError> {
+        if self.is_ca_only() {
+            Err(AnalysisError::CaOnlyChain(self.id))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Get the index of the first Residue numbered lower than the Residue before it, if any. The
+    /// Residues of a Chain are expected to be ordered by serial number, insertion codes aside, as
+    /// the torsion angles, the gap report and the placement of the TER record follow the stored
+    /// order. See `is_reversed`, `reverse_residues` and `sort_residues_by_number` to repair this.
+    pub fn first_unordered_residue(&self) -> Option<usize> {
+        self.residues
+            .windows(2)
+            .position(|pair| pair[1].serial_number() < pair[0].serial_number())
+            .map(|index| index + 1)
+    }
+
+    /// Get if the Residues of this Chain are stored in reverse, so numbered from high to low, as
+    /// happens when a script reversed the order of the atoms. This is the case if no Residue is
+    /// numbered higher than the Residue before it, and at least one is numbered lower.
+    pub fn is_reversed(&self) -> bool {
+        self.first_unordered_residue().is_some()
+            && self
+                .residues
+                .windows(2)
+                .all(|pair| pair[1].serial_number() <= pair[0].serial_number())
+    }
+
+    /// Reverse the order of the Residues of this Chain and of the atoms in every Residue, to
+    /// repair a Chain with its atoms stored in reverse (see `is_reversed`)
+    pub fn reverse_residues(&mut self) {
+        self.residues.reverse();
+        for residue in self.residues.iter_mut() {
+            residue.reverse_atoms();
+        }
+    }
+
+    /// Sort the Residues of this Chain by their numbering (see `Residue::numbering`), keeping
+    /// the order of Residues with the same numbering, to repair a Chain with some Residues out of
+    /// place (see `first_unordered_residue`). The order of the atoms in the Residues is kept.
+    pub fn sort_residues_by_number(&mut self) {
+        self.residues.sort_by_key(|residue| residue.numbering());
+    }
+
+    /// Check that the Residues of this Chain are ordered by serial number, for analyses that
+    /// follow the stored order
+    /// ## Fails
+    /// It fails with `AnalysisError::UnorderedResidues` for the first Residue numbered lower than
+    /// the Residue before it, see `first_unordered_residue`.
+    pub(crate) fn check_residue_order(&self) -> Result<(), AnalysisError> {
+        match self.first_unordered_residue() {
+            Some(index) => Err(AnalysisError::UnorderedResidues(
+                self.id,
+                self.residues[index].serial_number(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the omega (peptide bond) torsion angles for all pairs of consecutive Residues in this Chain.
+    /// The angle is defined by the atoms CA-C of the first Residue and N-CA of the second Residue.
+    /// It returns the serial numbers of both Residues and the angle in degrees. Pairs where any
+    /// of the atoms are missing or where the C-N distance is too long to be a peptide bond (> 2.0 Å)
+    /// are skipped.
+    fn omega_angles(&self) -> Vec<(&Residue, &Residue, f64)> {
+        let mut output = Vec::new();
+        for pair in self.residues.windows(2) {
+            let (previous, current) = (&pair[0], &pair[1]);
+            if let (Some(ca1), Some(c1), Some(n2), Some(ca2)) = (
+                previous.atom_by_name("CA"),
+                previous.atom_by_name("C"),
+                current.atom_by_name("N"),
+                current.atom_by_name("CA"),
+            ) {
+                if c1.distance(n2) <= 2.0 {
+                    output.push((previous, current, ca1.dihedral(c1, n2, ca2)));
+                }
+            }
+        }
+        output
+    }
+
+    /// Get the backbone dihedral angles (phi, psi, omega) in degrees for every Residue in this Chain,
+    /// in the same order as `residues()`. An angle is `None` when any of the needed atoms is missing
+    /// or when the neighbouring Residue is not bonded (C-N distance above 2.0 Å).
+    pub(crate) fn backbone_dihedrals(&self) -> Vec<(Option<f64>, Option<f64>, Option<f64>)> {
+        fn atoms(residue: &Residue) -> (Option<&Atom>, Option<&Atom>, Option<&Atom>) {
+            (
+                residue.atom_by_name("N"),
+                residue.atom_by_name("CA"),
+                residue.atom_by_name("C"),
+            )
+        }
+        (0..self.residues.len())
+            .map(|index| {
+                let current = &self.residues[index];
+                let (n, ca, c) = atoms(current);
+                let previous = index
+                    .checked_sub(1)
+                    .map(|i| &self.residues[i])
+                    .filter(|previous| peptide_bonded(previous, current));
+                let next = self
+                    .residues
+                    .get(index + 1)
+                    .filter(|next| peptide_bonded(current, next));
+                let phi = previous
+                    .and_then(|previous| Some(previous.atom_by_name("C")?.dihedral(n?, ca?, c?)));
+                let psi = next.and_then(|next| Some(n?.dihedral(ca?, c?, next.atom_by_name("N")?)));
+                let omega = previous.and_then(|previous| {
+                    let (_, previous_ca, previous_c) = atoms(previous);
+                    Some(previous_ca?.dihedral(previous_c?, n?, ca?))
+                });
+                (phi, psi, omega)
+            })
+            .collect()
+    }
+
+    /// Get the local geometry score of every amino acid Residue in this Chain with its N, CA and C
+    /// atoms present, as (residue serial number, score) in the same order as `residues()`. The score
+    /// combines the deviations of the backbone bond lengths and angles from their ideal values with
+    /// the position in the Ramachandran plot into a single Z-score, where 0.0 is ideal geometry and
+    /// values above 2.0 flag problematic Residues. See `Model::local_geometry_score`.
+    /// ## Fails
+    /// It fails with `AnalysisError::CaOnlyChain` if this Chain is CA-only, see `is_ca_only`, and
+    /// with `AnalysisError::UnorderedResidues` if the Residues are not ordered by serial number,
+    /// see `first_unordered_residue`.
+    pub fn local_geometry_profile(&self) -> Result<Vec<(usize, f64)>, AnalysisError> {
+        self.check_full_atoms()?;
+        self.check_residue_order()?;
+        let dihedrals = self.backbone_dihedrals();
+        Ok((0..self.residues.len())
+            .filter_map(|index| {
+                let current = &self.residues[index];
+                let previous = index
+                    .checked_sub(1)
+                    .map(|i| &self.residues[i])
+                    .filter(|previous| peptide_bonded(previous, current));
+                let next = self
+                    .residues
+                    .get(index + 1)
+                    .filter(|next| peptide_bonded(current, next));
+                let phi_psi = match dihedrals[index] {
+                    (Some(phi), Some(psi), _) => Some((phi, psi)),
+                    _ => None,
+                };
+                let class = RamachandranClass::from_residue_names(
+                    &current.id(),
+                    next.map(|next| next.id()).as_deref(),
+                );
+                local_geometry::residue_score(previous, current, next, phi_psi, class)
+                    .map(|score| (current.serial_number(), score))
+            })
+            .collect())
+    }
+
+    /// Find the gaps in this Chain, based on jumps in the residue numbering and on physical chain
+    /// breaks (a C-N distance between consecutive Residues above 2.0 Å, or a CA-CA distance above
+    /// 4.2 Å if the C or N atom is missing, as in CA-only Chains). See `Gap` for the details
+    /// and `gap_report_with_missing_residues` to add the missing Residues from REMARK 465.
+    /// ## Fails
+    /// It fails with `AnalysisError::UnorderedResidues` if the Residues are not ordered by serial
+    /// number, see `first_unordered_residue`.
+    pub fn gap_report(&self) -> Result<Vec<Gap>, AnalysisError> {
+        self.gap_report_with_missing_residues(&[])
+    }
+
+    /// Find the gaps in this Chain, based on jumps in the residue numbering, on physical chain
+    /// breaks (a C-N distance between consecutive Residues above 2.0 Å, or a CA-CA distance above
+    /// 4.2 Å if the C or N atom is missing) and on the given serial
+    /// numbers of Residues known to be missing, for example from REMARK 465 (see `PDB::missing_residues`).
+    /// Missing Residues before the first or after the last Residue give gaps at the ends of the Chain.
+    ///
+    /// ## Arguments
+    /// * `missing` - the serial numbers of the Residues of this Chain missing from the coordinates
+    /// ## Fails
+    /// It fails with `AnalysisError::UnorderedResidues` if the Residues are not ordered by serial
+    /// number, see `first_unordered_residue`.
+    pub fn gap_report_with_missing_residues(
+        &self,
+        missing: &[usize],
+    ) -> Result<Vec<Gap>, AnalysisError> {
+        self.check_residue_order()?;
+        let mut gaps = Vec::new();
+        let (first, last) = match (self.residues.first(), self.residues.last()) {
+            (Some(first), Some(last)) => (first.serial_number(), last.serial_number()),
+            _ => return Ok(gaps),
+        };
+        let count =
+            |from: usize, to: usize| missing.iter().filter(|s| **s > from && **s < to).count();
+
+        let leading = missing.iter().filter(|s| **s < first).count();
+        if leading > 0 {
+            gaps.push(Gap::new(None, Some(first), leading, false, false, true));
+        }
+        for pair in self.residues.windows(2) {
+            let (previous, current) = (pair[0].serial_number(), pair[1].serial_number());
+            let numbering_jump = current > previous + 1;
+            let chain_break = match (pair[0].atom_by_name("C"), pair[1].atom_by_name("N")) {
+                (Some(c), Some(n)) => c.distance(n) > 2.0,
+                _ => match (pair[0].atom_by_name("CA"), pair[1].atom_by_name("CA")) {
+                    (Some(first), Some(second)) => first.distance(second) > 4.2,
+                    _ => false,
+                },
+            };
+            let listed = count(previous, current);
+            if numbering_jump || chain_break || listed > 0 {
+                let size = if listed > 0 {
+                    listed
+                } else if numbering_jump {
+                    current - previous - 1
+                } else {
+                    0
+                };
+                gaps.push(Gap::new(
+                    Some(previous),
+                    Some(current),
+                    size,
+                    numbering_jump,
+                    chain_break,
+                    listed > 0,
+                ));
+            }
+        }
+        let trailing = missing.iter().filter(|s| **s > last).count();
+        if trailing > 0 {
+            gaps.push(Gap::new(Some(last), None, trailing, false, false, true));
+        }
+        Ok(gaps)
+    }
+
+    /// Get the backbone dihedral angles phi and psi in degrees with the residue name for every
+    /// Residue in this Chain with at least one of the angles defined, for example to create a
+    /// Ramachandran plot. The angle phi is `None` for the first Residue of a Chain (or after a
+    /// chain break) and psi is `None` for the last Residue.
+    /// ## Fails
+    /// It fails with `AnalysisError::CaOnlyChain` if this Chain is CA-only, see `is_ca_only`, and
+    /// with `AnalysisError::UnorderedResidues` if the Residues are not ordered by serial number,
+    /// see `first_unordered_residue`.
+    pub fn phi_psi_pairs(&self) -> Result<Vec<PhiPsiPair>, AnalysisError> {
+        self.check_full_atoms()?;
+        self.check_residue_order()?;
+        Ok(self
+            .backbone_dihedrals()
+            .into_iter()
+            .zip(self.residues())
+            .filter(|((phi, psi, _), _)| phi.is_some() || psi.is_some())
+            .map(|((phi, psi, _), residue)| (phi, psi, residue.id_array()))
+            .collect())
+    }
+
+    /// Returns `true` if this Chain contains any non-standard Residues, see `nonstandard_residues`
+    pub fn has_nonstandard_residues(&self) -> bool {
+        self.nonstandard_residues().next().is_some()
+    }
+
+    /// Get the non-standard Residues in this Chain, so Residues that are not one of the 20 standard
+    /// amino acids or one of the common (deoxy)ribonucleotides (see `reference_tables`). Water and
+    /// the unknown amino acid (UNK) are not considered non-standard Residues.
+    pub fn nonstandard_residues(&self) -> impl DoubleEndedIterator<Item = &Residue> + '_ {
+        self.residues().filter(|residue| {
+            let name = residue.id();
+            !reference_tables::is_standard_residue(&name)
+                && !reference_tables::is_unknown_amino_acid(&name)
+                && !reference_tables::is_water_residue(&name)
+        })
+    }
+
+    /// Get the modified Residues in this Chain, so the non-standard Residues (see `nonstandard_residues`)
+    /// that are part of the polymer, for example selenomethionine (MSE) or phosphoserine (SEP).
+    /// As MODRES records are not parsed (yet) a Residue is considered part of the polymer if it
+    /// contains the backbone atoms of an amino acid (N, CA and C) or of a nucleotide (P, O5', C5').
+    /// In CA-only Chains (see `is_ca_only`) the CA atom is enough.
+    pub fn modified_residues(&self) -> impl DoubleEndedIterator<Item = &Residue> + '_ {
+        let ca_only = self.is_ca_only();
+        self.nonstandard_residues().filter(move |residue| {
+            let has = |names: &[&str]| {
+                names
+                    .iter()
+                    .all(|name| residue.atom_by_name(name).is_some())
+            };
+            has(&["N", "CA", "C"]) || has(&["P", "O5'", "C5'"]) || (ca_only && has(&["CA"]))
+        })
+    }
+
+    /// Find all cis peptide bonds in this Chain, regardless of residue type. A peptide bond is
+    /// considered cis when its omega angle deviates more than `tolerance_deg` from 180° (trans).
+    /// It returns the serial numbers of the two Residues making up each cis peptide bond.
+    ///
+    /// ## Arguments
+    /// * `tolerance_deg` - the maximal deviation from trans in degrees
+    /// ## Fails
+    /// It fails with `AnalysisError::CaOnlyChain` if this Chain is CA-only, see `is_ca_only`, and
+    /// with `AnalysisError::UnorderedResidues` if the Residues are not ordered by serial number,
+    /// see `first_unordered_residue`.
+    pub fn detect_all_cis_peptides(
+        &self,
+        tolerance_deg: f64,
+    ) -> Result<Vec<(usize, usize)>, AnalysisError> {
+        self.check_full_atoms()?;
+        self.check_residue_order()?;
+        Ok(self
+            .omega_angles()
+            .into_iter()
+            .filter(|(_, _, omega)| omega.abs() < 180.0 - tolerance_deg)
+            .map(|(previous, current, _)| (previous.serial_number(), current.serial_number()))
+            .collect())
+    }
+
+    /// Find all cis prolines in this Chain. A proline is cis when the omega angle of the peptide bond
+    /// preceding it falls outside of [150°, 210°], so more than 30° from trans.
+    /// It returns the serial numbers of the PRO Residues.
+    /// ## Fails
+    /// It fails with `AnalysisError::CaOnlyChain` if this Chain is CA-only, see `is_ca_only`, and
+    /// with `AnalysisError::UnorderedResidues` if the Residues are not ordered by serial number,
+    /// see `first_unordered_residue`.
+    pub fn detect_cis_prolines(&self) -> Result<Vec<usize>, AnalysisError> {
+        self.check_full_atoms()?;
+        self.check_residue_order()?;
+        Ok(self
+            .omega_angles()
+            .into_iter()
+            .filter(|(_, current, omega)| current.id() == "PRO" && omega.abs() < 150.0)
+            .map(|(_, current, _)| current.serial_number())
+            .collect())
+    }
+
+    /// Get the observed sequence of this Chain as one letter codes, so only the Residues present in
+    /// the coordinates. Residues that are not one of the 20 standard amino acids, like the unknown
+    /// amino acid (UNK), are written as `X`.
+    pub fn sequence(&self) -> String {
+        self.residues()
+            .map(|residue| reference_tables::get_one_letter_code(&residue.id()).unwrap_or('X'))
+            .collect()
+    }
+
+    /// Find a sequence motif in the observed sequence of this Chain (see `sequence`). It returns the
+    /// index of the first Residue of every match, to be used with `residue`, overlapping matches
+    /// included. The pattern consists of one letter codes, `X` matching any Residue, alternatives
+    /// in brackets (`[ST]`) and excluded Residues in brackets starting with a caret (`[^P]`). For
+    /// example `N[^P][ST]` finds N-glycosylation sequons. An invalid pattern gives no matches.
+    ///
+    /// ## Arguments
+    /// * `pattern` - the motif to search for, case insensitive
+    pub fn find_motif(&self, pattern: &str) -> Vec<usize> {
+        let motif = match parse_motif(pattern) {
+            Some(motif) if !motif.is_empty() => motif,
+            _ => return Vec::new(),
+        };
+        let sequence = self.sequence().chars().collect::<Vec<char>>();
+        sequence
+            .windows(motif.len())
+            .enumerate()
+            .filter(|(_, window)| {
+                window
+                    .iter()
+                    .zip(motif.iter())
+                    .all(|(residue, (exclude, codes))| codes.contains(residue) != *exclude)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Add a new Atom to this Chain. It finds if there already is a Residue with the given serial number and insertion code if there is it will add this atom to that Residue, otherwise it will create a new Residue and add that to the list of Residues making up this Chain.
+    /// If the Residue exists with another name and the Atom has an alternate location, the name is kept as the name of that conformer (see `Residue::conformer_name`).
+    /// The Atom is added after the Atoms of its Residue and a new Residue after all Residues, regardless of its serial number.
     ///
     /// ## Arguments
     /// * `new_atom` - the new Atom to add
     /// * `residue_serial_number` - the serial number of the Residue to add the Atom to
+    /// * `insertion_code` - the insertion code of the Residue to add the Atom to, if any
     /// * `residue_name` - the name of the Residue to add the Atom to, only used to create a new Residue if needed
     ///
     /// ## Panics
-    /// It panics if the Residue name contains any invalid characters.
+    /// It panics if the insertion code or Residue name contains any invalid characters, the message lists the offending characters.
     pub fn add_atom(
         &mut self,
         new_atom: Atom,
         residue_serial_number: usize,
+        insertion_code: Option<char>,
         residue_name: [char; 3],
     ) {
         let mut found = false;
         let mut new_residue = Residue::new(residue_serial_number, residue_name, None)
-            .expect("Invalid chars in Residue creation");
+            .unwrap_or_else(|| {
+                panic!(
+                    "Invalid residue name, it has the invalid characters {}",
+                    describe_invalid_chars(&residue_name.iter().collect::<String>(), check_char)
+                        .unwrap()
+                )
+            });
+        new_residue
+            .set_insertion_code(insertion_code)
+            .unwrap_or_else(|message| panic!("{}", message));
+        let numbering = new_residue.numbering();
         let mut current_residue = &mut new_residue;
         for residue in &mut self.residues {
-            if residue.serial_number() == residue_serial_number {
+            if residue.numbering() == numbering {
                 current_residue = residue;
                 found = true;
                 break;
@@ -151,17 +587,20 @@ impl Chain {
         if !found {
             self.residues.push(new_residue);
             current_residue = self.residues.last_mut().unwrap();
+        } else if current_residue.id_array() != residue_name {
+            // An alternative conformer of the whole Residue with another name
+            current_residue.set_conformer_name(new_atom.alternate_location(), residue_name);
         }
 
         current_residue.add_atom(new_atom);
     }
 
     /// Add a Residue to the list of Residues making up this Chain. This does not detect any duplicates of names or serial numbers in the list of Residues.
-    fn add_residue(&mut self, residue: Residue) {
+    pub(crate) fn add_residue(&mut self, residue: Residue) {
         self.residues.push(residue);
     }
 
-    /// Remove all Atoms matching the given predicate. As this is done in place this is the fastest way to remove Atoms from this Chain.
+    /// Remove all Atoms matching the given predicate. As this is done in place this is the fastest way to remove Atoms from this Chain. The remaining Atoms keep their order.
     pub fn remove_atoms_by<F>(&mut self, predicate: F)
     where
         F: Fn(&Atom) -> bool,
@@ -171,7 +610,26 @@ impl Chain {
         }
     }
 
-    /// Remove all residues matching the given predicate. As this is done in place this is the fastest way to remove Residues from this Chain.
+    /// Apply the given function to every Residue in this Chain, to edit the Residues in place,
+    /// for example to set the B-factors per Residue or to rename modified Residues.
+    pub fn map_residues_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut Residue),
+    {
+        for residue in self.residues.iter_mut() {
+            f(residue);
+        }
+    }
+
+    /// Get the Residues in this Chain with the given name, for example "ALA"
+    pub fn residues_by_name<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl DoubleEndedIterator<Item = &'a Residue> + 'a {
+        self.residues().filter(move |residue| residue.id() == name)
+    }
+
+    /// Remove all residues matching the given predicate. As this is done in place this is the fastest way to remove Residues from this Chain. The remaining Residues keep their order.
     pub fn remove_residues_by<F>(&mut self, predicate: F)
     where
         F: Fn(&Residue) -> bool,
@@ -181,6 +639,19 @@ impl Chain {
             .extend(residues.into_iter().filter(|residue| !predicate(residue)));
     }
 
+    /// Remove all Residues matching the given predicate and return them, in the order they were
+    /// in this Chain. See `remove_residues_by` to discard them instead.
+    pub fn drain_residues_by<F>(&mut self, predicate: F) -> Vec<Residue>
+    where
+        F: Fn(&Residue) -> bool,
+    {
+        let (removed, kept) = std::mem::take(&mut self.residues)
+            .into_iter()
+            .partition(|residue| predicate(residue));
+        self.residues = kept;
+        removed
+    }
+
     /// Remove the Residue specified.
     ///
     /// ## Arguments
@@ -235,12 +706,21 @@ impl Chain {
     }
 
     /// Join this Chain with another Chain, this moves all atoms from the other Chain
-    /// to this Chain. All other (meta) data of this Chain will stay the same.
+    /// to this Chain. All other (meta) data of this Chain will stay the same. The Residues of the
+    /// other Chain are added after the Residues of this Chain.
     pub fn join(&mut self, other: Chain) {
         self.residues.extend(other.residues);
     }
 }
 
+/// Check if the given Residues are connected by a peptide bond (C-N distance up to 2.0 Å)
+fn peptide_bonded(previous: &Residue, current: &Residue) -> bool {
+    match (previous.atom_by_name("C"), current.atom_by_name("N")) {
+        (Some(c), Some(n)) => c.distance(n) <= 2.0,
+        _ => false,
+    }
+}
+
 use std::fmt;
 impl fmt::Display for Chain {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -267,3 +747,187 @@ impl PartialEq for Chain {
         self.id() == other.id() && self.residues == other.residues
     }
 }
+
+/// Parse a sequence motif (see `Chain::find_motif`) into one position per Residue, each position
+/// a list of one letter codes and if these are excluded (`true`) or the only ones allowed (`false`).
+/// `X` is taken as excluding nothing. It returns `None` if the brackets do not match up.
+fn parse_motif(pattern: &str) -> Option<Vec<(bool, Vec<char>)>> {
+    let mut motif = Vec::new();
+    let mut chars = pattern.chars().map(|c| c.to_ascii_uppercase());
+    while let Some(c) = chars.next() {
+        match c {
+            'X' => motif.push((true, Vec::new())),
+            '[' => {
+                let mut codes = Vec::new();
+                let mut exclude = false;
+                loop {
+                    match chars.next()? {
+                        ']' => break,
+                        '^' if codes.is_empty() && !exclude => exclude = true,
+                        c if c.is_ascii_alphabetic() => codes.push(c),
+                        _ => return None,
+                    }
+                }
+                if codes.is_empty() {
+                    return None;
+                }
+                motif.push((exclude, codes));
+            }
+            c if c.is_ascii_alphabetic() => motif.push((false, vec![c])),
+            _ => return None,
+        }
+    }
+    Some(motif)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(name: [char; 4], pos: (f64, f64, f64)) -> Atom {
+        Atom::new(0, name, pos.0, pos.1, pos.2, 1.0, 0.0, [' ', ' '], 0).unwrap()
+    }
+
+    /// Create a chain of three residues, where the second peptide bond is cis if `cis` is set
+    fn chain(second: [char; 3], third: [char; 3], cis: bool) -> Chain {
+        let mut chain = Chain::new('A').unwrap();
+        chain.add_atom(
+            atom([' ', 'C', 'A', ' '], (-0.5, 1.2, 0.0)),
+            1,
+            None,
+            ['A', 'L', 'A'],
+        );
+        chain.add_atom(
+            atom([' ', 'C', ' ', ' '], (0.0, 0.0, 0.0)),
+            1,
+            None,
+            ['A', 'L', 'A'],
+        );
+        chain.add_atom(atom([' ', 'N', ' ', ' '], (1.3, 0.0, 0.0)), 2, None, second);
+        chain.add_atom(
+            atom([' ', 'C', 'A', ' '], (2.6, -2.4, 0.0)),
+            2,
+            None,
+            second,
+        );
+        chain.add_atom(
+            atom([' ', 'C', ' ', ' '], (3.1, -1.2, 0.0)),
+            2,
+            None,
+            second,
+        );
+        chain.add_atom(atom([' ', 'N', ' ', ' '], (4.4, -1.2, 0.0)), 3, None, third);
+        let y = if cis { -2.4 } else { 0.0 };
+        chain.add_atom(atom([' ', 'C', 'A', ' '], (4.9, y, 0.0)), 3, None, third);
+        chain
+    }
+
+    #[test]
+    fn cis_prolines() {
+        let trans = chain(['P', 'R', 'O'], ['P', 'R', 'O'], false);
+        assert!(trans.detect_cis_prolines().unwrap().is_empty());
+        assert!(trans.detect_all_cis_peptides(30.0).unwrap().is_empty());
+
+        let cis = chain(['A', 'L', 'A'], ['P', 'R', 'O'], true);
+        assert_eq!(cis.detect_cis_prolines(), Ok(vec![3]));
+        assert_eq!(cis.detect_all_cis_peptides(30.0), Ok(vec![(2, 3)]));
+
+        let cis_non_pro = chain(['A', 'L', 'A'], ['G', 'L', 'Y'], true);
+        assert!(cis_non_pro.detect_cis_prolines().unwrap().is_empty());
+        assert_eq!(cis_non_pro.detect_all_cis_peptides(30.0), Ok(vec![(2, 3)]));
+    }
+
+    #[test]
+    fn ca_only() {
+        let mut chain = chain(['P', 'R', 'O'], ['P', 'R', 'O'], false);
+        assert!(!chain.is_ca_only());
+        chain.remove_atoms_by(|atom| atom.name() != "CA");
+        assert!(chain.is_ca_only());
+        assert_eq!(
+            chain.detect_cis_prolines(),
+            Err(AnalysisError::CaOnlyChain('A'))
+        );
+        assert_eq!(
+            chain.detect_all_cis_peptides(30.0),
+            Err(AnalysisError::CaOnlyChain('A'))
+        );
+        assert!(!Chain::new('B').unwrap().is_ca_only());
+    }
+
+    #[test]
+    fn nonstandard_residues() {
+        let standard = chain(['G', 'L', 'Y'], ['T', 'R', 'P'], false);
+        assert!(!standard.has_nonstandard_residues());
+        assert_eq!(standard.modified_residues().count(), 0);
+
+        let mut modified = chain(['A', 'L', 'A'], ['M', 'S', 'E'], false);
+        modified.add_atom(
+            atom([' ', 'C', ' ', ' '], (5.4, -1.2, 0.0)),
+            3,
+            None,
+            ['M', 'S', 'E'],
+        );
+        modified.add_atom(
+            atom([' ', 'O', ' ', ' '], (0.0, 9.0, 0.0)),
+            4,
+            None,
+            ['H', 'O', 'H'],
+        );
+        modified.add_atom(
+            atom([' ', 'C', '1', ' '], (9.0, 9.0, 0.0)),
+            5,
+            None,
+            ['G', 'O', 'L'],
+        );
+        assert!(modified.has_nonstandard_residues());
+        assert_eq!(
+            modified
+                .nonstandard_residues()
+                .map(|r| r.id())
+                .collect::<Vec<String>>(),
+            vec!["MSE", "GOL"]
+        );
+        assert_eq!(
+            modified
+                .modified_residues()
+                .map(|r| r.serial_number())
+                .collect::<Vec<usize>>(),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn gap_report() {
+        let mut chain = chain(['A', 'L', 'A'], ['G', 'L', 'Y'], false);
+        assert!(chain.gap_report().unwrap().is_empty());
+
+        // A jump in the numbering without a physical break
+        chain.residue_mut(2).unwrap().set_serial_number(10);
+        assert_eq!(
+            chain.gap_report(),
+            Ok(vec![Gap::new(Some(2), Some(10), 7, true, false, false)])
+        );
+
+        // A physical break without a jump in the numbering
+        chain.residue_mut(2).unwrap().set_serial_number(3);
+        chain
+            .residue_mut(2)
+            .unwrap()
+            .apply_transformation(&TransformationMatrix::translation(5.0, 0.0, 0.0));
+        assert_eq!(
+            chain.gap_report_with_missing_residues(&[0, 4, 5]),
+            Ok(vec![
+                Gap::new(None, Some(1), 1, false, false, true),
+                Gap::new(Some(2), Some(3), 0, false, true, false),
+                Gap::new(Some(3), None, 2, false, false, true),
+            ])
+        );
+
+        // Residues out of order cannot be reported on
+        chain.residue_mut(2).unwrap().set_serial_number(1);
+        assert_eq!(
+            chain.gap_report(),
+            Err(AnalysisError::UnorderedResidues(chain.id(), 1))
+        );
+    }
+}
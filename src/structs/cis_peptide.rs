@@ -0,0 +1,156 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A CISPEP record, describing a cis peptide bond between two consecutive Residues. Every Residue
+/// is identified by its name, chain id, serial number and insertion code. As the conformation can
+/// differ between the Models of an NMR ensemble every record refers to a single Model, by its
+/// serial number, with `0` for files with a single Model (without MODEL records).
+pub struct CisPeptide {
+    /// The serial number of the record
+    serial_number: usize,
+    /// The names of the two Residues
+    residue_names: [String; 2],
+    /// The ids of the Chains containing the two Residues
+    chain_ids: [char; 2],
+    /// The serial numbers of the two Residues
+    residue_serial_numbers: [usize; 2],
+    /// The insertion codes of the two Residues
+    insertion_codes: [char; 2],
+    /// The serial number of the Model, 0 if there is a single Model
+    model_number: usize,
+    /// The measured omega angle of the peptide bond in degrees, if given
+    angle: Option<f64>,
+}
+
+impl CisPeptide {
+    /// Create a new CisPeptide record, without an angle
+    ///
+    /// ## Arguments
+    /// * `serial_number` - the serial number of the record
+    /// * `residue_names` - the names of the two Residues
+    /// * `chain_ids` - the ids of the Chains containing the two Residues
+    /// * `residue_serial_numbers` - the serial numbers of the two Residues
+    /// * `insertion_codes` - the insertion codes of the two Residues, a space if there is none
+    /// * `model_number` - the serial number of the Model, 0 if there is a single Model
+    ///
+    /// ## Fails
+    /// It fails if any of the names, chain ids or insertion codes contain invalid characters, or
+    /// if a name is longer than three characters.
+    pub fn new(
+        serial_number: usize,
+        residue_names: [&str; 2],
+        chain_ids: [char; 2],
+        residue_serial_numbers: [usize; 2],
+        insertion_codes: [char; 2],
+        model_number: usize,
+    ) -> Option<CisPeptide> {
+        let residue_names = residue_names.map(str::trim);
+        if residue_names.iter().any(|name| name.len() > 3)
+            || !check_chars(residue_names.concat())
+            || !chain_ids
+                .iter()
+                .chain(insertion_codes.iter())
+                .all(|c| check_char(*c))
+        {
+            return None;
+        }
+        Some(CisPeptide {
+            serial_number,
+            residue_names: residue_names.map(str::to_string),
+            chain_ids,
+            residue_serial_numbers,
+            insertion_codes,
+            model_number,
+            angle: None,
+        })
+    }
+
+    /// The serial number of the record
+    pub fn serial_number(&self) -> usize {
+        self.serial_number
+    }
+
+    /// Set the serial number of the record
+    pub fn set_serial_number(&mut self, new_number: usize) {
+        self.serial_number = new_number;
+    }
+
+    /// The names of the two Residues
+    pub fn residue_names(&self) -> [&str; 2] {
+        [&self.residue_names[0], &self.residue_names[1]]
+    }
+
+    /// The ids of the Chains containing the two Residues
+    pub fn chain_ids(&self) -> [char; 2] {
+        self.chain_ids
+    }
+
+    /// The serial numbers of the two Residues
+    pub fn residue_serial_numbers(&self) -> [usize; 2] {
+        self.residue_serial_numbers
+    }
+
+    /// Set the chain ids and serial numbers of the two Residues, for example after renumbering
+    /// ## Fails
+    /// It fails if any of the chain ids is an invalid character.
+    pub fn set_residues(
+        &mut self,
+        chain_ids: [char; 2],
+        residue_serial_numbers: [usize; 2],
+    ) -> Result<(), String> {
+        if let Some(c) = chain_ids.iter().find(|c| !check_char(**c)) {
+            return Err(format!("The chain id '{}' is not a valid character", c));
+        }
+        self.chain_ids = chain_ids;
+        self.residue_serial_numbers = residue_serial_numbers;
+        Ok(())
+    }
+
+    /// The insertion codes of the two Residues, a space if there is none
+    pub fn insertion_codes(&self) -> [char; 2] {
+        self.insertion_codes
+    }
+
+    /// Set the insertion codes of the two Residues, for example after renumbering
+    /// ## Fails
+    /// It fails if any of the insertion codes is an invalid character.
+    pub fn set_insertion_codes(&mut self, insertion_codes: [char; 2]) -> Result<(), String> {
+        if let Some(c) = insertion_codes.iter().find(|c| !check_char(**c)) {
+            return Err(format!(
+                "The insertion code '{}' is not a valid character",
+                c
+            ));
+        }
+        self.insertion_codes = insertion_codes;
+        Ok(())
+    }
+
+    /// The serial number of the Model, 0 if there is a single Model (without MODEL records)
+    pub fn model_number(&self) -> usize {
+        self.model_number
+    }
+
+    /// Set the serial number of the Model, see `model_number`
+    pub fn set_model_number(&mut self, model_number: usize) {
+        self.model_number = model_number;
+    }
+
+    /// The measured omega angle of the peptide bond in degrees, if given
+    pub fn angle(&self) -> Option<f64> {
+        self.angle
+    }
+
+    /// Set the measured omega angle of the peptide bond in degrees
+    pub fn set_angle(&mut self, angle: Option<f64>) {
+        self.angle = angle;
+    }
+
+    /// Returns `true` if the given Residue is one of the two Residues of this cis peptide
+    pub fn contains(&self, chain_id: char, residue_serial_number: usize) -> bool {
+        (0..2).any(|index| {
+            self.chain_ids[index] == chain_id
+                && self.residue_serial_numbers[index] == residue_serial_number
+        })
+    }
+}
@@ -0,0 +1,234 @@
+#![allow(dead_code)]
+use crate::structs::*;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The composition of a single chain id in a Model, see `PDB::composition`. The Residues of the
+/// Normal Chain (ATOM records) are counted as polymer Residues, the Residues of the Hetero Chain
+/// (HETATM records) as waters or as ligands.
+pub struct ChainComposition {
+    /// The chain id
+    id: char,
+    /// The number of Residues in the Normal Chain
+    polymer_residues: usize,
+    /// The number of water Residues in the Hetero Chain
+    waters: usize,
+    /// The number of other Residues in the Hetero Chain
+    ligand_residues: usize,
+    /// The number of Atoms in the Normal Chain
+    atoms: usize,
+    /// The number of Atoms in the Hetero Chain
+    hetero_atoms: usize,
+    /// The number of Atoms per element, `?` for Atoms without an element
+    elements: BTreeMap<String, usize>,
+}
+
+impl ChainComposition {
+    /// Create an empty composition for the given chain id
+    fn new(id: char) -> ChainComposition {
+        ChainComposition {
+            id,
+            polymer_residues: 0,
+            waters: 0,
+            ligand_residues: 0,
+            atoms: 0,
+            hetero_atoms: 0,
+            elements: BTreeMap::new(),
+        }
+    }
+
+    /// Add the Residues and Atoms of the given Chain
+    fn add_chain(&mut self, chain: &Chain, hetero: bool) {
+        for residue in chain.residues() {
+            if !hetero {
+                self.polymer_residues += 1;
+            } else if residue.entity_type() == EntityType::Water {
+                self.waters += 1;
+            } else {
+                self.ligand_residues += 1;
+            }
+            for atom in residue.atoms() {
+                if hetero {
+                    self.hetero_atoms += 1;
+                } else {
+                    self.atoms += 1;
+                }
+                let element = atom.element();
+                let element = if element.is_empty() {
+                    "?".to_string()
+                } else {
+                    element
+                };
+                *self.elements.entry(element).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Add all counts of the other composition to this one
+    fn add(&mut self, other: &ChainComposition) {
+        self.polymer_residues += other.polymer_residues;
+        self.waters += other.waters;
+        self.ligand_residues += other.ligand_residues;
+        self.atoms += other.atoms;
+        self.hetero_atoms += other.hetero_atoms;
+        for (element, count) in &other.elements {
+            *self.elements.entry(element.clone()).or_insert(0) += count;
+        }
+    }
+
+    /// The chain id
+    pub fn id(&self) -> char {
+        self.id
+    }
+
+    /// The number of polymer Residues, the Residues of the Normal Chain
+    pub fn polymer_residue_count(&self) -> usize {
+        self.polymer_residues
+    }
+
+    /// The number of water Residues in the Hetero Chain
+    pub fn water_count(&self) -> usize {
+        self.waters
+    }
+
+    /// The number of ligand Residues, the Residues of the Hetero Chain that are not water
+    pub fn ligand_residue_count(&self) -> usize {
+        self.ligand_residues
+    }
+
+    /// The number of Atoms in the Normal Chain (ATOM records)
+    pub fn atom_count(&self) -> usize {
+        self.atoms
+    }
+
+    /// The number of Atoms in the Hetero Chain (HETATM records)
+    pub fn hetero_atom_count(&self) -> usize {
+        self.hetero_atoms
+    }
+
+    /// The number of Atoms per element, sorted by element. Atoms without an element are counted as `?`.
+    pub fn element_counts(&self) -> &BTreeMap<String, usize> {
+        &self.elements
+    }
+
+    /// Write this composition as a row of the table of `Composition`
+    fn write_row(&self, f: &mut fmt::Formatter, label: &str) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<6}{:>8}{:>7}{:>8}{:>8}{:>8}  {}",
+            label,
+            self.polymer_residues,
+            self.waters,
+            self.ligand_residues,
+            self.atoms,
+            self.hetero_atoms,
+            self.elements
+                .iter()
+                .map(|(element, count)| format!("{} {}", element, count))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The composition of a Model per chain id, see `PDB::composition`
+pub struct ModelComposition {
+    /// The serial number of the Model
+    serial_number: usize,
+    /// The composition per chain id, in the order of the Chains in the Model
+    chains: Vec<ChainComposition>,
+    /// The sum of the compositions of all chain ids
+    total: ChainComposition,
+}
+
+impl ModelComposition {
+    /// The serial number of the Model
+    pub fn serial_number(&self) -> usize {
+        self.serial_number
+    }
+
+    /// The composition per chain id, in the order of the Normal Chains with the chain ids only used
+    /// by Hetero Chains after them
+    pub fn chains(&self) -> &[ChainComposition] {
+        &self.chains
+    }
+
+    /// The composition of the given chain id, if it is used in this Model
+    pub fn chain(&self, id: char) -> Option<&ChainComposition> {
+        self.chains.iter().find(|chain| chain.id == id)
+    }
+
+    /// The sum of the compositions of all chain ids, its id is a space
+    pub fn total(&self) -> &ChainComposition {
+        &self.total
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The composition of a PDB per Model and per chain id: the number of polymer Residues, waters,
+/// ligand Residues, ATOM and HETATM records and Atoms per element, see `PDB::composition`. It is
+/// displayed as a table with a row per chain id and a total row per Model.
+pub struct Composition {
+    /// The composition of every Model
+    models: Vec<ModelComposition>,
+}
+
+impl Composition {
+    /// Get the composition of the given Model
+    pub(crate) fn new(models: &[Model]) -> Composition {
+        Composition {
+            models: models
+                .iter()
+                .map(|model| {
+                    let mut chains: Vec<ChainComposition> = Vec::new();
+                    let normal = model.chains().map(|chain| (chain, false));
+                    let hetero = model.hetero_chains().map(|chain| (chain, true));
+                    for (chain, is_hetero) in normal.chain(hetero) {
+                        let index = match chains.iter().position(|c| c.id == chain.id()) {
+                            Some(index) => index,
+                            None => {
+                                chains.push(ChainComposition::new(chain.id()));
+                                chains.len() - 1
+                            }
+                        };
+                        chains[index].add_chain(chain, is_hetero);
+                    }
+                    let mut total = ChainComposition::new(' ');
+                    for chain in &chains {
+                        total.add(chain);
+                    }
+                    ModelComposition {
+                        serial_number: model.serial_number(),
+                        chains,
+                        total,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// The composition of every Model
+    pub fn models(&self) -> &[ModelComposition] {
+        &self.models
+    }
+}
+
+impl fmt::Display for Composition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for model in &self.models {
+            writeln!(f, "Model {}", model.serial_number)?;
+            writeln!(
+                f,
+                "{:<6}{:>8}{:>7}{:>8}{:>8}{:>8}  Elements",
+                "Chain", "Polymer", "Water", "Ligand", "ATOM", "HETATM"
+            )?;
+            for chain in &model.chains {
+                chain.write_row(f, &chain.id.to_string())?;
+            }
+            model.total.write_row(f, "Total")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+#[derive(Debug, Clone, Copy)]
+/// A single conformer of a Residue: the Atoms with one alternate location identifier together
+/// with the Atoms without an alternate location, which are shared by all conformers. Ligands
+/// modelled at overlapping positions are generally given an alternate location for the whole
+/// Residue, possibly as different molecules, so every conformer has its own residue name.
+/// See `Residue::conformers`.
+pub struct Conformer<'a> {
+    /// The Residue containing this conformer
+    residue: &'a Residue,
+    /// The alternate location identifier of this conformer, a space if the Residue has no alternate locations
+    alternate_location: char,
+}
+
+impl<'a> Conformer<'a> {
+    /// Create a new Conformer
+    pub(crate) fn new(residue: &'a Residue, alternate_location: char) -> Conformer<'a> {
+        Conformer {
+            residue,
+            alternate_location,
+        }
+    }
+
+    /// The Residue containing this conformer
+    pub fn residue(&self) -> &'a Residue {
+        self.residue
+    }
+
+    /// The alternate location identifier of this conformer, a space if the Residue has no alternate locations
+    pub fn alternate_location(&self) -> char {
+        self.alternate_location
+    }
+
+    /// The residue name of this conformer, see `Residue::conformer_name`
+    pub fn name(&self) -> String {
+        self.residue.conformer_name(self.alternate_location)
+    }
+
+    /// The Atoms making up this conformer, in the order of the Residue
+    pub fn atoms(&self) -> impl DoubleEndedIterator<Item = &'a Atom> + 'a {
+        let alternate_location = self.alternate_location;
+        self.residue.atoms().filter(move |atom| {
+            atom.alternate_location() == ' ' || atom.alternate_location() == alternate_location
+        })
+    }
+
+    /// The number of Atoms making up this conformer
+    pub fn atom_count(&self) -> usize {
+        self.atoms().count()
+    }
+
+    /// The occupancy of this conformer, the mean occupancy of the Atoms with its alternate
+    /// location identifier. For a Residue without alternate locations it is the mean occupancy of
+    /// all Atoms. It is `None` if there are no such Atoms.
+    pub fn occupancy(&self) -> Option<f64> {
+        let occupancies = self
+            .atoms()
+            .filter(|atom| atom.alternate_location() == self.alternate_location)
+            .map(|atom| atom.occupancy())
+            .collect::<Vec<f64>>();
+        if occupancies.is_empty() {
+            None
+        } else {
+            Some(occupancies.iter().sum::<f64>() / occupancies.len() as f64)
+        }
+    }
+
+    /// Create a Residue containing only this conformer, named after this conformer
+    pub fn to_residue(&self) -> Residue {
+        let name = self.name().chars().collect::<Vec<char>>();
+        let mut id = [' '; 3];
+        id[..name.len()].copy_from_slice(&name);
+        let mut residue = Residue::new(self.residue.serial_number(), id, None)
+            .expect("Invalid characters in Residue creation");
+        for atom in self.atoms() {
+            residue.add_atom(atom.clone());
+        }
+        residue
+    }
+}
@@ -0,0 +1,226 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A DBREF record (or a DBREF1/DBREF2 pair for long accession codes), describing the segment of
+/// a sequence database entry, like UniProt, that a range of Residues of a Chain corresponds to.
+/// Every Residue in a range is identified by its serial number and insertion code. The
+/// differences between the sequence of the Chain and the database, given in SEQADV records, are
+/// kept with the reference they belong to.
+pub struct DatabaseReference {
+    /// The id of the Chain
+    chain_id: char,
+    /// The name of the sequence database, like `UNP` for UniProt
+    database: String,
+    /// The accession code of the database entry
+    accession: String,
+    /// The identification code of the database entry, like `HSLU_ECOLI`
+    database_id_code: String,
+    /// The first and last Residue of the segment in the Chain
+    pdb_range: [(usize, char); 2],
+    /// The first and last residue of the segment in the database entry
+    database_range: [(usize, char); 2],
+    /// The differences between the Chain and the database entry given in SEQADV records
+    differences: Vec<SequenceDifference>,
+}
+
+impl DatabaseReference {
+    /// Create a new DatabaseReference record, without any sequence differences
+    ///
+    /// ## Arguments
+    /// * `chain_id` - the id of the Chain
+    /// * `database` - the name of the sequence database, like `UNP` for UniProt
+    /// * `accession` - the accession code of the database entry
+    /// * `database_id_code` - the identification code of the database entry, can be empty
+    /// * `pdb_range` - the serial numbers and insertion codes of the first and last Residue in the Chain
+    /// * `database_range` - the serial numbers and insertion codes of the first and last residue in the database entry
+    ///
+    /// ## Fails
+    /// It fails if any of the chain id, texts or insertion codes contain invalid characters.
+    pub fn new(
+        chain_id: char,
+        database: &str,
+        accession: &str,
+        database_id_code: &str,
+        pdb_range: [(usize, char); 2],
+        database_range: [(usize, char); 2],
+    ) -> Option<DatabaseReference> {
+        if !check_char(chain_id)
+            || !check_chars(format!("{}{}{}", database, accession, database_id_code))
+            || !pdb_range
+                .iter()
+                .chain(database_range.iter())
+                .all(|(_, insertion_code)| check_char(*insertion_code))
+        {
+            return None;
+        }
+        Some(DatabaseReference {
+            chain_id,
+            database: database.trim().to_string(),
+            accession: accession.trim().to_string(),
+            database_id_code: database_id_code.trim().to_string(),
+            pdb_range,
+            database_range,
+            differences: Vec::new(),
+        })
+    }
+
+    /// The id of the Chain
+    pub fn chain_id(&self) -> char {
+        self.chain_id
+    }
+
+    /// The name of the sequence database, like `UNP` for UniProt
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    /// The accession code of the database entry, like `P0A6H5`
+    pub fn accession(&self) -> &str {
+        &self.accession
+    }
+
+    /// The identification code of the database entry, like `HSLU_ECOLI`, empty if not given
+    pub fn database_id_code(&self) -> &str {
+        &self.database_id_code
+    }
+
+    /// The serial numbers and insertion codes of the first and last Residue of the segment in the Chain
+    pub fn pdb_range(&self) -> [(usize, char); 2] {
+        self.pdb_range
+    }
+
+    /// The serial numbers and insertion codes of the first and last residue of the segment in the database entry
+    pub fn database_range(&self) -> [(usize, char); 2] {
+        self.database_range
+    }
+
+    /// Set the accession code and the range in the database entry, as given in the DBREF2
+    /// record following a DBREF1 record
+    /// ## Fails
+    /// It fails if the accession code or any of the insertion codes contain invalid characters.
+    pub fn set_database_entry(
+        &mut self,
+        accession: &str,
+        database_range: [(usize, char); 2],
+    ) -> Result<(), String> {
+        if let Some(invalid) = describe_invalid_chars(accession, check_char) {
+            return Err(format!(
+                "The accession code {} has the invalid characters {}",
+                accession, invalid
+            ));
+        }
+        if let Some((_, c)) = database_range.iter().find(|(_, c)| !check_char(*c)) {
+            return Err(format!(
+                "The insertion code '{}' is not a valid character",
+                c
+            ));
+        }
+        self.accession = accession.trim().to_string();
+        self.database_range = database_range;
+        Ok(())
+    }
+
+    /// Returns `true` if the given Residue lies within the segment of the Chain
+    pub fn contains(&self, chain_id: char, residue_serial_number: usize) -> bool {
+        self.chain_id == chain_id
+            && self.pdb_range[0].0 <= residue_serial_number
+            && residue_serial_number <= self.pdb_range[1].0
+    }
+
+    /// Get the number of sequence differences given in SEQADV records
+    pub fn sequence_difference_count(&self) -> usize {
+        self.differences.len()
+    }
+
+    /// Get the differences between the Chain and the database entry given in SEQADV records
+    pub fn sequence_differences(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &SequenceDifference> + '_ {
+        self.differences.iter()
+    }
+
+    /// Add a difference between the Chain and the database entry, to be written as SEQADV record
+    pub fn add_sequence_difference(&mut self, difference: SequenceDifference) {
+        self.differences.push(difference);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A SEQADV record, describing a difference between the sequence of a Chain and the database
+/// entry it refers to (see `DatabaseReference`), like an engineered mutation or an expression tag.
+/// Residues added to the Chain have no database residue and residues deleted from the Chain
+/// have no Residue in the Chain.
+pub struct SequenceDifference {
+    /// The name of the Residue in the Chain
+    residue_name: String,
+    /// The serial number and insertion code of the Residue in the Chain
+    residue: Option<(usize, char)>,
+    /// The name of the residue in the database entry
+    database_residue_name: String,
+    /// The serial number of the residue in the database entry
+    database_serial_number: Option<usize>,
+    /// The description of the difference, like `ENGINEERED MUTATION`
+    comment: String,
+}
+
+impl SequenceDifference {
+    /// Create a new SequenceDifference record
+    ///
+    /// ## Arguments
+    /// * `residue_name` - the name of the Residue in the Chain, empty if deleted
+    /// * `residue` - the serial number and insertion code of the Residue in the Chain, if present
+    /// * `database_residue_name` - the name of the residue in the database entry, empty if added
+    /// * `database_serial_number` - the serial number of the residue in the database entry, if present
+    /// * `comment` - the description of the difference, like `EXPRESSION TAG`
+    ///
+    /// ## Fails
+    /// It fails if any of the texts or the insertion code contain invalid characters.
+    pub fn new(
+        residue_name: &str,
+        residue: Option<(usize, char)>,
+        database_residue_name: &str,
+        database_serial_number: Option<usize>,
+        comment: &str,
+    ) -> Option<SequenceDifference> {
+        if !check_chars(format!(
+            "{}{}{}",
+            residue_name, database_residue_name, comment
+        )) || residue.is_some_and(|(_, insertion_code)| !check_char(insertion_code))
+        {
+            return None;
+        }
+        Some(SequenceDifference {
+            residue_name: residue_name.trim().to_string(),
+            residue,
+            database_residue_name: database_residue_name.trim().to_string(),
+            database_serial_number,
+            comment: comment.trim().to_string(),
+        })
+    }
+
+    /// The name of the Residue in the Chain, empty if the residue is deleted from the Chain
+    pub fn residue_name(&self) -> &str {
+        &self.residue_name
+    }
+
+    /// The serial number and insertion code of the Residue in the Chain, if present
+    pub fn residue(&self) -> Option<(usize, char)> {
+        self.residue
+    }
+
+    /// The name of the residue in the database entry, empty if the residue is added to the Chain
+    pub fn database_residue_name(&self) -> &str {
+        &self.database_residue_name
+    }
+
+    /// The serial number of the residue in the database entry, if present
+    pub fn database_serial_number(&self) -> Option<usize> {
+        self.database_serial_number
+    }
+
+    /// The description of the difference, like `ENGINEERED MUTATION` or `EXPRESSION TAG`
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+}
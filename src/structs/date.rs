@@ -0,0 +1,100 @@
+#![allow(dead_code)]
+use std::fmt;
+
+/// The abbreviations of the months as used in PDB files
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// The two digit years from this year on are in the 1900s, the others in the 2000s, as the PDB
+/// was founded in 1971
+const CENTURY_PIVOT: u16 = 70;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A date as used in PDB files, like the deposition date in the HEADER record. It is written as
+/// `DD-MMM-YY`, like `26-OCT-07`.
+pub struct Date {
+    /// The year, with the century
+    year: u16,
+    /// The month, 1 for January
+    month: u8,
+    /// The day of the month, starting at 1
+    day: u8,
+}
+
+impl Date {
+    /// Create a new Date
+    ///
+    /// ## Arguments
+    /// * `year` - the year, with the century
+    /// * `month` - the month, 1 for January
+    /// * `day` - the day of the month, starting at 1
+    ///
+    /// ## Fails
+    /// It fails if the day does not exist, like the 31st of April.
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Date> {
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year.is_multiple_of(4)
+                && (!year.is_multiple_of(100) || year.is_multiple_of(400)) =>
+            {
+                29
+            }
+            2 => 28,
+            _ => return None,
+        };
+        if (1..=days).contains(&day) {
+            Some(Date { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    /// Read a Date in the format of PDB files, `DD-MMM-YY` like `26-OCT-07`. Two digit years
+    /// from 70 on are in the 1900s, the others in the 2000s.
+    ///
+    /// ## Fails
+    /// It fails if the text is not in this format or if the day does not exist.
+    pub fn from_pdb(text: &str) -> Option<Date> {
+        let mut parts = text.trim().split('-');
+        let (day, month, year) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() || day.len() != 2 || year.len() != 2 {
+            return None;
+        }
+        let month = MONTHS
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(month))?;
+        let year = year.parse::<u16>().ok()?;
+        let century = if year >= CENTURY_PIVOT { 1900 } else { 2000 };
+        Date::new(century + year, month as u8 + 1, day.parse().ok()?)
+    }
+
+    /// The year, with the century
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// The month, 1 for January
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// The day of the month, starting at 1
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+impl fmt::Display for Date {
+    /// Write the Date in the format of PDB files, `DD-MMM-YY`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}-{}-{:02}",
+            self.day,
+            MONTHS[self.month as usize - 1],
+            self.year % 100
+        )
+    }
+}
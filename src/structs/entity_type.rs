@@ -0,0 +1,154 @@
+#![allow(dead_code)]
+use crate::classifier::Classifier;
+use crate::reference_tables;
+use std::fmt;
+
+/// The type of molecular entity a Residue or Chain represents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EntityType {
+    /// A polypeptide, built from amino acids
+    Protein,
+    /// A deoxyribonucleic acid
+    DNA,
+    /// A ribonucleic acid
+    RNA,
+    /// A (poly)saccharide, built from sugar units
+    Saccharide,
+    /// Water molecules
+    Water,
+    /// (Monoatomic) ions
+    Ion,
+    /// Anything not recognised as any of the other types, generally ligands
+    Other,
+}
+
+impl EntityType {
+    /// Classify a residue based on its name using the names registered in the `Classifier` and
+    /// the reference tables. The unknown amino acid (UNK) is a Protein, the unknown ligand (UNL)
+    /// and unknown atom or ion (UNX) are `EntityType::Other`, as is any name that is not recognised.
+    pub fn from_residue_name(name: &str) -> EntityType {
+        if let Some(entity) = Classifier::lookup(name) {
+            entity
+        } else if reference_tables::get_amino_acid_number(name).is_some()
+            || reference_tables::is_unknown_amino_acid(name)
+        {
+            EntityType::Protein
+        } else if reference_tables::is_unknown_ligand(name) {
+            EntityType::Other
+        } else if reference_tables::is_dna_residue(name) {
+            EntityType::DNA
+        } else if reference_tables::is_rna_residue(name) {
+            EntityType::RNA
+        } else if reference_tables::is_saccharide_residue(name) {
+            EntityType::Saccharide
+        } else if reference_tables::is_water_residue(name) {
+            EntityType::Water
+        } else if reference_tables::is_ion_residue(name) {
+            EntityType::Ion
+        } else {
+            EntityType::Other
+        }
+    }
+
+    /// Returns `true` if this entity type is a polymer (Protein, DNA, RNA, or Saccharide)
+    pub fn polymer(&self) -> bool {
+        matches!(
+            self,
+            EntityType::Protein | EntityType::DNA | EntityType::RNA | EntityType::Saccharide
+        )
+    }
+}
+
+impl fmt::Display for EntityType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            EntityType::Protein => "Protein",
+            EntityType::DNA => "DNA",
+            EntityType::RNA => "RNA",
+            EntityType::Saccharide => "Saccharide",
+            EntityType::Water => "Water",
+            EntityType::Ion => "Ion",
+            EntityType::Other => "Other",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EntityType;
+    use crate::structs::*;
+
+    fn chain_with(names: &[&str]) -> Chain {
+        let mut chain = Chain::new('A').unwrap();
+        for (index, name) in names.iter().enumerate() {
+            let chars = format!("{:>3}", name).chars().collect::<Vec<char>>();
+            let atom = Atom::new(
+                index,
+                [' ', 'C', 'A', ' '],
+                0.0,
+                0.0,
+                0.0,
+                1.0,
+                0.0,
+                [' ', 'C'],
+                0,
+            )
+            .unwrap();
+            chain.add_atom(atom, index, None, [chars[0], chars[1], chars[2]]);
+        }
+        chain
+    }
+
+    #[test]
+    fn residue_names() {
+        assert_eq!(EntityType::from_residue_name("ALA"), EntityType::Protein);
+        assert_eq!(EntityType::from_residue_name("DA"), EntityType::DNA);
+        assert_eq!(EntityType::from_residue_name("U"), EntityType::RNA);
+        assert_eq!(EntityType::from_residue_name("NAG"), EntityType::Saccharide);
+        assert_eq!(EntityType::from_residue_name("HOH"), EntityType::Water);
+        assert_eq!(EntityType::from_residue_name("ZN"), EntityType::Ion);
+        assert_eq!(EntityType::from_residue_name("ATP"), EntityType::Other);
+        assert_eq!(EntityType::from_residue_name("UNK"), EntityType::Protein);
+        assert_eq!(EntityType::from_residue_name("UNX"), EntityType::Other);
+    }
+
+    #[test]
+    fn chain_majority() {
+        assert_eq!(
+            chain_with(&["DA", "DC", "DG", "DT"]).entity_type(),
+            EntityType::DNA
+        );
+        assert_eq!(
+            chain_with(&["HOH", "HOH", "NA", "HOH"]).entity_type(),
+            EntityType::Water
+        );
+        let mixed = chain_with(&["ALA", "GLY", "NAG", "HOH", "HOH", "HOH"]);
+        assert_eq!(mixed.entity_type(), EntityType::Water);
+        assert_eq!(
+            mixed.entity_composition(),
+            vec![
+                (EntityType::Water, 3),
+                (EntityType::Protein, 2),
+                (EntityType::Saccharide, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn chain_ambiguous() {
+        // A short peptide ligand in a hetero chain is still classified as protein
+        assert_eq!(
+            chain_with(&["ALA", "GLY", "SER"]).entity_type(),
+            EntityType::Protein
+        );
+        // Ties are broken by the order of declaration of EntityType
+        assert_eq!(
+            chain_with(&["ALA", "HOH"]).entity_type(),
+            EntityType::Protein
+        );
+        assert_eq!(chain_with(&["HOH", "ATP"]).entity_type(), EntityType::Water);
+        // An empty chain is classified as other
+        assert_eq!(chain_with(&[]).entity_type(), EntityType::Other);
+    }
+}
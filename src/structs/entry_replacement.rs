@@ -0,0 +1,67 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// The replacement of PDB entries, given in an OBSLTE record (this entry is replaced by the
+/// listed entries) or an SPRSDE record (this entry replaces the listed entries). Every entry is
+/// identified by its PDB id code, like `1UBQ`.
+pub struct EntryReplacement {
+    /// The PDB id code of this entry
+    id_code: String,
+    /// The date of the replacement
+    date: Option<Date>,
+    /// The PDB id codes of the replacing or replaced entries
+    entries: Vec<String>,
+}
+
+impl EntryReplacement {
+    /// Create a new EntryReplacement, without any replacing or replaced entries
+    ///
+    /// ## Arguments
+    /// * `id_code` - the PDB id code of this entry
+    /// * `date` - the date of the replacement, if known
+    ///
+    /// ## Fails
+    /// It fails if the id code is not four alphanumeric ASCII characters.
+    pub fn new(id_code: &str, date: Option<Date>) -> Option<EntryReplacement> {
+        let id_code = id_code.trim();
+        if !check_id_code(id_code) {
+            return None;
+        }
+        Some(EntryReplacement {
+            id_code: id_code.to_string(),
+            date,
+            entries: Vec::new(),
+        })
+    }
+
+    /// The PDB id code of this entry
+    pub fn id_code(&self) -> &str {
+        &self.id_code
+    }
+
+    /// The date of the replacement, if known
+    pub fn date(&self) -> Option<Date> {
+        self.date
+    }
+
+    /// The PDB id codes of the replacing (OBSLTE) or replaced (SPRSDE) entries
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        self.entries.iter().map(String::as_str)
+    }
+
+    /// Add a replacing (OBSLTE) or replaced (SPRSDE) entry
+    /// ## Fails
+    /// It fails if the id code is not four alphanumeric ASCII characters.
+    pub fn add_entry(&mut self, id_code: &str) -> Result<(), String> {
+        let id_code = id_code.trim();
+        if !check_id_code(id_code) {
+            return Err(format!(
+                "The entry \"{}\" is not a valid PDB id code, it should be four alphanumeric characters",
+                id_code
+            ));
+        }
+        self.entries.push(id_code.to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+#![allow(dead_code)]
+use std::fmt;
+
+/// The experimental method used to determine a structure, as given in the EXPDTA record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ExperimentalMethod {
+    /// X-ray crystallography
+    XRayDiffraction,
+    /// Fiber diffraction
+    FiberDiffraction,
+    /// Neutron crystallography
+    NeutronDiffraction,
+    /// Electron crystallography, including microED
+    ElectronCrystallography,
+    /// Electron microscopy, including single particle cryo-EM
+    ElectronMicroscopy,
+    /// Solid-state NMR spectroscopy
+    SolidStateNmr,
+    /// Solution NMR spectroscopy
+    SolutionNmr,
+    /// Solution scattering, like SAXS
+    SolutionScattering,
+}
+
+impl ExperimentalMethod {
+    /// Get the experimental method from its name in the EXPDTA record, like `X-RAY DIFFRACTION`,
+    /// ignoring case. It returns `None` if the name is not known.
+    pub fn from_pdb(name: &str) -> Option<ExperimentalMethod> {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "X-RAY DIFFRACTION" => Some(ExperimentalMethod::XRayDiffraction),
+            "FIBER DIFFRACTION" => Some(ExperimentalMethod::FiberDiffraction),
+            "NEUTRON DIFFRACTION" => Some(ExperimentalMethod::NeutronDiffraction),
+            "ELECTRON CRYSTALLOGRAPHY" => Some(ExperimentalMethod::ElectronCrystallography),
+            "ELECTRON MICROSCOPY" => Some(ExperimentalMethod::ElectronMicroscopy),
+            "SOLID-STATE NMR" => Some(ExperimentalMethod::SolidStateNmr),
+            "SOLUTION NMR" => Some(ExperimentalMethod::SolutionNmr),
+            "SOLUTION SCATTERING" => Some(ExperimentalMethod::SolutionScattering),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ExperimentalMethod {
+    /// Write the name of the experimental method as used in the EXPDTA record
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ExperimentalMethod::XRayDiffraction => "X-RAY DIFFRACTION",
+            ExperimentalMethod::FiberDiffraction => "FIBER DIFFRACTION",
+            ExperimentalMethod::NeutronDiffraction => "NEUTRON DIFFRACTION",
+            ExperimentalMethod::ElectronCrystallography => "ELECTRON CRYSTALLOGRAPHY",
+            ExperimentalMethod::ElectronMicroscopy => "ELECTRON MICROSCOPY",
+            ExperimentalMethod::SolidStateNmr => "SOLID-STATE NMR",
+            ExperimentalMethod::SolutionNmr => "SOLUTION NMR",
+            ExperimentalMethod::SolutionScattering => "SOLUTION SCATTERING",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,73 @@
+#![allow(dead_code)]
+
+#[derive(Debug, Clone, PartialEq)]
+/// A gap in the coordinates of a Chain, as found by `Chain::gap_report`. It records the evidence
+/// for the gap, as every signal on its own can be misleading (for example a jump in the numbering
+/// without a physical break is often just a quirk of the numbering used by the authors).
+pub struct Gap {
+    /// The serial number of the last Residue before the gap, `None` for a gap at the start of the Chain
+    before: Option<usize>,
+    /// The serial number of the first Residue after the gap, `None` for a gap at the end of the Chain
+    after: Option<usize>,
+    /// The number of missing Residues
+    missing: usize,
+    /// The residue serial numbers jump over the gap
+    numbering_jump: bool,
+    /// The C-N distance between the Residues around the gap is too long for a peptide bond
+    chain_break: bool,
+    /// Missing Residues in the gap are listed in REMARK 465
+    remark_465: bool,
+}
+
+impl Gap {
+    /// Create a new Gap
+    pub(crate) fn new(
+        before: Option<usize>,
+        after: Option<usize>,
+        missing: usize,
+        numbering_jump: bool,
+        chain_break: bool,
+        remark_465: bool,
+    ) -> Gap {
+        Gap {
+            before,
+            after,
+            missing,
+            numbering_jump,
+            chain_break,
+            remark_465,
+        }
+    }
+
+    /// The serial number of the last Residue before the gap, `None` for missing Residues at the start of the Chain
+    pub fn before(&self) -> Option<usize> {
+        self.before
+    }
+
+    /// The serial number of the first Residue after the gap, `None` for missing Residues at the end of the Chain
+    pub fn after(&self) -> Option<usize> {
+        self.after
+    }
+
+    /// The number of missing Residues. This is the number of Residues listed in REMARK 465 when
+    /// available, otherwise the size of the jump in the numbering. It is 0 for a chain break
+    /// without other evidence, as the number of missing Residues is then unknown.
+    pub fn missing(&self) -> usize {
+        self.missing
+    }
+
+    /// Whether the residue serial numbers jump over the gap
+    pub fn numbering_jump(&self) -> bool {
+        self.numbering_jump
+    }
+
+    /// Whether the C-N distance between the Residues around the gap is too long for a peptide bond (above 2.0 Å)
+    pub fn chain_break(&self) -> bool {
+        self.chain_break
+    }
+
+    /// Whether missing Residues in the gap are listed in REMARK 465
+    pub fn remark_465(&self) -> bool {
+        self.remark_465
+    }
+}
@@ -0,0 +1,223 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+/// The helix class of a right-handed alpha helix, used when no class is given
+const RIGHT_HANDED_ALPHA: usize = 1;
+
+/// The maximal length of the comment of a HELIX record
+const MAX_COMMENT_LENGTH: usize = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A HELIX record, describing a helix in the secondary structure. The helix runs from its start
+/// Residue up to and including its end Residue, every Residue is identified by its name, chain id,
+/// serial number and insertion code.
+pub struct Helix {
+    /// The serial number of the helix
+    serial_number: usize,
+    /// The identifier of the helix, max three characters
+    id: String,
+    /// The names of the start and end Residues
+    residue_names: [[char; 3]; 2],
+    /// The ids of the Chains containing the start and end Residues
+    chain_ids: [char; 2],
+    /// The serial numbers of the start and end Residues
+    residue_serial_numbers: [usize; 2],
+    /// The insertion codes of the start and end Residues
+    insertion_codes: [char; 2],
+    /// The class of the helix, see `class`
+    class: usize,
+    /// The free text comment on the helix
+    comment: String,
+    /// The number of Residues in the helix
+    length: usize,
+}
+
+impl Helix {
+    /// Create a new Helix record, as a right-handed alpha helix without comment. The length is
+    /// the number of serial numbers from the start up to and including the end Residue.
+    ///
+    /// ## Arguments
+    /// * `serial_number` - the serial number of the helix
+    /// * `id` - the identifier of the helix, max three characters
+    /// * `residue_names` - the names of the start and end Residues
+    /// * `chain_ids` - the ids of the Chains containing the start and end Residues
+    /// * `residue_serial_numbers` - the serial numbers of the start and end Residues
+    /// * `insertion_codes` - the insertion codes of the start and end Residues, a space if there is none
+    ///
+    /// ## Fails
+    /// It fails if the id is longer than three characters or if any of the characters of the id,
+    /// residue names, chain ids or insertion codes is invalid.
+    pub fn new(
+        serial_number: usize,
+        id: &str,
+        residue_names: [[char; 3]; 2],
+        chain_ids: [char; 2],
+        residue_serial_numbers: [usize; 2],
+        insertion_codes: [char; 2],
+    ) -> Option<Helix> {
+        if id.trim().chars().count() > 3
+            || !check_chars(id.to_string())
+            || !residue_names.iter().all(|name| check_char3(*name))
+            || !chain_ids
+                .iter()
+                .chain(insertion_codes.iter())
+                .all(|c| check_char(*c))
+        {
+            return None;
+        }
+        Some(Helix {
+            serial_number,
+            id: id.trim().to_string(),
+            residue_names,
+            chain_ids,
+            residue_serial_numbers,
+            insertion_codes,
+            class: RIGHT_HANDED_ALPHA,
+            comment: String::new(),
+            length: (residue_serial_numbers[1] + 1).saturating_sub(residue_serial_numbers[0]),
+        })
+    }
+
+    /// The serial number of the helix
+    pub fn serial_number(&self) -> usize {
+        self.serial_number
+    }
+
+    /// Set the serial number of the helix
+    pub fn set_serial_number(&mut self, new_number: usize) {
+        self.serial_number = new_number;
+    }
+
+    /// The identifier of the helix
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The names of the start and end Residues
+    pub fn residue_names(&self) -> [String; 2] {
+        self.residue_names.map(|name| {
+            name.iter()
+                .collect::<String>()
+                .split_whitespace()
+                .collect::<String>()
+        })
+    }
+
+    /// The ids of the Chains containing the start and end Residues
+    pub fn chain_ids(&self) -> [char; 2] {
+        self.chain_ids
+    }
+
+    /// The serial numbers of the start and end Residues
+    pub fn residue_serial_numbers(&self) -> [usize; 2] {
+        self.residue_serial_numbers
+    }
+
+    /// Set the chain ids and serial numbers of the start and end Residues, for example after renumbering
+    /// ## Fails
+    /// It fails if any of the chain ids is an invalid character.
+    pub fn set_residues(
+        &mut self,
+        chain_ids: [char; 2],
+        residue_serial_numbers: [usize; 2],
+    ) -> Result<(), String> {
+        if let Some(c) = chain_ids.iter().find(|c| !check_char(**c)) {
+            return Err(format!("The chain id '{}' is not a valid character", c));
+        }
+        self.chain_ids = chain_ids;
+        self.residue_serial_numbers = residue_serial_numbers;
+        Ok(())
+    }
+
+    /// The insertion codes of the start and end Residues, a space if there is none
+    pub fn insertion_codes(&self) -> [char; 2] {
+        self.insertion_codes
+    }
+
+    /// Set the insertion codes of the start and end Residues, for example after renumbering
+    /// ## Fails
+    /// It fails if any of the insertion codes is an invalid character.
+    pub fn set_insertion_codes(&mut self, insertion_codes: [char; 2]) -> Result<(), String> {
+        if let Some(c) = insertion_codes.iter().find(|c| !check_char(**c)) {
+            return Err(format!(
+                "The insertion code '{}' is not a valid character",
+                c
+            ));
+        }
+        self.insertion_codes = insertion_codes;
+        Ok(())
+    }
+
+    /// The class of the helix: 1 right-handed alpha (the default), 2 right-handed omega,
+    /// 3 right-handed pi, 4 right-handed gamma, 5 right-handed 3-10, 6 left-handed alpha,
+    /// 7 left-handed omega, 8 left-handed gamma, 9 2-7 ribbon/helix and 10 polyproline
+    pub fn class(&self) -> usize {
+        self.class
+    }
+
+    /// Set the class of the helix, see `class`
+    /// ## Fails
+    /// It fails if the class is not in the range 1 to 10.
+    pub fn set_class(&mut self, class: usize) -> Result<(), String> {
+        if (1..=10).contains(&class) {
+            self.class = class;
+            Ok(())
+        } else {
+            Err(format!(
+                "The helix class {} is not valid, it should be in the range 1 to 10",
+                class
+            ))
+        }
+    }
+
+    /// The free text comment on the helix, empty if there is none
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    /// Set the free text comment on the helix
+    /// ## Fails
+    /// It fails if the comment is longer than 30 characters or contains invalid characters.
+    pub fn set_comment(&mut self, comment: &str) -> Result<(), String> {
+        if comment.trim().chars().count() > MAX_COMMENT_LENGTH {
+            Err(format!(
+                "The comment \"{}\" is longer than {} characters",
+                comment.trim(),
+                MAX_COMMENT_LENGTH
+            ))
+        } else if !check_chars(comment.to_string()) {
+            Err(format!(
+                "The comment \"{}\" contains invalid characters",
+                comment.trim()
+            ))
+        } else {
+            self.comment = comment.trim().to_string();
+            Ok(())
+        }
+    }
+
+    /// The number of Residues in the helix
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Set the number of Residues in the helix
+    pub fn set_length(&mut self, length: usize) {
+        self.length = length;
+    }
+
+    /// Returns `true` if the start Residue comes before or is the end Residue, in the same Chain
+    pub fn is_ordered(&self) -> bool {
+        self.chain_ids[0] == self.chain_ids[1]
+            && (self.residue_serial_numbers[0], self.insertion_codes[0])
+                <= (self.residue_serial_numbers[1], self.insertion_codes[1])
+    }
+
+    /// Returns `true` if the given Residue is part of this helix, judged on the serial numbers
+    /// of the start and end Residues (disregarding the insertion codes)
+    pub fn contains(&self, chain_id: char, residue_serial_number: usize) -> bool {
+        self.chain_ids.iter().all(|id| *id == chain_id)
+            && (self.residue_serial_numbers[0]..=self.residue_serial_numbers[1])
+                .contains(&residue_serial_number)
+    }
+}
@@ -34,6 +34,85 @@ pub fn check_chars(text: String) -> bool {
     true
 }
 
+/// Checks a chain identifier, the PDB format allows alphanumeric ASCII characters and space (for
+/// files without chain identifiers).
+/// Returns `true` if the identifier is valid.
+pub fn check_chain_id(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == ' '
+}
+
+/// Checks a PDB id code, which consists of four alphanumeric ASCII characters, like `1UBQ`.
+/// Returns `true` if the id code is valid.
+pub fn check_id_code(id: &str) -> bool {
+    id.len() == 4 && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// The maximal length of a data block name in a CIF file
+const MAX_BLOCK_NAME_LENGTH: usize = 75;
+
+/// Checks a data block name as used in CIF files (`data_name`), which consists of at most 75
+/// ASCII graphic characters, so no whitespace. An empty name is valid, meaning no name is set.
+/// Returns `true` if the name is valid.
+pub fn check_block_name(name: &str) -> bool {
+    name.len() <= MAX_BLOCK_NAME_LENGTH && name.chars().all(|c| c.is_ascii_graphic())
+}
+
+/// Get a data block name (see `check_block_name`) from the given path, as the file name without
+/// its extension with every invalid character replaced by an underscore.
+pub fn block_name_from_path(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_stem()
+        .map(|stem| {
+            stem.to_string_lossy()
+                .chars()
+                .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+                .take(MAX_BLOCK_NAME_LENGTH)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Describe the characters in the text that are invalid according to the given check, for use in
+/// error messages, for example `'∞' (U+221E)`. Returns `None` if all characters are valid.
+pub fn describe_invalid_chars(text: &str, valid: fn(char) -> bool) -> Option<String> {
+    let invalid = text
+        .chars()
+        .filter(|c| !valid(*c))
+        .map(|c| format!("{:?} (U+{:04X})", c, c as u32))
+        .collect::<Vec<String>>();
+    if invalid.is_empty() {
+        None
+    } else {
+        Some(invalid.join(", "))
+    }
+}
+
+/// Read the resolution of a REMARK 2 line (`RESOLUTION. 2.00 ANGSTROMS.`), it is `None` if the
+/// resolution is `NOT APPLICABLE`.
+/// ## Fails
+/// It fails if the line does not contain a resolution in Å.
+pub(crate) fn remark_resolution(text: &str) -> Result<Option<f64>, String> {
+    let value = text
+        .trim()
+        .trim_start_matches("RESOLUTION.")
+        .trim_end_matches('.')
+        .trim();
+    if value == "NOT APPLICABLE" {
+        return Ok(None);
+    }
+    value
+        .strip_suffix("ANGSTROMS")
+        .and_then(|number| number.trim().parse::<f64>().ok())
+        .filter(|number| number.is_finite() && *number > 0.0)
+        .map(Some)
+        .ok_or_else(|| {
+            format!(
+                "The resolution \"{}\" is not a number of Ångströms or NOT APPLICABLE",
+                value
+            )
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,6 +128,9 @@ mod tests {
         assert!(check_char4(['R', 'E', 'S', 'D']));
         assert!(check_chars("ResidueName".to_string()));
         assert!(check_chars("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890`-=[]\\;',./~!@#$%^&*()_+{}|:\"<>? ".to_string()));
+        assert!(check_block_name(""));
+        assert!(check_block_name("1UBQ"));
+        assert!(check_block_name("model_1.refined"));
     }
     #[test]
     fn incorrect_examples() {
@@ -62,5 +144,55 @@ mod tests {
         assert!(!check_char4(['R', 'E', '\u{0}', 'D']));
         assert!(!check_chars("ResidueName∞".to_string()));
         assert!(!check_chars("Escape\u{0}".to_string()));
+        assert!(!check_block_name("two words"));
+        assert!(!check_block_name("tab\tname"));
+        assert!(!check_block_name(&"x".repeat(76)));
+    }
+    #[test]
+    fn resolutions() {
+        assert_eq!(
+            remark_resolution("RESOLUTION.    2.00 ANGSTROMS."),
+            Ok(Some(2.0))
+        );
+        assert_eq!(
+            remark_resolution("RESOLUTION. 1.5 ANGSTROMS"),
+            Ok(Some(1.5))
+        );
+        assert_eq!(remark_resolution("RESOLUTION. NOT APPLICABLE."), Ok(None));
+        assert!(remark_resolution("RESOLUTION. 2.00").is_err());
+        assert!(remark_resolution("RESOLUTION. HIGH ANGSTROMS.").is_err());
+    }
+    #[test]
+    fn chain_ids() {
+        for code in 0..=0x2FF_u32 {
+            let c = std::char::from_u32(code).unwrap();
+            let expected =
+                c == ' ' || c.is_ascii_digit() || c.is_ascii_lowercase() || c.is_ascii_uppercase();
+            assert_eq!(check_chain_id(c), expected, "{:?}", c);
+            // Every valid chain id is a valid character
+            assert!(!check_chain_id(c) || check_char(c));
+        }
+        for c in &['👍', '∞', 'Α', '٣'] {
+            assert!(!check_chain_id(*c));
+        }
+    }
+    #[test]
+    fn boundaries() {
+        for code in 0..=0x2FF_u32 {
+            let c = std::char::from_u32(code).unwrap();
+            assert_eq!(check_char(c), (' '..='~').contains(&c), "{:?}", c);
+        }
+    }
+    #[test]
+    fn descriptions() {
+        assert_eq!(describe_invalid_chars("ALA", check_char), None);
+        assert_eq!(
+            describe_invalid_chars("A∞B\u{0}", check_char).unwrap(),
+            "'∞' (U+221E), '\\0' (U+0000)"
+        );
+        assert_eq!(
+            describe_invalid_chars("a1_", check_chain_id).unwrap(),
+            "'_' (U+005F)"
+        );
     }
 }
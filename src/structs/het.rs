@@ -0,0 +1,128 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A HET record, describing a single hetero group (non standard residue) in the coordinates
+pub struct Het {
+    /// The identifier of the hetero group, max three characters
+    id: [char; 3],
+    /// The identifier of the Chain containing the hetero group
+    chain_id: char,
+    /// The serial number of the Residue of the hetero group
+    residue_serial_number: usize,
+    /// The insertion code of the Residue of the hetero group
+    insertion_code: char,
+    /// The number of HETATM records for this group
+    atom_count: usize,
+    /// The free text describing this hetero group
+    text: String,
+}
+
+impl Het {
+    /// Create a new Het record
+    ///
+    /// ## Arguments
+    /// * `id` - the identifier of the hetero group
+    /// * `chain_id` - the identifier of the Chain containing the hetero group
+    /// * `residue_serial_number` - the serial number of the Residue of the hetero group
+    /// * `insertion_code` - the insertion code of the Residue of the hetero group
+    /// * `atom_count` - the number of HETATM records for this group
+    /// * `text` - the free text describing this hetero group
+    ///
+    /// ## Fails
+    /// It fails if any of the characters of the id, chain id, insertion code or text are invalid.
+    pub fn new(
+        id: [char; 3],
+        chain_id: char,
+        residue_serial_number: usize,
+        insertion_code: char,
+        atom_count: usize,
+        text: &str,
+    ) -> Option<Het> {
+        if !check_char3(id)
+            || !check_char(chain_id)
+            || !check_char(insertion_code)
+            || !check_chars(text.to_string())
+        {
+            return None;
+        }
+        Some(Het {
+            id,
+            chain_id,
+            residue_serial_number,
+            insertion_code,
+            atom_count,
+            text: text.trim().to_string(),
+        })
+    }
+
+    /// Create a new Het record describing the given Residue in the given Chain
+    pub fn from_residue(chain: &Chain, residue: &Residue) -> Het {
+        Het {
+            id: residue.id_array(),
+            chain_id: chain.id(),
+            residue_serial_number: residue.serial_number(),
+            insertion_code: ' ',
+            atom_count: residue.atom_count(),
+            text: String::new(),
+        }
+    }
+
+    /// The identifier of the hetero group
+    pub fn id(&self) -> String {
+        self.id
+            .iter()
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<String>()
+    }
+
+    /// The identifier of the Chain containing the hetero group
+    pub fn chain_id(&self) -> char {
+        self.chain_id
+    }
+
+    /// The serial number of the Residue of the hetero group
+    pub fn residue_serial_number(&self) -> usize {
+        self.residue_serial_number
+    }
+
+    /// Set the serial number of the Residue of the hetero group
+    pub fn set_residue_serial_number(&mut self, new_number: usize) {
+        self.residue_serial_number = new_number;
+    }
+
+    /// The insertion code of the Residue of the hetero group
+    pub fn insertion_code(&self) -> char {
+        self.insertion_code
+    }
+
+    /// The number of HETATM records for this group
+    pub fn atom_count(&self) -> usize {
+        self.atom_count
+    }
+
+    /// The free text describing this hetero group
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Set the free text describing this hetero group, returns `false` if the text contains invalid characters
+    pub fn set_text(&mut self, text: &str) -> bool {
+        if check_chars(text.to_string()) {
+            self.text = text.trim().to_string();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns `true` if this record describes the same hetero group as the other record,
+    /// so the same id, chain and residue
+    pub fn same_group(&self, other: &Het) -> bool {
+        self.id == other.id
+            && self.chain_id == other.chain_id
+            && self.residue_serial_number == other.residue_serial_number
+            && self.insertion_code == other.insertion_code
+    }
+}
@@ -0,0 +1,117 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// The description of a hetero group type, given in the HETNAM, HETSYN and FORMUL records, like
+/// the chemical name and formula of `HEM`. The hetero Residues with this name are the groups of
+/// this type, the HET records of the groups are found with `PDB::heterogen_groups`.
+pub struct Heterogen {
+    /// The identifier of the hetero group, max three characters
+    id: String,
+    /// The chemical name given in the HETNAM records
+    name: String,
+    /// The synonyms of the chemical name given in the HETSYN records
+    synonyms: Vec<String>,
+    /// The chemical formula given in the FORMUL records
+    formula: String,
+    /// The component number given in the FORMUL records, if available
+    component_number: Option<usize>,
+}
+
+impl Heterogen {
+    /// Create a new Heterogen, without a name, synonyms or formula
+    ///
+    /// ## Arguments
+    /// * `id` - the identifier of the hetero group, like `HEM`
+    ///
+    /// ## Fails
+    /// It fails if the id is empty, longer than three characters or contains invalid characters.
+    pub fn new(id: &str) -> Option<Heterogen> {
+        let id = id.trim();
+        if id.is_empty() || id.len() > 3 || !check_chars(id.to_string()) {
+            return None;
+        }
+        Some(Heterogen {
+            id: id.to_string(),
+            name: String::new(),
+            synonyms: Vec::new(),
+            formula: String::new(),
+            component_number: None,
+        })
+    }
+
+    /// The identifier of the hetero group, like `HEM`
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The chemical name, like `PROTOPORPHYRIN IX CONTAINING FE`, empty if not given
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the chemical name, written in the HETNAM records
+    /// ## Fails
+    /// It fails if the name contains invalid characters.
+    pub fn set_name(&mut self, name: &str) -> Result<(), String> {
+        if let Some(invalid) = describe_invalid_chars(name, check_char) {
+            return Err(format!(
+                "The name \"{}\" of hetero group {} contains the invalid characters {}",
+                name, self.id, invalid
+            ));
+        }
+        self.name = name.trim().to_string();
+        Ok(())
+    }
+
+    /// The synonyms of the chemical name, like `HEME`
+    pub fn synonyms(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        self.synonyms.iter().map(String::as_str)
+    }
+
+    /// Add a synonym of the chemical name, written in the HETSYN records
+    /// ## Fails
+    /// It fails if the synonym contains invalid characters or a semicolon (which separates the
+    /// synonyms).
+    pub fn add_synonym(&mut self, synonym: &str) -> Result<(), String> {
+        if let Some(invalid) = describe_invalid_chars(synonym, |c| check_char(c) && c != ';') {
+            return Err(format!(
+                "The synonym \"{}\" of hetero group {} contains the invalid characters {}",
+                synonym, self.id, invalid
+            ));
+        }
+        self.synonyms.push(synonym.trim().to_string());
+        Ok(())
+    }
+
+    /// The chemical formula, like `C34 H32 FE N4 O4`, empty if not given. The formula of groups
+    /// occurring multiple times is given with their number, like `2(C34 H32 FE N4 O4)`.
+    pub fn formula(&self) -> &str {
+        &self.formula
+    }
+
+    /// Set the chemical formula, written in the FORMUL records
+    /// ## Fails
+    /// It fails if the formula contains invalid characters.
+    pub fn set_formula(&mut self, formula: &str) -> Result<(), String> {
+        if let Some(invalid) = describe_invalid_chars(formula, check_char) {
+            return Err(format!(
+                "The formula \"{}\" of hetero group {} contains the invalid characters {}",
+                formula, self.id, invalid
+            ));
+        }
+        self.formula = formula.trim().to_string();
+        Ok(())
+    }
+
+    /// The component number given in the FORMUL records, numbering the chemical components of
+    /// the structure, the Chains counting as the first components
+    pub fn component_number(&self) -> Option<usize> {
+        self.component_number
+    }
+
+    /// Set the component number, see `component_number`
+    pub fn set_component_number(&mut self, component_number: Option<usize>) {
+        self.component_number = component_number;
+    }
+}
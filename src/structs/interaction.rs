@@ -0,0 +1,287 @@
+#![allow(dead_code)]
+use crate::structs::ResidueId;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// The type of a non covalent interaction between a ligand and another Residue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InteractionType {
+    /// A metal ion coordinated by an N, O or S atom
+    MetalCoordination,
+    /// Two oppositely charged N or O atoms
+    SaltBridge,
+    /// Two polar (N or O) atoms, one possibly donating a hydrogen to the other
+    HydrogenBond,
+    /// Two carbon atoms
+    Hydrophobic,
+}
+
+impl fmt::Display for InteractionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            InteractionType::MetalCoordination => "Metal coordination",
+            InteractionType::SaltBridge => "Salt bridge",
+            InteractionType::HydrogenBond => "Hydrogen bond",
+            InteractionType::Hydrophobic => "Hydrophobic",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The default maximal distances in Å between the two atoms of every type of interaction. The
+/// interaction types are listed in order of precedence, a pair of atoms is classified as the
+/// first type it matches.
+const DEFAULT_CUTOFFS: &[(InteractionType, f64)] = &[
+    (InteractionType::MetalCoordination, 2.8),
+    (InteractionType::SaltBridge, 4.0),
+    (InteractionType::HydrogenBond, 3.5),
+    (InteractionType::Hydrophobic, 4.0),
+];
+
+/// The charged atoms of the standard amino acids as (residue name, atom name, charge). The
+/// histidine side chain is taken as neutral. The termini are not included, as the charge of the
+/// N-terminal N is not known from the name alone, the C-terminal OXT is.
+const CHARGED_ATOMS: &[(&str, &str, isize)] = &[
+    ("ARG", "NE", 1),
+    ("ARG", "NH1", 1),
+    ("ARG", "NH2", 1),
+    ("LYS", "NZ", 1),
+    ("ASP", "OD1", -1),
+    ("ASP", "OD2", -1),
+    ("GLU", "OE1", -1),
+    ("GLU", "OE2", -1),
+    ("", "OXT", -1),
+];
+
+/// The elements that are considered metals for metal coordination
+const METALS: &[&str] = &[
+    "LI", "NA", "K", "RB", "CS", "MG", "CA", "SR", "BA", "MN", "FE", "CO", "NI", "CU", "ZN", "CD",
+    "HG", "PT", "AU", "AG", "PB", "AL", "GA", "MO", "W", "V", "CR",
+];
+
+/// The elements that can coordinate a metal
+const METAL_LIGANDS: &[&str] = &["N", "O", "S"];
+
+/// The elements that can take part in hydrogen bonds and salt bridges
+const POLAR: &[&str] = &["N", "O"];
+
+#[derive(Debug, Clone, PartialEq)]
+/// The criteria used to classify the interactions found by `Model::interaction_fingerprint`, the
+/// maximal distance between the two atoms of every type of interaction
+pub struct InteractionCriteria {
+    /// The cutoff in Å for every interaction type, in order of precedence
+    cutoffs: Vec<(InteractionType, f64)>,
+}
+
+impl Default for InteractionCriteria {
+    fn default() -> Self {
+        InteractionCriteria {
+            cutoffs: DEFAULT_CUTOFFS.to_vec(),
+        }
+    }
+}
+
+impl InteractionCriteria {
+    /// Create new criteria with the default cutoffs: 2.8 Å for metal coordination, 4.0 Å for salt
+    /// bridges, 3.5 Å for hydrogen bonds and 4.0 Å for hydrophobic contacts
+    pub fn new() -> InteractionCriteria {
+        InteractionCriteria::default()
+    }
+
+    /// Get the maximal distance in Å between the two atoms of the given type of interaction
+    pub fn cutoff(&self, interaction: InteractionType) -> f64 {
+        self.cutoffs
+            .iter()
+            .find(|(kind, _)| *kind == interaction)
+            .map_or(0.0, |(_, cutoff)| *cutoff)
+    }
+
+    /// Set the maximal distance in Å between the two atoms of the given type of interaction, a
+    /// cutoff of 0.0 disables this type of interaction
+    /// ## Fails
+    /// It fails if the cutoff is negative or not finite.
+    pub fn set_cutoff(&mut self, interaction: InteractionType, cutoff: f64) -> Result<(), String> {
+        if !cutoff.is_finite() || cutoff < 0.0 {
+            return Err(format!(
+                "The cutoff for {} should be a positive number, not {}",
+                interaction, cutoff
+            ));
+        }
+        for (kind, value) in self.cutoffs.iter_mut() {
+            if *kind == interaction {
+                *value = cutoff;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the largest cutoff of all interaction types
+    pub(crate) fn max_cutoff(&self) -> f64 {
+        self.cutoffs
+            .iter()
+            .map(|(_, cutoff)| *cutoff)
+            .fold(0.0, f64::max)
+    }
+
+    /// Classify the interaction between two atoms, given as (residue name, atom name, element,
+    /// charge) at the given distance, as the first matching type of interaction within its cutoff
+    pub(crate) fn classify(
+        &self,
+        a: (&str, &str, &str, isize),
+        b: (&str, &str, &str, isize),
+        distance: f64,
+    ) -> Option<InteractionType> {
+        self.cutoffs
+            .iter()
+            .filter(|(_, cutoff)| distance <= *cutoff)
+            .map(|(kind, _)| *kind)
+            .find(|kind| match kind {
+                InteractionType::MetalCoordination => {
+                    (METALS.contains(&a.2) && METAL_LIGANDS.contains(&b.2))
+                        || (METALS.contains(&b.2) && METAL_LIGANDS.contains(&a.2))
+                }
+                InteractionType::SaltBridge => {
+                    POLAR.contains(&a.2)
+                        && POLAR.contains(&b.2)
+                        && charge(a.0, a.1, a.3) * charge(b.0, b.1, b.3) < 0
+                }
+                InteractionType::HydrogenBond => POLAR.contains(&a.2) && POLAR.contains(&b.2),
+                InteractionType::Hydrophobic => a.2 == "C" && b.2 == "C",
+            })
+    }
+}
+
+/// The charge of an atom, from the charge column if given, otherwise from the charged atoms of
+/// the standard amino acids
+fn charge(residue: &str, atom: &str, charge: isize) -> isize {
+    if charge != 0 {
+        return charge;
+    }
+    CHARGED_ATOMS
+        .iter()
+        .find(|(r, a, _)| (r.is_empty() || *r == residue) && *a == atom)
+        .map_or(0, |(_, _, charge)| *charge)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A non covalent interaction between an atom of a ligand and an atom of another Residue, as
+/// found by `Model::interaction_fingerprint`
+pub struct Interaction {
+    /// The type of the interaction
+    kind: InteractionType,
+    /// The name of the atom of the ligand
+    ligand_atom: String,
+    /// The other Residue
+    residue: ResidueId,
+    /// The name of the other Residue
+    residue_name: String,
+    /// The name of the atom of the other Residue
+    residue_atom: String,
+    /// The distance between the two atoms in Å
+    distance: f64,
+}
+
+impl Interaction {
+    /// Create a new Interaction
+    pub(crate) fn new(
+        kind: InteractionType,
+        ligand_atom: String,
+        residue: ResidueId,
+        residue_name: String,
+        residue_atom: String,
+        distance: f64,
+    ) -> Interaction {
+        Interaction {
+            kind,
+            ligand_atom,
+            residue,
+            residue_name,
+            residue_atom,
+            distance,
+        }
+    }
+
+    /// The type of the interaction
+    pub fn kind(&self) -> InteractionType {
+        self.kind
+    }
+
+    /// The name of the atom of the ligand
+    pub fn ligand_atom(&self) -> &str {
+        &self.ligand_atom
+    }
+
+    /// The other Residue, with the id of its Chain
+    pub fn residue(&self) -> ResidueId {
+        self.residue
+    }
+
+    /// The chain id of the other Residue
+    pub fn chain_id(&self) -> char {
+        self.residue.chain_id()
+    }
+
+    /// The serial number of the other Residue
+    pub fn residue_serial_number(&self) -> usize {
+        self.residue.serial_number()
+    }
+
+    /// The name of the other Residue
+    pub fn residue_name(&self) -> &str {
+        &self.residue_name
+    }
+
+    /// The name of the atom of the other Residue
+    pub fn residue_atom(&self) -> &str {
+        &self.residue_atom
+    }
+
+    /// The distance between the two atoms in Å
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// The key of this interaction in a fingerprint, the type of the interaction with the name
+    /// and serial number (with insertion code) of the other Residue, for example `Hydrogen bond
+    /// ASP 45`. The chain id is left out so fingerprints of structures with a different chain
+    /// naming can be compared.
+    pub fn key(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.kind,
+            self.residue_name,
+            self.residue.numbering_label()
+        )
+    }
+
+    /// The Tanimoto similarity of two fingerprints, the number of shared keys (see `key`) divided
+    /// by the number of distinct keys in both. This is 1.0 if both fingerprints are empty.
+    pub fn tanimoto(a: &[Interaction], b: &[Interaction]) -> f64 {
+        let a = a.iter().map(Interaction::key).collect::<BTreeSet<_>>();
+        let b = b.iter().map(Interaction::key).collect::<BTreeSet<_>>();
+        let union = a.union(&b).count();
+        if union == 0 {
+            1.0
+        } else {
+            a.intersection(&b).count() as f64 / union as f64
+        }
+    }
+}
+
+impl fmt::Display for Interaction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} - {} {} {} {} ({:.2} Å)",
+            self.kind,
+            self.ligand_atom,
+            self.residue.chain_id(),
+            self.residue_name,
+            self.residue.numbering_label(),
+            self.residue_atom,
+            self.distance
+        )
+    }
+}
@@ -0,0 +1,168 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+/// The symmetry operator of the identity, used when no symmetry operator is given
+const IDENTITY_OPERATOR: usize = 1555;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A LINK record, describing a connection between two Atoms of different Residues that is not
+/// part of the standard polymer backbone, like a glycosylation site or the coordination of a
+/// metal ion. Every Atom is identified by its name and alternate location, and by the name, chain
+/// id, serial number and insertion code of its Residue. Every Atom has a symmetry operator (like
+/// `1555` for the identity) to place it, for connections between symmetry related molecules.
+pub struct Link {
+    /// The names of the two Atoms
+    atom_names: [String; 2],
+    /// The alternate locations of the two Atoms
+    alternate_locations: [char; 2],
+    /// The names of the Residues of the two Atoms
+    residue_names: [String; 2],
+    /// The ids of the Chains containing the two Atoms
+    chain_ids: [char; 2],
+    /// The serial numbers of the Residues of the two Atoms
+    residue_serial_numbers: [usize; 2],
+    /// The insertion codes of the Residues of the two Atoms
+    insertion_codes: [char; 2],
+    /// The symmetry operators of the two Atoms
+    symmetry_operators: [usize; 2],
+    /// The length of the connection in Å, if given
+    length: Option<f64>,
+}
+
+impl Link {
+    /// Create a new Link record, with the identity as symmetry operators and no length
+    ///
+    /// ## Arguments
+    /// * `atom_names` - the names of the two Atoms
+    /// * `alternate_locations` - the alternate locations of the two Atoms, a space if there is none
+    /// * `residue_names` - the names of the Residues of the two Atoms
+    /// * `chain_ids` - the ids of the Chains containing the two Atoms
+    /// * `residue_serial_numbers` - the serial numbers of the Residues of the two Atoms
+    /// * `insertion_codes` - the insertion codes of the Residues of the two Atoms, a space if there is none
+    ///
+    /// ## Fails
+    /// It fails if any of the names, alternate locations, chain ids or insertion codes contain
+    /// invalid characters, or if a name is too long (four characters for the Atoms and three for
+    /// the Residues).
+    pub fn new(
+        atom_names: [&str; 2],
+        alternate_locations: [char; 2],
+        residue_names: [&str; 2],
+        chain_ids: [char; 2],
+        residue_serial_numbers: [usize; 2],
+        insertion_codes: [char; 2],
+    ) -> Option<Link> {
+        let atom_names = atom_names.map(str::trim);
+        let residue_names = residue_names.map(str::trim);
+        if atom_names.iter().any(|name| name.len() > 4)
+            || residue_names.iter().any(|name| name.len() > 3)
+            || !check_chars(format!("{}{}", atom_names.concat(), residue_names.concat()))
+            || !alternate_locations
+                .iter()
+                .chain(chain_ids.iter())
+                .chain(insertion_codes.iter())
+                .all(|c| check_char(*c))
+        {
+            return None;
+        }
+        Some(Link {
+            atom_names: atom_names.map(str::to_string),
+            alternate_locations,
+            residue_names: residue_names.map(str::to_string),
+            chain_ids,
+            residue_serial_numbers,
+            insertion_codes,
+            symmetry_operators: [IDENTITY_OPERATOR; 2],
+            length: None,
+        })
+    }
+
+    /// The names of the two Atoms
+    pub fn atom_names(&self) -> [&str; 2] {
+        [&self.atom_names[0], &self.atom_names[1]]
+    }
+
+    /// The alternate locations of the two Atoms, a space if there is none
+    pub fn alternate_locations(&self) -> [char; 2] {
+        self.alternate_locations
+    }
+
+    /// The names of the Residues of the two Atoms
+    pub fn residue_names(&self) -> [&str; 2] {
+        [&self.residue_names[0], &self.residue_names[1]]
+    }
+
+    /// The ids of the Chains containing the two Atoms
+    pub fn chain_ids(&self) -> [char; 2] {
+        self.chain_ids
+    }
+
+    /// The serial numbers of the Residues of the two Atoms
+    pub fn residue_serial_numbers(&self) -> [usize; 2] {
+        self.residue_serial_numbers
+    }
+
+    /// Set the chain ids and serial numbers of the Residues of the two Atoms, for example after renumbering
+    /// ## Fails
+    /// It fails if any of the chain ids is an invalid character.
+    pub fn set_residues(
+        &mut self,
+        chain_ids: [char; 2],
+        residue_serial_numbers: [usize; 2],
+    ) -> Result<(), String> {
+        if let Some(c) = chain_ids.iter().find(|c| !check_char(**c)) {
+            return Err(format!("The chain id '{}' is not a valid character", c));
+        }
+        self.chain_ids = chain_ids;
+        self.residue_serial_numbers = residue_serial_numbers;
+        Ok(())
+    }
+
+    /// The insertion codes of the Residues of the two Atoms, a space if there is none
+    pub fn insertion_codes(&self) -> [char; 2] {
+        self.insertion_codes
+    }
+
+    /// Set the insertion codes of the Residues of the two Atoms, for example after renumbering
+    /// ## Fails
+    /// It fails if any of the insertion codes is an invalid character.
+    pub fn set_insertion_codes(&mut self, insertion_codes: [char; 2]) -> Result<(), String> {
+        if let Some(c) = insertion_codes.iter().find(|c| !check_char(**c)) {
+            return Err(format!(
+                "The insertion code '{}' is not a valid character",
+                c
+            ));
+        }
+        self.insertion_codes = insertion_codes;
+        Ok(())
+    }
+
+    /// The symmetry operators of the two Atoms, as the number of the symmetry operation
+    /// followed by the translations along a, b and c plus five, so `1555` is the identity
+    pub fn symmetry_operators(&self) -> [usize; 2] {
+        self.symmetry_operators
+    }
+
+    /// Set the symmetry operators of the two Atoms, see `symmetry_operators`
+    pub fn set_symmetry_operators(&mut self, operators: [usize; 2]) {
+        self.symmetry_operators = operators;
+    }
+
+    /// The length of the connection in Å, if given
+    pub fn length(&self) -> Option<f64> {
+        self.length
+    }
+
+    /// Set the length of the connection in Å
+    pub fn set_length(&mut self, length: Option<f64>) {
+        self.length = length;
+    }
+
+    /// Returns `true` if one of the two Atoms is in the given Residue
+    pub fn contains(&self, chain_id: char, residue_serial_number: usize) -> bool {
+        (0..2).any(|index| {
+            self.chain_ids[index] == chain_id
+                && self.residue_serial_numbers[index] == residue_serial_number
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use crate::structs::*;
+
+/// The ideal backbone bond lengths in Å as (atom, atom, target, standard deviation), from
+/// Engh & Huber (2001) International Tables for Crystallography F, 382-392. The atom prefixed with
+/// `-` is taken from the previous Residue. Glycine and proline specific targets are not used.
+const BONDS: &[(&str, &str, f64, f64)] = &[
+    ("N", "CA", 1.459, 0.020),
+    ("CA", "C", 1.525, 0.026),
+    ("C", "O", 1.229, 0.019),
+    ("CA", "CB", 1.530, 0.020),
+    ("-C", "N", 1.336, 0.023),
+];
+
+/// The ideal backbone bond angles in degrees as (atom, atom, atom, target, standard deviation), from
+/// Engh & Huber (2001). The atom prefixed with `-` is taken from the previous Residue and the one
+/// prefixed with `+` from the next Residue.
+const ANGLES: &[(&str, &str, &str, f64, f64)] = &[
+    ("N", "CA", "C", 111.0, 2.7),
+    ("CA", "C", "O", 120.1, 2.1),
+    ("N", "CA", "CB", 110.6, 1.8),
+    ("CB", "CA", "C", 110.4, 2.0),
+    ("-C", "N", "CA", 121.7, 2.5),
+    ("CA", "C", "+N", 117.2, 2.2),
+    ("O", "C", "+N", 122.7, 1.6),
+];
+
+/// The centres (phi, psi) of the favoured regions of the Ramachandran plot per class, approximating
+/// the regions of Lovell et al. (2003) Proteins 50, 437-450 as used by MolProbity
+const RAMACHANDRAN_CENTRES: &[(RamachandranClass, f64, f64)] = &[
+    (RamachandranClass::General, -63.0, -43.0),
+    (RamachandranClass::General, -120.0, 130.0),
+    (RamachandranClass::General, -65.0, 145.0),
+    (RamachandranClass::General, 57.0, 47.0),
+    (RamachandranClass::Glycine, -63.0, -43.0),
+    (RamachandranClass::Glycine, 63.0, 43.0),
+    (RamachandranClass::Glycine, -80.0, 180.0),
+    (RamachandranClass::Glycine, 80.0, 180.0),
+    (RamachandranClass::Proline, -63.0, -35.0),
+    (RamachandranClass::Proline, -65.0, 145.0),
+    (RamachandranClass::PreProline, -63.0, -43.0),
+    (RamachandranClass::PreProline, -120.0, 130.0),
+    (RamachandranClass::PreProline, -65.0, 145.0),
+];
+
+/// The width in degrees of a favoured region of the Ramachandran plot, taken as one standard deviation
+const RAMACHANDRAN_WIDTH: f64 = 20.0;
+
+/// Calculate the local geometry score of the given Residue, combining its backbone bond lengths,
+/// bond angles and Ramachandran position into a single Z-score, where 0.0 is ideal geometry and
+/// values above 2.0 flag problematic Residues.
+///
+/// MolProbity reports the covalent geometry as the root mean square of the Z-scores of all bonds
+/// and of all angles (RMSZ, Chen et al. (2010) Acta Cryst. D66, 12-21), those are the first two
+/// terms. The Ramachandran term is the angular distance to the nearest favoured region in units of
+/// its width. Following the equal footing MolProbity gives bond and angle outliers (both flagged
+/// at 4σ), the three terms are combined as their root mean square. Terms that cannot be calculated
+/// (like the Ramachandran term at the ends of a Chain) are left out.
+///
+/// It returns `None` if the Residue is not an amino acid or misses any of its N, CA or C atoms.
+pub(crate) fn residue_score(
+    previous: Option<&Residue>,
+    residue: &Residue,
+    next: Option<&Residue>,
+    phi_psi: Option<(f64, f64)>,
+    class: RamachandranClass,
+) -> Option<f64> {
+    if !residue.amino_acid() {
+        return None;
+    }
+    residue.atom_by_name("N")?;
+    residue.atom_by_name("CA")?;
+    residue.atom_by_name("C")?;
+
+    let find = |name: &str| match name.split_at(1) {
+        ("-", name) => previous?.atom_by_name(name),
+        ("+", name) => next?.atom_by_name(name),
+        _ => residue.atom_by_name(name),
+    };
+    let bonds = BONDS
+        .iter()
+        .filter_map(|(a, b, target, sigma)| Some((find(a)?.distance(find(b)?) - target) / sigma))
+        .collect::<Vec<f64>>();
+    let angles = ANGLES
+        .iter()
+        .filter_map(|(a, b, c, target, sigma)| {
+            Some((find(a)?.angle(find(b)?, find(c)?) - target) / sigma)
+        })
+        .collect::<Vec<f64>>();
+
+    let mut terms = vec![rmsz(&bonds), rmsz(&angles)];
+    if let Some((phi, psi)) = phi_psi {
+        terms.push(ramachandran_z(phi, psi, class));
+    }
+    let terms = terms.into_iter().flatten().collect::<Vec<f64>>();
+    rmsz(&terms)
+}
+
+/// The root mean square of the given Z-scores, `None` if there are none
+fn rmsz(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some((values.iter().map(|v| v * v).sum::<f64>() / values.len() as f64).sqrt())
+    }
+}
+
+/// The angular distance of the given backbone dihedrals to the nearest favoured region of the
+/// Ramachandran plot of the given class, in units of the width of the regions
+fn ramachandran_z(phi: f64, psi: f64, class: RamachandranClass) -> Option<f64> {
+    let difference = |a: f64, b: f64| {
+        let d = (a - b).rem_euclid(360.0);
+        d.min(360.0 - d)
+    };
+    RAMACHANDRAN_CENTRES
+        .iter()
+        .filter(|(c, _, _)| *c == class)
+        .map(|(_, centre_phi, centre_psi)| {
+            (difference(phi, *centre_phi).powi(2) + difference(psi, *centre_psi).powi(2)).sqrt()
+        })
+        .min_by(|a, b| a.partial_cmp(b).unwrap())
+        .map(|distance| distance / RAMACHANDRAN_WIDTH)
+}
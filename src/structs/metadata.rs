@@ -0,0 +1,72 @@
+#![allow(dead_code)]
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A value of a metadata entry of a Model, for example the score of a docking pose or the time of a trajectory frame
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataValue {
+    /// A numeric value
+    Number(f64),
+    /// A textual value
+    Text(String),
+}
+
+impl MetadataValue {
+    /// Parse a value from text, it is a `Number` if the text is a valid number otherwise it is `Text`
+    pub fn parse(text: &str) -> MetadataValue {
+        let text = text.trim();
+        match text.parse::<f64>() {
+            Ok(number) => MetadataValue::Number(number),
+            Err(_) => MetadataValue::Text(text.to_string()),
+        }
+    }
+
+    /// Get the numeric value, if this is a `Number`
+    pub fn number(&self) -> Option<f64> {
+        match self {
+            MetadataValue::Number(number) => Some(*number),
+            MetadataValue::Text(_) => None,
+        }
+    }
+
+    /// Get the textual value, if this is `Text`
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            MetadataValue::Number(_) => None,
+            MetadataValue::Text(text) => Some(text),
+        }
+    }
+}
+
+impl From<f64> for MetadataValue {
+    fn from(number: f64) -> Self {
+        MetadataValue::Number(number)
+    }
+}
+
+impl From<&str> for MetadataValue {
+    fn from(text: &str) -> Self {
+        MetadataValue::Text(text.to_string())
+    }
+}
+
+impl PartialOrd for MetadataValue {
+    /// Numbers are ordered numerically, text lexicographically and numbers are placed before text
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (MetadataValue::Number(a), MetadataValue::Number(b)) => a.partial_cmp(b),
+            (MetadataValue::Text(a), MetadataValue::Text(b)) => a.partial_cmp(b),
+            (MetadataValue::Number(_), MetadataValue::Text(_)) => Some(Ordering::Less),
+            (MetadataValue::Text(_), MetadataValue::Number(_)) => Some(Ordering::Greater),
+        }
+    }
+}
+
+impl fmt::Display for MetadataValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MetadataValue::Number(number) => write!(f, "{}", number),
+            MetadataValue::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
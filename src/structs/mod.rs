@@ -1,23 +1,72 @@
+mod assembly;
 mod atom;
+mod atom_id;
+mod caveat;
 mod chain;
+mod cis_peptide;
+mod composition;
+mod conformer;
+mod database_reference;
+mod date;
+mod entity_type;
+mod entry_replacement;
+mod experimental_method;
+mod gap;
+mod helix;
 mod helper;
+mod het;
+mod heterogen;
+mod interaction;
+mod link;
+mod local_geometry;
+mod metadata;
 mod model;
 mod mtrix;
 mod origx;
 mod pdb;
+mod ramachandran_class;
 mod residue;
+mod residue_id;
 mod scale;
+mod selection;
+mod site;
+mod ssbond;
+mod structure_comparison;
 mod symmetry;
 mod unit_cell;
 
+pub use assembly::Assembly;
 pub use atom::Atom;
+pub use atom_id::AtomId;
+pub use caveat::Caveat;
 pub use chain::Chain;
-use helper::*;
+pub use cis_peptide::CisPeptide;
+pub use composition::{ChainComposition, Composition, ModelComposition};
+pub use conformer::Conformer;
+pub use database_reference::{DatabaseReference, SequenceDifference};
+pub use date::Date;
+pub use entity_type::EntityType;
+pub use entry_replacement::EntryReplacement;
+pub use experimental_method::ExperimentalMethod;
+pub use gap::Gap;
+pub use helix::Helix;
+pub(crate) use helper::*;
+pub use het::Het;
+pub use heterogen::Heterogen;
+pub use interaction::{Interaction, InteractionCriteria, InteractionType};
+pub use link::Link;
+pub use metadata::MetadataValue;
 pub use model::Model;
 pub use mtrix::MtriX;
 pub use origx::OrigX;
 pub use pdb::PDB;
+pub use ramachandran_class::RamachandranClass;
 pub use residue::Residue;
+pub use residue_id::ResidueId;
 pub use scale::Scale;
+pub use selection::Selection;
+pub use site::Site;
+pub use ssbond::SSBond;
+pub use structure_comparison::StructureComparison;
 pub use symmetry::Symmetry;
 pub use unit_cell::UnitCell;
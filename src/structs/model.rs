@@ -1,9 +1,30 @@
 #![allow(dead_code)]
+use crate::error::*;
+use crate::reference_tables;
 use crate::structs::*;
 use crate::transformation::*;
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The (chain id, Residue numbering, Atom name, alternate location) used to pair the Atoms
+/// of two Models
+type AtomKey = (char, (usize, Option<char>), String, char);
+
+/// The positions of paired Atoms in two Models
+type PairedPositions = (Vec<(f64, f64, f64)>, Vec<(f64, f64, f64)>);
 
 #[derive(Debug)]
 /// A Model containing multiple Chains
+///
+/// ## Ordering
+/// The Atoms of a Residue are iterated in the order they were added, the Residues of a Chain and
+/// the Chains of a Model in the order they are stored. The `all_*` iterators give all Normal
+/// Chains (with their Residues and Atoms) before all Hetero Chains, and the accessors by index
+/// (`chain`, `residue`, `atom` and their `_mut` versions) use this same order. This is also the
+/// order in which the Atoms are saved and renumbered, unless the `SaveOptions` ask otherwise.
+/// Adding appends at the end, removing keeps the relative order of the remaining items, and
+/// the methods that reorder (like `sort_chains`) state their effect.
 pub struct Model {
     /// The serial number of this Model
     serial_number: usize,
@@ -11,8 +32,15 @@ pub struct Model {
     chains: Vec<Chain>,
     /// The Chains with Hetero Atoms making up this model
     hetero_chains: Vec<Chain>,
+    /// The metadata of this model, for example the score of a docking pose
+    metadata: BTreeMap<String, MetadataValue>,
+    /// A number unique to the current state of this Model, renewed by every structural change
+    revision: u64,
 }
 
+/// The last revision given out to a Model, revisions are unique over all Models
+static LAST_REVISION: AtomicU64 = AtomicU64::new(0);
+
 impl Model {
     /// Create a new Model
     ///
@@ -23,9 +51,24 @@ impl Model {
             serial_number,
             chains: Vec::new(),
             hetero_chains: Vec::new(),
+            metadata: BTreeMap::new(),
+            revision: LAST_REVISION.fetch_add(1, Ordering::Relaxed) + 1,
         }
     }
 
+    /// Get the revision of this Model, a number unique to its current state. Any structural change
+    /// (any method taking `&mut self`, except for the metadata) gives the Model a new revision, a
+    /// clone keeps the revision as its state is the same. Used to only validate changed Models, see
+    /// `validate_incremental`.
+    pub(crate) fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Give this Model a new revision, to be called before any structural change
+    fn touch(&mut self) {
+        self.revision = LAST_REVISION.fetch_add(1, Ordering::Relaxed) + 1;
+    }
+
     /// The serial number of this Model
     pub fn serial_number(&self) -> usize {
         self.serial_number
@@ -33,9 +76,43 @@ impl Model {
 
     /// Set the serial number of this Model
     pub fn set_serial_number(&mut self, new_number: usize) {
+        self.touch();
         self.serial_number = new_number;
     }
 
+    /// Get the metadata value stored under the given key
+    /// ## Fails
+    /// It fails if there is no metadata with this key.
+    pub fn metadata(&self, key: &str) -> Option<&MetadataValue> {
+        self.metadata.get(key)
+    }
+
+    /// Get all metadata of this Model as (key, value) pairs, sorted by key
+    pub fn all_metadata(&self) -> impl DoubleEndedIterator<Item = (&String, &MetadataValue)> + '_ {
+        self.metadata.iter()
+    }
+
+    /// Set the metadata value for the given key, replacing any previous value
+    /// ## Fails
+    /// It fails if the key is empty, contains " = " or contains invalid characters (only ASCII
+    /// graphic and space is allowed), as it could then not be saved.
+    pub fn set_metadata(&mut self, key: &str, value: MetadataValue) -> Result<(), String> {
+        if key.trim().is_empty() || key.contains(" = ") || !check_chars(key.to_string()) {
+            Err(format!(
+                "Invalid metadata key \"{}\" for model {}",
+                key, self.serial_number
+            ))
+        } else {
+            self.metadata.insert(key.trim().to_string(), value);
+            Ok(())
+        }
+    }
+
+    /// Remove the metadata with the given key, returning its value if it was present
+    pub fn remove_metadata(&mut self, key: &str) -> Option<MetadataValue> {
+        self.metadata.remove(key)
+    }
+
     /// Get the amount of Chains making up this Model.
     /// This disregards all Hetero Chains.
     pub fn chain_count(&self) -> usize {
@@ -94,6 +171,7 @@ impl Model {
     /// ## Fails
     /// It fails when the index is outside bounds.
     pub fn chain_mut(&mut self, index: usize) -> Option<&mut Chain> {
+        self.touch();
         self.all_chains_mut().nth(index)
     }
 
@@ -116,6 +194,7 @@ impl Model {
     /// ## Fails
     /// It fails when the index is outside bounds.
     pub fn residue_mut(&mut self, index: usize) -> Option<&mut Residue> {
+        self.touch();
         self.all_residues_mut().nth(index)
     }
 
@@ -138,6 +217,7 @@ impl Model {
     /// ## Fails
     /// It fails when the index is outside bounds.
     pub fn atom_mut(&mut self, index: usize) -> Option<&mut Atom> {
+        self.touch();
         self.all_atoms_mut().nth(index)
     }
 
@@ -152,6 +232,7 @@ impl Model {
     /// This disregards all Hetero Chains.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn chains_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Chain> + '_ {
+        self.touch();
         self.chains.iter_mut()
     }
 
@@ -166,6 +247,7 @@ impl Model {
     /// This disregards all Hetero Residues.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn residues_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Residue> + '_ {
+        self.touch();
         self.chains.iter_mut().flat_map(|a| a.residues_mut())
     }
 
@@ -180,6 +262,7 @@ impl Model {
     /// This disregards all Hetero Atoms.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn atoms_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Atom> + '_ {
+        self.touch();
         self.chains.iter_mut().flat_map(|a| a.atoms_mut())
     }
 
@@ -194,6 +277,7 @@ impl Model {
     /// This disregards all Normal Chains.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn hetero_chains_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Chain> + '_ {
+        self.touch();
         self.hetero_chains.iter_mut()
     }
 
@@ -208,10 +292,8 @@ impl Model {
     /// This disregards all Normal Residues
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn hetero_residues_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Residue> + '_ {
-        self.hetero_chains
-            .iter_mut()
-            .map(|a| a.residues_mut())
-            .flatten()
+        self.touch();
+        self.hetero_chains.iter_mut().flat_map(|a| a.residues_mut())
     }
 
     /// Get the list of Atoms making up this Model.
@@ -225,10 +307,8 @@ impl Model {
     /// This disregards all Normal Atoms.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn hetero_atoms_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Atom> + '_ {
-        self.hetero_chains
-            .iter_mut()
-            .map(|a| a.atoms_mut())
-            .flatten()
+        self.touch();
+        self.hetero_chains.iter_mut().flat_map(|a| a.atoms_mut())
     }
 
     /// Get the list of Chains making up this Model.
@@ -242,6 +322,7 @@ impl Model {
     /// This includes all Normal and Hetero Chains.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn all_chains_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Chain> + '_ {
+        self.touch();
         self.chains.iter_mut().chain(self.hetero_chains.iter_mut())
     }
 
@@ -251,8 +332,7 @@ impl Model {
     pub fn all_residues(&self) -> impl DoubleEndedIterator<Item = &Residue> + '_ {
         self.chains
             .iter()
-            .map(|a| a.residues())
-            .flatten()
+            .flat_map(|a| a.residues())
             .chain(self.hetero_chains.iter().flat_map(|a| a.residues()))
     }
 
@@ -260,6 +340,7 @@ impl Model {
     /// This includes all Normal and Hetero Residues
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn all_residues_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Residue> + '_ {
+        self.touch();
         self.chains
             .iter_mut()
             .flat_map(|a| a.residues_mut())
@@ -276,35 +357,110 @@ impl Model {
             .chain(self.hetero_chains.iter().flat_map(|a| a.atoms()))
     }
 
+    /// Get the Chain with the given id, the Normal Chain is returned if there is also a Hetero
+    /// Chain with this id.
+    ///
+    /// ## Fails
+    /// It fails when no Chain has the given id.
+    pub fn chain_by_id(&self, id: char) -> Option<&Chain> {
+        self.all_chains().find(|chain| chain.id() == id)
+    }
+
+    /// Get the Chain with the given id as a mutable reference, see `chain_by_id`.
+    ///
+    /// ## Fails
+    /// It fails when no Chain has the given id.
+    pub fn chain_by_id_mut(&mut self, id: char) -> Option<&mut Chain> {
+        self.all_chains_mut().find(|chain| chain.id() == id)
+    }
+
+    /// Get the Residue with the given serial number and insertion code in the Chain with the given
+    /// id. Both Normal and Hetero Chains with this id are searched.
+    ///
+    /// ## Fails
+    /// It fails when no Residue matches the chain id, serial number and insertion code.
+    pub fn residue_by_id(&self, id: ResidueId) -> Option<&Residue> {
+        self.all_chains()
+            .filter(|chain| chain.id() == id.chain_id())
+            .flat_map(|chain| chain.residues())
+            .find(|residue| residue.numbering() == (id.serial_number(), id.insertion_code()))
+    }
+
+    /// Get the Residue with the given serial number and insertion code in the Chain with the given
+    /// id as a mutable reference, see `residue_by_id`.
+    ///
+    /// ## Fails
+    /// It fails when no Residue matches the chain id, serial number and insertion code.
+    pub fn residue_by_id_mut(&mut self, id: ResidueId) -> Option<&mut Residue> {
+        self.all_chains_mut()
+            .filter(|chain| chain.id() == id.chain_id())
+            .flat_map(|chain| chain.residues_mut())
+            .find(|residue| residue.numbering() == (id.serial_number(), id.insertion_code()))
+    }
+
+    /// Get all Atoms (Normal and Hetero) of this Model together with the Chain and Residue they
+    /// are part of.
+    pub fn atoms_with_hierarchy(&self) -> impl Iterator<Item = (&Chain, &Residue, &Atom)> + '_ {
+        self.all_chains().flat_map(|chain| {
+            chain
+                .residues()
+                .flat_map(move |residue| residue.atoms().map(move |atom| (chain, residue, atom)))
+        })
+    }
+
+    /// Get the Atoms (Normal and Hetero) of this Model with an alternate location identifier, so
+    /// all Atoms with an alternate location other than blank (' ').
+    pub fn atoms_with_altloc(&self) -> impl DoubleEndedIterator<Item = &Atom> + '_ {
+        self.all_atoms()
+            .filter(|atom| atom.alternate_location() != ' ')
+    }
+
+    /// Get the distinct alternate location identifiers used in this Model, excluding the blank (' ') identifier
+    pub fn altloc_characters(&self) -> BTreeSet<char> {
+        self.atoms_with_altloc()
+            .map(|atom| atom.alternate_location())
+            .collect()
+    }
+
     /// Get the list of Atoms as mutable references making up this Model.
     /// This includes all Normal and Hetero Atoms.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn all_atoms_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Atom> + '_ {
+        self.touch();
         self.chains
             .iter_mut()
             .flat_map(|a| a.atoms_mut())
             .chain(self.hetero_chains.iter_mut().flat_map(|a| a.atoms_mut()))
     }
 
-    /// Add a new Atom to this Model. It finds if there already is a Chain with the given `chain_id` if there is it will add this atom to that Chain, otherwise it will create a new Chain and add that to the list of Chains making up this Model. It does the same for the Residue, so it will create a new one if there does not yet exist a Residue with the given serial number.
+    /// Add a new Atom to this Model. It finds if there already is a Chain with the given `chain_id` if there is it will add this atom to that Chain, otherwise it will create a new Chain and add that to the list of Chains making up this Model. It does the same for the Residue, so it will create a new one if there does not yet exist a Residue with the given serial number and insertion code.
+    /// New Atoms, Residues and Chains are added at the end, regardless of their serial numbers or ids.
     ///
     /// ## Arguments
     /// * `new_atom` - the new Atom to add
     /// * `chain_id` - the id of the Chain to add the Atom to
     /// * `residue_serial_number` - the serial number of the Residue to add the Atom to
+    /// * `insertion_code` - the insertion code of the Residue to add the Atom to, if any
     /// * `residue_name` - the name of the Residue to add the Atom to, only used to create a new Residue if needed
     ///
     /// ## Panics
-    /// It panics if the Chain id or Residue name contains any invalid characters.
+    /// It panics if the Chain id, insertion code or Residue name contains any invalid characters, the message lists the offending characters.
     pub fn add_atom(
         &mut self,
         new_atom: Atom,
         chain_id: char,
         residue_serial_number: usize,
+        insertion_code: Option<char>,
         residue_name: [char; 3],
     ) {
+        self.touch();
         let mut found = false;
-        let mut new_chain = Chain::new(chain_id).expect("Invalid characters in chain creation");
+        let mut new_chain = Chain::new(chain_id).unwrap_or_else(|| {
+            panic!(
+                "Invalid chain id {}, only alphanumeric ASCII characters and space are allowed",
+                describe_invalid_chars(&chain_id.to_string(), check_chain_id).unwrap()
+            )
+        });
         let mut current_chain = &mut new_chain;
         for chain in &mut self.chains {
             if chain.id() == chain_id {
@@ -316,32 +472,45 @@ impl Model {
         if !found {
             // As this moves the chain the atom should be added later to keep the reference intact
             self.chains.push(new_chain);
-            current_chain = (&mut self.chains).last_mut().unwrap();
+            current_chain = self.chains.last_mut().unwrap();
         }
 
-        current_chain.add_atom(new_atom, residue_serial_number, residue_name);
+        current_chain.add_atom(
+            new_atom,
+            residue_serial_number,
+            insertion_code,
+            residue_name,
+        );
     }
 
-    /// Add a new Atom to the hetero Atoms of this Model. It finds if there already is a Chain with the given `chain_id` if there is it will add this atom to that Chain, otherwise it will create a new Chain and add that to the list of Chains making up this Model. It does the same for the Residue, so it will create a new one if there does not yet exist a Residue with the given serial number.
+    /// Add a new Atom to the hetero Atoms of this Model, marking it as a hetero Atom (see `Atom::hetero`). It finds if there already is a Chain with the given `chain_id` if there is it will add this atom to that Chain, otherwise it will create a new Chain and add that to the list of Chains making up this Model. It does the same for the Residue, so it will create a new one if there does not yet exist a Residue with the given serial number and insertion code.
+    /// New Atoms, Residues and Chains are added at the end, regardless of their serial numbers or ids.
     ///
     /// ## Arguments
     /// * `new_atom` - the new Atom to add
     /// * `chain_id` - the id of the Chain to add the Atom to
     /// * `residue_serial_number` - the serial number of the Residue to add the Atom to
+    /// * `insertion_code` - the insertion code of the Residue to add the Atom to, if any
     /// * `residue_name` - the name of the Residue to add the Atom to, only used to create a new Residue if needed
     ///
     /// ## Panics
-    /// It panics if the Chain id or Residue name contains any invalid characters.
+    /// It panics if the Chain id, insertion code or Residue name contains any invalid characters, the message lists the offending characters.
     pub fn add_hetero_atom(
         &mut self,
         new_atom: Atom,
         chain_id: char,
         residue_serial_number: usize,
+        insertion_code: Option<char>,
         residue_name: [char; 3],
     ) {
+        self.touch();
         let mut found = false;
-        let mut new_chain = Chain::new(chain_id)
-            .unwrap_or_else(|| panic!("Invalid characters in chain creation ({})", chain_id));
+        let mut new_chain = Chain::new(chain_id).unwrap_or_else(|| {
+            panic!(
+                "Invalid chain id {}, only alphanumeric ASCII characters and space are allowed",
+                describe_invalid_chars(&chain_id.to_string(), check_chain_id).unwrap()
+            )
+        });
         let mut current_chain = &mut new_chain;
         for chain in &mut self.hetero_chains {
             if chain.id() == chain_id {
@@ -355,47 +524,80 @@ impl Model {
             current_chain = self.hetero_chains.last_mut().unwrap();
         }
 
-        current_chain.add_atom(new_atom, residue_serial_number, residue_name);
+        let mut new_atom = new_atom;
+        new_atom.set_hetero(true);
+        current_chain.add_atom(
+            new_atom,
+            residue_serial_number,
+            insertion_code,
+            residue_name,
+        );
     }
 
     /// Add a Chain to the list of Chains making up this Model. This does not detect any duplicates of names or serial numbers in the list of Chains.
     fn add_chain(&mut self, chain: Chain) {
+        self.touch();
         self.chains.push(chain);
     }
 
     /// Add a Chain to the list of Hetero Chains making up this Model. This does not detect any duplicates of names or serial numbers in the list of Chains.
     fn add_hetero_chain(&mut self, chain: Chain) {
+        self.touch();
         self.hetero_chains.push(chain);
     }
 
     /// Remove all Atoms matching the given predicate. The predicate will be run on all Atoms (Normal and Hetero).
-    /// As this is done in place this is the fastest way to remove Atoms from this Model.
+    /// As this is done in place this is the fastest way to remove Atoms from this Model. The remaining Atoms keep their order.
     pub fn remove_atoms_by<F>(&mut self, predicate: F)
     where
         F: Fn(&Atom) -> bool,
     {
+        self.touch();
         for residue in self.all_residues_mut() {
             residue.remove_atoms_by(&predicate);
         }
     }
 
+    /// Apply the given function to every Residue (Normal and Hetero) in this Model, to edit the
+    /// Residues in place, see `Chain::map_residues_mut`.
+    pub fn map_residues_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut Residue),
+    {
+        self.touch();
+        for chain in self.all_chains_mut() {
+            chain.map_residues_mut(&mut f);
+        }
+    }
+
+    /// Get the Residues (Normal and Hetero) in this Model with the given name, for example "ALA"
+    pub fn residues_by_name<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl DoubleEndedIterator<Item = &'a Residue> + 'a {
+        self.all_residues()
+            .filter(move |residue| residue.id() == name)
+    }
+
     /// Remove all Residues matching the given predicate. The predicate will be run on all Residues (Normal and Hetero).
-    /// As this is done in place this is the fastest way to remove Residues from this Model.
+    /// As this is done in place this is the fastest way to remove Residues from this Model. The remaining Residues keep their order.
     pub fn remove_residues_by<F>(&mut self, predicate: F)
     where
         F: Fn(&Residue) -> bool,
     {
+        self.touch();
         for chain in self.all_chains_mut() {
             chain.remove_residues_by(&predicate);
         }
     }
 
     /// Remove all Chains matching the given predicate. The predicate will be run on all Chains (Normal and Hetero).
-    /// As this is done in place this is the fastest way to remove Chains from this Model.
+    /// As this is done in place this is the fastest way to remove Chains from this Model. The remaining Chains keep their order.
     pub fn remove_chains_by<F>(&mut self, predicate: F)
     where
         F: Fn(&Chain) -> bool,
     {
+        self.touch();
         let chains = std::mem::take(&mut self.chains);
         self.chains
             .extend(chains.into_iter().filter(|chain| !predicate(chain)));
@@ -404,7 +606,93 @@ impl Model {
             .extend(hetero_chains.into_iter().filter(|chain| !predicate(chain)));
     }
 
-    /// Remove the Chain specified.
+    /// Remove all Atoms matching the given predicate and return them, each with the AtomId telling
+    /// where it came from. The predicate will be run on all Atoms (Normal and Hetero). The Residues
+    /// and Chains left empty are removed as well. The removed Atoms are returned in the order they
+    /// were in this Model and the remaining Atoms keep their order. See `remove_atoms_by` to
+    /// discard the Atoms instead.
+    pub fn drain_atoms_by<F>(&mut self, predicate: F) -> Vec<(AtomId, Atom)>
+    where
+        F: Fn(&Atom) -> bool,
+    {
+        self.touch();
+        let serial_number = self.serial_number;
+        let mut removed = Vec::new();
+        for chain in self.chains.iter_mut().chain(self.hetero_chains.iter_mut()) {
+            let chain_id = chain.id();
+            for residue in chain.residues_mut() {
+                let residue_id =
+                    ResidueId::new(chain_id, residue.serial_number(), residue.insertion_code());
+                removed.extend(residue.drain_atoms_by(&predicate).into_iter().map(|atom| {
+                    let id = AtomId::new(
+                        serial_number,
+                        residue_id,
+                        &atom.name(),
+                        atom.alternate_location(),
+                    );
+                    (id, atom)
+                }));
+            }
+            chain.remove_residues_by(|residue| residue.atom_count() == 0);
+        }
+        self.remove_chains_by(|chain| chain.residue_count() == 0);
+        removed
+    }
+
+    /// Remove all Residues matching the given predicate and return them as a new Model with the
+    /// same serial number, with the Residues in Chains with the same ids as they came from (Normal
+    /// or Hetero). The removed Residues can be added back with `join`. The predicate will be run on
+    /// all Residues (Normal and Hetero). The Chains left empty are removed as well. Both the removed
+    /// and the remaining Residues keep their order. See `remove_residues_by` to discard the
+    /// Residues instead.
+    pub fn drain_residues_by<F>(&mut self, predicate: F) -> Model
+    where
+        F: Fn(&Residue) -> bool,
+    {
+        self.touch();
+        let drain = |chains: &mut Vec<Chain>| {
+            let mut removed = Vec::new();
+            for chain in chains.iter_mut() {
+                let residues = chain.drain_residues_by(&predicate);
+                if !residues.is_empty() {
+                    let mut removed_chain = Chain::new(chain.id()).unwrap();
+                    for residue in residues {
+                        removed_chain.add_residue(residue);
+                    }
+                    removed.push(removed_chain);
+                }
+            }
+            chains.retain(|chain| chain.residue_count() > 0);
+            removed
+        };
+        let mut removed = Model::new(self.serial_number);
+        removed.chains = drain(&mut self.chains);
+        removed.hetero_chains = drain(&mut self.hetero_chains);
+        removed
+    }
+
+    /// Remove all Chains matching the given predicate and return them as a new Model with the same
+    /// serial number, keeping Normal and Hetero Chains apart. The removed Chains can be added back
+    /// with `join`. The predicate will be run on all Chains (Normal and Hetero). Both the removed
+    /// and the remaining Chains keep their order. See `remove_chains_by` to discard the Chains
+    /// instead.
+    pub fn drain_chains_by<F>(&mut self, predicate: F) -> Model
+    where
+        F: Fn(&Chain) -> bool,
+    {
+        self.touch();
+        let mut removed = Model::new(self.serial_number);
+        (removed.chains, self.chains) = std::mem::take(&mut self.chains)
+            .into_iter()
+            .partition(|chain| predicate(chain));
+        (removed.hetero_chains, self.hetero_chains) = std::mem::take(&mut self.hetero_chains)
+            .into_iter()
+            .partition(|chain| predicate(chain));
+        removed
+    }
+
+    /// Remove the Chain specified. The index is the same as for `chain`, so it counts the Normal
+    /// Chains before the Hetero Chains. The remaining Chains keep their order.
     ///
     /// ## Arguments
     /// * `index` - the index of the Chain to remove
@@ -412,16 +700,23 @@ impl Model {
     /// ## Panics
     /// It panics when the index is outside bounds.
     pub fn remove_chain(&mut self, index: usize) {
-        self.chains.remove(index);
+        self.touch();
+        if index < self.chains.len() {
+            self.chains.remove(index);
+        } else {
+            self.hetero_chains.remove(index - self.chains.len());
+        }
     }
 
     /// Remove the Chain specified. It returns `true` if it found a matching Chain and removed it.
-    /// It removes the first matching Chain from the list.
+    /// It removes the first matching Chain from the list, looking at the Normal Chains before the
+    /// Hetero Chains.
     ///
     /// ## Arguments
     /// * `id` - the id of the Chain to remove
     pub fn remove_chain_id(&mut self, id: char) -> bool {
-        let index = self.chains.iter().position(|a| a.id() == id);
+        self.touch();
+        let index = self.all_chains().position(|a| a.id() == id);
 
         if let Some(i) = index {
             self.remove_chain(i);
@@ -431,22 +726,807 @@ impl Model {
         }
     }
 
+    /// Remove all water Residues (Normal and Hetero) from this Model, including the residue names
+    /// registered as water in the `Classifier`. Chains left without Residues are removed as well.
+    /// The remaining Residues and Chains keep their order. It returns the number of removed Residues.
+    pub fn remove_waters(&mut self) -> usize {
+        let is_water = |residue: &Residue| reference_tables::is_water_residue(&residue.id());
+        let count = self
+            .all_residues()
+            .filter(|residue| is_water(residue))
+            .count();
+        if count > 0 {
+            self.remove_residues_by(is_water);
+            self.chains.retain(|chain| chain.residue_count() > 0);
+            self.hetero_chains.retain(|chain| chain.residue_count() > 0);
+        }
+        count
+    }
+
+    /// Remove the alternate conformations of all Residues (Normal and Hetero) of this Model by
+    /// keeping only the copy with the highest occupancy of every Atom, see
+    /// `Residue::remove_alternate_conformers`. It returns the number of removed Atoms.
+    pub fn remove_alternate_conformers(&mut self) -> usize {
+        self.all_residues_mut()
+            .map(|residue| residue.remove_alternate_conformers())
+            .sum()
+    }
+
+    /// Remove duplicated waters, as often found after symmetry expansion or after combining files.
+    /// Of any pair of water oxygens within the cutoff distance the water with the lower occupancy,
+    /// or the later one if the occupancies are equal, is removed. The whole water Residue is removed
+    /// and Chains left empty by this are removed as well. Other Residues are never touched.
+    /// It returns the number of removed waters.
+    ///
+    /// ## Arguments
+    /// * `cutoff` - the maximal distance in Å between the oxygens of duplicated waters
+    pub fn deduplicate_waters(&mut self, cutoff: f64) -> usize {
+        // The waters as (chain index in all_chains, residue index, oxygen)
+        let waters = self
+            .all_chains()
+            .enumerate()
+            .flat_map(|(chain_index, chain)| {
+                chain
+                    .residues()
+                    .enumerate()
+                    .filter(|(_, residue)| reference_tables::is_water_residue(&residue.id()))
+                    .filter_map(move |(residue_index, residue)| {
+                        let oxygen = residue.atoms().find(|atom| atom.element() == "O")?;
+                        Some((chain_index, residue_index, oxygen))
+                    })
+            })
+            .collect::<Vec<(usize, usize, &Atom)>>();
+        if waters.is_empty() || cutoff <= 0.0 || !cutoff.is_finite() {
+            return 0;
+        }
+
+        // Put all oxygens in a grid with cells of the cutoff size to only look at neighbouring cells
+        let cell = |atom: &Atom| {
+            (
+                (atom.x() / cutoff).floor() as i64,
+                (atom.y() / cutoff).floor() as i64,
+                (atom.z() / cutoff).floor() as i64,
+            )
+        };
+        let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+        for (index, water) in waters.iter().enumerate() {
+            grid.entry(cell(water.2)).or_default().push(index);
+        }
+
+        let mut removed = vec![false; waters.len()];
+        for index in 0..waters.len() {
+            if removed[index] {
+                continue;
+            }
+            let oxygen = waters[index].2;
+            let (x, y, z) = cell(oxygen);
+            let mut neighbours: Vec<usize> = Vec::new();
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        neighbours
+                            .extend(grid.get(&(x + dx, y + dy, z + dz)).into_iter().flatten());
+                    }
+                }
+            }
+            neighbours.sort_unstable();
+            for other in neighbours {
+                if other <= index || removed[other] || oxygen.distance(waters[other].2) > cutoff {
+                    continue;
+                }
+                if oxygen.occupancy() < waters[other].2.occupancy() {
+                    removed[index] = true;
+                    break;
+                }
+                removed[other] = true;
+            }
+        }
+
+        let mut to_remove = waters
+            .iter()
+            .zip(&removed)
+            .filter(|(_, removed)| **removed)
+            .map(|(water, _)| (water.0, water.1))
+            .collect::<Vec<_>>();
+        let count = to_remove.len();
+        if count == 0 {
+            return 0;
+        }
+        // Remove from the back to keep the indices of the remaining waters valid
+        to_remove.sort_unstable();
+        self.touch();
+        let normal_chains = self.chains.len();
+        for (chain_index, residue_index) in to_remove.into_iter().rev() {
+            let chain = if chain_index < normal_chains {
+                &mut self.chains[chain_index]
+            } else {
+                &mut self.hetero_chains[chain_index - normal_chains]
+            };
+            chain.remove_residue_by_id(residue_index);
+            if chain.residue_count() == 0 {
+                if chain_index < normal_chains {
+                    self.chains.remove(chain_index);
+                } else {
+                    self.hetero_chains.remove(chain_index - normal_chains);
+                }
+            }
+        }
+        count
+    }
+
+    /// Renumber the water Residues (Normal and Hetero) of every Chain to a contiguous block, so
+    /// that no water shares its serial number with another Residue of a Chain with the same id.
+    /// Waters often get numbers that collide with the polymer or ligands after combining files or
+    /// adding solvent, which makes the Residues ambiguous (see `validate_solvent_numbering`). The
+    /// waters are numbered in the order of `all_chains`, skipping the numbers used by the other
+    /// Residues. The other Residues are never touched. Renumbered waters lose their insertion code.
+    /// It returns the renumbered waters as (chain id, old serial number, new serial number).
+    ///
+    /// ## Arguments
+    /// * `start` - the first serial number of the waters in every Chain, if `None` the number
+    ///   following the highest serial number of the other Residues in the Chain is used
+    pub fn renumber_solvent(&mut self, start: Option<usize>) -> Vec<(char, usize, usize)> {
+        let is_water = |residue: &Residue| reference_tables::is_water_residue(&residue.id());
+        let mut taken: HashMap<char, BTreeSet<usize>> = HashMap::new();
+        for chain in self.all_chains() {
+            let numbers = taken.entry(chain.id()).or_default();
+            numbers.extend(
+                chain
+                    .residues()
+                    .filter(|residue| !is_water(residue))
+                    .map(|residue| residue.serial_number()),
+            );
+        }
+        let mut next: HashMap<char, usize> = taken
+            .iter()
+            .map(|(id, numbers)| {
+                let first = start.unwrap_or_else(|| numbers.iter().max().map_or(1, |max| max + 1));
+                (*id, first)
+            })
+            .collect();
+
+        let mut renumbered = Vec::new();
+        for chain in self.all_chains_mut() {
+            let id = chain.id();
+            let numbers = taken.get_mut(&id).expect("Every chain id is registered");
+            let counter = next.get_mut(&id).expect("Every chain id is registered");
+            for residue in chain.residues_mut().filter(|residue| is_water(residue)) {
+                while numbers.contains(counter) {
+                    *counter += 1;
+                }
+                if residue.numbering() != (*counter, None) {
+                    renumbered.push((id, residue.serial_number(), *counter));
+                    residue.set_serial_number(*counter);
+                    residue
+                        .set_insertion_code(None)
+                        .expect("An empty insertion code is always valid");
+                }
+                numbers.insert(*counter);
+                *counter += 1;
+            }
+        }
+        renumbered
+    }
+
+    /// Reorder the Chains (Normal and Hetero) of this Model to follow the given order of chain ids.
+    /// Hetero Chains are ordered separately from the Normal Chains, but using the same order, so a
+    /// Hetero Chain stays with the Normal Chain with the same id.
+    ///
+    /// ## Arguments
+    /// * `order` - the chain ids in the new order, every id of this Model should occur exactly once
+    ///
+    /// ## Fails
+    /// It fails if the order contains an id not used in this Model, contains an id more than once,
+    /// or misses an id used in this Model. The Model is not changed in that case.
+    pub fn reorder_chains(&mut self, order: &[char]) -> Result<(), PDBError> {
+        self.touch();
+        let ids = self
+            .all_chains()
+            .map(|chain| chain.id())
+            .collect::<BTreeSet<char>>();
+        let mut seen = BTreeSet::new();
+        for id in order {
+            if !ids.contains(id) {
+                return Err(PDBError::new(
+                    ErrorLevel::InvalidatingError,
+                    "Unknown chain id",
+                    &format!(
+                        "The chain id '{}' in the new order is not used in model {}",
+                        id, self.serial_number
+                    ),
+                    PDBContext::none(),
+                ));
+            }
+            if !seen.insert(*id) {
+                return Err(PDBError::new(
+                    ErrorLevel::InvalidatingError,
+                    "Duplicate chain id",
+                    &format!(
+                        "The chain id '{}' occurs more than once in the new order",
+                        id
+                    ),
+                    PDBContext::none(),
+                ));
+            }
+        }
+        if let Some(missing) = ids.difference(&seen).next() {
+            return Err(PDBError::new(
+                ErrorLevel::InvalidatingError,
+                "Missing chain id",
+                &format!(
+                    "The chain id '{}' of model {} is missing from the new order",
+                    missing, self.serial_number
+                ),
+                PDBContext::none(),
+            ));
+        }
+        let position = |chain: &Chain| order.iter().position(|id| *id == chain.id());
+        self.chains.sort_by_key(position);
+        self.hetero_chains.sort_by_key(position);
+        Ok(())
+    }
+
+    /// Sort the Chains (Normal and Hetero) of this Model alphabetically on their id. Chains with
+    /// the same id keep their relative order.
+    pub fn sort_chains(&mut self) {
+        self.touch();
+        self.chains.sort_by_key(|chain| chain.id());
+        self.hetero_chains.sort_by_key(|chain| chain.id());
+    }
+
     /// Apply a transformation to the position of all atoms (Normal and Hetero) making up this Model, the new position is immediately set.
     pub fn apply_transformation(&mut self, transformation: &TransformationMatrix) {
+        self.touch();
         for atom in self.all_atoms_mut() {
             atom.apply_transformation(transformation);
         }
     }
 
+    /// Calculate the root mean square deviation of the CA atoms per Chain between this Model and the
+    /// reference Model. The Chains are matched by their id and the CA atoms by the serial number
+    /// of their Residue. The Chains are not superposed individually, so the Models should already
+    /// be superposed globally, making this a measure of the relative movement of the Chains (domains).
+    /// Only the normal (non hetero) Chains are taken into account.
+    ///
+    /// It returns a list of (chain id, RMSD) pairs for every Chain in this Model. The RMSD is `None`
+    /// when the reference does not contain a Chain with the same id or when the Chains do not share
+    /// any CA atoms.
+    pub fn per_chain_ca_rmsd(&self, reference: &Model) -> Vec<(char, Option<f64>)> {
+        self.chains()
+            .map(|chain| {
+                let rmsd =
+                    reference
+                        .chains()
+                        .find(|c| c.id() == chain.id())
+                        .and_then(|reference_chain| {
+                            let squared_distances = chain
+                                .residues()
+                                .filter_map(|residue| {
+                                    let ca = residue.atom_by_name("CA")?;
+                                    let reference_ca = reference_chain
+                                        .residues()
+                                        .find(|r| r.numbering() == residue.numbering())?
+                                        .atom_by_name("CA")?;
+                                    Some(ca.distance(reference_ca).powi(2))
+                                })
+                                .collect::<Vec<f64>>();
+                            if squared_distances.is_empty() {
+                                None
+                            } else {
+                                Some(
+                                    (squared_distances.iter().sum::<f64>()
+                                        / squared_distances.len() as f64)
+                                        .sqrt(),
+                                )
+                            }
+                        });
+                (chain.id(), rmsd)
+            })
+            .collect()
+    }
+
+    /// Get the Atoms (Normal and Hetero) in this Model that are selected by the given Selection
+    pub fn select_atoms(&self, selection: &Selection) -> Vec<&Atom> {
+        self.selected_atoms(selection)
+            .into_iter()
+            .map(|(_, atom)| atom)
+            .collect()
+    }
+
+    /// Get the selected Atoms with the (chain id, Residue numbering, Atom name, alternate
+    /// location) used to pair them with the Atoms of another Model
+    fn selected_atoms<'a>(&'a self, selection: &Selection) -> Vec<(AtomKey, &'a Atom)> {
+        let selection = selection.resolve(self);
+        let normal = self.chains().map(|chain| (chain, false));
+        let hetero = self.hetero_chains().map(|chain| (chain, true));
+        let mut atoms = Vec::new();
+        for (chain, is_hetero) in normal.chain(hetero) {
+            for residue in chain.residues() {
+                let entity = residue.entity_type();
+                for atom in residue.atoms() {
+                    if selection.matches(residue, entity, atom, is_hetero) {
+                        let key = (
+                            chain.id(),
+                            residue.numbering(),
+                            atom.name(),
+                            atom.alternate_location(),
+                        );
+                        atoms.push((key, atom));
+                    }
+                }
+            }
+        }
+        atoms
+    }
+
+    /// Get the positions of the selected Atoms of this Model and of the reference Model, paired by
+    /// chain id, Residue numbering, Atom name and alternate location
+    fn paired_positions(&self, reference: &Model, selection: &Selection) -> PairedPositions {
+        let reference_atoms = reference
+            .selected_atoms(selection)
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        self.selected_atoms(selection)
+            .into_iter()
+            .filter_map(|(key, atom)| {
+                reference_atoms
+                    .get(&key)
+                    .map(|reference_atom| (atom.pos(), reference_atom.pos()))
+            })
+            .unzip()
+    }
+
+    /// Get the transformation that superposes the selected Atoms of this Model onto those of the
+    /// reference Model with the least root mean square deviation. The Atoms are paired by chain
+    /// id, Residue serial number, Atom name and alternate location. Apply the transformation with
+    /// `apply_transformation` to superpose this Model.
+    ///
+    /// It returns `None` when fewer than three Atoms could be paired.
+    pub fn superpose_onto(
+        &self,
+        reference: &Model,
+        selection: &Selection,
+    ) -> Option<TransformationMatrix> {
+        let (source, target) = self.paired_positions(reference, selection);
+        if source.len() < 3 {
+            return None;
+        }
+        TransformationMatrix::superpose(&source, &target)
+    }
+
+    /// Calculate the root mean square deviation of the selected Atoms between this Model and the
+    /// reference Model, with the Atoms paired as in `superpose_onto`. If `superpose` is set the
+    /// RMSD is calculated after optimally superposing the paired Atoms, otherwise the positions
+    /// are compared as they are.
+    ///
+    /// It returns `None` when no Atoms could be paired, or fewer than three when superposing.
+    pub fn rmsd(&self, reference: &Model, selection: &Selection, superpose: bool) -> Option<f64> {
+        let (source, target) = self.paired_positions(reference, selection);
+        if superpose {
+            return superposed_rmsd(&source, &target);
+        }
+        if source.is_empty() {
+            return None;
+        }
+        let sum = source
+            .iter()
+            .zip(&target)
+            .map(|(a, b)| (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2))
+            .sum::<f64>();
+        Some((sum / source.len() as f64).sqrt())
+    }
+
+    /// Remove all Atoms that are not selected by the given Selection, and the Residues and Chains
+    /// that are left empty
+    pub fn keep_selection(&mut self, selection: &Selection) {
+        // The distance clauses are resolved on the Model before any Atom is removed
+        let resolved = selection.resolve(self);
+        let normal = self.chains.iter().map(|chain| (chain, false));
+        let hetero = self.hetero_chains.iter().map(|chain| (chain, true));
+        let mut selected = Vec::new();
+        for (chain, is_hetero) in normal.chain(hetero) {
+            for residue in chain.residues() {
+                let entity = residue.entity_type();
+                selected.extend(
+                    residue
+                        .atoms()
+                        .map(|atom| resolved.matches(residue, entity, atom, is_hetero)),
+                );
+            }
+        }
+        self.touch();
+        let mut selected = selected.into_iter();
+        for chain in self.chains.iter_mut().chain(self.hetero_chains.iter_mut()) {
+            for residue in chain.residues_mut() {
+                let keep = selected
+                    .by_ref()
+                    .take(residue.atom_count())
+                    .collect::<Vec<bool>>();
+                let index = Cell::new(0);
+                residue.remove_atoms_by(|_| {
+                    index.set(index.get() + 1);
+                    !keep[index.get() - 1]
+                });
+            }
+            chain.remove_residues_by(|residue| residue.atom_count() == 0);
+        }
+        self.remove_chains_by(|chain| chain.residue_count() == 0);
+    }
+
+    /// Get the unique names of the non-standard Residues in all Chains (Normal and Hetero) of this
+    /// Model, see `Chain::nonstandard_residues`. This includes ligands and modified residues.
+    pub fn nonstandard_residue_types(&self) -> BTreeSet<String> {
+        self.all_chains()
+            .flat_map(|chain| chain.nonstandard_residues())
+            .map(|residue| residue.id())
+            .collect()
+    }
+
+    /// Cluster the Chains (Normal only) of this Model by their structural similarity, for example
+    /// to find the distinct chains in a homo-oligomer. For every pair of Chains the CA atoms of
+    /// Residues with the same serial number are superposed and the RMSD is calculated. Chains with
+    /// an RMSD below the cutoff are placed in the same cluster (single linkage). Pairs of Chains
+    /// sharing less than three CA atoms are never linked.
+    /// It returns the clusters in order of their first Chain, with the chain ids in the order of this Model.
+    /// ## Arguments
+    /// * `rmsd_cutoff` - the maximal RMSD in Å to consider two Chains similar
+    pub fn cluster_chains_by_structure_ca(&self, rmsd_cutoff: f64) -> Vec<Vec<char>> {
+        let chains = self.chains().collect::<Vec<&Chain>>();
+        let mut cluster_of = (0..chains.len()).collect::<Vec<usize>>();
+        for i in 0..chains.len() {
+            for j in (i + 1)..chains.len() {
+                if cluster_of[i] == cluster_of[j] {
+                    continue;
+                }
+                if superposed_ca_rmsd(chains[i], chains[j]).is_some_and(|rmsd| rmsd < rmsd_cutoff) {
+                    let (keep, merge) = (cluster_of[i], cluster_of[j]);
+                    for cluster in cluster_of.iter_mut() {
+                        if *cluster == merge {
+                            *cluster = keep;
+                        }
+                    }
+                }
+            }
+        }
+        let mut clusters: Vec<(usize, Vec<char>)> = Vec::new();
+        for (chain, cluster) in chains.iter().zip(cluster_of) {
+            match clusters.iter_mut().find(|c| c.0 == cluster) {
+                Some(c) => c.1.push(chain.id()),
+                None => clusters.push((cluster, vec![chain.id()])),
+            }
+        }
+        clusters.into_iter().map(|c| c.1).collect()
+    }
+
+    /// Get one representative Chain (the first) of every cluster of structurally similar Chains,
+    /// see `cluster_chains_by_structure_ca` for the details.
+    /// ## Arguments
+    /// * `rmsd_cutoff` - the maximal RMSD in Å to consider two Chains similar
+    pub fn structurally_unique_chains(&self, rmsd_cutoff: f64) -> Vec<char> {
+        self.cluster_chains_by_structure_ca(rmsd_cutoff)
+            .into_iter()
+            .map(|cluster| cluster[0])
+            .collect()
+    }
+
+    /// Get the ids of the Chains (Normal only) of this Model that are CA-only, see `Chain::is_ca_only`
+    pub fn ca_only_chains(&self) -> Vec<char> {
+        self.chains()
+            .filter(|chain| chain.is_ca_only())
+            .map(|chain| chain.id())
+            .collect()
+    }
+
+    /// Check that the Chains (Normal only) of this Model have more than CA atoms, for analyses
+    /// that need the full backbone or all heavy atoms
+    /// ## Fails
+    /// It fails with `AnalysisError::CaOnlyChain` for the first CA-only Chain, see `Chain::is_ca_only`.
+    pub(crate) fn check_full_atoms(&self) -> Result<(), AnalysisError> {
+        self.chains().try_for_each(|chain| chain.check_full_atoms())
+    }
+
+    /// Get the backbone dihedral angles (phi, psi) in degrees of all Residues in the Chains (Normal
+    /// only) of this Model with both angles defined, grouped by their Ramachandran class (see
+    /// `RamachandranClass`). Classes without any Residues are not present in the map.
+    /// ## Fails
+    /// It fails with `AnalysisError::CaOnlyChain` if any of the Chains is CA-only, see `Chain::is_ca_only`,
+    /// and with `AnalysisError::UnorderedResidues` if the Residues of any of the Chains are not
+    /// ordered by serial number, see `Chain::first_unordered_residue`.
+    pub fn ramachandran_pairs_by_residue_class(
+        &self,
+    ) -> Result<HashMap<RamachandranClass, Vec<(f64, f64)>>, AnalysisError> {
+        self.check_full_atoms()?;
+        self.chains()
+            .try_for_each(|chain| chain.check_residue_order())?;
+        let mut output: HashMap<RamachandranClass, Vec<(f64, f64)>> = HashMap::new();
+        for chain in self.chains() {
+            let residues = chain.residues().collect::<Vec<&Residue>>();
+            for (index, (phi, psi, _)) in chain.backbone_dihedrals().into_iter().enumerate() {
+                if let (Some(phi), Some(psi)) = (phi, psi) {
+                    // psi is only defined when the next Residue is bonded
+                    let next = residues.get(index + 1).map(|r| r.id());
+                    let class = RamachandranClass::from_residue_names(
+                        &residues[index].id(),
+                        next.as_deref(),
+                    );
+                    output.entry(class).or_default().push((phi, psi));
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    /// Get the local geometry score of the given Residue, combining the deviations of its backbone
+    /// bond lengths and angles from the Engh & Huber targets with its position in the Ramachandran
+    /// plot into a single Z-score. A score of 0.0 is ideal geometry, values above 2.0 flag
+    /// problematic Residues. See `Chain::local_geometry_profile` to get the scores of a whole Chain.
+    ///
+    /// ## Arguments
+    /// * `chain` - the id of the Chain (Normal only) containing the Residue
+    /// * `serial` - the serial number of the Residue
+    ///
+    /// ## Fails
+    /// It returns `None` if the Residue cannot be found, is not an amino acid or misses any of its
+    /// N, CA or C atoms. It fails with `AnalysisError::CaOnlyChain` if the Chain is CA-only, see
+    /// `Chain::is_ca_only`, and with `AnalysisError::UnorderedResidues` if its Residues are not
+    /// ordered by serial number, see `Chain::first_unordered_residue`.
+    pub fn local_geometry_score(
+        &self,
+        chain: char,
+        serial: usize,
+    ) -> Result<Option<f64>, AnalysisError> {
+        for c in self.chains().filter(|c| c.id() == chain) {
+            if let Some((_, score)) = c
+                .local_geometry_profile()?
+                .into_iter()
+                .find(|(serial_number, _)| *serial_number == serial)
+            {
+                return Ok(Some(score));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the occupancy weighted mean B-factor of every Residue (Normal and Hetero) in this Model,
+    /// see `Residue::occupancy_weighted_b_factor`. It returns a list of (chain id, residue serial
+    /// number, weighted B-factor) in the same order as `all_residues()`, skipping Residues without atoms.
+    pub fn occupancy_weighted_b_factors(&self) -> Vec<(char, usize, f64)> {
+        self.all_chains()
+            .flat_map(|chain| {
+                chain.residues().filter_map(move |residue| {
+                    residue
+                        .occupancy_weighted_b_factor()
+                        .map(|b| (chain.id(), residue.serial_number(), b))
+                })
+            })
+            .collect()
+    }
+
+    /// Get the mean occupancy of all Atoms (Normal and Hetero) in this Model, 0.0 if there are no Atoms
+    pub fn mean_occupancy(&self) -> f64 {
+        let count = self.all_atoms().count();
+        if count == 0 {
+            0.0
+        } else {
+            self.all_atoms().map(|a| a.occupancy()).sum::<f64>() / count as f64
+        }
+    }
+
+    /// Get the median B-factor of all Atoms (Normal and Hetero) in this Model, 0.0 if there are no Atoms.
+    /// For an even number of Atoms it is the mean of the two middle values.
+    pub fn median_b_factor(&self) -> f64 {
+        let mut b_factors = self.all_atoms().map(|a| a.b_factor()).collect::<Vec<f64>>();
+        if b_factors.is_empty() {
+            return 0.0;
+        }
+        b_factors.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let middle = b_factors.len() / 2;
+        if b_factors.len() % 2 == 0 {
+            (b_factors[middle - 1] + b_factors[middle]) / 2.0
+        } else {
+            b_factors[middle]
+        }
+    }
+
+    /// Get the interatomic vectors between all Atoms (Normal and Hetero) of the given elements,
+    /// which are the positions of the peaks in the Patterson function caused by these atoms.
+    /// Every vector is weighted by the expected peak height, the product of the atomic numbers of
+    /// the two Atoms (Z_i × Z_j). Only one vector of each centrosymmetric pair (u and -u) is given.
+    /// The vectors are sorted by descending weight and then by ascending length.
+    ///
+    /// ## Arguments
+    /// * `heavy_elements` - the elements to include, for example `&["S", "Zn"]` (case insensitive)
+    pub fn patterson_vectors(&self, heavy_elements: &[&str]) -> Vec<([f64; 3], f64)> {
+        let atoms = self
+            .all_atoms()
+            .filter(|atom| {
+                heavy_elements
+                    .iter()
+                    .any(|element| element.eq_ignore_ascii_case(&atom.element()))
+            })
+            .filter_map(|atom| atom.atomic_number().map(|z| (atom.pos(), z as f64)))
+            .collect::<Vec<((f64, f64, f64), f64)>>();
+        let mut vectors = Vec::new();
+        for (index, (a, z_a)) in atoms.iter().enumerate() {
+            for (b, z_b) in &atoms[index + 1..] {
+                vectors.push(([b.0 - a.0, b.1 - a.1, b.2 - a.2], z_a * z_b));
+            }
+        }
+        let length = |v: &[f64; 3]| v[0] * v[0] + v[1] * v[1] + v[2] * v[2];
+        vectors.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap()
+                .then(length(&a.0).partial_cmp(&length(&b.0)).unwrap())
+        });
+        vectors
+    }
+
+    /// Get the interaction fingerprint of the given ligand: all non covalent interactions between
+    /// an Atom of the ligand and an Atom of another Residue (Normal or Hetero, waters excluded),
+    /// classified with the given criteria as metal coordination, salt bridge, hydrogen bond or
+    /// hydrophobic contact. Every pair of Atoms is classified as the first type of interaction it
+    /// matches within its cutoff, in this order, see `InteractionCriteria`. The elements are taken
+    /// from the element column, so Atoms without element are not classified. The ligand is found
+    /// in the Hetero Chains first. Fingerprints of the same ligand in different structures can be
+    /// compared with `Interaction::tanimoto`.
+    ///
+    /// ## Arguments
+    /// * `ligand` - the ligand Residue
+    /// * `criteria` - the cutoffs for every type of interaction
+    /// ## Fails
+    /// It fails with `AnalysisError::ResidueNotFound` if the ligand cannot be found, and with
+    /// `AnalysisError::CaOnlyChain` if any of the Chains is CA-only (see `Chain::is_ca_only`), as
+    /// hydrogen bonds and salt bridges cannot be found without the side chain atoms.
+    pub fn interaction_fingerprint(
+        &self,
+        ligand: ResidueId,
+        criteria: &InteractionCriteria,
+    ) -> Result<Vec<Interaction>, AnalysisError> {
+        let ligand = self
+            .hetero_chains()
+            .chain(self.chains())
+            .flat_map(|chain| {
+                chain
+                    .residues()
+                    .filter(move |residue| ligand.matches(chain.id(), residue))
+            })
+            .next()
+            .ok_or(AnalysisError::ResidueNotFound(ligand))?;
+        self.check_full_atoms()?;
+        let ligand_name = ligand.id();
+        let max_cutoff = criteria.max_cutoff();
+        let mut interactions = Vec::new();
+        for chain in self.chains().chain(self.hetero_chains()) {
+            for residue in chain.residues() {
+                if std::ptr::eq(residue, ligand)
+                    || EntityType::from_residue_name(&residue.id()) == EntityType::Water
+                {
+                    continue;
+                }
+                let residue_name = residue.id();
+                for atom in residue.atoms() {
+                    let element = atom.element().to_ascii_uppercase();
+                    for ligand_atom in ligand.atoms() {
+                        let distance = atom.distance(ligand_atom);
+                        if distance > max_cutoff {
+                            continue;
+                        }
+                        let ligand_element = ligand_atom.element().to_ascii_uppercase();
+                        if let Some(kind) = criteria.classify(
+                            (
+                                &ligand_name,
+                                &ligand_atom.name(),
+                                &ligand_element,
+                                ligand_atom.charge(),
+                            ),
+                            (&residue_name, &atom.name(), &element, atom.charge()),
+                            distance,
+                        ) {
+                            interactions.push(Interaction::new(
+                                kind,
+                                ligand_atom.name(),
+                                ResidueId::from_hierarchy(chain, residue),
+                                residue_name.clone(),
+                                atom.name(),
+                                distance,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(interactions)
+    }
+
+    /// Translate every Residue by an integer number of unit cell vectors so that its center of
+    /// geometry lies inside the primary unit cell (fractional coordinates in [0, 1)). The Residues
+    /// are moved as rigid units, so a Residue will never be split across the cell boundary.
+    /// Non orthogonal cells are handled via the fractionalisation matrix.
+    /// ## Arguments
+    /// * `cell` - the UnitCell to wrap into
+    pub fn wrap_into_unit_cell(&mut self, cell: &UnitCell) {
+        self.touch();
+        let fractionalisation = cell.fractionalisation_matrix();
+        let orthogonalisation = cell.orthogonalisation_matrix();
+        for residue in self.all_residues_mut() {
+            if let Some(center) = center_of_geometry(residue) {
+                let f = fractionalisation.apply(center);
+                let shift = orthogonalisation.apply((-f.0.floor(), -f.1.floor(), -f.2.floor()));
+                residue.apply_transformation(&TransformationMatrix::translation(
+                    shift.0, shift.1, shift.2,
+                ));
+            }
+        }
+    }
+
+    /// Make every Chain contiguous, undoing the splitting of Chains across the cell boundary as done
+    /// by `wrap_into_unit_cell`. Every Residue is translated by an integer number of unit cell vectors
+    /// to the periodic image with its center of geometry closest to the center of geometry of the
+    /// previous Residue in the Chain (minimum image convention). The first Residue of each Chain is not moved.
+    /// ## Arguments
+    /// * `cell` - the UnitCell defining the periodicity
+    pub fn unwrap_chains(&mut self, cell: &UnitCell) {
+        self.touch();
+        for chain in self.all_chains_mut() {
+            let mut previous: Option<(f64, f64, f64)> = None;
+            for residue in chain.residues_mut() {
+                if let Some(center) = center_of_geometry(residue) {
+                    if let Some(p) = previous {
+                        let d = (center.0 - p.0, center.1 - p.1, center.2 - p.2);
+                        let image = cell.minimum_image_vector(d);
+                        residue.apply_transformation(&TransformationMatrix::translation(
+                            image.0 - d.0,
+                            image.1 - d.1,
+                            image.2 - d.2,
+                        ));
+                    }
+                    previous = center_of_geometry(residue);
+                }
+            }
+        }
+    }
+
     /// Join this Model with another Model, this moves all atoms from the other Model
     /// to this Model. All other (meta) data of this Model will stay the same. It will add
-    /// new Chains and residues as defined in the other model.
+    /// new Chains and residues as defined in the other model. The Chains of the other Model are
+    /// added after the Chains of this Model (Normal and Hetero separately), even if a Chain with
+    /// the same id already exists.
     pub fn join(&mut self, other: Model) {
+        self.touch();
         self.chains.extend(other.chains);
         self.hetero_chains.extend(other.hetero_chains);
     }
 }
 
+/// The RMSD of the CA atoms of Residues with the same numbering in both Chains after superposition.
+/// Returns `None` if the Chains share less than three CA atoms.
+fn superposed_ca_rmsd(a: &Chain, b: &Chain) -> Option<f64> {
+    let (source, target): (Vec<_>, Vec<_>) = a
+        .residues()
+        .filter_map(|residue| {
+            let ca = residue.atom_by_name("CA")?;
+            let other = b
+                .residues()
+                .find(|r| r.numbering() == residue.numbering())?
+                .atom_by_name("CA")?;
+            Some((ca.pos(), other.pos()))
+        })
+        .unzip();
+    superposed_rmsd(&source, &target)
+}
+
+/// The center of geometry of the atoms of a Residue, `None` if the Residue has no atoms
+fn center_of_geometry(residue: &Residue) -> Option<(f64, f64, f64)> {
+    if residue.atom_count() == 0 {
+        return None;
+    }
+    let n = residue.atom_count() as f64;
+    Some(residue.atoms().fold((0.0, 0.0, 0.0), |acc, atom| {
+        let pos = atom.pos();
+        (acc.0 + pos.0 / n, acc.1 + pos.1 / n, acc.2 + pos.2 / n)
+    }))
+}
+
 use std::fmt;
 impl fmt::Display for Model {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -464,6 +1544,8 @@ impl Clone for Model {
         let mut model = Model::new(self.serial_number);
         model.chains = self.chains.clone();
         model.hetero_chains = self.hetero_chains.clone();
+        model.metadata = self.metadata.clone();
+        model.revision = self.revision;
         model
     }
 }
@@ -473,5 +1555,116 @@ impl PartialEq for Model {
         self.serial_number == other.serial_number
             && self.chains == other.chains
             && self.hetero_chains == other.hetero_chains
+            && self.metadata == other.metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structs::*;
+    use crate::transformation::*;
+
+    #[test]
+    fn per_chain_ca_rmsd() {
+        let mut model = Model::new(0);
+        for chain in &['A', 'B'] {
+            for serial in 0..10 {
+                let atom = Atom::new(
+                    serial,
+                    [' ', 'C', 'A', ' '],
+                    serial as f64 * 3.8,
+                    if *chain == 'A' { 0.0 } else { 10.0 },
+                    0.0,
+                    1.0,
+                    0.0,
+                    [' ', 'C'],
+                    0,
+                )
+                .unwrap();
+                model.add_atom(atom, *chain, serial, None, ['G', 'L', 'Y']);
+            }
+        }
+        // Swing domain B away while domain A stays fixed
+        let mut moved = model.clone();
+        moved
+            .chains_mut()
+            .find(|c| c.id() == 'B')
+            .unwrap()
+            .apply_transformation(&TransformationMatrix::rotation_z(20.0));
+        let mut reference = model.clone();
+        reference.remove_chain_id('B');
+
+        let rmsd = moved.per_chain_ca_rmsd(&model);
+        assert_eq!(rmsd[0], ('A', Some(0.0)));
+        assert_eq!(rmsd[1].0, 'B');
+        assert!(rmsd[1].1.unwrap() > 1.0);
+        assert_eq!(moved.per_chain_ca_rmsd(&reference)[1], ('B', None));
+    }
+
+    #[test]
+    fn occupancy_weighted_b_factors() {
+        let mut model = Model::new(0);
+        // A residue with two conformers, A (occupancy 0.7, B 10) and B (occupancy 0.3, B 40)
+        for (serial, (location, occupancy, b)) in
+            [('A', 0.7, 10.0), ('B', 0.3, 40.0)].iter().enumerate()
+        {
+            let mut atom = Atom::new(
+                serial,
+                [' ', 'C', 'B', ' '],
+                0.0,
+                0.0,
+                0.0,
+                *occupancy,
+                *b,
+                [' ', 'C'],
+                0,
+            )
+            .unwrap();
+            atom.set_alternate_location(*location).unwrap();
+            model.add_atom(atom, 'A', 1, None, ['S', 'E', 'R']);
+        }
+        let weighted = model.occupancy_weighted_b_factors();
+        assert_eq!(weighted.len(), 1);
+        assert_eq!((weighted[0].0, weighted[0].1), ('A', 1));
+        assert!((weighted[0].2 - 19.0).abs() < 1e-9);
+        // The plain average is not biased towards the major conformer
+        assert_eq!(model.residue(0).unwrap().average_b_factor(), Some(25.0));
+        assert!((model.mean_occupancy() - 0.5).abs() < 1e-9);
+        assert_eq!(model.median_b_factor(), 25.0);
+        assert_eq!(Model::new(1).median_b_factor(), 0.0);
+    }
+
+    #[test]
+    fn patterson_vectors() {
+        let mut model = Model::new(0);
+        let atoms = [
+            (1, 'S', (0.0, 0.0, 0.0)),
+            (2, 'S', (2.05, 0.0, 0.0)),
+            (3, 'S', (10.0, 4.0, 0.0)),
+            (1, 'C', (-1.5, 0.5, 0.0)),
+        ];
+        for (serial, (residue, element, pos)) in atoms.iter().enumerate() {
+            let atom = Atom::new(
+                serial,
+                [' ', *element, 'G', ' '],
+                pos.0,
+                pos.1,
+                pos.2,
+                1.0,
+                0.0,
+                [' ', *element],
+                0,
+            )
+            .unwrap();
+            model.add_atom(atom, 'A', *residue, None, ['C', 'Y', 'S']);
+        }
+        let vectors = model.patterson_vectors(&["s"]);
+        assert_eq!(vectors.len(), 3);
+        // The disulfide gives the shortest vector with the highest weight
+        assert_eq!(vectors[0], ([2.05, 0.0, 0.0], 256.0));
+        assert!(vectors.iter().all(|v| v.1 == 256.0));
+        let weights = model.patterson_vectors(&["S", "C"]);
+        assert_eq!(weights.len(), 6);
+        assert_eq!(weights.last().unwrap().1, 96.0);
     }
 }
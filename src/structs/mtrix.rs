@@ -70,10 +70,7 @@ impl MtriX {
     /// ```
     pub fn set_row(&mut self, row: usize, data: [f64; 4]) {
         if row > 2 {
-            panic!(format!(
-                "Row in MtriX.set_row is too big (max 2, value: {})",
-                row
-            ));
+            panic!("Row in MtriX.set_row is too big (max 2, value: {})", row);
         }
         let mut matrix = self.transformation.matrix();
         matrix[row] = data;
@@ -83,10 +80,10 @@ impl MtriX {
         }
         self.rows_set[row] = true;
     }
-    /// Checks if this MtriX is valid, for this all rows have to be set (also see `set_row`).
-    /// Mainly used to validate a structure after parsing.
+    /// Checks if this MtriX is valid, for this all rows have to be set (also see `set_row`) and
+    /// the rotation part has to be non-singular. Mainly used to validate a structure after parsing.
     pub fn valid(&self) -> bool {
-        self.rows_set == [true, true, true]
+        self.rows_set == [true, true, true] && self.transformation.inverse().is_some()
     }
 }
 
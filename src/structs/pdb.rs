@@ -1,12 +1,49 @@
 #![allow(dead_code)]
+use crate::error::*;
 use crate::reference_tables;
+use crate::serial_overflow::*;
 use crate::structs::*;
 use crate::transformation::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Mutex;
 
 #[derive(Debug)]
 /// A PDB file containing the 3D coordinates of many atoms making up the
 /// 3D structure of a protein, but it can also be used for other molecules.
+///
+/// The iterators over Chains, Residues and Atoms go through the Models in the order they are
+/// stored, following the ordering of every Model (see `Model`). So `all_atoms` gives the Normal
+/// Atoms before the Hetero Atoms of a Model, but the Hetero Atoms of the first Model before the
+/// Normal Atoms of the second Model.
 pub struct PDB {
+    /// The name identifying this structure, see `name`
+    name: String,
+    /// The classification of the molecule given in the HEADER record
+    classification: String,
+    /// The deposition date given in the HEADER record, if available
+    deposition_date: Option<Date>,
+    /// The PDB id code given in the HEADER record, if available
+    identifier: Option<String>,
+    /// The title of the structure given in the TITLE records
+    title: String,
+    /// The entries replacing this obsolete entry given in the OBSLTE records
+    obsolete: Option<EntryReplacement>,
+    /// The entries superseded by this entry given in the SPRSDE records
+    supersedes: Option<EntryReplacement>,
+    /// The entries making up a structure split over multiple entries given in the SPLIT records
+    split_entries: Vec<String>,
+    /// The warning about severe errors given in the CAVEAT records
+    caveat: Option<Caveat>,
+    /// The molecules described in the COMPND records, as the tokens and values per MOL_ID
+    compounds: Vec<Vec<(String, String)>>,
+    /// The sources of the molecules described in the SOURCE records, as the tokens and values per MOL_ID
+    sources: Vec<Vec<(String, String)>>,
+    /// The experimental methods given in the EXPDTA record
+    experimental_methods: Vec<ExperimentalMethod>,
+    /// The annotations of the model type given in the MDLTYP record
+    model_types: Vec<String>,
+    /// The number of Models given in the NUMMDL record
+    declared_model_count: Option<usize>,
     /// The remarks above the PDB file, containing the remark-type-number and a line of free text
     remarks: Vec<(usize, String)>,
     /// The Scale needed to transform orthogonal coordinates to fractional coordinates, if available
@@ -15,28 +52,464 @@ pub struct PDB {
     origx: Option<OrigX>,
     /// The MtriXs needed to transform the Models to the full assymetric subunit, if needed to contain the non-crystallographic symmetry
     mtrix: Vec<MtriX>,
+    /// The biological assemblies, written as REMARK 350 when saving
+    assemblies: Vec<Assembly>,
+    /// The HET records describing the hetero groups in the coordinates
+    hets: Vec<Het>,
+    /// The descriptions of the hetero group types given in HETNAM, HETSYN and FORMUL records, by their id
+    heterogens: BTreeMap<String, Heterogen>,
+    /// The references to sequence databases given in DBREF records, with the sequence differences given in SEQADV records
+    database_references: Vec<DatabaseReference>,
+    /// The disulfide bridges given in SSBOND records
+    ssbonds: Vec<SSBond>,
+    /// The connections between Atoms of different Residues given in LINK records
+    links: Vec<Link>,
+    /// The cis peptide bonds given in CISPEP records
+    cis_peptides: Vec<CisPeptide>,
+    /// The functional sites given in SITE records
+    sites: Vec<Site>,
+    /// The helices of the secondary structure given in HELIX records
+    helices: Vec<Helix>,
+    /// The bonds given in CONECT records, as pairs of Atom serial numbers with the lowest first
+    connections: BTreeSet<(usize, usize)>,
+    /// The SEQRES records containing the sequence of every Chain as the chain id and the residue names
+    seqres: Vec<(char, Vec<String>)>,
     /// The unit cell of the crystal, containing its size and shape, if available
     unit_cell: Option<UnitCell>,
     /// The Symmetry or space group of the crystal, if available
     symmetry: Option<Symmetry>,
     /// The Models making up this PDB
     models: Vec<Model>,
+    /// The errors found by `validate_incremental` per Model, by the revisions of the first Model and the validated Model
+    validation_cache: Mutex<HashMap<(u64, u64), Vec<PDBError>>>,
+    /// If the parsed file contained an END record
+    saw_end: bool,
+    /// If the parsed file contained any TER record
+    saw_ter: bool,
+    /// If the parsed file contained a MASTER record
+    saw_master: bool,
+    /// The record types (tags) found in the parsed file
+    source_record_types: HashSet<String>,
+    /// The number of records of every record type found in the parsed file
+    source_record_counts: HashMap<String, usize>,
 }
 
 impl PDB {
     /// Create an empty PDB struct
     pub fn new() -> PDB {
         PDB {
+            name: String::new(),
+            classification: String::new(),
+            deposition_date: None,
+            identifier: None,
+            title: String::new(),
+            obsolete: None,
+            supersedes: None,
+            split_entries: Vec::new(),
+            caveat: None,
+            compounds: Vec::new(),
+            sources: Vec::new(),
+            experimental_methods: Vec::new(),
+            model_types: Vec::new(),
+            declared_model_count: None,
             remarks: Vec::new(),
             scale: None,
             origx: None,
             mtrix: Vec::new(),
+            assemblies: Vec::new(),
+            hets: Vec::new(),
+            heterogens: BTreeMap::new(),
+            database_references: Vec::new(),
+            ssbonds: Vec::new(),
+            links: Vec::new(),
+            cis_peptides: Vec::new(),
+            sites: Vec::new(),
+            helices: Vec::new(),
+            connections: BTreeSet::new(),
+            seqres: Vec::new(),
             unit_cell: None,
             symmetry: None,
             models: Vec::new(),
+            validation_cache: Mutex::new(HashMap::new()),
+            saw_end: false,
+            saw_ter: false,
+            saw_master: false,
+            source_record_types: HashSet::new(),
+            source_record_counts: HashMap::new(),
         }
     }
 
+    /// Get the errors found by the last `validate_incremental` per Model, by the revisions of the
+    /// first Model and the validated Model
+    pub(crate) fn validation_cache(&self) -> &Mutex<HashMap<(u64, u64), Vec<PDBError>>> {
+        &self.validation_cache
+    }
+
+    /// Get the name identifying this structure, empty if it has no name. When parsing a PDB file
+    /// it is the id code of the HEADER record, or the file name without extension if there is
+    /// none. It is written as the id code of the HEADER record when saving if there is no
+    /// `identifier` and it is a valid PDB id code. It is meant to be the data block name
+    /// (`data_name`) in mmCIF files, so it follows the rules of those names.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the name identifying this structure, see `name`. Use an empty name to remove it.
+    /// ## Fails
+    /// It fails if the name is not a valid CIF data block name: longer than 75 characters or
+    /// containing characters that are not ASCII graphic (like whitespace).
+    pub fn set_name(&mut self, new_name: &str) -> Result<(), String> {
+        if check_block_name(new_name) {
+            self.name = new_name.to_string();
+            Ok(())
+        } else {
+            Err(format!(
+                "The name \"{}\" is not a valid data block name, it should be at most 75 ASCII graphic characters (no whitespace)",
+                new_name
+            ))
+        }
+    }
+
+    /// Get the classification of the molecule given in the HEADER record, like `TRANSPORT PROTEIN`,
+    /// empty if there is none
+    pub fn classification(&self) -> &str {
+        &self.classification
+    }
+
+    /// Set the classification of the molecule, written in the HEADER record
+    /// ## Fails
+    /// It fails if the classification is longer than 40 characters or contains invalid characters.
+    pub fn set_classification(&mut self, new_classification: &str) -> Result<(), String> {
+        let trimmed = new_classification.trim();
+        if trimmed.chars().count() > 40 {
+            Err(format!(
+                "The classification \"{}\" is longer than 40 characters",
+                trimmed
+            ))
+        } else if !check_chars(trimmed.to_string()) {
+            Err(format!(
+                "The classification \"{}\" contains invalid characters",
+                trimmed
+            ))
+        } else {
+            self.classification = trimmed.to_string();
+            Ok(())
+        }
+    }
+
+    /// Get the deposition date given in the HEADER record, if available
+    pub fn deposition_date(&self) -> Option<Date> {
+        self.deposition_date
+    }
+
+    /// Set the deposition date, written in the HEADER record
+    pub fn set_deposition_date(&mut self, new_date: Option<Date>) {
+        self.deposition_date = new_date;
+    }
+
+    /// Get the PDB id code given in the HEADER record, like `1UBQ`, if available. See `name` for
+    /// a more general identifier which is always set for parsed files.
+    pub fn identifier(&self) -> Option<&str> {
+        self.identifier.as_deref()
+    }
+
+    /// Set the PDB id code, written in the HEADER record
+    /// ## Fails
+    /// It fails if the id code does not consist of four alphanumeric ASCII characters.
+    pub fn set_identifier(&mut self, new_identifier: Option<&str>) -> Result<(), String> {
+        match new_identifier {
+            Some(id) if !check_id_code(id) => {
+                Err(format!(
+                    "The identifier \"{}\" is not a valid PDB id code, it should be four alphanumeric characters",
+                    id
+                ))
+            }
+            _ => {
+                self.identifier = new_identifier.map(|id| id.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the title of the structure given in the TITLE records, with all continuation lines
+    /// joined into one line. It is empty if there is no title.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Set the title of the structure, written in the TITLE records (wrapped over as many lines
+    /// as needed)
+    /// ## Fails
+    /// It fails if the title contains invalid characters.
+    pub fn set_title(&mut self, new_title: &str) -> Result<(), String> {
+        if check_chars(new_title.to_string()) {
+            self.title = new_title.trim().to_string();
+            Ok(())
+        } else {
+            Err(format!(
+                "The title \"{}\" contains invalid characters",
+                new_title.trim()
+            ))
+        }
+    }
+
+    /// Get the entries replacing this entry if it is obsolete, given in the OBSLTE records
+    pub fn obsolete(&self) -> Option<&EntryReplacement> {
+        self.obsolete.as_ref()
+    }
+
+    /// Set the entries replacing this entry, written in the OBSLTE records
+    pub fn set_obsolete(&mut self, obsolete: Option<EntryReplacement>) {
+        self.obsolete = obsolete;
+    }
+
+    /// Get the entries superseded by this entry, given in the SPRSDE records
+    pub fn supersedes(&self) -> Option<&EntryReplacement> {
+        self.supersedes.as_ref()
+    }
+
+    /// Set the entries superseded by this entry, written in the SPRSDE records
+    pub fn set_supersedes(&mut self, supersedes: Option<EntryReplacement>) {
+        self.supersedes = supersedes;
+    }
+
+    /// Get the PDB id codes of the entries making up a structure that is split over multiple
+    /// entries, given in the SPLIT records. It is empty if the structure is not split.
+    pub fn split_entries(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        self.split_entries.iter().map(String::as_str)
+    }
+
+    /// Add an entry making up the structure together with this entry, written in the SPLIT records
+    /// ## Fails
+    /// It fails if the id code is not four alphanumeric ASCII characters.
+    pub fn add_split_entry(&mut self, id_code: &str) -> Result<(), String> {
+        let id_code = id_code.trim();
+        if !check_id_code(id_code) {
+            return Err(format!(
+                "The split entry \"{}\" is not a valid PDB id code, it should be four alphanumeric characters",
+                id_code
+            ));
+        }
+        self.split_entries.push(id_code.to_string());
+        Ok(())
+    }
+
+    /// Get the warning about severe errors in this entry, given in the CAVEAT records
+    pub fn caveat(&self) -> Option<&Caveat> {
+        self.caveat.as_ref()
+    }
+
+    /// Set the warning about severe errors in this entry, written in the CAVEAT records
+    pub fn set_caveat(&mut self, caveat: Option<Caveat>) {
+        self.caveat = caveat;
+    }
+
+    /// Get the molecules described in the COMPND records, every molecule as the tokens and values
+    /// of its specifications in the order given, like `("MOL_ID", "1")`, `("MOLECULE", "HSLV")`
+    /// and `("CHAIN", "A, B")`.
+    pub fn compounds(&self) -> impl DoubleEndedIterator<Item = &Vec<(String, String)>> + '_ {
+        self.compounds.iter()
+    }
+
+    /// Add a molecule to the COMPND records, as the tokens and values of its specifications,
+    /// starting with its MOL_ID
+    pub fn add_compound(&mut self, specifications: Vec<(String, String)>) {
+        self.compounds.push(specifications);
+    }
+
+    /// Get the sources of the molecules described in the SOURCE records, every source as the
+    /// tokens and values of its specifications in the order given, like `("MOL_ID", "1")` and
+    /// `("ORGANISM_SCIENTIFIC", "ESCHERICHIA COLI")`.
+    pub fn sources(&self) -> impl DoubleEndedIterator<Item = &Vec<(String, String)>> + '_ {
+        self.sources.iter()
+    }
+
+    /// Add the source of a molecule to the SOURCE records, as the tokens and values of its
+    /// specifications, starting with the MOL_ID of the molecule
+    pub fn add_source(&mut self, specifications: Vec<(String, String)>) {
+        self.sources.push(specifications);
+    }
+
+    /// Get the molecule of the COMPND records containing the Chain with the given id (as listed
+    /// in its CHAIN specification), if available
+    pub fn chain_compound(&self, chain_id: char) -> Option<&[(String, String)]> {
+        self.compounds
+            .iter()
+            .find(|compound| {
+                specification(compound, "CHAIN").is_some_and(|chains| {
+                    chains
+                        .split(',')
+                        .any(|id| id.trim() == chain_id.to_string())
+                })
+            })
+            .map(|compound| compound.as_slice())
+    }
+
+    /// Get the source of the SOURCE records of the molecule containing the Chain with the given
+    /// id, found by the MOL_ID of the molecule, if available
+    pub fn chain_source(&self, chain_id: char) -> Option<&[(String, String)]> {
+        let mol_id = specification(self.chain_compound(chain_id)?, "MOL_ID")?;
+        self.sources
+            .iter()
+            .find(|source| specification(source, "MOL_ID") == Some(mol_id))
+            .map(|source| source.as_slice())
+    }
+
+    /// Get the name of the molecule (the MOLECULE specification of the COMPND records) containing
+    /// the Chain with the given id, if available
+    pub fn molecule_name(&self, chain_id: char) -> Option<&str> {
+        specification(self.chain_compound(chain_id)?, "MOLECULE")
+    }
+
+    /// Get the experimental method used to determine this structure, as given in the EXPDTA
+    /// record. For structures determined with multiple methods this is the first method, see
+    /// `experimental_methods`. It is `None` if no method is given.
+    pub fn experimental_method(&self) -> Option<ExperimentalMethod> {
+        self.experimental_methods.first().copied()
+    }
+
+    /// Get all experimental methods used to determine this structure, as given in the EXPDTA record
+    pub fn experimental_methods(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &ExperimentalMethod> + '_ {
+        self.experimental_methods.iter()
+    }
+
+    /// Set the experimental methods used to determine this structure, written in the EXPDTA record
+    pub fn set_experimental_methods(&mut self, methods: Vec<ExperimentalMethod>) {
+        self.experimental_methods = methods;
+    }
+
+    /// Get the annotations of the model type given in the MDLTYP record, like `CA ATOMS ONLY, CHAIN A, B`
+    /// or `MINIMIZED AVERAGE`
+    pub fn model_types(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        self.model_types.iter().map(String::as_str)
+    }
+
+    /// Add an annotation of the model type, written in the MDLTYP record
+    /// ## Fails
+    /// It fails if the annotation contains invalid characters or a semicolon (which separates the
+    /// annotations).
+    pub fn add_model_type(&mut self, annotation: &str) -> Result<(), String> {
+        if let Some(invalid) = describe_invalid_chars(annotation, |c| check_char(c) && c != ';') {
+            return Err(format!(
+                "The model type \"{}\" contains the invalid characters {}",
+                annotation, invalid
+            ));
+        }
+        self.model_types.push(annotation.trim().to_string());
+        Ok(())
+    }
+
+    /// Get the number of Models given in the NUMMDL record, for ensembles like NMR structures.
+    /// `validate_pdb` checks it against the number of Models (see `model_count`).
+    pub fn declared_model_count(&self) -> Option<usize> {
+        self.declared_model_count
+    }
+
+    /// Set the number of Models, written in the NUMMDL record
+    pub fn set_declared_model_count(&mut self, count: Option<usize>) {
+        self.declared_model_count = count;
+    }
+
+    /// Get the ids of the CA-only Chains of this PDB, so the Chains marked as such in the MDLTYP
+    /// record (`CA ATOMS ONLY, CHAIN A, B`, all Chains if none are listed) and the Chains of the
+    /// first Model that only contain CA atoms (see `Chain::is_ca_only`). Analyses that need the
+    /// full backbone or all heavy atoms give `AnalysisError::CaOnlyChain` for these Chains.
+    pub fn ca_only_chains(&self) -> Vec<char> {
+        let mut output = self
+            .model(0)
+            .map_or_else(Vec::new, |model| model.ca_only_chains());
+        for annotation in &self.model_types {
+            if let Some(chains) = annotation.strip_prefix("CA ATOMS ONLY") {
+                let listed = chains
+                    .split(',')
+                    .map(|part| part.trim())
+                    .map(|part| part.strip_prefix("CHAIN").map_or(part, str::trim_start))
+                    .filter_map(|part| {
+                        let mut chars = part.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(id), None) => Some(id),
+                            _ => None,
+                        }
+                    })
+                    .collect::<Vec<char>>();
+                if listed.is_empty() {
+                    output.extend(self.chains().map(|chain| chain.id()));
+                } else {
+                    output.extend(listed);
+                }
+            }
+        }
+        output.sort_unstable();
+        output.dedup();
+        output
+    }
+
+    /// Get the resolution in Å given in REMARK 2 (`RESOLUTION. 2.00 ANGSTROMS.`). It is `None` if
+    /// there is no such remark or if the resolution is `NOT APPLICABLE`, like for NMR structures.
+    pub fn resolution(&self) -> Option<f64> {
+        self.remarks
+            .iter()
+            .filter(|remark| remark.0 == 2 && remark.1.trim_start().starts_with("RESOLUTION."))
+            .find_map(|remark| remark_resolution(&remark.1).ok().flatten())
+    }
+
+    /// Get if the file this PDB was parsed from contained an END record. A file without it is
+    /// possibly truncated, but fragment files written by scripts often leave it out as well.
+    /// This is `false` for a PDB that was not parsed from a file.
+    pub fn saw_end(&self) -> bool {
+        self.saw_end
+    }
+
+    /// Get if the file this PDB was parsed from contained any TER record.
+    /// This is `false` for a PDB that was not parsed from a file.
+    pub fn saw_ter(&self) -> bool {
+        self.saw_ter
+    }
+
+    /// Get if the file this PDB was parsed from contained a MASTER record.
+    /// This is `false` for a PDB that was not parsed from a file.
+    pub fn saw_master(&self) -> bool {
+        self.saw_master
+    }
+
+    /// Set which of the END, TER and MASTER records were found while parsing
+    pub(crate) fn set_records_seen(&mut self, end: bool, ter: bool, master: bool) {
+        self.saw_end = end;
+        self.saw_ter = ter;
+        self.saw_master = master;
+    }
+
+    /// Get the record types (the tags, like `ATOM`, `ANISOU` or `CONECT`, without trailing spaces)
+    /// found in the file this PDB was parsed from, including the records that are not parsed.
+    /// When only the header was read (see `ReadOptions::set_header_only`) only the records of the
+    /// header are included. This is empty for a PDB that was not parsed from a file.
+    pub fn source_record_types(&self) -> &HashSet<String> {
+        &self.source_record_types
+    }
+
+    /// Get the number of records of every record type found in the file this PDB was parsed from,
+    /// see `source_record_types`
+    pub fn source_record_counts(&self) -> &HashMap<String, usize> {
+        &self.source_record_counts
+    }
+
+    /// Get the number of records of the given record type (like `ATOM`) found in the file this PDB
+    /// was parsed from, see `source_record_types`
+    pub fn source_record_count(&self, record_type: &str) -> usize {
+        self.source_record_counts
+            .get(record_type)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Set the number of records of every record type found while parsing
+    pub(crate) fn set_source_record_counts(&mut self, counts: HashMap<String, usize>) {
+        self.source_record_types = counts.keys().cloned().collect();
+        self.source_record_counts = counts;
+    }
+
     /// Get the number of REMARK records in the PDB file
     pub fn remark_count(&self) -> usize {
         self.remarks.len()
@@ -52,6 +525,93 @@ impl PDB {
         self.remarks.iter_mut()
     }
 
+    /// Get the Residues listed as missing from the coordinates in REMARK 465, as (chain id,
+    /// residue serial number, residue name). Lines that cannot be read (like the header of the
+    /// remark) are skipped, the model number (for NMR structures) and insertion code are ignored.
+    pub fn missing_residues(&self) -> Vec<(char, usize, String)> {
+        self.remarks
+            .iter()
+            .filter(|remark| remark.0 == 465)
+            .filter_map(|remark| {
+                let fields = remark.1.split_whitespace().collect::<Vec<&str>>();
+                let fields = match fields.len() {
+                    3 => &fields[..],
+                    4 => &fields[1..],
+                    _ => return None,
+                };
+                let mut chain = fields[1].chars();
+                let chain_id = chain.next()?;
+                let serial = fields[2]
+                    .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+                    .parse::<usize>()
+                    .ok()?;
+                if chain.next().is_some() || fields[0].len() > 3 {
+                    return None;
+                }
+                Some((chain_id, serial, fields[0].to_string()))
+            })
+            .collect()
+    }
+
+    /// Get the composition of this PDB per Model and per chain id: the number of polymer Residues,
+    /// waters, ligand Residues, ATOM and HETATM records and Atoms per element. All counts are
+    /// gathered in a single pass over the structure. Its `Display` gives a compact table.
+    pub fn composition(&self) -> Composition {
+        Composition::new(&self.models)
+    }
+
+    /// Find a sequence motif in all Chains (Normal only) of the first Model. It returns the chain id
+    /// and the index of the first Residue in that Chain for every match, see `Chain::find_motif`
+    /// for the pattern syntax.
+    ///
+    /// ## Arguments
+    /// * `pattern` - the motif to search for, case insensitive
+    pub fn find_motif(&self, pattern: &str) -> Vec<(char, usize)> {
+        self.models
+            .first()
+            .map(|model| {
+                model
+                    .chains()
+                    .flat_map(|chain| {
+                        chain
+                            .find_motif(pattern)
+                            .into_iter()
+                            .map(move |index| (chain.id(), index))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Find the gaps in all Chains (Normal only) of the first Model, combining jumps in the residue
+    /// numbering, physical chain breaks and the missing Residues in REMARK 465. It returns the gaps
+    /// per chain id, see `Chain::gap_report_with_missing_residues` for the details.
+    /// ## Fails
+    /// It fails with `AnalysisError::UnorderedResidues` for the first Chain with its Residues not
+    /// ordered by serial number, see `Chain::first_unordered_residue`.
+    pub fn gap_report(&self) -> Result<Vec<(char, Vec<Gap>)>, AnalysisError> {
+        let missing = self.missing_residues();
+        self.models.first().map_or_else(
+            || Ok(Vec::new()),
+            |model| {
+                model
+                    .chains()
+                    .map(|chain| {
+                        let serials = missing
+                            .iter()
+                            .filter(|m| m.0 == chain.id())
+                            .map(|m| m.1)
+                            .collect::<Vec<usize>>();
+                        Ok((
+                            chain.id(),
+                            chain.gap_report_with_missing_residues(&serials)?,
+                        ))
+                    })
+                    .collect()
+            },
+        )
+    }
+
     /// Add a remark
     ///
     /// ## Arguments
@@ -62,7 +622,7 @@ impl PDB {
     /// It panics if the text if too long, the text contains invalid characters or the remark-type-number is not valid (wwPDB v3.30).
     pub fn add_remark(&mut self, remark_type: usize, remark_text: String) {
         if !reference_tables::valid_remark_type_number(remark_type) {
-            panic!(format!("The given remark-type-number is not valid: {}, see wwPDB v3.30 for valid remark-type-numbers", remark_type));
+            panic!("The given remark-type-number is not valid: {}, see wwPDB v3.30 for valid remark-type-numbers", remark_type);
         }
         if !check_chars(remark_text.clone()) {
             panic!("The given remark text contains invalid characters.");
@@ -172,6 +732,497 @@ impl PDB {
         self.mtrix.push(mtrix);
     }
 
+    /// Get the biological assemblies of this PDB
+    pub fn assemblies(&self) -> impl DoubleEndedIterator<Item = &Assembly> + '_ {
+        self.assemblies.iter()
+    }
+
+    /// Get the biological assemblies of this PDB, as mutable references
+    pub fn assemblies_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Assembly> + '_ {
+        self.assemblies.iter_mut()
+    }
+
+    /// Add a biological assembly to this PDB. When saving, the assemblies are written as REMARK 350
+    /// records, replacing any REMARK 350 in the remarks of this PDB.
+    pub fn add_assembly(&mut self, assembly: Assembly) {
+        self.assemblies.push(assembly);
+    }
+
+    /// Get the number of HET records in this PDB
+    pub fn het_count(&self) -> usize {
+        self.hets.len()
+    }
+
+    /// Get the HET records for this PDB, describing the hetero groups in the coordinates
+    pub fn hets(&self) -> impl DoubleEndedIterator<Item = &Het> + '_ {
+        self.hets.iter()
+    }
+
+    /// Get the HET records for this PDB, as mutable references
+    pub fn hets_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Het> + '_ {
+        self.hets.iter_mut()
+    }
+
+    /// Add a HET record to this PDB
+    pub fn add_het(&mut self, het: Het) {
+        self.hets.push(het);
+    }
+
+    /// Calculate the HET records from the hetero Residues in the first Model of this PDB.
+    /// Waters are excluded as defined in the specification. The free text of the
+    /// current HET records is kept for hetero groups that are still present.
+    pub fn calculate_het_records(&self) -> Vec<Het> {
+        let mut output = Vec::new();
+        if let Some(model) = self.models.first() {
+            for chain in model.hetero_chains() {
+                for residue in chain.residues() {
+                    if residue.entity_type() == EntityType::Water {
+                        continue;
+                    }
+                    let mut het = Het::from_residue(chain, residue);
+                    if let Some(old) = self.hets.iter().find(|h| h.same_group(&het)) {
+                        het.set_text(old.text());
+                    }
+                    output.push(het);
+                }
+            }
+        }
+        output
+    }
+
+    /// Rebuild the HET records from the hetero Residues actually present in this PDB,
+    /// see `calculate_het_records()`. This is also done when saving a PDB.
+    pub fn recalculate_het_records(&mut self) {
+        self.hets = self.calculate_het_records();
+    }
+
+    /// Get the number of hetero group types described in HETNAM, HETSYN or FORMUL records
+    pub fn heterogen_count(&self) -> usize {
+        self.heterogens.len()
+    }
+
+    /// Get the descriptions of the hetero group types given in HETNAM, HETSYN and FORMUL records,
+    /// in the order of their ids
+    pub fn heterogens(&self) -> impl DoubleEndedIterator<Item = &Heterogen> + '_ {
+        self.heterogens.values()
+    }
+
+    /// Get the descriptions of the hetero group types, as mutable references
+    pub fn heterogens_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Heterogen> + '_ {
+        self.heterogens.values_mut()
+    }
+
+    /// Get the description of the hetero group type with the given id, like `HEM`. Use the name
+    /// of a hetero Residue (see `Residue::id`) to get its description.
+    pub fn heterogen(&self, id: &str) -> Option<&Heterogen> {
+        self.heterogens.get(id.trim())
+    }
+
+    /// Get the description of the hetero group type with the given id, as a mutable reference
+    pub fn heterogen_mut(&mut self, id: &str) -> Option<&mut Heterogen> {
+        self.heterogens.get_mut(id.trim())
+    }
+
+    /// Add the description of a hetero group type, to be written as HETNAM, HETSYN and FORMUL
+    /// records. It replaces the description with the same id, if any.
+    pub fn add_heterogen(&mut self, heterogen: Heterogen) {
+        self.heterogens
+            .insert(heterogen.id().to_string(), heterogen);
+    }
+
+    /// Get the HET records of the hetero groups with the given id, giving the occurrences of a
+    /// hetero group type with their number of HETATM records
+    pub fn heterogen_groups<'a>(
+        &'a self,
+        id: &'a str,
+    ) -> impl DoubleEndedIterator<Item = &'a Het> + 'a {
+        self.hets.iter().filter(move |het| het.id() == id.trim())
+    }
+
+    /// Get the number of references to sequence databases given in DBREF records
+    pub fn database_reference_count(&self) -> usize {
+        self.database_references.len()
+    }
+
+    /// Get the references to sequence databases given in DBREF records, with the differences
+    /// in sequence given in SEQADV records
+    pub fn database_references(&self) -> impl DoubleEndedIterator<Item = &DatabaseReference> + '_ {
+        self.database_references.iter()
+    }
+
+    /// Get the references to sequence databases given in DBREF records, as mutable references
+    pub fn database_references_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut DatabaseReference> + '_ {
+        self.database_references.iter_mut()
+    }
+
+    /// Get the references to sequence databases of the Chain with the given id, a Chain can
+    /// refer to multiple database entries (like a chimeric protein)
+    pub fn chain_database_references(
+        &self,
+        chain_id: char,
+    ) -> impl DoubleEndedIterator<Item = &DatabaseReference> + '_ {
+        self.database_references
+            .iter()
+            .filter(move |reference| reference.chain_id() == chain_id)
+    }
+
+    /// Add a reference to a sequence database, to be written as DBREF record (or DBREF1 and
+    /// DBREF2 records if it does not fit) followed by the SEQADV records of its differences
+    pub fn add_database_reference(&mut self, reference: DatabaseReference) {
+        self.database_references.push(reference);
+    }
+
+    /// Get the number of disulfide bridges given in SSBOND records
+    pub fn ssbond_count(&self) -> usize {
+        self.ssbonds.len()
+    }
+
+    /// Get the disulfide bridges given in SSBOND records
+    pub fn ssbonds(&self) -> impl DoubleEndedIterator<Item = &SSBond> + '_ {
+        self.ssbonds.iter()
+    }
+
+    /// Get the disulfide bridges given in SSBOND records, as mutable references
+    pub fn ssbonds_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut SSBond> + '_ {
+        self.ssbonds.iter_mut()
+    }
+
+    /// Add a disulfide bridge, to be written as SSBOND record
+    pub fn add_ssbond(&mut self, ssbond: SSBond) {
+        self.ssbonds.push(ssbond);
+    }
+
+    /// Get the number of connections given in LINK records
+    pub fn link_count(&self) -> usize {
+        self.links.len()
+    }
+
+    /// Get the connections between Atoms of different Residues given in LINK records, like
+    /// glycosylation sites and metal coordination
+    pub fn links(&self) -> impl DoubleEndedIterator<Item = &Link> + '_ {
+        self.links.iter()
+    }
+
+    /// Get the connections given in LINK records, as mutable references
+    pub fn links_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Link> + '_ {
+        self.links.iter_mut()
+    }
+
+    /// Add a connection between Atoms of different Residues, to be written as LINK record
+    pub fn add_link(&mut self, link: Link) {
+        self.links.push(link);
+    }
+
+    /// Get the number of cis peptide bonds given in CISPEP records
+    pub fn cis_peptide_count(&self) -> usize {
+        self.cis_peptides.len()
+    }
+
+    /// Get the cis peptide bonds given in CISPEP records, of all Models
+    pub fn cis_peptides(&self) -> impl DoubleEndedIterator<Item = &CisPeptide> + '_ {
+        self.cis_peptides.iter()
+    }
+
+    /// Get the cis peptide bonds given in CISPEP records, as mutable references
+    pub fn cis_peptides_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut CisPeptide> + '_ {
+        self.cis_peptides.iter_mut()
+    }
+
+    /// Get the cis peptide bonds of the Model with the given serial number. Files with a single
+    /// Model use 0 as model number, as do the Models parsed from files without MODEL records.
+    pub fn model_cis_peptides(
+        &self,
+        model_serial_number: usize,
+    ) -> impl DoubleEndedIterator<Item = &CisPeptide> + '_ {
+        self.cis_peptides
+            .iter()
+            .filter(move |cis_peptide| cis_peptide.model_number() == model_serial_number)
+    }
+
+    /// Add a cis peptide bond, to be written as CISPEP record
+    pub fn add_cis_peptide(&mut self, cis_peptide: CisPeptide) {
+        self.cis_peptides.push(cis_peptide);
+    }
+
+    /// Get the number of functional sites given in SITE records
+    pub fn site_count(&self) -> usize {
+        self.sites.len()
+    }
+
+    /// Get the functional sites given in SITE records, like active sites and binding sites
+    pub fn sites(&self) -> impl DoubleEndedIterator<Item = &Site> + '_ {
+        self.sites.iter()
+    }
+
+    /// Get the functional sites given in SITE records, as mutable references
+    pub fn sites_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Site> + '_ {
+        self.sites.iter_mut()
+    }
+
+    /// Get the functional site with the given name, like `AC1`
+    pub fn site(&self, id: &str) -> Option<&Site> {
+        self.sites.iter().find(|site| site.id() == id)
+    }
+
+    /// Add a functional site, to be written as SITE records
+    pub fn add_site(&mut self, site: Site) {
+        self.sites.push(site);
+    }
+
+    /// Get the Residues of the functional site with the given name in the first Model, in the
+    /// order of the SITE records. Residues that are not found in the first Model, or that have
+    /// another name, are left out.
+    pub fn site_residues(&self, id: &str) -> Vec<&Residue> {
+        self.site(id).map_or_else(Vec::new, |site| {
+            site.residues()
+                .filter_map(|(name, chain_id, serial_number, insertion_code)| {
+                    let insertion_code = Some(insertion_code).filter(|code| *code != ' ');
+                    self.models
+                        .first()?
+                        .all_chains()
+                        .filter(|chain| chain.id() == chain_id)
+                        .flat_map(|chain| chain.residues())
+                        .find(|residue| residue.numbering() == (serial_number, insertion_code))
+                        .filter(|residue| residue.id() == name)
+                })
+                .collect()
+        })
+    }
+
+    /// Get the number of helices given in HELIX records
+    pub fn helix_count(&self) -> usize {
+        self.helices.len()
+    }
+
+    /// Get the helices of the secondary structure given in HELIX records
+    pub fn helices(&self) -> impl DoubleEndedIterator<Item = &Helix> + '_ {
+        self.helices.iter()
+    }
+
+    /// Get the helices of the secondary structure given in HELIX records, as mutable references
+    pub fn helices_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Helix> + '_ {
+        self.helices.iter_mut()
+    }
+
+    /// Add a helix, to be written as HELIX record
+    pub fn add_helix(&mut self, helix: Helix) {
+        self.helices.push(helix);
+    }
+
+    /// Get the number of bonds given in CONECT records, see `connections`
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Get the bonds given in CONECT records, as pairs of Atom serial numbers with the lowest
+    /// serial number first, sorted. Every bond is listed once, even though a PDB file lists it
+    /// for both Atoms. The serial numbers refer to the Atoms of every Model, see `bonds` for the
+    /// bonds as AtomIds.
+    pub fn connections(&self) -> impl DoubleEndedIterator<Item = (usize, usize)> + '_ {
+        self.connections.iter().copied()
+    }
+
+    /// Get the serial numbers of the Atoms bonded to the Atom with the given serial number
+    /// according to the CONECT records, sorted
+    pub fn bonded_serial_numbers(&self, serial_number: usize) -> Vec<usize> {
+        let mut bonded = self
+            .connections
+            .iter()
+            .filter_map(|(a, b)| {
+                if *a == serial_number {
+                    Some(*b)
+                } else if *b == serial_number {
+                    Some(*a)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<usize>>();
+        bonded.sort_unstable();
+        bonded
+    }
+
+    /// Add a bond between the Atoms with the given serial numbers, to be written as CONECT
+    /// records. Adding a bond that is already present, in either order, does nothing.
+    /// ## Fails
+    /// It fails if both serial numbers are the same.
+    pub fn add_connection(&mut self, a: usize, b: usize) -> Result<(), String> {
+        if a == b {
+            return Err(format!("An Atom cannot be bonded to itself ({})", a));
+        }
+        self.connections.insert((a.min(b), a.max(b)));
+        Ok(())
+    }
+
+    /// Remove all bonds matching the given predicate, which is given the serial numbers of both
+    /// Atoms with the lowest first
+    pub fn remove_connections_by<F>(&mut self, predicate: F)
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        self.connections.retain(|(a, b)| !predicate(*a, *b));
+    }
+
+    /// Get the bonds given in CONECT records (see `connections`) as pairs of AtomIds, so they can
+    /// be used with `get`, `StructureDiff` and `drain_atoms_by`. As the serial numbers refer to the
+    /// Atoms of every Model, the bonds are given for every Model containing both Atoms, in the
+    /// order of the Models and then in the order of `connections`.
+    pub fn bonds(&self) -> Vec<(AtomId, AtomId)> {
+        let mut bonds = Vec::new();
+        for model in self.models() {
+            let ids = atom_ids_by_serial_number(model);
+            bonds.extend(
+                self.connections
+                    .iter()
+                    .filter_map(|(a, b)| Some((ids.get(a)?.clone(), ids.get(b)?.clone()))),
+            );
+        }
+        bonds
+    }
+
+    /// Get the Atoms bonded to the Atom with the given AtomId according to the CONECT records, in
+    /// the same Model and sorted by serial number. It is empty if the Atom does not exist.
+    pub fn bonded_atoms(&self, id: &AtomId) -> Vec<AtomId> {
+        let (model, serial_number) = match self.bond_target(id) {
+            Some(target) => target,
+            None => return Vec::new(),
+        };
+        let ids = atom_ids_by_serial_number(model);
+        self.bonded_serial_numbers(serial_number)
+            .into_iter()
+            .filter_map(|serial_number| ids.get(&serial_number).cloned())
+            .collect()
+    }
+
+    /// Add a bond between the Atoms with the given AtomIds, to be written as CONECT records, see
+    /// `add_connection`. The bond is stored by the serial numbers of the Atoms, so it holds for
+    /// the Atoms with the same serial numbers in the other Models as well.
+    /// ## Fails
+    /// It fails if either Atom does not exist, if the Atoms are in different Models or if both
+    /// Atoms have the same serial number.
+    pub fn add_bond(&mut self, a: &AtomId, b: &AtomId) -> Result<(), String> {
+        if a.model_serial_number() != b.model_serial_number() {
+            return Err(format!(
+                "The Atoms {} and {} are in different Models, so they cannot be bonded",
+                a, b
+            ));
+        }
+        let serial_number = |id: &AtomId| {
+            self.bond_target(id)
+                .map(|(_, serial_number)| serial_number)
+                .ok_or_else(|| format!("The Atom {} does not exist", id))
+        };
+        let (a, b) = (serial_number(a)?, serial_number(b)?);
+        self.add_connection(a, b)
+    }
+
+    /// Find the Model and the serial number of the Atom with the given AtomId
+    fn bond_target(&self, id: &AtomId) -> Option<(&Model, usize)> {
+        let model = self
+            .models()
+            .find(|model| model.serial_number() == id.model_serial_number())?;
+        Some((model, self.get(id)?.serial_number()))
+    }
+
+    /// Get the number of Chains with a sequence given in SEQRES records
+    pub fn seqres_count(&self) -> usize {
+        self.seqres.len()
+    }
+
+    /// Get the number of SEQRES lines needed to write the sequences, with 13 residues on a line
+    pub fn seqres_line_count(&self) -> usize {
+        self.seqres
+            .iter()
+            .map(|(_, residues)| residues.len().div_ceil(13))
+            .sum()
+    }
+
+    /// Get the sequences from the SEQRES records, as the chain id and the residue names
+    pub fn seqres(&self) -> impl DoubleEndedIterator<Item = &(char, Vec<String>)> + '_ {
+        self.seqres.iter()
+    }
+
+    /// Get the sequence from the SEQRES records for the Chain with the given id, if available
+    pub fn chain_seqres(&self, chain_id: char) -> Option<&[String]> {
+        self.seqres
+            .iter()
+            .find(|(id, _)| *id == chain_id)
+            .map(|(_, residues)| &residues[..])
+    }
+
+    /// Add residue names to the sequence of the Chain with the given id, as done for every SEQRES
+    /// line while parsing. If there is no sequence for this Chain yet a new one is started.
+    pub fn add_seqres(&mut self, chain_id: char, residues: Vec<String>) {
+        if let Some((_, sequence)) = self.seqres.iter_mut().find(|(id, _)| *id == chain_id) {
+            sequence.extend(residues);
+        } else {
+            self.seqres.push((chain_id, residues));
+        }
+    }
+
+    /// Set the sequence of the Chain with the given id, replacing the current sequence if there is one
+    pub fn set_seqres(&mut self, chain_id: char, residues: Vec<String>) {
+        if let Some((_, sequence)) = self.seqres.iter_mut().find(|(id, _)| *id == chain_id) {
+            *sequence = residues;
+        } else {
+            self.seqres.push((chain_id, residues));
+        }
+    }
+
+    /// Calculate the sequences of the Chains in the first Model of this PDB from the Residues
+    /// actually present. Only polymer Residues are used, so standard amino acids and nucleotides
+    /// and the modified Residues (see `Chain::modified_residues`), also if these are given as
+    /// hetero atoms. The Residues are ordered by serial number. Chains without any polymer
+    /// Residues (like water or ligand only chains) get no sequence.
+    pub fn calculate_seqres(&self) -> Vec<(char, Vec<String>)> {
+        let mut output = Vec::new();
+        if let Some(model) = self.models.first() {
+            let mut ids = Vec::new();
+            for chain in model.all_chains() {
+                if !ids.contains(&chain.id()) {
+                    ids.push(chain.id());
+                }
+            }
+            for id in ids {
+                let mut residues = model
+                    .all_chains()
+                    .filter(|chain| chain.id() == id)
+                    .flat_map(|chain| {
+                        chain
+                            .residues()
+                            .filter(|r| reference_tables::is_standard_residue(&r.id()))
+                            .chain(chain.modified_residues())
+                    })
+                    .collect::<Vec<&Residue>>();
+                if residues.is_empty() {
+                    continue;
+                }
+                residues.sort_by_key(|r| r.numbering());
+                output.push((id, residues.iter().map(|r| r.id()).collect()));
+            }
+        }
+        output
+    }
+
+    /// Generate the SEQRES records from the Residues actually present in this PDB, see
+    /// `calculate_seqres()`, so that they are written when saving. This is useful for
+    /// structures that are built or heavily edited programmatically.
+    /// ## Arguments
+    /// * `overwrite` - if `true` all current SEQRES records are replaced, otherwise only the
+    ///   Chains without a sequence get one
+    pub fn generate_seqres(&mut self, overwrite: bool) {
+        if overwrite {
+            self.seqres.clear();
+        }
+        for (id, residues) in self.calculate_seqres() {
+            if self.chain_seqres(id).is_none() {
+                self.seqres.push((id, residues));
+            }
+        }
+    }
+
     /// Returns `true` if the PDB has a UnitCell
     pub fn has_unit_cell(&self) -> bool {
         self.unit_cell.is_some()
@@ -232,7 +1283,31 @@ impl PDB {
         self.symmetry = Some(symmetry);
     }
 
-    /// Adds a Model to this PDB
+    /// Get the Matthews coefficient (VM, in Å³/Da), the volume of the unit cell divided by the
+    /// total molecular weight of all molecules in the unit cell. Returns `None` if the PDB has no
+    /// UnitCell or Symmetry, or if the molecular weight or number of molecules is not positive.
+    /// ## Arguments
+    /// * `molecular_weight` - the molecular weight of one molecule in Da
+    /// * `n_molecules_per_asu` - the number of molecules in the asymmetric unit, if `None` this is
+    ///   the Z value of the UnitCell divided by the number of general positions of the space group
+    ///   (see `Symmetry::z`), or 1 if the Z value is not known
+    pub fn matthews_coefficient(
+        &self,
+        molecular_weight: f64,
+        n_molecules_per_asu: Option<usize>,
+    ) -> Option<f64> {
+        let (unit_cell, symmetry) = (self.unit_cell.as_ref()?, self.symmetry.as_ref()?);
+        let positions = symmetry.z();
+        let n = n_molecules_per_asu
+            .or_else(|| unit_cell.z().map(|z| z / positions))
+            .unwrap_or(1);
+        if n == 0 || molecular_weight.is_nan() || molecular_weight <= 0.0 {
+            return None;
+        }
+        Some(unit_cell.volume() / (molecular_weight * (positions * n) as f64))
+    }
+
+    /// Adds a Model to this PDB, after the Models already in it
     pub fn add_model(&mut self, new_model: Model) {
         self.models.push(new_model);
     }
@@ -312,6 +1387,96 @@ impl PDB {
         self.models.get_mut(index)
     }
 
+    /// Get the Atom identified by the given AtomId. The Model is found by its serial number, the
+    /// Chain by its id, both Normal and Hetero Chains are searched, and the Residue by its serial
+    /// number and insertion code.
+    ///
+    /// ## Fails
+    /// It fails when no Atom matches the identifier.
+    pub fn get(&self, id: &AtomId) -> Option<&Atom> {
+        self.models
+            .iter()
+            .filter(|model| model.serial_number() == id.model_serial_number())
+            .flat_map(|model| model.all_chains())
+            .filter(|chain| chain.id() == id.chain_id())
+            .flat_map(|chain| chain.residues())
+            .filter(|residue| {
+                residue.numbering() == (id.residue_serial_number(), id.insertion_code())
+            })
+            .flat_map(|residue| residue.atoms())
+            .find(|atom| {
+                atom.name() == id.name() && atom.alternate_location() == id.alternate_location()
+            })
+    }
+
+    /// Get the Atom identified by the given AtomId as a mutable reference, see `get`.
+    ///
+    /// ## Fails
+    /// It fails when no Atom matches the identifier.
+    pub fn get_mut(&mut self, id: &AtomId) -> Option<&mut Atom> {
+        self.models
+            .iter_mut()
+            .filter(|model| model.serial_number() == id.model_serial_number())
+            .flat_map(|model| model.all_chains_mut())
+            .filter(|chain| chain.id() == id.chain_id())
+            .flat_map(|chain| chain.residues_mut())
+            .filter(|residue| {
+                residue.numbering() == (id.residue_serial_number(), id.insertion_code())
+            })
+            .flat_map(|residue| residue.atoms_mut())
+            .find(|atom| {
+                atom.name() == id.name() && atom.alternate_location() == id.alternate_location()
+            })
+    }
+
+    /// Get the Chain with the given id in the first Model, a shortcut for single Model
+    /// structures. Use `Model::chain_by_id` to search the other Models.
+    ///
+    /// ## Fails
+    /// It fails when there are no Models or no Chain has the given id.
+    pub fn chain_by_id(&self, id: char) -> Option<&Chain> {
+        self.models.first()?.chain_by_id(id)
+    }
+
+    /// Get the Chain with the given id in the first Model as a mutable reference, see `chain_by_id`.
+    ///
+    /// ## Fails
+    /// It fails when there are no Models or no Chain has the given id.
+    pub fn chain_by_id_mut(&mut self, id: char) -> Option<&mut Chain> {
+        self.models.first_mut()?.chain_by_id_mut(id)
+    }
+
+    /// Get the Residue with the given serial number and insertion code in the Chain with the given
+    /// id in the first Model, a shortcut for single Model structures. Use `Model::residue_by_id`
+    /// to search the other Models.
+    ///
+    /// ## Fails
+    /// It fails when there are no Models or no Residue matches the chain id, serial number and
+    /// insertion code.
+    pub fn residue_by_id(&self, id: ResidueId) -> Option<&Residue> {
+        self.models.first()?.residue_by_id(id)
+    }
+
+    /// Get the Residue with the given serial number and insertion code in the Chain with the given
+    /// id in the first Model as a mutable reference, see `residue_by_id`.
+    ///
+    /// ## Fails
+    /// It fails when there are no Models or no Residue matches the chain id, serial number and
+    /// insertion code.
+    pub fn residue_by_id_mut(&mut self, id: ResidueId) -> Option<&mut Residue> {
+        self.models.first_mut()?.residue_by_id_mut(id)
+    }
+
+    /// Get all Atoms (Normal and Hetero) of the first Model together with the Chain and Residue
+    /// they are part of, a shortcut for single Model structures. Use
+    /// `Model::atoms_with_hierarchy` for the other Models.
+    pub fn atoms_with_hierarchy(&self) -> impl Iterator<Item = (&Chain, &Residue, &Atom)> + '_ {
+        self.models
+            .first()
+            .into_iter()
+            .flat_map(|model| model.atoms_with_hierarchy())
+    }
+
     /// Get a specific Chain from the Chains making up this PDB. Including Hetero Atoms.
     ///
     /// ## Arguments
@@ -441,10 +1606,7 @@ impl PDB {
     /// This disregards all Normal Chains.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn hetero_chains_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Chain> + '_ {
-        self.models
-            .iter_mut()
-            .map(|a| a.hetero_chains_mut())
-            .flatten()
+        self.models.iter_mut().flat_map(|a| a.hetero_chains_mut())
     }
 
     /// Get the list of Residues making up this Model.
@@ -458,10 +1620,7 @@ impl PDB {
     /// This disregards all Normal Residues.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn hetero_residues_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Residue> + '_ {
-        self.models
-            .iter_mut()
-            .map(|a| a.hetero_residues_mut())
-            .flatten()
+        self.models.iter_mut().flat_map(|a| a.hetero_residues_mut())
     }
 
     /// Get the list of Atoms making up this Model.
@@ -475,10 +1634,7 @@ impl PDB {
     /// This disregards all Normal Atoms.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn hetero_atoms_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Atom> + '_ {
-        self.models
-            .iter_mut()
-            .map(|a| a.hetero_atoms_mut())
-            .flatten()
+        self.models.iter_mut().flat_map(|a| a.hetero_atoms_mut())
     }
 
     /// Get the list of Chains making up this Model.
@@ -506,10 +1662,7 @@ impl PDB {
     /// This includes all Normal and Hetero Residues.
     /// Double ended so iterating from the end is just as fast as from the start.
     pub fn all_residues_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Residue> + '_ {
-        self.models
-            .iter_mut()
-            .map(|a| a.all_residues_mut())
-            .flatten()
+        self.models.iter_mut().flat_map(|a| a.all_residues_mut())
     }
 
     /// Get the list of Atoms making up this Model.
@@ -526,8 +1679,62 @@ impl PDB {
         self.models.iter_mut().flat_map(|a| a.all_atoms_mut())
     }
 
+    /// Get all distinct alternate location identifiers used in this PDB, sorted and excluding the blank (' ') identifier
+    pub fn alternate_locations(&self) -> Vec<char> {
+        let mut locations = self
+            .all_atoms()
+            .map(|atom| atom.alternate_location())
+            .filter(|location| *location != ' ')
+            .collect::<Vec<char>>();
+        locations.sort_unstable();
+        locations.dedup();
+        locations
+    }
+
+    /// Returns `true` if any Atom in this PDB has an alternate location identifier, so if the
+    /// structure contains alternate conformations
+    pub fn has_multiple_conformations(&self) -> bool {
+        self.all_atoms()
+            .any(|atom| atom.alternate_location() != ' ')
+    }
+
+    /// Get the number of distinct alternate location identifiers used in this PDB (see
+    /// `alternate_locations`), for example 2 for a structure with A and B conformers. This is 0
+    /// if the PDB does not contain alternate conformations.
+    pub fn conformation_count(&self) -> usize {
+        self.alternate_locations().len()
+    }
+
+    /// Create a copy of this PDB containing only a single conformer: all Atoms with the `preferred`
+    /// alternate location identifier and all Atoms without an alternate location (' '). Residues
+    /// left without any Atoms are removed. Residues with conformers named differently (see
+    /// `Residue::conformer_name`) get the name of the preferred conformer.
+    /// ## Arguments
+    /// * `preferred` - the alternate location identifier to keep
+    pub fn select_altloc(&self, preferred: char) -> PDB {
+        let mut pdb = self.clone();
+        pdb.remove_atoms_by(|atom| {
+            atom.alternate_location() != ' ' && atom.alternate_location() != preferred
+        });
+        pdb.remove_residues_by(|residue| residue.atom_count() == 0);
+        for residue in pdb.all_residues_mut() {
+            residue.keep_conformer_name(preferred);
+        }
+        pdb
+    }
+
+    /// Split this PDB into one PDB per alternate location identifier (see `alternate_locations`),
+    /// each containing the Atoms with that identifier and all Atoms without an alternate location,
+    /// see `select_altloc`. If this PDB contains no alternate locations the map is empty.
+    pub fn split_by_altloc(&self) -> HashMap<char, PDB> {
+        self.alternate_locations()
+            .into_iter()
+            .map(|location| (location, self.select_altloc(location)))
+            .collect()
+    }
+
     /// Remove all Atoms matching the given predicate. The predicate will be run on all Atoms (Normal and Hetero).
-    /// As this is done in place this is the fastest way to remove Atoms from this Model.
+    /// As this is done in place this is the fastest way to remove Atoms from this PDB. The remaining Atoms keep their order.
     pub fn remove_atoms_by<F>(&mut self, predicate: F)
     where
         F: Fn(&Atom) -> bool,
@@ -537,8 +1744,69 @@ impl PDB {
         }
     }
 
+    /// Remove all Atoms that are not selected by the given Selection in all Models of this PDB,
+    /// and the Residues and Chains that are left empty, see `Model::keep_selection`
+    pub fn keep_selection(&mut self, selection: &Selection) {
+        for model in self.models_mut() {
+            model.keep_selection(selection);
+        }
+    }
+
+    /// Remove all Atoms matching the given predicate in all Models and return them, each with the
+    /// AtomId telling where it came from, see `Model::drain_atoms_by`.
+    pub fn drain_atoms_by<F>(&mut self, predicate: F) -> Vec<(AtomId, Atom)>
+    where
+        F: Fn(&Atom) -> bool,
+    {
+        self.models_mut()
+            .flat_map(|model| model.drain_atoms_by(&predicate))
+            .collect()
+    }
+
+    /// Remove all Residues matching the given predicate in all Models and return them as a Model
+    /// per Model of this PDB, see `Model::drain_residues_by`.
+    pub fn drain_residues_by<F>(&mut self, predicate: F) -> Vec<Model>
+    where
+        F: Fn(&Residue) -> bool,
+    {
+        self.models_mut()
+            .map(|model| model.drain_residues_by(&predicate))
+            .collect()
+    }
+
+    /// Remove all Chains matching the given predicate in all Models and return them as a Model
+    /// per Model of this PDB, see `Model::drain_chains_by`.
+    pub fn drain_chains_by<F>(&mut self, predicate: F) -> Vec<Model>
+    where
+        F: Fn(&Chain) -> bool,
+    {
+        self.models_mut()
+            .map(|model| model.drain_chains_by(&predicate))
+            .collect()
+    }
+
+    /// Apply the given function to every Residue (Normal and Hetero) in all Models of this PDB,
+    /// to edit the Residues in place, see `Chain::map_residues_mut`.
+    pub fn map_residues_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut Residue),
+    {
+        for model in self.models_mut() {
+            model.map_residues_mut(&mut f);
+        }
+    }
+
+    /// Get the Residues (Normal and Hetero) in all Models of this PDB with the given name, for example "ALA"
+    pub fn residues_by_name<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl DoubleEndedIterator<Item = &'a Residue> + 'a {
+        self.all_residues()
+            .filter(move |residue| residue.id() == name)
+    }
+
     /// Remove all Residues matching the given predicate. The predicate will be run on all Residues (Normal and Hetero).
-    /// As this is done in place this is the fastest way to remove Residues from this Model.
+    /// As this is done in place this is the fastest way to remove Residues from this PDB. The remaining Residues keep their order.
     pub fn remove_residues_by<F>(&mut self, predicate: F)
     where
         F: Fn(&Residue) -> bool,
@@ -548,8 +1816,8 @@ impl PDB {
         }
     }
 
-    /// Remove all Residues matching the given predicate. The predicate will be run on all Residues (Normal and Hetero).
-    /// As this is done in place this is the fastest way to remove Residues from this Model.
+    /// Remove all Chains matching the given predicate. The predicate will be run on all Chains (Normal and Hetero).
+    /// As this is done in place this is the fastest way to remove Chains from this PDB. The remaining Chains keep their order.
     pub fn remove_chains_by<F>(&mut self, predicate: F)
     where
         F: Fn(&Chain) -> bool,
@@ -559,8 +1827,8 @@ impl PDB {
         }
     }
 
-    /// Remove all Chains matching the given predicate. The predicate will be run on all Chains (Normal and Hetero).
-    /// As this is done in place this is the fastest way to remove Chains from this Model.
+    /// Remove all Models matching the given predicate.
+    /// As this is done in place this is the fastest way to remove Models from this PDB. The remaining Models keep their order.
     pub fn remove_models_by<F>(&mut self, predicate: F)
     where
         F: Fn(&Model) -> bool,
@@ -570,6 +1838,86 @@ impl PDB {
             .extend(models.into_iter().filter(|model| !predicate(model)));
     }
 
+    /// Remove all water Residues (Normal and Hetero) from all Models, see `Model::remove_waters`.
+    /// It returns the number of removed Residues.
+    pub fn remove_waters(&mut self) -> usize {
+        self.models_mut().map(|model| model.remove_waters()).sum()
+    }
+
+    /// Remove the alternate conformations of all Models by keeping only the copy with the highest
+    /// occupancy of every Atom, see `Residue::remove_alternate_conformers`. Use `select_altloc` to
+    /// keep a whole conformer instead. It returns the number of removed Atoms.
+    pub fn remove_alternate_conformers(&mut self) -> usize {
+        self.models_mut()
+            .map(|model| model.remove_alternate_conformers())
+            .sum()
+    }
+
+    /// Renumber the water Residues of all Models so that they do not share their serial number
+    /// with another Residue of the same Chain, see `Model::renumber_solvent`. The HET records of
+    /// waters (see `hets`) and the links and sites containing waters (see `links` and `sites`)
+    /// are renumbered along using the waters of the first Model. It returns
+    /// the renumbered waters of the first Model as (chain id, old serial number, new serial number).
+    ///
+    /// ## Arguments
+    /// * `start` - the first serial number of the waters in every Chain, if `None` the number
+    ///   following the highest serial number of the other Residues in the Chain is used
+    pub fn renumber_solvent(&mut self, start: Option<usize>) -> Vec<(char, usize, usize)> {
+        let mut models = self.models_mut();
+        let renumbered = models
+            .next()
+            .map_or_else(Vec::new, |model| model.renumber_solvent(start));
+        for model in models {
+            model.renumber_solvent(start);
+        }
+        for het in self
+            .hets
+            .iter_mut()
+            .filter(|het| reference_tables::is_water_residue(&het.id()))
+        {
+            if let Some((_, _, new)) = renumbered.iter().find(|(chain_id, old, _)| {
+                *chain_id == het.chain_id() && *old == het.residue_serial_number()
+            }) {
+                het.set_residue_serial_number(*new);
+            }
+        }
+        for link in self.links.iter_mut() {
+            let [a, b] = [0, 1].map(|index| {
+                let old = (
+                    link.chain_ids()[index],
+                    link.residue_serial_numbers()[index],
+                );
+                if !reference_tables::is_water_residue(link.residue_names()[index]) {
+                    return old;
+                }
+                renumbered
+                    .iter()
+                    .find(|(chain_id, serial_number, _)| (*chain_id, *serial_number) == old)
+                    .map_or(old, |(chain_id, _, new)| (*chain_id, *new))
+            });
+            link.set_residues([a.0, b.0], [a.1, b.1])
+                .expect("Renumbered chain ids are always valid");
+        }
+        for site in self.sites.iter_mut() {
+            let moved = site
+                .residues()
+                .enumerate()
+                .filter(|(_, (name, _, _, _))| reference_tables::is_water_residue(name))
+                .filter_map(|(index, (_, chain_id, serial_number, _))| {
+                    renumbered
+                        .iter()
+                        .find(|(c, old, _)| (*c, *old) == (chain_id, serial_number))
+                        .map(|(_, _, new)| (index, chain_id, *new))
+                })
+                .collect::<Vec<_>>();
+            for (index, chain_id, serial_number) in moved {
+                site.set_residue(index, chain_id, serial_number)
+                    .expect("Renumbered residues are always valid");
+            }
+        }
+        renumbered
+    }
+
     /// Remove the Model specified.
     ///
     /// ## Arguments
@@ -601,34 +1949,329 @@ impl PDB {
     }
 
     /// This renumbers all numbered structs in the PDB.
-    /// So it renumbers models, atoms, residues, chains and MtriXs.
+    /// So it renumbers models, atoms, residues, chains and MtriXs. The Atoms are numbered in the
+    /// order they are saved, leaving a serial number for the TER record after every (Normal) Chain.
+    /// The bonds (see `connections`) are renumbered along using the Atoms of the first Model,
+    /// bonds to Atoms not in the first Model are removed. The disulfide bridges (see `ssbonds`),
+    /// links (see `links`), cis peptides (see `cis_peptides`), sites (see `sites`) and helices
+    /// (see `helices`) are renumbered along using the Residues of the first Model, the model numbers of the cis
+    /// peptides are renumbered along with the Models. The insertion codes of the Residues are
+    /// removed, as the new serial numbers are unique.
     pub fn renumber(&mut self) {
-        let mut model_counter = 1;
-        for model in self.models_mut() {
-            model.set_serial_number(model_counter);
-            model_counter += 1;
-
-            let mut counter = 1;
-            for atom in model.all_atoms_mut() {
-                atom.set_serial_number(counter);
-                counter += 1;
+        let old_serial_numbers = self.models.first().map_or_else(Vec::new, |model| {
+            model
+                .all_atoms()
+                .map(|atom| atom.serial_number())
+                .collect::<Vec<usize>>()
+        });
+        let old_residues = self.models.first().map_or_else(Vec::new, |model| {
+            model
+                .all_chains()
+                .flat_map(|chain| {
+                    chain.residues().map(move |residue| {
+                        (
+                            chain.id(),
+                            residue.serial_number(),
+                            residue.insertion_code().unwrap_or(' '),
+                        )
+                    })
+                })
+                .collect::<Vec<(char, usize, char)>>()
+        });
+        let new_models = self
+            .models
+            .iter()
+            .enumerate()
+            .map(|(counter, model)| (model.serial_number(), counter + 1))
+            .collect::<HashMap<usize, usize>>();
+        for cis_peptide in self.cis_peptides.iter_mut() {
+            if let Some(new) = new_models.get(&cis_peptide.model_number()) {
+                cis_peptide.set_model_number(*new);
             }
-            counter = 1;
-            for residue in model.all_residues_mut() {
-                residue.set_serial_number(counter);
+        }
+        for (model_counter, model) in self.models_mut().enumerate() {
+            model.set_serial_number(model_counter + 1);
+
+            // The TER record following every (Normal) Chain consumes a serial number
+            let mut counter = 0;
+            for chain in model.chains_mut() {
+                for atom in chain.atoms_mut() {
+                    counter += 1;
+                    atom.set_serial_number(counter);
+                }
                 counter += 1;
             }
-            counter = 0;
-            for chain in model.all_chains_mut() {
-                chain.set_id(std::char::from_u32((65 + counter % 26) as u32).unwrap());
+            for atom in model.hetero_atoms_mut() {
                 counter += 1;
+                atom.set_serial_number(counter);
+            }
+            for (counter, residue) in model.all_residues_mut().enumerate() {
+                residue.set_serial_number(counter + 1);
+                residue
+                    .set_insertion_code(None)
+                    .expect("An empty insertion code is always valid");
+            }
+            for (counter, chain) in model.all_chains_mut().enumerate() {
+                chain
+                    .set_id(std::char::from_u32((65 + counter % 26) as u32).unwrap())
+                    .expect("Generated chain ids are always valid");
             }
         }
-        let mut counter = 1;
-        for mtrix in &mut self.mtrix {
-            mtrix.serial_number = counter;
-            counter += 1;
+        for (counter, mtrix) in self.mtrix.iter_mut().enumerate() {
+            mtrix.serial_number = counter + 1;
+        }
+        if let Some(model) = self.models.first() {
+            let mut new_residues = HashMap::new();
+            let residues = model.all_chains().flat_map(|chain| {
+                chain
+                    .residues()
+                    .map(move |residue| (chain.id(), residue.serial_number()))
+            });
+            for (old, new) in old_residues.into_iter().zip(residues) {
+                new_residues.entry(old).or_insert(new);
+            }
+            // Renumbered Residues lose their insertion codes, Residues not in the first Model keep
+            // their old numbering
+            let renumber = |chain_id: char, serial_number: usize, insertion_code: char| {
+                new_residues
+                    .get(&(chain_id, serial_number, insertion_code))
+                    .map_or((chain_id, serial_number, insertion_code), |new| {
+                        (new.0, new.1, ' ')
+                    })
+            };
+            for ssbond in self.ssbonds.iter_mut() {
+                let [a, b] = [0, 1].map(|index| {
+                    renumber(
+                        ssbond.chain_ids()[index],
+                        ssbond.residue_serial_numbers()[index],
+                        ssbond.insertion_codes()[index],
+                    )
+                });
+                ssbond
+                    .set_residues([a.0, b.0], [a.1, b.1])
+                    .and_then(|_| ssbond.set_insertion_codes([a.2, b.2]))
+                    .expect("Generated chain ids and insertion codes are always valid");
+            }
+            for link in self.links.iter_mut() {
+                let [a, b] = [0, 1].map(|index| {
+                    renumber(
+                        link.chain_ids()[index],
+                        link.residue_serial_numbers()[index],
+                        link.insertion_codes()[index],
+                    )
+                });
+                link.set_residues([a.0, b.0], [a.1, b.1])
+                    .and_then(|_| link.set_insertion_codes([a.2, b.2]))
+                    .expect("Generated chain ids and insertion codes are always valid");
+            }
+            for cis_peptide in self.cis_peptides.iter_mut() {
+                let [a, b] = [0, 1].map(|index| {
+                    renumber(
+                        cis_peptide.chain_ids()[index],
+                        cis_peptide.residue_serial_numbers()[index],
+                        cis_peptide.insertion_codes()[index],
+                    )
+                });
+                cis_peptide
+                    .set_residues([a.0, b.0], [a.1, b.1])
+                    .and_then(|_| cis_peptide.set_insertion_codes([a.2, b.2]))
+                    .expect("Generated chain ids and insertion codes are always valid");
+            }
+            for site in self.sites.iter_mut() {
+                let renumbered = site
+                    .residues()
+                    .map(|(_, chain_id, serial_number, insertion_code)| {
+                        renumber(chain_id, serial_number, insertion_code)
+                    })
+                    .collect::<Vec<_>>();
+                for (index, (chain_id, serial_number, insertion_code)) in
+                    renumbered.into_iter().enumerate()
+                {
+                    site.set_residue(index, chain_id, serial_number)
+                        .and_then(|_| site.set_insertion_code(index, insertion_code))
+                        .expect("Generated chain ids and insertion codes are always valid");
+                }
+            }
+            for helix in self.helices.iter_mut() {
+                let [a, b] = [0, 1].map(|index| {
+                    renumber(
+                        helix.chain_ids()[index],
+                        helix.residue_serial_numbers()[index],
+                        helix.insertion_codes()[index],
+                    )
+                });
+                helix
+                    .set_residues([a.0, b.0], [a.1, b.1])
+                    .and_then(|_| helix.set_insertion_codes([a.2, b.2]))
+                    .expect("Generated chain ids and insertion codes are always valid");
+            }
+        }
+        for (counter, ssbond) in self.ssbonds.iter_mut().enumerate() {
+            ssbond.set_serial_number(counter + 1);
         }
+        for (counter, helix) in self.helices.iter_mut().enumerate() {
+            helix.set_serial_number(counter + 1);
+        }
+        for (counter, cis_peptide) in self.cis_peptides.iter_mut().enumerate() {
+            cis_peptide.set_serial_number(counter + 1);
+        }
+        if !self.connections.is_empty() {
+            let mut new_serial_numbers = HashMap::new();
+            if let Some(model) = self.models.first() {
+                for (old, atom) in old_serial_numbers.into_iter().zip(model.all_atoms()) {
+                    new_serial_numbers
+                        .entry(old)
+                        .or_insert(atom.serial_number());
+                }
+            }
+            self.connections = std::mem::take(&mut self.connections)
+                .into_iter()
+                .filter_map(|(a, b)| {
+                    let (a, b) = (new_serial_numbers.get(&a)?, new_serial_numbers.get(&b)?);
+                    Some((*a.min(b), *a.max(b)))
+                })
+                .collect();
+        }
+    }
+
+    /// Renumber this PDB (see `renumber`) and apply the given SerialOverflowPolicy to the resulting
+    /// serial numbers. It returns the warnings generated by the policy.
+    /// ## Fails
+    /// It fails if the serial numbers cannot be represented according to the policy, see `SerialOverflowPolicy`.
+    /// In that case the PDB is renumbered but the serial numbers are left too big to save.
+    pub fn renumber_with_policy(
+        &mut self,
+        policy: SerialOverflowPolicy,
+    ) -> Result<Vec<PDBError>, PDBError> {
+        self.renumber();
+        apply_serial_overflow_policy(&mut self.models, "renumber", policy)
+    }
+
+    /// Join another PDB into this PDB (see `join`) and apply the given SerialOverflowPolicy to the
+    /// resulting serial numbers. It returns the warnings generated by the policy.
+    /// ## Fails
+    /// It fails if the serial numbers cannot be represented according to the policy, see `SerialOverflowPolicy`.
+    /// In that case the PDBs are joined but the serial numbers are left too big to save.
+    pub fn join_with_policy(
+        &mut self,
+        other: PDB,
+        policy: SerialOverflowPolicy,
+    ) -> Result<Vec<PDBError>, PDBError> {
+        self.join(other);
+        apply_serial_overflow_policy(&mut self.models, "join", policy)
+    }
+
+    /// Build an assembly by applying every given transformation to a copy of each Model, for
+    /// example the BIOMT operators of a biological assembly. Include the identity transformation
+    /// to keep the original coordinates. With the `Error` and `Hybrid36` policies all copies of a
+    /// Model are placed in a single Model, keeping their Chain ids, and the Atoms are renumbered
+    /// sequentially. With the `WrapWithWarning` policy every copy is placed in a new Model, so that
+    /// the Atom serial numbers stay unique within each Model. When the copies are placed in a single
+    /// Model, Atoms of a copy within 0.1 Å of an already placed Atom of the same element are
+    /// skipped, as these are on a special position and map onto themselves (for example an ion on
+    /// a symmetry axis), Residues and Chains left empty are removed. It returns the warnings
+    /// generated by the policy, and a warning with the number of skipped Atoms if any.
+    /// ## Arguments
+    /// * `transformations` - the transformations generating the copies
+    /// * `policy` - what to do when the serial numbers overflow
+    /// ## Fails
+    /// It fails if the serial numbers cannot be represented according to the policy, see `SerialOverflowPolicy`.
+    /// In that case this PDB is not changed.
+    pub fn build_assembly(
+        &mut self,
+        transformations: &[TransformationMatrix],
+        policy: SerialOverflowPolicy,
+    ) -> Result<Vec<PDBError>, PDBError> {
+        let mut models = Vec::new();
+        let mut skipped = 0;
+        for model in self.models() {
+            let copies = transformations.iter().map(|transformation| {
+                let mut copy = model.clone();
+                copy.apply_transformation(transformation);
+                copy
+            });
+            if policy == SerialOverflowPolicy::WrapWithWarning {
+                models.extend(copies);
+            } else {
+                let mut assembly = Model::new(model.serial_number());
+                let mut placed = PositionGrid::new(SPECIAL_POSITION_TOLERANCE);
+                for mut copy in copies {
+                    let before = copy.total_atom_count();
+                    copy.remove_atoms_by(|atom| placed.contains(atom));
+                    if copy.total_atom_count() < before {
+                        skipped += before - copy.total_atom_count();
+                        copy.remove_residues_by(|residue| residue.atom_count() == 0);
+                        copy.remove_chains_by(|chain| chain.residue_count() == 0);
+                    }
+                    for atom in copy.all_atoms() {
+                        placed.insert(atom);
+                    }
+                    assembly.join(copy);
+                }
+                for (counter, atom) in assembly.all_atoms_mut().enumerate() {
+                    atom.set_serial_number(counter + 1);
+                }
+                models.push(assembly);
+            }
+        }
+        if policy == SerialOverflowPolicy::WrapWithWarning {
+            for (counter, model) in models.iter_mut().enumerate() {
+                model.set_serial_number(counter + 1);
+            }
+        }
+        let mut warnings = apply_serial_overflow_policy(&mut models, "build_assembly", policy)?;
+        if skipped > 0 {
+            warnings.push(PDBError::new(
+                ErrorLevel::LooseWarning,
+                "Special position Atoms skipped",
+                &format!("{} Atoms of the copies made by build_assembly were within {} Å of an Atom of the same element already placed, as they are on a special position (for example an ion on a symmetry axis). These Atoms were skipped.", skipped, SPECIAL_POSITION_TOLERANCE),
+                PDBContext::None,
+            ));
+        }
+        self.models = models;
+        Ok(warnings)
+    }
+
+    /// Expand the asymmetric unit using the MtriX records whose copies are not contained in the
+    /// coordinates (see `MtriX::contained`), by building an assembly (see `build_assembly`) from the
+    /// identity and these transformations. Afterwards these MtriX records are marked as contained.
+    /// If there are no such MtriX records nothing is changed. It returns the warnings generated by the policy.
+    /// ## Fails
+    /// It fails if the serial numbers cannot be represented according to the policy, see `SerialOverflowPolicy`.
+    /// In that case this PDB is not changed.
+    pub fn expand_mtrix(
+        &mut self,
+        policy: SerialOverflowPolicy,
+    ) -> Result<Vec<PDBError>, PDBError> {
+        let mut transformations = vec![TransformationMatrix::identity()];
+        transformations.extend(
+            self.mtrix()
+                .filter(|mtrix| !mtrix.contained() && mtrix.valid())
+                .map(|mtrix| mtrix.transformation().clone()),
+        );
+        if transformations.len() == 1 {
+            return Ok(Vec::new());
+        }
+        let warnings = self.build_assembly(&transformations, policy)?;
+        for mtrix in self.mtrix_mut().filter(|mtrix| mtrix.valid()) {
+            mtrix.set_contained(true);
+        }
+        Ok(warnings)
+    }
+
+    /// Sort the Models by the metadata value stored under the given key (see `Model::metadata`),
+    /// in ascending order. Numbers are sorted numerically and placed before text values, Models
+    /// without this key are placed last. The sort is stable and the Models are not renumbered.
+    /// ## Arguments
+    /// * `key` - the metadata key to sort on, for example the score of docking poses
+    pub fn sort_models_by_metadata(&mut self, key: &str) {
+        self.models
+            .sort_by(|a, b| match (a.metadata(key), b.metadata(key)) {
+                (Some(a), Some(b)) => a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            });
     }
 
     /// Apply a transformation to the position of all atoms (Normal and Hetero) making up this PDB, the new position is immediately set.
@@ -638,9 +2281,77 @@ impl PDB {
         }
     }
 
+    /// Round the positions of all Atoms (Normal and Hetero) to the given number of decimals, in the
+    /// same way as they are written when saving with that number of decimals (see
+    /// `SaveOptions::set_coordinate_decimals`), so the values in memory agree with the saved file.
+    /// Halves are rounded away from zero based on the shortest decimal representation of the value.
+    pub fn round_coordinates(&mut self, decimals: usize) {
+        let round = |value: f64| {
+            crate::save::format_fixed(value, 0, decimals)
+                .parse::<f64>()
+                .unwrap()
+        };
+        for atom in self.all_atoms_mut() {
+            let (x, y, z) = atom.pos();
+            atom.set_pos((round(x), round(y), round(z))).unwrap();
+        }
+    }
+
+    /// Check heuristically if the coordinates of this PDB are fractional instead of orthogonal
+    /// (Cartesian) coordinates in Å, as found in some files converted directly from CIF. They are
+    /// considered fractional if all coordinates of all Atoms (Normal and Hetero) are in [0, 1].
+    /// It returns `false` if there are no Atoms.
+    pub fn coordinates_are_fractional(&self) -> bool {
+        let in_range = |value: f64| (0.0..=1.0).contains(&value);
+        self.all_atoms().next().is_some()
+            && self
+                .all_atoms()
+                .all(|atom| in_range(atom.x()) && in_range(atom.y()) && in_range(atom.z()))
+    }
+
+    /// Convert the positions of all Atoms (Normal and Hetero) from fractional coordinates to
+    /// orthogonal (Cartesian) coordinates in Å. The inverse of the Scale is used if this PDB has a
+    /// valid Scale, otherwise the orthogonalisation matrix of the UnitCell is used. The positions
+    /// are converted unconditionally, see `coordinates_are_fractional` to check if that is needed.
+    /// ## Fails
+    /// It fails with a BreakingError if there is no (valid and invertible) Scale and no UnitCell.
+    pub fn fractional_to_cartesian_coordinates(&mut self) -> Result<(), PDBError> {
+        let transformation = match (&self.scale, &self.unit_cell) {
+            (Some(scale), _) if scale.valid() && scale.transformation().inverse().is_some() => {
+                scale.transformation().inverse().unwrap()
+            }
+            (_, Some(cell)) => cell.orthogonalisation_matrix(),
+            _ => {
+                return Err(PDBError::new(
+                    ErrorLevel::BreakingError,
+                    "No crystal information",
+                    "Fractional coordinates can only be converted to Cartesian coordinates with a SCALE or CRYST1 record.",
+                    PDBContext::none(),
+                ))
+            }
+        };
+        self.apply_transformation(&transformation);
+        Ok(())
+    }
+
+    /// Convert the positions of all Atoms from fractional to orthogonal (Cartesian) coordinates if
+    /// they look fractional (see `coordinates_are_fractional` and `fractional_to_cartesian_coordinates`).
+    /// It returns `true` if the coordinates were converted.
+    /// ## Fails
+    /// It fails with a BreakingError if the coordinates look fractional but there is no Scale or UnitCell.
+    pub fn apply_scale_to_coordinates(&mut self) -> Result<bool, PDBError> {
+        if self.coordinates_are_fractional() {
+            self.fractional_to_cartesian_coordinates()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
     /// Joins two PDBs. If one has multiple models it extends the models of this PDB with the models of the other PDB. If this PDB does
     /// not have any models it moves the models of the other PDB to this PDB. If both have one model it moves all chains/residues/atoms
     /// form the first model of the other PDB to the first model of this PDB. Effectively the same as calling join on those models.
+    /// The Models, or the Chains when joining Models, of the other PDB are added after those of this PDB.
     pub fn join(&mut self, mut other: PDB) {
         if self.model_count() > 1 || other.model_count() > 1 {
             self.models.extend(other.models);
@@ -652,6 +2363,688 @@ impl PDB {
             self.model_mut(0).unwrap().join(other.models.remove(0))
         }
     }
+
+    /// Merge all Models into a single Model with every Model as an alternate location, for
+    /// example to represent two docking poses as conformers of one Model instead of two Models.
+    /// Every Atom appears once for every Model, directly after each other, with alternate location
+    /// `A` for the first Model, `B` for the second and so on, and the occupancy given for its
+    /// Model. The Atoms of the merged Model are numbered from 1 like `renumber` does, the Models
+    /// of this PDB are left unchanged.
+    ///
+    /// ## Arguments
+    /// * `occupancies` - the occupancy of the Atoms of every Model, one for every Model
+    ///
+    /// ## Fails
+    /// It fails with a BreakingError if the number of occupancies differs from the number of
+    /// Models, if there are no or more than 26 Models, if any occupancy is not finite, if any Atom
+    /// already has an alternate location, or if the Models do not contain the same Chains,
+    /// Residues and Atoms (see `Atom::corresponds`) in the same order.
+    pub fn merge_models_as_altlocs(&self, occupancies: &[f64]) -> Result<Model, PDBError> {
+        let error = |short: &str, long: &str| {
+            PDBError::new(ErrorLevel::BreakingError, short, long, PDBContext::none())
+        };
+        if self.models.is_empty() || self.models.len() > 26 {
+            return Err(error(
+                "Invalid number of Models",
+                &format!(
+                    "Only 1 to 26 Models can be merged as alternate locations A to Z, but there are {} Models.",
+                    self.models.len()
+                ),
+            ));
+        }
+        if occupancies.len() != self.models.len() {
+            return Err(error(
+                "Invalid number of occupancies",
+                &format!(
+                    "There are {} Models, but {} occupancies are given.",
+                    self.models.len(),
+                    occupancies.len()
+                ),
+            ));
+        }
+        if let Some(occupancy) = occupancies.iter().find(|o| !o.is_finite()) {
+            return Err(error(
+                "Invalid occupancy",
+                &format!("The occupancy {} is not a finite number.", occupancy),
+            ));
+        }
+        if self
+            .all_atoms()
+            .any(|atom| atom.alternate_location() != ' ')
+        {
+            return Err(error(
+                "Alternate locations present",
+                "The Models already contain alternate locations, so they cannot be merged as alternate locations.",
+            ));
+        }
+        let first = &self.models[0];
+        let layout = |model: &Model| {
+            model
+                .all_chains()
+                .flat_map(|chain| {
+                    chain
+                        .residues()
+                        .map(move |residue| (chain.id(), residue.serial_number(), residue.id()))
+                })
+                .collect::<Vec<_>>()
+        };
+        for model in self.models.iter().skip(1) {
+            if model.chain_count() != first.chain_count()
+                || layout(model) != layout(first)
+                || model.total_atom_count() != first.total_atom_count()
+                || !first
+                    .all_residues()
+                    .zip(model.all_residues())
+                    .all(|(a, b)| a.atom_count() == b.atom_count())
+                || !first
+                    .all_atoms()
+                    .zip(model.all_atoms())
+                    .all(|(a, b)| a.corresponds(b))
+            {
+                return Err(error(
+                    "Models differ in composition",
+                    &format!(
+                        "Model {} does not contain the same Chains, Residues and Atoms as the first Model.",
+                        model.serial_number()
+                    ),
+                ));
+            }
+        }
+
+        let mut residues = self
+            .models
+            .iter()
+            .map(|model| model.all_residues())
+            .collect::<Vec<_>>();
+        let mut merged = first.clone();
+        for residue in merged.all_residues_mut() {
+            let sources = residues
+                .iter_mut()
+                .map(|iter| iter.next().unwrap())
+                .collect::<Vec<&Residue>>();
+            residue.remove_atoms_by(|_| true);
+            for index in 0..sources[0].atom_count() {
+                for (model, source) in sources.iter().enumerate() {
+                    let mut atom = source.atom(index).unwrap().clone();
+                    atom.set_alternate_location((b'A' + model as u8) as char)
+                        .unwrap();
+                    atom.set_occupancy(occupancies[model]).unwrap();
+                    residue.add_atom(atom);
+                }
+            }
+        }
+        // The TER record following every (Normal) Chain consumes a serial number
+        let mut counter = 0;
+        for chain in merged.chains_mut() {
+            for atom in chain.atoms_mut() {
+                counter += 1;
+                atom.set_serial_number(counter);
+            }
+            counter += 1;
+        }
+        for atom in merged.hetero_atoms_mut() {
+            counter += 1;
+            atom.set_serial_number(counter);
+        }
+        Ok(merged)
+    }
+
+    /// Compare this structure with another structure of the same molecule, for example from a
+    /// different crystal form or resolution, see `StructureComparison`. Only the Normal Chains of
+    /// the first Model of both structures are compared. The CA atoms are paired on chain id and
+    /// residue serial number, and superposed for the RMSDs.
+    pub fn compare_structures(&self, other: &PDB) -> StructureComparison {
+        let self_chains = self
+            .models
+            .first()
+            .map_or_else(Vec::new, |model| model.chains().collect::<Vec<&Chain>>());
+        let other_chains = other
+            .models
+            .first()
+            .map_or_else(Vec::new, |model| model.chains().collect::<Vec<&Chain>>());
+        let sequences = |chains: &[&Chain]| {
+            chains
+                .iter()
+                .map(|chain| chain.residues().map(|r| r.id()).collect::<Vec<String>>())
+                .collect::<Vec<Vec<String>>>()
+        };
+        let residue_count =
+            |chains: &[&Chain]| chains.iter().map(|c| c.residue_count()).sum::<usize>() as isize;
+        let ids = |chains: &[&Chain]| chains.iter().map(|c| c.id()).collect::<BTreeSet<char>>();
+        let self_ids = ids(&self_chains);
+        let other_ids = ids(&other_chains);
+
+        let mut pairs = Vec::new();
+        let mut per_chain_rmsd = Vec::new();
+        for chain in &self_chains {
+            let chain_pairs = other_chains
+                .iter()
+                .find(|c| c.id() == chain.id())
+                .map_or_else(Vec::new, |other| ca_pairs(chain, other));
+            let (source, target): (Vec<_>, Vec<_>) =
+                chain_pairs.iter().map(|(a, b)| (a.pos(), b.pos())).unzip();
+            per_chain_rmsd.push((chain.id(), superposed_rmsd(&source, &target)));
+            pairs.extend(chain_pairs);
+        }
+        let (source, target): (Vec<_>, Vec<_>) =
+            pairs.iter().map(|(a, b)| (a.pos(), b.pos())).unzip();
+        let (b_self, b_other): (Vec<_>, Vec<_>) = pairs
+            .iter()
+            .map(|(a, b)| (a.b_factor(), b.b_factor()))
+            .unzip();
+
+        StructureComparison::new(
+            sequences(&self_chains) == sequences(&other_chains),
+            self_ids == other_ids,
+            residue_count(&other_chains) - residue_count(&self_chains),
+            superposed_rmsd(&source, &target),
+            per_chain_rmsd,
+            self_ids.difference(&other_ids).copied().collect(),
+            other_ids.difference(&self_ids).copied().collect(),
+            pearson_correlation(&b_self, &b_other),
+        )
+    }
+
+    /// Cluster the Models of this PDB by their RMSD, for example to find the distinct poses in the
+    /// output of a docking run. The Atoms (Normal and Hetero) of two Models are paired in order, so
+    /// only Models with the same number of Atoms are compared, as checked by `validate_pdb`. Every Model
+    /// joins the cluster of the first earlier Model starting a cluster that is within the cutoff,
+    /// or starts a new cluster. So every Model is only compared with the first Model of every
+    /// cluster, which keeps this tractable for large ensembles with few distinct structures.
+    /// It returns the index of the cluster of every Model, the clusters numbered in order of their first Model.
+    /// ## Arguments
+    /// * `rmsd_cutoff` - the maximal RMSD in Å to consider two Models the same
+    /// * `superpose` - if the Models should be superposed before calculating the RMSD, for docking
+    ///   poses this is generally not wanted as the placement of the pose is the result
+    pub fn cluster_models(&self, rmsd_cutoff: f64, superpose: bool) -> Vec<usize> {
+        // Collect the positions once, so they are not gathered again for every pair
+        let positions = self
+            .models
+            .iter()
+            .map(|model| model.all_atoms().map(|atom| atom.pos()).collect())
+            .collect::<Vec<Vec<(f64, f64, f64)>>>();
+        let mut representatives: Vec<usize> = Vec::new();
+        let mut clusters = Vec::with_capacity(positions.len());
+        for (index, model) in positions.iter().enumerate() {
+            let cluster = representatives.iter().position(|representative| {
+                let representative = &positions[*representative];
+                let rmsd = if superpose {
+                    superposed_rmsd(model, representative)
+                } else {
+                    rmsd(model, representative)
+                };
+                rmsd.is_some_and(|rmsd| rmsd <= rmsd_cutoff)
+            });
+            clusters.push(cluster.unwrap_or_else(|| {
+                representatives.push(index);
+                representatives.len() - 1
+            }));
+        }
+        clusters
+    }
+
+    /// Remove all Models that are within the RMSD cutoff of an earlier Model, keeping the first Model
+    /// of every cluster as found by `cluster_models`. It returns the number of removed Models.
+    /// ## Arguments
+    /// * `rmsd_cutoff` - the maximal RMSD in Å to consider two Models the same
+    /// * `superpose` - if the Models should be superposed before calculating the RMSD
+    pub fn deduplicate_models(&mut self, rmsd_cutoff: f64, superpose: bool) -> usize {
+        let clusters = self.cluster_models(rmsd_cutoff, superpose);
+        let mut seen = 0;
+        let keep = clusters
+            .iter()
+            .map(|cluster| {
+                // Clusters are numbered in order, so a new cluster has the next number
+                let first = *cluster == seen;
+                if first {
+                    seen += 1;
+                }
+                first
+            })
+            .collect::<Vec<bool>>();
+        let models = std::mem::take(&mut self.models);
+        self.models.extend(
+            models
+                .into_iter()
+                .zip(&keep)
+                .filter(|(_, keep)| **keep)
+                .map(|(model, _)| model),
+        );
+        keep.iter().filter(|keep| !**keep).count()
+    }
+
+    /// Get the binding site of the given ligand in the first Model, including the Residues of
+    /// symmetry mates, as ligands sometimes bind at crystal contacts. All Residues (Normal and Hetero)
+    /// with any Atom within the cutoff of any Atom of the ligand are included, together with the
+    /// ligand itself. The copies of the asymmetric unit made by the operators of the space group,
+    /// translated to all unit cells that could be in reach, are searched as well, and only the Residues of
+    /// these copies that are within the cutoff are added. Residues of a copy keep their serial
+    /// numbers but are placed in a Chain with a chain id not used in this Model, one for every
+    /// original Chain and operator. The operator is recorded in the metadata of the returned Model
+    /// under the key `symmetry_copy_<new chain id>` as `chain <original chain id> operator <index>
+    /// translation <a> <b> <c>`, with the index of the operator in `Symmetry::transformations` and
+    /// the translation in unit cells. The metadata is saved with the Model, so the returned Model
+    /// can be saved as a standalone file, the Atoms are renumbered for this.
+    ///
+    /// If this PDB has no unit cell or symmetry only the Residues of the asymmetric unit are used.
+    /// It returns `None` if there is no Model, the ligand cannot be found or no unused chain ids
+    /// are left for the copies.
+    /// ## Arguments
+    /// * `ligand` - the ligand Residue, Hetero Chains are searched first
+    /// * `cutoff` - the maximal distance in Å between an Atom of a Residue and an Atom of the ligand
+    pub fn binding_site_with_symmetry(&self, ligand: ResidueId, cutoff: f64) -> Option<Model> {
+        let model = self.models.first()?;
+        let ligand = model
+            .hetero_chains()
+            .chain(model.chains())
+            .flat_map(|chain| {
+                chain
+                    .residues()
+                    .filter(move |residue| ligand.matches(chain.id(), residue))
+            })
+            .next()?;
+        let ligand_positions = ligand.atoms().map(|atom| atom.pos()).collect::<Vec<_>>();
+        let (ligand_centre, ligand_radius) = bounding_sphere(&ligand_positions)?;
+        let model_positions = model.all_atoms().map(|atom| atom.pos()).collect::<Vec<_>>();
+        let (model_centre, model_radius) = bounding_sphere(&model_positions)?;
+        let near_ligand = |position: (f64, f64, f64)| {
+            distance(position, ligand_centre) <= ligand_radius + cutoff
+                && ligand_positions
+                    .iter()
+                    .any(|ligand| distance(position, *ligand) <= cutoff)
+        };
+
+        // The chains as (hetero, Chain) to keep the Residues Normal or Hetero in the binding site
+        let chains = model
+            .chains()
+            .map(|chain| (false, chain))
+            .chain(model.hetero_chains().map(|chain| (true, chain)))
+            .collect::<Vec<_>>();
+        let add = |site: &mut Model,
+                   hetero: bool,
+                   chain_id: char,
+                   residue: &Residue,
+                   transformation: &TransformationMatrix| {
+            for atom in residue.atoms() {
+                let mut atom = atom.clone();
+                atom.apply_transformation(transformation);
+                if hetero {
+                    site.add_hetero_atom(
+                        atom,
+                        chain_id,
+                        residue.serial_number(),
+                        residue.insertion_code(),
+                        residue.id_array(),
+                    );
+                } else {
+                    site.add_atom(
+                        atom,
+                        chain_id,
+                        residue.serial_number(),
+                        residue.insertion_code(),
+                        residue.id_array(),
+                    );
+                }
+            }
+        };
+
+        let mut site = Model::new(model.serial_number());
+        let identity = TransformationMatrix::identity();
+        for (hetero, chain) in &chains {
+            for residue in chain.residues() {
+                let is_ligand = std::ptr::eq(residue, ligand);
+                if is_ligand || residue.atoms().any(|atom| near_ligand(atom.pos())) {
+                    add(&mut site, *hetero, chain.id(), residue, &identity);
+                }
+            }
+        }
+
+        if let (Some(symmetry), Some(unit_cell)) = (&self.symmetry, &self.unit_cell) {
+            let fractionalisation = unit_cell.fractionalisation_matrix();
+            let orthogonalisation = unit_cell.orthogonalisation_matrix();
+            let ligand_fractional = fractionalisation.apply(ligand_centre);
+            let used = model
+                .all_chains()
+                .map(|chain| chain.id())
+                .collect::<BTreeSet<char>>();
+            let mut free = ('A'..='Z')
+                .chain('a'..='z')
+                .chain('0'..='9')
+                .filter(|id| !used.contains(id));
+            for (index, operator) in symmetry.transformations().iter().enumerate() {
+                // Start from the lattice translation placing the copy closest to the ligand
+                let centre = operator.apply(fractionalisation.apply(model_centre));
+                let base = (
+                    (ligand_fractional.0 - centre.0).round() as i32,
+                    (ligand_fractional.1 - centre.1).round() as i32,
+                    (ligand_fractional.2 - centre.2).round() as i32,
+                );
+                // Only the lattice translations within this radius of the base can place the
+                // copy within reach of the ligand
+                let base_centre = orthogonalisation.apply((
+                    centre.0 + base.0 as f64,
+                    centre.1 + base.1 as f64,
+                    centre.2 + base.2 as f64,
+                ));
+                let reach = model_radius + ligand_radius + cutoff;
+                for (da, db, dc) in unit_cell
+                    .lattice_translations_within(reach + distance(base_centre, ligand_centre))
+                {
+                    let translation = (base.0 + da, base.1 + db, base.2 + dc);
+                    if index == 0 && translation == (0, 0, 0) {
+                        continue;
+                    }
+                    let transformation = fractionalisation
+                        .combine(operator)
+                        .combine(&TransformationMatrix::translation(
+                            translation.0 as f64,
+                            translation.1 as f64,
+                            translation.2 as f64,
+                        ))
+                        .combine(&orthogonalisation);
+                    if distance(transformation.apply(model_centre), ligand_centre) > reach {
+                        continue;
+                    }
+                    // Give the Normal and Hetero Chain with the same id the same new id
+                    for original in &used {
+                        let mut new_id = None;
+                        for (hetero, chain) in
+                            chains.iter().filter(|(_, chain)| chain.id() == *original)
+                        {
+                            for residue in chain.residues().filter(|residue| {
+                                residue
+                                    .atoms()
+                                    .any(|atom| near_ligand(transformation.apply(atom.pos())))
+                            }) {
+                                if new_id.is_none() {
+                                    let id = free.next()?;
+                                    site.set_metadata(
+                                        &format!("symmetry_copy_{}", id),
+                                        MetadataValue::Text(format!(
+                                            "chain {} operator {} translation {} {} {}",
+                                            original,
+                                            index,
+                                            translation.0,
+                                            translation.1,
+                                            translation.2
+                                        )),
+                                    )
+                                    .expect("Invalid metadata key");
+                                    new_id = Some(id);
+                                }
+                                add(
+                                    &mut site,
+                                    *hetero,
+                                    new_id.unwrap(),
+                                    residue,
+                                    &transformation,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for (counter, atom) in site.all_atoms_mut().enumerate() {
+            atom.set_serial_number(counter + 1);
+        }
+        Some(site)
+    }
+}
+
+/// The distance in Å within which a transformed Atom is considered to be on a special position,
+/// mapping onto an Atom of the same element that is already placed
+const SPECIAL_POSITION_TOLERANCE: f64 = 0.1;
+
+/// The positions and elements of Atoms in a grid with cells of the tolerance size, to find Atoms
+/// of the same element within the tolerance by looking at neighbouring cells only
+struct PositionGrid {
+    /// The maximal distance between Atoms considered at the same position
+    tolerance: f64,
+    /// The positions and elements of the Atoms
+    atoms: Vec<((f64, f64, f64), String)>,
+    /// The indices of the Atoms per cell
+    cells: HashMap<(i64, i64, i64), Vec<usize>>,
+}
+
+impl PositionGrid {
+    /// Create an empty grid
+    fn new(tolerance: f64) -> Self {
+        PositionGrid {
+            tolerance,
+            atoms: Vec::new(),
+            cells: HashMap::new(),
+        }
+    }
+
+    /// The cell of the given position
+    fn cell(&self, position: (f64, f64, f64)) -> (i64, i64, i64) {
+        (
+            (position.0 / self.tolerance).floor() as i64,
+            (position.1 / self.tolerance).floor() as i64,
+            (position.2 / self.tolerance).floor() as i64,
+        )
+    }
+
+    /// Add the given Atom
+    fn insert(&mut self, atom: &Atom) {
+        let cell = self.cell(atom.pos());
+        self.cells.entry(cell).or_default().push(self.atoms.len());
+        self.atoms.push((atom.pos(), atom.element()));
+    }
+
+    /// Check if there is an Atom of the same element within the tolerance of the given Atom
+    fn contains(&self, atom: &Atom) -> bool {
+        let (x, y, z) = self.cell(atom.pos());
+        let element = atom.element();
+        (-1..=1).any(|dx| {
+            (-1..=1).any(|dy| {
+                (-1..=1).any(|dz| {
+                    self.cells
+                        .get(&(x + dx, y + dy, z + dz))
+                        .into_iter()
+                        .flatten()
+                        .any(|index| {
+                            let (position, other) = &self.atoms[*index];
+                            *other == element && distance(*position, atom.pos()) <= self.tolerance
+                        })
+                })
+            })
+        })
+    }
+}
+
+/// The AtomIds of all Atoms of the given Model by their serial number, the first Atom is used if
+/// several Atoms share a serial number
+fn atom_ids_by_serial_number(model: &Model) -> HashMap<usize, AtomId> {
+    let mut ids = HashMap::new();
+    for (chain, residue, atom) in model.atoms_with_hierarchy() {
+        ids.entry(atom.serial_number())
+            .or_insert_with(|| AtomId::from_hierarchy(model, chain, residue, atom));
+    }
+    ids
+}
+
+/// The Euclidean distance between two positions
+fn distance(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)).sqrt()
+}
+
+/// The centre of the given positions and the distance to the furthest position from it,
+/// `None` if there are no positions
+fn bounding_sphere(positions: &[(f64, f64, f64)]) -> Option<((f64, f64, f64), f64)> {
+    if positions.is_empty() {
+        return None;
+    }
+    let n = positions.len() as f64;
+    let centre = positions.iter().fold((0.0, 0.0, 0.0), |acc, p| {
+        (acc.0 + p.0 / n, acc.1 + p.1 / n, acc.2 + p.2 / n)
+    });
+    let radius = positions
+        .iter()
+        .map(|p| distance(*p, centre))
+        .fold(0.0, f64::max);
+    Some((centre, radius))
+}
+
+/// The root mean square deviation of the positions paired by index, without superposition.
+/// Returns `None` if there are no positions or the slices are of different lengths.
+fn rmsd(a: &[(f64, f64, f64)], b: &[(f64, f64, f64)]) -> Option<f64> {
+    if a.is_empty() || a.len() != b.len() {
+        return None;
+    }
+    let sum = a
+        .iter()
+        .zip(b)
+        .map(|(a, b)| (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2))
+        .sum::<f64>();
+    Some((sum / a.len() as f64).sqrt())
+}
+
+/// Pair the CA atoms of Residues with the same numbering in both Chains
+fn ca_pairs<'a>(a: &'a Chain, b: &'a Chain) -> Vec<(&'a Atom, &'a Atom)> {
+    a.residues()
+        .filter_map(|residue| {
+            let ca = residue.atom_by_name("CA")?;
+            let other = b
+                .residues()
+                .find(|r| r.numbering() == residue.numbering())?
+                .atom_by_name("CA")?;
+            Some((ca, other))
+        })
+        .collect()
+}
+
+/// The Pearson correlation coefficient of the paired values, 0.0 if it is undefined (less than
+/// two values or no variation in one of the lists)
+fn pearson_correlation(a: &[f64], b: &[f64]) -> f64 {
+    if a.len() < 2 || a.len() != b.len() {
+        return 0.0;
+    }
+    let n = a.len() as f64;
+    let mean_a = a.iter().sum::<f64>() / n;
+    let mean_b = b.iter().sum::<f64>() / n;
+    let (mut covariance, mut variance_a, mut variance_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        covariance += (x - mean_a) * (y - mean_b);
+        variance_a += (x - mean_a).powi(2);
+        variance_b += (y - mean_b).powi(2);
+    }
+    if variance_a == 0.0 || variance_b == 0.0 {
+        0.0
+    } else {
+        covariance / (variance_a * variance_b).sqrt()
+    }
+}
+
+/// Check the serial numbers of the given Models against the given policy. For the `WrapWithWarning`
+/// policy the Atoms or Residues of a Model are renumbered from 1 if needed, generating a warning.
+/// ## Fails
+/// It fails if the numbers cannot be represented, naming the operation and the number reached.
+fn apply_serial_overflow_policy(
+    models: &mut [Model],
+    operation: &str,
+    policy: SerialOverflowPolicy,
+) -> Result<Vec<PDBError>, PDBError> {
+    let overflow = |kind: &str, number: usize, model: usize, limit: &str| {
+        PDBError::new(
+            ErrorLevel::BreakingError,
+            "Serial number overflow",
+            &format!(
+                "The operation `{}` resulted in {} serial number {} in Model {}, which is more than the maximum of {} for the {:?} SerialOverflowPolicy.",
+                operation, kind, number, model, limit, policy
+            ),
+            PDBContext::none(),
+        )
+    };
+    let mut warnings = Vec::new();
+    for model in models.iter_mut() {
+        let max_atom = model
+            .all_atoms()
+            .map(|a| a.serial_number())
+            .max()
+            .unwrap_or(0);
+        let max_residue = model
+            .all_residues()
+            .map(|r| r.serial_number())
+            .max()
+            .unwrap_or(0);
+        match policy {
+            SerialOverflowPolicy::Error => {
+                if max_atom > max_decimal(5) {
+                    return Err(overflow("atom", max_atom, model.serial_number(), "99,999"));
+                }
+                if max_residue > max_decimal(4) {
+                    return Err(overflow(
+                        "residue",
+                        max_residue,
+                        model.serial_number(),
+                        "9,999",
+                    ));
+                }
+            }
+            SerialOverflowPolicy::Hybrid36 => {
+                if hybrid36_encode(max_atom, 5).is_none() {
+                    return Err(overflow(
+                        "atom",
+                        max_atom,
+                        model.serial_number(),
+                        "87,440,031",
+                    ));
+                }
+                if hybrid36_encode(max_residue, 4).is_none() {
+                    return Err(overflow(
+                        "residue",
+                        max_residue,
+                        model.serial_number(),
+                        "2,436,111",
+                    ));
+                }
+            }
+            SerialOverflowPolicy::WrapWithWarning => {
+                if model.total_atom_count() > max_decimal(5) {
+                    return Err(overflow(
+                        "atom",
+                        model.total_atom_count(),
+                        model.serial_number(),
+                        "99,999 atoms in a single Model to keep the serial numbers unique",
+                    ));
+                }
+                if max_atom > max_decimal(5) {
+                    for (counter, atom) in model.all_atoms_mut().enumerate() {
+                        atom.set_serial_number(counter + 1);
+                    }
+                    warnings.push(PDBError::new(
+                        ErrorLevel::LooseWarning,
+                        "Serial numbers wrapped",
+                        &format!("The operation `{}` resulted in atom serial number {} in Model {}, so the atoms of this Model were renumbered from 1.", operation, max_atom, model.serial_number()),
+                        PDBContext::none(),
+                    ));
+                }
+                if max_residue > max_decimal(4) {
+                    for residue in model.all_residues_mut() {
+                        residue.set_serial_number(wrap(residue.serial_number(), 4));
+                    }
+                    warnings.push(PDBError::new(
+                        ErrorLevel::LooseWarning,
+                        "Serial numbers wrapped",
+                        &format!("The operation `{}` resulted in residue serial number {} in Model {}, so the residue serial numbers in this Model were wrapped back to 1 after 9,999.", operation, max_residue, model.serial_number()),
+                        PDBContext::none(),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(warnings)
+}
+
+/// Get the value of the first specification with the given token
+fn specification<'a>(specifications: &'a [(String, String)], token: &str) -> Option<&'a str> {
+    specifications
+        .iter()
+        .find(|(name, _)| name == token)
+        .map(|(_, value)| value.as_str())
 }
 
 use std::fmt;
@@ -664,13 +3057,43 @@ impl fmt::Display for PDB {
 impl Clone for PDB {
     fn clone(&self) -> Self {
         let mut pdb = PDB::new();
+        pdb.name = self.name.clone();
+        pdb.classification = self.classification.clone();
+        pdb.deposition_date = self.deposition_date;
+        pdb.identifier = self.identifier.clone();
+        pdb.title = self.title.clone();
+        pdb.obsolete = self.obsolete.clone();
+        pdb.supersedes = self.supersedes.clone();
+        pdb.split_entries = self.split_entries.clone();
+        pdb.caveat = self.caveat.clone();
+        pdb.compounds = self.compounds.clone();
+        pdb.sources = self.sources.clone();
+        pdb.experimental_methods = self.experimental_methods.clone();
+        pdb.model_types = self.model_types.clone();
+        pdb.declared_model_count = self.declared_model_count;
         pdb.remarks = self.remarks.clone();
         pdb.scale = self.scale.clone();
         pdb.origx = self.origx.clone();
         pdb.mtrix = self.mtrix.clone();
+        pdb.assemblies = self.assemblies.clone();
+        pdb.hets = self.hets.clone();
+        pdb.heterogens = self.heterogens.clone();
+        pdb.seqres = self.seqres.clone();
+        pdb.database_references = self.database_references.clone();
+        pdb.ssbonds = self.ssbonds.clone();
+        pdb.links = self.links.clone();
+        pdb.cis_peptides = self.cis_peptides.clone();
+        pdb.sites = self.sites.clone();
+        pdb.helices = self.helices.clone();
+        pdb.connections = self.connections.clone();
         pdb.symmetry = self.symmetry.clone();
         pdb.unit_cell = self.unit_cell.clone();
         pdb.models = self.models.clone();
+        pdb.saw_end = self.saw_end;
+        pdb.saw_ter = self.saw_ter;
+        pdb.saw_master = self.saw_master;
+        pdb.source_record_types = self.source_record_types.clone();
+        pdb.source_record_counts = self.source_record_counts.clone();
         pdb
     }
 }
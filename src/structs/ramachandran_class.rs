@@ -0,0 +1,122 @@
+#![allow(dead_code)]
+use std::fmt;
+
+/// The class of a Residue for the validation of its backbone dihedral angles (phi, psi) in a
+/// Ramachandran plot, following the classes used by MolProbity. Glycine and proline have very
+/// different allowed regions, as has any residue preceding a proline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RamachandranClass {
+    /// Any residue not in any of the other classes
+    General,
+    /// Glycine (GLY)
+    Glycine,
+    /// Proline (PRO)
+    Proline,
+    /// Any residue, except glycine and proline, preceding a proline
+    PreProline,
+}
+
+impl RamachandranClass {
+    /// Classify a residue based on its name and the name of the next residue in the chain
+    ///
+    /// ## Arguments
+    /// * `name` - the name of the residue
+    /// * `next` - the name of the next (bonded) residue in the chain, if there is one
+    pub fn from_residue_names(name: &str, next: Option<&str>) -> RamachandranClass {
+        match name {
+            "GLY" => RamachandranClass::Glycine,
+            "PRO" => RamachandranClass::Proline,
+            _ if next == Some("PRO") => RamachandranClass::PreProline,
+            _ => RamachandranClass::General,
+        }
+    }
+}
+
+impl fmt::Display for RamachandranClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            RamachandranClass::General => "General",
+            RamachandranClass::Glycine => "Glycine",
+            RamachandranClass::Proline => "Proline",
+            RamachandranClass::PreProline => "PreProline",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RamachandranClass;
+    use crate::structs::*;
+
+    /// Create a model with a chain with a planar zigzag backbone (N, CA, C) for the given residue names
+    fn backbone(names: &[[char; 3]]) -> Model {
+        let mut model = Model::new(1);
+        for (index, name) in names.iter().enumerate() {
+            for (offset, atom_name) in [
+                [' ', 'N', ' ', ' '],
+                [' ', 'C', 'A', ' '],
+                [' ', 'C', ' ', ' '],
+            ]
+            .iter()
+            .enumerate()
+            {
+                let position = index * 3 + offset;
+                let atom = Atom::new(
+                    position,
+                    *atom_name,
+                    position as f64 * 1.2,
+                    (position % 2) as f64 * 0.8,
+                    0.0,
+                    1.0,
+                    0.0,
+                    [' ', atom_name[1]],
+                    0,
+                )
+                .unwrap();
+                model.add_atom(atom, 'A', index + 1, None, *name);
+            }
+        }
+        model
+    }
+
+    #[test]
+    fn phi_psi_pairs() {
+        let model = backbone(&[
+            ['A', 'L', 'A'],
+            ['G', 'L', 'Y'],
+            ['S', 'E', 'R'],
+            ['P', 'R', 'O'],
+        ]);
+        let pairs = model.chain(0).unwrap().phi_psi_pairs().unwrap();
+        assert_eq!(pairs.len(), 4);
+        assert!(pairs[0].0.is_none() && pairs[0].1.is_some());
+        assert!(pairs[3].0.is_some() && pairs[3].1.is_none());
+        assert_eq!(pairs[1].2, ['G', 'L', 'Y']);
+        assert!((pairs[1].0.unwrap().abs() - 180.0).abs() < 1e-6);
+
+        let classes = model.ramachandran_pairs_by_residue_class().unwrap();
+        assert_eq!(classes.values().map(|v| v.len()).sum::<usize>(), 2);
+        assert_eq!(classes[&RamachandranClass::Glycine].len(), 1);
+        assert_eq!(classes[&RamachandranClass::PreProline].len(), 1);
+        assert!(!classes.contains_key(&RamachandranClass::General));
+    }
+
+    #[test]
+    fn classes() {
+        use RamachandranClass::*;
+        assert_eq!(RamachandranClass::from_residue_names("ALA", None), General);
+        assert_eq!(
+            RamachandranClass::from_residue_names("GLY", Some("PRO")),
+            Glycine
+        );
+        assert_eq!(
+            RamachandranClass::from_residue_names("PRO", Some("PRO")),
+            Proline
+        );
+        assert_eq!(
+            RamachandranClass::from_residue_names("TRP", Some("PRO")),
+            PreProline
+        );
+    }
+}
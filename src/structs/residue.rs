@@ -1,18 +1,23 @@
 #![allow(dead_code)]
+use crate::error::*;
 use crate::reference_tables;
 use crate::structs::*;
 use crate::transformation::*;
 use std::fmt;
 
 #[derive(Debug)]
-/// A Residue containing multiple atoms
+/// A Residue containing multiple atoms, iterated in the order they were added
 pub struct Residue {
     /// The identifier or name of Residue
     id: [char; 3],
     /// The serial number of this Residue
     serial_number: usize,
+    /// The insertion code of this Residue, to distinguish Residues with the same serial number
+    insertion_code: Option<char>,
     /// The list of atoms making up this Residue
     atoms: Vec<Atom>,
+    /// The names of the conformers named differently from this Residue, by alternate location identifier
+    conformer_names: Vec<(char, [char; 3])>,
 }
 
 impl Residue {
@@ -33,7 +38,9 @@ impl Residue {
         let mut res = Residue {
             id: name,
             serial_number: number,
+            insertion_code: None,
             atoms: Vec::new(),
+            conformer_names: Vec::new(),
         };
 
         if let Some(a) = atom {
@@ -67,14 +74,15 @@ impl Residue {
         let new_id = format!("{:>3}", new_id);
         let chars = new_id.to_ascii_uppercase().chars().collect::<Vec<char>>();
         if chars.len() <= 3 {
-            if check_chars(new_id.to_string()) {
-                self.id = [chars[0], chars[1], chars[2]];
-                Ok(())
-            } else {
-                Err(format!(
-                    "New id has invalid characters for residue {} name {}",
-                    self.serial_number, new_id
-                ))
+            match describe_invalid_chars(&new_id, check_char) {
+                None => {
+                    self.id = [chars[0], chars[1], chars[2]];
+                    Ok(())
+                }
+                Some(invalid) => Err(format!(
+                    "New id has invalid characters for residue {} name {}: {}",
+                    self.serial_number, new_id, invalid
+                )),
             }
         } else {
             Err(format!(
@@ -94,6 +102,38 @@ impl Residue {
         self.serial_number = new_number;
     }
 
+    /// The insertion code of the Residue, used to insert Residues between two serial numbers, for
+    /// example the Residues 100, 100A and 100B in antibody numbering. Together with the serial
+    /// number it identifies the Residue within its Chain.
+    pub fn insertion_code(&self) -> Option<char> {
+        self.insertion_code
+    }
+
+    /// Set the insertion code of the Residue, a space is the same as no insertion code
+    ///
+    /// ## Fails
+    /// It fails if the insertion code is an invalid character.
+    pub fn set_insertion_code(&mut self, new_code: Option<char>) -> Result<(), String> {
+        match new_code {
+            Some(code) if !check_char(code) => Err(format!(
+                "New insertion code has an invalid character for residue {} name {}: '{}'",
+                self.serial_number,
+                self.id(),
+                code
+            )),
+            _ => {
+                self.insertion_code = new_code.filter(|code| *code != ' ');
+                Ok(())
+            }
+        }
+    }
+
+    /// The serial number and insertion code of the Residue, which identify it within its Chain.
+    /// Sorting on this gives the order of the numbering, so 100 before 100A before 100B before 101.
+    pub fn numbering(&self) -> (usize, Option<char>) {
+        (self.serial_number, self.insertion_code)
+    }
+
     /// The amount of atoms making up this Residue
     pub fn atom_count(&self) -> usize {
         self.atoms.len()
@@ -133,19 +173,146 @@ impl Residue {
         self.atoms.iter_mut()
     }
 
-    /// Add a new atom to the list of atoms making up this Residue.
+    /// Get the first atom with the given name from the list of atoms making up this Residue.
+    ///
+    /// ## Arguments
+    /// * `name` - the name of the atom, as returned by `atom.name()`
+    ///
+    /// ## Fails
+    /// It fails when there is no atom with the given name.
+    pub fn atom_by_name(&self, name: &str) -> Option<&Atom> {
+        self.atoms.iter().find(|a| a.name() == name)
+    }
+
+    /// Get the first line number of the records the Atoms of this Residue were parsed from,
+    /// see `Atom::source_line`. It is `None` if none of the Atoms has a source line.
+    pub fn source_line(&self) -> Option<usize> {
+        self.atoms
+            .iter()
+            .filter_map(|atom| atom.source_line())
+            .min()
+    }
+
+    /// Add a new atom to the list of atoms making up this Residue, after the atoms already in it.
     /// ## Arguments
     /// * `new_atom` - the new Atom to add
     pub fn add_atom(&mut self, new_atom: Atom) {
         self.atoms.push(new_atom);
     }
 
+    /// Get the name of the conformer with the given alternate location identifier. Whole Residues
+    /// can be modelled as alternatives with different names, for example two different ligands
+    /// in one binding site. This gives the name of the Atoms of that conformer as read from the
+    /// file, which is the name of this Residue (see `id`) if it was not different.
+    pub fn conformer_name(&self, alternate_location: char) -> String {
+        self.conformer_names
+            .iter()
+            .find(|(location, _)| *location == alternate_location)
+            .map_or_else(
+                || self.id(),
+                |(_, name)| name.iter().collect::<String>().trim().to_string(),
+            )
+    }
+
+    /// Reverse the order of the atoms making up this Residue
+    pub(crate) fn reverse_atoms(&mut self) {
+        self.atoms.reverse();
+    }
+
+    /// Set the name of the conformer with the given alternate location identifier, if it differs
+    /// from the name of this Residue
+    pub(crate) fn set_conformer_name(&mut self, alternate_location: char, name: [char; 3]) {
+        self.conformer_names
+            .retain(|(location, _)| *location != alternate_location);
+        if name != self.id && alternate_location != ' ' {
+            self.conformer_names.push((alternate_location, name));
+        }
+    }
+
+    /// Keep only the given conformer name, by renaming this Residue to the name of that conformer
+    /// and forgetting the names of the other conformers. Used after removing the other conformers.
+    pub(crate) fn keep_conformer_name(&mut self, alternate_location: char) {
+        if let Some((_, name)) = self
+            .conformer_names
+            .iter()
+            .find(|(location, _)| *location == alternate_location)
+        {
+            self.id = *name;
+        }
+        self.conformer_names.clear();
+    }
+
+    /// Get the conformers of this Residue, one for every alternate location identifier of its
+    /// Atoms, sorted by identifier. A Residue without alternate locations has a single conformer
+    /// with a blank (' ') identifier containing all Atoms. See `Conformer`.
+    pub fn conformers(&self) -> Vec<Conformer<'_>> {
+        let mut locations = self
+            .atoms
+            .iter()
+            .map(|atom| atom.alternate_location())
+            .filter(|location| *location != ' ')
+            .collect::<Vec<char>>();
+        locations.sort_unstable();
+        locations.dedup();
+        if locations.is_empty() {
+            locations.push(' ');
+        }
+        locations
+            .into_iter()
+            .map(|location| Conformer::new(self, location))
+            .collect()
+    }
+
+    /// Get the conformer of this Residue with the given alternate location identifier, see
+    /// `conformers`. It returns `None` if no Atom has this identifier.
+    pub fn conformer(&self, alternate_location: char) -> Option<Conformer<'_>> {
+        self.conformers()
+            .into_iter()
+            .find(|conformer| conformer.alternate_location() == alternate_location)
+    }
+
+    /// Get the mean B-factor of the atoms of this Residue, without taking the occupancy into account.
+    /// ## Fails
+    /// It fails if this Residue has no atoms.
+    pub fn average_b_factor(&self) -> Option<f64> {
+        if self.atoms.is_empty() {
+            None
+        } else {
+            Some(self.atoms().map(|a| a.b_factor()).sum::<f64>() / self.atoms.len() as f64)
+        }
+    }
+
+    /// Get the occupancy weighted mean B-factor of the atoms of this Residue: Σ(occupancy × B) / Σ(occupancy).
+    /// With alternate conformations present this gives the effective B-factor of the Residue,
+    /// biased towards the conformer with the highest occupancy. If all occupancies are zero
+    /// this is the same as `average_b_factor`.
+    /// ## Fails
+    /// It fails if this Residue has no atoms.
+    pub fn occupancy_weighted_b_factor(&self) -> Option<f64> {
+        let total_occupancy = self.atoms().map(|a| a.occupancy()).sum::<f64>();
+        if total_occupancy > 0.0 {
+            Some(
+                self.atoms()
+                    .map(|a| a.occupancy() * a.b_factor())
+                    .sum::<f64>()
+                    / total_occupancy,
+            )
+        } else {
+            self.average_b_factor()
+        }
+    }
+
     /// Returns if this Residue is an amino acid
     pub fn amino_acid(&self) -> bool {
         reference_tables::get_amino_acid_number(self.id().as_str()).is_some()
     }
 
-    /// Remove all Atoms matching the given predicate. As this is done in place this is the fastest way to remove Atoms from this Residue.
+    /// Get the type of molecular entity this Residue belongs to, based on its name
+    pub fn entity_type(&self) -> EntityType {
+        EntityType::from_residue_name(self.id().as_str())
+    }
+
+    /// Remove all Atoms matching the given predicate. As this is done in place this is the fastest way to remove Atoms from this Residue. The remaining Atoms keep their order.
     pub fn remove_atoms_by<F>(&mut self, predicate: F)
     where
         F: Fn(&Atom) -> bool,
@@ -155,6 +322,64 @@ impl Residue {
             .extend(atoms.into_iter().filter(|atom| !predicate(atom)));
     }
 
+    /// Remove the alternate conformations of this Residue by keeping only the copy with the highest
+    /// occupancy of every Atom with an alternate location, the first if multiple copies have the
+    /// same occupancy. Copies are Atoms with the same name. The kept Atoms lose their alternate
+    /// location identifier and the Residue gets the name of the conformer of the kept copy with the
+    /// highest occupancy (see `conformer_name`). It returns the number of removed Atoms.
+    pub fn remove_alternate_conformers(&mut self) -> usize {
+        let alternate = |atom: &Atom| atom.alternate_location() != ' ';
+        let keep = self
+            .atoms
+            .iter()
+            .enumerate()
+            .map(|(index, atom)| {
+                !alternate(atom)
+                    || !self.atoms.iter().enumerate().any(|(other_index, other)| {
+                        alternate(other)
+                            && other.name() == atom.name()
+                            && (other.occupancy() > atom.occupancy()
+                                || (other.occupancy() == atom.occupancy() && other_index < index))
+                    })
+            })
+            .collect::<Vec<bool>>();
+        let location = self
+            .atoms
+            .iter()
+            .zip(&keep)
+            .filter(|(atom, kept)| **kept && alternate(atom))
+            .fold(None, |best: Option<&Atom>, (atom, _)| match best {
+                Some(best) if best.occupancy() >= atom.occupancy() => Some(best),
+                _ => Some(atom),
+            })
+            .map(|atom| atom.alternate_location());
+        let location = match location {
+            Some(location) => location,
+            None => return 0,
+        };
+        let count = self.atoms.len();
+        let mut keep = keep.into_iter();
+        self.atoms.retain(|_| keep.next().unwrap());
+        for atom in &mut self.atoms {
+            atom.set_alternate_location(' ').unwrap();
+        }
+        self.keep_conformer_name(location);
+        count - self.atoms.len()
+    }
+
+    /// Remove all Atoms matching the given predicate and return them, in the order they were in
+    /// this Residue. See `remove_atoms_by` to discard them instead.
+    pub fn drain_atoms_by<F>(&mut self, predicate: F) -> Vec<Atom>
+    where
+        F: Fn(&Atom) -> bool,
+    {
+        let (removed, kept) = std::mem::take(&mut self.atoms)
+            .into_iter()
+            .partition(|atom| predicate(atom));
+        self.atoms = kept;
+        removed
+    }
+
     /// Remove the Atom specified.
     ///
     /// ## Arguments
@@ -214,8 +439,94 @@ impl Residue {
         }
     }
 
+    /// Compute the rigid body transformation that superposes the named atoms of this Residue onto
+    /// the atoms with the same names in the target Residue (Kabsch fit, see `TransformationMatrix::superpose`).
+    /// Nothing is mutated, use `apply_transformation` with the result or use `superpose_onto_and_apply`.
+    ///
+    /// ## Arguments
+    /// * `target` - the Residue to superpose onto
+    /// * `atom_names` - the names of the atoms to use in the fit, if empty all atom names present in both Residues are used
+    ///
+    /// ## Fails
+    /// It fails if any of the given atom names is missing in either Residue, or if no atom names
+    /// are given and the Residues do not share any atom names. The error lists the atom names
+    /// present in one Residue but not the other.
+    pub fn superpose_onto(
+        &self,
+        target: &Residue,
+        atom_names: &[&str],
+    ) -> Result<TransformationMatrix, PDBError> {
+        let names: Vec<String> = if atom_names.is_empty() {
+            self.atoms()
+                .map(|a| a.name())
+                .filter(|name| target.atom_by_name(name).is_some())
+                .collect()
+        } else {
+            atom_names
+                .iter()
+                .map(|name| name.trim().to_string())
+                .collect()
+        };
+        let missing = |residue: &Residue, names: Vec<String>| {
+            names
+                .into_iter()
+                .filter(|name| residue.atom_by_name(name).is_none())
+                .collect::<Vec<String>>()
+        };
+        let (missing_self, missing_target) = if atom_names.is_empty() {
+            (
+                missing(self, target.atoms().map(|a| a.name()).collect()),
+                missing(target, self.atoms().map(|a| a.name()).collect()),
+            )
+        } else {
+            (missing(self, names.clone()), missing(target, names.clone()))
+        };
+
+        let all_present = missing_self.is_empty() && missing_target.is_empty();
+        if names.is_empty() || !(atom_names.is_empty() || all_present) {
+            return Err(PDBError::new(
+                ErrorLevel::BreakingError,
+                "Atoms not shared",
+                &format!(
+                    "Residue {} {} cannot be superposed onto Residue {} {} as not all atoms are present in both Residues. Missing in {} {}: [{}], missing in {} {}: [{}].",
+                    self.serial_number(), self.id(), target.serial_number(), target.id(),
+                    self.serial_number(), self.id(), missing_self.join(", "),
+                    target.serial_number(), target.id(), missing_target.join(", ")
+                ),
+                PDBContext::none(),
+            ));
+        }
+
+        let positions = |residue: &Residue| {
+            names
+                .iter()
+                .map(|name| residue.atom_by_name(name).unwrap().pos())
+                .collect::<Vec<(f64, f64, f64)>>()
+        };
+        Ok(
+            TransformationMatrix::superpose(&positions(self), &positions(target))
+                .expect("The superposition failed on non empty sets of atoms"),
+        )
+    }
+
+    /// Superpose this Residue onto the target Residue, see `superpose_onto`, and immediately apply
+    /// the resulting transformation to all atoms of this Residue. The transformation is returned.
+    ///
+    /// ## Fails
+    /// It fails in the same cases as `superpose_onto`, in which case this Residue is not changed.
+    pub fn superpose_onto_and_apply(
+        &mut self,
+        target: &Residue,
+        atom_names: &[&str],
+    ) -> Result<TransformationMatrix, PDBError> {
+        let transformation = self.superpose_onto(target, atom_names)?;
+        self.apply_transformation(&transformation);
+        Ok(transformation)
+    }
+
     /// Join this Residue with another Residue, this moves all atoms from the other Residue
-    /// to this Residue. All other (meta) data of this Residue will stay the same.
+    /// to this Residue. All other (meta) data of this Residue will stay the same. The atoms of the
+    /// other Residue are added after the atoms of this Residue.
     pub fn join(&mut self, other: Residue) {
         self.atoms.extend(other.atoms);
     }
@@ -236,7 +547,9 @@ impl fmt::Display for Residue {
 impl Clone for Residue {
     fn clone(&self) -> Self {
         let mut res = Residue::new(self.serial_number, self.id, None).unwrap();
+        res.insertion_code = self.insertion_code;
         res.atoms = self.atoms.clone();
+        res.conformer_names = self.conformer_names.clone();
         res
     }
 }
@@ -244,7 +557,72 @@ impl Clone for Residue {
 impl PartialEq for Residue {
     fn eq(&self, other: &Self) -> bool {
         self.serial_number == other.serial_number
+            && self.insertion_code == other.insertion_code
             && self.id() == other.id()
             && self.atoms == other.atoms
+            && self.conformer_names == other.conformer_names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structs::*;
+    use crate::transformation::*;
+
+    fn residue(atoms: &[(&str, (f64, f64, f64))]) -> Residue {
+        let mut residue = Residue::new(1, ['A', 'L', 'A'], None).unwrap();
+        for (index, (name, pos)) in atoms.iter().enumerate() {
+            let chars = format!("{:^4}", name).chars().collect::<Vec<char>>();
+            residue.add_atom(
+                Atom::new(
+                    index,
+                    [chars[0], chars[1], chars[2], chars[3]],
+                    pos.0,
+                    pos.1,
+                    pos.2,
+                    1.0,
+                    0.0,
+                    [' ', 'C'],
+                    0,
+                )
+                .unwrap(),
+            );
+        }
+        residue
+    }
+
+    #[test]
+    fn superpose_onto() {
+        let source = residue(&[
+            ("N", (0.0, 1.4, 0.0)),
+            ("CA", (0.0, 0.0, 0.0)),
+            ("C", (1.5, 0.0, 0.0)),
+            ("CB", (-0.5, -0.8, 1.2)),
+        ]);
+        let mut target = source.clone();
+        let transformation = TransformationMatrix::rotation_y(70.0)
+            .combine(&TransformationMatrix::translation(-2.0, 5.0, 1.0));
+        target.apply_transformation(&transformation);
+        target.remove_atom_name("CB".to_string());
+
+        let mut moved = source.clone();
+        let found = moved.superpose_onto_and_apply(&target, &[]).unwrap();
+        for name in &["N", "CA", "C"] {
+            let a = moved.atom_by_name(name).unwrap().pos();
+            let b = target.atom_by_name(name).unwrap().pos();
+            assert!(
+                (a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6 && (a.2 - b.2).abs() < 1e-6
+            );
+        }
+        assert_eq!(
+            found,
+            source.superpose_onto(&target, &["N", "CA", "C"]).unwrap()
+        );
+
+        let error = source.superpose_onto(&target, &["CA", "CB"]).unwrap_err();
+        assert!(error.long_description().contains("missing in 1 ALA: [CB]"));
+        assert!(moved
+            .superpose_onto(&residue(&[("O", (0.0, 0.0, 0.0))]), &[])
+            .is_err());
     }
 }
@@ -0,0 +1,102 @@
+#![allow(dead_code)]
+use crate::structs::*;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An identifier of a Residue by its place in the hierarchy instead of by its values, so it can be
+/// used as a key in a HashMap, HashSet or BTreeMap. It consists of the chain id, the serial number
+/// and the insertion code of the Residue, so the Residues 100, 100A and 100B are kept apart.
+/// Identifiers are ordered in this same order, see `Residue::numbering`.
+pub struct ResidueId {
+    /// The id of the Chain
+    chain_id: char,
+    /// The serial number of the Residue
+    serial_number: usize,
+    /// The insertion code of the Residue, if any
+    insertion_code: Option<char>,
+}
+
+impl ResidueId {
+    /// Create a new ResidueId
+    ///
+    /// ## Arguments
+    /// * `chain_id` - the id of the Chain
+    /// * `serial_number` - the serial number of the Residue
+    /// * `insertion_code` - the insertion code of the Residue, a space is the same as no insertion code
+    pub fn new(chain_id: char, serial_number: usize, insertion_code: Option<char>) -> ResidueId {
+        ResidueId {
+            chain_id,
+            serial_number,
+            insertion_code: insertion_code.filter(|code| *code != ' '),
+        }
+    }
+
+    /// Create the ResidueId of the given Residue, with the given Chain containing it
+    pub fn from_hierarchy(chain: &Chain, residue: &Residue) -> ResidueId {
+        ResidueId::new(
+            chain.id(),
+            residue.serial_number(),
+            residue.insertion_code(),
+        )
+    }
+
+    /// The id of the Chain
+    pub fn chain_id(&self) -> char {
+        self.chain_id
+    }
+
+    /// The serial number of the Residue
+    pub fn serial_number(&self) -> usize {
+        self.serial_number
+    }
+
+    /// The insertion code of the Residue, if any
+    pub fn insertion_code(&self) -> Option<char> {
+        self.insertion_code
+    }
+
+    /// The serial number directly followed by the insertion code, if any, like `100A`
+    pub(crate) fn numbering_label(&self) -> String {
+        match self.insertion_code {
+            Some(code) => format!("{}{}", self.serial_number, code),
+            None => self.serial_number.to_string(),
+        }
+    }
+
+    /// Check if this identifies the given Residue in a Chain with the given id
+    pub(crate) fn matches(&self, chain_id: char, residue: &Residue) -> bool {
+        self.chain_id == chain_id
+            && residue.numbering() == (self.serial_number, self.insertion_code)
+    }
+}
+
+impl From<(char, usize)> for ResidueId {
+    /// The ResidueId of the Residue with the given chain id and serial number without insertion code
+    fn from((chain_id, serial_number): (char, usize)) -> Self {
+        ResidueId::new(chain_id, serial_number, None)
+    }
+}
+
+impl fmt::Display for ResidueId {
+    /// Formats as `chain/residue`, with the insertion code directly following the serial number
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.chain_id, self.numbering_label())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResidueId;
+
+    #[test]
+    fn ordering_and_display() {
+        let a = ResidueId::from(('A', 100));
+        let b = ResidueId::new('A', 100, Some('A'));
+        let c = ResidueId::new('A', 101, Some(' '));
+        assert!(a < b && b < c);
+        assert_eq!(c.insertion_code(), None);
+        assert_eq!(format!("{}", a), "A/100");
+        assert_eq!(format!("{}", b), "A/100A");
+    }
+}
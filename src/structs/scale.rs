@@ -48,10 +48,7 @@ impl Scale {
     /// ```
     pub fn set_row(&mut self, row: usize, data: [f64; 4]) {
         if row > 2 {
-            panic!(format!(
-                "Row in Scale.set_row is too big (max 2, value: {})",
-                row
-            ));
+            panic!("Row in Scale.set_row is too big (max 2, value: {})", row);
         }
         let mut matrix = self.transformation.matrix();
         matrix[row] = data;
@@ -61,10 +58,10 @@ impl Scale {
         }
         self.rows_set[row] = true;
     }
-    /// Checks if this Scale is valid, for this all rows have to be set (also see `set_row`).
-    /// Mainly used to validate a structure after parsing.
+    /// Checks if this Scale is valid, for this all rows have to be set (also see `set_row`) and
+    /// the rotation part has to be non-singular. Mainly used to validate a structure after parsing.
     pub fn valid(&self) -> bool {
-        self.rows_set == [true, true, true]
+        self.rows_set == [true, true, true] && self.transformation.inverse().is_some()
     }
 }
 
@@ -0,0 +1,420 @@
+#![allow(dead_code)]
+use crate::structs::*;
+use std::collections::HashMap;
+use std::ops;
+
+/// The names of the backbone Atoms of an amino acid
+const BACKBONE: &[&str] = &["N", "CA", "C", "O"];
+
+/// The names of the Atoms of an amino acid that are not part of its side chain
+const NON_SIDECHAIN: &[&str] = &["N", "CA", "C", "O", "OXT"];
+
+#[derive(Debug, Clone, PartialEq)]
+/// The kind of a Selection
+enum Kind {
+    CAlpha,
+    Backbone,
+    HeavyAtoms,
+    Sidechains,
+    Polymer,
+    Hetero,
+    HeteroNonWater,
+    Element(String, bool),
+    Charge(isize),
+    SerialNumbers(ops::RangeInclusive<usize>),
+    BFactorAbove(f64),
+    BFactorBelow(f64),
+    OccupancyAbove(f64),
+    OccupancyBelow(f64),
+    ResidueName(String),
+    Within(f64, Box<Kind>),
+    And(Box<Kind>, Box<Kind>),
+    Or(Box<Kind>, Box<Kind>),
+    Not(Box<Kind>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A selection of Atoms based on the names of the Atoms and their Residues, their properties
+/// (element, charge, serial number, B-factor and occupancy) and their distance to other selected
+/// Atoms, for example all CA atoms, all heavy atoms of the side chains or all Atoms within 5 Å of
+/// an ATP. Selections can be combined with `and`, `or` and `!` (not), and are used by
+/// `Model::select_atoms`, `Model::superpose_onto`, `Model::rmsd`, `PDB::keep_selection` and
+/// `save_filtered`.
+pub struct Selection {
+    kind: Kind,
+}
+
+impl Selection {
+    /// The CA atoms of the amino acids
+    pub fn calpha() -> Selection {
+        Selection { kind: Kind::CAlpha }
+    }
+
+    /// The backbone atoms (N, CA, C and O) of the amino acids
+    pub fn backbone() -> Selection {
+        Selection {
+            kind: Kind::Backbone,
+        }
+    }
+
+    /// All atoms that are not hydrogen or deuterium. Atoms without an element are judged on the
+    /// first letter of their name that is not a digit.
+    pub fn heavy_atoms() -> Selection {
+        Selection {
+            kind: Kind::HeavyAtoms,
+        }
+    }
+
+    /// The side chain atoms of the amino acids, all atoms except N, CA, C, O and OXT
+    pub fn sidechains() -> Selection {
+        Selection {
+            kind: Kind::Sidechains,
+        }
+    }
+
+    /// All atoms of the Normal Chains (ATOM records)
+    pub fn polymer() -> Selection {
+        Selection {
+            kind: Kind::Polymer,
+        }
+    }
+
+    /// All atoms of the Hetero Chains (HETATM records), including those of waters
+    pub fn hetero() -> Selection {
+        Selection { kind: Kind::Hetero }
+    }
+
+    /// All atoms of the Hetero Chains (HETATM records) except those of waters
+    pub fn hetero_non_water() -> Selection {
+        Selection {
+            kind: Kind::HeteroNonWater,
+        }
+    }
+
+    /// The atoms of the given element, ignoring case. Atoms without an element are judged on the
+    /// first letter of their name that is not a digit, as for `heavy_atoms`.
+    ///
+    /// ## Arguments
+    /// * `element` - the element, like `C` or `Fe`
+    /// * `hydrogen_isotopes` - if hydrogen (H) and deuterium (D) should be treated as the same
+    ///   element, so selecting either selects both
+    pub fn element(element: &str, hydrogen_isotopes: bool) -> Selection {
+        Selection {
+            kind: Kind::Element(element.trim().to_ascii_uppercase(), hydrogen_isotopes),
+        }
+    }
+
+    /// The atoms with the given formal charge
+    pub fn charge(charge: isize) -> Selection {
+        Selection {
+            kind: Kind::Charge(charge),
+        }
+    }
+
+    /// The atoms with a serial number in the given range, like `1..=100`
+    pub fn serial_numbers(range: ops::RangeInclusive<usize>) -> Selection {
+        Selection {
+            kind: Kind::SerialNumbers(range),
+        }
+    }
+
+    /// The atoms with a B-factor above the given value, combine with `!` to select the atoms
+    /// with a B-factor of at most the value
+    pub fn b_factor_above(value: f64) -> Selection {
+        Selection {
+            kind: Kind::BFactorAbove(value),
+        }
+    }
+
+    /// The atoms with a B-factor below the given value, combine with `!` to select the atoms
+    /// with a B-factor of at least the value
+    pub fn b_factor_below(value: f64) -> Selection {
+        Selection {
+            kind: Kind::BFactorBelow(value),
+        }
+    }
+
+    /// The atoms with an occupancy above the given value, combine with `!` to select the atoms
+    /// with an occupancy of at most the value
+    pub fn occupancy_above(value: f64) -> Selection {
+        Selection {
+            kind: Kind::OccupancyAbove(value),
+        }
+    }
+
+    /// The atoms with an occupancy below the given value, for example `occupancy_below(1.0)` for
+    /// the atoms with partial occupancy
+    pub fn occupancy_below(value: f64) -> Selection {
+        Selection {
+            kind: Kind::OccupancyBelow(value),
+        }
+    }
+
+    /// The atoms of the Residues with the given name, like `ATP`
+    pub fn residue_name(name: &str) -> Selection {
+        Selection {
+            kind: Kind::ResidueName(name.trim().to_string()),
+        }
+    }
+
+    /// The atoms within the given distance of any atom of the given selection in the same Model,
+    /// including the atoms of that selection themselves. The other selection is resolved first
+    /// and its atoms are put in a grid, so only the atoms in neighbouring cells are compared.
+    /// As this needs the other atoms of the Model, `matches` never selects atoms with this.
+    ///
+    /// ## Arguments
+    /// * `distance` - the maximal distance in Å
+    /// * `selection` - the atoms to measure the distance to
+    ///
+    /// ## Panics
+    /// It panics if the distance is not finite or negative.
+    pub fn within(distance: f64, selection: Selection) -> Selection {
+        assert!(
+            distance.is_finite() && distance >= 0.0,
+            "The distance is not finite or negative"
+        );
+        Selection {
+            kind: Kind::Within(distance, Box::new(selection.kind)),
+        }
+    }
+
+    /// The atoms in both this and the other selection
+    pub fn and(self, other: Selection) -> Selection {
+        Selection {
+            kind: Kind::And(Box::new(self.kind), Box::new(other.kind)),
+        }
+    }
+
+    /// The atoms in this or the other selection
+    pub fn or(self, other: Selection) -> Selection {
+        Selection {
+            kind: Kind::Or(Box::new(self.kind), Box::new(other.kind)),
+        }
+    }
+
+    /// Get if the given Atom is selected. Selections made with `within` need the other Atoms of
+    /// the Model and never select an Atom here, use `Model::select_atoms` for those.
+    ///
+    /// ## Arguments
+    /// * `residue` - the Residue the Atom belongs to
+    /// * `atom` - the Atom
+    /// * `hetero` - if the Residue is part of a Hetero Chain (HETATM records)
+    pub fn matches(&self, residue: &Residue, atom: &Atom, hetero: bool) -> bool {
+        matches(&self.kind, residue, residue.entity_type(), atom, hetero)
+    }
+
+    /// Resolve the distance clauses (see `within`) of this Selection using the Atoms of the given Model
+    pub(crate) fn resolve(&self, model: &Model) -> ResolvedSelection<'_> {
+        ResolvedSelection {
+            resolved: resolve(&self.kind, model),
+        }
+    }
+}
+
+impl ops::Not for Selection {
+    type Output = Selection;
+
+    /// The atoms not in this selection
+    fn not(self) -> Selection {
+        Selection {
+            kind: Kind::Not(Box::new(self.kind)),
+        }
+    }
+}
+
+/// A Selection with its distance clauses resolved using the Atoms of a Model, see `Selection::resolve`
+pub(crate) struct ResolvedSelection<'a> {
+    resolved: Resolved<'a>,
+}
+
+impl ResolvedSelection<'_> {
+    /// Get if the given Atom is selected, with the entity type of its Residue
+    pub(crate) fn matches(
+        &self,
+        residue: &Residue,
+        entity: EntityType,
+        atom: &Atom,
+        hetero: bool,
+    ) -> bool {
+        self.resolved.matches(residue, entity, atom, hetero)
+    }
+}
+
+/// A kind of Selection with the distance clauses replaced by a grid of the Atoms to measure to
+enum Resolved<'a> {
+    Kind(&'a Kind),
+    Within(NeighbourGrid),
+    And(Box<Resolved<'a>>, Box<Resolved<'a>>),
+    Or(Box<Resolved<'a>>, Box<Resolved<'a>>),
+    Not(Box<Resolved<'a>>),
+}
+
+impl Resolved<'_> {
+    /// Get if the given Atom is selected
+    fn matches(&self, residue: &Residue, entity: EntityType, atom: &Atom, hetero: bool) -> bool {
+        match self {
+            Resolved::Kind(kind) => matches(kind, residue, entity, atom, hetero),
+            Resolved::Within(grid) => grid.contains(atom.pos()),
+            Resolved::And(a, b) => {
+                a.matches(residue, entity, atom, hetero) && b.matches(residue, entity, atom, hetero)
+            }
+            Resolved::Or(a, b) => {
+                a.matches(residue, entity, atom, hetero) || b.matches(residue, entity, atom, hetero)
+            }
+            Resolved::Not(a) => !a.matches(residue, entity, atom, hetero),
+        }
+    }
+}
+
+/// Resolve the distance clauses of the given kind of Selection using the Atoms of the given Model,
+/// the selections to measure to are resolved first so they can contain distance clauses as well
+fn resolve<'a>(kind: &'a Kind, model: &Model) -> Resolved<'a> {
+    match kind {
+        Kind::Within(distance, inner) => {
+            let inner = resolve(inner, model);
+            let mut grid = NeighbourGrid::new(*distance);
+            let normal = model.chains().map(|chain| (chain, false));
+            let hetero = model.hetero_chains().map(|chain| (chain, true));
+            for (chain, is_hetero) in normal.chain(hetero) {
+                for residue in chain.residues() {
+                    let entity = residue.entity_type();
+                    for atom in residue.atoms() {
+                        if inner.matches(residue, entity, atom, is_hetero) {
+                            grid.insert(atom.pos());
+                        }
+                    }
+                }
+            }
+            Resolved::Within(grid)
+        }
+        Kind::And(a, b) => Resolved::And(Box::new(resolve(a, model)), Box::new(resolve(b, model))),
+        Kind::Or(a, b) => Resolved::Or(Box::new(resolve(a, model)), Box::new(resolve(b, model))),
+        Kind::Not(a) => Resolved::Not(Box::new(resolve(a, model))),
+        _ => Resolved::Kind(kind),
+    }
+}
+
+/// Get if the given Atom is selected by the given kind of Selection, distance clauses never match
+fn matches(kind: &Kind, residue: &Residue, entity: EntityType, atom: &Atom, hetero: bool) -> bool {
+    match kind {
+        Kind::CAlpha => entity == EntityType::Protein && atom.name() == "CA",
+        Kind::Backbone => entity == EntityType::Protein && BACKBONE.contains(&atom.name().as_str()),
+        Kind::HeavyAtoms => !is_hydrogen(atom),
+        Kind::Sidechains => {
+            entity == EntityType::Protein && !NON_SIDECHAIN.contains(&atom.name().as_str())
+        }
+        Kind::Polymer => !hetero,
+        Kind::Hetero => hetero,
+        Kind::HeteroNonWater => hetero && entity != EntityType::Water,
+        Kind::Element(element, hydrogen_isotopes) => {
+            let own = element_of(atom);
+            own == *element
+                || (*hydrogen_isotopes
+                    && matches!(own.as_str(), "H" | "D")
+                    && matches!(element.as_str(), "H" | "D"))
+        }
+        Kind::Charge(charge) => atom.charge() == *charge,
+        Kind::SerialNumbers(range) => range.contains(&atom.serial_number()),
+        Kind::BFactorAbove(value) => atom.b_factor() > *value,
+        Kind::BFactorBelow(value) => atom.b_factor() < *value,
+        Kind::OccupancyAbove(value) => atom.occupancy() > *value,
+        Kind::OccupancyBelow(value) => atom.occupancy() < *value,
+        Kind::ResidueName(name) => residue.conformer_name(atom.alternate_location()) == *name,
+        Kind::Within(_, _) => false,
+        Kind::And(a, b) => {
+            matches(a, residue, entity, atom, hetero) && matches(b, residue, entity, atom, hetero)
+        }
+        Kind::Or(a, b) => {
+            matches(a, residue, entity, atom, hetero) || matches(b, residue, entity, atom, hetero)
+        }
+        Kind::Not(a) => !matches(a, residue, entity, atom, hetero),
+    }
+}
+
+/// Get the element of the given Atom in upper case or, if that is not given, the first letter of
+/// its name that is not a digit
+fn element_of(atom: &Atom) -> String {
+    let element = atom.element();
+    if element.is_empty() {
+        atom.name()
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .chars()
+            .next()
+            .map_or_else(String::new, |c| c.to_ascii_uppercase().to_string())
+    } else {
+        element.to_ascii_uppercase()
+    }
+}
+
+/// Get if the given Atom is a hydrogen or deuterium, based on its element or, if that is not
+/// given, on the first letter of its name that is not a digit
+fn is_hydrogen(atom: &Atom) -> bool {
+    matches!(element_of(atom).as_str(), "H" | "D")
+}
+
+/// The positions of Atoms in a grid with cells of the distance size, to find the Atoms within the
+/// distance of a position by looking at neighbouring cells only
+struct NeighbourGrid {
+    /// The maximal distance
+    distance: f64,
+    /// The positions of the Atoms
+    positions: Vec<(f64, f64, f64)>,
+    /// The indices of the positions per cell
+    cells: HashMap<(i64, i64, i64), Vec<usize>>,
+}
+
+impl NeighbourGrid {
+    /// Create an empty grid
+    fn new(distance: f64) -> Self {
+        NeighbourGrid {
+            distance,
+            positions: Vec::new(),
+            cells: HashMap::new(),
+        }
+    }
+
+    /// The cell of the given position, all positions are in the same cell if the distance is zero
+    fn cell(&self, position: (f64, f64, f64)) -> (i64, i64, i64) {
+        if self.distance > 0.0 {
+            (
+                (position.0 / self.distance).floor() as i64,
+                (position.1 / self.distance).floor() as i64,
+                (position.2 / self.distance).floor() as i64,
+            )
+        } else {
+            (0, 0, 0)
+        }
+    }
+
+    /// Add the given position
+    fn insert(&mut self, position: (f64, f64, f64)) {
+        let cell = self.cell(position);
+        self.cells
+            .entry(cell)
+            .or_default()
+            .push(self.positions.len());
+        self.positions.push(position);
+    }
+
+    /// Check if there is a position within the distance of the given position
+    fn contains(&self, position: (f64, f64, f64)) -> bool {
+        let (x, y, z) = self.cell(position);
+        (-1..=1).any(|dx| {
+            (-1..=1).any(|dy| {
+                (-1..=1).any(|dz| {
+                    self.cells
+                        .get(&(x + dx, y + dy, z + dz))
+                        .into_iter()
+                        .flatten()
+                        .any(|index| {
+                            let other = self.positions[*index];
+                            (other.0 - position.0).powi(2)
+                                + (other.1 - position.1).powi(2)
+                                + (other.2 - position.2).powi(2)
+                                <= self.distance * self.distance
+                        })
+                })
+            })
+        })
+    }
+}
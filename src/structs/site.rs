@@ -0,0 +1,148 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A functional site given in SITE records, like an active site or a ligand binding site. It
+/// groups the Residues making up the site under a name (like `AC1`). Every Residue is identified
+/// by its name, chain id, serial number and insertion code, in the order of the records. The
+/// Residues themselves can be found with `PDB::site_residues`.
+pub struct Site {
+    /// The name of the site, max three characters
+    id: String,
+    /// The name, chain id, serial number and insertion code of the Residues in the site
+    residues: Vec<(String, char, usize, char)>,
+}
+
+impl Site {
+    /// Create a new Site, without any Residues
+    ///
+    /// ## Arguments
+    /// * `id` - the name of the site, like `AC1`
+    ///
+    /// ## Fails
+    /// It fails if the id is empty, longer than three characters or contains invalid characters.
+    pub fn new(id: &str) -> Option<Site> {
+        let id = id.trim();
+        if id.is_empty() || id.len() > 3 || !check_chars(id.to_string()) {
+            return None;
+        }
+        Some(Site {
+            id: id.to_string(),
+            residues: Vec::new(),
+        })
+    }
+
+    /// The name of the site, like `AC1`
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the number of Residues in the site
+    pub fn residue_count(&self) -> usize {
+        self.residues.len()
+    }
+
+    /// Get the name, chain id, serial number and insertion code of the Residues in the site
+    pub fn residues(&self) -> impl DoubleEndedIterator<Item = (&str, char, usize, char)> + '_ {
+        self.residues
+            .iter()
+            .map(|(name, chain_id, serial_number, insertion_code)| {
+                (name.as_str(), *chain_id, *serial_number, *insertion_code)
+            })
+    }
+
+    /// Add a Residue to the site, to be written after the Residues already in the site
+    ///
+    /// ## Arguments
+    /// * `residue_name` - the name of the Residue
+    /// * `chain_id` - the id of the Chain containing the Residue
+    /// * `residue_serial_number` - the serial number of the Residue
+    /// * `insertion_code` - the insertion code of the Residue, a space if there is none
+    ///
+    /// ## Fails
+    /// It fails if the name is longer than three characters or if any of the name, chain id or
+    /// insertion code contain invalid characters.
+    pub fn add_residue(
+        &mut self,
+        residue_name: &str,
+        chain_id: char,
+        residue_serial_number: usize,
+        insertion_code: char,
+    ) -> Result<(), String> {
+        let residue_name = residue_name.trim();
+        if residue_name.len() > 3 || !check_chars(residue_name.to_string()) {
+            return Err(format!(
+                "The residue name \"{}\" of site {} is not valid",
+                residue_name, self.id
+            ));
+        }
+        if let Some(c) = [chain_id, insertion_code].iter().find(|c| !check_char(**c)) {
+            return Err(format!("The character '{}' is not a valid character", c));
+        }
+        self.residues.push((
+            residue_name.to_string(),
+            chain_id,
+            residue_serial_number,
+            insertion_code,
+        ));
+        Ok(())
+    }
+
+    /// Set the chain id and serial number of the Residue at the given index, for example after
+    /// renumbering
+    /// ## Fails
+    /// It fails if the index is outside bounds or if the chain id is an invalid character.
+    pub fn set_residue(
+        &mut self,
+        index: usize,
+        chain_id: char,
+        residue_serial_number: usize,
+    ) -> Result<(), String> {
+        if !check_char(chain_id) {
+            return Err(format!(
+                "The chain id '{}' is not a valid character",
+                chain_id
+            ));
+        }
+        match self.residues.get_mut(index) {
+            Some(residue) => {
+                residue.1 = chain_id;
+                residue.2 = residue_serial_number;
+                Ok(())
+            }
+            None => Err(format!(
+                "The index {} is outside the Residues of site {}",
+                index, self.id
+            )),
+        }
+    }
+
+    /// Set the insertion code of the Residue at the given index, for example after renumbering
+    /// ## Fails
+    /// It fails if the index is outside bounds or if the insertion code is an invalid character.
+    pub fn set_insertion_code(&mut self, index: usize, insertion_code: char) -> Result<(), String> {
+        if !check_char(insertion_code) {
+            return Err(format!(
+                "The insertion code '{}' is not a valid character",
+                insertion_code
+            ));
+        }
+        match self.residues.get_mut(index) {
+            Some(residue) => {
+                residue.3 = insertion_code;
+                Ok(())
+            }
+            None => Err(format!(
+                "The index {} is outside the Residues of site {}",
+                index, self.id
+            )),
+        }
+    }
+
+    /// Returns `true` if the given Residue is part of the site
+    pub fn contains(&self, chain_id: char, residue_serial_number: usize) -> bool {
+        self.residues
+            .iter()
+            .any(|(_, c, serial, _)| *c == chain_id && *serial == residue_serial_number)
+    }
+}
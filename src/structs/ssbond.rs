@@ -0,0 +1,143 @@
+#![allow(dead_code)]
+use crate::structs::*;
+
+/// The symmetry operator of the identity, used when no symmetry operator is given
+const IDENTITY_OPERATOR: usize = 1555;
+
+#[derive(Debug, Clone, PartialEq)]
+/// An SSBOND record, describing a disulfide bridge between two cysteine Residues. Every Residue
+/// is identified by its chain id, serial number and insertion code, and has a symmetry operator
+/// (like `1555` for the identity) to place it, for bridges between symmetry related molecules.
+pub struct SSBond {
+    /// The serial number of the bridge
+    serial_number: usize,
+    /// The ids of the Chains containing the two cysteines
+    chain_ids: [char; 2],
+    /// The serial numbers of the two cysteines
+    residue_serial_numbers: [usize; 2],
+    /// The insertion codes of the two cysteines
+    insertion_codes: [char; 2],
+    /// The symmetry operators of the two cysteines
+    symmetry_operators: [usize; 2],
+    /// The length of the bond in Å, if given
+    length: Option<f64>,
+}
+
+impl SSBond {
+    /// Create a new SSBond record, with the identity as symmetry operators and no length
+    ///
+    /// ## Arguments
+    /// * `serial_number` - the serial number of the bridge
+    /// * `chain_ids` - the ids of the Chains containing the two cysteines
+    /// * `residue_serial_numbers` - the serial numbers of the two cysteines
+    /// * `insertion_codes` - the insertion codes of the two cysteines, a space if there is none
+    ///
+    /// ## Fails
+    /// It fails if any of the chain ids or insertion codes is an invalid character.
+    pub fn new(
+        serial_number: usize,
+        chain_ids: [char; 2],
+        residue_serial_numbers: [usize; 2],
+        insertion_codes: [char; 2],
+    ) -> Option<SSBond> {
+        if !chain_ids
+            .iter()
+            .chain(insertion_codes.iter())
+            .all(|c| check_char(*c))
+        {
+            return None;
+        }
+        Some(SSBond {
+            serial_number,
+            chain_ids,
+            residue_serial_numbers,
+            insertion_codes,
+            symmetry_operators: [IDENTITY_OPERATOR; 2],
+            length: None,
+        })
+    }
+
+    /// The serial number of the bridge
+    pub fn serial_number(&self) -> usize {
+        self.serial_number
+    }
+
+    /// Set the serial number of the bridge
+    pub fn set_serial_number(&mut self, new_number: usize) {
+        self.serial_number = new_number;
+    }
+
+    /// The ids of the Chains containing the two cysteines
+    pub fn chain_ids(&self) -> [char; 2] {
+        self.chain_ids
+    }
+
+    /// The serial numbers of the two cysteines
+    pub fn residue_serial_numbers(&self) -> [usize; 2] {
+        self.residue_serial_numbers
+    }
+
+    /// Set the chain ids and serial numbers of the two cysteines, for example after renumbering
+    /// ## Fails
+    /// It fails if any of the chain ids is an invalid character.
+    pub fn set_residues(
+        &mut self,
+        chain_ids: [char; 2],
+        residue_serial_numbers: [usize; 2],
+    ) -> Result<(), String> {
+        if let Some(c) = chain_ids.iter().find(|c| !check_char(**c)) {
+            return Err(format!("The chain id '{}' is not a valid character", c));
+        }
+        self.chain_ids = chain_ids;
+        self.residue_serial_numbers = residue_serial_numbers;
+        Ok(())
+    }
+
+    /// The insertion codes of the two cysteines, a space if there is none
+    pub fn insertion_codes(&self) -> [char; 2] {
+        self.insertion_codes
+    }
+
+    /// Set the insertion codes of the two cysteines, for example after renumbering
+    /// ## Fails
+    /// It fails if any of the insertion codes is an invalid character.
+    pub fn set_insertion_codes(&mut self, insertion_codes: [char; 2]) -> Result<(), String> {
+        if let Some(c) = insertion_codes.iter().find(|c| !check_char(**c)) {
+            return Err(format!(
+                "The insertion code '{}' is not a valid character",
+                c
+            ));
+        }
+        self.insertion_codes = insertion_codes;
+        Ok(())
+    }
+
+    /// The symmetry operators of the two cysteines, as the number of the symmetry operation
+    /// followed by the translations along a, b and c plus five, so `1555` is the identity
+    pub fn symmetry_operators(&self) -> [usize; 2] {
+        self.symmetry_operators
+    }
+
+    /// Set the symmetry operators of the two cysteines, see `symmetry_operators`
+    pub fn set_symmetry_operators(&mut self, operators: [usize; 2]) {
+        self.symmetry_operators = operators;
+    }
+
+    /// The length of the bond in Å, if given
+    pub fn length(&self) -> Option<f64> {
+        self.length
+    }
+
+    /// Set the length of the bond in Å
+    pub fn set_length(&mut self, length: Option<f64>) {
+        self.length = length;
+    }
+
+    /// Returns `true` if the given Residue is one of the two cysteines of this bridge
+    pub fn contains(&self, chain_id: char, residue_serial_number: usize) -> bool {
+        (0..2).any(|index| {
+            self.chain_ids[index] == chain_id
+                && self.residue_serial_numbers[index] == residue_serial_number
+        })
+    }
+}
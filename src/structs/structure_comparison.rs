@@ -0,0 +1,133 @@
+#![allow(dead_code)]
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A summary of the differences between two structures of the same molecule, for example from
+/// different crystal forms, as made by `PDB::compare_structures`. Only the Normal Chains of the
+/// first Model of both structures are compared, Residues are paired by chain id and serial number.
+pub struct StructureComparison {
+    /// The sequences of the Chains are the same
+    sequence_identical: bool,
+    /// The same chain ids are used
+    chain_ids_match: bool,
+    /// The number of Residues in the other structure minus the number in this structure
+    residue_count_diff: isize,
+    /// The RMSD of all paired CA atoms after superposition
+    global_rmsd: Option<f64>,
+    /// The RMSD of the paired CA atoms of every Chain in this structure after superposition
+    per_chain_rmsd: Vec<(char, Option<f64>)>,
+    /// The ids of the Chains only present in this structure
+    extra_chains_in_self: Vec<char>,
+    /// The ids of the Chains only present in the other structure
+    extra_chains_in_other: Vec<char>,
+    /// The Pearson correlation of the B-factors of the paired CA atoms
+    b_factor_correlation: f64,
+}
+
+impl StructureComparison {
+    /// Create a new StructureComparison
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        sequence_identical: bool,
+        chain_ids_match: bool,
+        residue_count_diff: isize,
+        global_rmsd: Option<f64>,
+        per_chain_rmsd: Vec<(char, Option<f64>)>,
+        extra_chains_in_self: Vec<char>,
+        extra_chains_in_other: Vec<char>,
+        b_factor_correlation: f64,
+    ) -> StructureComparison {
+        StructureComparison {
+            sequence_identical,
+            chain_ids_match,
+            residue_count_diff,
+            global_rmsd,
+            per_chain_rmsd,
+            extra_chains_in_self,
+            extra_chains_in_other,
+            b_factor_correlation,
+        }
+    }
+
+    /// Whether the sequences (the residue names of every Chain in order) of both structures are the same
+    pub fn sequence_identical(&self) -> bool {
+        self.sequence_identical
+    }
+
+    /// Whether both structures use the same chain ids
+    pub fn chain_ids_match(&self) -> bool {
+        self.chain_ids_match
+    }
+
+    /// The number of Residues in the other structure minus the number of Residues in this structure
+    pub fn residue_count_diff(&self) -> isize {
+        self.residue_count_diff
+    }
+
+    /// The RMSD in Å of all paired CA atoms after superposition, `None` if there are less than three pairs
+    pub fn global_rmsd(&self) -> Option<f64> {
+        self.global_rmsd
+    }
+
+    /// The RMSD in Å of the paired CA atoms of every Chain in this structure after superposing the
+    /// Chain onto the Chain with the same id in the other structure, `None` if there are less than three pairs
+    pub fn per_chain_rmsd(&self) -> &[(char, Option<f64>)] {
+        &self.per_chain_rmsd
+    }
+
+    /// The ids of the Chains only present in this structure
+    pub fn extra_chains_in_self(&self) -> &[char] {
+        &self.extra_chains_in_self
+    }
+
+    /// The ids of the Chains only present in the other structure
+    pub fn extra_chains_in_other(&self) -> &[char] {
+        &self.extra_chains_in_other
+    }
+
+    /// The Pearson correlation coefficient of the B-factors of the paired CA atoms, 0.0 if it is
+    /// undefined (less than two pairs or no variation in the B-factors)
+    pub fn b_factor_correlation(&self) -> f64 {
+        self.b_factor_correlation
+    }
+}
+
+impl fmt::Display for StructureComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rmsd = |value: Option<f64>| match value {
+            Some(value) => format!("{:.3} Å", value),
+            None => "undefined".to_string(),
+        };
+        writeln!(
+            f,
+            "Sequence identical: {}",
+            if self.sequence_identical { "yes" } else { "no" }
+        )?;
+        writeln!(
+            f,
+            "Chain ids match: {}",
+            if self.chain_ids_match { "yes" } else { "no" }
+        )?;
+        writeln!(f, "Residue count difference: {}", self.residue_count_diff)?;
+        writeln!(f, "Global CA RMSD: {}", rmsd(self.global_rmsd))?;
+        for (chain, value) in &self.per_chain_rmsd {
+            writeln!(f, "Chain {} CA RMSD: {}", chain, rmsd(*value))?;
+        }
+        if !self.extra_chains_in_self.is_empty() {
+            writeln!(
+                f,
+                "Chains only in self: {}",
+                self.extra_chains_in_self.iter().collect::<String>()
+            )?;
+        }
+        if !self.extra_chains_in_other.is_empty() {
+            writeln!(
+                f,
+                "Chains only in other: {}",
+                self.extra_chains_in_other.iter().collect::<String>()
+            )?;
+        }
+        write!(f, "B-factor correlation: {:.3}", self.b_factor_correlation)
+    }
+}
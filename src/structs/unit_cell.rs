@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+use crate::transformation::*;
 
 #[derive(Debug, Clone)]
 /// A unit cell of a crystal, containing its dimensions and angles
@@ -15,6 +16,8 @@ pub struct UnitCell {
     beta: f64,
     /// gamma angle in degrees
     gamma: f64,
+    /// The Z value, the number of polymeric chains in the unit cell, as given in the CRYST1 record
+    z: Option<usize>,
 }
 
 impl UnitCell {
@@ -34,6 +37,7 @@ impl UnitCell {
             alpha,
             beta,
             gamma,
+            z: None,
         }
     }
     /// Get the a-axis dimension
@@ -60,10 +64,157 @@ impl UnitCell {
     pub fn gamma(&self) -> f64 {
         self.gamma
     }
+    /// Get the Z value, the number of polymeric chains in the unit cell, if it is known. This is
+    /// read from the CRYST1 record and written back when saving.
+    pub fn z(&self) -> Option<usize> {
+        self.z
+    }
+    /// Set the Z value, the number of polymeric chains in the unit cell
+    pub fn set_z(&mut self, z: Option<usize>) {
+        self.z = z;
+    }
     /// Get the dimensions in a tuple (a, b, c)
     pub fn size(&self) -> (f64, f64, f64) {
         (self.a, self.b, self.c)
     }
+
+    /// Get the volume of the UnitCell
+    pub fn volume(&self) -> f64 {
+        let (ca, cb, cg) = (
+            self.alpha.to_radians().cos(),
+            self.beta.to_radians().cos(),
+            self.gamma.to_radians().cos(),
+        );
+        self.a * self.b * self.c * (1.0 - ca * ca - cb * cb - cg * cg + 2.0 * ca * cb * cg).sqrt()
+    }
+
+    /// Get the matrix to convert fractional coordinates to orthogonal (Cartesian) coordinates.
+    /// This uses the standard PDB convention, with the a-axis along X and the b-axis in the XY plane.
+    pub fn orthogonalisation_matrix(&self) -> TransformationMatrix {
+        let (ca, cb) = (self.alpha.to_radians().cos(), self.beta.to_radians().cos());
+        let (sg, cg) = self.gamma.to_radians().sin_cos();
+        TransformationMatrix::from_matrix([
+            [self.a, self.b * cg, self.c * cb, 0.0],
+            [0.0, self.b * sg, self.c * (ca - cb * cg) / sg, 0.0],
+            [0.0, 0.0, self.volume() / (self.a * self.b * sg), 0.0],
+        ])
+    }
+
+    /// Get the matrix to convert orthogonal (Cartesian) coordinates to fractional coordinates.
+    /// This is the inverse of `orthogonalisation_matrix`.
+    pub fn fractionalisation_matrix(&self) -> TransformationMatrix {
+        let m = self.orthogonalisation_matrix().matrix();
+        TransformationMatrix::from_matrix([
+            [
+                1.0 / m[0][0],
+                -m[0][1] / (m[0][0] * m[1][1]),
+                (m[0][1] * m[1][2] - m[0][2] * m[1][1]) / (m[0][0] * m[1][1] * m[2][2]),
+                0.0,
+            ],
+            [0.0, 1.0 / m[1][1], -m[1][2] / (m[1][1] * m[2][2]), 0.0],
+            [0.0, 0.0, 1.0 / m[2][2], 0.0],
+        ])
+    }
+
+    /// Get if all angles of the UnitCell are 90 degrees, so the axes are orthogonal and the
+    /// minimum image of a vector can be found by rounding its fractional coordinates
+    pub fn is_orthorhombic(&self) -> bool {
+        [self.alpha, self.beta, self.gamma]
+            .iter()
+            .all(|angle| (angle - 90.0).abs() < ANGLE_TOLERANCE)
+    }
+
+    /// Get if none of the angles of the UnitCell are 90 degrees. Note that a monoclinic cell is
+    /// neither orthorhombic nor triclinic.
+    pub fn is_triclinic(&self) -> bool {
+        [self.alpha, self.beta, self.gamma]
+            .iter()
+            .all(|angle| (angle - 90.0).abs() >= ANGLE_TOLERANCE)
+    }
+
+    /// Get all lattice translations (in unit cell vectors along a, b and c) with a length of at
+    /// most `radius` Å, including (0, 0, 0). The translations are sorted by length. For skewed
+    /// cells this can include translations outside of the 27 cells surrounding the origin. It is
+    /// empty if the radius is negative or not finite.
+    /// ## Arguments
+    /// * `radius` - the maximal length of the translations in Å
+    pub fn lattice_translations_within(&self, radius: f64) -> Vec<(i32, i32, i32)> {
+        if !radius.is_finite() || radius < 0.0 {
+            return Vec::new();
+        }
+        let orthogonalisation = self.orthogonalisation_matrix();
+        let fractionalisation = self.fractionalisation_matrix().matrix();
+        // A vector of length `radius` has at most `radius * |row|` as fractional coordinate
+        let bound = |row: [f64; 4]| {
+            (radius * (row[0] * row[0] + row[1] * row[1] + row[2] * row[2]).sqrt()).floor() as i32
+        };
+        let (na, nb, nc) = (
+            bound(fractionalisation[0]),
+            bound(fractionalisation[1]),
+            bound(fractionalisation[2]),
+        );
+        let mut translations = Vec::new();
+        for i in -na..=na {
+            for j in -nb..=nb {
+                for k in -nc..=nc {
+                    let length = length(orthogonalisation.apply((i as f64, j as f64, k as f64)));
+                    if length <= radius {
+                        translations.push((length, (i, j, k)));
+                    }
+                }
+            }
+        }
+        translations.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        translations
+            .into_iter()
+            .map(|(_, translation)| translation)
+            .collect()
+    }
+
+    /// Get the shortest vector equivalent to the given vector under periodic boundary conditions
+    /// (the minimum image convention). The fractional coordinates of the vector are shifted into
+    /// [-0.5, 0.5], which gives the minimum image for orthorhombic cells. For other cells the
+    /// lattice translations that could give a shorter vector are searched as well, so this is also
+    /// exact for highly skewed triclinic cells.
+    /// ## Arguments
+    /// * `d` - the vector (x, y, z) in Å
+    pub fn minimum_image_vector(&self, d: (f64, f64, f64)) -> (f64, f64, f64) {
+        let f = self.fractionalisation_matrix().apply(d);
+        let orthogonalisation = self.orthogonalisation_matrix();
+        let reduced =
+            orthogonalisation.apply((f.0 - f.0.round(), f.1 - f.1.round(), f.2 - f.2.round()));
+        if self.is_orthorhombic() {
+            return reduced;
+        }
+        // Any shorter image differs from the reduced vector by at most twice its length
+        let mut best = (length(reduced), reduced);
+        for (i, j, k) in self.lattice_translations_within(2.0 * best.0) {
+            let t = orthogonalisation.apply((i as f64, j as f64, k as f64));
+            let image = (reduced.0 + t.0, reduced.1 + t.1, reduced.2 + t.2);
+            let image_length = length(image);
+            if image_length < best.0 {
+                best = (image_length, image);
+            }
+        }
+        best.1
+    }
+
+    /// Get the minimum image distance between two positions under periodic boundary conditions,
+    /// so the distance from `a` to the closest periodic image of `b`, see `minimum_image_vector`.
+    /// ## Arguments
+    /// * `a` - the first position (x, y, z) in Å
+    /// * `b` - the second position (x, y, z) in Å
+    pub fn minimum_image_distance(&self, a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+        length(self.minimum_image_vector((b.0 - a.0, b.1 - a.1, b.2 - a.2)))
+    }
+}
+
+/// The tolerance in degrees used to decide if an angle is 90 degrees
+const ANGLE_TOLERANCE: f64 = 1e-6;
+
+/// The length of a vector
+fn length(v: (f64, f64, f64)) -> f64 {
+    (v.0 * v.0 + v.1 * v.1 + v.2 * v.2).sqrt()
 }
 
 impl PartialEq for UnitCell {
@@ -74,3 +225,147 @@ impl PartialEq for UnitCell {
             && self.gamma == other.gamma
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::UnitCell;
+
+    #[test]
+    fn fractionalisation() {
+        let cell = UnitCell::new(30.0, 40.0, 50.0, 80.0, 95.0, 110.0);
+        let orthogonal = cell.orthogonalisation_matrix();
+        let fractional = cell.fractionalisation_matrix();
+        let pos = (0.25, -1.5, 0.75);
+        let back = fractional.apply(orthogonal.apply(pos));
+        assert!((pos.0 - back.0).abs() < 1e-9);
+        assert!((pos.1 - back.1).abs() < 1e-9);
+        assert!((pos.2 - back.2).abs() < 1e-9);
+        assert_eq!(orthogonal.apply((1.0, 0.0, 0.0)), (30.0, 0.0, 0.0));
+        let cubic = UnitCell::new(10.0, 10.0, 10.0, 90.0, 90.0, 90.0);
+        assert!((cubic.volume() - 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn minimum_image_distance() {
+        let cubic = UnitCell::new(10.0, 10.0, 10.0, 90.0, 90.0, 90.0);
+        let distance = cubic.minimum_image_distance((0.0, 0.0, 0.0), (9.0, 0.0, 0.0));
+        assert!((distance - 1.0).abs() < 1e-9);
+        let distance = cubic.minimum_image_distance((1.0, 2.0, 3.0), (1.0, -17.0, 3.5));
+        assert!((distance - (1.0_f64 + 0.25).sqrt()).abs() < 1e-9);
+        let monoclinic = UnitCell::new(10.0, 12.0, 14.0, 90.0, 100.0, 90.0);
+        let c = monoclinic.orthogonalisation_matrix().apply((0.0, 0.0, 1.0));
+        let distance =
+            monoclinic.minimum_image_distance((1.0, 1.0, 1.0), (1.0 + c.0, 1.5 + c.1, 1.0 + c.2));
+        assert!((distance - 0.5).abs() < 1e-9);
+    }
+
+    /// The shortest image of `d` found by trying all translations up to 15 cells away
+    fn brute_force_minimum_image(cell: &UnitCell, d: (f64, f64, f64)) -> f64 {
+        let orthogonalisation = cell.orthogonalisation_matrix();
+        let mut best = f64::MAX;
+        for i in -15..=15 {
+            for j in -15..=15 {
+                for k in -15..=15 {
+                    let t = orthogonalisation.apply((i as f64, j as f64, k as f64));
+                    let image = (d.0 + t.0, d.1 + t.1, d.2 + t.2);
+                    best = best.min(super::length(image));
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn cell_shape() {
+        assert!(UnitCell::new(10.0, 12.0, 14.0, 90.0, 90.0, 90.0).is_orthorhombic());
+        assert!(!UnitCell::new(10.0, 12.0, 14.0, 90.0, 90.0, 90.0).is_triclinic());
+        let monoclinic = UnitCell::new(10.0, 12.0, 14.0, 90.0, 100.0, 90.0);
+        assert!(!monoclinic.is_orthorhombic());
+        assert!(!monoclinic.is_triclinic());
+        assert!(UnitCell::new(10.0, 12.0, 14.0, 80.0, 100.0, 110.0).is_triclinic());
+    }
+
+    #[test]
+    fn lattice_translations_within() {
+        let cubic = UnitCell::new(10.0, 10.0, 10.0, 90.0, 90.0, 90.0);
+        assert_eq!(cubic.lattice_translations_within(5.0), vec![(0, 0, 0)]);
+        let translations = cubic.lattice_translations_within(10.0);
+        assert_eq!(translations.len(), 7);
+        assert_eq!(translations[0], (0, 0, 0));
+        assert_eq!(cubic.lattice_translations_within(14.2).len(), 19);
+        assert!(cubic.lattice_translations_within(-1.0).is_empty());
+
+        // With a gamma of 20 degrees a - b is only about 3.5 Å long, so even (3, -3, 0) is
+        // closer than the c-axis
+        let skewed = UnitCell::new(10.0, 10.0, 20.0, 90.0, 90.0, 20.0);
+        let translations = skewed.lattice_translations_within(12.0);
+        assert!(translations.contains(&(3, -3, 0)));
+        assert!(!translations.contains(&(0, 0, 1)));
+        let orthogonalisation = skewed.orthogonalisation_matrix();
+        for i in -10..=10 {
+            for j in -10..=10 {
+                for k in -10..=10 {
+                    let t = orthogonalisation.apply((i as f64, j as f64, k as f64));
+                    assert_eq!(
+                        super::length(t) <= 12.0,
+                        translations.contains(&(i, j, k)),
+                        "translation ({}, {}, {})",
+                        i,
+                        j,
+                        k
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn minimum_image_vector_skewed() {
+        let cells = [
+            UnitCell::new(10.0, 10.0, 20.0, 90.0, 90.0, 20.0),
+            UnitCell::new(8.0, 15.0, 30.0, 100.0, 110.0, 40.0),
+            UnitCell::new(20.0, 7.0, 9.0, 120.0, 45.0, 100.0),
+        ];
+        let mut rounding_differs = false;
+        for cell in &cells {
+            let orthogonalisation = cell.orthogonalisation_matrix();
+            for step in 0..100 {
+                let x = step as f64;
+                let d = (
+                    (x * 1.37).sin() * 15.0,
+                    (x * 2.11).cos() * 15.0,
+                    (x * 0.53).sin() * 15.0,
+                );
+                let image = cell.minimum_image_vector(d);
+                let expected = brute_force_minimum_image(cell, d);
+                assert!((super::length(image) - expected).abs() < 1e-9);
+                assert!(
+                    (cell.minimum_image_distance(
+                        (1.0, 2.0, 3.0),
+                        (1.0 + d.0, 2.0 + d.1, 3.0 + d.2)
+                    ) - expected)
+                        .abs()
+                        < 1e-9
+                );
+                // The image differs from the vector by a lattice translation
+                let shift = cell.fractionalisation_matrix().apply((
+                    image.0 - d.0,
+                    image.1 - d.1,
+                    image.2 - d.2,
+                ));
+                assert!((shift.0 - shift.0.round()).abs() < 1e-9);
+                assert!((shift.1 - shift.1.round()).abs() < 1e-9);
+                assert!((shift.2 - shift.2.round()).abs() < 1e-9);
+                // Rounding the fractional coordinates alone is not enough for these cells
+                let f = cell.fractionalisation_matrix().apply(d);
+                let rounded = orthogonalisation.apply((
+                    f.0 - f.0.round(),
+                    f.1 - f.1.round(),
+                    f.2 - f.2.round(),
+                ));
+                rounding_differs |= super::length(rounded) > expected + 1e-6;
+            }
+        }
+        assert!(rounding_differs);
+    }
+}
@@ -98,12 +98,141 @@ impl TransformationMatrix {
         }
     }
 
+    /// Create the rigid body transformation (rotation and translation) that superposes the `source`
+    /// positions onto the `target` positions with the minimal root mean square deviation. The
+    /// positions are paired by index. It uses the quaternion based method by Horn (Closed-form solution
+    /// of absolute orientation using unit quaternions, J. Opt. Soc. Am. A 4, 629-642, 1987), which gives
+    /// the same result as the Kabsch algorithm but is guaranteed to never introduce a reflection.
+    /// ## Fails
+    /// It fails if the slices are empty or of different lengths.
+    pub fn superpose(
+        source: &[(f64, f64, f64)],
+        target: &[(f64, f64, f64)],
+    ) -> Option<TransformationMatrix> {
+        if source.is_empty() || source.len() != target.len() {
+            return None;
+        }
+        let centroid = |positions: &[(f64, f64, f64)]| {
+            let n = positions.len() as f64;
+            positions.iter().fold((0.0, 0.0, 0.0), |acc, p| {
+                (acc.0 + p.0 / n, acc.1 + p.1 / n, acc.2 + p.2 / n)
+            })
+        };
+        let cs = centroid(source);
+        let ct = centroid(target);
+
+        // The cross covariance matrix of the centered positions
+        let mut s = [[0.0; 3]; 3];
+        for (a, b) in source.iter().zip(target) {
+            let x = [a.0 - cs.0, a.1 - cs.1, a.2 - cs.2];
+            let y = [b.0 - ct.0, b.1 - ct.1, b.2 - ct.2];
+            for i in 0..3 {
+                for j in 0..3 {
+                    s[i][j] += x[i] * y[j];
+                }
+            }
+        }
+        let n = [
+            [
+                s[0][0] + s[1][1] + s[2][2],
+                s[1][2] - s[2][1],
+                s[2][0] - s[0][2],
+                s[0][1] - s[1][0],
+            ],
+            [
+                s[1][2] - s[2][1],
+                s[0][0] - s[1][1] - s[2][2],
+                s[0][1] + s[1][0],
+                s[2][0] + s[0][2],
+            ],
+            [
+                s[2][0] - s[0][2],
+                s[0][1] + s[1][0],
+                -s[0][0] + s[1][1] - s[2][2],
+                s[1][2] + s[2][1],
+            ],
+            [
+                s[0][1] - s[1][0],
+                s[2][0] + s[0][2],
+                s[1][2] + s[2][1],
+                -s[0][0] - s[1][1] + s[2][2],
+            ],
+        ];
+        let q = largest_eigenvector(n);
+        let rotation = [
+            [
+                q[0] * q[0] + q[1] * q[1] - q[2] * q[2] - q[3] * q[3],
+                2.0 * (q[1] * q[2] - q[0] * q[3]),
+                2.0 * (q[1] * q[3] + q[0] * q[2]),
+            ],
+            [
+                2.0 * (q[1] * q[2] + q[0] * q[3]),
+                q[0] * q[0] - q[1] * q[1] + q[2] * q[2] - q[3] * q[3],
+                2.0 * (q[2] * q[3] - q[0] * q[1]),
+            ],
+            [
+                2.0 * (q[1] * q[3] - q[0] * q[2]),
+                2.0 * (q[2] * q[3] + q[0] * q[1]),
+                q[0] * q[0] - q[1] * q[1] - q[2] * q[2] + q[3] * q[3],
+            ],
+        ];
+        let mut matrix = [[0.0; 4]; 3];
+        for i in 0..3 {
+            matrix[i][..3].copy_from_slice(&rotation[i]);
+            matrix[i][3] = [ct.0, ct.1, ct.2][i]
+                - (rotation[i][0] * cs.0 + rotation[i][1] * cs.1 + rotation[i][2] * cs.2);
+        }
+        Some(TransformationMatrix { matrix })
+    }
+
     pub fn multiply_translation(&mut self, factors: (f64, f64, f64)) {
         self.matrix[0][3] *= factors.0;
         self.matrix[1][3] *= factors.1;
         self.matrix[2][3] *= factors.2;
     }
 
+    /// Get the inverse of this transformation, so applying this transformation and then its
+    /// inverse gives back the original position.
+    /// ## Fails
+    /// It returns `None` if the transformation is not invertible (the determinant of the rotation
+    /// part is zero or not finite).
+    pub fn inverse(&self) -> Option<Self> {
+        let m = &self.matrix;
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+        };
+        let adjugate = [
+            [
+                cofactor(1, 2, 1, 2),
+                -cofactor(0, 2, 1, 2),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                -cofactor(1, 2, 0, 2),
+                cofactor(0, 2, 0, 2),
+                -cofactor(0, 1, 0, 2),
+            ],
+            [
+                cofactor(1, 2, 0, 1),
+                -cofactor(0, 2, 0, 1),
+                cofactor(0, 1, 0, 1),
+            ],
+        ];
+        let determinant =
+            m[0][0] * adjugate[0][0] + m[0][1] * adjugate[1][0] + m[0][2] * adjugate[2][0];
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        let mut matrix = [[0.0; 4]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                matrix[i][j] = adjugate[i][j] / determinant;
+            }
+            matrix[i][3] = -(0..3).map(|j| matrix[i][j] * m[j][3]).sum::<f64>();
+        }
+        Some(TransformationMatrix { matrix })
+    }
+
     /// Apply this transformation to the given position.
     /// It returns the new position.
     /// ## Arguments
@@ -180,6 +309,90 @@ impl TransformationMatrix {
     }
 }
 
+/// The root mean square deviation of the `source` positions after superposing them onto the
+/// `target` positions (see `TransformationMatrix::superpose`), paired by index.
+/// Returns `None` if there are less than three positions or the slices are of different lengths.
+pub(crate) fn superposed_rmsd(
+    source: &[(f64, f64, f64)],
+    target: &[(f64, f64, f64)],
+) -> Option<f64> {
+    if source.len() < 3 {
+        return None;
+    }
+    let transformation = TransformationMatrix::superpose(source, target)?;
+    let sum = source
+        .iter()
+        .zip(target)
+        .map(|(s, t)| {
+            let s = transformation.apply(*s);
+            (s.0 - t.0).powi(2) + (s.1 - t.1).powi(2) + (s.2 - t.2).powi(2)
+        })
+        .sum::<f64>();
+    Some((sum / source.len() as f64).sqrt())
+}
+
+/// Find the normalised eigenvector belonging to the largest eigenvalue of a symmetric 4x4 matrix
+/// using the cyclic Jacobi eigenvalue algorithm.
+fn largest_eigenvector(mut a: [[f64; 4]; 4]) -> [f64; 4] {
+    let mut v = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+    for _ in 0..50 {
+        let off_diagonal: f64 = (0..4)
+            .flat_map(|p| (p + 1..4).map(move |q| (p, q)))
+            .map(|(p, q)| a[p][q] * a[p][q])
+            .sum();
+        if off_diagonal < 1e-22 {
+            break;
+        }
+        for p in 0..4 {
+            for q in p + 1..4 {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let akp = row[p];
+                    let akq = row[q];
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (row_p, row_q) = (a[p], a[q]);
+                for (k, (apk, aqk)) in row_p.iter().zip(row_q.iter()).enumerate() {
+                    a[p][k] = c * apk - s * aqk;
+                    a[q][k] = s * apk + c * aqk;
+                }
+                for row in v.iter_mut() {
+                    let vkp = row[p];
+                    let vkq = row[q];
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    let mut largest = 0;
+    for i in 1..4 {
+        if a[i][i] > a[largest][largest] {
+            largest = i;
+        }
+    }
+    let vector = [v[0][largest], v[1][largest], v[2][largest], v[3][largest]];
+    let length = vector.iter().map(|x| x * x).sum::<f64>().sqrt();
+    [
+        vector[0] / length,
+        vector[1] / length,
+        vector[2] / length,
+        vector[3] / length,
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::TransformationMatrix;
@@ -191,6 +404,20 @@ mod tests {
         assert_eq!(pos, new_pos);
     }
 
+    #[test]
+    fn inverse() {
+        let transformation = TransformationMatrix::rotation_x(30.0)
+            .combine(&TransformationMatrix::translation(1.0, -2.0, 3.0))
+            .combine(&TransformationMatrix::magnify(2.0));
+        let pos = (4.0, 5.0, -6.0);
+        let back = transformation
+            .inverse()
+            .unwrap()
+            .apply(transformation.apply(pos));
+        assert!(close_tuple(back, pos));
+        assert!(TransformationMatrix::magnify(0.0).inverse().is_none());
+    }
+
     #[test]
     fn combination() {
         let pos = (10.0, 0.0, 0.0);
@@ -236,8 +463,8 @@ mod tests {
         let new_pos = TransformationMatrix::rotation_x(44.5).apply(pos);
         let end = (
             0.0,
-            (44.5 as f64).to_radians().sin(),
-            -(44.5 as f64).to_radians().cos(),
+            44.5_f64.to_radians().sin(),
+            -44.5_f64.to_radians().cos(),
         );
         println!("{:?} vs {:?}", new_pos, end);
         assert!(close_tuple(new_pos, end));
@@ -285,9 +512,9 @@ mod tests {
         let pos = (0.0, 0.0, -1.0);
         let new_pos = TransformationMatrix::rotation_y(44.5).apply(pos);
         let end = (
-            -(44.5 as f64).to_radians().sin(),
+            -44.5_f64.to_radians().sin(),
             0.0,
-            -(44.5 as f64).to_radians().cos(),
+            -44.5_f64.to_radians().cos(),
         );
         println!("{:?} vs {:?}", new_pos, end);
         assert!(close_tuple(new_pos, end));
@@ -336,8 +563,8 @@ mod tests {
         let pos = (0.0, -1.0, 0.0);
         let new_pos = TransformationMatrix::rotation_z(44.5).apply(pos);
         let end = (
-            (44.5 as f64).to_radians().sin(),
-            -(44.5 as f64).to_radians().cos(),
+            44.5_f64.to_radians().sin(),
+            -44.5_f64.to_radians().cos(),
             0.0,
         );
         println!("{:?} vs {:?}", new_pos, end);
@@ -394,7 +621,7 @@ mod tests {
     fn matrix() {
         let normal = TransformationMatrix::rotation_x(45.0);
         let raw = normal.matrix();
-        let from_matrix = TransformationMatrix::from_matrix(raw.clone());
+        let from_matrix = TransformationMatrix::from_matrix(raw);
         let mut set = TransformationMatrix::identity();
         set.set_matrix(raw);
         assert_eq!(normal, from_matrix);
@@ -402,6 +629,29 @@ mod tests {
         assert_eq!(normal, set);
     }
 
+    #[test]
+    fn superpose() {
+        let source = vec![
+            (1.0, 0.0, 0.0),
+            (0.0, 2.0, 0.0),
+            (0.0, 0.0, 3.0),
+            (1.5, -2.0, 0.5),
+        ];
+        let transformation = TransformationMatrix::rotation_x(35.0)
+            .combine(&TransformationMatrix::rotation_z(-120.0))
+            .combine(&TransformationMatrix::translation(4.0, -3.0, 12.5));
+        let target = source
+            .iter()
+            .map(|p| transformation.apply(*p))
+            .collect::<Vec<_>>();
+        let found = TransformationMatrix::superpose(&source, &target).unwrap();
+        for (s, t) in source.iter().zip(&target) {
+            assert!(close_tuple(found.apply(*s), *t));
+        }
+        assert!(TransformationMatrix::superpose(&source, &target[1..]).is_none());
+        assert!(TransformationMatrix::superpose(&[], &[]).is_none());
+    }
+
     fn close_tuple(a: (f64, f64, f64), b: (f64, f64, f64)) -> bool {
         close(a.0, b.0) && close(a.1, b.1) && close(a.2, b.2)
     }
@@ -1,87 +1,869 @@
+use crate::b_factors::{self, BFactorStatistics, BFactorThresholds};
 use crate::error::*;
+use crate::reference_tables;
 use crate::structs::*;
+use crate::validation_profile::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 /// Validate a given PDB file in terms of invariants that should be held up.
 /// It returns PDBErrors with the warning messages.
 ///
 /// ## Invariants Tested
 /// * With multiple models the models should all contain atoms that correspond.
+/// * The number of models given in the NUMMDL record matches the number of models.
 /// * All matrix type PDB records (SCALEn, ORIGXn, MTRIXn) have to be fully specified, so all rows set.
+/// * All helices (HELIX records) start before they end, in the same Chain.
 ///
 /// ## Invariants Not Tested
 /// * Numbering of all structs, serial numbers should be unique. To enforce this the `renumber()` function should be called on the PDB struct.
-pub fn validate(pdb: &PDB) -> Vec<PDBError> {
+///   The order of the Atom serial numbers in the file is checked while parsing.
+pub fn validate_pdb(pdb: &PDB) -> Vec<PDBError> {
     // Print warnings/errors and return a bool for success
     let mut errors = Vec::new();
     if pdb.model_count() > 1 {
-        errors.append(&mut validate_models(pdb));
+        let first = pdb.model(0).unwrap();
+        for model in pdb.models().skip(1) {
+            errors.append(&mut validate_model(first, model));
+        }
     }
-    if pdb.has_scale() && !pdb.scale().valid() {
-        errors.push(PDBError::new(
-            ErrorLevel::InvalidatingError,
-            "Row not set",
-            "A row was not set for SCALEn in the PDB.",
-            Context::None,
+    errors.append(&mut validate_model_count(pdb));
+    errors.append(&mut validate_matrices(pdb));
+    errors.append(&mut validate_helices(pdb));
+    errors
+}
+
+/// Validate a given PDB file with the checks of the given profile, at the levels set in the profile.
+/// The checks done while parsing (like the MASTER checksum) are not part of this, see
+/// `ReadOptions::set_validation_profile` to use a profile while parsing. Using
+/// `ValidationProfile::preset(ValidationPreset::Minimal)` gives the same errors as `validate_pdb`.
+pub fn validate_with(pdb: &PDB, profile: &ValidationProfile) -> Vec<PDBError> {
+    let mut errors = Vec::new();
+    if profile.runs(ValidationCheck::ModelCorrespondence) && pdb.model_count() > 1 {
+        let first = pdb.model(0).unwrap();
+        for model in pdb.models().skip(1) {
+            errors.append(&mut validate_model(first, model));
+        }
+    }
+    if profile.runs(ValidationCheck::ModelCount) {
+        errors.append(&mut validate_model_count(pdb));
+    }
+    if profile.runs(ValidationCheck::MatrixRows) {
+        errors.append(&mut validate_matrices(pdb));
+    }
+    if profile.runs(ValidationCheck::HelixRanges) {
+        errors.append(&mut validate_helices(pdb));
+    }
+    if profile.runs(ValidationCheck::HetatmCompleteness) {
+        errors.append(&mut validate_hetatm_completeness(pdb));
+    }
+    if profile.runs(ValidationCheck::HetatmConnections) {
+        errors.append(&mut validate_hetatm_has_conect(pdb));
+    }
+    if profile.runs(ValidationCheck::ConformerOccupancies) {
+        errors.append(&mut validate_conformer_occupancies(pdb));
+    }
+    if profile.runs(ValidationCheck::AnisotropicFactors) {
+        errors.append(&mut validate_anisotropic_factors(pdb, 0.05, 0.95));
+    }
+    if profile.runs(ValidationCheck::CisPeptideResidues) {
+        errors.append(&mut validate_cis_peptides(pdb));
+    }
+    if profile.runs(ValidationCheck::SolventNumbering) {
+        errors.append(&mut validate_solvent_numbering(pdb));
+    }
+    if profile.runs(ValidationCheck::ResidueNumbering) {
+        errors.append(&mut validate_residue_numbering(pdb));
+    }
+    if profile.runs(ValidationCheck::BFactorDistribution) {
+        errors.append(&mut validate_b_factors(pdb, &BFactorThresholds::default()));
+    }
+    profile.apply(errors)
+}
+
+/// Validate a given PDB file in terms of the same invariants as `validate_pdb`, but only re-check the
+/// Models changed since the last call. This is meant for interactive tools that validate after
+/// every edit, where a full validation of a big structure would be too slow. It returns the same
+/// errors, in the same order, as `validate_pdb` would after any sequence of edits.
+///
+/// Every structural change to a Model (anything done through a method taking `&mut self` of the
+/// Model, or of the PDB, Chains, Residues or Atoms reached through it) marks that Model as changed.
+/// As every Model is compared to the first Model, a change to the first Model means all Models are
+/// checked again. The checks of the number of Models, the matrix type records (SCALEn, ORIGXn,
+/// MTRIXn) and the helices are cheap and always done. The results are cached in the PDB, a clone starts with an empty cache.
+pub fn validate_incremental(pdb: &PDB) -> Vec<PDBError> {
+    let mut errors = Vec::new();
+    if pdb.model_count() > 1 {
+        let first = pdb.model(0).unwrap();
+        let mut cache = pdb.validation_cache().lock().unwrap();
+        let mut current = HashMap::new();
+        for model in pdb.models().skip(1) {
+            let key = (first.revision(), model.revision());
+            let model_errors = match current.get(&key).or_else(|| cache.get(&key)) {
+                Some(found) => found.clone(),
+                None => validate_model(first, model),
+            };
+            errors.extend(model_errors.iter().cloned());
+            current.insert(key, model_errors);
+        }
+        // Only keep the results of the current Models
+        *cache = current;
+    }
+    errors.append(&mut validate_model_count(pdb));
+    errors.append(&mut validate_matrices(pdb));
+    errors.append(&mut validate_helices(pdb));
+    errors
+}
+
+/// Validate that the number of Models given in the NUMMDL record, if any, matches the number of Models
+fn validate_model_count(pdb: &PDB) -> Vec<PDBError> {
+    match pdb.declared_model_count() {
+        Some(declared) if declared != pdb.model_count() => vec![PDBError::new(
+            ErrorLevel::StrictWarning,
+            "Model count mismatch",
+            &format!(
+                "The NUMMDL record declares {} models, but the PDB contains {} models.",
+                declared,
+                pdb.model_count()
+            ),
+            PDBContext::None,
+        )],
+        _ => Vec::new(),
+    }
+}
+
+/// Validate that all matrix type PDB records (SCALEn, ORIGXn, MTRIXn) are fully specified, so all
+/// rows set, and that their rotation part is non-singular.
+fn validate_matrices(pdb: &PDB) -> Vec<PDBError> {
+    let mut matrices = Vec::new();
+    if pdb.has_scale() {
+        let scale = pdb.scale();
+        matrices.push((scale.valid(), scale.transformation(), "SCALEn".to_string()));
+    }
+    if pdb.has_origx() {
+        let origx = pdb.origx();
+        matrices.push((origx.valid(), origx.transformation(), "ORIGXn".to_string()));
+    }
+    for m in pdb.mtrix() {
+        matrices.push((
+            m.valid(),
+            m.transformation(),
+            format!("MTRIXn Serial number {}", m.serial_number()),
         ));
     }
-    if pdb.has_origx() && !pdb.origx().valid() {
+    matrices
+        .into_iter()
+        .filter(|(valid, _, _)| !valid)
+        .map(|(_, transformation, name)| {
+            if transformation.inverse().is_none() {
+                PDBError::new(
+                    ErrorLevel::InvalidatingError,
+                    "Singular matrix",
+                    &format!(
+                        "The rotation part of {} in the PDB is singular, so it cannot be inverted.",
+                        name
+                    ),
+                    PDBContext::None,
+                )
+            } else {
+                PDBError::new(
+                    ErrorLevel::InvalidatingError,
+                    "Row not set",
+                    &format!("A row was not set for {} in the PDB.", name),
+                    PDBContext::None,
+                )
+            }
+        })
+        .collect()
+}
+
+/// Validate that all helices (HELIX records) start before they end, in the same Chain.
+fn validate_helices(pdb: &PDB) -> Vec<PDBError> {
+    pdb.helices()
+        .filter(|helix| !helix.is_ordered())
+        .map(|helix| {
+            let [chain1, chain2] = helix.chain_ids();
+            let [serial1, serial2] = helix.residue_serial_numbers();
+            let [insertion1, insertion2] = helix.insertion_codes();
+            PDBError::new(
+                ErrorLevel::StrictWarning,
+                "Invalid helix range",
+                &format!(
+                    "Helix {} runs from Residue {}{}{} to Residue {}{}{}, but the start should come before the end in the same Chain.",
+                    helix.id(),
+                    chain1,
+                    serial1,
+                    insertion1.to_string().trim(),
+                    chain2,
+                    serial2,
+                    insertion2.to_string().trim(),
+                ),
+                PDBContext::None,
+            )
+        })
+        .collect()
+}
+
+/// Validate a model by enforcing that it contains the same atoms (with possibly different data) as the first model.
+/// It checks this by matching all atoms (not hetatoms) to see if they correspond (`Atom::correspond`).
+fn validate_model(first: &Model, model: &Model) -> Vec<PDBError> {
+    let mut errors = Vec::new();
+    if model.atom_count() != first.atom_count() {
         errors.push(PDBError::new(
-            ErrorLevel::InvalidatingError,
-            "Row not set",
-            "A row was not set for ORIGXn in the PDB.",
-            Context::None,
+            ErrorLevel::StrictWarning,
+            "Invalid Model",
+            &format!(
+                "Model {} does not have the same amount of atoms as the first model.",
+                model.serial_number()
+            ),
+            PDBContext::None,
         ));
+        return errors;
     }
-    for m in pdb.mtrix() {
-        if !m.valid() {
+    for (standard_atom, current_atom) in first.atoms().zip(model.atoms()) {
+        if !standard_atom.corresponds(current_atom) {
             errors.push(PDBError::new(
-                ErrorLevel::InvalidatingError,
-                "Row not set",
+                ErrorLevel::StrictWarning,
+                "Atoms in Models not corresponding",
                 &format!(
-                    "A row was not set for MTRIXn Serial number {} in the PDB.",
-                    m.serial_number()
+                    "Atom {} in Model {} does not correspond to the respective Atom in the first model.",
+                    current_atom.serial_number(),
+                    model.serial_number()
                 ),
-                Context::None,
+                current_atom
+                    .source_line()
+                    .map_or(PDBContext::None, PDBContext::line_number),
             ));
         }
     }
     errors
 }
 
-/// Validate the models by enforcing that all models should contain the same atoms (with possibly different data).
-/// It checks this by matching all atoms (not hetatoms) for each model to see if they correspond (`Atom::correspond`).
-fn validate_models(pdb: &PDB) -> Vec<PDBError> {
+/// Validate the completeness of the hetero groups (ligands) described by a HET record, for every
+/// Model. The number of Atoms of a hetero group is compared with the number of HETATM records given
+/// in its HET record, and its number of heavy (non hydrogen) Atoms with the number of heavy atoms
+/// in the formula of its FORMUL record (see `Heterogen::formula`), if any. The Atoms named in the
+/// CONECT records (see `PDB::connections`) that are bonded to an Atom of the hetero group but do
+/// not exist are missing as well. Every missing Atom, for example due to poor electron density,
+/// gives a `StrictWarning`, naming its serial number if it is known from the CONECT records. If the
+/// source lines were kept while parsing (see `ReadOptions`) the context gives the line of the hetero group.
+/// Unknown ligands (UNL) and unknown atoms or ions (UNX) have no known formula, so they are not checked.
+pub fn validate_hetatm_completeness(pdb: &PDB) -> Vec<PDBError> {
+    let mut errors = Vec::new();
+    for model in pdb.models() {
+        let serial_numbers = model
+            .atoms_with_hierarchy()
+            .map(|(chain, residue, atom)| {
+                (
+                    atom.serial_number(),
+                    (ResidueId::from_hierarchy(chain, residue), atom.name()),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        // The Atoms named in CONECT records that do not exist, by the Residue of an Atom bonded to them
+        let mut missing_bonded: HashMap<ResidueId, BTreeMap<usize, String>> = HashMap::new();
+        for (a, b) in pdb.connections() {
+            for (missing, present) in [(a, b), (b, a)] {
+                if let (None, Some((residue, name))) =
+                    (serial_numbers.get(&missing), serial_numbers.get(&present))
+                {
+                    missing_bonded
+                        .entry(*residue)
+                        .or_default()
+                        .entry(missing)
+                        .or_insert_with(|| name.clone());
+                }
+            }
+        }
+        for het in pdb
+            .hets()
+            .filter(|het| !reference_tables::is_unknown_ligand(&het.id()))
+        {
+            let id = ResidueId::new(
+                het.chain_id(),
+                het.residue_serial_number(),
+                Some(het.insertion_code()),
+            );
+            let residue = model
+                .all_chains()
+                .filter(|chain| chain.id() == het.chain_id())
+                .flat_map(|chain| chain.residues())
+                .find(|residue| id.matches(het.chain_id(), residue) && residue.id() == het.id());
+            let context = residue
+                .and_then(|residue| residue.source_line())
+                .map_or(PDBContext::None, PDBContext::line_number);
+            let found = residue.map_or(0, |residue| residue.atom_count());
+            let heavy_found = residue.map_or(0, |residue| {
+                residue
+                    .atoms()
+                    .filter(|atom| !["H", "D"].contains(&atom.element().as_str()))
+                    .count()
+            });
+            let heavy_declared = pdb
+                .heterogen(&het.id())
+                .and_then(|heterogen| formula_heavy_atoms(heterogen.formula()));
+            let missing = het
+                .atom_count()
+                .saturating_sub(found)
+                .max(heavy_declared.map_or(0, |declared| declared.saturating_sub(heavy_found)));
+            let bonded = missing_bonded.remove(&id).unwrap_or_default();
+            for (serial_number, name) in &bonded {
+                errors.push(PDBError::new(
+                    ErrorLevel::StrictWarning,
+                    "Incomplete hetero group",
+                    &format!(
+                        "Hetero group {} {} in Model {} misses the Atom with serial number {}, which is bonded to its Atom {} by a CONECT record.",
+                        het.id(),
+                        id,
+                        model.serial_number(),
+                        serial_number,
+                        name
+                    ),
+                    context.clone(),
+                ));
+            }
+            let unnamed = missing.saturating_sub(bonded.len());
+            for index in 1..=unnamed {
+                errors.push(PDBError::new(
+                    ErrorLevel::StrictWarning,
+                    "Incomplete hetero group",
+                    &format!(
+                        "Hetero group {} {} in Model {} has {} Atoms ({} heavy Atoms) while its HET record declares {}{}, so an Atom is missing (missing Atom {} of {}).",
+                        het.id(),
+                        id,
+                        model.serial_number(),
+                        found,
+                        heavy_found,
+                        het.atom_count(),
+                        heavy_declared.map_or_else(String::new, |declared| format!(
+                            " and its formula {} heavy Atoms",
+                            declared
+                        )),
+                        bonded.len() + index,
+                        missing.max(bonded.len())
+                    ),
+                    context.clone(),
+                ));
+            }
+        }
+    }
+    errors
+}
+
+/// Validate that every hetero group (Residue of HETATM records) with more than one Atom has its
+/// bonds given in CONECT records (see `PDB::connections`), as the connectivity of ligands cannot be
+/// derived from their residue name. Waters and ions are not checked. Every hetero group without
+/// any bond gives a `GeneralWarning` per Model.
+pub fn validate_hetatm_has_conect(pdb: &PDB) -> Vec<PDBError> {
+    let bonded = pdb
+        .connections()
+        .flat_map(|(a, b)| [a, b])
+        .collect::<BTreeSet<usize>>();
+    let mut errors = Vec::new();
+    for model in pdb.models() {
+        for chain in model.all_chains() {
+            for residue in chain.residues().filter(|residue| {
+                residue.atom_count() > 1
+                    && residue.atoms().all(|atom| atom.hetero())
+                    && !reference_tables::is_water_residue(&residue.id())
+                    && !reference_tables::is_ion_residue(&residue.id())
+                    && !residue
+                        .atoms()
+                        .any(|atom| bonded.contains(&atom.serial_number()))
+            }) {
+                errors.push(PDBError::new(
+                    ErrorLevel::GeneralWarning,
+                    "Hetero group without CONECT",
+                    &format!(
+                        "Hetero group {} {} in Model {} has no bonds given in CONECT records, so its connectivity is unknown.",
+                        residue.id(),
+                        ResidueId::from_hierarchy(chain, residue),
+                        model.serial_number()
+                    ),
+                    residue
+                        .source_line()
+                        .map_or(PDBContext::None, PDBContext::line_number),
+                ));
+            }
+        }
+    }
+    errors
+}
+
+/// Validate the occupancies of the conformers of every Residue with alternate locations (see
+/// `Residue::conformers`). The conformers are alternatives for the same place, so the sum of their
+/// occupancies cannot be more than one. Every Residue for which it is (allowing for the rounding
+/// of occupancies to two decimals) gives a `StrictWarning` listing the conformers and their occupancies.
+pub fn validate_conformer_occupancies(pdb: &PDB) -> Vec<PDBError> {
+    let mut errors = Vec::new();
+    for model in pdb.models() {
+        for chain in model.all_chains() {
+            for residue in chain.residues() {
+                let conformers = residue.conformers();
+                if conformers.len() < 2 {
+                    continue;
+                }
+                let occupancies = conformers
+                    .iter()
+                    .filter_map(|conformer| Some((conformer, conformer.occupancy()?)))
+                    .collect::<Vec<_>>();
+                let sum = occupancies
+                    .iter()
+                    .map(|(_, occupancy)| occupancy)
+                    .sum::<f64>();
+                if sum > 1.005 {
+                    errors.push(PDBError::new(
+                        ErrorLevel::StrictWarning,
+                        "Conformer occupancies above one",
+                        &format!(
+                            "The occupancies of the conformers of Residue {}{} in Model {} sum to {:.2}, while they should be at most 1.00: {}.",
+                            chain.id(),
+                            residue.serial_number(),
+                            model.serial_number(),
+                            sum,
+                            occupancies
+                                .iter()
+                                .map(|(conformer, occupancy)| format!(
+                                    "{} {} {:.2}",
+                                    conformer.alternate_location(),
+                                    conformer.name(),
+                                    occupancy
+                                ))
+                                .collect::<Vec<String>>()
+                                .join(", ")
+                        ),
+                        residue
+                            .source_line()
+                            .map_or(PDBContext::None, PDBContext::line_number),
+                    ));
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Validate that the Residues of every cis peptide bond (CISPEP record, see `PDB::cis_peptides`)
+/// exist in the Model the record refers to, with the same residue names. A model number of 0 refers
+/// to the first Model, as used by files with a single Model. A missing Model or Residue gives a
+/// `StrictWarning` per record.
+pub fn validate_cis_peptides(pdb: &PDB) -> Vec<PDBError> {
+    let mut errors = Vec::new();
+    for cis_peptide in pdb.cis_peptides() {
+        let number = cis_peptide.model_number();
+        let model = pdb
+            .models()
+            .find(|model| model.serial_number() == number)
+            .or_else(|| if number == 0 { pdb.model(0) } else { None });
+        let model = match model {
+            Some(model) => model,
+            None => {
+                errors.push(PDBError::new(
+                    ErrorLevel::StrictWarning,
+                    "CISPEP model not found",
+                    &format!(
+                        "The CISPEP record {} refers to Model {}, which is not present in the coordinates.",
+                        cis_peptide.serial_number(),
+                        number
+                    ),
+                    PDBContext::None,
+                ));
+                continue;
+            }
+        };
+        for index in 0..2 {
+            let (name, chain_id, serial_number, insertion_code) = (
+                cis_peptide.residue_names()[index],
+                cis_peptide.chain_ids()[index],
+                cis_peptide.residue_serial_numbers()[index],
+                insertion_code(cis_peptide.insertion_codes()[index]),
+            );
+            let found = model.all_chains().any(|chain| {
+                chain.id() == chain_id
+                    && chain.residues().any(|residue| {
+                        residue.numbering() == (serial_number, insertion_code)
+                            && residue.id() == name
+                    })
+            });
+            if !found {
+                errors.push(PDBError::new(
+                    ErrorLevel::StrictWarning,
+                    "CISPEP residue not found",
+                    &format!(
+                        "The CISPEP record {} refers to Residue {} {}{}{} in Model {}, which is not present in the coordinates.",
+                        cis_peptide.serial_number(),
+                        name,
+                        chain_id,
+                        serial_number,
+                        insertion_code.map_or_else(String::new, String::from),
+                        model.serial_number()
+                    ),
+                    PDBContext::None,
+                ));
+            }
+        }
+    }
+    errors
+}
+
+/// Validate that the Residues of every Chain (Normal only) are ordered by serial number, so never
+/// numbered lower than the Residue before them (insertion codes aside), as the torsion angles, the
+/// gap report and the placement of the TER records follow the stored order. A Chain stored in
+/// reverse (see `Chain::is_reversed`) gives a `StrictWarning` "Chain reversed", which can be
+/// repaired with `Chain::reverse_residues`. Any other Chain out of order gives a `StrictWarning`
+/// "Residue numbering decreasing" for the first Residue out of place, which can be repaired with
+/// `Chain::sort_residues_by_number`. Every Model is checked.
+pub fn validate_residue_numbering(pdb: &PDB) -> Vec<PDBError> {
     let mut errors = Vec::new();
-    let total_atoms = pdb.model(0).unwrap().atom_count();
-    for model in pdb.models().skip(1) {
-        if model.atom_count() != total_atoms {
+    for model in pdb.models() {
+        for chain in model.chains() {
+            let index = match chain.first_unordered_residue() {
+                Some(index) => index,
+                None => continue,
+            };
+            let context = chain
+                .residue(index)
+                .and_then(|residue| residue.atom(0))
+                .and_then(|atom| atom.source_line())
+                .map_or(PDBContext::None, PDBContext::line_number);
+            errors.push(if chain.is_reversed() {
+                PDBError::new(
+                    ErrorLevel::StrictWarning,
+                    "Chain reversed",
+                    &format!(
+                        "The Residues of Chain {} in Model {} are numbered from high to low, the atoms look to be stored in reverse. Use `Chain::reverse_residues` to restore the order.",
+                        chain.id(),
+                        model.serial_number()
+                    ),
+                    context,
+                )
+            } else {
+                let (previous, current) = (
+                    chain.residue(index - 1).unwrap(),
+                    chain.residue(index).unwrap(),
+                );
+                PDBError::new(
+                    ErrorLevel::StrictWarning,
+                    "Residue numbering decreasing",
+                    &format!(
+                        "Residue {} {} (position {}) of Chain {} in Model {} follows Residue {} {}, so the residue numbers decrease. Use `Chain::sort_residues_by_number` to restore the order.",
+                        current.id(),
+                        current.serial_number(),
+                        index + 1,
+                        chain.id(),
+                        model.serial_number(),
+                        previous.id(),
+                        previous.serial_number()
+                    ),
+                    context,
+                )
+            });
+        }
+    }
+    errors
+}
+
+/// Validate that no water Residue shares its serial number with another Residue of a Chain with
+/// the same id (Normal or Hetero), as the Residues cannot be told apart in that case, for example
+/// by the HET, LINK or SITE records of other programs. Every Chain with such waters gives a
+/// `LooseWarning` per Model, listing the colliding serial numbers. Use `PDB::renumber_solvent` to
+/// give the waters unique serial numbers.
+pub fn validate_solvent_numbering(pdb: &PDB) -> Vec<PDBError> {
+    let mut errors = Vec::new();
+    for model in pdb.models() {
+        let mut residues: BTreeMap<char, Vec<(usize, bool)>> = BTreeMap::new();
+        for chain in model.all_chains() {
+            residues
+                .entry(chain.id())
+                .or_default()
+                .extend(chain.residues().map(|residue| {
+                    (
+                        residue.serial_number(),
+                        reference_tables::is_water_residue(&residue.id()),
+                    )
+                }));
+        }
+        for (chain_id, residues) in residues {
+            let others = residues
+                .iter()
+                .filter(|(_, water)| !water)
+                .map(|(serial_number, _)| *serial_number)
+                .collect::<BTreeSet<usize>>();
+            let mut waters = BTreeSet::new();
+            let mut collisions = BTreeSet::new();
+            for (serial_number, _) in residues.iter().filter(|(_, water)| *water) {
+                if others.contains(serial_number) || !waters.insert(*serial_number) {
+                    collisions.insert(*serial_number);
+                }
+            }
+            if !collisions.is_empty() {
+                errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Water residue number collision",
+                    &format!(
+                        "The waters in Chain {} in Model {} share the serial numbers {} with other Residues. Use `renumber_solvent` to give the waters unique serial numbers.",
+                        chain_id,
+                        model.serial_number(),
+                        collisions
+                            .iter()
+                            .map(|n| n.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                    PDBContext::None,
+                ));
+            }
+        }
+    }
+    errors
+}
+
+/// The maximal number of Atoms named in a single error message
+const MAX_REPORTED_ATOMS: usize = 10;
+
+/// Validate the anisotropic temperature factors (ANISOU records) of every Model. After anisotropic
+/// refinement essentially all Atoms have anisotropic factors, otherwise none have. So a Model in
+/// which the fraction of Atoms (Normal and Hetero) with anisotropic factors is strictly between the
+/// `low` and `high` fractions, for example 3% of the Atoms, likely results from a broken merge of
+/// files and gives a `LooseWarning`. Furthermore the U matrix of every Atom with anisotropic factors
+/// has to be positive semi-definite, as negative eigenvalues are physically impossible. The Atoms
+/// that violate this give a `StrictWarning` per Model. Every message names at most ten Atoms.
+/// ## Arguments
+/// * `low` - the fraction of Atoms with anisotropic factors at or below which no warning is given, for example 0.05
+/// * `high` - the fraction of Atoms with anisotropic factors at or above which no warning is given, for example 0.95
+pub fn validate_anisotropic_factors(pdb: &PDB, low: f64, high: f64) -> Vec<PDBError> {
+    let mut errors = Vec::new();
+    for model in pdb.models() {
+        let mut total = 0;
+        let mut with = Vec::new();
+        let mut without = Vec::new();
+        let mut invalid = Vec::new();
+        for chain in model.all_chains() {
+            for residue in chain.residues() {
+                for atom in residue.atoms() {
+                    total += 1;
+                    let id = || AtomId::from_hierarchy(model, chain, residue, atom);
+                    match atom.anisotropic_u() {
+                        Some(u) => {
+                            with.push(id());
+                            if !positive_semi_definite(u) {
+                                invalid.push((id(), atom.source_line()));
+                            }
+                        }
+                        None => without.push(id()),
+                    }
+                }
+            }
+        }
+        if total == 0 {
+            continue;
+        }
+        let fraction = with.len() as f64 / total as f64;
+        if fraction > low && fraction < high {
+            // Name the Atoms of the minority, as those are most likely the odd ones
+            let (kind, atoms) = if with.len() <= without.len() {
+                ("with", &with)
+            } else {
+                ("without", &without)
+            };
+            errors.push(PDBError::new(
+                ErrorLevel::LooseWarning,
+                "Incomplete anisotropic factors",
+                &format!(
+                    "{} of the {} Atoms ({:.1}%) in Model {} have anisotropic temperature factors, while either almost all or almost none are expected. Atoms {} them: {}.",
+                    with.len(),
+                    total,
+                    fraction * 100.0,
+                    model.serial_number(),
+                    kind,
+                    list_atoms(atoms.iter())
+                ),
+                PDBContext::None,
+            ));
+        }
+        if !invalid.is_empty() {
             errors.push(PDBError::new(
                 ErrorLevel::StrictWarning,
-                "Invalid Model",
+                "Invalid anisotropic factors",
                 &format!(
-                    "Model {} does not have the same amount of atoms as the first model.",
-                    model.serial_number()
+                    "The anisotropic temperature factors of {} Atoms in Model {} are not positive semi-definite, which is physically impossible: {}.",
+                    invalid.len(),
+                    model.serial_number(),
+                    list_atoms(invalid.iter().map(|(id, _)| id))
                 ),
-                Context::None,
+                invalid[0].1.map_or(PDBContext::None, PDBContext::line_number),
             ));
-            continue;
         }
-        for index in 0..model.atom_count() {
-            let current_atom = model.atom(index).unwrap();
-            let standard_atom = pdb.model(0).unwrap().atom(index).unwrap();
-            if !standard_atom.corresponds(current_atom) {
+    }
+    errors
+}
+
+/// Validate the distribution of the B-factors of every Chain (Normal and Hetero) in every Model.
+/// Every finding gives a `LooseWarning` per Chain, naming at most ten Atoms:
+/// * "Identical B-factors" if all Atoms of the Chain (at least two) have the same B-factor, a sign
+///   that the column was zeroed or used for something else.
+/// * "Negative B-factors" for the Atoms with a negative B-factor, which is physically impossible.
+/// * "B-factor outliers" for the Atoms with a B-factor more than `thresholds.outlier_deviations()`
+///   standard deviations above the mean of the other Atoms in their neighbourhood, the Residues
+///   within `thresholds.neighbourhood()` positions in the Chain, as these are possibly mismodelled.
+///
+/// The distributions themselves are given by `b_factor_statistics`.
+pub fn validate_b_factors(pdb: &PDB, thresholds: &BFactorThresholds) -> Vec<PDBError> {
+    let mut errors = Vec::new();
+    for model in pdb.models() {
+        for chain in model.all_chains() {
+            let statistics = BFactorStatistics::of(model, chain);
+            let first_line = || {
+                chain
+                    .atoms()
+                    .next()
+                    .and_then(Atom::source_line)
+                    .map_or(PDBContext::None, PDBContext::line_number)
+            };
+            if statistics.all_identical() {
                 errors.push(PDBError::new(
-                    ErrorLevel::StrictWarning,
-                    "Atoms in Models not corresponding",
+                    ErrorLevel::LooseWarning,
+                    "Identical B-factors",
                     &format!(
-                        "Atom {} in Model {} does not correspond to the respective Atom in the first model.",
-                        current_atom.serial_number(),
-                        model.serial_number()
+                        "All {} Atoms of Chain {} in Model {} have a B-factor of {}, the column was possibly zeroed or used for something else.",
+                        statistics.count(),
+                        chain.id(),
+                        model.serial_number(),
+                        statistics.b_factors()[0]
                     ),
-                    Context::None,
+                    first_line(),
+                ));
+                continue;
+            }
+
+            let residues = chain.residues().collect::<Vec<&Residue>>();
+            let mut negative = Vec::new();
+            let mut outliers = Vec::new();
+            for (index, residue) in residues.iter().enumerate() {
+                let window = &residues[index.saturating_sub(thresholds.neighbourhood())
+                    ..(index + thresholds.neighbourhood() + 1).min(residues.len())];
+                for atom in residue.atoms() {
+                    let id = || AtomId::from_hierarchy(model, chain, residue, atom);
+                    if atom.b_factor() < 0.0 {
+                        negative.push((id(), atom.source_line()));
+                    }
+                    let others = window
+                        .iter()
+                        .flat_map(|r| r.atoms())
+                        .filter(|other| !std::ptr::eq(*other, atom))
+                        .map(Atom::b_factor)
+                        .collect::<Vec<f64>>();
+                    if others.len() < 2 {
+                        continue;
+                    }
+                    let (mean, deviation) = b_factors::mean_and_deviation(&others);
+                    if deviation > 0.0
+                        && atom.b_factor() > mean + thresholds.outlier_deviations() * deviation
+                    {
+                        outliers.push((id(), atom.source_line()));
+                    }
+                }
+            }
+            if !negative.is_empty() {
+                errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "Negative B-factors",
+                    &format!(
+                        "{} Atoms of Chain {} in Model {} have a negative B-factor, which is physically impossible: {}.",
+                        negative.len(),
+                        chain.id(),
+                        model.serial_number(),
+                        list_atoms(negative.iter().map(|(id, _)| id))
+                    ),
+                    negative[0].1.map_or(PDBContext::None, PDBContext::line_number),
+                ));
+            }
+            if !outliers.is_empty() {
+                errors.push(PDBError::new(
+                    ErrorLevel::LooseWarning,
+                    "B-factor outliers",
+                    &format!(
+                        "{} Atoms of Chain {} in Model {} have a B-factor more than {} standard deviations above the mean of the Atoms within {} Residues, they are possibly mismodelled: {}.",
+                        outliers.len(),
+                        chain.id(),
+                        model.serial_number(),
+                        thresholds.outlier_deviations(),
+                        thresholds.neighbourhood(),
+                        list_atoms(outliers.iter().map(|(id, _)| id))
+                    ),
+                    outliers[0].1.map_or(PDBContext::None, PDBContext::line_number),
                 ));
             }
         }
     }
     errors
 }
+
+/// Convert the insertion code of a record to the one used by Residues, where a blank is `None`
+fn insertion_code(code: char) -> Option<char> {
+    if code == ' ' {
+        None
+    } else {
+        Some(code)
+    }
+}
+
+/// The number of heavy (non hydrogen) atoms of a single hetero group in the given formula of a
+/// FORMUL record, like `2(C10 H15 N5 O10 P2)` giving 27. It is `None` if the formula cannot be read.
+fn formula_heavy_atoms(formula: &str) -> Option<usize> {
+    let formula = match formula.trim().split_once('(') {
+        Some((_, group)) => group.strip_suffix(')')?,
+        None => formula,
+    };
+    let mut count = 0;
+    for part in formula.split_whitespace() {
+        let element = part.trim_end_matches(|c: char| c.is_ascii_digit());
+        if element.is_empty() || element.contains(['+', '-']) {
+            // A charge, like the 2+ of MG 2+
+            continue;
+        }
+        if !element.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let number = match &part[element.len()..] {
+            "" => 1,
+            number => number.parse::<usize>().ok()?,
+        };
+        if !["H", "D"].contains(&element) {
+            count += number;
+        }
+    }
+    Some(count)
+}
+
+/// List the given Atoms, naming at most `MAX_REPORTED_ATOMS` of them
+fn list_atoms<'a>(atoms: impl ExactSizeIterator<Item = &'a AtomId>) -> String {
+    let count = atoms.len();
+    let mut list = atoms
+        .take(MAX_REPORTED_ATOMS)
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    if count > MAX_REPORTED_ATOMS {
+        list.push_str(&format!(" and {} more", count - MAX_REPORTED_ATOMS));
+    }
+    list
+}
+
+/// Check if the U matrix given as [U11, U22, U33, U12, U13, U23] is positive semi-definite, by
+/// checking that all its principal minors are not negative. A small tolerance allows for the
+/// rounding of the factors in the PDB format.
+fn positive_semi_definite(u: [f64; 6]) -> bool {
+    const TOLERANCE: f64 = 1e-10;
+    let [u11, u22, u33, u12, u13, u23] = u;
+    let minors = [
+        u11,
+        u22,
+        u33,
+        u11 * u22 - u12 * u12,
+        u11 * u33 - u13 * u13,
+        u22 * u33 - u23 * u23,
+        u11 * (u22 * u33 - u23 * u23) - u12 * (u12 * u33 - u23 * u13)
+            + u13 * (u12 * u23 - u22 * u13),
+    ];
+    minors.iter().all(|minor| *minor >= -TOLERANCE)
+}
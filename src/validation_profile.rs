@@ -0,0 +1,229 @@
+use crate::error::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A check done while parsing or validating a PDB, see `ValidationProfile`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ValidationCheck {
+    /// The Atoms of every Model correspond to the Atoms of the first Model, done by `validate_pdb`
+    ModelCorrespondence,
+    /// The number of Models given in the NUMMDL record matches the number of Models, done by `validate_pdb`
+    ModelCount,
+    /// All rows of the matrix type records (SCALEn, ORIGXn, MTRIXn) are set, done by `validate_pdb`
+    MatrixRows,
+    /// The helices (see `PDB::helices`) start before they end, in the same Chain, done by `validate_pdb`
+    HelixRanges,
+    /// The hetero groups have all Atoms declared in their HET record, see `validate_hetatm_completeness`
+    HetatmCompleteness,
+    /// The hetero groups with more than one Atom have bonds given in CONECT records, see
+    /// `validate_hetatm_has_conect`
+    HetatmConnections,
+    /// The occupancies of the conformers of a Residue sum to at most one, see `validate_conformer_occupancies`
+    ConformerOccupancies,
+    /// The anisotropic temperature factors are given for almost all or almost no Atoms and are
+    /// physically possible, see `validate_anisotropic_factors` (with 0.05 and 0.95 as fractions)
+    AnisotropicFactors,
+    /// The Residues of the cis peptides (CISPEP records) exist in their Model, see `validate_cis_peptides`
+    CisPeptideResidues,
+    /// No water Residue shares its serial number with another Residue of the same Chain, see
+    /// `validate_solvent_numbering`
+    SolventNumbering,
+    /// The Residues of every Chain (Normal only) are ordered by serial number, see
+    /// `validate_residue_numbering`
+    ResidueNumbering,
+    /// The B-factors of every Chain are not all identical, not negative and without outliers in
+    /// their neighbourhood, see `validate_b_factors` (with the default `BFactorThresholds`)
+    BFactorDistribution,
+    /// The serial numbers of the ATOM, HETATM and TER records increase, checked while parsing
+    SerialOrder,
+    /// The record counts in the MASTER record are correct, checked while parsing
+    MasterChecksum,
+    /// The number of residues in the SEQRES records matches their stated length, checked while parsing
+    SeqresLength,
+    /// The REMARK records have a valid remark-type-number and are not too long, checked while parsing
+    RemarkFormat,
+    /// The END, MASTER and TER records are present, checked while parsing (see
+    /// `ReadOptions::set_warn_missing_records`)
+    MissingRecords,
+}
+
+impl ValidationCheck {
+    /// All checks, in the order they are listed
+    pub const ALL: [ValidationCheck; 17] = [
+        ValidationCheck::ModelCorrespondence,
+        ValidationCheck::ModelCount,
+        ValidationCheck::MatrixRows,
+        ValidationCheck::HelixRanges,
+        ValidationCheck::HetatmCompleteness,
+        ValidationCheck::HetatmConnections,
+        ValidationCheck::ConformerOccupancies,
+        ValidationCheck::AnisotropicFactors,
+        ValidationCheck::CisPeptideResidues,
+        ValidationCheck::SolventNumbering,
+        ValidationCheck::ResidueNumbering,
+        ValidationCheck::BFactorDistribution,
+        ValidationCheck::SerialOrder,
+        ValidationCheck::MasterChecksum,
+        ValidationCheck::SeqresLength,
+        ValidationCheck::RemarkFormat,
+        ValidationCheck::MissingRecords,
+    ];
+
+    /// Get the check that gave the given error, if it was given by one of the checks
+    pub fn of(error: &PDBError) -> Option<ValidationCheck> {
+        match error.short_description() {
+            "Invalid Model" | "Atoms in Models not corresponding" => {
+                Some(ValidationCheck::ModelCorrespondence)
+            }
+            "Model count mismatch" => Some(ValidationCheck::ModelCount),
+            "Row not set" => Some(ValidationCheck::MatrixRows),
+            "Invalid helix range" => Some(ValidationCheck::HelixRanges),
+            "Incomplete hetero group" => Some(ValidationCheck::HetatmCompleteness),
+            "Hetero group without CONECT" => Some(ValidationCheck::HetatmConnections),
+            "Conformer occupancies above one" => Some(ValidationCheck::ConformerOccupancies),
+            "Incomplete anisotropic factors" | "Invalid anisotropic factors" => {
+                Some(ValidationCheck::AnisotropicFactors)
+            }
+            "CISPEP model not found" | "CISPEP residue not found" => {
+                Some(ValidationCheck::CisPeptideResidues)
+            }
+            "Water residue number collision" => Some(ValidationCheck::SolventNumbering),
+            "Residue numbering decreasing" | "Chain reversed" => {
+                Some(ValidationCheck::ResidueNumbering)
+            }
+            "Identical B-factors" | "Negative B-factors" | "B-factor outliers" => {
+                Some(ValidationCheck::BFactorDistribution)
+            }
+            "Atom serial number out of order" | "TER serial number out of order" => {
+                Some(ValidationCheck::SerialOrder)
+            }
+            "MASTER checksum failed" => Some(ValidationCheck::MasterChecksum),
+            "SEQRES length incorrect" => Some(ValidationCheck::SeqresLength),
+            "Remark type number invalid" | "Remark too long" => Some(ValidationCheck::RemarkFormat),
+            "Missing END record" | "Missing MASTER record" | "Missing TER record" => {
+                Some(ValidationCheck::MissingRecords)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A predefined `ValidationProfile`, see `ValidationProfile::preset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationPreset {
+    /// All checks at their own level, for preparing a structure for deposition
+    Deposition,
+    /// The checks on the structure itself, leaving out the bookkeeping records (MASTER, SEQRES,
+    /// REMARK and missing records), with an out of order serial number as a `GeneralWarning`, for
+    /// example for MD or modelling output
+    Analysis,
+    /// Only the checks that find invalid data: corresponding Models, the declared number of
+    /// Models, fully set matrices and ordered helices
+    Minimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// The checks to do when validating a PDB and the level at which they report, used by
+/// `validate_with` and by the parser (see `ReadOptions::set_validation_profile`). Errors that are
+/// not given by one of the checks (see `ValidationCheck`), like unreadable numbers, are always
+/// reported at their own level.
+pub struct ValidationProfile {
+    /// The checks to do
+    checks: BTreeSet<ValidationCheck>,
+    /// The level to report the errors of a check at, instead of their own level
+    levels: BTreeMap<ValidationCheck, ErrorLevel>,
+}
+
+impl ValidationProfile {
+    /// Create a new profile without any checks
+    pub fn new() -> ValidationProfile {
+        ValidationProfile::default()
+    }
+
+    /// Create a new profile for the given preset, see `ValidationPreset` for what each preset does
+    pub fn preset(preset: ValidationPreset) -> ValidationProfile {
+        let mut profile = ValidationProfile::new();
+        match preset {
+            ValidationPreset::Deposition => {
+                profile.checks.extend(ValidationCheck::ALL);
+            }
+            ValidationPreset::Analysis => {
+                profile.checks.extend([
+                    ValidationCheck::ModelCorrespondence,
+                    ValidationCheck::ModelCount,
+                    ValidationCheck::MatrixRows,
+                    ValidationCheck::HelixRanges,
+                    ValidationCheck::HetatmCompleteness,
+                    ValidationCheck::ConformerOccupancies,
+                    ValidationCheck::AnisotropicFactors,
+                    ValidationCheck::CisPeptideResidues,
+                    ValidationCheck::SolventNumbering,
+                    ValidationCheck::ResidueNumbering,
+                    ValidationCheck::SerialOrder,
+                ]);
+                profile
+                    .levels
+                    .insert(ValidationCheck::SerialOrder, ErrorLevel::GeneralWarning);
+            }
+            ValidationPreset::Minimal => {
+                profile.checks.extend([
+                    ValidationCheck::ModelCorrespondence,
+                    ValidationCheck::ModelCount,
+                    ValidationCheck::MatrixRows,
+                    ValidationCheck::HelixRanges,
+                ]);
+            }
+        }
+        profile
+    }
+
+    /// Get the checks done, in the order of `ValidationCheck::ALL`
+    pub fn checks(&self) -> impl Iterator<Item = ValidationCheck> + '_ {
+        self.checks.iter().copied()
+    }
+
+    /// Get if the given check is done
+    pub fn runs(&self, check: ValidationCheck) -> bool {
+        self.checks.contains(&check)
+    }
+
+    /// Add a check to do
+    pub fn add_check(&mut self, check: ValidationCheck) {
+        self.checks.insert(check);
+    }
+
+    /// Remove a check, so it is not done
+    pub fn remove_check(&mut self, check: ValidationCheck) {
+        self.checks.remove(&check);
+    }
+
+    /// Get the level the errors of the given check are reported at, if it is changed by this profile
+    pub fn level(&self, check: ValidationCheck) -> Option<ErrorLevel> {
+        self.levels.get(&check).copied()
+    }
+
+    /// Set the level the errors of the given check should be reported at, instead of their own level
+    pub fn set_level(&mut self, check: ValidationCheck, level: ErrorLevel) {
+        self.levels.insert(check, level);
+    }
+
+    /// Remove the errors of the checks not done and change the level of the errors of the checks
+    /// with a different level in this profile
+    pub(crate) fn apply(&self, errors: Vec<PDBError>) -> Vec<PDBError> {
+        errors
+            .into_iter()
+            .filter_map(|error| match ValidationCheck::of(&error) {
+                None => Some(error),
+                Some(check) if !self.runs(check) => None,
+                Some(check) => Some(match self.level(check) {
+                    Some(level) => PDBError::new(
+                        level,
+                        error.short_description(),
+                        error.long_description(),
+                        error.context().clone(),
+                    ),
+                    None => error,
+                }),
+            })
+            .collect()
+    }
+}
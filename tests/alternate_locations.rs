@@ -0,0 +1,118 @@
+use pdbtbx::*;
+use std::fs;
+
+const STRUCTURE: &str = "\
+ATOM      1  N   SER A   1       0.000   1.400   0.000  1.00 10.00           N
+ATOM      2  CA  SER A   1       0.000   0.000   0.000  1.00 10.00           C
+ATOM      3  C   SER A   1       1.500   0.000   0.000  1.00 10.00           C
+ATOM      4  O   SER A   1       2.100   1.000   0.000  1.00 10.00           O
+ATOM      5  CB ASER A   1      -0.700  -0.700   1.200  0.60 12.00           C
+ATOM      6  OG ASER A   1      -2.000  -0.300   1.400  0.60 12.00           O
+ATOM      7  CB BSER A   1      -0.700  -0.700  -1.200  0.40 14.00           C
+ATOM      8  OG BSER A   1      -2.000  -0.300  -1.400  0.40 14.00           O
+ATOM      9  N  AGLY A   2       2.100  -1.200   0.000  0.60 10.00           N
+ATOM     10  CA AGLY A   2       3.500  -1.400   0.000  0.60 10.00           C
+ATOM     11  N  BGLY A   2       2.100  -1.200   0.300  0.40 10.00           N
+ATOM     12  CA BGLY A   2       3.500  -1.400   0.400  0.40 10.00           C
+END
+";
+
+fn structure() -> PDB {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/alternate_locations.pdb", STRUCTURE).unwrap();
+    read_pdb("dump/alternate_locations.pdb").unwrap().0
+}
+
+#[test]
+fn split_by_altloc() {
+    let pdb = structure();
+    assert_eq!(pdb.alternate_locations(), vec!['A', 'B']);
+    let split = pdb.split_by_altloc();
+    assert_eq!(split.len(), 2);
+    assert_eq!(split[&'A'].total_atom_count(), 8);
+    assert_eq!(split[&'B'].total_atom_count(), 8);
+    assert!(split[&'A']
+        .all_atoms()
+        .all(|a| a.alternate_location() != 'B'));
+
+    // A residue completely made up of two conformers is divided over the two PDBs
+    let glycine_atoms = |pdb: &PDB| {
+        pdb.residues()
+            .find(|r| r.id() == "GLY")
+            .map_or(0, |r| r.atom_count())
+    };
+    assert_eq!(
+        glycine_atoms(&split[&'A']) + glycine_atoms(&split[&'B']),
+        glycine_atoms(&pdb)
+    );
+}
+
+#[test]
+fn select_altloc() {
+    let pdb = structure();
+    let selected = pdb.select_altloc('B');
+    assert_eq!(selected.total_atom_count(), 8);
+    assert_eq!(selected.residue_count(), 2);
+    // Selecting an identifier that is not present leaves only the atoms without alternate locations
+    let none = pdb.select_altloc('C');
+    assert_eq!(none.total_atom_count(), 4);
+    assert_eq!(none.residue_count(), 1);
+}
+
+#[test]
+fn alternate_locations_are_saved() {
+    let pdb = structure();
+    save_pdb(&pdb, "dump/alternate_locations_saved.pdb").unwrap();
+    let (saved, _) = read_pdb("dump/alternate_locations_saved.pdb").unwrap();
+    assert_eq!(saved.alternate_locations(), vec!['A', 'B']);
+    assert_eq!(saved, pdb);
+}
+
+#[test]
+fn conformations() {
+    let pdb = structure();
+    assert!(pdb.has_multiple_conformations());
+    assert_eq!(pdb.conformation_count(), 2);
+    let model = pdb.model(0).unwrap();
+    assert_eq!(model.atoms_with_altloc().count(), 8);
+    assert_eq!(
+        model.altloc_characters().into_iter().collect::<Vec<char>>(),
+        vec!['A', 'B']
+    );
+
+    let (single, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert!(!single.has_multiple_conformations());
+    assert_eq!(single.conformation_count(), 0);
+    assert!(single.model(0).unwrap().altloc_characters().is_empty());
+}
+
+#[test]
+fn remove_alternate_conformers() {
+    let mut pdb = structure();
+    for atom in pdb.atoms_mut().filter(|atom| atom.serial_number() == 12) {
+        atom.set_occupancy(0.9).unwrap();
+    }
+    let original = pdb.clone();
+    assert_eq!(pdb.remove_alternate_conformers(), 4);
+    assert_eq!(pdb.remove_alternate_conformers(), 0);
+    assert!(!pdb.has_multiple_conformations());
+    let kept = pdb
+        .atoms()
+        .map(|atom| (atom.name(), atom.z()))
+        .collect::<Vec<_>>();
+    // Every atom is chosen on its own, so the glycine combines the N of A with the CA of B
+    assert_eq!(
+        kept,
+        vec![
+            ("N".to_string(), 0.0),
+            ("CA".to_string(), 0.0),
+            ("C".to_string(), 0.0),
+            ("O".to_string(), 0.0),
+            ("CB".to_string(), 1.2),
+            ("OG".to_string(), 1.4),
+            ("N".to_string(), 0.0),
+            ("CA".to_string(), 0.4),
+        ]
+    );
+    assert_eq!(original.all_atoms().count(), 12);
+}
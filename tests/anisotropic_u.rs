@@ -0,0 +1,95 @@
+use pdbtbx::*;
+use std::fs;
+
+const FIXTURE: &str = "\
+ATOM      1  N   SER A   1      -4.231  13.380  28.143  1.00 40.17           N
+ANISOU    1  N   SER A   1     3181   3287   2592  -1211  -1085   1043       N
+ATOM      2  CA  SER A   1      -3.128  12.436  28.428  1.00 38.61           C
+ANISOU    2  CA  SER A   1     3005   3176   2550  -1083   -978    985       C
+TER       3      SER A   1
+END
+";
+
+fn anisou_factors(filename: &str) -> Vec<String> {
+    fs::read_to_string(filename)
+        .unwrap()
+        .lines()
+        .filter(|line| line.starts_with("ANISOU"))
+        .map(|line| line[28..70].to_string())
+        .collect()
+}
+
+#[test]
+fn round_trip() {
+    fs::write("dump/anisotropic_u.pdb", FIXTURE).unwrap();
+    let (pdb, errors) = read_pdb("dump/anisotropic_u.pdb").unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+    let atom = pdb.atom(0).unwrap();
+    assert_eq!(
+        atom.anisotropic_u(),
+        Some([0.3181, 0.3287, 0.2592, -0.1211, -0.1085, 0.1043])
+    );
+    assert_eq!(
+        atom.anisotropic_temperature_factors(),
+        Some([[0.3181, 0.3287, 0.2592], [-0.1211, -0.1085, 0.1043]])
+    );
+
+    save_pdb(&pdb, "dump/anisotropic_u_saved.pdb").unwrap();
+    assert_eq!(
+        anisou_factors("dump/anisotropic_u.pdb"),
+        anisou_factors("dump/anisotropic_u_saved.pdb")
+    );
+    let (reread, errors) = read_pdb("dump/anisotropic_u_saved.pdb").unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+    for (a, b) in pdb.atoms().zip(reread.atoms()) {
+        assert_eq!(a.anisotropic_u(), b.anisotropic_u());
+    }
+}
+
+#[test]
+fn matrix() {
+    let mut atom = Atom::new(
+        1,
+        [' ', 'C', 'A', ' '],
+        0.0,
+        0.0,
+        0.0,
+        1.0,
+        0.0,
+        [' ', 'C'],
+        0,
+    )
+    .unwrap();
+    assert_eq!(atom.anisotropic_u_matrix(), None);
+    atom.set_anisotropic_u([0.1, 0.2, 0.3, 0.01, 0.02, 0.03]);
+    assert_eq!(
+        atom.anisotropic_u_matrix(),
+        Some([[0.1, 0.01, 0.02], [0.01, 0.2, 0.03], [0.02, 0.03, 0.3]])
+    );
+
+    // The off-diagonal elements are averaged to keep the tensor symmetric
+    atom.set_anisotropic_u_matrix([[0.1, 0.01, 0.0], [0.03, 0.2, 0.0], [0.0, 0.0, 0.3]]);
+    assert_eq!(atom.anisotropic_u(), Some([0.1, 0.2, 0.3, 0.02, 0.0, 0.0]));
+}
+
+#[test]
+fn rotate() {
+    let mut atom = Atom::new(
+        1,
+        [' ', 'C', 'A', ' '],
+        1.0,
+        0.0,
+        0.0,
+        1.0,
+        0.0,
+        [' ', 'C'],
+        0,
+    )
+    .unwrap();
+    atom.set_anisotropic_u([0.1, 0.2, 0.3, 0.0, 0.0, 0.0]);
+    atom.apply_transformation(&TransformationMatrix::rotation_z(90.0));
+    let u = atom.anisotropic_u().unwrap();
+    for (found, expected) in u.iter().zip([0.2, 0.1, 0.3, 0.0, 0.0, 0.0]) {
+        assert!((found - expected).abs() < 1e-9, "{:?}", u);
+    }
+}
@@ -0,0 +1,60 @@
+use pdbtbx::*;
+use std::fs;
+
+const STRUCTURE: &str = "\
+ATOM      1  N   ALA A   1       0.000   0.000   0.000  1.00 10.00           N
+ANISOU    1  N   ALA A   1     1000   1000   1000      0      0      0       N
+ATOM      2  CA  ALA A   1       1.458   0.000   0.000  1.00 10.00           C
+ANISOU    2  CA  ALA A   1     1000   1000   1000   2000      0      0       C
+ATOM      3  C   ALA A   1       2.009   1.420   0.000  1.00 10.00           C
+ATOM      4  O   ALA A   1       1.246   2.390   0.000  1.00 10.00           O
+END
+";
+
+#[test]
+fn complete_factors() {
+    let (pdb, _) = read_pdb("example-pdbs/pTLS-6484.pdb").unwrap();
+    assert!(validate_anisotropic_factors(&pdb, 0.05, 0.95).is_empty());
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert!(validate_anisotropic_factors(&pdb, 0.05, 0.95).is_empty());
+}
+
+#[test]
+fn incomplete_and_invalid_factors() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/anisotropic.pdb", STRUCTURE).unwrap();
+    let mut options = ReadOptions::new();
+    options.set_keep_source_lines(true);
+    let (pdb, _) = parse_with_options("dump/anisotropic.pdb", &options).unwrap();
+    let errors = validate_anisotropic_factors(&pdb, 0.05, 0.95);
+    assert_eq!(errors.len(), 2);
+
+    assert_eq!(errors[0].level(), ErrorLevel::LooseWarning);
+    assert!(errors[0]
+        .long_description()
+        .starts_with("2 of the 4 Atoms (50.0%)"));
+    assert!(errors[0]
+        .long_description()
+        .ends_with("Atoms with them: 0/A/1/N, 0/A/1/CA."));
+
+    // The CA has a U12 larger than U11 and U22, which gives a negative eigenvalue
+    assert_eq!(errors[1].level(), ErrorLevel::StrictWarning);
+    assert!(errors[1].long_description().ends_with(": 0/A/1/CA."));
+    assert_eq!(errors[1].context().linenumber(), Some(3));
+
+    // With a wider allowed range only the invalid factors are reported
+    let errors = validate_anisotropic_factors(&pdb, 0.5, 0.95);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].short_description(), "Invalid anisotropic factors");
+}
+
+#[test]
+fn capped_atom_list() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    for atom in pdb.atoms_mut().take(12) {
+        atom.set_anisotropic_temperature_factors([[-0.1, 0.1, 0.1], [0.0, 0.0, 0.0]]);
+    }
+    let errors = validate_anisotropic_factors(&pdb, 0.05, 0.95);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].long_description().ends_with(" and 2 more."));
+}
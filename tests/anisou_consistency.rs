@@ -0,0 +1,67 @@
+use pdbtbx::*;
+use std::fs;
+
+fn mismatches(errors: &[PDBError]) -> Vec<&PDBError> {
+    errors
+        .iter()
+        .filter(|e| e.short_description() == "ANISOU does not match atom")
+        .collect()
+}
+
+#[test]
+fn matching_records() {
+    let (pdb, errors) = read_pdb("example-pdbs/pTLS-6484.pdb").unwrap();
+    assert!(mismatches(&errors).is_empty());
+    assert!(pdb.atoms().all(|atom| atom.anisotropic_u().is_some()));
+}
+
+#[test]
+fn mismatching_records() {
+    let _ = fs::create_dir("dump");
+    fs::write(
+        "dump/anisou_mismatch.pdb",
+        "\
+ATOM      1  N   SER A   1      -2.764   9.176 -17.021  1.00 23.84           N
+ANISOU    1  N   SER A   1     3181   3287   2592  -1211  -1085   1043       N
+ATOM      2  CA ASER A   1      -2.377   9.146 -15.615  0.50 22.98           C
+ANISOU    2  CB BTHR B   2     3005   3176   2550  -1083   -978    985       O
+ATOM      3  C   SER A   1      -1.000   9.146 -15.615  1.00 22.98
+ANISOU    3  C   SER A   1     3005   3176   2550  -1083   -978    985       C
+END
+",
+    )
+    .unwrap();
+    let (pdb, errors) = read_pdb("dump/anisou_mismatch.pdb").unwrap();
+    let found = mismatches(&errors);
+    // The atom name, alternate location, residue name, residue serial number, chain id and
+    // element of the second ANISOU record differ, the missing element of the third atom is fine
+    assert_eq!(found.len(), 6, "{:?}", found);
+    assert!(found.iter().all(|e| e.level() == ErrorLevel::StrictWarning));
+    assert!(found[0].long_description().contains(
+        "atom name of the ANISOU record (\"CB\") differs from the atom name of atom 2 (\"CA\")"
+    ));
+    assert!(found[5].long_description().contains("element"));
+    // The factors are still attached
+    assert!(pdb.atoms().all(|atom| atom.anisotropic_u().is_some()));
+}
+
+#[test]
+fn truncated_record() {
+    let _ = fs::create_dir("dump");
+    fs::write(
+        "dump/anisou_truncated.pdb",
+        "\
+ATOM      1  N   SER A   1      -2.764   9.176 -17.021  1.00 23.84           N
+ANISOU    1  N   SER
+END
+",
+    )
+    .unwrap();
+    let (pdb, errors) = read_pdb("dump/anisou_truncated.pdb").unwrap();
+    let error = errors
+        .iter()
+        .find(|e| e.short_description() == "ANISOU line too short")
+        .unwrap();
+    assert_eq!(error.context().linenumber(), Some(2));
+    assert!(pdb.atom(0).unwrap().anisotropic_u().is_none());
+}
@@ -0,0 +1,82 @@
+use pdbtbx::*;
+use std::fs;
+
+fn write_frame(pdb: &PDB, shift: f64, filename: &str) {
+    let mut frame = pdb.clone();
+    frame.apply_transformation(&TransformationMatrix::translation(shift, 0.0, 0.0));
+    save_pdb(&frame, filename).unwrap();
+}
+
+#[test]
+fn append_frames() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let _ = fs::create_dir("dump");
+    let topology = pdb.clone();
+    for frame in 1..=3 {
+        let filename = format!("dump/1ubq_frame_{}.pdb", frame);
+        write_frame(&topology, frame as f64, &filename);
+        let serial = pdb.append_model_from(&filename).unwrap();
+        assert_eq!(serial, pdb.model(frame).unwrap().serial_number());
+    }
+    assert_eq!(pdb.model_count(), 4);
+
+    let first = pdb.model(0).unwrap().atom(0).unwrap().x();
+    let last = pdb.model(3).unwrap().atom(0).unwrap().x();
+    assert!((last - first - 3.0).abs() < 1e-3);
+    // The hierarchy of the new Models is a copy of the first Model
+    assert_eq!(
+        pdb.model(3).unwrap().total_atom_count(),
+        pdb.model(0).unwrap().total_atom_count()
+    );
+}
+
+#[test]
+fn mismatching_frames() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let _ = fs::create_dir("dump");
+
+    let mut missing = pdb.clone();
+    missing.remove_residues_by(|residue| residue.serial_number() == 76);
+    save_pdb(&missing, "dump/1ubq_missing.pdb").unwrap();
+    let error = pdb.append_model_from("dump/1ubq_missing.pdb").unwrap_err();
+    assert!(error.long_description().contains("atoms where"));
+    assert!(error.long_description().contains("expected A 76 GLY"));
+
+    let mut renamed = pdb.clone();
+    renamed
+        .residue_mut(0)
+        .unwrap()
+        .atom_mut(1)
+        .unwrap()
+        .set_name("CX")
+        .unwrap();
+    save_pdb(&renamed, "dump/1ubq_renamed.pdb").unwrap();
+    let error = pdb.append_model_from("dump/1ubq_renamed.pdb").unwrap_err();
+    assert!(error
+        .long_description()
+        .contains("atom 2: expected A 1 MET CA, found A 1 MET CX"));
+    assert!(error.context().linenumber().is_some());
+    assert_eq!(pdb.model_count(), 1);
+}
+
+#[test]
+fn invalid_coordinates() {
+    let _ = fs::create_dir("dump");
+    fs::write(
+        "dump/append_topology.pdb",
+        "ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C\nEND\n",
+    )
+    .unwrap();
+    fs::write(
+        "dump/append_invalid.pdb",
+        "ATOM      1  CA  ALA A   1         NaN   0.000   0.000  1.00 10.00           C\nEND\n",
+    )
+    .unwrap();
+    let (mut pdb, _) = read_pdb("dump/append_topology.pdb").unwrap();
+    let error = pdb
+        .append_model_from("dump/append_invalid.pdb")
+        .unwrap_err();
+    assert_eq!(error.short_description(), "Invalid coordinates");
+    assert_eq!(error.context().linenumber(), Some(1));
+    assert_eq!(pdb.model_count(), 1);
+}
@@ -0,0 +1,83 @@
+use pdbtbx::*;
+use std::fs;
+
+fn remarks(filename: &str, remark_type: &str) -> Vec<String> {
+    fs::read_to_string(filename)
+        .unwrap()
+        .lines()
+        .filter(|line| line.starts_with("REMARK") && line[6..].starts_with(remark_type))
+        .map(|line| line.to_string())
+        .collect()
+}
+
+#[test]
+fn write_assembly() {
+    let (mut pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let mut assembly = Assembly::new(1, &['A'], vec![TransformationMatrix::identity()]).unwrap();
+    assembly.add_transformation(TransformationMatrix::from_matrix([
+        [-1.0, 0.0, 0.0, 10.5],
+        [0.0, -1.0, 0.0, -20.25],
+        [0.0, 0.0, 1.0, 0.0],
+    ]));
+    pdb.add_assembly(assembly);
+    save_pdb(&pdb, "dump/assembly.pdb").unwrap();
+
+    let lines = remarks("dump/assembly.pdb", " 350");
+    assert_eq!(lines.len(), 15);
+    assert_eq!(lines[0], "REMARK 350");
+    assert_eq!(lines[7], "REMARK 350 BIOMOLECULE: 1");
+    assert_eq!(lines[8], "REMARK 350 APPLY THE FOLLOWING TO CHAINS: A");
+    assert_eq!(
+        lines[9],
+        "REMARK 350   BIOMT1   1  1.000000  0.000000  0.000000        0.00000"
+    );
+    assert_eq!(
+        lines[12],
+        "REMARK 350   BIOMT1   2 -1.000000  0.000000  0.000000       10.50000"
+    );
+    assert_eq!(
+        lines[13],
+        "REMARK 350   BIOMT2   2  0.000000 -1.000000  0.000000      -20.25000"
+    );
+
+    // The REMARK 350 block replaces the parsed one, between REMARK 300 and REMARK 500
+    let content = fs::read_to_string("dump/assembly.pdb").unwrap();
+    let position = |tag: &str| content.find(tag).unwrap();
+    assert!(position("REMARK 300") < position("REMARK 350"));
+    assert!(position("REMARK 350   BIOMT3   2") < position("REMARK 500"));
+
+    let (reread, errors) = read_pdb("dump/assembly.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .all(|e| e.level() != ErrorLevel::BreakingError));
+    assert!(!errors
+        .iter()
+        .any(|e| e.short_description().contains("MASTER")));
+    assert_eq!(reread.remarks().filter(|r| r.0 == 350).count(), 15);
+}
+
+#[test]
+fn wrap_chains() {
+    let mut pdb = PDB::new();
+    let chains = "ABCDEFGHIJKLMNOP".chars().collect::<Vec<char>>();
+    pdb.add_assembly(Assembly::new(2, &chains, vec![TransformationMatrix::identity()]).unwrap());
+    save_pdb(&pdb, "dump/assembly_chains.pdb").unwrap();
+
+    let lines = remarks("dump/assembly_chains.pdb", " 350");
+    assert_eq!(
+        lines[8],
+        "REMARK 350 APPLY THE FOLLOWING TO CHAINS: A, B, C, D, E, F, G, H, I, J, K, L,"
+    );
+    assert_eq!(
+        lines[9],
+        "REMARK 350                    AND CHAINS: M, N, O, P"
+    );
+    assert!(lines.iter().all(|line| line.len() <= 80));
+}
+
+#[test]
+fn invalid_assembly() {
+    assert!(Assembly::new(1, &[], vec![TransformationMatrix::identity()]).is_none());
+    assert!(Assembly::new(1, &['A'], Vec::new()).is_none());
+    assert!(Assembly::new(1, &['Å'], vec![TransformationMatrix::identity()]).is_none());
+}
@@ -0,0 +1,32 @@
+use pdbtbx::*;
+use std::collections::HashSet;
+
+#[test]
+fn atom_ids_are_unique_keys() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let mut ids = Vec::new();
+    for model in pdb.models() {
+        for chain in model.all_chains() {
+            for residue in chain.residues() {
+                for atom in residue.atoms() {
+                    ids.push(AtomId::from_hierarchy(model, chain, residue, atom));
+                }
+            }
+        }
+    }
+    assert_eq!(ids.len(), pdb.all_atoms().count());
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+    for (id, atom) in ids.iter().zip(pdb.all_atoms()) {
+        assert_eq!(pdb.get(id), Some(atom));
+    }
+
+    let id = &ids[1];
+    assert_eq!(
+        format!("{}", id),
+        format!("{}/A/1/CA", id.model_serial_number())
+    );
+    pdb.get_mut(id).unwrap().set_b_factor(99.0).unwrap();
+    assert_eq!(pdb.atom(1).unwrap().b_factor(), 99.0);
+    let missing = AtomId::new(id.model_serial_number(), ('A', 1).into(), "CA", 'B');
+    assert!(pdb.get(&missing).is_none());
+}
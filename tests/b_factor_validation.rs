@@ -0,0 +1,121 @@
+use pdbtbx::*;
+
+mod common;
+
+/// A chain of five Residues with three Atoms each, with an outlier in the third Residue and a
+/// negative B-factor in the last Residue
+const STRUCTURE: &str = "\
+ATOM      1  N   GLY A   1       0.000   0.000   0.000  1.00 20.00           N
+ATOM      2  CA  GLY A   1       1.500   0.000   0.000  1.00 21.00           C
+ATOM      3  C   GLY A   1       2.500   1.000   0.000  1.00 22.00           C
+ATOM      4  N   GLY A   2       3.800   0.000   0.000  1.00 20.50           N
+ATOM      5  CA  GLY A   2       5.300   0.000   0.000  1.00 21.50           C
+ATOM      6  C   GLY A   2       6.300   1.000   0.000  1.00 22.50           C
+ATOM      7  N   GLY A   3       7.600   0.000   0.000  1.00 21.00           N
+ATOM      8  CA  GLY A   3       9.100   0.000   0.000  1.00 95.00           C
+ATOM      9  C   GLY A   3      10.100   1.000   0.000  1.00 22.00           C
+ATOM     10  N   GLY A   4      11.400   0.000   0.000  1.00 20.00           N
+ATOM     11  CA  GLY A   4      12.900   0.000   0.000  1.00 21.00           C
+ATOM     12  C   GLY A   4      13.900   1.000   0.000  1.00 22.00           C
+ATOM     13  N   GLY A   5      15.200   0.000   0.000  1.00 20.50           N
+ATOM     14  CA  GLY A   5      16.700   0.000   0.000  1.00 21.50           C
+ATOM     15  C   GLY A   5      17.700   1.000   0.000  1.00 -1.00           C
+TER      16      GLY A   5
+ATOM     17  CA  ALA B   1      30.000   0.000   0.000  1.00  0.00           C
+ATOM     18  CA  ALA B   2      33.800   0.000   0.000  1.00  0.00           C
+ATOM     19  CA  ALA B   3      37.600   0.000   0.000  1.00  0.00           C
+TER      20      ALA B   3
+END
+";
+
+fn read(name: &str) -> PDB {
+    let mut options = ReadOptions::new();
+    options.set_keep_source_lines(true);
+    common::read_text_with_options(name, STRUCTURE, &options).0
+}
+
+#[test]
+fn anomalies() {
+    let pdb = read("b_factor_validation");
+    let errors = validate_b_factors(&pdb, &BFactorThresholds::default());
+    let found = errors
+        .iter()
+        .map(|error| error.short_description())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            "Negative B-factors",
+            "B-factor outliers",
+            "Identical B-factors"
+        ]
+    );
+    assert!(errors
+        .iter()
+        .all(|error| error.level() == ErrorLevel::LooseWarning));
+    assert!(errors
+        .iter()
+        .all(|error| ValidationCheck::of(error) == Some(ValidationCheck::BFactorDistribution)));
+
+    assert!(errors[0].long_description().contains("0/A/5/C"));
+    assert_eq!(errors[0].context().linenumber(), Some(15));
+    assert!(errors[1]
+        .long_description()
+        .starts_with("1 Atoms of Chain A"));
+    assert!(errors[1].long_description().contains("0/A/3/CA"));
+    assert_eq!(errors[1].context().linenumber(), Some(8));
+    assert!(errors[2]
+        .long_description()
+        .starts_with("All 3 Atoms of Chain B in Model 0 have a B-factor of 0"));
+
+    assert_eq!(
+        validate_with(
+            &pdb,
+            &ValidationProfile::preset(ValidationPreset::Deposition)
+        )
+        .iter()
+        .filter(|error| ValidationCheck::of(error) == Some(ValidationCheck::BFactorDistribution))
+        .count(),
+        3
+    );
+}
+
+#[test]
+fn thresholds() {
+    let pdb = read("b_factor_thresholds");
+    let errors = validate_b_factors(&pdb, &BFactorThresholds::new(100.0, 2));
+    assert!(errors
+        .iter()
+        .all(|error| error.short_description() != "B-factor outliers"));
+    // Only compared to the other Atoms of its own Residue
+    let errors = validate_b_factors(&pdb, &BFactorThresholds::new(3.0, 0));
+    assert!(errors
+        .iter()
+        .any(|error| error.short_description() == "B-factor outliers"));
+    assert_eq!(BFactorThresholds::default().outlier_deviations(), 3.0);
+    assert_eq!(BFactorThresholds::default().neighbourhood(), 2);
+}
+
+#[test]
+#[should_panic]
+fn invalid_thresholds() {
+    BFactorThresholds::new(-1.0, 2);
+}
+
+#[test]
+fn statistics() {
+    let pdb = read("b_factor_statistics");
+    let statistics = b_factor_statistics(&pdb);
+    assert_eq!(statistics.len(), 2);
+    assert_eq!(statistics[0].chain_id(), 'A');
+    assert_eq!(statistics[0].model_serial_number(), 0);
+    assert_eq!(statistics[0].count(), 15);
+    assert_eq!(statistics[0].b_factors()[7], 95.0);
+    assert_eq!(statistics[0].min(), Some(-1.0));
+    assert_eq!(statistics[0].max(), Some(95.0));
+    assert!(!statistics[0].all_identical());
+    assert!(statistics[0].standard_deviation() > 0.0);
+    assert!(statistics[1].all_identical());
+    assert_eq!(statistics[1].mean(), 0.0);
+    assert_eq!(statistics[1].standard_deviation(), 0.0);
+}
@@ -0,0 +1,102 @@
+use pdbtbx::*;
+use std::fs;
+
+const STRUCTURE: &str = "\
+CRYST1   10.000   10.000   10.000  90.00  90.00  90.00 P 1           1
+ATOM      1  CA  ALA A   1       0.500   5.000   5.000  1.00 10.00           C
+ATOM      2  CA  ALA A   2       8.000   5.000   5.000  1.00 10.00           C
+ATOM      3  CA  ALA A   3       5.000   5.000   5.000  1.00 10.00           C
+TER       4      ALA A   3
+HETATM    5  C1  LIG B 101       9.500   5.000   5.000  1.00 10.00           C
+END
+";
+
+#[test]
+fn binding_site_across_cell_boundary() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/binding_site.pdb", STRUCTURE).unwrap();
+    let (pdb, _) = read_pdb("dump/binding_site.pdb").unwrap();
+    assert!(pdb
+        .binding_site_with_symmetry(('B', 102).into(), 2.0)
+        .is_none());
+
+    let site = pdb
+        .binding_site_with_symmetry(('B', 101).into(), 2.0)
+        .unwrap();
+    let residues = site
+        .all_chains()
+        .flat_map(|chain| {
+            chain
+                .residues()
+                .map(move |residue| (chain.id(), residue.serial_number()))
+        })
+        .collect::<Vec<_>>();
+    // A1 is only close to the ligand in the next unit cell along a
+    assert_eq!(residues, vec![('A', 2), ('C', 1), ('B', 101)]);
+    let copy = site.atom(1).unwrap();
+    assert!((copy.x() - 10.5).abs() < 1e-6);
+    assert_eq!(
+        site.metadata("symmetry_copy_C"),
+        Some(&MetadataValue::Text(
+            "chain A operator 0 translation 1 0 0".to_string()
+        ))
+    );
+    assert_eq!(
+        site.all_atoms()
+            .map(|a| a.serial_number())
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+
+    // Without symmetry only the asymmetric unit is used
+    fs::write(
+        "dump/binding_site_no_symmetry.pdb",
+        STRUCTURE.lines().skip(1).collect::<Vec<_>>().join("\n"),
+    )
+    .unwrap();
+    let (pdb, _) = read_pdb("dump/binding_site_no_symmetry.pdb").unwrap();
+    let site = pdb
+        .binding_site_with_symmetry(('B', 101).into(), 2.0)
+        .unwrap();
+    assert_eq!(site.total_atom_count(), 2);
+    assert!(PDB::new()
+        .binding_site_with_symmetry(('B', 101).into(), 2.0)
+        .is_none());
+}
+
+#[test]
+fn water_at_crystal_contact() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let model = pdb.model(0).unwrap();
+    let cutoff = 4.0;
+    let mut found_copies = false;
+    for water in model.hetero_residues() {
+        let site = pdb
+            .binding_site_with_symmetry(('A', water.serial_number()).into(), cutoff)
+            .unwrap();
+        let ligand = site
+            .hetero_chains()
+            .find(|chain| chain.id() == 'A')
+            .and_then(|chain| {
+                chain
+                    .residues()
+                    .find(|r| r.serial_number() == water.serial_number())
+            })
+            .unwrap();
+        // Every Residue in the binding site is close to the ligand
+        for residue in site.all_residues() {
+            assert!(residue
+                .atoms()
+                .any(|atom| ligand.atoms().any(|other| atom.distance(other) <= cutoff)));
+        }
+        if site.all_chains().any(|chain| chain.id() != 'A') {
+            found_copies = true;
+            let filename = "dump/binding_site_1ubq.pdb";
+            let mut output = PDB::new();
+            output.add_model(site);
+            save_pdb(&output, filename).unwrap();
+            assert!(read_pdb(filename).is_ok());
+        }
+    }
+    assert!(found_copies);
+}
@@ -0,0 +1,148 @@
+use pdbtbx::*;
+use std::fs;
+
+fn ca_only() -> PDB {
+    read_pdb("example-pdbs/3b5j_ca.pdb").unwrap().0
+}
+
+#[test]
+fn clean_fixture() {
+    let (_, errors) = read_pdb("example-pdbs/3b5j_ca.pdb").unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert!(fs::read_to_string("example-pdbs/3b5j_ca.pdb")
+        .unwrap()
+        .lines()
+        .all(|line| line.len() <= 80));
+}
+
+#[test]
+fn detection() {
+    let pdb = ca_only();
+    assert_eq!(
+        pdb.model_types().collect::<Vec<_>>(),
+        vec!["CA ATOMS ONLY, CHAIN A"]
+    );
+    assert_eq!(pdb.ca_only_chains(), vec!['A']);
+    let model = pdb.model(0).unwrap();
+    assert!(model.chain(0).unwrap().is_ca_only());
+    assert_eq!(model.ca_only_chains(), vec!['A']);
+    // The ligand does not count as CA-only chain
+    assert!(!model.hetero_chains().any(|chain| chain.is_ca_only()));
+
+    let (full, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert!(full.ca_only_chains().is_empty());
+    assert!(full.model(0).unwrap().ca_only_chains().is_empty());
+
+    // Found by the census alone
+    let mut census = full.clone();
+    census.remove_atoms_by(|atom| !atom.hetero() && atom.name() != "CA");
+    assert_eq!(census.model_types().count(), 0);
+    assert_eq!(census.ca_only_chains(), vec!['A']);
+}
+
+#[test]
+fn model_type_record() {
+    fs::create_dir_all("dump").unwrap();
+    fs::write(
+        "dump/ca_only_model_types.pdb",
+        "MDLTYP    CA ATOMS ONLY, CHAIN A, B; P ATOMS ONLY, CHAIN C; MINIMIZED AVERAGE;\n\
+         MDLTYP   2 CA ATOMS ONLY, CHAIN D\n\
+         END\n",
+    )
+    .unwrap();
+    let (pdb, _) = read_pdb("dump/ca_only_model_types.pdb").unwrap();
+    assert_eq!(
+        pdb.model_types().collect::<Vec<_>>(),
+        vec![
+            "CA ATOMS ONLY, CHAIN A, B",
+            "P ATOMS ONLY, CHAIN C",
+            "MINIMIZED AVERAGE",
+            "CA ATOMS ONLY, CHAIN D"
+        ]
+    );
+    assert_eq!(pdb.ca_only_chains(), vec!['A', 'B', 'D']);
+
+    save_pdb(&pdb, "dump/ca_only_model_types_saved.pdb").unwrap();
+    let (saved, _) = read_pdb("dump/ca_only_model_types_saved.pdb").unwrap();
+    assert_eq!(
+        saved.model_types().collect::<Vec<_>>(),
+        pdb.model_types().collect::<Vec<_>>()
+    );
+
+    let mut pdb = PDB::new();
+    assert!(pdb.add_model_type("CA ATOMS ONLY; CHAIN A").is_err());
+    assert!(pdb.add_model_type("NMR REPRESENTATIVE").is_ok());
+}
+
+#[test]
+fn full_atom_analyses_fail() {
+    let pdb = ca_only();
+    let model = pdb.model(0).unwrap();
+    let chain = model.chain(0).unwrap();
+    let error = Err(AnalysisError::CaOnlyChain('A'));
+    assert_eq!(chain.phi_psi_pairs().map(|pairs| pairs.len()), error);
+    assert_eq!(
+        chain.detect_all_cis_peptides(30.0),
+        error.map(|_| Vec::new())
+    );
+    assert_eq!(chain.detect_cis_prolines(), error.map(|_| Vec::new()));
+    assert_eq!(
+        model
+            .ramachandran_pairs_by_residue_class()
+            .map(|pairs| pairs.len()),
+        error
+    );
+    assert_eq!(
+        model.atomic_sasa(&SasaParameters::default()),
+        error.map(|_| Vec::new())
+    );
+    assert_eq!(
+        model.residue_sasa(&SasaParameters::default()),
+        error.map(|_| Vec::new())
+    );
+    assert_eq!(
+        format!("{}", AnalysisError::CaOnlyChain('A')),
+        "Chain A only contains CA atoms, this analysis needs all backbone and side chain atoms"
+    );
+
+    // Without values the residue table leaves the cells empty
+    let rows = pdb
+        .residue_table()
+        .with_sasa(SasaParameters::default())
+        .with_torsions()
+        .rows();
+    assert_eq!(rows.len(), 244);
+    assert!(rows
+        .iter()
+        .all(|row| row[2..].iter().all(|cell| cell.is_empty())));
+}
+
+#[test]
+fn ca_based_analyses() {
+    let pdb = ca_only();
+    let (full, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let model = pdb.model(0).unwrap();
+    let reference = full.model(0).unwrap();
+    assert_eq!(model.per_chain_ca_rmsd(reference), vec![('A', Some(0.0))]);
+    assert!(model.rmsd(reference, &Selection::calpha(), true).unwrap() < 1e-6);
+    assert_eq!(
+        model.rmsd(reference, &Selection::calpha(), false),
+        Some(0.0)
+    );
+    let comparison = pdb.compare_structures(&full);
+    assert!(comparison.sequence_identical());
+    assert!(comparison.global_rmsd().unwrap() < 1e-6);
+    assert_eq!(model.cluster_chains_by_structure_ca(1.0), vec![vec!['A']]);
+    assert_eq!(pdb.gap_report(), full.gap_report());
+}
+
+#[test]
+fn clone_keeps_model_types() {
+    let pdb = ca_only();
+    let copy = pdb.clone();
+    assert_eq!(
+        copy.model_types().collect::<Vec<_>>(),
+        pdb.model_types().collect::<Vec<_>>()
+    );
+    assert_eq!(copy.ca_only_chains(), pdb.ca_only_chains());
+}
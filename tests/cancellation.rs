@@ -0,0 +1,93 @@
+use pdbtbx::*;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The number of lines in the large structure
+const LINES: usize = 200_000;
+
+fn large_structure(name: &str) -> String {
+    let _ = fs::create_dir("dump");
+    let path = format!("dump/{}.pdb", name);
+    let mut text = String::with_capacity(LINES * 81);
+    for index in 0..LINES {
+        text.push_str(&format!(
+            "ATOM  {:5}  CA  ALA A{:4}    {:8.3}   0.000   0.000  1.00 10.00           C\n",
+            index % 99_999 + 1,
+            index % 9_999 + 1,
+            (index % 1000) as f64
+        ));
+    }
+    fs::write(&path, text).unwrap();
+    path
+}
+
+#[test]
+fn cancel_parsing_from_thread() {
+    let path = large_structure("cancellation_parse");
+    let token = CancellationToken::new();
+    let mut options = ReadOptions::new();
+    options.set_cancellation_token(Some(token.clone()));
+
+    let start = Instant::now();
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(5));
+        token.cancel();
+    });
+    let error = parse_with_options(&path, &options).unwrap_err();
+    canceller.join().unwrap();
+    assert!(error.is_cancellation());
+    assert_eq!(error.level(), ErrorLevel::BreakingError);
+    assert!(error
+        .long_description()
+        .starts_with("Parsing was cancelled after "));
+    let lines = error
+        .long_description()
+        .trim_start_matches("Parsing was cancelled after ")
+        .trim_end_matches(" lines.")
+        .parse::<usize>()
+        .unwrap();
+    assert!(lines < LINES);
+    assert!(lines.is_multiple_of(1000));
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn cancelled_before_parsing() {
+    let flag = Arc::new(AtomicBool::new(false));
+    let mut options = ReadOptions::new();
+    options.set_cancellation_token(Some(CancellationToken::from(flag.clone())));
+    assert!(parse_with_options("example-pdbs/1ubq.pdb", &options).is_ok());
+
+    flag.store(true, Ordering::Relaxed);
+    let error = parse_with_options("example-pdbs/1ubq.pdb", &options).unwrap_err();
+    assert!(error.is_cancellation());
+    assert_eq!(
+        error.long_description(),
+        "Parsing was cancelled after 0 lines."
+    );
+    let (_, errors) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert!(errors.iter().all(|error| !error.is_cancellation()));
+}
+
+#[test]
+fn cancel_saving() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let token = CancellationToken::new();
+    let mut options = SaveOptions::new();
+    options.set_cancellation_token(Some(token.clone()));
+    assert_eq!(options.clone(), options);
+    save_with_options(&pdb, "dump/cancellation_save.pdb", &options).unwrap();
+
+    token.cancel();
+    let message = save_with_options(&pdb, "dump/cancellation_save.pdb", &options).unwrap_err();
+    assert!(message.contains("Saving was cancelled after 0 lines."));
+
+    let mut output = Vec::new();
+    let error = PdbWriter::with_options(&mut output, &pdb, &options)
+        .err()
+        .unwrap();
+    assert!(error.is_cancellation());
+}
@@ -0,0 +1,28 @@
+use pdbtbx::*;
+
+#[test]
+fn features() {
+    let capabilities = capabilities();
+    assert_eq!(capabilities.serde(), cfg!(feature = "serde"));
+    assert_eq!(
+        capabilities.features().contains(&"serde"),
+        cfg!(feature = "serde")
+    );
+    assert_eq!(capabilities.version(), env!("CARGO_PKG_VERSION"));
+    assert_eq!(capabilities, pdbtbx::capabilities());
+}
+
+#[test]
+fn formats() {
+    let capabilities = capabilities();
+    assert_eq!(capabilities.readable_formats(), vec![Format::Pdb]);
+    for format in Format::ALL.iter() {
+        assert_eq!(capabilities.can_read(*format), format.is_readable());
+    }
+    assert!(capabilities.can_read(Format::Pdb));
+    assert!(!capabilities.can_read(Format::Mmcif));
+
+    let text = capabilities.to_string();
+    assert!(text.starts_with(&format!("pdbtbx {}, features: ", capabilities.version())));
+    assert!(text.ends_with("readable formats: PDB"));
+}
@@ -0,0 +1,70 @@
+use pdbtbx::*;
+use std::fs;
+
+const STRUCTURE: &str = "\
+ATOM      1  CA  ALA B   1       0.000   0.000   0.000  1.00 10.00           C
+TER       2      ALA B   1
+ATOM      3  CA  GLY A   1       3.800   0.000   0.000  1.00 10.00           C
+TER       4      GLY A   1
+HETATM    5  O   HOH B 101       0.000   3.000   0.000  1.00 20.00           O
+HETATM    6  O   HOH C 201       0.000   6.000   0.000  1.00 20.00           O
+HETATM    7  PG  ATP A 301       5.000   5.000   0.000  1.00 30.00           P
+HETATM    8  C1  NAG B 302       8.000   5.000   0.000  1.00 30.00           C
+END
+";
+
+fn structure(name: &str) -> PDB {
+    let _ = fs::create_dir("dump");
+    let filename = format!("dump/{}.pdb", name);
+    fs::write(&filename, STRUCTURE).unwrap();
+    read_pdb(&filename).unwrap().0
+}
+
+fn chain_ids<'a>(chains: impl Iterator<Item = &'a Chain>) -> String {
+    chains.map(|chain| chain.id()).collect()
+}
+
+#[test]
+fn reorder_chains() {
+    let mut pdb = structure("chain_order_reorder");
+    let model = pdb.model_mut(0).unwrap();
+    assert_eq!(chain_ids(model.chains()), "BA");
+    assert_eq!(chain_ids(model.hetero_chains()), "BCA");
+
+    model.reorder_chains(&['C', 'A', 'B']).unwrap();
+    assert_eq!(chain_ids(model.chains()), "AB");
+    assert_eq!(chain_ids(model.hetero_chains()), "CAB");
+
+    assert!(model.reorder_chains(&['A', 'B']).is_err());
+    assert!(model.reorder_chains(&['A', 'B', 'C', 'D']).is_err());
+    assert!(model.reorder_chains(&['A', 'B', 'C', 'A']).is_err());
+    assert_eq!(chain_ids(model.hetero_chains()), "CAB");
+
+    model.sort_chains();
+    assert_eq!(chain_ids(model.chains()), "AB");
+    assert_eq!(chain_ids(model.hetero_chains()), "ABC");
+}
+
+#[test]
+fn canonical_order_on_save() {
+    let pdb = structure("chain_order_save");
+    let mut options = SaveOptions::new();
+    options.set_canonical_chain_order(true);
+    save_with_options(&pdb, "dump/chain_order_saved.pdb", &options).unwrap();
+
+    let residues = fs::read_to_string("dump/chain_order_saved.pdb")
+        .unwrap()
+        .lines()
+        .filter(|line| line.starts_with("ATOM") || line.starts_with("HETATM"))
+        .map(|line| line[17..22].to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        residues,
+        vec!["GLY A", "ALA B", "ATP A", "NAG B", "HOH B", "HOH C"]
+    );
+    // The structure itself is not changed
+    assert_eq!(chain_ids(pdb.model(0).unwrap().chains()), "BA");
+
+    let (saved, _) = read_pdb("dump/chain_order_saved.pdb").unwrap();
+    assert_eq!(saved.total_atom_count(), pdb.total_atom_count());
+}
@@ -0,0 +1,122 @@
+use pdbtbx::*;
+use std::fs;
+
+mod common;
+
+const STRUCTURE: &str = "\
+CISPEP   1 GLY A    2    PRO A    3          1        -6.10
+CISPEP   2 GLY A    2    PRO A    3          2        10.25
+CISPEP   3 ALA A    1    GLY A    2          2
+MODEL        1
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C
+ATOM      2  CA  GLY A   2       3.800   0.000   0.000  1.00 10.00           C
+ATOM      3  CA  PRO A   3       7.600   0.000   0.000  1.00 10.00           C
+TER       4      PRO A   3
+ENDMDL
+MODEL        2
+ATOM      1  CA  ALA A   1       0.100   0.000   0.000  1.00 10.00           C
+ATOM      2  CA  GLY A   2       3.900   0.000   0.000  1.00 10.00           C
+ATOM      3  CA  PRO A   3       7.700   0.000   0.000  1.00 10.00           C
+TER       4      PRO A   3
+ENDMDL
+END
+";
+
+#[test]
+fn parse_cis_peptides() {
+    let (pdb, errors) = common::read_text("cis_peptides", STRUCTURE);
+    assert!(
+        errors
+            .iter()
+            .all(|e| !e.short_description().contains("CISPEP")),
+        "{:?}",
+        errors
+    );
+    assert_eq!(pdb.cis_peptide_count(), 3);
+    let first = pdb.cis_peptides().next().unwrap();
+    assert_eq!(first.serial_number(), 1);
+    assert_eq!(first.residue_names(), ["GLY", "PRO"]);
+    assert_eq!(first.chain_ids(), ['A', 'A']);
+    assert_eq!(first.residue_serial_numbers(), [2, 3]);
+    assert_eq!(first.insertion_codes(), [' ', ' ']);
+    assert_eq!(first.model_number(), 1);
+    assert_eq!(first.angle(), Some(-6.1));
+    assert!(first.contains('A', 3));
+
+    // The cis peptides differ between the Models of the ensemble
+    assert_eq!(pdb.model_cis_peptides(1).count(), 1);
+    let second = pdb.model_cis_peptides(2).collect::<Vec<_>>();
+    assert_eq!(second.len(), 2);
+    assert_eq!(second[0].angle(), Some(10.25));
+    assert_eq!(second[1].angle(), None);
+}
+
+#[test]
+fn save_cis_peptides() {
+    let (pdb, _) = common::read_text("cis_peptides_save", STRUCTURE);
+    save_pdb(&pdb, "dump/cis_peptides_saved.pdb").unwrap();
+    let text = fs::read_to_string("dump/cis_peptides_saved.pdb").unwrap();
+    for line in STRUCTURE.lines().filter(|line| line.starts_with("CISPEP")) {
+        assert!(text.lines().any(|saved| saved == line), "{}", line);
+    }
+    let (reloaded, _) = read_pdb("dump/cis_peptides_saved.pdb").unwrap();
+    assert_eq!(
+        reloaded.cis_peptides().collect::<Vec<_>>(),
+        pdb.cis_peptides().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn validate_residues() {
+    let (pdb, _) = common::read_text("cis_peptides_valid", STRUCTURE);
+    assert!(validate_cis_peptides(&pdb).is_empty());
+
+    let broken = STRUCTURE
+        .replace("PRO A    3          1", "PRO A    4          1")
+        .replace("A    2          2", "A    2          3");
+    let (pdb, _) = common::read_text("cis_peptides_invalid", &broken);
+    let errors = validate_cis_peptides(&pdb);
+    assert_eq!(
+        errors
+            .iter()
+            .map(|e| e.short_description())
+            .collect::<Vec<_>>(),
+        vec!["CISPEP residue not found", "CISPEP model not found"]
+    );
+    assert!(errors[0].long_description().contains("PRO A4 in Model 1"));
+    assert_eq!(
+        ValidationCheck::of(&errors[1]),
+        Some(ValidationCheck::CisPeptideResidues)
+    );
+}
+
+#[test]
+fn renumbered_cis_peptides() {
+    let (mut pdb, _) = common::read_text("cis_peptides_renumber", STRUCTURE);
+    for model in pdb.models_mut() {
+        model.set_serial_number(model.serial_number() + 10);
+    }
+    for residue in pdb.residues_mut() {
+        residue.set_serial_number(residue.serial_number() + 100);
+    }
+    pdb.renumber();
+    assert_eq!(pdb.model_cis_peptides(2).count(), 2);
+    assert_eq!(
+        pdb.cis_peptides().next().unwrap().residue_serial_numbers(),
+        [2, 3]
+    );
+    assert!(validate_cis_peptides(&pdb).is_empty());
+}
+
+#[test]
+fn invalid_cis_peptide_is_skipped() {
+    let structure = STRUCTURE.replacen("GLY A    2    PRO A", "GLY \u{e9}    2    PRO A", 1);
+    let (pdb, errors) = common::read_text("cis_peptides_invalid", &structure);
+    assert_eq!(pdb.cis_peptide_count(), 2);
+    let error = errors
+        .iter()
+        .find(|e| e.short_description() == "Invalid CISPEP")
+        .unwrap();
+    assert_eq!(error.level(), ErrorLevel::LooseWarning);
+    assert_eq!(error.context().linenumber(), Some(1));
+}
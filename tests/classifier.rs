@@ -0,0 +1,93 @@
+use pdbtbx::*;
+use std::fs;
+
+/// A small solvated system with CHARMM residue names, which are four characters wide
+const CHARMM: &str = "\
+ATOM      1  N   ALA P   1       0.000   0.000   0.000  1.00  0.00           N
+ATOM      2  CA  ALA P   1       1.458   0.000   0.000  1.00  0.00           C
+ATOM      3  OH2 TIP3W   1      10.000   0.000   0.000  1.00  0.00           O
+ATOM      4  H1  TIP3W   1      10.957   0.000   0.000  1.00  0.00           H
+ATOM      5  OH2 TIP3W   2      20.000   0.000   0.000  1.00  0.00           O
+ATOM      6 SOD  SOD I   1      30.000   0.000   0.000  1.00  0.00          NA
+ATOM      7 CLA  CLA I   2      40.000   0.000   0.000  1.00  0.00          CL
+END
+";
+
+// The registered names are shared by the whole program, so this is a single test
+#[test]
+fn charmm_names() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/charmm.pdb", CHARMM).unwrap();
+    let (pdb, _) = read_pdb("dump/charmm.pdb").unwrap();
+    let entities = |pdb: &PDB| {
+        pdb.residues()
+            .map(|residue| residue.entity_type())
+            .collect::<Vec<_>>()
+    };
+
+    // By default these names are not recognised
+    assert_eq!(
+        entities(&pdb),
+        vec![
+            EntityType::Protein,
+            EntityType::Other,
+            EntityType::Other,
+            EntityType::Other,
+            EntityType::Other
+        ]
+    );
+    assert_eq!(pdb.clone().remove_waters(), 0);
+
+    assert!(Classifier::register("", EntityType::Water).is_err());
+    assert!(Classifier::register("TIP3P", EntityType::Water).is_err());
+    assert!(Classifier::register("T-3", EntityType::Water).is_err());
+    assert_eq!(Classifier::register("TIP3", EntityType::Water), Ok(None));
+    assert_eq!(Classifier::register("SOD", EntityType::Ion), Ok(None));
+    assert_eq!(Classifier::register("CLA", EntityType::Other), Ok(None));
+    assert_eq!(
+        Classifier::register("CLA", EntityType::Ion),
+        Ok(Some(EntityType::Other))
+    );
+    assert_eq!(
+        Classifier::registered(),
+        vec![
+            ("CLA".to_string(), EntityType::Ion),
+            ("SOD".to_string(), EntityType::Ion),
+            ("TIP".to_string(), EntityType::Water)
+        ]
+    );
+    assert_eq!(
+        entities(&pdb),
+        vec![
+            EntityType::Protein,
+            EntityType::Water,
+            EntityType::Water,
+            EntityType::Ion,
+            EntityType::Ion
+        ]
+    );
+
+    let mut without_waters = pdb.clone();
+    assert_eq!(without_waters.remove_waters(), 2);
+    assert_eq!(without_waters.atom_count(), 4);
+    assert_eq!(
+        without_waters
+            .chains()
+            .map(|chain| chain.id())
+            .collect::<String>(),
+        "PI"
+    );
+
+    // Built in names are still recognised and can be overruled
+    assert_eq!(EntityType::from_residue_name("HOH"), EntityType::Water);
+    assert_eq!(Classifier::register("HOH", EntityType::Other), Ok(None));
+    assert_eq!(EntityType::from_residue_name("HOH"), EntityType::Other);
+
+    assert_eq!(Classifier::unregister("TIP3"), Some(EntityType::Water));
+    assert_eq!(Classifier::unregister("TIP3"), None);
+    assert_eq!(pdb.clone().remove_waters(), 0);
+    Classifier::clear();
+    assert!(Classifier::registered().is_empty());
+    assert_eq!(EntityType::from_residue_name("HOH"), EntityType::Water);
+    assert_eq!(entities(&pdb)[3], EntityType::Other);
+}
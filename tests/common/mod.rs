@@ -0,0 +1,21 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+use pdbtbx::*;
+use std::fs;
+
+/// Write the given text to `dump/<name>.pdb` and parse it with the given options
+pub fn read_text_with_options(
+    name: &str,
+    text: &str,
+    options: &ReadOptions,
+) -> (PDB, Vec<PDBError>) {
+    let _ = fs::create_dir("dump");
+    let path = format!("dump/{}.pdb", name);
+    fs::write(&path, text).unwrap();
+    parse_with_options(&path, options).unwrap()
+}
+
+/// Write the given text to `dump/<name>.pdb` and parse it with the default options, like `read_pdb`
+pub fn read_text(name: &str, text: &str) -> (PDB, Vec<PDBError>) {
+    read_text_with_options(name, text, &ReadOptions::new())
+}
@@ -0,0 +1,35 @@
+use pdbtbx::*;
+
+#[test]
+fn ubiquitin() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let composition = pdb.composition();
+    assert_eq!(composition.models().len(), 1);
+    let model = &composition.models()[0];
+    assert_eq!(model.chains().len(), 1);
+    let chain = model.chain('A').unwrap();
+    assert_eq!(chain.polymer_residue_count(), 76);
+    assert_eq!(chain.water_count(), 40);
+    assert_eq!(chain.ligand_residue_count(), 0);
+    assert_eq!(chain.atom_count(), 1231);
+    assert_eq!(chain.hetero_atom_count(), 40);
+    assert_eq!(chain.element_counts()["C"], 378);
+    assert_eq!(chain.element_counts()["O"], 118 + 40);
+    assert_eq!(model.total().id(), ' ');
+    assert_eq!(model.total().atom_count(), 1231);
+    assert_eq!(model.total().element_counts(), chain.element_counts());
+}
+
+#[test]
+fn display() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let table = pdb.composition().to_string();
+    let lines = table.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "Model 0");
+    assert_eq!(
+        lines[1],
+        "Chain  Polymer  Water  Ligand    ATOM  HETATM  Elements"
+    );
+    assert!(lines[2].starts_with("A          "), "{}", table);
+    assert!(lines.last().unwrap().starts_with("Total"));
+}
@@ -0,0 +1,113 @@
+use pdbtbx::*;
+use std::fs;
+
+fn pairs(list: &[(&str, &str)]) -> Vec<(String, String)> {
+    list.iter()
+        .map(|(token, value)| (token.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn parse_compounds() {
+    let (pdb, errors) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .all(|error| error.short_description() != "Invalid specification"));
+    let compounds = pdb.compounds().collect::<Vec<_>>();
+    assert_eq!(compounds.len(), 2);
+    // The value continued on the next line is joined with a space
+    assert_eq!(
+        compounds[0],
+        &pairs(&[
+            ("MOL_ID", "1"),
+            (
+                "MOLECULE",
+                "ATP-DEPENDENT HSL PROTEASE ATP-BINDING SUBUNIT HSLU"
+            ),
+            ("CHAIN", "A, B"),
+            ("SYNONYM", "HEAT SHOCK PROTEIN HSLU"),
+            ("ENGINEERED", "YES"),
+        ])
+    );
+    assert_eq!(compounds[1][3], ("EC".to_string(), "3.4.25.-".to_string()));
+    assert_eq!(pdb.sources().count(), 2);
+
+    assert_eq!(pdb.molecule_name('C'), Some("ATP-DEPENDENT PROTEASE HSLV"));
+    assert_eq!(pdb.chain_compound('B').unwrap()[0].1, "1");
+    assert_eq!(
+        pdb.chain_source('D').unwrap()[1],
+        (
+            "ORGANISM_SCIENTIFIC".to_string(),
+            "BACILLUS SUBTILIS".to_string()
+        )
+    );
+    assert!(pdb.chain_compound('E').is_none());
+
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert_eq!(pdb.compounds().count(), 0);
+}
+
+#[test]
+fn shuffled_and_invalid() {
+    fs::create_dir_all("dump").unwrap();
+    fs::write(
+        "dump/compound_shuffled.pdb",
+        "COMPND   3 CHAIN: A;\n\
+         COMPND    MOL_ID: 1;\n\
+         COMPND   2 MOLECULE: A VERY LONG;NAME;\n\
+         COMPND   4 MOL_ID: 2;\n\
+         COMPND   5 MOLECULE: SECOND;\n\
+         COMPND   6 CHAIN: B\n\
+         END\n",
+    )
+    .unwrap();
+    let (pdb, errors) = read_pdb("dump/compound_shuffled.pdb").unwrap();
+    assert_eq!(
+        errors
+            .iter()
+            .filter(|error| error.short_description() == "Invalid specification")
+            .count(),
+        1
+    );
+    assert_eq!(pdb.molecule_name('A'), Some("A VERY LONG"));
+    assert_eq!(pdb.molecule_name('B'), Some("SECOND"));
+}
+
+#[test]
+fn save_compounds() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    let long = format!("A {} MOLECULE", "VERY LONG ".repeat(12));
+    pdb.add_compound(pairs(&[
+        ("MOL_ID", "3"),
+        ("MOLECULE", &long),
+        ("CHAIN", "E"),
+    ]));
+    save_pdb(&pdb, "dump/compound.pdb").unwrap();
+
+    let text = fs::read_to_string("dump/compound.pdb").unwrap();
+    let lines = text
+        .lines()
+        .filter(|line| line.starts_with("COMPND"))
+        .collect::<Vec<_>>();
+    assert!(lines.iter().all(|line| line.len() <= 80));
+    assert_eq!(lines[0], "COMPND    MOL_ID: 1;");
+    assert_eq!(
+        lines[1],
+        "COMPND   2 MOLECULE: ATP-DEPENDENT HSL PROTEASE ATP-BINDING SUBUNIT HSLU;"
+    );
+    assert_eq!(
+        lines.last().unwrap(),
+        &format!("COMPND  {:2} CHAIN: E", lines.len())
+    );
+
+    let (saved, _) = read_pdb("dump/compound.pdb").unwrap();
+    assert_eq!(
+        saved.compounds().collect::<Vec<_>>(),
+        pdb.compounds().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        saved.sources().collect::<Vec<_>>(),
+        pdb.sources().collect::<Vec<_>>()
+    );
+    assert_eq!(saved.molecule_name('E'), Some(long.as_str()));
+}
@@ -0,0 +1,112 @@
+use pdbtbx::*;
+use std::fs;
+
+const FIXTURE: &str = "\
+HETATM    1  C1  ACT A   1       1.000   0.000   0.000  1.00 20.00           C
+HETATM    2  C2  ACT A   1       2.500   0.000   0.000  1.00 20.00           C
+HETATM    3  O1  ACT A   1       3.000   1.200   0.000  1.00 20.00           O
+HETATM    4  O2  ACT A   1       3.000  -1.200   0.000  1.00 20.00           O
+CONECT    1    2
+CONECT    2    1    3    4
+CONECT    3    2
+CONECT    4    2    9
+CONECT    2    1
+END
+";
+
+#[test]
+fn parse_connections() {
+    fs::write("dump/conect.pdb", FIXTURE).unwrap();
+    let (pdb, errors) = read_pdb("dump/conect.pdb").unwrap();
+    assert_eq!(
+        pdb.connections().collect::<Vec<_>>(),
+        vec![(1, 2), (2, 3), (2, 4)]
+    );
+    assert_eq!(pdb.bonded_serial_numbers(2), vec![1, 3, 4]);
+    assert_eq!(pdb.bonded_serial_numbers(9), Vec::<usize>::new());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].level(), ErrorLevel::StrictWarning);
+    assert_eq!(errors[0].short_description(), "CONECT atom not found");
+    assert_eq!(errors[0].context().linenumber(), Some(8));
+}
+
+#[test]
+fn ligand_connections() {
+    let (pdb, errors) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert!(!errors.iter().any(
+        |e| e.short_description().contains("CONECT") || e.long_description().contains("CONECT")
+    ));
+    assert_eq!(pdb.connection_count(), 46);
+    assert_eq!(
+        pdb.bonded_serial_numbers(1913),
+        vec![1914, 1915, 1916, 1920]
+    );
+}
+
+#[test]
+fn round_trip() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    save_pdb(&pdb, "dump/conect_3b5j.pdb").unwrap();
+    let (saved, errors) = read_pdb("dump/conect_3b5j.pdb").unwrap();
+    assert!(!errors
+        .iter()
+        .any(|e| e.long_description().contains("CONECT")));
+    assert_eq!(
+        saved.connections().collect::<Vec<_>>(),
+        pdb.connections().collect::<Vec<_>>()
+    );
+    let text = fs::read_to_string("dump/conect_3b5j.pdb").unwrap();
+    assert!(text.contains("\nCONECT 1913 1914 1915 1916 1920\n"));
+}
+
+#[test]
+fn renumber_connections() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    pdb.add_connection(1, 2).unwrap();
+    assert!(pdb.add_connection(3, 3).is_err());
+    pdb.remove_atoms_by(|atom| atom.serial_number() == 1);
+    pdb.add_connection(5, 2).unwrap();
+    pdb.renumber();
+    assert_eq!(pdb.connections().collect::<Vec<_>>(), vec![(1, 4)]);
+}
+
+#[test]
+fn bonds_by_atom_id() {
+    let (mut pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let bonds = pdb.bonds();
+    assert_eq!(bonds.len(), 46);
+    let (a, b) = &bonds[0];
+    assert_eq!(pdb.get(a).unwrap().serial_number(), 1913);
+    assert!(pdb.get(b).is_some());
+    assert_eq!(
+        pdb.bonded_atoms(a)
+            .iter()
+            .map(|id| pdb.get(id).unwrap().serial_number())
+            .collect::<Vec<_>>(),
+        vec![1914, 1915, 1916, 1920]
+    );
+
+    // Drained Atoms can be looked up in the bonds by their AtomId
+    let drained = pdb.drain_atoms_by(|atom| atom.serial_number() == 1914);
+    assert!(bonds
+        .iter()
+        .any(|(a, b)| a == &drained[0].0 || b == &drained[0].0));
+    assert!(!pdb
+        .bonds()
+        .iter()
+        .any(|(a, b)| a == &drained[0].0 || b == &drained[0].0));
+    assert!(pdb.bonded_atoms(&drained[0].0).is_empty());
+
+    let first = bonds[0].0.clone();
+    let other = pdb
+        .atoms_with_hierarchy()
+        .find(|(_, _, atom)| atom.serial_number() == 1)
+        .map(|(chain, residue, atom)| {
+            AtomId::from_hierarchy(pdb.model(0).unwrap(), chain, residue, atom)
+        })
+        .unwrap();
+    pdb.add_bond(&first, &other).unwrap();
+    assert!(pdb.bonded_serial_numbers(1).contains(&1913));
+    assert!(pdb.add_bond(&first, &drained[0].0).is_err());
+    assert!(pdb.add_bond(&first, &first).is_err());
+}
@@ -0,0 +1,104 @@
+use pdbtbx::*;
+use std::fs;
+
+/// A binding site with two different ligands modelled at the same place, like in 1CBS
+const STRUCTURE: &str = "\
+ATOM      1  N   SER A   1       0.000   1.400   0.000  1.00 10.00           N
+ATOM      2  CA  SER A   1       0.000   0.000   0.000  1.00 10.00           C
+ATOM      3  CB ASER A   1      -0.700  -0.700   1.200  0.70 12.00           C
+ATOM      4  CB BSER A   1      -0.700  -0.700  -1.200  0.50 14.00           C
+TER       5      SER A   1
+HETATM    6  C1 AREA A 200      10.000   0.000   0.000  0.60 20.00           C
+HETATM    7  C2 AREA A 200      11.500   0.000   0.000  0.60 20.00           C
+HETATM    8  O1 AREA A 200      12.100   1.000   0.000  0.60 20.00           O
+HETATM    9  C1 BRTL A 200      10.000   0.300   0.000  0.40 22.00           C
+HETATM   10  C2 BRTL A 200      11.500   0.300   0.000  0.40 22.00           C
+END
+";
+
+fn structure() -> PDB {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/conformers.pdb", STRUCTURE).unwrap();
+    read_pdb("dump/conformers.pdb").unwrap().0
+}
+
+fn ligand(pdb: &PDB) -> &Residue {
+    pdb.model(0)
+        .unwrap()
+        .hetero_residues()
+        .find(|residue| residue.serial_number() == 200)
+        .unwrap()
+}
+
+#[test]
+fn ligand_conformers() {
+    let pdb = structure();
+    let ligand = ligand(&pdb);
+    assert_eq!(ligand.id(), "REA");
+    let conformers = ligand.conformers();
+    assert_eq!(conformers.len(), 2);
+    assert_eq!(
+        conformers
+            .iter()
+            .map(|c| (c.alternate_location(), c.name(), c.atom_count()))
+            .collect::<Vec<_>>(),
+        vec![('A', "REA".to_string(), 3), ('B', "RTL".to_string(), 2)]
+    );
+    assert!((conformers[1].occupancy().unwrap() - 0.4).abs() < 1e-9);
+    assert!(ligand.conformer('C').is_none());
+
+    let residue = ligand.conformer('B').unwrap().to_residue();
+    assert_eq!(residue.id(), "RTL");
+    assert_eq!(residue.serial_number(), 200);
+    assert_eq!(residue.atom_count(), 2);
+
+    // A Residue without alternate locations has a single conformer with all Atoms
+    let (single, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let residue = single.residue(0).unwrap();
+    let conformers = residue.conformers();
+    assert_eq!(conformers.len(), 1);
+    assert_eq!(conformers[0].alternate_location(), ' ');
+    assert_eq!(conformers[0].atom_count(), residue.atom_count());
+    assert_eq!(conformers[0].name(), residue.id());
+}
+
+#[test]
+fn conformer_names_are_saved() {
+    let pdb = structure();
+    save_pdb(&pdb, "dump/conformers_saved.pdb").unwrap();
+    let (saved, _) = read_pdb("dump/conformers_saved.pdb").unwrap();
+    assert_eq!(saved, pdb);
+    assert_eq!(ligand(&saved).conformer_name('B'), "RTL");
+}
+
+#[test]
+fn select_conformer_for_save() {
+    let pdb = structure();
+    let selected = pdb.select_altloc('B');
+    let ligand = ligand(&selected);
+    assert_eq!(ligand.id(), "RTL");
+    assert_eq!(ligand.atom_count(), 2);
+    assert_eq!(ligand.conformer_name('B'), "RTL");
+
+    save_pdb(&selected, "dump/conformers_selected.pdb").unwrap();
+    let written = fs::read_to_string("dump/conformers_selected.pdb").unwrap();
+    assert!(written.contains("RTL A 200"));
+    assert!(!written.contains("REA"));
+    let (saved, _) = read_pdb("dump/conformers_selected.pdb").unwrap();
+    assert_eq!(saved.atoms().count(), selected.atoms().count());
+}
+
+#[test]
+fn conformer_occupancies() {
+    let pdb = structure();
+    let errors = validate_conformer_occupancies(&pdb);
+    // The serine conformers have a total occupancy of 1.2, the ligands of 1.0
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].level(), ErrorLevel::StrictWarning);
+    assert!(errors[0]
+        .long_description()
+        .contains("Residue A1 in Model 0 sum to 1.20"));
+    assert!(errors[0]
+        .long_description()
+        .ends_with("A SER 0.70, B SER 0.50."));
+}
@@ -0,0 +1,48 @@
+use pdbtbx::*;
+use std::fs;
+
+#[test]
+fn decimals_option() {
+    let mut options = SaveOptions::new();
+    assert_eq!(options.coordinate_decimals(), 3);
+    assert!(options.set_coordinate_decimals(0).is_err());
+    assert!(options.set_coordinate_decimals(4).is_err());
+    assert_eq!(options.coordinate_decimals(), 3);
+    options.set_coordinate_decimals(1).unwrap();
+    assert_eq!(options.coordinate_decimals(), 1);
+}
+
+#[test]
+fn save_rounded_coordinates() {
+    let _ = fs::create_dir("dump");
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let mut options = SaveOptions::new();
+    options.set_coordinate_decimals(2).unwrap();
+    save_with_options(&pdb, "dump/1ubq_2_decimals.pdb", &options).unwrap();
+
+    let content = fs::read_to_string("dump/1ubq_2_decimals.pdb").unwrap();
+    let mut atoms = 0;
+    for line in content.lines() {
+        if line.starts_with("ATOM") || line.starts_with("HETATM") {
+            atoms += 1;
+            for field in &[&line[30..38], &line[38..46], &line[46..54]] {
+                assert_eq!(field.trim().split('.').nth(1).unwrap().len(), 2, "{}", line);
+            }
+        } else if line.starts_with("CRYST1") {
+            assert_eq!(&line[6..15], "    50.84");
+        }
+    }
+    assert_eq!(atoms, pdb.all_atoms().count());
+
+    // Rounding in memory gives the same values as reading the saved file
+    let (saved, _) = read_pdb("dump/1ubq_2_decimals.pdb").unwrap();
+    let mut rounded = pdb.clone();
+    rounded.round_coordinates(2);
+    for (a, b) in rounded.all_atoms().zip(saved.all_atoms()) {
+        assert_eq!(a.pos(), b.pos());
+    }
+    assert!(rounded
+        .all_atoms()
+        .zip(pdb.all_atoms())
+        .all(|(a, b)| a.distance(b) < 0.01));
+}
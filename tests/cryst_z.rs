@@ -0,0 +1,51 @@
+use pdbtbx::*;
+use std::fs;
+
+const FIXTURE: &str = "\
+CRYST1   40.000   50.000   60.000  90.00  90.00  90.00 P 21 21 21    8          
+ATOM      1  CA  ALA A   1       1.000   2.000   3.000  1.00 20.00           C  
+END
+";
+
+#[test]
+fn z_value_round_trip() {
+    fs::write("dump/cryst_z.pdb", FIXTURE).unwrap();
+    let (pdb, errors) = read_pdb("dump/cryst_z.pdb").unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(pdb.unit_cell().z(), Some(8));
+
+    save_pdb(&pdb, "dump/cryst_z_saved.pdb").unwrap();
+    let saved = fs::read_to_string("dump/cryst_z_saved.pdb").unwrap();
+    let cryst = saved.lines().find(|l| l.starts_with("CRYST1")).unwrap();
+    assert_eq!(&cryst[55..66], "P 21 21 21 ");
+    assert_eq!(&cryst[66..70], "   8");
+    let (reparsed, _) = read_pdb("dump/cryst_z_saved.pdb").unwrap();
+    assert_eq!(reparsed.unit_cell().z(), Some(8));
+}
+
+#[test]
+fn missing_z_value() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert_eq!(pdb.unit_cell().z(), None);
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert_eq!(pdb.unit_cell().z(), Some(4));
+}
+
+#[test]
+fn matthews_coefficient() {
+    fs::write("dump/cryst_z_matthews.pdb", FIXTURE).unwrap();
+    let (mut pdb, _) = read_pdb("dump/cryst_z_matthews.pdb").unwrap();
+    let volume = 40.0 * 50.0 * 60.0;
+    // Z = 8 with 4 general positions in P 21 21 21 gives 2 molecules per asymmetric unit
+    let vm = pdb.matthews_coefficient(10_000.0, None).unwrap();
+    assert!((vm - volume / 80_000.0).abs() < 1e-9);
+    assert_eq!(pdb.matthews_coefficient(10_000.0, Some(2)), Some(vm));
+    let vm = pdb.matthews_coefficient(10_000.0, Some(1)).unwrap();
+    assert!((vm - volume / 40_000.0).abs() < 1e-9);
+
+    pdb.unit_cell_mut().set_z(None);
+    assert_eq!(pdb.matthews_coefficient(10_000.0, None), Some(vm));
+    assert_eq!(pdb.matthews_coefficient(0.0, None), None);
+    assert_eq!(pdb.matthews_coefficient(10_000.0, Some(0)), None);
+    assert_eq!(PDB::new().matthews_coefficient(10_000.0, None), None);
+}
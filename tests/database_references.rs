@@ -0,0 +1,136 @@
+use pdbtbx::*;
+use std::fs;
+
+#[test]
+fn parse_database_references() {
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    assert_eq!(pdb.database_reference_count(), 4);
+    let reference = pdb.chain_database_references('C').next().unwrap();
+    assert_eq!(reference.database(), "UNP");
+    assert_eq!(reference.accession(), "P39070");
+    assert_eq!(reference.database_id_code(), "HSLV_BACSU");
+    assert_eq!(reference.pdb_range(), [(1, ' '), (181, ' ')]);
+    assert_eq!(reference.database_range(), [(1, ' '), (181, ' ')]);
+    assert_eq!(reference.sequence_difference_count(), 0);
+    assert_eq!(pdb.clone().database_reference_count(), 4);
+
+    let (pdb, errors) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .all(|error| error.short_description() != "SEQADV without DBREF"));
+    let reference = pdb.chain_database_references('A').next().unwrap();
+    assert_eq!(reference.accession(), "P08716");
+    assert_eq!(reference.pdb_range(), [(467, ' '), (707, ' ')]);
+    assert!(reference.contains('A', 504));
+    assert!(!reference.contains('A', 465));
+    let differences = reference.sequence_differences().collect::<Vec<_>>();
+    assert_eq!(differences.len(), 3);
+    assert_eq!(differences[0].residue_name(), "HIS");
+    assert_eq!(differences[0].residue(), Some((465, ' ')));
+    assert_eq!(differences[0].database_residue_name(), "");
+    assert_eq!(differences[0].database_serial_number(), None);
+    assert_eq!(differences[0].comment(), "EXPRESSION TAG");
+    assert_eq!(differences[2].residue_name(), "ALA");
+    assert_eq!(differences[2].database_residue_name(), "SER");
+    assert_eq!(differences[2].database_serial_number(), Some(504));
+    assert_eq!(differences[2].comment(), "ENGINEERED");
+}
+
+#[test]
+fn save_database_references() {
+    fs::create_dir_all("dump").unwrap();
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    save_pdb(&pdb, "dump/database_references.pdb").unwrap();
+    let original = fs::read_to_string("example-pdbs/3b5j.pdb").unwrap();
+    let saved = fs::read_to_string("dump/database_references.pdb").unwrap();
+    let records = |text: &str| {
+        text.lines()
+            .filter(|line| line.starts_with("DBREF") || line.starts_with("SEQADV"))
+            .map(|line| line.trim_end().to_string())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(records(&saved), records(&original));
+    let (reloaded, _) = read_pdb("dump/database_references.pdb").unwrap();
+    assert_eq!(
+        reloaded.database_references().collect::<Vec<_>>(),
+        pdb.database_references().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn long_accession_codes() {
+    fs::create_dir_all("dump").unwrap();
+    fs::write(
+        "dump/database_references_long.pdb",
+        "DBREF1 4ABC A    1   120  UNP                  A0A0F6B4R1_ECOLX\n\
+         DBREF2 4ABC A     A0A0F6B4R1                        101         220\n\
+         SEQADV 4ABC MET A    0  UNP  A0A0F6B4R           INITIATING METHIONINE\n\
+         SEQADV 4ABC GLY B   10  UNP  P12345    ALA    15 CONFLICT\n\
+         DBREF2 4ABC B     P12345                              1          10\n\
+         END\n",
+    )
+    .unwrap();
+    let (pdb, errors) = read_pdb("dump/database_references_long.pdb").unwrap();
+    let short = errors
+        .iter()
+        .map(|error| error.short_description())
+        .collect::<Vec<_>>();
+    assert!(short.contains(&"SEQADV without DBREF"));
+    assert!(short.contains(&"DBREF2 without DBREF1"));
+    assert_eq!(pdb.database_reference_count(), 1);
+    let reference = pdb.database_references().next().unwrap();
+    assert_eq!(reference.database(), "UNP");
+    assert_eq!(reference.accession(), "A0A0F6B4R1");
+    assert_eq!(reference.database_id_code(), "A0A0F6B4R1_ECOLX");
+    assert_eq!(reference.database_range(), [(101, ' '), (220, ' ')]);
+    assert_eq!(reference.sequence_difference_count(), 1);
+
+    save_pdb(&pdb, "dump/database_references_long_saved.pdb").unwrap();
+    let saved = fs::read_to_string("dump/database_references_long_saved.pdb").unwrap();
+    assert!(saved.contains("DBREF1      A    1   120  UNP                  A0A0F6B4R1_ECOLX"));
+    assert!(saved.contains("DBREF2      A     A0A0F6B4R1"));
+    let (reloaded, _) = read_pdb("dump/database_references_long_saved.pdb").unwrap();
+    assert_eq!(
+        reloaded.database_references().collect::<Vec<_>>(),
+        pdb.database_references().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn invalid_records_are_skipped() {
+    fs::create_dir_all("dump").unwrap();
+    fs::write(
+        "dump/database_references_invalid.pdb",
+        "DBREF  3B5J A  467   707  UNP    P08716   HLYBP_ECOLX    467    707\n\
+         SEQADV 3B5J HIS A  465  UNP  P08716              EXPRESSION T\u{e9}G\n\
+         SEQADV 3B5J ALA A  504  UNP  P08716    SER   504 ENGINEERED\n\
+         DBREF  3B5J B  467   707  UNP    P08716   HLYBP_\u{e9}COLX    467    707\n\
+         DBREF1 4ABC C    1   120  UNP                  A0A0F6B4R1_\u{e9}COLX\n\
+         DBREF2 4ABC C     A0A0F6B4R1                        101         220\n\
+         DBREF1 4ABC D    1   120  UNP                  A0A0F6B4R1_ECOLX\n\
+         DBREF2 4ABC D     A0A0F6B4\tR1                        101         220\n\
+         END\n",
+    )
+    .unwrap();
+    let (pdb, errors) = read_pdb("dump/database_references_invalid.pdb").unwrap();
+    let warnings = errors
+        .iter()
+        .filter(|error| error.level() == ErrorLevel::LooseWarning)
+        .map(|error| (error.short_description(), error.context().linenumber()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            ("Invalid SEQADV", Some(2)),
+            ("Invalid DBREF", Some(4)),
+            ("Invalid DBREF1", Some(5)),
+            ("DBREF2 without DBREF1", Some(6)),
+            ("Invalid DBREF2", Some(8)),
+        ]
+    );
+    assert_eq!(pdb.database_reference_count(), 2);
+    let reference = pdb.chain_database_references('A').next().unwrap();
+    assert_eq!(reference.sequence_difference_count(), 1);
+    let reference = pdb.chain_database_references('D').next().unwrap();
+    assert_eq!(reference.accession(), "");
+}
@@ -0,0 +1,63 @@
+use pdbtbx::*;
+use std::fs;
+
+const STRUCTURE: &str = "\
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C
+TER       2      ALA A   1
+HETATM    3 NA    NA A 101      10.000   0.000   0.000  1.00 20.00          NA
+HETATM    4  O   HOH A 201      10.500   0.000   0.000  1.00 20.00           O
+HETATM    5  O   HOH A 202      20.000   0.000   0.000  0.50 20.00           O
+HETATM    6  H1  HOH A 202      20.500   0.500   0.000  0.50 20.00           H
+HETATM    7  O   HOH B 301      20.400   0.000   0.000  1.00 20.00           O
+HETATM    8  O   HOH B 302      30.000   0.000   0.000  1.00 20.00           O
+HETATM    9  O   HOH C 401      30.300   0.000   0.000  1.00 20.00           O
+HETATM   10  O   HOH C 402      40.000   0.000   0.000  1.00 20.00           O
+HETATM   11  O   HOH D 501      50.000   0.000   0.000  1.00 20.00           O
+HETATM   12  O   HOH D 502      50.000   0.800   0.000  1.00 20.00           O
+END
+";
+
+#[test]
+fn remove_duplicated_waters() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/duplicated_waters.pdb", STRUCTURE).unwrap();
+    let (mut pdb, _) = read_pdb("dump/duplicated_waters.pdb").unwrap();
+    let model = pdb.model_mut(0).unwrap();
+    let residues = |model: &Model| {
+        model
+            .all_chains()
+            .flat_map(|chain| {
+                chain
+                    .residues()
+                    .map(move |residue| format!("{}{}", chain.id(), residue.serial_number()))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(model.deduplicate_waters(0.0), 0);
+    // The water next to the sodium ion is kept, of A202 and B301 the lower occupancy water is
+    // removed and of B302 and C401, with the same occupancy, the later one is removed
+    assert_eq!(model.deduplicate_waters(0.6), 2);
+    assert_eq!(
+        residues(model),
+        vec!["A1", "A101", "A201", "B301", "B302", "C402", "D501", "D502"]
+    );
+    // The waters of chain D are further apart than the first cutoff
+    assert_eq!(model.deduplicate_waters(0.6), 0);
+    assert_eq!(model.deduplicate_waters(1.0), 1);
+    assert_eq!(residues(model).last().unwrap(), "D501");
+}
+
+#[test]
+fn empty_chains_are_removed() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/duplicated_waters_chains.pdb", STRUCTURE).unwrap();
+    let (mut pdb, _) = read_pdb("dump/duplicated_waters_chains.pdb").unwrap();
+    let model = pdb.model_mut(0).unwrap();
+    let chains = model.total_chain_count();
+    // Only A201, B302 and D501 are kept, so chain C loses all its Residues
+    assert_eq!(model.deduplicate_waters(15.0), 5);
+    assert_eq!(model.total_chain_count(), chains - 1);
+    assert_eq!(model.total_atom_count(), 5);
+    assert_eq!(model.residues_by_name("NA").count(), 1);
+}
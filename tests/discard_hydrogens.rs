@@ -0,0 +1,53 @@
+use pdbtbx::*;
+use std::fs;
+
+const FIXTURE: &str = "\
+ATOM      1  N   GLY A   1       0.000   0.000   0.000  1.00 10.00           N
+ATOM      2  H   GLY A   1       0.000   1.000   0.000  1.00 10.00           H
+ANISOU    2  H   GLY A   1      100    100    100      0      0      0       H
+ATOM      3  CA  GLY A   1       1.458   0.000   0.000  1.00 10.00           C
+ANISOU    3  CA  GLY A   1      100    100    100      0      0      0       C
+ATOM      4 1HA  GLY A   1       1.800   1.000   0.000  1.00 10.00
+ATOM      5 HA2  GLY A   1       1.800  -1.000   0.000  1.00 10.00
+ATOM      6  D   GLY A   1       0.000  -1.000   0.000  1.00 10.00           D
+TER       7      GLY A   1
+HETATM    8 HG   HG  A 101       5.000   5.000   5.000  1.00 10.00
+HETATM    9  O   HOH A 201       8.000   8.000   8.000  1.00 10.00           O
+HETATM   10  H1  HOH A 201       8.000   9.000   8.000  1.00 10.00           H
+MASTER        0    0    0    0    0    0    0    0    6    1    0    0
+END
+";
+
+#[test]
+fn discard_hydrogens() {
+    fs::write("dump/discard_hydrogens.pdb", FIXTURE).unwrap();
+    let (full, full_errors) = read_pdb("dump/discard_hydrogens.pdb").unwrap();
+    assert_eq!(full.all_atoms().count(), 9);
+    assert!(full_errors.is_empty(), "{:?}", full_errors);
+
+    let mut options = ReadOptions::new();
+    options.set_discard_hydrogens(true);
+    let (pdb, errors) = parse_with_options("dump/discard_hydrogens.pdb", &options).unwrap();
+    // No serial number gaps or MASTER checksum errors from the skipped atoms
+    assert_eq!(errors.len(), full_errors.len(), "{:?}", errors);
+    let names = pdb.all_atoms().map(|atom| atom.name()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["N", "CA", "HG", "O"]);
+    assert!(pdb
+        .all_atoms()
+        .find(|atom| atom.name() == "CA")
+        .unwrap()
+        .anisotropic_temperature_factors()
+        .is_some());
+}
+
+#[test]
+fn same_as_removing() {
+    let (mut full, full_errors) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    full.remove_atoms_by(|atom| atom.element() == "H");
+    let mut options = ReadOptions::new();
+    options.set_discard_hydrogens(true);
+    let (pdb, errors) = parse_with_options("example-pdbs/1ubq.pdb", &options).unwrap();
+    assert_eq!(errors.len(), full_errors.len());
+    assert_eq!(pdb.total_atom_count(), full.total_atom_count());
+    assert!(pdb.all_atoms().all(|atom| atom.element() != "H"));
+}
@@ -0,0 +1,53 @@
+use pdbtbx::*;
+
+#[test]
+fn split_protein_waters_ligands() {
+    let (mut pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let model = pdb.model_mut(0).unwrap();
+    let waters = model.drain_residues_by(|residue| residue.entity_type() == EntityType::Water);
+    let ligands = model.drain_residues_by(|residue| residue.entity_type() == EntityType::Other);
+
+    assert_eq!(model.total_atom_count(), 1911);
+    assert_eq!(model.hetero_chains().count(), 0);
+    assert_eq!(waters.total_atom_count(), 261);
+    assert_eq!(waters.chain_count(), 0);
+    assert!(waters
+        .hetero_residues()
+        .all(|residue| residue.id() == "HOH"));
+    assert_eq!(ligands.total_atom_count(), 42);
+    assert!(ligands
+        .hetero_residues()
+        .all(|residue| residue.id() == "12D"));
+    assert_eq!(ligands.serial_number(), model.serial_number());
+
+    // Re-add the ligands
+    model.join(ligands);
+    assert_eq!(model.total_atom_count(), 1911 + 42);
+}
+
+#[test]
+fn drain_atoms() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let removed = pdb.drain_atoms_by(|atom| atom.element() == "H");
+    assert!(!removed.is_empty());
+    assert!(pdb.atoms().all(|atom| atom.element() != "H"));
+    let (id, atom) = &removed[0];
+    assert_eq!(id.model_serial_number(), 0);
+    assert_eq!(id.chain_id(), 'A');
+    assert_eq!(id.name(), atom.name());
+    let residue = pdb
+        .residues()
+        .find(|residue| residue.serial_number() == id.residue_serial_number())
+        .unwrap();
+    assert!(residue.atom_by_name(id.name()).is_none());
+}
+
+#[test]
+fn drain_chains() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let removed = pdb.drain_chains_by(|chain| chain.id() == 'A');
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].chain_count(), 1);
+    assert_eq!(removed[0].hetero_chains().count(), 1);
+    assert_eq!(pdb.total_chain_count(), 0);
+}
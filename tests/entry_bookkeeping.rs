@@ -0,0 +1,98 @@
+use pdbtbx::*;
+use std::fs;
+
+mod common;
+
+const STRUCTURE: &str = "\
+HEADER    HYDROLASE                               14-FEB-97   1ABC
+OBSLTE     31-JAN-02 1ABC      1XYZ 2XYZ 3XYZ 4XYZ 5XYZ 6XYZ 7XYZ 8XYZ 9XYZ
+OBSLTE   2 31-JAN-02 1ABC      1QQQ
+TITLE     AN EXAMPLE STRUCTURE
+SPLIT      1VOQ 1VOR 1VOS
+CAVEAT     1ABC    THE CHIRALITY OF RESIDUE A 2 IS INCORRECT AND THE GEOMETRY
+CAVEAT   2 1ABC    OF THE LIGAND IS POOR
+SPRSDE     14-FEB-97 1ABC      2ABC 3ABC
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C
+END
+";
+
+fn records(text: &str) -> Vec<&str> {
+    text.lines()
+        .filter(|line| {
+            ["OBSLTE", "SPLIT", "CAVEAT", "SPRSDE"]
+                .iter()
+                .any(|record| line.starts_with(record))
+        })
+        .collect()
+}
+
+#[test]
+fn parse_bookkeeping() {
+    let (pdb, errors) = common::read_text("bookkeeping", STRUCTURE);
+    let obsolete = pdb.obsolete().unwrap();
+    assert_eq!(obsolete.id_code(), "1ABC");
+    assert_eq!(obsolete.date(), Date::new(2002, 1, 31));
+    assert_eq!(obsolete.entries().count(), 10);
+    assert_eq!(obsolete.entries().last(), Some("1QQQ"));
+
+    let supersedes = pdb.supersedes().unwrap();
+    assert_eq!(
+        supersedes.entries().collect::<Vec<_>>(),
+        vec!["2ABC", "3ABC"]
+    );
+    assert_eq!(
+        pdb.split_entries().collect::<Vec<_>>(),
+        vec!["1VOQ", "1VOR", "1VOS"]
+    );
+
+    let caveat = pdb.caveat().unwrap();
+    assert_eq!(caveat.id_code(), "1ABC");
+    assert_eq!(
+        caveat.text(),
+        "THE CHIRALITY OF RESIDUE A 2 IS INCORRECT AND THE GEOMETRY OF THE LIGAND IS POOR"
+    );
+    // The caveat is reported so scripts notice it
+    let warning = errors
+        .iter()
+        .find(|e| e.short_description() == "Caveat")
+        .unwrap();
+    assert_eq!(warning.level(), ErrorLevel::LooseWarning);
+    assert!(warning
+        .long_description()
+        .contains("THE GEOMETRY OF THE LIGAND"));
+}
+
+#[test]
+fn save_bookkeeping() {
+    let (pdb, _) = common::read_text("bookkeeping_save", STRUCTURE);
+    save_pdb(&pdb, "dump/bookkeeping_saved.pdb").unwrap();
+    let text = fs::read_to_string("dump/bookkeeping_saved.pdb").unwrap();
+    assert_eq!(records(&text), records(STRUCTURE));
+    let (reloaded, _) = read_pdb("dump/bookkeeping_saved.pdb").unwrap();
+    assert_eq!(reloaded.obsolete(), pdb.obsolete());
+    assert_eq!(reloaded.caveat(), pdb.caveat());
+}
+
+#[test]
+fn invalid_bookkeeping() {
+    let (pdb, errors) = common::read_text(
+        "bookkeeping_invalid",
+        "\
+OBSLTE     31-XXX-02 1ABC      1XYZ TOOLONG
+SPLIT      1VOQ AB
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C
+END
+",
+    );
+    let descriptions = errors
+        .iter()
+        .map(|e| e.short_description())
+        .collect::<Vec<_>>();
+    assert!(descriptions.contains(&"Invalid date"));
+    assert!(descriptions.contains(&"Invalid id code"));
+    assert!(!descriptions.contains(&"Caveat"));
+    assert_eq!(pdb.obsolete().unwrap().date(), None);
+    assert_eq!(pdb.split_entries().collect::<Vec<_>>(), vec!["1VOQ"]);
+    assert!(pdb.caveat().is_none());
+    assert!(pdb.supersedes().is_none());
+}
@@ -0,0 +1,88 @@
+use pdbtbx::*;
+use std::fs;
+
+#[test]
+fn parse_experimental_data() {
+    let (pdb, errors) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .all(|error| error.short_description() != "Invalid resolution"));
+    assert_eq!(
+        pdb.experimental_method(),
+        Some(ExperimentalMethod::XRayDiffraction)
+    );
+    assert_eq!(pdb.resolution(), Some(2.0));
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    assert_eq!(pdb.resolution(), Some(4.16));
+
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert_eq!(pdb.experimental_method(), None);
+    assert_eq!(pdb.resolution(), None);
+}
+
+#[test]
+fn nmr_and_multiple_methods() {
+    fs::create_dir_all("dump").unwrap();
+    fs::write(
+        "dump/experimental_nmr.pdb",
+        "EXPDTA    SOLUTION NMR; SOLUTION SCATTERING; MAGIC\n\
+         REMARK   2\n\
+         REMARK   2 RESOLUTION. NOT APPLICABLE.\n\
+         END\n",
+    )
+    .unwrap();
+    let (pdb, errors) = read_pdb("dump/experimental_nmr.pdb").unwrap();
+    assert_eq!(
+        pdb.experimental_method(),
+        Some(ExperimentalMethod::SolutionNmr)
+    );
+    assert_eq!(
+        pdb.experimental_methods().copied().collect::<Vec<_>>(),
+        vec![
+            ExperimentalMethod::SolutionNmr,
+            ExperimentalMethod::SolutionScattering
+        ]
+    );
+    assert_eq!(pdb.resolution(), None);
+    let short = errors
+        .iter()
+        .map(|error| error.short_description())
+        .collect::<Vec<_>>();
+    assert!(short.contains(&"Invalid experimental method"));
+    assert!(!short.contains(&"Invalid resolution"));
+
+    fs::write(
+        "dump/experimental_invalid.pdb",
+        "REMARK   2 RESOLUTION. HIGH.\nEND\n",
+    )
+    .unwrap();
+    let (pdb, errors) = read_pdb("dump/experimental_invalid.pdb").unwrap();
+    assert_eq!(pdb.resolution(), None);
+    assert!(errors
+        .iter()
+        .any(|error| error.short_description() == "Invalid resolution"));
+}
+
+#[test]
+fn save_experimental_methods() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    pdb.set_experimental_methods(vec![
+        ExperimentalMethod::XRayDiffraction,
+        ExperimentalMethod::NeutronDiffraction,
+    ]);
+    pdb.add_remark(2, "RESOLUTION.    1.80 ANGSTROMS.".to_string());
+    save_pdb(&pdb, "dump/experimental.pdb").unwrap();
+    let text = fs::read_to_string("dump/experimental.pdb").unwrap();
+    assert!(text.contains("EXPDTA    X-RAY DIFFRACTION; NEUTRON DIFFRACTION\n"));
+
+    let (saved, _) = read_pdb("dump/experimental.pdb").unwrap();
+    assert_eq!(
+        saved.experimental_methods().collect::<Vec<_>>(),
+        pdb.experimental_methods().collect::<Vec<_>>()
+    );
+    assert_eq!(saved.resolution(), Some(1.8));
+    assert_eq!(
+        ExperimentalMethod::from_pdb("solid-state nmr"),
+        Some(ExperimentalMethod::SolidStateNmr)
+    );
+}
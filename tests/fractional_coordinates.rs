@@ -0,0 +1,75 @@
+use pdbtbx::*;
+
+fn close(a: (f64, f64, f64), b: (f64, f64, f64)) -> bool {
+    (a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6 && (a.2 - b.2).abs() < 1e-6
+}
+
+/// Create a PDB with two atoms at the given fractional positions
+fn fractional_structure(positions: &[(f64, f64, f64)]) -> PDB {
+    let mut pdb = PDB::new();
+    let mut model = Model::new(0);
+    for (index, (x, y, z)) in positions.iter().enumerate() {
+        let atom = Atom::new(
+            index + 1,
+            [' ', 'O', ' ', ' '],
+            *x,
+            *y,
+            *z,
+            1.0,
+            20.0,
+            [' ', 'O'],
+            0,
+        )
+        .unwrap();
+        model.add_hetero_atom(atom, 'A', index + 1, None, ['H', 'O', 'H']);
+    }
+    pdb.add_model(model);
+    pdb
+}
+
+#[test]
+fn convert_with_unit_cell() {
+    let mut pdb = fractional_structure(&[(0.5, 0.25, 0.1), (1.0, 0.0, 0.75)]);
+    assert!(pdb.coordinates_are_fractional());
+    assert!(pdb.fractional_to_cartesian_coordinates().is_err());
+    assert!(pdb.apply_scale_to_coordinates().is_err());
+    assert!(pdb.coordinates_are_fractional());
+
+    pdb.set_unit_cell(UnitCell::new(10.0, 20.0, 30.0, 90.0, 90.0, 90.0));
+    assert!(pdb.apply_scale_to_coordinates().unwrap());
+    assert!(close(pdb.atom(0).unwrap().pos(), (5.0, 5.0, 3.0)));
+    assert!(close(pdb.atom(1).unwrap().pos(), (10.0, 0.0, 22.5)));
+    assert!(!pdb.coordinates_are_fractional());
+    assert!(!pdb.apply_scale_to_coordinates().unwrap());
+}
+
+#[test]
+fn convert_with_scale() {
+    // A monoclinic cell with a = 10, b = 20, c = 30 and beta = 120
+    let mut pdb = fractional_structure(&[(0.5, 0.5, 0.5)]);
+    let cell = UnitCell::new(10.0, 20.0, 30.0, 90.0, 120.0, 90.0);
+    let mut scale = Scale::new();
+    scale.set_transformation(cell.fractionalisation_matrix());
+    pdb.set_scale(scale);
+    pdb.fractional_to_cartesian_coordinates().unwrap();
+    // 0.5 a + 0.5 b + 0.5 c, with c at 120 degrees from a in the XZ plane
+    let expected = (
+        5.0 + 15.0 * 120.0_f64.to_radians().cos(),
+        10.0,
+        15.0 * 120.0_f64.to_radians().sin(),
+    );
+    assert!(close(pdb.atom(0).unwrap().pos(), expected));
+}
+
+#[test]
+fn cartesian_structure() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert!(!pdb.coordinates_are_fractional());
+    let original = pdb.atom(0).unwrap().pos();
+    let fractionalisation = pdb.scale().transformation().clone();
+    pdb.apply_transformation(&fractionalisation);
+    assert!(!close(pdb.atom(0).unwrap().pos(), original));
+    pdb.fractional_to_cartesian_coordinates().unwrap();
+    assert!(close(pdb.atom(0).unwrap().pos(), original));
+    assert!(!PDB::new().coordinates_are_fractional());
+}
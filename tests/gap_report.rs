@@ -0,0 +1,47 @@
+use pdbtbx::*;
+
+#[test]
+fn missing_loop() {
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    let missing = pdb.missing_residues();
+    assert_eq!(missing.len(), 72);
+    assert_eq!(missing[0], ('A', 175, "ILE".to_string()));
+
+    let report = pdb.gap_report().unwrap();
+    let (chain, gaps) = &report[0];
+    assert_eq!(*chain, 'A');
+    assert_eq!(gaps.len(), 1);
+    let gap = &gaps[0];
+    assert_eq!((gap.before(), gap.after()), (Some(174), Some(210)));
+    assert_eq!(gap.missing(), 35);
+    assert!(gap.numbering_jump() && gap.chain_break() && gap.remark_465());
+}
+
+#[test]
+fn missing_start() {
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    let report = pdb.gap_report().unwrap();
+    let (chain, gaps) = &report[2];
+    assert_eq!(*chain, 'D');
+    assert_eq!((gaps[0].before(), gaps[0].after()), (None, Some(2)));
+    assert_eq!(gaps[0].missing(), 1);
+}
+
+#[test]
+fn ca_only_chain_break() {
+    let (mut pdb, _) = read_pdb("example-pdbs/3b5j_ca.pdb").unwrap();
+    assert!(pdb.gap_report().unwrap()[0].1.is_empty());
+    for residue in pdb
+        .residues_mut()
+        .filter(|residue| residue.serial_number() > 600)
+    {
+        for atom in residue.atoms_mut() {
+            atom.set_pos((atom.x() + 10.0, atom.y(), atom.z())).unwrap();
+        }
+    }
+    let report = pdb.gap_report().unwrap();
+    let gaps = &report[0].1;
+    assert_eq!(gaps.len(), 1);
+    assert_eq!((gaps[0].before(), gaps[0].after()), (Some(600), Some(601)));
+    assert!(gaps[0].chain_break() && !gaps[0].numbering_jump());
+}
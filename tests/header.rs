@@ -0,0 +1,91 @@
+use pdbtbx::*;
+use std::fs;
+
+#[test]
+fn parse_header_record() {
+    let (pdb, errors) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert_eq!(pdb.classification(), "TRANSPORT PROTEIN");
+    assert_eq!(pdb.deposition_date(), Date::new(2007, 10, 26));
+    assert_eq!(pdb.identifier(), Some("3B5J"));
+    assert!(errors
+        .iter()
+        .all(|error| error.context().linenumber() != Some(1)));
+
+    let (pdb, _) = read_pdb("example-pdbs/liquid.pdb").unwrap();
+    assert_eq!(
+        pdb.classification(),
+        "Water Cubic Box (24.859 Ang, 512 TIP3P)"
+    );
+    assert_eq!(pdb.deposition_date(), None);
+    assert_eq!(pdb.identifier(), None);
+}
+
+#[test]
+fn dates() {
+    let date = Date::from_pdb("26-OCT-07").unwrap();
+    assert_eq!((date.year(), date.month(), date.day()), (2007, 10, 26));
+    assert_eq!(date.to_string(), "26-OCT-07");
+    assert_eq!(Date::from_pdb("24-FEB-71").unwrap().year(), 1971);
+    assert_eq!(Date::from_pdb("29-FEB-00"), Date::new(2000, 2, 29));
+    assert!(Date::from_pdb("29-FEB-99").is_none());
+    assert!(Date::from_pdb("31-APR-07").is_none());
+    assert!(Date::from_pdb("26-OKT-07").is_none());
+    assert!(Date::from_pdb("2007-10-26").is_none());
+    assert!(Date::from_pdb("26-OCT-07") < Date::from_pdb("01-JAN-08"));
+}
+
+#[test]
+fn invalid_date() {
+    fs::write(
+        "dump/header_date.pdb",
+        "\
+HEADER    HYDROLASE                               31-APR-07   1ABC
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 20.00           C
+END
+",
+    )
+    .unwrap();
+    let (pdb, errors) = read_pdb("dump/header_date.pdb").unwrap();
+    assert_eq!(pdb.deposition_date(), None);
+    assert_eq!(pdb.classification(), "HYDROLASE");
+    assert_eq!(pdb.identifier(), Some("1ABC"));
+    let error = errors
+        .iter()
+        .find(|error| error.short_description() == "Invalid deposition date")
+        .unwrap();
+    assert_eq!(error.level(), ErrorLevel::LooseWarning);
+}
+
+#[test]
+fn save_header_record() {
+    let original = fs::read_to_string("example-pdbs/3b5j.pdb").unwrap();
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    save_pdb(&pdb, "dump/header.pdb").unwrap();
+    let saved = fs::read_to_string("dump/header.pdb").unwrap();
+    assert_eq!(
+        saved.lines().next().unwrap(),
+        original.lines().next().unwrap().trim_end()
+    );
+
+    let mut pdb = PDB::new();
+    pdb.set_classification("DE NOVO PROTEIN").unwrap();
+    pdb.set_deposition_date(Date::new(2021, 3, 4));
+    pdb.set_identifier(Some("7ABC")).unwrap();
+    assert!(pdb.set_identifier(Some("7ABCD")).is_err());
+    assert!(pdb.set_classification(&"X".repeat(41)).is_err());
+    save_pdb(&pdb, "dump/header_new.pdb").unwrap();
+    let (saved, _) = read_pdb("dump/header_new.pdb").unwrap();
+    assert_eq!(saved.classification(), "DE NOVO PROTEIN");
+    assert_eq!(saved.deposition_date(), Date::new(2021, 3, 4));
+    assert_eq!(saved.identifier(), Some("7ABC"));
+    assert_eq!(saved.name(), "7ABC");
+
+    // A classification of the full 40 characters keeps the date and id code in their columns
+    pdb.set_classification(&"X".repeat(40)).unwrap();
+    save_pdb(&pdb, "dump/header_full.pdb").unwrap();
+    let saved = fs::read_to_string("dump/header_full.pdb").unwrap();
+    assert_eq!(
+        saved.lines().next().unwrap(),
+        format!("HEADER    {}04-MAR-21   7ABC", "X".repeat(40))
+    );
+}
@@ -0,0 +1,173 @@
+use pdbtbx::*;
+use std::fs;
+
+#[test]
+fn parse_helices() {
+    let (pdb, errors) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert_eq!(pdb.helix_count(), 11);
+    assert!(errors.iter().all(
+        |error| error.short_description() != "Could not recognise tag."
+            || !error.context().to_string().contains("HELIX")
+    ));
+    let helix = pdb.helices().next().unwrap();
+    assert_eq!(helix.serial_number(), 1);
+    assert_eq!(helix.id(), "1");
+    assert_eq!(
+        helix.residue_names(),
+        ["GLY".to_string(), "GLN".to_string()]
+    );
+    assert_eq!(helix.chain_ids(), ['A', 'A']);
+    assert_eq!(helix.residue_serial_numbers(), [507, 516]);
+    assert_eq!(helix.insertion_codes(), [' ', ' ']);
+    assert_eq!(helix.class(), 1);
+    assert_eq!(helix.comment(), "");
+    assert_eq!(helix.length(), 10);
+    assert!(helix.contains('A', 510));
+    assert!(!helix.contains('A', 517));
+    assert!(!helix.contains('B', 510));
+}
+
+#[test]
+fn save_helices() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    save_pdb(&pdb, "dump/helix.pdb").unwrap();
+    let original = fs::read_to_string("example-pdbs/3b5j.pdb").unwrap();
+    let saved = fs::read_to_string("dump/helix.pdb").unwrap();
+    let helix_lines = |text: &str| {
+        text.lines()
+            .filter(|line| line.starts_with("HELIX"))
+            .map(|line| line.trim_end().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(helix_lines(&saved), helix_lines(&original));
+
+    let (reread, errors) = read_pdb("dump/helix.pdb").unwrap();
+    assert_eq!(
+        reread.helices().collect::<Vec<_>>(),
+        pdb.helices().collect::<Vec<_>>()
+    );
+    assert!(errors
+        .iter()
+        .all(|error| error.short_description() != "MASTER checksum failed"));
+}
+
+#[test]
+fn optional_fields() {
+    fs::write(
+        "dump/helix_comment.pdb",
+        "\
+HELIX    1  H1 ALA A    1  ALA A    3A 5 SHORT 3-10 HELIX
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 20.00           C
+ATOM      2  CA  ALA A   2       3.800   0.000   0.000  1.00 20.00           C
+ATOM      3  CA  ALA A   3       7.600   0.000   0.000  1.00 20.00           C
+ATOM      4  CA  ALA A   3A     11.400   0.000   0.000  1.00 20.00           C
+END
+",
+    )
+    .unwrap();
+    let (pdb, _) = read_pdb("dump/helix_comment.pdb").unwrap();
+    let helix = pdb.helices().next().unwrap();
+    assert_eq!(helix.id(), "H1");
+    assert_eq!(helix.insertion_codes(), [' ', 'A']);
+    assert_eq!(helix.class(), 5);
+    assert_eq!(helix.comment(), "SHORT 3-10 HELIX");
+    assert_eq!(helix.length(), 3);
+}
+
+#[test]
+fn validate_helix_order() {
+    let mut pdb = PDB::new();
+    pdb.add_helix(
+        Helix::new(
+            1,
+            "1",
+            [['A', 'L', 'A']; 2],
+            ['A', 'A'],
+            [5, 10],
+            [' ', ' '],
+        )
+        .unwrap(),
+    );
+    assert!(validate_pdb(&pdb).is_empty());
+    pdb.add_helix(
+        Helix::new(
+            2,
+            "2",
+            [['A', 'L', 'A']; 2],
+            ['A', 'A'],
+            [10, 5],
+            [' ', ' '],
+        )
+        .unwrap(),
+    );
+    pdb.add_helix(
+        Helix::new(
+            3,
+            "3",
+            [['A', 'L', 'A']; 2],
+            ['A', 'B'],
+            [5, 10],
+            [' ', ' '],
+        )
+        .unwrap(),
+    );
+    pdb.add_helix(
+        Helix::new(4, "4", [['A', 'L', 'A']; 2], ['A', 'A'], [5, 5], ['B', 'A']).unwrap(),
+    );
+    let errors = validate_pdb(&pdb);
+    assert_eq!(errors.len(), 3);
+    assert!(errors
+        .iter()
+        .all(|error| error.short_description() == "Invalid helix range"
+            && ValidationCheck::of(error) == Some(ValidationCheck::HelixRanges)));
+    assert_eq!(
+        validate_with(&pdb, &ValidationProfile::preset(ValidationPreset::Minimal)).len(),
+        3
+    );
+}
+
+#[test]
+fn invalid_helices() {
+    assert!(Helix::new(
+        1,
+        "LONG",
+        [['A', 'L', 'A']; 2],
+        ['A', 'A'],
+        [1, 2],
+        [' ', ' ']
+    )
+    .is_none());
+    let mut helix =
+        Helix::new(1, "1", [['A', 'L', 'A']; 2], ['A', 'A'], [1, 2], [' ', ' ']).unwrap();
+    assert!(helix.set_class(11).is_err());
+    assert!(helix.set_comment(&"X".repeat(31)).is_err());
+    assert_eq!(helix.length(), 2);
+}
+
+#[test]
+fn invalid_helix_records_are_skipped() {
+    let input = fs::read_to_string("example-pdbs/3b5j.pdb")
+        .unwrap()
+        .replacen("GLY A  507  GLN A  516", "GLY \u{e9}  507  GLN A  516", 1)
+        .replacen(
+            "SER A  558  SER A  564  1     ",
+            "SER A  558  SER A  564  1 \t   ",
+            1,
+        );
+    let _ = fs::create_dir("dump");
+    fs::write("dump/helix_invalid.pdb", input).unwrap();
+    let (pdb, errors) = read_pdb("dump/helix_invalid.pdb").unwrap();
+    assert_eq!(pdb.helix_count(), 9);
+    let warnings = errors
+        .iter()
+        .filter(|error| error.short_description() == "Invalid HELIX")
+        .map(|error| (error.level(), error.context().linenumber()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            (ErrorLevel::LooseWarning, Some(378)),
+            (ErrorLevel::LooseWarning, Some(380))
+        ]
+    );
+}
@@ -0,0 +1,52 @@
+use pdbtbx::*;
+use std::fs;
+
+fn het_checksum_failed(errors: &[PDBError]) -> bool {
+    errors
+        .iter()
+        .any(|e| format!("{}", e).contains("number of HET records"))
+}
+
+#[test]
+fn stale_het_records_removed_on_save() {
+    let (mut pdb, errors) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    assert!(!het_checksum_failed(&errors));
+    assert_eq!(pdb.het_count(), 2);
+    let het = pdb.hets().next().unwrap();
+    assert_eq!(het.id(), "ADP");
+    assert_eq!(het.chain_id(), 'A');
+    assert_eq!(het.residue_serial_number(), 905);
+    assert_eq!(het.atom_count(), 27);
+
+    // Delete the ligand of chain B
+    pdb.remove_residues_by(|residue| residue.id() == "ADP" && residue.serial_number() == 906);
+    let _ = fs::create_dir("dump");
+    save_pdb(&pdb, "dump/1yyf_het.pdb").unwrap();
+
+    let (reparsed, errors) = read_pdb("dump/1yyf_het.pdb").unwrap();
+    assert!(!het_checksum_failed(&errors));
+    assert_eq!(reparsed.het_count(), 1);
+    assert_eq!(reparsed.hets().next().unwrap().chain_id(), 'A');
+}
+
+#[test]
+fn invalid_het_record_is_skipped() {
+    let input = fs::read_to_string("example-pdbs/1yyf.pdb")
+        .unwrap()
+        .replacen(
+            "HET    ADP  A 905      27",
+            "HET    ADP  \u{e9} 905      27",
+            1,
+        );
+    let _ = fs::create_dir("dump");
+    fs::write("dump/1yyf_invalid_het.pdb", input).unwrap();
+    let (pdb, errors) = read_pdb("dump/1yyf_invalid_het.pdb").unwrap();
+    assert_eq!(pdb.het_count(), 1);
+    assert_eq!(pdb.hets().next().unwrap().chain_id(), 'B');
+    let error = errors
+        .iter()
+        .find(|e| e.short_description() == "Invalid HET")
+        .unwrap();
+    assert_eq!(error.level(), ErrorLevel::LooseWarning);
+    assert_eq!(error.context().linenumber(), Some(1117));
+}
@@ -0,0 +1,73 @@
+use pdbtbx::*;
+use std::fs;
+
+#[test]
+fn complete_ligands() {
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    assert_eq!(pdb.het_count(), 2);
+    assert!(validate_hetatm_completeness(&pdb).is_empty());
+    assert!(validate_hetatm_has_conect(&pdb).is_empty());
+}
+
+#[test]
+fn missing_ligand_atoms() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    pdb.remove_atoms_by(|atom| ["O1B", "O2B", "O3B"].contains(&atom.name().as_str()));
+    let errors = validate_hetatm_completeness(&pdb);
+    // One warning per missing Atom, named by the CONECT records
+    assert_eq!(errors.len(), 6);
+    assert!(errors
+        .iter()
+        .all(|error| error.level() == ErrorLevel::StrictWarning));
+    assert!(errors[0].long_description().starts_with(
+        "Hetero group ADP A/905 in Model 0 misses the Atom with serial number 9167, which is bonded to its Atom PB"
+    ));
+}
+
+#[test]
+fn missing_atoms_without_conect() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    pdb.remove_connections_by(|_, _| true);
+    pdb.remove_atoms_by(|atom| atom.name() == "O1B");
+    let errors = validate_hetatm_completeness(&pdb);
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].long_description().contains(
+        "ADP A/905 in Model 0 has 26 Atoms (26 heavy Atoms) while its HET record declares 27 and its formula 27 heavy Atoms"
+    ));
+    assert!(errors[0]
+        .long_description()
+        .ends_with("(missing Atom 1 of 1)."));
+
+    let errors = validate_hetatm_has_conect(&pdb);
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].level(), ErrorLevel::GeneralWarning);
+    assert_eq!(
+        ValidationCheck::of(&errors[0]),
+        Some(ValidationCheck::HetatmConnections)
+    );
+}
+
+#[test]
+fn formula_heavy_atoms() {
+    let _ = fs::create_dir("dump");
+    fs::write(
+        "dump/hetatm_formula.pdb",
+        "\
+HET    ACT  A   1       2
+FORMUL   2  ACT    C2 H3 O2 1-
+HETATM    1  C   ACT A   1       1.000   0.000   0.000  1.00 20.00           C
+HETATM    2  CH3 ACT A   1       2.500   0.000   0.000  1.00 20.00           C
+CONECT    1    2
+END
+",
+    )
+    .unwrap();
+    let (pdb, _) = read_pdb("dump/hetatm_formula.pdb").unwrap();
+    // The HET record is satisfied, but the formula gives two more heavy atoms
+    let errors = validate_hetatm_completeness(&pdb);
+    assert_eq!(errors.len(), 2);
+    assert!(errors[1]
+        .long_description()
+        .ends_with("(missing Atom 2 of 2)."));
+    assert!(validate_hetatm_has_conect(&pdb).is_empty());
+}
@@ -0,0 +1,75 @@
+use pdbtbx::*;
+use std::fs;
+
+#[test]
+fn parsed_atoms_know_their_record() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert_eq!(pdb.atoms().filter(|atom| !atom.hetero()).count(), 1231);
+    assert!(pdb.hetero_atoms().all(|atom| atom.hetero()));
+    assert!(pdb
+        .chains()
+        .flat_map(|chain| chain.atoms())
+        .all(|atom| !atom.hetero()));
+}
+
+#[test]
+fn flag_independent_of_alternate_location() {
+    let mut atom = Atom::new(
+        1,
+        [' ', 'O', ' ', ' '],
+        0.0,
+        0.0,
+        0.0,
+        1.0,
+        0.0,
+        ['O', ' '],
+        0,
+    )
+    .unwrap();
+    assert!(!atom.hetero());
+    atom.set_hetero(true);
+    atom.set_alternate_location('B').unwrap();
+    assert!(atom.hetero());
+    assert_eq!(atom.alternate_location(), 'B');
+    atom.set_hetero(false);
+    assert!(!atom.hetero());
+    assert_eq!(atom.alternate_location(), 'B');
+}
+
+#[test]
+fn moved_atoms_keep_their_record() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let water = pdb.hetero_atoms().next().unwrap().clone();
+    let serial = water.serial_number();
+    let model = pdb.model_mut(0).unwrap();
+    model.remove_atoms_by(|atom| atom.serial_number() == serial);
+    model.add_atom(water, 'A', 200, None, ['H', 'O', 'H']);
+
+    let mut protein = Atom::new(
+        9000,
+        [' ', 'C', 'A', ' '],
+        0.0,
+        0.0,
+        0.0,
+        1.0,
+        0.0,
+        ['C', ' '],
+        0,
+    )
+    .unwrap();
+    protein.set_hetero(false);
+    model.add_hetero_atom(protein, 'A', 201, None, ['M', 'S', 'E']);
+
+    save_pdb(&pdb, "dump/hetero_flag.pdb").unwrap();
+    let saved = fs::read_to_string("dump/hetero_flag.pdb").unwrap();
+    let record = |serial: usize| {
+        saved
+            .lines()
+            .find(|line| line.get(6..11).map(str::trim) == Some(&serial.to_string()))
+            .unwrap()[..6]
+            .to_string()
+    };
+    assert_eq!(record(serial), "HETATM");
+    assert_eq!(record(9000), "HETATM");
+    assert_eq!(record(1), "ATOM  ");
+}
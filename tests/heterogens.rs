@@ -0,0 +1,124 @@
+use pdbtbx::*;
+use std::fs;
+
+const LONG_NAMES: &str = "\
+HET    NAG  A 201      14
+HETNAM     NAG 2-ACETAMIDO-2-DEOXY-BETA-D-GLUCOPYRANOSE
+HETSYN     NAG N-ACETYL-BETA-D-GLUCOSAMINE; 2-ACETAMIDO-2-DEOXY-BETA-D-
+HETSYN   2 NAG  GLUCOSE; 2-ACETAMIDO-2-DEOXY-D-GLUCOSE; 2-ACETAMIDO-2-DEOXY-
+HETSYN   3 NAG  GLUCOSE; N-ACETYL-D-GLUCOSAMINE
+FORMUL   2  NAG    C8 H15 N O6
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C
+TER       2      ALA A   1
+HETATM    3  C1  NAG A 201       1.000   0.000   0.000  1.00 20.00           C
+END
+";
+
+#[test]
+fn parse_heterogens() {
+    let (pdb, errors) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert!(
+        errors
+            .iter()
+            .all(|e| e.short_description() != "Invalid heterogen"),
+        "{:?}",
+        errors
+    );
+    assert_eq!(pdb.heterogen_count(), 2);
+    let ligand = pdb.heterogen("12D").unwrap();
+    // The name is continued after a hyphen, so without a space
+    assert_eq!(
+        ligand.name(),
+        "2',3'-O-[(1R,6R)-2,4,6-TRINITROCYCLOHEXA-2,4-DIENE-1,1-DIYL]ADENOSINE 5'-(TRIHYDROGEN DIPHOSPHATE)"
+    );
+    assert_eq!(ligand.formula(), "C16 H16 N8 O16 P2");
+    assert_eq!(ligand.component_number(), Some(2));
+    assert_eq!(ligand.synonyms().count(), 0);
+    let water = pdb.heterogen("HOH").unwrap();
+    assert_eq!(water.name(), "");
+    assert_eq!(water.formula(), "261(H2 O)");
+    assert_eq!(
+        pdb.heterogens().map(Heterogen::id).collect::<Vec<_>>(),
+        vec!["12D", "HOH"]
+    );
+}
+
+#[test]
+fn lookup_by_residue() {
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    let residue = pdb
+        .hetero_residues()
+        .find(|residue| residue.id() == "ADP")
+        .unwrap();
+    let heterogen = pdb.heterogen(&residue.id()).unwrap();
+    assert_eq!(heterogen.name(), "ADENOSINE-5'-DIPHOSPHATE");
+    assert_eq!(heterogen.formula(), "2(C10 H15 N5 O10 P2)");
+    assert_eq!(
+        pdb.heterogen_groups("ADP")
+            .map(|het| (het.chain_id(), het.atom_count()))
+            .collect::<Vec<_>>(),
+        vec![('A', 27), ('B', 27)]
+    );
+    assert!(pdb.heterogen("ATP").is_none());
+}
+
+#[test]
+fn continued_synonyms() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/heterogens.pdb", LONG_NAMES).unwrap();
+    let (pdb, _) = read_pdb("dump/heterogens.pdb").unwrap();
+    let sugar = pdb.heterogen("NAG").unwrap();
+    assert_eq!(
+        sugar.synonyms().collect::<Vec<_>>(),
+        vec![
+            "N-ACETYL-BETA-D-GLUCOSAMINE",
+            "2-ACETAMIDO-2-DEOXY-BETA-D-GLUCOSE",
+            "2-ACETAMIDO-2-DEOXY-D-GLUCOSE",
+            "2-ACETAMIDO-2-DEOXY-GLUCOSE",
+            "N-ACETYL-D-GLUCOSAMINE"
+        ]
+    );
+    assert_eq!(sugar.formula(), "C8 H15 N O6");
+}
+
+#[test]
+fn save_heterogens() {
+    let _ = fs::create_dir("dump");
+    for (input, output) in [
+        ("example-pdbs/3b5j.pdb", "dump/3b5j_heterogens.pdb"),
+        (
+            "dump/heterogens_saved_input.pdb",
+            "dump/heterogens_saved.pdb",
+        ),
+    ] {
+        fs::write("dump/heterogens_saved_input.pdb", LONG_NAMES).unwrap();
+        let (pdb, _) = read_pdb(input).unwrap();
+        save_pdb(&pdb, output).unwrap();
+        let (reloaded, _) = read_pdb(output).unwrap();
+        assert_eq!(
+            reloaded.heterogens().collect::<Vec<_>>(),
+            pdb.heterogens().collect::<Vec<_>>()
+        );
+        let text = fs::read_to_string(output).unwrap();
+        assert!(text.lines().all(|line| line.len() <= 80));
+    }
+    let text = fs::read_to_string("dump/3b5j_heterogens.pdb").unwrap();
+    assert!(text.contains("\nFORMUL   3  HOH   *261(H2 O)\n"));
+}
+
+#[test]
+fn new_heterogen() {
+    assert!(Heterogen::new("").is_none());
+    assert!(Heterogen::new("HEME").is_none());
+    let mut heme = Heterogen::new("HEM").unwrap();
+    heme.set_name("PROTOPORPHYRIN IX CONTAINING FE").unwrap();
+    assert!(heme.add_synonym("HEME; HAEM").is_err());
+    heme.add_synonym("HEME").unwrap();
+    heme.set_formula("C34 H32 FE N4 O4").unwrap();
+    let mut pdb = PDB::new();
+    pdb.add_heterogen(heme);
+    assert_eq!(
+        pdb.heterogen("HEM").unwrap().synonyms().next(),
+        Some("HEME")
+    );
+}
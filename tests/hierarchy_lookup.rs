@@ -0,0 +1,73 @@
+use pdbtbx::*;
+
+mod common;
+
+const STRUCTURE: &str = "\
+MODEL        1
+ATOM      1  N   ALA A   1       0.000   0.000   0.000  1.00 10.00           N
+ATOM      2  CA  ALA A   1       1.458   0.000   0.000  1.00 10.00           C
+ATOM      3  CA  GLY A   2       3.800   0.000   0.000  1.00 10.00           C
+TER       4      GLY A   2
+HETATM    5  O   HOH A 101      10.000   0.000   0.000  1.00 20.00           O
+ENDMDL
+MODEL        2
+ATOM      1  N   ALA A   1       0.100   0.000   0.000  1.00 10.00           N
+ATOM      2  CA  ALA A   1       1.558   0.000   0.000  1.00 10.00           C
+ATOM      3  CA  GLY A   2       3.900   0.000   0.000  1.00 10.00           C
+TER       4      GLY A   2
+HETATM    5  O   HOH A 101      10.100   0.000   0.000  1.00 20.00           O
+ENDMDL
+END
+";
+
+#[test]
+fn first_model_lookup() {
+    let mut pdb = common::read_text("hierarchy_lookup", STRUCTURE).0;
+    let chain = pdb.chain_by_id('A').unwrap();
+    assert_eq!(chain.residue_count(), 2);
+    assert!(pdb.chain_by_id('B').is_none());
+
+    // Normal and Hetero Chains with the same id are both searched
+    assert_eq!(pdb.residue_by_id(('A', 2).into()).unwrap().id(), "GLY");
+    assert_eq!(pdb.residue_by_id(('A', 101).into()).unwrap().id(), "HOH");
+    assert!(pdb.residue_by_id(('A', 3).into()).is_none());
+
+    pdb.residue_by_id_mut(('A', 2).into())
+        .unwrap()
+        .set_serial_number(3);
+    assert!(pdb.residue_by_id(('A', 3).into()).is_some());
+    // Only the first Model is changed
+    assert!(pdb
+        .model(1)
+        .unwrap()
+        .residue_by_id(('A', 2).into())
+        .is_some());
+
+    pdb.chain_by_id_mut('A').unwrap().set_id('B').unwrap();
+    assert!(pdb.chain_by_id('B').is_some());
+    assert_eq!(pdb.model(1).unwrap().chain_by_id('A').unwrap().id(), 'A');
+
+    assert!(PDB::new().chain_by_id('A').is_none());
+    assert!(PDB::new().residue_by_id(('A', 1).into()).is_none());
+}
+
+#[test]
+fn atoms_with_hierarchy() {
+    let pdb = common::read_text("hierarchy_atoms", STRUCTURE).0;
+    let atoms = pdb
+        .atoms_with_hierarchy()
+        .map(|(chain, residue, atom)| (chain.id(), residue.serial_number(), atom.name()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        atoms,
+        vec![
+            ('A', 1, "N".to_string()),
+            ('A', 1, "CA".to_string()),
+            ('A', 2, "CA".to_string()),
+            ('A', 101, "O".to_string())
+        ]
+    );
+    let (_, _, last) = pdb.model(1).unwrap().atoms_with_hierarchy().last().unwrap();
+    assert_eq!(last.x(), 10.1);
+    assert_eq!(PDB::new().atoms_with_hierarchy().count(), 0);
+}
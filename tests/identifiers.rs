@@ -0,0 +1,64 @@
+use pdbtbx::*;
+use std::fs;
+
+const LOWERCASE_CHAINS: &str =
+    "ATOM      1  N   ALA a   1      11.104   6.134  -6.504  1.00  0.00           N
+ATOM      2  CA  ALA a   1      11.639   6.071  -5.147  1.00  0.00           C
+TER       3      ALA a   1
+ATOM      4  N   GLY 1   1      13.104   6.134  -6.504  1.00  0.00           N
+TER       5      GLY 1   1
+HETATM    6  O   HOH z   2      15.000   6.000  -6.000  1.00  0.00           O
+END
+";
+
+#[test]
+fn lowercase_and_digit_chain_ids() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/lowercase_chains.pdb", LOWERCASE_CHAINS).unwrap();
+    let (pdb, errors) = read_pdb("dump/lowercase_chains.pdb").unwrap();
+    assert!(
+        errors
+            .iter()
+            .all(|e| e.level() != ErrorLevel::BreakingError),
+        "{:?}",
+        errors
+    );
+    assert_eq!(
+        pdb.all_chains().map(|c| c.id()).collect::<Vec<char>>(),
+        vec!['a', '1', 'z']
+    );
+
+    save_pdb(&pdb, "dump/lowercase_chains_saved.pdb").unwrap();
+    let (reparsed, _) = read_pdb("dump/lowercase_chains_saved.pdb").unwrap();
+    assert_eq!(
+        reparsed.all_chains().map(|c| c.id()).collect::<Vec<char>>(),
+        vec!['a', '1', 'z']
+    );
+    assert_eq!(reparsed.all_atoms().count(), 4);
+}
+
+#[test]
+fn invalid_characters_are_reported() {
+    let _ = fs::create_dir("dump");
+    let text = LOWERCASE_CHAINS
+        .replace("GLY 1", "GLY _")
+        .replace("HOH z", "HOH é");
+    fs::write("dump/invalid_chains.pdb", text).unwrap();
+    let (pdb, errors) = read_pdb("dump/invalid_chains.pdb").unwrap();
+    assert_eq!(pdb.all_atoms().count(), 2);
+    let messages = errors
+        .iter()
+        .filter(|e| e.short_description() == "Invalid characters in atom")
+        .map(|e| e.long_description())
+        .collect::<Vec<&str>>();
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0].contains("'_' (U+005F)"));
+    assert!(messages[1].contains("'é' (U+00E9)"));
+
+    let mut chain = Chain::new('a').unwrap();
+    assert!(Chain::new('_').is_none());
+    assert!(chain.set_id('9').is_ok());
+    let error = chain.set_id('-').unwrap_err();
+    assert!(error.contains("'-' (U+002D)"));
+    assert_eq!(chain.id(), '9');
+}
@@ -0,0 +1,126 @@
+use pdbtbx::*;
+
+/// A small linear congruential generator, to get reproducible random edits without dependencies
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self, max: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % max.max(1)
+    }
+}
+
+fn errors(errors: Vec<PDBError>) -> Vec<String> {
+    errors.iter().map(|e| format!("{:?}", e)).collect()
+}
+
+/// Open 1ubq and make it an ensemble of the given number of Models
+fn ensemble(models: usize) -> PDB {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let first = pdb.model(0).unwrap().clone();
+    for serial in 2..=models {
+        let mut model = first.clone();
+        model.set_serial_number(serial);
+        pdb.add_model(model);
+    }
+    pdb
+}
+
+/// Make a random edit to the given PDB
+fn edit(pdb: &mut PDB, random: &mut Random) {
+    let model_index = random.next(pdb.model_count());
+    let model_count = pdb.model_count();
+    let model = pdb.model_mut(model_index).unwrap();
+    let atom_index = random.next(model.atom_count());
+    match random.next(10) {
+        0 => {
+            if let Some(atom) = model.atom_mut(atom_index) {
+                atom.set_name("XX").unwrap();
+            }
+        }
+        1 => {
+            let serial = model.atom(atom_index).map_or(0, |a| a.serial_number());
+            model.remove_atoms_by(|atom| atom.serial_number() == serial);
+        }
+        2 => model.apply_transformation(&TransformationMatrix::translation(1.0, 0.0, 0.0)),
+        3 => model.set_serial_number(100 + random.next(100)),
+        4 => {
+            if model_count > 2 {
+                pdb.remove_model(model_index);
+            }
+        }
+        5 => {
+            let mut model = model.clone();
+            model.set_serial_number(200 + random.next(100));
+            pdb.add_model(model);
+        }
+        6 => pdb.renumber(),
+        7 => {
+            let serial = model
+                .residue(random.next(model.residue_count()))
+                .map_or(0, |r| r.serial_number());
+            model.remove_residues_by(|residue| residue.serial_number() == serial);
+        }
+        8 => {
+            let first = pdb.model(0).unwrap().clone();
+            let serial = pdb.model(model_index).unwrap().serial_number();
+            let target = pdb.models_mut().nth(model_index).unwrap();
+            *target = first;
+            target.set_serial_number(serial);
+        }
+        _ => {
+            if let Some(atom) = pdb.atom_mut(atom_index) {
+                atom.set_element("S").unwrap();
+            }
+        }
+    }
+}
+
+#[test]
+fn same_errors_as_full_validation() {
+    for seed in 0..5 {
+        let mut random = Random(seed);
+        let mut pdb = ensemble(4);
+        assert!(validate_incremental(&pdb).is_empty());
+        for _ in 0..40 {
+            edit(&mut pdb, &mut random);
+            assert_eq!(
+                errors(validate_incremental(&pdb)),
+                errors(validate_pdb(&pdb)),
+                "seed {}",
+                seed
+            );
+            // Validating again without edits uses the cached results
+            assert_eq!(
+                errors(validate_incremental(&pdb)),
+                errors(validate_pdb(&pdb))
+            );
+        }
+    }
+}
+
+#[test]
+fn clones_validate_independently() {
+    let mut pdb = ensemble(3);
+    assert!(validate_incremental(&pdb).is_empty());
+    let mut copy = pdb.clone();
+    copy.model_mut(1)
+        .unwrap()
+        .atom_mut(0)
+        .unwrap()
+        .set_name("XX")
+        .unwrap();
+    assert_eq!(validate_incremental(&copy).len(), 1);
+    assert!(validate_incremental(&pdb).is_empty());
+    pdb.model_mut(0)
+        .unwrap()
+        .remove_atoms_by(|atom| atom.serial_number() == 1);
+    assert_eq!(validate_incremental(&pdb).len(), 2);
+    assert_eq!(
+        errors(validate_incremental(&pdb)),
+        errors(validate_pdb(&pdb))
+    );
+}
@@ -0,0 +1,180 @@
+use pdbtbx::*;
+use std::fs;
+
+mod common;
+
+const STRUCTURE: &str = "\
+ATOM      1  CA  GLY A  99       0.000   0.000   0.000  1.00 10.00           C
+ATOM      2  CA  ALA A 100       3.800   0.000   0.000  1.00 10.00           C
+ATOM      3  CA  SER A 100A      7.600   0.000   0.000  1.00 10.00           C
+ATOM      4  CA  THR A 100B     11.400   0.000   0.000  1.00 10.00           C
+ATOM      5  CA  VAL A 101      15.200   0.000   0.000  1.00 10.00           C
+TER       6      VAL A 101
+END
+";
+
+#[test]
+fn distinct_residues() {
+    let pdb = common::read_text("insertion_codes", STRUCTURE).0;
+    assert_eq!(pdb.residues().count(), 5);
+    let numbering = pdb
+        .residues()
+        .map(|residue| (residue.id(), residue.numbering()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        numbering,
+        vec![
+            ("GLY".to_string(), (99, None)),
+            ("ALA".to_string(), (100, None)),
+            ("SER".to_string(), (100, Some('A'))),
+            ("THR".to_string(), (100, Some('B'))),
+            ("VAL".to_string(), (101, None)),
+        ]
+    );
+    assert!(numbering.windows(2).all(|pair| pair[0].1 < pair[1].1));
+}
+
+#[test]
+fn add_atom() {
+    let mut model = Model::new(1);
+    let atom = || {
+        Atom::new(
+            1,
+            [' ', 'C', 'A', ' '],
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0,
+            [' ', 'C'],
+            0,
+        )
+        .unwrap()
+    };
+    model.add_atom(atom(), 'A', 100, None, ['A', 'L', 'A']);
+    model.add_atom(atom(), 'A', 100, Some('A'), ['S', 'E', 'R']);
+    model.add_atom(atom(), 'A', 100, Some('A'), ['S', 'E', 'R']);
+    assert_eq!(model.residue_count(), 2);
+    assert_eq!(model.atom_count(), 3);
+
+    let mut residue = Residue::new(1, ['A', 'L', 'A'], None).unwrap();
+    assert!(residue.set_insertion_code(Some(' ')).is_ok());
+    assert_eq!(residue.insertion_code(), None);
+    assert!(residue.set_insertion_code(Some('é')).is_err());
+}
+
+#[test]
+fn save_and_renumber() {
+    let pdb = common::read_text("insertion_codes_save", STRUCTURE).0;
+    save_pdb(&pdb, "dump/insertion_codes_saved.pdb").unwrap();
+    let saved = fs::read_to_string("dump/insertion_codes_saved.pdb").unwrap();
+    let columns = saved
+        .lines()
+        .filter(|line| line.starts_with("ATOM"))
+        .map(|line| line.chars().nth(26).unwrap())
+        .collect::<String>();
+    assert_eq!(columns, "  AB ");
+    let (reread, _) = read_pdb("dump/insertion_codes_saved.pdb").unwrap();
+    assert_eq!(reread, pdb);
+
+    let mut renumbered = pdb.clone();
+    renumbered.renumber();
+    let numbering = renumbered
+        .residues()
+        .map(|residue| residue.numbering())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        numbering,
+        vec![(1, None), (2, None), (3, None), (4, None), (5, None)]
+    );
+}
+
+#[test]
+fn residue_keys() {
+    let pdb = common::read_text("insertion_codes_keys", STRUCTURE).0;
+    let model = pdb.model(0).unwrap();
+    let ids = model
+        .atoms_with_hierarchy()
+        .map(|(chain, residue, atom)| AtomId::from_hierarchy(model, chain, residue, atom))
+        .collect::<Vec<_>>();
+    assert_eq!(ids[2].insertion_code(), Some('A'));
+    assert_eq!(
+        format!("{}", ids[3]),
+        format!("{}/A/100B/CA", model.serial_number())
+    );
+    for (index, id) in ids.iter().enumerate() {
+        assert_eq!(pdb.get(id), pdb.atom(index));
+    }
+
+    let mut changed = pdb.clone();
+    changed.remove_residues_by(|residue| residue.numbering() == (100, Some('A')));
+    let atom = changed.atom_mut(2).unwrap();
+    atom.set_pos((atom.x() + 1.0, atom.y(), atom.z())).unwrap();
+    let result = diff(&pdb, &changed, 0.1);
+    assert_eq!(
+        result.removed_residues(),
+        &[(ResidueId::new('A', 100, Some('A')), "SER".to_string())]
+    );
+    assert!(result.renamed_residues().is_empty());
+    assert_eq!(result.moved_atoms().len(), 1);
+    assert_eq!(result.moved_atoms()[0].0, ids[3]);
+    assert!(format!("{}", result).contains("- residue A100A SER"));
+
+    let mut drained = pdb.clone();
+    let removed = drained
+        .model_mut(0)
+        .unwrap()
+        .drain_atoms_by(|atom| atom.serial_number() == 3);
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].0, ids[2]);
+
+    let ligand = ResidueId::new('A', 100, Some('B'));
+    let site = pdb.binding_site_with_symmetry(ligand, 4.0).unwrap();
+    let numbering = site
+        .all_residues()
+        .map(|residue| residue.numbering())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        numbering,
+        vec![(100, Some('A')), (100, Some('B')), (101, None)]
+    );
+
+    let missing = ResidueId::new('A', 100, Some('C'));
+    let error = model
+        .interaction_fingerprint(missing, &InteractionCriteria::new())
+        .unwrap_err();
+    assert_eq!(error, AnalysisError::ResidueNotFound(missing));
+    assert_eq!(
+        format!("{}", error),
+        "Residue 100C of chain A could not be found"
+    );
+}
+
+#[test]
+fn residue_by_id() {
+    let mut pdb = common::read_text("insertion_codes_lookup", STRUCTURE).0;
+    let id = ResidueId::new('A', 100, Some('B'));
+    assert_eq!(pdb.residue_by_id(('A', 100).into()).unwrap().id(), "ALA");
+    assert_eq!(pdb.residue_by_id(id).unwrap().id(), "THR");
+    assert!(pdb
+        .residue_by_id(ResidueId::new('A', 100, Some('C')))
+        .is_none());
+    pdb.residue_by_id_mut(id).unwrap().set_id("CYS").unwrap();
+    assert_eq!(pdb.residue(3).unwrap().id(), "CYS");
+}
+
+#[test]
+fn invalid_insertion_code() {
+    let _ = fs::create_dir("dump");
+    let path = "dump/insertion_codes_invalid.pdb";
+    fs::write(path, STRUCTURE.replacen("A 100A ", "A 100\u{e9} ", 1)).unwrap();
+    let (pdb, errors) = read_pdb(path).unwrap();
+    assert_eq!(pdb.residues().count(), 4);
+    let error = errors
+        .iter()
+        .find(|e| e.short_description() == "Invalid characters in atom")
+        .unwrap();
+    assert_eq!(error.level(), ErrorLevel::BreakingError);
+    assert!(error.long_description().contains("insertion code"));
+    assert_eq!(error.context().linenumber(), Some(3));
+}
@@ -0,0 +1,114 @@
+use pdbtbx::*;
+use std::fs;
+
+const FIXTURE: &str = "\
+ATOM      1  OG  SER A  10       2.900   0.000   0.000  1.00 20.00           O
+ATOM      2  NZ  LYS A  20      23.000   0.000   0.000  1.00 20.00           N
+ATOM      3  CD1 LEU A  30      43.800   0.000   0.000  1.00 20.00           C
+TER       4      LEU A  30
+HETATM    5  O1  LIG B 401       0.000   0.000   0.000  1.00 20.00           O
+HETATM    6  O2  LIG B 401      20.000   0.000   0.000  1.00 20.00           O1-
+HETATM    7  C1  LIG B 401      40.000   0.000   0.000  1.00 20.00           C
+HETATM    8  N1  LIG B 401      60.000   0.000   0.000  1.00 20.00           N
+HETATM    9 ZN    ZN C 501      62.100   0.000   0.000  1.00 20.00          ZN2+
+HETATM   10  O   HOH D 601       0.000   2.800   0.000  1.00 20.00           O
+END
+";
+
+fn model() -> Model {
+    fs::write("dump/interaction_fingerprint.pdb", FIXTURE).unwrap();
+    let (pdb, _) = read_pdb("dump/interaction_fingerprint.pdb").unwrap();
+    pdb.model(0).unwrap().clone()
+}
+
+#[test]
+fn classification() {
+    let model = model();
+    let fingerprint = model
+        .interaction_fingerprint(('B', 401).into(), &InteractionCriteria::new())
+        .unwrap();
+    let kinds = fingerprint.iter().map(|i| i.kind()).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            InteractionType::HydrogenBond,
+            InteractionType::SaltBridge,
+            InteractionType::Hydrophobic,
+            InteractionType::MetalCoordination,
+        ]
+    );
+    let metal = &fingerprint[3];
+    assert_eq!(metal.ligand_atom(), "N1");
+    assert_eq!(metal.chain_id(), 'C');
+    assert_eq!(metal.residue_serial_number(), 501);
+    assert_eq!(metal.residue_name(), "ZN");
+    assert_eq!(metal.residue_atom(), "ZN");
+    assert!((metal.distance() - 2.1).abs() < 1e-9);
+    assert_eq!(fingerprint[0].key(), "Hydrogen bond SER 10");
+
+    assert_eq!(
+        model
+            .interaction_fingerprint(('B', 402).into(), &InteractionCriteria::new())
+            .err(),
+        Some(AnalysisError::ResidueNotFound(('B', 402).into()))
+    );
+}
+
+#[test]
+fn criteria() {
+    let model = model();
+    let mut criteria = InteractionCriteria::new();
+    assert_eq!(criteria.cutoff(InteractionType::HydrogenBond), 3.5);
+    criteria
+        .set_cutoff(InteractionType::HydrogenBond, 2.5)
+        .unwrap();
+    criteria
+        .set_cutoff(InteractionType::SaltBridge, 0.0)
+        .unwrap();
+    assert!(criteria
+        .set_cutoff(InteractionType::Hydrophobic, -1.0)
+        .is_err());
+    let kinds = model
+        .interaction_fingerprint(('B', 401).into(), &criteria)
+        .unwrap()
+        .iter()
+        .map(|i| i.kind())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            InteractionType::Hydrophobic,
+            InteractionType::MetalCoordination
+        ]
+    );
+}
+
+#[test]
+fn tanimoto() {
+    let model = model();
+    let criteria = InteractionCriteria::new();
+    let full = model
+        .interaction_fingerprint(('B', 401).into(), &criteria)
+        .unwrap();
+    let mut without_metal = model.clone();
+    without_metal.remove_chains_by(|chain| chain.id() == 'C');
+    let partial = without_metal
+        .interaction_fingerprint(('B', 401).into(), &criteria)
+        .unwrap();
+    assert_eq!(partial.len(), 3);
+    assert!((Interaction::tanimoto(&full, &partial) - 0.75).abs() < 1e-9);
+    assert_eq!(Interaction::tanimoto(&full, &full), 1.0);
+    assert_eq!(Interaction::tanimoto(&[], &[]), 1.0);
+}
+
+#[test]
+fn ca_only() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j_ca.pdb").unwrap();
+    let model = pdb.model(0).unwrap();
+    assert_eq!(
+        model
+            .interaction_fingerprint(('A', 708).into(), &InteractionCriteria::new())
+            .err(),
+        Some(AnalysisError::CaOnlyChain('A'))
+    );
+}
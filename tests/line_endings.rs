@@ -0,0 +1,79 @@
+use pdbtbx::*;
+use std::fs;
+
+fn descriptions(errors: &[PDBError]) -> Vec<(ErrorLevel, String, String)> {
+    errors
+        .iter()
+        .map(|error| {
+            (
+                error.level(),
+                error.short_description().to_string(),
+                error.long_description().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn crlf_example_files() {
+    for name in &["1ubq", "1yyf", "3b5j", "pTLS-6484"] {
+        let original = format!("example-pdbs/{}.pdb", name);
+        let crlf = format!("dump/{}_crlf.pdb", name);
+        let text = fs::read_to_string(&original).unwrap();
+        fs::write(&crlf, text.replace("\r\n", "\n").replace('\n', "\r\n")).unwrap();
+
+        let (lf_pdb, lf_errors) = read_pdb(&original).unwrap();
+        let (crlf_pdb, crlf_errors) = read_pdb(&crlf).unwrap();
+        assert!(
+            lf_pdb == crlf_pdb,
+            "{} differs with CRLF line endings",
+            name
+        );
+        assert_eq!(descriptions(&lf_errors), descriptions(&crlf_errors));
+    }
+}
+
+#[test]
+fn trailing_whitespace() {
+    let lines = [
+        "CRYST1   30.000   30.000   30.000  90.00  90.00  90.00 P 1           1",
+        "ATOM      1  N   ALA A   1       0.000   0.000   0.000  1.00 10.00           N",
+        "ANISOU    1  N   ALA A   1      100    100    100      0      0      0       N",
+        "ATOM      2  CA  ALA A   1       1.458   0.000   0.000  1.00 10.00",
+        "ANISOU    2  CA  ALA A   1      100    100    100      0      0      0",
+        "TER       3      ALA A   1",
+        "END",
+    ];
+    fs::write("dump/trailing_clean.pdb", lines.join("\n")).unwrap();
+    let messy = lines
+        .iter()
+        .map(|line| format!("{} \t \r", line))
+        .collect::<Vec<_>>();
+    fs::write("dump/trailing_messy.pdb", messy.join("\n")).unwrap();
+
+    let (clean, clean_errors) = read_pdb("dump/trailing_clean.pdb").unwrap();
+    let (messy, messy_errors) = read_pdb("dump/trailing_messy.pdb").unwrap();
+    assert!(clean == messy);
+    assert_eq!(descriptions(&clean_errors), descriptions(&messy_errors));
+    let ca = messy.atoms().nth(1).unwrap();
+    assert_eq!(ca.element(), "");
+    assert!(ca.anisotropic_temperature_factors().is_some());
+}
+
+#[test]
+fn saved_lines() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    save_pdb(&pdb, "dump/1ubq_lf.pdb").unwrap();
+    let text = fs::read_to_string("dump/1ubq_lf.pdb").unwrap();
+    assert!(!text.contains('\r'));
+    assert!(text.lines().all(|line| line == line.trim_end()));
+
+    let mut options = SaveOptions::new();
+    options.set_crlf_line_endings(true);
+    save_with_options(&pdb, "dump/1ubq_crlf.pdb", &options).unwrap();
+    let crlf = fs::read_to_string("dump/1ubq_crlf.pdb").unwrap();
+    assert_eq!(crlf, text.replace('\n', "\r\n"));
+    let (reparsed, _) = read_pdb("dump/1ubq_crlf.pdb").unwrap();
+    let (original, _) = read_pdb("dump/1ubq_lf.pdb").unwrap();
+    assert!(reparsed == original);
+}
@@ -0,0 +1,132 @@
+use pdbtbx::*;
+use std::fs;
+
+mod common;
+
+const STRUCTURE: &str = "\
+LINK         ND2 ASN A   2                 C1  NAG A 201     1555   1555  1.44
+LINK        ZN    ZN A 301                 O  AHOH A   2     1555   2565  2.10
+LINK         SG  CYS A   1A               ZN    ZN A 301
+ATOM      1  SG  CYS A   1       0.000   0.000   0.000  1.00 10.00           S
+ATOM      2  ND2 ASN A   2       3.800   0.000   0.000  1.00 10.00           N
+TER       3      ASN A   2
+HETATM    4  C1  NAG A 201       5.200   0.000   0.000  1.00 20.00           C
+HETATM    5 ZN    ZN A 301       0.000   2.300   0.000  1.00 20.00          ZN
+HETATM    6  O  AHOH A   2      10.000   2.000   0.000  0.50 20.00           O
+END
+";
+
+#[test]
+fn parse_links() {
+    let (pdb, errors) = common::read_text("links", STRUCTURE);
+    assert!(
+        errors
+            .iter()
+            .all(|e| !e.short_description().contains("LINK")),
+        "{:?}",
+        errors
+    );
+    assert_eq!(pdb.link_count(), 3);
+    let links = pdb.links().collect::<Vec<_>>();
+
+    assert_eq!(links[0].atom_names(), ["ND2", "C1"]);
+    assert_eq!(links[0].residue_names(), ["ASN", "NAG"]);
+    assert_eq!(links[0].chain_ids(), ['A', 'A']);
+    assert_eq!(links[0].residue_serial_numbers(), [2, 201]);
+    assert_eq!(links[0].symmetry_operators(), [1555, 1555]);
+    assert_eq!(links[0].length(), Some(1.44));
+    assert!(links[0].contains('A', 201));
+
+    assert_eq!(links[1].atom_names(), ["ZN", "O"]);
+    assert_eq!(links[1].alternate_locations(), [' ', 'A']);
+    assert_eq!(links[1].symmetry_operators(), [1555, 2565]);
+
+    // Older files leave out the symmetry operators and the length
+    assert_eq!(links[2].insertion_codes(), ['A', ' ']);
+    assert_eq!(links[2].symmetry_operators(), [1555, 1555]);
+    assert_eq!(links[2].length(), None);
+}
+
+#[test]
+fn save_links() {
+    let (pdb, _) = common::read_text("links_save", STRUCTURE);
+    save_pdb(&pdb, "dump/links_saved.pdb").unwrap();
+    let text = fs::read_to_string("dump/links_saved.pdb").unwrap();
+    assert!(text.contains(
+        "\nLINK        ND2  ASN A   2                 C1  NAG A 201     1555   1555  1.44\n"
+    ));
+    let (reloaded, _) = read_pdb("dump/links_saved.pdb").unwrap();
+    assert_eq!(
+        reloaded.links().collect::<Vec<_>>(),
+        pdb.links().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn renumbered_links() {
+    let (mut pdb, _) = common::read_text("links_renumber", STRUCTURE);
+    // The water shares its serial number with the asparagine
+    pdb.renumber_solvent(None);
+    let water = pdb.links().nth(1).unwrap();
+    assert_eq!(water.residue_serial_numbers(), [301, 302]);
+    assert_eq!(
+        pdb.links().next().unwrap().residue_serial_numbers(),
+        [2, 201]
+    );
+
+    pdb.renumber();
+    let residues = pdb
+        .all_chains()
+        .flat_map(|chain| {
+            chain
+                .residues()
+                .map(move |residue| (chain.id(), residue.serial_number(), residue.id()))
+        })
+        .collect::<Vec<_>>();
+    for link in pdb.links() {
+        for index in 0..2 {
+            assert!(residues.contains(&(
+                link.chain_ids()[index],
+                link.residue_serial_numbers()[index],
+                link.residue_names()[index].to_string()
+            )));
+        }
+    }
+}
+
+#[test]
+fn invalid_links() {
+    assert!(Link::new(
+        ["CA", "ZN"],
+        [' ', ' '],
+        ["ALA", "ZINC"],
+        ['A', 'A'],
+        [1, 2],
+        [' ', ' ']
+    )
+    .is_none());
+    assert!(Link::new(
+        ["CA", "ZN"],
+        [' ', ' '],
+        ["ALA", "ZN"],
+        ['A', 'A'],
+        [1, 2],
+        [' ', ' ']
+    )
+    .is_some());
+}
+
+#[test]
+fn invalid_link_is_skipped() {
+    let _ = fs::create_dir("dump");
+    let input = STRUCTURE.replacen("ND2 ASN A   2", "ND2 ASN \t   2", 1);
+    fs::write("dump/links_invalid.pdb", input).unwrap();
+    let (pdb, errors) = read_pdb("dump/links_invalid.pdb").unwrap();
+    assert_eq!(pdb.link_count(), 2);
+    let error = errors
+        .iter()
+        .find(|e| e.short_description() == "Invalid LINK")
+        .unwrap();
+    assert_eq!(error.level(), ErrorLevel::LooseWarning);
+    assert_eq!(error.context().linenumber(), Some(1));
+}
@@ -0,0 +1,103 @@
+use pdbtbx::*;
+
+/// Create a Model with a single alanine with ideal backbone geometry
+fn ideal_alanine() -> Model {
+    let cos = |angle: f64| angle.to_radians().cos();
+    let sin = |angle: f64| angle.to_radians().sin();
+    let ca = [0.0, 0.0, 0.0];
+    let n = [1.459, 0.0, 0.0];
+    let c = [1.525 * cos(111.0), 1.525 * sin(111.0), 0.0];
+    // O in the N-CA-C plane, at the side opposite of CA
+    let direction = [-c[0] / 1.525, -c[1] / 1.525];
+    let rotation = 120.1_f64.to_radians();
+    let o = [
+        c[0] + 1.229 * (direction[0] * rotation.cos() - direction[1] * rotation.sin()),
+        c[1] + 1.229 * (direction[0] * rotation.sin() + direction[1] * rotation.cos()),
+        0.0,
+    ];
+    // CB solved from the N-CA-CB and CB-CA-C angles
+    let g = cos(111.0);
+    let (target_n, target_c) = (cos(110.6), cos(110.4));
+    let b = (target_c - g * target_n) / (1.0 - g * g);
+    let a = target_n - b * g;
+    let u_c = [cos(111.0), sin(111.0), 0.0];
+    let z = (1.0 - a * a - b * b - 2.0 * a * b * g).sqrt();
+    let cb = [1.530 * (a + b * u_c[0]), 1.530 * (b * u_c[1]), 1.530 * z];
+
+    let mut model = Model::new(1);
+    for (serial, (name, pos)) in [("N", n), ("CA", ca), ("C", c), ("O", o), ("CB", cb)]
+        .iter()
+        .enumerate()
+    {
+        let chars = format!("{:^4}", name).chars().collect::<Vec<char>>();
+        let atom = Atom::new(
+            serial + 1,
+            [chars[0], chars[1], chars[2], chars[3]],
+            pos[0],
+            pos[1],
+            pos[2],
+            1.0,
+            10.0,
+            [' ', name.chars().next().unwrap()],
+            0,
+        )
+        .unwrap();
+        model.add_atom(atom, 'A', 1, None, ['A', 'L', 'A']);
+    }
+    model
+}
+
+#[test]
+fn ideal_geometry() {
+    let model = ideal_alanine();
+    let score = model.local_geometry_score('A', 1).unwrap().unwrap();
+    assert!(score < 0.01, "{}", score);
+    assert_eq!(model.local_geometry_score('A', 2), Ok(None));
+    assert_eq!(model.local_geometry_score('B', 1), Ok(None));
+}
+
+#[test]
+fn distorted_geometry() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let chain = pdb.chain(0).unwrap();
+    let profile = chain.local_geometry_profile().unwrap();
+    assert_eq!(
+        profile.len(),
+        chain.residues().filter(|r| r.amino_acid()).count()
+    );
+    let flagged = profile.iter().filter(|(_, score)| *score > 2.0).count();
+    assert!(flagged * 10 < profile.len(), "{:?}", profile);
+
+    let model = pdb.model(0).unwrap();
+    let before = model.local_geometry_score('A', 30).unwrap().unwrap();
+    assert_eq!(profile.iter().find(|(s, _)| *s == 30).unwrap().1, before);
+    let ca = pdb
+        .residues_mut()
+        .find(|r| r.serial_number() == 30)
+        .unwrap()
+        .atoms_mut()
+        .find(|a| a.name() == "CA")
+        .unwrap();
+    ca.set_pos((ca.x() + 0.5, ca.y(), ca.z())).unwrap();
+    let after = pdb
+        .model(0)
+        .unwrap()
+        .local_geometry_score('A', 30)
+        .unwrap()
+        .unwrap();
+    assert!(after > 2.0 && after > before, "{} {}", before, after);
+}
+
+#[test]
+fn ca_only() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j_ca.pdb").unwrap();
+    let model = pdb.model(0).unwrap();
+    assert_eq!(
+        model.local_geometry_score('A', 500),
+        Err(AnalysisError::CaOnlyChain('A'))
+    );
+    assert_eq!(
+        model.chain(0).unwrap().local_geometry_profile(),
+        Err(AnalysisError::CaOnlyChain('A'))
+    );
+}
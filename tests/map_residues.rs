@@ -0,0 +1,36 @@
+use pdbtbx::*;
+
+#[test]
+fn rename_all_residues() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let total = pdb.all_residues().count();
+    assert!(pdb.residues_by_name("ALA").count() < total);
+
+    pdb.chain_mut(0)
+        .unwrap()
+        .map_residues_mut(|residue| residue.set_id("ALA").unwrap());
+    let chain = pdb.chain(0).unwrap();
+    assert_eq!(chain.residues_by_name("ALA").count(), chain.residue_count());
+
+    pdb.map_residues_mut(|residue| residue.set_id("ALA").unwrap());
+    assert_eq!(pdb.residues_by_name("ALA").count(), total);
+    assert_eq!(pdb.model(0).unwrap().residues_by_name("ALA").count(), total);
+}
+
+#[test]
+fn residue_b_factors() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let mut count = 0;
+    pdb.map_residues_mut(|residue| {
+        count += 1;
+        let b = residue.average_b_factor().unwrap_or(0.0);
+        for atom in residue.atoms_mut() {
+            atom.set_b_factor(b).unwrap();
+        }
+    });
+    assert_eq!(count, pdb.all_residues().count());
+    for residue in pdb.all_residues() {
+        let first = residue.atom(0).unwrap().b_factor();
+        assert!(residue.atoms().all(|atom| atom.b_factor() == first));
+    }
+}
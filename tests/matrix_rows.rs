@@ -0,0 +1,121 @@
+use pdbtbx::*;
+
+mod common;
+
+const ORIGX: &str = "\
+ORIGX1      1.000000  0.000000  0.000000        0.00000
+ORIGX2      0.000000  1.000000  0.000000        0.00000
+ORIGX3      0.000000  0.000000  1.000000        0.00000
+";
+
+const ATOM: &str = "\
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C
+";
+
+fn master(xform: usize) -> String {
+    format!(
+        "MASTER        0    0    0    0    0    0    0{:5}    1    0    0    0\nEND\n",
+        xform
+    )
+}
+
+fn read(name: &str, matrices: &str, xform: usize) -> (PDB, Vec<PDBError>) {
+    common::read_text(
+        name,
+        &format!("{}{}{}{}", ORIGX, matrices, ATOM, master(xform)),
+    )
+}
+
+fn descriptions(errors: &[PDBError]) -> Vec<&str> {
+    errors.iter().map(|e| e.short_description()).collect()
+}
+
+#[test]
+fn duplicate_row() {
+    let (pdb, errors) = read(
+        "matrix_rows_duplicate",
+        "\
+SCALE1      0.020000  0.000000  0.000000        0.00000
+SCALE2      0.000000  0.020000  0.000000        0.00000
+SCALE2      0.000000  0.040000  0.000000        0.00000
+SCALE3      0.000000  0.000000  0.020000        0.00000
+",
+        6,
+    );
+    assert_eq!(descriptions(&errors), vec!["Duplicate matrix row"]);
+    assert_eq!(errors[0].level(), ErrorLevel::StrictWarning);
+    assert!(errors[0].long_description().contains("SCALE2"));
+    // The first row is kept
+    assert!(pdb.scale().valid());
+    assert_eq!(pdb.scale().transformation().matrix()[1][1], 0.02);
+}
+
+#[test]
+fn missing_row() {
+    let scale = "\
+SCALE1      0.020000  0.000000  0.000000        0.00000
+SCALE2      0.000000  0.020000  0.000000        0.00000
+";
+    // Only the complete ORIGXn matrix counts for the MASTER record
+    let (pdb, errors) = read("matrix_rows_missing", scale, 3);
+    assert!(!pdb.scale().valid());
+    assert_eq!(
+        descriptions(&errors),
+        vec!["Incomplete matrix", "Row not set"]
+    );
+    assert_eq!(errors[0].level(), ErrorLevel::StrictWarning);
+    assert!(errors[0]
+        .long_description()
+        .contains("SCALE3 records are missing"));
+
+    let (_, errors) = read("matrix_rows_missing_master", scale, 5);
+    assert!(descriptions(&errors).contains(&"MASTER checksum failed"));
+}
+
+#[test]
+fn missing_mtrix_rows() {
+    let (pdb, errors) = read(
+        "matrix_rows_mtrix",
+        "\
+MTRIX1   1  1.000000  0.000000  0.000000        0.00000    1
+MTRIX3   1  0.000000  0.000000  1.000000        0.00000    1
+MTRIX1   2  1.000000  0.000000  0.000000        0.00000    1
+MTRIX2   2  0.000000  1.000000  0.000000        0.00000    1
+MTRIX3   2  0.000000  0.000000  1.000000        0.00000    1
+MTRIX1   2  0.000000  1.000000  0.000000        0.00000    1
+",
+        6,
+    );
+    assert_eq!(
+        descriptions(&errors),
+        vec!["Duplicate matrix row", "Incomplete matrix", "Row not set"]
+    );
+    assert!(errors[1]
+        .long_description()
+        .contains("MTRIX2 records are missing for MTRIXn Serial number 1"));
+    let valid = pdb.mtrix().map(MtriX::valid).collect::<Vec<_>>();
+    assert_eq!(valid, vec![false, true]);
+}
+
+#[test]
+fn singular_matrix() {
+    let (pdb, errors) = read(
+        "matrix_rows_singular",
+        "\
+MTRIX1   1  1.000000  0.000000  0.000000        0.00000    1
+MTRIX2   1  1.000000  0.000000  0.000000        0.00000    1
+MTRIX3   1  0.000000  0.000000  1.000000        0.00000    1
+",
+        3,
+    );
+    assert!(!pdb.mtrix().next().unwrap().valid());
+    assert_eq!(descriptions(&errors), vec!["Singular matrix"]);
+    assert_eq!(errors[0].level(), ErrorLevel::InvalidatingError);
+
+    let mut scale = Scale::new();
+    assert!(scale.valid());
+    scale.set_row(0, [0.0, 0.0, 0.0, 0.0]);
+    scale.set_row(1, [0.0, 1.0, 0.0, 0.0]);
+    scale.set_row(2, [0.0, 0.0, 1.0, 0.0]);
+    assert!(!scale.valid());
+}
@@ -0,0 +1,87 @@
+use pdbtbx::*;
+
+fn two_poses() -> PDB {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let mut second = pdb.model(0).unwrap().clone();
+    second.set_serial_number(2);
+    for atom in second.all_atoms_mut() {
+        atom.set_x(atom.x() + 1.0).unwrap();
+    }
+    pdb.add_model(second);
+    pdb
+}
+
+#[test]
+fn merge() {
+    let pdb = two_poses();
+    let model = pdb.merge_models_as_altlocs(&[0.7, 0.3]).unwrap();
+    let first = pdb.model(0).unwrap();
+    assert_eq!(model.total_atom_count(), 2 * first.total_atom_count());
+    assert_eq!(model.total_residue_count(), first.total_residue_count());
+
+    let residue = model.residue(0).unwrap();
+    let conformers = residue.conformers();
+    assert_eq!(conformers.len(), 2);
+    assert_eq!(conformers[0].alternate_location(), 'A');
+    assert_eq!(conformers[1].alternate_location(), 'B');
+    assert!((conformers[0].occupancy().unwrap() - 0.7).abs() < 1e-9);
+    assert!((conformers[1].occupancy().unwrap() - 0.3).abs() < 1e-9);
+    let (a, b) = (model.atom(0).unwrap(), model.atom(1).unwrap());
+    assert_eq!(a.name(), b.name());
+    assert!((b.x() - a.x() - 1.0).abs() < 1e-9);
+    assert_eq!(a.x(), first.atom(0).unwrap().x());
+
+    // Unique serial numbers, and the hetero flag is kept
+    let mut serials = model
+        .all_atoms()
+        .map(|atom| atom.serial_number())
+        .collect::<Vec<_>>();
+    serials.dedup();
+    assert_eq!(serials.len(), model.total_atom_count());
+    assert!(model.hetero_atoms().all(|atom| atom.hetero()));
+
+    // The merged Model saves and reads back with its alternate locations
+    let mut merged = PDB::new();
+    merged.add_model(model);
+    save_pdb(&merged, "dump/merged_altlocs.pdb").unwrap();
+    let (saved, errors) = read_pdb("dump/merged_altlocs.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .all(|error| error.level() != ErrorLevel::BreakingError));
+    let saved = saved.model(0).unwrap();
+    assert_eq!(saved.total_atom_count(), 2 * first.total_atom_count());
+    assert_eq!(saved.residue(0).unwrap().conformers().len(), 2);
+    assert_eq!(saved.atom(1).unwrap().alternate_location(), 'B');
+    assert_eq!(saved.atom(1).unwrap().occupancy(), 0.3);
+}
+
+#[test]
+fn merge_errors() {
+    let pdb = two_poses();
+    assert!(pdb.merge_models_as_altlocs(&[1.0]).is_err());
+    assert!(pdb.merge_models_as_altlocs(&[0.5, f64::NAN]).is_err());
+    assert!(PDB::new().merge_models_as_altlocs(&[]).is_err());
+
+    let mut different = two_poses();
+    different
+        .model_mut(1)
+        .unwrap()
+        .remove_atoms_by(|atom| atom.name() == "CA");
+    let error = different.merge_models_as_altlocs(&[0.5, 0.5]).unwrap_err();
+    assert_eq!(error.short_description(), "Models differ in composition");
+
+    let mut many = two_poses();
+    for serial in 3..=27 {
+        let mut model = many.model(0).unwrap().clone();
+        model.set_serial_number(serial);
+        many.add_model(model);
+    }
+    assert!(many.merge_models_as_altlocs(&[0.1; 27]).is_err());
+    many.remove_model(26);
+    assert!(many.merge_models_as_altlocs(&[0.1; 26]).is_ok());
+
+    // Atoms with alternate locations cannot be merged again
+    let mut merged = PDB::new();
+    merged.add_model(pdb.merge_models_as_altlocs(&[0.5, 0.5]).unwrap());
+    assert!(merged.merge_models_as_altlocs(&[1.0]).is_err());
+}
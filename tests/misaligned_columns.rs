@@ -0,0 +1,73 @@
+use pdbtbx::*;
+use std::fs;
+
+const FIXTURE: &str = "\
+ATOM      1  N   MET A   1       27.251  24.447   2.594  1.00 11.79           N
+ATOM      2  CA  MET A   1      26.198  25.467   2.821  1.00  9.72           C
+ATOM      3  O   MET A   1       -27.624 -26.019   2.842  0.50 10.35           O
+ATOM      4  CB  MET A   1     25.435 -24.091   1.659
+ATOM      5  CG  MET A   1      25.435  abc   1.659  1.00  9.76           C
+END
+";
+
+#[test]
+fn strict() {
+    fs::write("dump/misaligned_columns_strict.pdb", FIXTURE).unwrap();
+    let (pdb, errors) = read_pdb("dump/misaligned_columns_strict.pdb").unwrap();
+    assert_eq!(
+        pdb.atoms().map(|a| a.serial_number()).collect::<Vec<_>>(),
+        vec![2]
+    );
+    assert_eq!(
+        errors
+            .iter()
+            .filter(|e| e.level() == ErrorLevel::BreakingError)
+            .count(),
+        4
+    );
+}
+
+#[test]
+fn recover() {
+    fs::write("dump/misaligned_columns.pdb", FIXTURE).unwrap();
+    let mut options = ReadOptions::new();
+    options.set_recover_misaligned_columns(true);
+    let (pdb, errors) = parse_with_options("dump/misaligned_columns.pdb", &options).unwrap();
+
+    let atoms = pdb.atoms().collect::<Vec<_>>();
+    assert_eq!(
+        atoms.iter().map(|a| a.serial_number()).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+    assert_eq!(atoms[0].pos(), (27.251, 24.447, 2.594));
+    assert_eq!(atoms[0].b_factor(), 11.79);
+    assert_eq!(atoms[0].element(), "N");
+    assert_eq!(atoms[2].pos(), (-27.624, -26.019, 2.842));
+    assert_eq!(atoms[2].occupancy(), 0.5);
+    assert_eq!(atoms[2].element(), "O");
+    assert_eq!(atoms[3].pos(), (25.435, -24.091, 1.659));
+
+    let warnings = errors
+        .iter()
+        .filter(|e| e.short_description() == "Misaligned atom columns")
+        .collect::<Vec<_>>();
+    assert_eq!(warnings.len(), 3);
+    assert!(warnings
+        .iter()
+        .all(|e| e.level() == ErrorLevel::StrictWarning));
+    assert!(warnings[0]
+        .long_description()
+        .contains("shifted by 1 column"));
+    assert!(warnings[2]
+        .long_description()
+        .contains("shifted by -1 column"));
+
+    // Text that is not a number is still an error
+    assert_eq!(
+        errors
+            .iter()
+            .filter(|e| e.level() == ErrorLevel::BreakingError)
+            .count(),
+        1
+    );
+}
@@ -0,0 +1,73 @@
+use pdbtbx::*;
+use std::fs;
+
+const FRAGMENT: &str = "\
+ATOM      1  N   ALA A   1       0.000   0.000   0.000  1.00 10.00           N
+ATOM      2  CA  ALA A   1       1.458   0.000   0.000  1.00 10.00           C
+";
+
+fn missing(errors: &[PDBError]) -> Vec<&str> {
+    errors
+        .iter()
+        .map(|error| error.short_description())
+        .filter(|description| description.starts_with("Missing"))
+        .collect()
+}
+
+#[test]
+fn complete_file() {
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    assert!(pdb.saw_end());
+    assert!(pdb.saw_ter());
+    assert!(pdb.saw_master());
+    let clone = pdb.clone();
+    assert!(clone.saw_end() && clone.saw_ter() && clone.saw_master());
+    assert!(!PDB::new().saw_end());
+}
+
+#[test]
+fn fragment_without_records() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/fragment.pdb", FRAGMENT).unwrap();
+    let (pdb, errors) = read_pdb("dump/fragment.pdb").unwrap();
+    assert_eq!(pdb.atom_count(), 2);
+    assert!(!pdb.saw_end());
+    assert!(!pdb.saw_ter());
+    assert!(!pdb.saw_master());
+    assert!(missing(&errors).is_empty());
+
+    let mut options = ReadOptions::new();
+    options.set_warn_missing_records(true);
+    let (_, errors) = parse_with_options("dump/fragment.pdb", &options).unwrap();
+    assert_eq!(
+        missing(&errors),
+        vec![
+            "Missing END record",
+            "Missing TER record",
+            "Missing MASTER record"
+        ]
+    );
+    assert!(errors
+        .iter()
+        .filter(|error| error.short_description().starts_with("Missing"))
+        .all(|error| error.level() == ErrorLevel::LooseWarning));
+}
+
+#[test]
+fn ensemble_end_model_is_not_end() {
+    let _ = fs::create_dir("dump");
+    fs::write(
+        "dump/fragment_ensemble.pdb",
+        format!("MODEL        1\n{}TER\nENDMDL\n", FRAGMENT),
+    )
+    .unwrap();
+    let mut options = ReadOptions::new();
+    options.set_warn_missing_records(true);
+    let (pdb, errors) = parse_with_options("dump/fragment_ensemble.pdb", &options).unwrap();
+    assert!(!pdb.saw_end());
+    assert!(pdb.saw_ter());
+    assert_eq!(
+        missing(&errors),
+        vec!["Missing END record", "Missing MASTER record"]
+    );
+}
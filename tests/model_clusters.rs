@@ -0,0 +1,55 @@
+use pdbtbx::*;
+
+/// Make an ensemble of 1ubq with the given transformations applied to the first Model
+fn ensemble(transformations: &[TransformationMatrix]) -> PDB {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let first = pdb.model(0).unwrap().clone();
+    for (index, transformation) in transformations.iter().enumerate() {
+        let mut model = first.clone();
+        model.set_serial_number(index + 1);
+        model.apply_transformation(transformation);
+        pdb.add_model(model);
+    }
+    pdb
+}
+
+#[test]
+fn cluster_poses() {
+    let pdb = ensemble(&[
+        TransformationMatrix::translation(0.1, 0.0, 0.0),
+        TransformationMatrix::translation(5.0, 0.0, 0.0),
+        TransformationMatrix::rotation_z(90.0),
+        TransformationMatrix::translation(5.0, 0.1, 0.0),
+    ]);
+    assert_eq!(pdb.cluster_models(0.5, false), vec![0, 0, 1, 2, 1]);
+    // After superposition all Models are the same
+    assert_eq!(pdb.cluster_models(0.5, true), vec![0, 0, 0, 0, 0]);
+    assert_eq!(pdb.cluster_models(0.0, false), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn deduplicate_poses() {
+    let mut pdb = ensemble(&[
+        TransformationMatrix::translation(0.1, 0.0, 0.0),
+        TransformationMatrix::translation(5.0, 0.0, 0.0),
+        TransformationMatrix::translation(5.0, 0.1, 0.0),
+    ]);
+    assert_eq!(pdb.deduplicate_models(0.5, false), 2);
+    assert_eq!(
+        pdb.models().map(|m| m.serial_number()).collect::<Vec<_>>(),
+        vec![0, 2]
+    );
+    assert_eq!(pdb.deduplicate_models(0.5, false), 0);
+    assert_eq!(pdb.deduplicate_models(0.5, true), 1);
+    assert_eq!(pdb.model_count(), 1);
+}
+
+#[test]
+fn different_models_are_kept() {
+    let mut pdb = ensemble(&[TransformationMatrix::identity()]);
+    pdb.model_mut(1)
+        .unwrap()
+        .remove_atoms_by(|atom| atom.serial_number() == 1);
+    assert_eq!(pdb.cluster_models(100.0, false), vec![0, 1]);
+    assert_eq!(pdb.deduplicate_models(100.0, true), 0);
+}
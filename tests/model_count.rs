@@ -0,0 +1,78 @@
+use pdbtbx::*;
+use std::fs;
+
+mod common;
+
+fn structure(declared: usize) -> String {
+    format!(
+        "\
+NUMMDL    {:<4}
+MDLTYP    MINIMIZED AVERAGE; CA ATOMS ONLY, CHAIN A, B; P ATOMS ONLY,
+MDLTYP   2 CHAIN X, Y, Z
+MODEL        1
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C
+TER       2      ALA A   1
+ENDMDL
+MODEL        2
+ATOM      1  CA  ALA A   1       1.000   0.000   0.000  1.00 10.00           C
+TER       2      ALA A   1
+ENDMDL
+END
+",
+        declared
+    )
+}
+
+fn read(name: &str, declared: usize) -> (PDB, Vec<PDBError>) {
+    common::read_text(name, &structure(declared))
+}
+
+#[test]
+fn matching_count() {
+    let (pdb, errors) = read("model_count", 2);
+    assert!(errors
+        .iter()
+        .all(|e| e.short_description() != "Could not recognise tag."));
+    assert_eq!(pdb.declared_model_count(), Some(2));
+    assert_eq!(
+        pdb.model_types().collect::<Vec<_>>(),
+        vec![
+            "MINIMIZED AVERAGE",
+            "CA ATOMS ONLY, CHAIN A, B",
+            "P ATOMS ONLY, CHAIN X, Y, Z"
+        ]
+    );
+    assert!(validate_pdb(&pdb).is_empty());
+
+    save_pdb(&pdb, "dump/model_count_saved.pdb").unwrap();
+    let saved = fs::read_to_string("dump/model_count_saved.pdb").unwrap();
+    assert!(saved.starts_with("NUMMDL    2\nMDLTYP    MINIMIZED AVERAGE;"));
+    let (reread, _) = read_pdb("dump/model_count_saved.pdb").unwrap();
+    assert_eq!(reread.declared_model_count(), Some(2));
+    assert_eq!(
+        reread.model_types().collect::<Vec<_>>(),
+        pdb.model_types().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn mismatching_count() {
+    let (pdb, errors) = read("model_count_mismatch", 20);
+    let error = errors
+        .iter()
+        .find(|e| e.short_description() == "Model count mismatch")
+        .unwrap();
+    assert_eq!(error.level(), ErrorLevel::StrictWarning);
+    assert!(error.long_description().contains("declares 20 models"));
+
+    let errors = validate_pdb(&pdb);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        ValidationCheck::of(&errors[0]),
+        Some(ValidationCheck::ModelCount)
+    );
+
+    let mut profile = ValidationProfile::preset(ValidationPreset::Minimal);
+    profile.remove_check(ValidationCheck::ModelCount);
+    assert!(validate_with(&pdb, &profile).is_empty());
+}
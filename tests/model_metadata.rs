@@ -0,0 +1,67 @@
+use pdbtbx::*;
+use std::fs;
+
+const VINA_OUTPUT: &str = "MODEL 1
+REMARK VINA RESULT:      -6.1      0.000      0.000
+HETATM    1  C1  LIG A   1       1.000   2.000   3.000  1.00  0.00           C
+ENDMDL
+MODEL 2
+REMARK VINA RESULT:      -7.4      1.512      2.843
+HETATM    1  C1  LIG A   1       1.500   2.000   3.000  1.00  0.00           C
+ENDMDL
+MODEL 3
+REMARK VINA RESULT:      -5.2      2.103      3.320
+HETATM    1  C1  LIG A   1       2.000   2.000   3.000  1.00  0.00           C
+ENDMDL
+END
+";
+
+#[test]
+fn vina_poses() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/vina_poses.pdb", VINA_OUTPUT).unwrap();
+    let (mut pdb, errors) =
+        parse_with_metadata_hooks("dump/vina_poses.pdb", &[vina_result_hook]).unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(pdb.model_count(), 3);
+    assert_eq!(pdb.remark_count(), 0);
+    assert_eq!(
+        pdb.model(1).unwrap().metadata("vina_rmsd_lb"),
+        Some(&MetadataValue::Number(1.512))
+    );
+
+    pdb.model_mut(2).unwrap().remove_metadata("vina_affinity");
+    pdb.sort_models_by_metadata("vina_affinity");
+    assert_eq!(
+        pdb.models().map(|m| m.serial_number()).collect::<Vec<_>>(),
+        vec![2, 1, 3]
+    );
+
+    pdb.model_mut(0)
+        .unwrap()
+        .set_metadata("name", MetadataValue::from("best pose"))
+        .unwrap();
+    assert!(pdb
+        .model_mut(0)
+        .unwrap()
+        .set_metadata("a = b", MetadataValue::Number(1.0))
+        .is_err());
+    save_pdb(&pdb, "dump/vina_poses_sorted.pdb").unwrap();
+    let (reparsed, _) =
+        parse_with_metadata_hooks("dump/vina_poses_sorted.pdb", &[metadata_remark_hook]).unwrap();
+    for (model, original) in reparsed.models().zip(pdb.models()) {
+        assert_eq!(
+            model.all_metadata().collect::<Vec<_>>(),
+            original.all_metadata().collect::<Vec<_>>()
+        );
+    }
+    assert_eq!(
+        reparsed
+            .model(0)
+            .unwrap()
+            .metadata("vina_affinity")
+            .unwrap()
+            .number(),
+        Some(-7.4)
+    );
+}
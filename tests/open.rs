@@ -0,0 +1,55 @@
+use pdbtbx::*;
+use std::fs;
+
+#[test]
+fn by_extension() {
+    let (pdb, _) = open("example-pdbs/1ubq.pdb").unwrap();
+    assert_eq!(pdb.total_atom_count(), 1231);
+
+    fs::write("dump/open.cif", "data_1UBQ\n#\n").unwrap();
+    let error = open("dump/open.cif").unwrap_err();
+    assert_eq!(error.short_description(), "Unsupported file format");
+    assert!(error
+        .long_description()
+        .contains("mmCIF (from the extension"));
+}
+
+#[test]
+fn by_content() {
+    fs::copy("example-pdbs/1ubq.pdb", "dump/open_pdb.txt").unwrap();
+    let (pdb, _) = open("dump/open_pdb.txt").unwrap();
+    assert_eq!(pdb.total_atom_count(), 1231);
+
+    fs::write("dump/open_cif.txt", "data_1UBQ\n#\n_entry.id 1UBQ\n").unwrap();
+    let error = open("dump/open_cif.txt").unwrap_err();
+    assert!(error
+        .long_description()
+        .contains("mmCIF (from the start of the file)"));
+
+    fs::write("dump/open_gz.txt", [0x1f, 0x8b, 0x08, 0x00]).unwrap();
+    let error = open("dump/open_gz.txt").unwrap_err();
+    assert!(error.long_description().contains("gzip"));
+
+    fs::write(
+        "dump/open_gro.txt",
+        "Water\n    1\n    1SOL     OW    1   0.126   1.624   1.679\n   1.86206   1.86206   1.86206\n",
+    )
+    .unwrap();
+    let error = open("dump/open_gro.txt").unwrap_err();
+    assert!(error.long_description().contains("GRO"));
+
+    fs::write("dump/open_unknown.txt", "Hello world\n").unwrap();
+    let error = open("dump/open_unknown.txt").unwrap_err();
+    assert_eq!(error.short_description(), "Unknown file format");
+    assert!(open("dump/does_not_exist.txt").is_err());
+}
+
+#[test]
+fn with_format() {
+    fs::copy("example-pdbs/1ubq.pdb", "dump/open_misnamed.cif").unwrap();
+    assert!(open("dump/open_misnamed.cif").is_err());
+    let (pdb, _) = open_with_format("dump/open_misnamed.cif", Format::Pdb).unwrap();
+    assert_eq!(pdb.total_atom_count(), 1231);
+    assert_eq!(Format::from_extension("1ABC.ENT"), Some(Format::Pdb));
+    assert_eq!(Format::from_extension("1abc"), None);
+}
@@ -0,0 +1,136 @@
+use pdbtbx::*;
+use std::fs;
+
+fn atom(serial_number: usize, name: [char; 4]) -> Atom {
+    Atom::new(serial_number, name, 0.0, 0.0, 0.0, 1.0, 0.0, [' ', ' '], 0).unwrap()
+}
+
+fn serials<'a>(atoms: impl Iterator<Item = &'a Atom>) -> Vec<usize> {
+    atoms.map(|atom| atom.serial_number()).collect()
+}
+
+#[test]
+fn iterators_agree() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let model = pdb.model(0).unwrap();
+    let all = serials(model.all_atoms());
+    let mut expected = serials(model.atoms());
+    expected.extend(serials(model.hetero_atoms()));
+    assert_eq!(all, expected);
+    assert_eq!(
+        all,
+        serials(model.all_chains().flat_map(|chain| chain.atoms()))
+    );
+    assert_eq!(
+        all,
+        serials(model.all_residues().flat_map(|residue| residue.atoms()))
+    );
+    assert_eq!(
+        all,
+        serials((0..model.total_atom_count()).map(|index| model.atom(index).unwrap()))
+    );
+    assert_eq!(
+        model
+            .all_chains()
+            .map(|chain| chain.id())
+            .collect::<Vec<_>>(),
+        (0..model.total_chain_count())
+            .map(|index| model.chain(index).unwrap().id())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(all, serials(pdb.all_atoms()));
+    // The file is in this order as well
+    assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn adding_appends() {
+    let mut model = Model::new(1);
+    model.add_atom(atom(1, [' ', 'N', ' ', ' ']), 'B', 5, None, ['A', 'L', 'A']);
+    model.add_atom(atom(2, [' ', 'N', ' ', ' ']), 'B', 2, None, ['G', 'L', 'Y']);
+    model.add_hetero_atom(atom(3, [' ', 'O', ' ', ' ']), 'A', 1, None, ['H', 'O', 'H']);
+    model.add_atom(atom(4, [' ', 'N', ' ', ' ']), 'A', 1, None, ['S', 'E', 'R']);
+    model.add_atom(atom(5, [' ', 'C', 'A', ' ']), 'B', 5, None, ['A', 'L', 'A']);
+
+    // Atoms in insertion order within the Residue, Residues and Chains in insertion order
+    assert_eq!(serials(model.all_atoms()), vec![1, 5, 2, 4, 3]);
+    assert_eq!(
+        model
+            .residues()
+            .map(|residue| residue.serial_number())
+            .collect::<Vec<_>>(),
+        vec![5, 2, 1]
+    );
+    assert_eq!(
+        model
+            .all_chains()
+            .map(|chain| chain.id())
+            .collect::<Vec<_>>(),
+        vec!['B', 'A', 'A']
+    );
+
+    // Sorting is stable and keeps Normal before Hetero
+    model.sort_chains();
+    assert_eq!(serials(model.all_atoms()), vec![4, 1, 5, 2, 3]);
+}
+
+#[test]
+fn removing_keeps_order() {
+    let (mut pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let before = serials(pdb.all_atoms());
+    pdb.remove_atoms_by(|atom| atom.serial_number() % 3 == 0);
+    let expected = before
+        .iter()
+        .copied()
+        .filter(|serial| serial % 3 != 0)
+        .collect::<Vec<_>>();
+    assert_eq!(serials(pdb.all_atoms()), expected);
+
+    let model = pdb.model_mut(0).unwrap();
+    let drained = model.drain_residues_by(|residue| residue.serial_number() % 2 == 0);
+    let kept = serials(model.atoms());
+    assert!(kept.windows(2).all(|pair| pair[0] < pair[1]));
+    let removed = serials(drained.atoms());
+    assert!(removed.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Joining appends the Chains after the existing Chains
+    model.join(drained);
+    let mut expected = kept;
+    expected.extend(removed);
+    assert_eq!(serials(model.atoms()), expected);
+}
+
+#[test]
+fn remove_chain_by_index() {
+    let mut model = Model::new(1);
+    model.add_atom(atom(1, [' ', 'N', ' ', ' ']), 'A', 1, None, ['A', 'L', 'A']);
+    model.add_hetero_atom(atom(2, [' ', 'O', ' ', ' ']), 'B', 2, None, ['H', 'O', 'H']);
+    model.add_hetero_atom(atom(3, [' ', 'O', ' ', ' ']), 'C', 3, None, ['H', 'O', 'H']);
+    assert_eq!(model.chain(1).unwrap().id(), 'B');
+    model.remove_chain(1);
+    assert_eq!(
+        model
+            .all_chains()
+            .map(|chain| chain.id())
+            .collect::<Vec<_>>(),
+        vec!['A', 'C']
+    );
+    assert!(model.remove_chain_id('C'));
+    assert_eq!(model.total_chain_count(), 1);
+}
+
+#[test]
+fn saved_in_iteration_order() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    save_pdb(&pdb, "dump/ordering.pdb").unwrap();
+    let text = fs::read_to_string("dump/ordering.pdb").unwrap();
+    let names = text
+        .lines()
+        .filter(|line| line.starts_with("ATOM") || line.starts_with("HETATM"))
+        .map(|line| line[12..16].trim().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        pdb.all_atoms().map(|atom| atom.name()).collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,65 @@
+use pdbtbx::*;
+
+#[test]
+fn header_matches_full_parse() {
+    for filename in &[
+        "example-pdbs/1ubq.pdb",
+        "example-pdbs/1yyf.pdb",
+        "example-pdbs/3b5j.pdb",
+        "example-pdbs/pTLS-6484.pdb",
+    ] {
+        let (full, full_errors) = read_pdb(filename).unwrap();
+        let (header, errors) = parse_header(filename).unwrap();
+        // The errors in the header are found in the same way by a full parse
+        let full_errors = full_errors
+            .iter()
+            .map(|error| format!("{:?}", error))
+            .collect::<Vec<_>>();
+        for error in &errors {
+            assert!(full_errors.contains(&format!("{:?}", error)));
+        }
+        assert_eq!(header.model_count(), 0);
+        assert_eq!(header.total_atom_count(), 0);
+        assert!(full.model_count() > 0);
+
+        assert_eq!(
+            header.remarks().collect::<Vec<_>>(),
+            full.remarks().collect::<Vec<_>>()
+        );
+        assert_eq!(header.has_unit_cell(), full.has_unit_cell());
+        if full.has_unit_cell() {
+            assert_eq!(
+                format!("{:?}", header.unit_cell()),
+                format!("{:?}", full.unit_cell())
+            );
+        }
+        assert_eq!(header.has_symmetry(), full.has_symmetry());
+        if full.has_symmetry() {
+            assert_eq!(header.symmetry().symbol(), full.symmetry().symbol());
+        }
+        assert_eq!(header.has_scale(), full.has_scale());
+        assert_eq!(header.has_origx(), full.has_origx());
+        assert_eq!(
+            format!("{:?}", header.mtrix().collect::<Vec<_>>()),
+            format!("{:?}", full.mtrix().collect::<Vec<_>>())
+        );
+        assert_eq!(
+            header.hets().collect::<Vec<_>>(),
+            full.hets().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            header.seqres().collect::<Vec<_>>(),
+            full.seqres().collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn header_only_option() {
+    let mut options = ReadOptions::new();
+    assert!(!options.header_only());
+    options.set_header_only(true);
+    let (pdb, _) = parse_with_options("example-pdbs/1ubq.pdb", &options).unwrap();
+    assert_eq!(pdb.model_count(), 0);
+    assert_eq!(pdb.unit_cell().a(), 50.84);
+}
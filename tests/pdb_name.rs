@@ -0,0 +1,76 @@
+use pdbtbx::*;
+use std::fs;
+
+#[test]
+fn name_from_header() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert_eq!(pdb.name(), "3B5J");
+    let (pdb, _) = parse_header("example-pdbs/1yyf.pdb").unwrap();
+    assert_eq!(pdb.name(), "1YYF");
+}
+
+#[test]
+fn name_from_filename() {
+    // No HEADER record
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert_eq!(pdb.name(), "1ubq");
+    // A HEADER record without id code
+    let (pdb, _) = read_pdb("example-pdbs/liquid.pdb").unwrap();
+    assert_eq!(pdb.name(), "liquid");
+}
+
+#[test]
+fn set_name() {
+    let mut pdb = PDB::new();
+    assert_eq!(pdb.name(), "");
+    pdb.set_name("refined_model.2").unwrap();
+    assert_eq!(pdb.name(), "refined_model.2");
+    assert!(pdb.set_name("two words").is_err());
+    assert!(pdb.set_name(&"x".repeat(76)).is_err());
+    assert_eq!(pdb.name(), "refined_model.2");
+    pdb.set_name("").unwrap();
+    assert_eq!(pdb.name(), "");
+}
+
+#[test]
+fn name_in_header() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    pdb.set_name("1UBR").unwrap();
+    save_pdb(&pdb, "dump/pdb_name.pdb").unwrap();
+    let text = fs::read_to_string("dump/pdb_name.pdb").unwrap();
+    assert_eq!(
+        text.lines().next().unwrap(),
+        format!("HEADER{}1UBR", " ".repeat(56))
+    );
+    let (saved, _) = read_pdb("dump/pdb_name.pdb").unwrap();
+    assert_eq!(saved.name(), "1UBR");
+
+    // Names that are not a PDB id code are left out of the HEADER record
+    pdb.set_name("UBQ_relaxed").unwrap();
+    save_pdb(&pdb, "dump/pdb_name_long.pdb").unwrap();
+    let text = fs::read_to_string("dump/pdb_name_long.pdb").unwrap();
+    assert!(!text.contains("UBQ_relaxed"));
+    let (saved, _) = read_pdb("dump/pdb_name_long.pdb").unwrap();
+    assert_eq!(saved.name(), "pdb_name_long");
+}
+
+#[test]
+fn file_name_not_in_header() {
+    let (pdb, _) = read_pdb("example-pdbs/liquid.pdb").unwrap();
+    save_pdb(&pdb, "dump/liquid_saved.pdb").unwrap();
+    let text = fs::read_to_string("dump/liquid_saved.pdb").unwrap();
+    let header = text.lines().find(|line| line.starts_with("HEADER"));
+    assert!(header.iter().all(|line| line.len() <= 62));
+    assert!(text.lines().all(|line| line.len() <= 80));
+}
+
+#[test]
+fn empty_name_from_output_path() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    pdb.set_name("").unwrap();
+    save_pdb(&pdb, "dump/2abc.pdb").unwrap();
+    let (saved, _) = read_pdb("dump/2abc.pdb").unwrap();
+    assert_eq!(saved.name(), "2abc");
+    let text = fs::read_to_string("dump/2abc.pdb").unwrap();
+    assert!(text.starts_with(&format!("HEADER{}2abc\n", " ".repeat(56))));
+}
@@ -0,0 +1,42 @@
+use pdbtbx::*;
+use std::fs::{self, File};
+use std::io::BufWriter;
+
+#[test]
+fn write_trajectory() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let _ = fs::create_dir("dump");
+    let file = BufWriter::new(File::create("dump/1ubq_trajectory.pdb").unwrap());
+    let mut writer = PdbWriter::new(file, &pdb).unwrap();
+    let mut frame = pdb.model(0).unwrap().clone();
+    for _ in 0..100 {
+        frame.apply_transformation(&TransformationMatrix::translation(0.5, 0.0, 0.0));
+        writer.write_model(&frame).unwrap();
+    }
+    assert_eq!(writer.model_count(), 100);
+    writer.finish().unwrap();
+
+    let (trajectory, errors) = read_pdb("dump/1ubq_trajectory.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .all(|e| e.level() != ErrorLevel::BreakingError));
+    assert_eq!(trajectory.model_count(), 100);
+    assert_eq!(trajectory.remark_count(), pdb.remark_count());
+    assert_eq!(
+        trajectory.model(99).unwrap().total_atom_count(),
+        pdb.model(0).unwrap().total_atom_count()
+    );
+    let first = pdb.model(0).unwrap().atom(0).unwrap().x();
+    let last = trajectory.model(99).unwrap().atom(0).unwrap().x();
+    assert!((last - first - 50.0).abs() < 1e-3);
+}
+
+#[test]
+fn write_model_overflowing_serial() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let mut model = pdb.model(0).unwrap().clone();
+    model.atom_mut(0).unwrap().set_serial_number(100_000);
+    let mut writer = PdbWriter::with_policy(Vec::new(), &pdb, SerialOverflowPolicy::Error).unwrap();
+    assert!(writer.write_model(&model).is_err());
+    assert_eq!(writer.model_count(), 0);
+}
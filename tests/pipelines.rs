@@ -0,0 +1,125 @@
+use pdbtbx::*;
+
+mod common;
+
+const STRUCTURE: &str = "\
+ATOM      1  N   ALA B   1       0.000   1.000   0.000  1.00 10.00           N
+ATOM      2  CA  ALA B   1       0.000   0.000   0.000  1.00 10.00           C
+ATOM      3  C   ALA B   1       1.500   0.000   0.000  1.00 10.00           C
+ATOM      4  O   ALA B   1       2.125   1.083   0.000  1.00 10.00           O
+TER       5      ALA B   1
+ATOM      6  N   HIE A   1       5.000   0.000   0.000  1.00 10.00           N
+ATOM      7  CA AHIE A   1       6.000   0.000   0.000  0.30 10.00           C
+ATOM      8  CA BHIE A   1       6.000   0.500   0.000  0.70 10.00           C
+ATOM      9  C   HIE A   1       7.000   0.000   0.000  1.00 10.00
+ATOM     10  O   HIE A   1       7.500   1.000   0.000  1.00 10.00
+ATOM     11  OXT HIE A   1       7.500  -1.000   0.000  1.00 10.00           O
+TER      12      HIE A   1
+HETATM   13  O   HOH A 101      20.000  20.000  20.000  1.00 10.00           O
+END
+";
+
+#[test]
+fn docking() {
+    let pdb = common::read_text("pipelines_docking", STRUCTURE).0;
+    let (prepared, report) = prepare_for_docking(&pdb, &DockingOptions::new());
+    assert_eq!(
+        report.actions().cloned().collect::<Vec<_>>(),
+        vec![
+            PipelineAction::RemovedWaters(1),
+            PipelineAction::ResolvedAlternateLocations {
+                residues: 1,
+                removed_atoms: 1
+            },
+            PipelineAction::InferredElements(2),
+            PipelineAction::Renumbered,
+        ]
+    );
+    assert!(report.to_string().starts_with("Removed 1 waters\n"));
+    assert!(!prepared.has_multiple_conformations());
+    assert_eq!(prepared.all_atoms().count(), 9);
+    let ca = prepared
+        .atoms()
+        .find(|atom| atom.name() == "CA" && atom.y() > 0.0)
+        .unwrap();
+    assert_eq!(ca.occupancy(), 0.7);
+    assert!(prepared.atoms().all(|atom| !atom.element().is_empty()));
+    let serials = prepared
+        .atoms()
+        .map(|atom| atom.serial_number())
+        .collect::<Vec<_>>();
+    assert_eq!(serials, vec![1, 2, 3, 4, 6, 7, 8, 9, 10]);
+    // The original is left untouched
+    assert_eq!(pdb.all_atoms().count(), 11);
+
+    let mut options = DockingOptions::new();
+    options.set_remove_waters(false);
+    options.set_resolve_alternate_locations(false);
+    options.set_infer_elements(false);
+    options.set_renumber(false);
+    let (unchanged, report) = prepare_for_docking(&pdb, &options);
+    assert!(report.is_empty());
+    assert_eq!(unchanged, pdb);
+}
+
+#[test]
+fn md() {
+    let pdb = common::read_text("pipelines_md", STRUCTURE).0;
+    let (cleaned, report) = clean_for_md(&pdb, &MdOptions::new());
+    assert_eq!(
+        report.actions().cloned().collect::<Vec<_>>(),
+        vec![
+            PipelineAction::StandardizedResidueNames(1),
+            PipelineAction::AddedTerminalOxygens(1),
+            PipelineAction::SortedChains,
+        ]
+    );
+    let chains = cleaned.chains().map(|chain| chain.id()).collect::<Vec<_>>();
+    assert_eq!(chains, vec!['A', 'B']);
+    assert_eq!(cleaned.chain(0).unwrap().residue(0).unwrap().id(), "HIS");
+
+    let alanine = cleaned.chain(1).unwrap().residue(0).unwrap();
+    let oxt = alanine.atoms().find(|atom| atom.name() == "OXT").unwrap();
+    let c = alanine.atoms().find(|atom| atom.name() == "C").unwrap();
+    assert!((oxt.distance(c) - 1.25).abs() < 1e-6);
+    assert_eq!(oxt.element(), "O");
+    assert_eq!(oxt.serial_number(), 14);
+    assert!(oxt.y() < 0.0 && oxt.x() > c.x());
+
+    // Cleaning again does not change anything
+    let (_, report) = clean_for_md(&cleaned, &MdOptions::new());
+    assert!(report.is_empty());
+}
+
+#[test]
+fn ligand_and_pocket() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert!(isolate_ligand_and_pocket(&pdb, "XXX", 4.0).is_none());
+
+    let (pocket, report) = isolate_ligand_and_pocket(&pdb, "12D", 4.0).unwrap();
+    let actions = report.actions().cloned().collect::<Vec<_>>();
+    assert_eq!(actions[0], PipelineAction::KeptLigands(1));
+    let kept = match actions[1] {
+        PipelineAction::KeptPocketResidues(kept) => kept,
+        _ => panic!("No pocket residues kept"),
+    };
+    assert!(kept > 0);
+    assert_eq!(
+        actions[2],
+        PipelineAction::RemovedResidues(pdb.all_residues().count() - kept - 1)
+    );
+    assert_eq!(pocket.all_residues().count(), kept + 1);
+
+    let ligand = pocket
+        .all_residues()
+        .filter(|residue| residue.id() == "12D")
+        .flat_map(|residue| residue.atoms())
+        .collect::<Vec<_>>();
+    assert!(pocket
+        .all_residues()
+        .filter(|residue| residue.id() != "12D")
+        .all(|residue| residue
+            .atoms()
+            .any(|atom| ligand.iter().any(|l| atom.distance(l) <= 4.0))));
+    assert!(pocket.all_chains().all(|chain| chain.residue_count() > 0));
+}
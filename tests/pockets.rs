@@ -0,0 +1,98 @@
+use pdbtbx::*;
+
+fn distance(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)).sqrt()
+}
+
+/// The rank (from 1) of the pocket found for the binding site of the given ligand after removing
+/// all hetero atoms. The pocket is the binding site if its centroid is within 6 Å of the ligand
+/// and it is lined by at least half of the Residues within 4 Å of the ligand.
+fn rank_of_site(filename: &str, chain_id: char, ligand: usize) -> Option<usize> {
+    let (pdb, _) = read_pdb(filename).unwrap();
+    let model = pdb.model(0).unwrap();
+    let ligand_atoms = model
+        .all_chains()
+        .filter(|chain| chain.id() == chain_id)
+        .flat_map(|chain| chain.residues())
+        .filter(|residue| residue.serial_number() == ligand)
+        .flat_map(|residue| residue.atoms())
+        .collect::<Vec<&Atom>>();
+    assert!(!ligand_atoms.is_empty());
+    let site = model
+        .chains()
+        .flat_map(|chain| {
+            chain
+                .residues()
+                .filter(|residue| {
+                    residue.atoms().any(|atom| {
+                        ligand_atoms
+                            .iter()
+                            .any(|ligand| ligand.distance(atom) < 4.0)
+                    })
+                })
+                .map(move |residue| (chain.id(), residue.serial_number()))
+        })
+        .collect::<Vec<(char, usize)>>();
+
+    let mut apo = model.clone();
+    apo.remove_atoms_by(|atom| atom.hetero());
+    let pockets = apo.detect_pockets(&PocketParameters::default()).unwrap();
+    pockets
+        .iter()
+        .position(|pocket| {
+            let lining = pocket
+                .residues()
+                .iter()
+                .filter(|residue| site.contains(residue))
+                .count();
+            ligand_atoms
+                .iter()
+                .any(|atom| distance(atom.pos(), pocket.centroid()) < 6.0)
+                && 2 * lining >= site.len()
+        })
+        .map(|index| index + 1)
+}
+
+#[test]
+fn known_binding_sites() {
+    // TNP-ADP bound to the nucleotide binding domain of HlyB
+    assert!(rank_of_site("example-pdbs/3b5j.pdb", 'A', 708).is_some_and(|rank| rank <= 3));
+    // ADP bound to two subunits of HslU
+    assert!(rank_of_site("example-pdbs/1yyf.pdb", 'A', 905).is_some_and(|rank| rank <= 3));
+    assert!(rank_of_site("example-pdbs/1yyf.pdb", 'B', 906).is_some_and(|rank| rank <= 3));
+}
+
+#[test]
+fn pocket_properties() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let parameters = PocketParameters::default();
+    let pockets = pdb.model(0).unwrap().detect_pockets(&parameters).unwrap();
+    assert!(!pockets.is_empty());
+    for pocket in &pockets {
+        assert_eq!(pocket.volume(), pocket.points().len() as f64);
+        assert!(pocket.volume() >= parameters.min_volume());
+        assert!(pocket.volume() <= parameters.max_volume() || pocket.buriedness() == 7.0);
+        assert!(pocket.buriedness() >= 3.0 && pocket.buriedness() <= 7.0);
+        assert!(!pocket.residues().is_empty());
+        assert!(pocket.residues().iter().all(|(chain, _)| *chain == 'A'));
+    }
+    assert_eq!(Model::new(1).detect_pockets(&parameters), Ok(Vec::new()));
+}
+
+#[test]
+#[should_panic]
+fn invalid_parameters() {
+    PocketParameters::new(1.0, 1.0, 8, 20.0, 1000.0);
+}
+
+#[test]
+fn ca_only() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j_ca.pdb").unwrap();
+    assert_eq!(
+        pdb.model(0)
+            .unwrap()
+            .detect_pockets(&PocketParameters::default())
+            .map(|pockets| pockets.len()),
+        Err(AnalysisError::CaOnlyChain('A'))
+    );
+}
@@ -0,0 +1,32 @@
+use pdbtbx::prelude::*;
+
+// A type with the same name as the context of pdbtbx errors, as defined by other crates
+#[allow(dead_code)]
+struct Context;
+
+#[test]
+fn prelude() {
+    let (pdb, errors) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .all(|error| error.level() != ErrorLevel::BreakingError));
+    assert!(validate_pdb(&pdb).is_empty());
+    save_pdb(&pdb, "dump/prelude.pdb").unwrap();
+    let (saved, _): (PDB, Vec<PDBError>) = open("dump/prelude.pdb").unwrap();
+    assert_eq!(saved.total_atom_count(), pdb.total_atom_count());
+    let _context = Context;
+    let _ = pdbtbx::PDBContext::None;
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_names() {
+    let (pdb, _) = pdbtbx::parse("example-pdbs/1ubq.pdb").unwrap();
+    assert!(pdbtbx::validate(&pdb).is_empty());
+    pdbtbx::save(&pdb, "dump/prelude_deprecated.pdb").unwrap();
+    let context = pdbtbx::Context::show("line");
+    assert_eq!(
+        context.to_string(),
+        pdbtbx::PDBContext::show("line").to_string()
+    );
+}
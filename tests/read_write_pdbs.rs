@@ -1,3 +1,5 @@
+#![allow(clippy::assign_op_pattern)]
+#![allow(deprecated)]
 use pdbtbx::*;
 use std::path::Path;
 use std::time::Instant;
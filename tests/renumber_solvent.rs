@@ -0,0 +1,113 @@
+use pdbtbx::*;
+use std::collections::HashSet;
+
+mod common;
+
+const STRUCTURE: &str = "\
+HET     NA  A 101       1
+HET    HOH  A   2       1
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C
+ATOM      2  CA  ALA A   2       3.800   0.000   0.000  1.00 10.00           C
+TER       3      ALA A   2
+ATOM      4  CA  GLY B   5      10.000   0.000   0.000  1.00 10.00           C
+TER       5      GLY B   5
+HETATM    6 NA    NA A 101      10.000   5.000   0.000  1.00 20.00          NA
+HETATM    7  O   HOH A   1      10.500   5.000   0.000  1.00 20.00           O
+HETATM    8  O   HOH A   2      20.000   5.000   0.000  1.00 20.00           O
+HETATM    9  O   HOH A 200      30.000   5.000   0.000  1.00 20.00           O
+HETATM   10  O   HOH B   5      40.000   5.000   0.000  1.00 20.00           O
+HETATM   11  O   HOH B   6      45.000   5.000   0.000  1.00 20.00           O
+END
+";
+
+fn residues(pdb: &PDB) -> Vec<(char, usize, String)> {
+    pdb.model(0)
+        .unwrap()
+        .all_chains()
+        .flat_map(|chain| {
+            chain
+                .residues()
+                .map(move |residue| (chain.id(), residue.serial_number(), residue.id()))
+        })
+        .collect()
+}
+
+#[test]
+fn collisions_are_reported() {
+    let pdb = common::read_text("solvent_collisions", STRUCTURE).0;
+    let errors = validate_solvent_numbering(&pdb);
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert_eq!(
+        errors[0].short_description(),
+        "Water residue number collision"
+    );
+    assert!(errors[0].long_description().contains("Chain A"));
+    assert!(errors[0].long_description().contains("1, 2 "));
+    assert!(errors[1].long_description().contains("Chain B"));
+    assert_eq!(
+        ValidationCheck::of(&errors[0]),
+        Some(ValidationCheck::SolventNumbering)
+    );
+}
+
+#[test]
+fn renumber_above_polymer() {
+    let mut pdb = common::read_text("solvent_renumber", STRUCTURE).0;
+    let renumbered = pdb.renumber_solvent(None);
+    assert_eq!(
+        renumbered,
+        vec![
+            ('A', 1, 102),
+            ('A', 2, 103),
+            ('A', 200, 104),
+            ('B', 5, 6),
+            ('B', 6, 7)
+        ]
+    );
+    assert!(validate_solvent_numbering(&pdb).is_empty());
+    // The HET record of the water follows it
+    let water = pdb.hets().find(|het| het.id() == "HOH").unwrap();
+    assert_eq!(water.residue_serial_number(), 103);
+    assert_eq!(
+        pdb.hets()
+            .find(|het| het.id() == "NA")
+            .unwrap()
+            .residue_serial_number(),
+        101
+    );
+    // Renumbering again changes nothing
+    assert!(pdb.renumber_solvent(None).is_empty());
+}
+
+#[test]
+fn renumber_from_start() {
+    let mut pdb = common::read_text("solvent_start", STRUCTURE).0;
+    let model = pdb.model_mut(0).unwrap();
+    model.renumber_solvent(Some(101));
+    // 101 is used by the sodium ion in chain A, so the waters skip it
+    let waters = residues(&pdb)
+        .into_iter()
+        .filter(|(_, _, name)| name == "HOH")
+        .map(|(chain, serial, _)| (chain, serial))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        waters,
+        vec![('A', 102), ('A', 103), ('A', 104), ('B', 101), ('B', 102)]
+    );
+}
+
+#[test]
+fn round_trip_is_unique() {
+    let mut pdb = common::read_text("solvent_round_trip", STRUCTURE).0;
+    pdb.renumber_solvent(None);
+    save_pdb(&pdb, "dump/solvent_round_trip_saved.pdb").unwrap();
+    let (reloaded, _) = read_pdb("dump/solvent_round_trip_saved.pdb").unwrap();
+    let residues = residues(&reloaded);
+    assert_eq!(residues.len(), 9);
+    let unique = residues
+        .iter()
+        .map(|(chain, serial, _)| (*chain, *serial))
+        .collect::<HashSet<_>>();
+    assert_eq!(unique.len(), residues.len());
+    assert!(validate_solvent_numbering(&reloaded).is_empty());
+}
@@ -0,0 +1,112 @@
+use pdbtbx::*;
+
+mod common;
+
+const REVERSED: &str = "\
+ATOM      1  C   GLY A   3       7.500   1.000   0.000  1.00 10.00           C
+ATOM      2  CA  GLY A   3       6.500   0.000   0.000  1.00 10.00           C
+ATOM      3  N   GLY A   3       5.000   0.000   0.000  1.00 10.00           N
+ATOM      4  C   GLY A   2       4.000   1.000   0.000  1.00 10.00           C
+ATOM      5  CA  GLY A   2       3.000   0.000   0.000  1.00 10.00           C
+ATOM      6  N   GLY A   2       1.500   0.000   0.000  1.00 10.00           N
+ATOM      7  C   GLY A   1       0.500   1.000   0.000  1.00 10.00           C
+ATOM      8  CA  GLY A   1      -0.500   0.000   0.000  1.00 10.00           C
+ATOM      9  N   GLY A   1      -2.000   0.000   0.000  1.00 10.00           N
+TER      10      GLY A   1
+END
+";
+
+fn read(name: &str, text: &str) -> PDB {
+    let mut options = ReadOptions::new();
+    options.set_keep_source_lines(true);
+    common::read_text_with_options(name, text, &options).0
+}
+
+#[test]
+fn reversed_chain() {
+    let mut pdb = read("residue_numbering_reversed", REVERSED);
+    let chain = pdb.chain(0).unwrap();
+    assert_eq!(chain.first_unordered_residue(), Some(1));
+    assert!(chain.is_reversed());
+    assert_eq!(
+        chain.phi_psi_pairs(),
+        Err(AnalysisError::UnorderedResidues('A', 2))
+    );
+    assert_eq!(
+        pdb.gap_report(),
+        Err(AnalysisError::UnorderedResidues('A', 2))
+    );
+
+    let errors = validate_residue_numbering(&pdb);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].short_description(), "Chain reversed");
+    assert_eq!(errors[0].level(), ErrorLevel::StrictWarning);
+    assert_eq!(errors[0].context().linenumber(), Some(4));
+    assert_eq!(
+        ValidationCheck::of(&errors[0]),
+        Some(ValidationCheck::ResidueNumbering)
+    );
+    assert_eq!(
+        validate_with(&pdb, &ValidationProfile::preset(ValidationPreset::Analysis)).len(),
+        1
+    );
+    assert!(validate_with(&pdb, &ValidationProfile::preset(ValidationPreset::Minimal)).is_empty());
+
+    pdb.chain_mut(0).unwrap().reverse_residues();
+    let chain = pdb.chain(0).unwrap();
+    assert_eq!(chain.first_unordered_residue(), None);
+    assert!(!chain.is_reversed());
+    let serials = chain
+        .atoms()
+        .map(|atom| atom.serial_number())
+        .collect::<Vec<_>>();
+    assert_eq!(serials, vec![9, 8, 7, 6, 5, 4, 3, 2, 1]);
+    let pairs = chain.phi_psi_pairs().unwrap();
+    assert_eq!(pairs.len(), 3);
+    assert!(pairs[1].0.is_some() && pairs[1].1.is_some());
+    assert_eq!(pdb.gap_report().unwrap()[0].1, Vec::new());
+    assert!(validate_residue_numbering(&pdb).is_empty());
+}
+
+#[test]
+fn misplaced_residue() {
+    let text = REVERSED
+        .lines()
+        .filter(|line| line.starts_with("ATOM"))
+        .rev()
+        .map(|line| {
+            // Swap the numbers of the last two Residues
+            if &line[22..26] == "   2" {
+                line.replace("A   2", "A   3")
+            } else if &line[22..26] == "   3" {
+                line.replace("A   3", "A   2")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut pdb = read("residue_numbering_misplaced", &text);
+    let chain = pdb.chain(0).unwrap();
+    assert_eq!(chain.first_unordered_residue(), Some(2));
+    assert!(!chain.is_reversed());
+
+    let errors = validate_residue_numbering(&pdb);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].short_description(),
+        "Residue numbering decreasing"
+    );
+    assert!(errors[0]
+        .long_description()
+        .starts_with("Residue GLY 2 (position 3) of Chain A in Model 0 follows Residue GLY 3"));
+
+    pdb.chain_mut(0).unwrap().sort_residues_by_number();
+    let chain = pdb.chain(0).unwrap();
+    let serials = chain
+        .residues()
+        .map(|residue| residue.serial_number())
+        .collect::<Vec<_>>();
+    assert_eq!(serials, vec![1, 2, 3]);
+    assert!(validate_residue_numbering(&pdb).is_empty());
+}
@@ -0,0 +1,133 @@
+use pdbtbx::*;
+use std::fs;
+
+const STRUCTURE: &str = "\
+REMARK   1 SMALL TEST SYSTEM
+CRYST1   30.000   30.000   30.000  90.00  90.00  90.00 P 1           1
+ATOM      1  N   ALA A   1       0.000   0.000   0.000  1.00 10.00           N
+ATOM      2  CA  ALA A   1       1.458   0.000   0.000  1.00 10.00
+ATOM      3  OXT ALA A   1       2.009   1.420   0.000  1.00 10.00           O1-
+TER       4      ALA A   1
+ATOM      5  N   GLY B   1       5.000   0.000   0.000  1.00 10.00           N
+ATOM      6  CA  GLY B   1       6.458   0.000   0.000  1.00 10.00           C
+TER       7      GLY B   1
+HETATM    8 ZN    ZN A 101      10.000   0.000   0.000  1.00 20.00
+HETATM    9  O   HOH A 201      12.000   0.000   0.000  1.00 20.00           O
+HETATM   10  O   HOH B 202      14.000   0.000   0.000  1.00 20.00           O
+END
+";
+
+fn structure() -> PDB {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/dialects.pdb", STRUCTURE).unwrap();
+    read_pdb("dump/dialects.pdb").unwrap().0
+}
+
+/// Save with the given dialect and return the written lines
+fn save_as(pdb: &PDB, dialect: Dialect, filename: &str) -> Vec<String> {
+    save_with_options(pdb, filename, &SaveOptions::preset(dialect)).unwrap();
+    fs::read_to_string(filename)
+        .unwrap()
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+fn records<'a>(lines: &'a [String], record: &str) -> Vec<&'a String> {
+    lines.iter().filter(|l| l.starts_with(record)).collect()
+}
+
+#[test]
+fn default_is_unchanged() {
+    assert_eq!(SaveOptions::new(), SaveOptions::default());
+    let options = SaveOptions::new();
+    assert!(!options.pad_lines());
+    assert_eq!(options.ter_policy(), TerPolicy::AfterChains);
+    assert!(options.write_elements() && options.write_charges());
+    assert!(!options.guess_missing_elements() && !options.interleave_hetero());
+}
+
+#[test]
+fn wwpdb() {
+    let pdb = structure();
+    let lines = save_as(&pdb, Dialect::Wwpdb, "dump/dialect_wwpdb.pdb");
+    assert!(lines.iter().all(|line| line.chars().count() == 80));
+    assert!(records(&lines, "ATOM")[2].ends_with("O1-"));
+    assert_eq!(records(&lines, "TER").len(), 2);
+    let (saved, _) = read_pdb("dump/dialect_wwpdb.pdb").unwrap();
+    assert_eq!(saved, pdb);
+}
+
+#[test]
+fn amber() {
+    let pdb = structure();
+    let lines = save_as(&pdb, Dialect::Amber, "dump/dialect_amber.pdb");
+    // A TER record after both chains and after the ion and both waters
+    assert_eq!(records(&lines, "TER").len(), 5);
+    let hetero_and_ter = lines
+        .iter()
+        .filter(|l| l.starts_with("HETATM") || l.starts_with("TER"))
+        .map(|l| &l[..6])
+        .collect::<Vec<_>>();
+    assert_eq!(
+        hetero_and_ter,
+        vec!["TER   ", "TER   ", "HETATM", "TER   ", "HETATM", "TER   ", "HETATM", "TER   "]
+    );
+    assert!(records(&lines, "ATOM")[2].ends_with(" O"));
+    let (saved, errors) = read_pdb("dump/dialect_amber.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .all(|e| e.level() != ErrorLevel::BreakingError));
+    assert_eq!(saved.total_atom_count(), pdb.total_atom_count());
+}
+
+#[test]
+fn gromacs() {
+    let pdb = structure();
+    let lines = save_as(&pdb, Dialect::Gromacs, "dump/dialect_gromacs.pdb");
+    let atoms = lines
+        .iter()
+        .filter(|l| l.starts_with("ATOM") || l.starts_with("HETATM"))
+        .collect::<Vec<_>>();
+    // Every Atom has an element, the missing ones are guessed, and there are no charges
+    assert!(atoms
+        .iter()
+        .all(|l| l.len() == 78 && !l[76..78].trim().is_empty()));
+    assert_eq!(&atoms[1][76..78], " C");
+    assert_eq!(&atoms[3][76..78], "ZN");
+    // Every chain id is used in one block
+    let chain_ids = atoms
+        .iter()
+        .map(|l| l.chars().nth(21).unwrap())
+        .collect::<String>();
+    assert_eq!(chain_ids, "AAAAABBB");
+    let (saved, _) = read_pdb("dump/dialect_gromacs.pdb").unwrap();
+    assert_eq!(saved.total_atom_count(), pdb.total_atom_count());
+    // The structure itself is not changed
+    assert_eq!(pdb.atom(1).unwrap().element(), "");
+}
+
+#[test]
+fn plain() {
+    let pdb = structure();
+    let lines = save_as(&pdb, Dialect::Plain, "dump/dialect_plain.pdb");
+    assert!(lines
+        .iter()
+        .filter(|l| l.starts_with("ATOM") || l.starts_with("HETATM"))
+        .all(|l| l.len() == 66));
+    let (saved, _) = read_pdb("dump/dialect_plain.pdb").unwrap();
+    assert_eq!(saved.total_atom_count(), pdb.total_atom_count());
+    assert!(saved.atoms().all(|atom| atom.element().is_empty()));
+}
+
+#[test]
+fn pdb_writer_pads_lines() {
+    let pdb = structure();
+    let mut output = Vec::new();
+    let mut writer =
+        PdbWriter::with_options(&mut output, &pdb, &SaveOptions::preset(Dialect::Wwpdb)).unwrap();
+    writer.write_model(pdb.model(0).unwrap()).unwrap();
+    writer.finish().unwrap();
+    let text = String::from_utf8(output).unwrap();
+    assert!(text.lines().all(|line| line.len() == 80));
+}
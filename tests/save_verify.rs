@@ -0,0 +1,32 @@
+use pdbtbx::*;
+
+#[test]
+fn verify_clean_files() {
+    let mut options = SaveOptions::new();
+    assert!(!options.verify());
+    assert_eq!(options.verify_level(), ErrorLevel::StrictWarning);
+    options.set_verify(true);
+    for name in ["1ubq", "3b5j"] {
+        let (pdb, _) = read_pdb(&format!("example-pdbs/{}.pdb", name)).unwrap();
+        save_with_options(&pdb, &format!("dump/{}_verified.pdb", name), &options).unwrap();
+    }
+}
+
+#[test]
+fn verify_reports_problems() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    // Swap two serial numbers, so the file is written with serial numbers out of order
+    pdb.atom_mut(0).unwrap().set_serial_number(2);
+    pdb.atom_mut(1).unwrap().set_serial_number(1);
+    let mut options = SaveOptions::new();
+    options.set_verify(true);
+    save_with_options(&pdb, "dump/verify_problems.pdb", &options).unwrap();
+
+    options.set_verify_level(ErrorLevel::LooseWarning);
+    let error = save_with_options(&pdb, "dump/verify_problems.pdb", &options).unwrap_err();
+    assert!(error.contains("does not read back cleanly"));
+    assert!(error.contains("Atom serial number out of order"));
+
+    options.set_verify(false);
+    save_with_options(&pdb, "dump/verify_problems.pdb", &options).unwrap();
+}
@@ -0,0 +1,87 @@
+use pdbtbx::*;
+use std::fs;
+
+mod common;
+
+const STRUCTURE: &str = "\
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00      PROT C
+ANISOU    1  CA  ALA A   1      100    200    300      0      0      0  PROT C
+ATOM      2  CA  ALA A   2       3.800   0.000   0.000  1.00 10.00      PROT C
+TER       3      ALA A   2
+HETATM    4  P   POP A   3      10.000   0.000   0.000  1.00 10.00      MEMB P
+HETATM    5  O   HOH A   4      20.000   0.000   0.000  1.00 10.00      W
+HETATM    6  O   HOH A   5      30.000   0.000   0.000  1.00 10.00           O
+END
+";
+
+#[test]
+fn read_and_filter() {
+    let (mut pdb, errors) = common::read_text("segment_id", STRUCTURE);
+    assert!(errors
+        .iter()
+        .all(|e| e.short_description() != "ANISOU does not match atom"));
+    let ids = pdb
+        .all_atoms()
+        .map(|atom| atom.segment_id())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["PROT", "PROT", "MEMB", "W", ""]);
+
+    pdb.remove_atoms_by(|atom| atom.segment_id() == "MEMB");
+    assert_eq!(pdb.all_atoms().count(), 4);
+    assert!(pdb.all_atoms().all(|atom| atom.segment_id() != "MEMB"));
+}
+
+#[test]
+fn set() {
+    let mut atom = Atom::new(
+        1,
+        [' ', 'C', 'A', ' '],
+        0.0,
+        0.0,
+        0.0,
+        1.0,
+        0.0,
+        [' ', 'C'],
+        0,
+    )
+    .unwrap();
+    assert_eq!(atom.segment_id(), "");
+    let plain = atom.clone();
+    atom.set_segment_id(" SOL").unwrap();
+    assert_eq!(atom.segment_id(), "SOL");
+    assert_ne!(atom, plain);
+    assert!(atom.set_segment_id("WATER").is_err());
+    assert!(atom.set_segment_id("Wé").is_err());
+    assert_eq!(atom.segment_id(), "SOL");
+    atom.set_segment_id("").unwrap();
+    assert_eq!(atom, plain);
+}
+
+#[test]
+fn save() {
+    let (pdb, _) = common::read_text("segment_id_save", STRUCTURE);
+    save_pdb(&pdb, "dump/segment_id_saved.pdb").unwrap();
+    let saved = fs::read_to_string("dump/segment_id_saved.pdb").unwrap();
+    let lines = saved
+        .lines()
+        .filter(|line| {
+            line.starts_with("ATOM") || line.starts_with("HETATM") || line.starts_with("ANISOU")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(&lines[0][72..], "PROT C");
+    assert_eq!(&lines[1][72..], "PROT C");
+    assert_eq!(&lines[3][72..], "MEMB P");
+    assert_eq!(&lines[4][72..], "W");
+    assert_eq!(&lines[5][72..], "     O");
+    let (reread, _) = read_pdb("dump/segment_id_saved.pdb").unwrap();
+    assert_eq!(reread, pdb);
+
+    save_with_options(
+        &pdb,
+        "dump/segment_id_plain.pdb",
+        &SaveOptions::preset(Dialect::Plain),
+    )
+    .unwrap();
+    let plain = fs::read_to_string("dump/segment_id_plain.pdb").unwrap();
+    assert!(!plain.contains("PROT") && !plain.contains("MEMB"));
+}
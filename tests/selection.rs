@@ -0,0 +1,149 @@
+use pdbtbx::*;
+
+#[test]
+fn ubiquitin() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let model = pdb.model(0).unwrap();
+    let hydrogens = pdb.composition().models()[0].total().element_counts()["H"];
+    assert_eq!(model.select_atoms(&Selection::calpha()).len(), 76);
+    assert_eq!(model.select_atoms(&Selection::backbone()).len(), 76 * 4);
+    assert_eq!(
+        model.select_atoms(&Selection::heavy_atoms()).len(),
+        1231 + 40 - hydrogens
+    );
+    assert_eq!(
+        model
+            .select_atoms(&Selection::polymer().and(!Selection::heavy_atoms()))
+            .len(),
+        hydrogens
+    );
+    assert_eq!(
+        model
+            .select_atoms(&Selection::backbone().or(Selection::sidechains()))
+            .len(),
+        1231 - 1 // the OXT of the C-terminus
+    );
+    assert!(model
+        .select_atoms(&Selection::sidechains())
+        .iter()
+        .all(|atom| !atom.backbone()));
+    assert!(model
+        .select_atoms(&Selection::hetero_non_water())
+        .is_empty());
+}
+
+#[test]
+fn hetero_non_water() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let model = pdb.model(0).unwrap();
+    let selected = model.select_atoms(&Selection::hetero_non_water());
+    assert_eq!(selected.len(), 42);
+}
+
+#[test]
+fn superpose() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let reference = pdb.model(0).unwrap();
+    let mut moved = reference.clone();
+    let transformation = TransformationMatrix::rotation_z(30.0)
+        .combine(&TransformationMatrix::translation(5.0, -2.0, 1.0));
+    moved.apply_transformation(&transformation);
+
+    let calpha = Selection::calpha();
+    assert!(moved.rmsd(reference, &calpha, false).unwrap() > 1.0);
+    assert!(moved.rmsd(reference, &calpha, true).unwrap() < 1e-6);
+    let superposition = moved.superpose_onto(reference, &calpha).unwrap();
+    moved.apply_transformation(&superposition);
+    assert!(
+        moved
+            .rmsd(reference, &Selection::heavy_atoms(), false)
+            .unwrap()
+            < 1e-6
+    );
+    assert!(moved
+        .superpose_onto(reference, &Selection::hetero_non_water())
+        .is_none());
+}
+
+#[test]
+fn save_filtered_selection() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    save_filtered(&pdb, "dump/selection_calpha.pdb", &Selection::calpha()).unwrap();
+    assert_eq!(pdb.total_atom_count(), 1231);
+    let (filtered, _) = read_pdb("dump/selection_calpha.pdb").unwrap();
+    assert_eq!(filtered.total_atom_count(), 76);
+    assert_eq!(filtered.total_residue_count(), 76);
+    assert!(filtered.atoms().all(|atom| atom.name() == "CA"));
+}
+
+#[test]
+fn atom_properties() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    pdb.atoms_mut().nth(3).unwrap().set_charge(-1).unwrap();
+    let model = pdb.model(0).unwrap();
+    let count = |selection: Selection| model.select_atoms(&selection).len();
+    let brute =
+        |predicate: &dyn Fn(&Atom) -> bool| model.all_atoms().filter(|a| predicate(a)).count();
+
+    assert_eq!(
+        count(Selection::element("c", false)),
+        brute(&|a| a.element() == "C")
+    );
+    assert_eq!(
+        count(Selection::element("D", true)),
+        count(!Selection::heavy_atoms())
+    );
+    assert_eq!(count(Selection::element("D", false)), 0);
+    assert_eq!(count(Selection::charge(-1)), 1);
+    assert_eq!(count(Selection::serial_numbers(1..=10)), 10);
+    assert_eq!(
+        count(Selection::b_factor_above(30.0)),
+        brute(&|a| a.b_factor() > 30.0)
+    );
+    assert_eq!(
+        count(!Selection::b_factor_below(30.0)),
+        brute(&|a| a.b_factor() >= 30.0)
+    );
+    assert_eq!(
+        count(Selection::occupancy_below(1.0)),
+        brute(&|a| a.occupancy() < 1.0)
+    );
+    assert_eq!(count(Selection::hetero()), 40);
+    assert_eq!(
+        count(Selection::residue_name("HOH").and(Selection::occupancy_above(0.5))),
+        brute(&|a| a.hetero() && a.occupancy() > 0.5)
+    );
+}
+
+#[test]
+fn within() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let model = pdb.model(0).unwrap();
+    let ligand = model
+        .all_residues()
+        .filter(|residue| residue.id() == "12D")
+        .flat_map(|residue| residue.atoms())
+        .collect::<Vec<_>>();
+    let expected = model
+        .all_atoms()
+        .filter(|atom| ligand.iter().any(|l| l.distance(atom) <= 5.0))
+        .count();
+    let near = Selection::within(5.0, Selection::residue_name("12D"));
+    assert_eq!(model.select_atoms(&near).len(), expected);
+    let pocket = near.clone().and(!Selection::residue_name("12D"));
+    assert_eq!(model.select_atoms(&pocket).len(), expected - ligand.len());
+
+    // Distance clauses can be nested
+    let shell = Selection::within(2.0, pocket.clone());
+    assert!(model.select_atoms(&shell).len() > expected - ligand.len());
+
+    // The distance clauses need the Model, so they never match on their own
+    let residue = model.residues().next().unwrap();
+    let atom = residue.atoms().next().unwrap();
+    assert!(!Selection::within(1000.0, Selection::polymer()).matches(residue, atom, false));
+
+    save_filtered(&pdb, "dump/selection_within.pdb", &pocket).unwrap();
+    let (filtered, _) = read_pdb("dump/selection_within.pdb").unwrap();
+    assert_eq!(filtered.all_atoms().count(), expected - ligand.len());
+    assert!(filtered.all_residues().all(|residue| residue.id() != "12D"));
+}
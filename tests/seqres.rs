@@ -0,0 +1,70 @@
+use pdbtbx::*;
+use std::fs;
+
+fn seqres_failed(errors: &[PDBError]) -> bool {
+    errors.iter().any(|e| format!("{}", e).contains("SEQRES"))
+}
+
+#[test]
+fn seqres_round_trip() {
+    let (pdb, errors) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    assert!(!seqres_failed(&errors));
+    assert_eq!(pdb.seqres_count(), 4);
+    assert_eq!(pdb.seqres_line_count(), 98);
+    let sequence = pdb.chain_seqres('A').unwrap();
+    assert_eq!(sequence.len(), 443);
+    assert_eq!(&sequence[..3], &["MET", "SER", "GLU"]);
+
+    let _ = fs::create_dir("dump");
+    save_pdb(&pdb, "dump/1yyf_seqres.pdb").unwrap();
+    let (reparsed, errors) = read_pdb("dump/1yyf_seqres.pdb").unwrap();
+    assert!(!seqres_failed(&errors));
+    assert_eq!(
+        reparsed.seqres().collect::<Vec<_>>(),
+        pdb.seqres().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn generate_seqres() {
+    let (mut pdb, _errors) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert_eq!(pdb.seqres_count(), 0);
+    pdb.generate_seqres(false);
+    // Only chain A contains polymer residues, the waters are skipped
+    assert_eq!(pdb.seqres_count(), 1);
+    let sequence = pdb.chain_seqres('A').unwrap();
+    assert_eq!(sequence.len(), 76);
+    assert_eq!(sequence[0], "MET");
+    assert_eq!(sequence[75], "GLY");
+
+    pdb.set_seqres('A', vec!["ALA".to_string()]);
+    pdb.generate_seqres(false);
+    assert_eq!(pdb.chain_seqres('A').unwrap().len(), 1);
+    pdb.generate_seqres(true);
+    assert_eq!(pdb.chain_seqres('A').unwrap().len(), 76);
+
+    let _ = fs::create_dir("dump");
+    save_pdb(&pdb, "dump/1ubq_seqres.pdb").unwrap();
+    let (reparsed, errors) = read_pdb("dump/1ubq_seqres.pdb").unwrap();
+    assert!(!seqres_failed(&errors));
+    assert_eq!(reparsed.chain_seqres('A'), pdb.chain_seqres('A'));
+}
+
+#[test]
+fn clone_keeps_seqres() {
+    let (pdb, _errors) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    let cloned = pdb.clone();
+    assert_eq!(cloned.seqres_count(), 4);
+    assert_eq!(
+        cloned.seqres().collect::<Vec<_>>(),
+        pdb.seqres().collect::<Vec<_>>()
+    );
+
+    let _ = fs::create_dir("dump");
+    save_pdb(&cloned, "dump/1yyf_seqres_clone.pdb").unwrap();
+    let lines = fs::read_to_string("dump/1yyf_seqres_clone.pdb").unwrap();
+    assert_eq!(
+        lines.lines().filter(|l| l.starts_with("SEQRES")).count(),
+        98
+    );
+}
@@ -0,0 +1,35 @@
+use pdbtbx::*;
+
+const UBIQUITIN: &str =
+    "MQIFVKTLTGKTITLEVEPSDTIENVKAKIQDKEGIPPDQQRLIFAGKQLEDGRTLSDYNIQKESTLHLVLRLRGG";
+
+#[test]
+fn sequence() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert_eq!(pdb.chain(0).unwrap().sequence(), UBIQUITIN);
+}
+
+#[test]
+fn find_motif() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let chain = pdb.chain(0).unwrap();
+
+    assert_eq!(chain.find_motif("LRLRGG"), vec![70]);
+    assert_eq!(chain.residue(70).unwrap().serial_number(), 71);
+    assert_eq!(chain.find_motif("tlt"), vec![6]);
+    assert_eq!(chain.find_motif("TXT"), vec![6, 11]);
+    assert_eq!(chain.find_motif("K[ST]"), vec![5, 10]);
+    assert_eq!(chain.find_motif("K[^ST]"), vec![26, 28, 32, 47, 62]);
+    assert_eq!(chain.find_motif("N[^P][ST]"), Vec::<usize>::new());
+    assert_eq!(chain.find_motif("PP"), vec![36]);
+    assert_eq!(pdb.find_motif("GG"), vec![('A', 74)]);
+}
+
+#[test]
+fn invalid_motif() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let chain = pdb.chain(0).unwrap();
+    for pattern in ["", "[ST", "K[]", "K*", "[S^T]"] {
+        assert!(chain.find_motif(pattern).is_empty(), "{}", pattern);
+    }
+}
@@ -0,0 +1,83 @@
+use pdbtbx::*;
+use std::fs;
+
+const UNORDERED: &str =
+    "ATOM      1  N   ALA A   1       0.000   0.000   0.000  1.00  0.00           N
+ATOM      2  CA  ALA A   1       1.000   0.000   0.000  1.00  0.00           C
+ATOM      2  C   ALA A   1       2.000   0.000   0.000  1.00  0.00           C
+ATOM      5  O   ALA A   1       3.000   0.000   0.000  1.00  0.00           O
+TER
+ATOM      7  N   GLY B   1       0.000   1.000   0.000  1.00  0.00           N
+ATOM      6  CA  GLY B   1       1.000   1.000   0.000  1.00  0.00           C
+TER       6      GLY B   1
+HETATM    9  O   HOH C   1       0.000   2.000   0.000  1.00  0.00           O
+END
+";
+
+fn serial_warnings(errors: &[PDBError]) -> Vec<&PDBError> {
+    errors
+        .iter()
+        .filter(|e| format!("{}", e).contains("serial number out of order"))
+        .collect()
+}
+
+#[test]
+fn unordered_serial_numbers() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/unordered_serials.pdb", UNORDERED).unwrap();
+    let (_, errors) = read_pdb("dump/unordered_serials.pdb").unwrap();
+    let warnings = serial_warnings(&errors);
+    assert!(warnings
+        .iter()
+        .all(|e| e.level() == ErrorLevel::LooseWarning));
+    // The duplicate, the gap, the decrease, the duplicate TER and the gap after the TER
+    assert_eq!(
+        warnings
+            .iter()
+            .map(|e| e.context().linenumber().unwrap())
+            .collect::<Vec<_>>(),
+        vec![3, 4, 7, 8, 9]
+    );
+    // The TER without serial number consumes one serial number
+    assert!(warnings.iter().all(|e| e.context().linenumber() != Some(6)));
+}
+
+#[test]
+fn renumbered_serial_numbers() {
+    let (mut pdb, errors) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    assert!(serial_warnings(&errors).is_empty());
+    pdb.remove_atoms_by(|atom| atom.serial_number() % 10 == 0);
+    let _ = fs::create_dir("dump");
+    save_pdb(&pdb, "dump/1yyf_gaps.pdb").unwrap();
+    let (_, errors) = read_pdb("dump/1yyf_gaps.pdb").unwrap();
+    assert!(!serial_warnings(&errors).is_empty());
+
+    pdb.renumber();
+    save_pdb(&pdb, "dump/1yyf_renumbered.pdb").unwrap();
+    let (_, errors) = read_pdb("dump/1yyf_renumbered.pdb").unwrap();
+    assert!(serial_warnings(&errors).is_empty());
+}
+
+#[test]
+fn grouped_serial_gaps() {
+    let sparse = (0..5)
+        .map(|i| {
+            format!(
+                "ATOM  {:5}  CA  ALA A{:4}    {:8.3}   0.000   0.000  1.00  0.00           C\n",
+                i * 8 + 1,
+                i + 1,
+                i as f64 * 3.8
+            )
+        })
+        .collect::<String>()
+        + "ATOM     34  CB  ALA A   5      15.200   1.000   0.000  1.00  0.00           C\nEND\n";
+    let _ = fs::create_dir("dump");
+    fs::write("dump/sparse_serials.pdb", sparse).unwrap();
+    let (_, errors) = read_pdb("dump/sparse_serials.pdb").unwrap();
+    let warnings = serial_warnings(&errors);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].context().linenumber(), Some(2));
+    assert!(
+        format!("{}", warnings[0]).contains("The 3 records following it up to serial number 33")
+    );
+}
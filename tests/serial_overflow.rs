@@ -0,0 +1,113 @@
+use pdbtbx::*;
+use std::collections::HashSet;
+use std::fs;
+
+/// 60 rotations, as found in an icosahedral capsid
+fn capsid_operators() -> Vec<TransformationMatrix> {
+    (0..60)
+        .map(|i| {
+            TransformationMatrix::rotation_z(i as f64 * 72.0)
+                .combine(&TransformationMatrix::rotation_x((i / 5) as f64 * 30.0))
+        })
+        .collect()
+}
+
+fn asymmetric_unit() -> PDB {
+    // 3b5j contains 2214 atoms, so 60 copies contain more than 100k atoms
+    let (mut pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert_eq!(pdb.all_atoms().count(), 2214);
+    // Move it away from the rotation axes, so no atoms are on a special position
+    pdb.apply_transformation(&TransformationMatrix::translation(50.0, 50.0, 50.0));
+    pdb
+}
+
+#[test]
+fn error_policy() {
+    let mut pdb = asymmetric_unit();
+    let error = pdb
+        .build_assembly(&capsid_operators(), SerialOverflowPolicy::Error)
+        .unwrap_err();
+    assert!(error.long_description().contains("build_assembly"));
+    assert!(error.long_description().contains("132840"));
+    // The PDB is left untouched
+    assert_eq!(pdb.all_atoms().count(), 2214);
+}
+
+#[test]
+fn hybrid36_policy() {
+    let mut pdb = asymmetric_unit();
+    let warnings = pdb
+        .build_assembly(&capsid_operators(), SerialOverflowPolicy::Hybrid36)
+        .unwrap();
+    assert!(warnings.is_empty());
+    assert_eq!(pdb.model_count(), 1);
+    assert_eq!(pdb.all_atoms().count(), 132_840);
+
+    let _ = fs::create_dir("dump");
+    assert!(save_with_policy(
+        &pdb,
+        "dump/capsid_hybrid36.pdb",
+        SerialOverflowPolicy::Error
+    )
+    .is_err());
+    save_pdb(&pdb, "dump/capsid_hybrid36.pdb").unwrap();
+    let (reparsed, _) = read_pdb("dump/capsid_hybrid36.pdb").unwrap();
+    assert_eq!(reparsed.all_atoms().count(), 132_840);
+    assert_eq!(
+        reparsed.all_atoms().map(|a| a.serial_number()).max(),
+        Some(132_840)
+    );
+}
+
+#[test]
+fn wrap_policy() {
+    let mut pdb = asymmetric_unit();
+    pdb.build_assembly(&capsid_operators(), SerialOverflowPolicy::WrapWithWarning)
+        .unwrap();
+    assert_eq!(pdb.model_count(), 60);
+    assert_eq!(pdb.all_atoms().count(), 132_840);
+    for model in pdb.models() {
+        let serials = model
+            .all_atoms()
+            .map(|a| a.serial_number())
+            .collect::<HashSet<usize>>();
+        assert_eq!(serials.len(), model.total_atom_count());
+    }
+
+    // All copies in a single Model cannot be numbered uniquely with five digits
+    let mut joined = PDB::new();
+    let mut model = Model::new(1);
+    for other in pdb.models() {
+        model.join(other.clone());
+    }
+    joined.add_model(model);
+    for policy in &[
+        SerialOverflowPolicy::Error,
+        SerialOverflowPolicy::WrapWithWarning,
+    ] {
+        let error = joined.renumber_with_policy(*policy).unwrap_err();
+        assert!(error.long_description().contains("renumber"));
+    }
+    assert!(joined
+        .renumber_with_policy(SerialOverflowPolicy::Hybrid36)
+        .is_ok());
+}
+
+#[test]
+fn wrap_renumbers_model() {
+    let mut pdb = asymmetric_unit();
+    for atom in pdb.all_atoms_mut() {
+        atom.set_serial_number(atom.serial_number() + 99_000);
+    }
+    let warnings = pdb
+        .join_with_policy(PDB::new(), SerialOverflowPolicy::WrapWithWarning)
+        .unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].long_description().contains("join"));
+    let serials = pdb
+        .all_atoms()
+        .map(|a| a.serial_number())
+        .collect::<HashSet<usize>>();
+    assert_eq!(serials.len(), 2214);
+    assert!(serials.iter().all(|s| *s <= 99_999));
+}
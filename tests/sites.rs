@@ -0,0 +1,93 @@
+use pdbtbx::*;
+use std::fs;
+
+const STRUCTURE: &str = "\
+SITE     2 AC1  5 HOH A   3
+SITE     1 AC1  5 ALA A   1  GLY A   2  ALA A   9  GLY B   1
+SITE     1 AC2  2 GLY A   2
+ATOM      1  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C
+ATOM      2  CA  GLY A   2       3.800   0.000   0.000  1.00 10.00           C
+TER       3      GLY A   2
+HETATM    4  O   HOH A   3      10.000   0.000   0.000  1.00 20.00           O
+END
+";
+
+fn site_records(text: &str) -> Vec<String> {
+    text.lines()
+        .filter(|line| line.starts_with("SITE"))
+        .map(|line| line.trim_end().to_string())
+        .collect()
+}
+
+#[test]
+fn parse_sites() {
+    let (pdb, errors) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .all(|e| !e.long_description().contains("SITE")));
+    assert_eq!(pdb.site_count(), 2);
+    let site = pdb.site("AC2").unwrap();
+    assert_eq!(site.residue_count(), 14);
+    assert_eq!(site.residues().next(), Some(("ILE", 'B', 17, ' ')));
+    assert_eq!(site.residues().last(), Some(("HIS", 'B', 396, ' ')));
+    assert!(site.contains('B', 343));
+    assert!(pdb.site("AC3").is_none());
+
+    let residues = pdb.site_residues("AC1");
+    assert_eq!(residues.len(), 12);
+    assert_eq!(residues[11].id(), "ARG");
+    assert!(residues.iter().flat_map(|residue| residue.atoms()).count() > 12);
+}
+
+#[test]
+fn save_sites() {
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    let _ = fs::create_dir("dump");
+    save_pdb(&pdb, "dump/1yyf_sites.pdb").unwrap();
+    let original = fs::read_to_string("example-pdbs/1yyf.pdb").unwrap();
+    let saved = fs::read_to_string("dump/1yyf_sites.pdb").unwrap();
+    assert_eq!(site_records(&saved), site_records(&original));
+
+    let (reloaded, errors) = read_pdb("dump/1yyf_sites.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .all(|e| !e.long_description().contains("SITE")));
+    assert_eq!(
+        reloaded.sites().collect::<Vec<_>>(),
+        pdb.sites().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn continued_sites() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/sites.pdb", STRUCTURE).unwrap();
+    let (mut pdb, errors) = read_pdb("dump/sites.pdb").unwrap();
+    // The lines are ordered by their sequence number
+    let site = pdb.site("AC1").unwrap();
+    assert_eq!(
+        site.residues()
+            .map(|(name, _, _, _)| name)
+            .collect::<Vec<_>>(),
+        vec!["ALA", "GLY", "ALA", "GLY", "HOH"]
+    );
+    let warning = errors
+        .iter()
+        .find(|e| e.short_description() == "Site residue count")
+        .unwrap();
+    assert!(warning.long_description().contains("site AC2 list 1"));
+
+    // Residues that are not in the structure are left out
+    let residues = pdb.site_residues("AC1");
+    assert_eq!(
+        residues
+            .iter()
+            .map(|residue| residue.serial_number())
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+
+    pdb.renumber_solvent(Some(100));
+    assert!(pdb.site("AC1").unwrap().contains('A', 100));
+    assert_eq!(pdb.site_residues("AC1").len(), 3);
+}
@@ -0,0 +1,64 @@
+use pdbtbx::*;
+use std::fs;
+
+const MODELS: &str = "MODEL        1
+ATOM      1  N   ALA A   1       0.000   0.000   0.000  1.00  0.00           N
+ATOM      2  CA  ALA A   1       1.000   0.000   0.000  1.00  0.00           C
+ENDMDL
+MODEL        2
+ATOM      1  N   ALA A   1       0.000   0.000   0.500  1.00  0.00           N
+ATOM      2  CB  ALA A   1       1.000   0.000   0.500  1.00  0.00           C
+ENDMDL
+END
+";
+
+fn keep_source_lines() -> ReadOptions {
+    let mut options = ReadOptions::new();
+    options.set_keep_source_lines(true);
+    options
+}
+
+#[test]
+fn atom_source_lines() {
+    let (pdb, _) = parse_with_options("example-pdbs/1ubq.pdb", &keep_source_lines()).unwrap();
+    assert_eq!(pdb.atom(0).unwrap().source_line(), Some(104));
+    assert_eq!(pdb.residue(1).unwrap().source_line(), Some(123));
+
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert!(pdb.all_atoms().all(|atom| atom.source_line().is_none()));
+
+    let atom = Atom::new(
+        1,
+        [' ', 'C', 'A', ' '],
+        0.0,
+        0.0,
+        0.0,
+        1.0,
+        0.0,
+        [' ', 'C'],
+        0,
+    )
+    .unwrap();
+    assert_eq!(atom.source_line(), None);
+}
+
+#[test]
+fn validation_errors_point_at_source_lines() {
+    let _ = fs::create_dir("dump");
+    fs::write("dump/source_lines.pdb", MODELS).unwrap();
+
+    let (_, errors) = read_pdb("dump/source_lines.pdb").unwrap();
+    let error = errors
+        .iter()
+        .find(|e| e.short_description() == "Atoms in Models not corresponding")
+        .unwrap();
+    assert_eq!(error.context().linenumber(), None);
+
+    let (_, errors) = parse_with_options("dump/source_lines.pdb", &keep_source_lines()).unwrap();
+    let error = errors
+        .iter()
+        .find(|e| e.short_description() == "Atoms in Models not corresponding")
+        .unwrap();
+    assert_eq!(error.context().linenumber(), Some(7));
+    assert!(error.context().text().unwrap().contains("CB  ALA"));
+}
@@ -0,0 +1,31 @@
+use pdbtbx::*;
+
+#[test]
+fn source_records() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let types = pdb.source_record_types();
+    for record in &[
+        "HEADER", "ATOM", "HETATM", "CONECT", "SEQRES", "HELIX", "END", "TER",
+    ] {
+        assert!(types.contains(*record), "{} not found", record);
+    }
+    assert!(!types.contains("ANISOU"));
+    assert!(!types.contains("MODEL"));
+    assert_eq!(pdb.source_record_count("ATOM"), 1911);
+    assert_eq!(pdb.source_record_count("HETATM"), 303);
+    assert_eq!(pdb.source_record_count("CONECT"), 42);
+    assert_eq!(pdb.source_record_count("ANISOU"), 0);
+    assert_eq!(
+        pdb.source_record_counts().len(),
+        pdb.source_record_types().len()
+    );
+    assert_eq!(pdb.clone().source_record_count("SEQRES"), 19);
+
+    let (pdb, _) = read_pdb("example-pdbs/pTLS-6484.pdb").unwrap();
+    assert!(pdb.source_record_types().contains("ANISOU"));
+
+    let (header, _) = parse_header("example-pdbs/3b5j.pdb").unwrap();
+    assert!(header.source_record_types().contains("SEQRES"));
+    assert!(!header.source_record_types().contains("ATOM"));
+    assert!(PDB::new().source_record_types().is_empty());
+}
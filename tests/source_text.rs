@@ -0,0 +1,124 @@
+use pdbtbx::*;
+use std::fs;
+
+mod common;
+
+// Coordinates with two decimals, occupancies with one and B-factors without trailing zeros, as
+// written by some programs
+const STRUCTURE: &str = "\
+ATOM      1  N   ALA A   1       -1.20    0.5    12.00   1.0  20.5           N
+ATOM      2  CA  ALA A   1       0.000   0.000   0.000  1.00 10.00           C
+ATOM      3  C   ALA A   1      1.5000 -0.1200     0.7   0.5     7           C
+HETATM    4  O   HOH A 101       10.10   -3.07    4.50  1.00  30.1           O
+";
+
+fn read(name: &str, keep: bool) -> PDB {
+    let mut options = ReadOptions::new();
+    options.set_keep_source_text(keep);
+    common::read_text_with_options(name, STRUCTURE, &options).0
+}
+
+fn atom_lines(pdb: &PDB, name: &str, prefer: bool) -> Vec<String> {
+    let path = format!("dump/{}_saved.pdb", name);
+    let mut options = SaveOptions::new();
+    options.set_prefer_source_text(prefer);
+    save_with_options(pdb, &path, &options).unwrap();
+    fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .filter(|line| line.starts_with("ATOM") || line.starts_with("HETATM"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn byte_identical_atoms() {
+    let pdb = read("source_text", true);
+    assert_eq!(
+        pdb.atoms().next().unwrap().source_text(),
+        Some(["   -1.20", "    0.5 ", "   12.00", "   1.0", "  20.5"])
+    );
+    assert_eq!(
+        atom_lines(&pdb, "source_text", true),
+        STRUCTURE.lines().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn untouched_structure() {
+    let mut options = ReadOptions::new();
+    options.set_keep_source_text(true);
+    let (pdb, _) = parse_with_options("example-pdbs/1ubq.pdb", &options).unwrap();
+    let original = fs::read_to_string("example-pdbs/1ubq.pdb")
+        .unwrap()
+        .lines()
+        .filter(|line| line.starts_with("ATOM") || line.starts_with("HETATM"))
+        .map(|line| line[30..66].to_string())
+        .collect::<Vec<_>>();
+    let saved = atom_lines(&pdb, "1ubq_source_text", true)
+        .iter()
+        .map(|line| line[30..66].to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(saved, original);
+}
+
+#[test]
+fn edited_fields_are_reformatted() {
+    let mut pdb = read("source_text_edit", true);
+    let atom = pdb.atoms_mut().next().unwrap();
+    atom.set_x(-1.25).unwrap();
+    atom.set_b_factor(21.0).unwrap();
+    let lines = atom_lines(&pdb, "source_text_edit", true);
+    assert_eq!(
+        lines[0],
+        "ATOM      1  N   ALA A   1      -1.250    0.5    12.00   1.0 21.00           N"
+    );
+    assert_eq!(
+        lines[1..],
+        STRUCTURE.lines().skip(1).collect::<Vec<_>>()[..]
+    );
+}
+
+#[test]
+fn opt_in() {
+    // Without keeping the text nothing is stored
+    let pdb = read("source_text_off", false);
+    assert!(pdb.atoms().all(|atom| atom.source_text().is_none()));
+    // Without preferring the text all fields are formatted
+    let pdb = read("source_text_formatted", true);
+    let lines = atom_lines(&pdb, "source_text_formatted", false);
+    assert_eq!(
+        lines[0],
+        "ATOM      1  N   ALA A   1      -1.200   0.500  12.000  1.00 20.50           N"
+    );
+}
+
+#[test]
+fn set_source_text() {
+    let mut atom = Atom::new(
+        1,
+        [' ', 'C', 'A', ' '],
+        1.0,
+        2.0,
+        3.0,
+        1.0,
+        10.0,
+        [' ', 'C'],
+        0,
+    )
+    .unwrap();
+    assert!(atom
+        .set_source_text(Some(["123456789", "2.0", "3.0", "1.0", "10.0"]))
+        .is_err());
+    assert!(atom
+        .set_source_text(Some(["1.0", "2.0", "3.0", "1.0000", "10.0000"]))
+        .is_err());
+    atom.set_source_text(Some(["1.0", "2.0", "3.0", "1.0", "10.0"]))
+        .unwrap();
+    assert_eq!(
+        atom.source_text(),
+        Some(["     1.0", "     2.0", "     3.0", "   1.0", "  10.0"])
+    );
+    atom.set_source_text(None).unwrap();
+    assert_eq!(atom.source_text(), None);
+}
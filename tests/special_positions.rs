@@ -0,0 +1,83 @@
+use pdbtbx::*;
+use std::fs;
+
+/// An alanine fragment, a zinc ion on the two-fold axis along b and a water just next to it,
+/// with the two-fold given both as an MTRIX record and as the space group
+const FIXTURE: &str = "\
+MTRIX1   1 -1.000000  0.000000  0.000000        0.00000
+MTRIX2   1  0.000000  1.000000  0.000000        0.00000
+MTRIX3   1  0.000000  0.000000 -1.000000        0.00000
+CRYST1   20.000   20.000   20.000  90.00  90.00  90.00 P 1 2 1       2
+ATOM      1  CA  ALA A   1       3.000   4.000   2.000  1.00 20.00           C
+ATOM      2  N   ALA A   1       4.000   4.500   2.500  1.00 20.00           N
+HETATM    3 ZN    ZN B 101       0.000   5.000   0.000  1.00 20.00          ZN
+HETATM    4  O   HOH C 201       0.020   8.000   0.010  1.00 20.00           O
+END
+";
+
+fn structure() -> PDB {
+    fs::write("dump/special_positions.pdb", FIXTURE).unwrap();
+    let (pdb, _) = read_pdb("dump/special_positions.pdb").unwrap();
+    pdb
+}
+
+fn assert_no_duplicates(model: &Model) {
+    let atoms = model.all_atoms().collect::<Vec<_>>();
+    for (index, a) in atoms.iter().enumerate() {
+        for b in &atoms[index + 1..] {
+            assert!(a.distance(b) >= 0.1, "{} and {} overlap", a, b);
+        }
+    }
+}
+
+fn skipped_warning(warnings: &[PDBError]) -> Option<&PDBError> {
+    warnings
+        .iter()
+        .find(|warning| warning.short_description() == "Special position Atoms skipped")
+}
+
+#[test]
+fn build_assembly() {
+    let mut pdb = structure();
+    let transformations = pdb.symmetry().transformations_absolute(pdb.unit_cell());
+    assert_eq!(transformations.len(), 2);
+    let warnings = pdb
+        .build_assembly(&transformations, SerialOverflowPolicy::Hybrid36)
+        .unwrap();
+    let model = pdb.model(0).unwrap();
+    assert_no_duplicates(model);
+    assert_eq!(model.total_atom_count(), 6);
+    assert_eq!(
+        model
+            .all_atoms()
+            .filter(|atom| atom.element() == "ZN")
+            .count(),
+        1
+    );
+    // The Chains of the ion and the water of the copy were left empty and are removed
+    assert_eq!(model.total_chain_count(), 4);
+    let warning = skipped_warning(&warnings).unwrap();
+    assert!(warning.long_description().starts_with("2 Atoms"));
+}
+
+#[test]
+fn expand_mtrix() {
+    let mut pdb = structure();
+    let warnings = pdb.expand_mtrix(SerialOverflowPolicy::Hybrid36).unwrap();
+    let model = pdb.model(0).unwrap();
+    assert_no_duplicates(model);
+    assert_eq!(model.total_atom_count(), 6);
+    assert!(skipped_warning(&warnings).is_some());
+}
+
+#[test]
+fn separate_models_keep_all_atoms() {
+    let mut pdb = structure();
+    let transformations = pdb.symmetry().transformations_absolute(pdb.unit_cell());
+    let warnings = pdb
+        .build_assembly(&transformations, SerialOverflowPolicy::WrapWithWarning)
+        .unwrap();
+    assert_eq!(pdb.model_count(), 2);
+    assert!(pdb.models().all(|model| model.total_atom_count() == 4));
+    assert!(skipped_warning(&warnings).is_none());
+}
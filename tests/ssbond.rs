@@ -0,0 +1,85 @@
+use pdbtbx::*;
+use std::fs;
+
+const FIXTURE: &str = "\
+SSBOND   1 CYS A    6    CYS A  127                          1555   1555  2.03
+SSBOND   2 CYS A   30A   CYS B   15                          1555   3654  2.05
+ATOM      1  SG  CYS A   6      10.000  10.000  10.000  1.00 20.00           S
+ATOM      2  SG  CYS A  30      14.000  10.000  10.000  1.00 20.00           S
+ATOM      3  SG  CYS A 127      12.030  10.000  10.000  1.00 20.00           S
+TER       4      CYS A 127
+END
+";
+
+#[test]
+fn parse_ssbonds() {
+    fs::write("dump/ssbond.pdb", FIXTURE).unwrap();
+    let (pdb, errors) = read_pdb("dump/ssbond.pdb").unwrap();
+    assert_eq!(pdb.ssbond_count(), 2);
+    let first = pdb.ssbonds().next().unwrap();
+    assert_eq!(first.serial_number(), 1);
+    assert_eq!(first.chain_ids(), ['A', 'A']);
+    assert_eq!(first.residue_serial_numbers(), [6, 127]);
+    assert_eq!(first.insertion_codes(), [' ', ' ']);
+    assert_eq!(first.symmetry_operators(), [1555, 1555]);
+    assert_eq!(first.length(), Some(2.03));
+    assert!(first.contains('A', 127));
+    let second = pdb.ssbonds().nth(1).unwrap();
+    assert_eq!(second.insertion_codes(), ['A', ' ']);
+    assert_eq!(second.symmetry_operators(), [1555, 3654]);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].level(), ErrorLevel::StrictWarning);
+    assert_eq!(errors[0].short_description(), "SSBOND residue not found");
+    assert!(errors[0].long_description().contains("B15"));
+    assert_eq!(errors[0].context().linenumber(), Some(2));
+}
+
+#[test]
+fn save_ssbonds() {
+    fs::write("dump/ssbond_input.pdb", FIXTURE).unwrap();
+    let (mut pdb, _) = read_pdb("dump/ssbond_input.pdb").unwrap();
+    let mut bond = SSBond::new(3, ['A', 'A'], [30, 127], [' ', ' ']).unwrap();
+    bond.set_length(None);
+    pdb.add_ssbond(bond);
+    save_pdb(&pdb, "dump/ssbond_output.pdb").unwrap();
+    let text = fs::read_to_string("dump/ssbond_output.pdb").unwrap();
+    let lines = text
+        .lines()
+        .filter(|line| line.starts_with("SSBOND"))
+        .collect::<Vec<_>>();
+    assert_eq!(lines[0], FIXTURE.lines().next().unwrap());
+    assert_eq!(lines[1], FIXTURE.lines().nth(1).unwrap());
+    assert_eq!(
+        lines[2],
+        "SSBOND   3 CYS A   30    CYS A  127                          1555   1555"
+    );
+    let (saved, _) = read_pdb("dump/ssbond_output.pdb").unwrap();
+    assert_eq!(
+        saved.ssbonds().collect::<Vec<_>>(),
+        pdb.ssbonds().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn renumber_ssbonds() {
+    fs::write("dump/ssbond_renumber.pdb", FIXTURE).unwrap();
+    let (mut pdb, _) = read_pdb("dump/ssbond_renumber.pdb").unwrap();
+    pdb.renumber();
+    let first = pdb.ssbonds().next().unwrap();
+    assert_eq!(first.residue_serial_numbers(), [1, 3]);
+}
+
+#[test]
+fn invalid_ssbond_is_skipped() {
+    let input = FIXTURE.replacen("CYS A    6", "CYS \t    6", 1);
+    fs::write("dump/ssbond_invalid.pdb", input).unwrap();
+    let (pdb, errors) = read_pdb("dump/ssbond_invalid.pdb").unwrap();
+    assert_eq!(pdb.ssbond_count(), 1);
+    let error = errors
+        .iter()
+        .find(|e| e.short_description() == "Invalid SSBOND")
+        .unwrap();
+    assert_eq!(error.level(), ErrorLevel::LooseWarning);
+    assert_eq!(error.context().linenumber(), Some(1));
+}
@@ -0,0 +1,17 @@
+use pdbtbx::*;
+
+#[test]
+fn homo_dimers() {
+    // 1yyf contains two homo-dimers, chains A/B and chains C/D
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    let model = pdb.model(0).unwrap();
+    assert_eq!(
+        model.cluster_chains_by_structure_ca(2.0),
+        vec![vec!['A', 'B'], vec!['D', 'C']]
+    );
+    assert_eq!(model.structurally_unique_chains(2.0), vec!['A', 'D']);
+    assert_eq!(
+        model.cluster_chains_by_structure_ca(0.0),
+        vec![vec!['A'], vec!['B'], vec!['D'], vec!['C']]
+    );
+}
@@ -0,0 +1,46 @@
+use pdbtbx::*;
+
+#[test]
+fn identical_copies() {
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    let mut copy = pdb.clone();
+    copy.apply_transformation(&TransformationMatrix::rotation_x(30.0));
+    let comparison = pdb.compare_structures(&copy);
+    assert!(comparison.sequence_identical());
+    assert!(comparison.chain_ids_match());
+    assert_eq!(comparison.residue_count_diff(), 0);
+    assert!(comparison.global_rmsd().unwrap() < 1e-6);
+    assert_eq!(comparison.per_chain_rmsd().len(), 4);
+    assert!(comparison
+        .per_chain_rmsd()
+        .iter()
+        .all(|(_, rmsd)| rmsd.unwrap() < 1e-6));
+    assert!(comparison.extra_chains_in_self().is_empty());
+    assert!(comparison.extra_chains_in_other().is_empty());
+    assert!((comparison.b_factor_correlation() - 1.0).abs() < 1e-9);
+    assert!(format!("{}", comparison).contains("Sequence identical: yes"));
+}
+
+#[test]
+fn different_structures() {
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    let mut other = pdb.clone();
+    other.remove_chains_by(|chain| chain.id() == 'D');
+    other.remove_residues_by(|residue| residue.serial_number() > 400);
+    let comparison = pdb.compare_structures(&other);
+    assert!(!comparison.sequence_identical());
+    assert!(!comparison.chain_ids_match());
+    assert!(comparison.residue_count_diff() < 0);
+    assert_eq!(comparison.extra_chains_in_self(), &['D']);
+    assert!(comparison.extra_chains_in_other().is_empty());
+    assert_eq!(
+        comparison
+            .per_chain_rmsd()
+            .iter()
+            .find(|(id, _)| *id == 'D')
+            .unwrap()
+            .1,
+        None
+    );
+    assert!(comparison.global_rmsd().unwrap() < 1e-6);
+}
@@ -0,0 +1,58 @@
+use pdbtbx::*;
+
+#[test]
+fn identical_structures() {
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let result = diff(&pdb, &pdb.clone(), 0.01);
+    assert!(result.is_empty());
+    assert_eq!(result.matched_atoms(), pdb.all_atoms().count());
+    assert_eq!(result.rms_displacement(), 0.0);
+    assert!(format!("{}", result).starts_with("No differences"));
+}
+
+#[test]
+fn prepared_structure() {
+    let (original, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let mut prepared = original.clone();
+    prepared.remove_atoms_by(|atom| atom.element() == "H");
+    prepared.remove_residues_by(|residue| residue.id() == "HOH");
+    prepared
+        .residues_mut()
+        .find(|residue| residue.serial_number() == 2)
+        .unwrap()
+        .set_id("ALA")
+        .unwrap();
+    let atom = prepared.atom_mut(0).unwrap();
+    atom.set_pos((atom.x() + 0.5, atom.y(), atom.z())).unwrap();
+    atom.set_b_factor(20.0).unwrap();
+    prepared.atom_mut(1).unwrap().set_occupancy(0.5).unwrap();
+
+    let result = diff(&original, &prepared, 0.1);
+    assert!(!result.is_empty());
+    assert!(result.added_chains().is_empty());
+    assert!(result.removed_chains().is_empty());
+    let waters = original
+        .all_residues()
+        .filter(|residue| residue.id() == "HOH")
+        .count();
+    assert_eq!(result.removed_residues().len(), waters);
+    assert!(result.removed_residues().iter().all(|r| r.1 == "HOH"));
+    assert!(result.added_residues().is_empty());
+    assert_eq!(
+        result.renamed_residues(),
+        &[(('A', 2).into(), "GLN".to_string(), "ALA".to_string())]
+    );
+    assert!(result
+        .removed_atoms()
+        .iter()
+        .all(|a| a.name().starts_with('H')));
+    assert_eq!(result.moved_atoms().len(), 1);
+    let moved = &result.moved_atoms()[0].0;
+    assert_eq!(moved.name(), "N");
+    assert_eq!(prepared.get(moved), prepared.atom(0));
+    assert!(original.get(moved).is_some());
+    assert!((result.max_displacement() - 0.5).abs() < 1e-9);
+    assert_eq!(result.changed_b_factors().len(), 1);
+    assert_eq!(result.changed_occupancies().len(), 1);
+    assert!(format!("{}", result).contains("~ residue A2 GLN -> ALA"));
+}
@@ -0,0 +1,92 @@
+use pdbtbx::*;
+use std::fs;
+
+#[test]
+fn parse_title() {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    assert_eq!(
+        pdb.title(),
+        "CRYSTAL STRUCTURES OF THE S504A MUTANT OF AN ISOLATED ABC-ATPASE IN COMPLEX WITH TNP-ADP"
+    );
+    // A word split with a hyphen is joined without space
+    let (pdb, _) = read_pdb("example-pdbs/1yyf.pdb").unwrap();
+    assert_eq!(
+        pdb.title(),
+        "CORRECTION OF X-RAY INTENSITIES FROM AN HSLV-HSLU CO-CRYSTAL CONTAINING LATTICE TRANSLOCATION DEFECTS"
+    );
+    let (pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    assert_eq!(pdb.title(), "");
+}
+
+#[test]
+fn shuffled_continuations() {
+    fs::create_dir_all("dump").unwrap();
+    fs::write(
+        "dump/title_shuffled.pdb",
+        "TITLE    3 THIRD  LINE   \n\
+         TITLE     FIRST LINE                                                            \n\
+         TITLE    2 SECOND LINE\n\
+         END\n",
+    )
+    .unwrap();
+    let (pdb, errors) = read_pdb("dump/title_shuffled.pdb").unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(pdb.title(), "FIRST LINE SECOND LINE THIRD  LINE");
+}
+
+#[test]
+fn invalid_continuation() {
+    fs::create_dir_all("dump").unwrap();
+    fs::write(
+        "dump/title_invalid.pdb",
+        "TITLE     FIRST LINE\nTITLE    X SECOND LINE\nEND\n",
+    )
+    .unwrap();
+    let (pdb, errors) = read_pdb("dump/title_invalid.pdb").unwrap();
+    assert!(errors
+        .iter()
+        .any(|error| error.short_description() == "Not a number"));
+    assert_eq!(pdb.title(), "FIRST LINE");
+}
+
+#[test]
+fn save_title() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let title = format!(
+        "A {} TITLE WITH  DOUBLE SPACES, A HYPHENATED-WORD AND {} THAT GOES ON AND ON FOR A FEW LINES",
+        "VERY-".repeat(12),
+        "X".repeat(80)
+    );
+    pdb.set_title(&title).unwrap();
+    save_pdb(&pdb, "dump/title.pdb").unwrap();
+
+    let text = fs::read_to_string("dump/title.pdb").unwrap();
+    let lines = text
+        .lines()
+        .filter(|line| line.starts_with("TITLE"))
+        .collect::<Vec<_>>();
+    assert!(lines.len() > 2);
+    assert!(lines.iter().all(|line| line.len() <= 80));
+    assert!(lines[0].starts_with("TITLE     A VERY-"));
+    for (index, line) in lines.iter().enumerate().skip(1) {
+        assert_eq!(&line[..11], format!("TITLE   {:2} ", index + 1));
+    }
+
+    let (saved, _) = read_pdb("dump/title.pdb").unwrap();
+    // Only the word longer than a line is broken without a hyphen
+    assert_eq!(
+        saved.title().replace("X X", "XX"),
+        title.replace("X X", "XX")
+    );
+    assert!(saved
+        .title()
+        .starts_with(&title[..title.find("XXX").unwrap()]));
+}
+
+#[test]
+fn invalid_title() {
+    let mut pdb = PDB::new();
+    assert!(pdb.set_title("TITLE WITH Å").is_err());
+    assert!(pdb.set_title("  PADDED TITLE  ").is_ok());
+    assert_eq!(pdb.title(), "PADDED TITLE");
+}
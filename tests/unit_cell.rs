@@ -0,0 +1,67 @@
+use pdbtbx::*;
+
+fn center(residue: &Residue) -> (f64, f64, f64) {
+    let n = residue.atom_count() as f64;
+    residue.atoms().fold((0.0, 0.0, 0.0), |acc, atom| {
+        let pos = atom.pos();
+        (acc.0 + pos.0 / n, acc.1 + pos.1 / n, acc.2 + pos.2 / n)
+    })
+}
+
+fn distance(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)).sqrt()
+}
+
+/// Open 3b5j, which has a monoclinic cell (beta = 98.4) so the axes are not orthogonal,
+/// and return the cell, the original model and the model translated by (2a, -b, 0)
+fn translated_structure() -> (UnitCell, Model, Model) {
+    let (pdb, _) = read_pdb("example-pdbs/3b5j.pdb").unwrap();
+    let cell = pdb.unit_cell().clone();
+    let original = pdb.model(0).unwrap().clone();
+    let mut translated = original.clone();
+    let shift = cell.orthogonalisation_matrix().apply((2.0, -1.0, 0.0));
+    translated.apply_transformation(&TransformationMatrix::translation(
+        shift.0, shift.1, shift.2,
+    ));
+    (cell, original, translated)
+}
+
+#[test]
+fn wrap_into_unit_cell() {
+    let (cell, mut original, mut translated) = translated_structure();
+    original.wrap_into_unit_cell(&cell);
+    translated.wrap_into_unit_cell(&cell);
+
+    let fractionalisation = cell.fractionalisation_matrix();
+    for (a, b) in original.all_residues().zip(translated.all_residues()) {
+        let f = fractionalisation.apply(center(a));
+        assert!((0.0..1.0).contains(&f.0));
+        assert!((0.0..1.0).contains(&f.1));
+        assert!((0.0..1.0).contains(&f.2));
+        assert!(distance(center(a), center(b)) < 1e-6);
+    }
+}
+
+#[test]
+fn unwrap_chains() {
+    let (cell, original, mut model) = translated_structure();
+    model.wrap_into_unit_cell(&cell);
+    model.unwrap_chains(&cell);
+
+    // The distances between consecutive residues in each polymer chain are restored, solvent
+    // chains are not contiguous to begin with
+    for (chain, original_chain) in model.chains().zip(original.chains()) {
+        let distances = |chain: &Chain| {
+            chain
+                .residues()
+                .map(center)
+                .collect::<Vec<_>>()
+                .windows(2)
+                .map(|w| distance(w[0], w[1]))
+                .collect::<Vec<f64>>()
+        };
+        for (a, b) in distances(chain).iter().zip(distances(original_chain)) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use pdbtbx::*;
+use std::fs;
+
+/// A chain with a stretch of unknown amino acids and unknown ligands, as found in low resolution structures
+const FIXTURE: &str = "\
+HET    UNL  A 101      10
+HET    UNX  A 102       1
+ATOM      1  N   ALA A   1       0.000   2.000   2.000  1.00 30.00           N
+ATOM      2  CA  ALA A   1       1.200   3.000   2.000  1.00 30.00           C
+ATOM      3  C   ALA A   1       2.400   1.000   2.000  1.00 30.00           C
+ATOM      4  O   ALA A   1       3.600   2.000   2.000  1.00 30.00           O
+ATOM      5  N   UNK A   2       4.800   3.000   2.000  1.00 30.00           N
+ATOM      6  CA  UNK A   2       6.000   1.000   2.000  1.00 30.00           C
+ATOM      7  C   UNK A   2       7.200   2.000   2.000  1.00 30.00           C
+ATOM      8  O   UNK A   2       8.400   3.000   2.000  1.00 30.00           O
+ATOM      9  N   UNK A   3       9.600   1.000   2.000  1.00 30.00           N
+ATOM     10  CA  UNK A   3      10.800   2.000   2.000  1.00 30.00           C
+ATOM     11  C   UNK A   3      12.000   3.000   2.000  1.00 30.00           C
+ATOM     12  O   UNK A   3      13.200   1.000   2.000  1.00 30.00           O
+ATOM     13  N   UNK A   4      14.400   2.000   2.000  1.00 30.00           N
+ATOM     14  CA  UNK A   4      15.600   3.000   2.000  1.00 30.00           C
+ATOM     15  C   UNK A   4      16.800   1.000   2.000  1.00 30.00           C
+ATOM     16  O   UNK A   4      18.000   2.000   2.000  1.00 30.00           O
+ATOM     17  N   GLY A   5      19.200   3.000   2.000  1.00 30.00           N
+ATOM     18  CA  GLY A   5      20.400   1.000   2.000  1.00 30.00           C
+ATOM     19  C   GLY A   5      21.600   2.000   2.000  1.00 30.00           C
+ATOM     20  O   GLY A   5      22.800   3.000   2.000  1.00 30.00           O
+TER      21      GLY A   5
+HETATM   22  C1  UNL A 101      24.000   0.500   0.500  1.00 30.00           X
+HETATM   23  C2  UNL A 101      25.500   0.500   0.500  1.00 30.00           X
+HETATM   24  UNX UNX A 102      27.000   3.000   3.000  1.00 30.00
+END
+";
+
+#[test]
+fn unknown_residues() {
+    fs::write("dump/unknown_residues.pdb", FIXTURE).unwrap();
+    let (pdb, errors) = read_pdb("dump/unknown_residues.pdb").unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert!(validate_hetatm_completeness(&pdb).is_empty());
+
+    let chain = pdb.chains().next().unwrap();
+    assert_eq!(chain.sequence(), "AXXXG");
+    assert_eq!(chain.entity_type(), EntityType::Protein);
+    assert!(!chain.has_nonstandard_residues());
+    assert_eq!(chain.modified_residues().count(), 0);
+
+    let model = pdb.model(0).unwrap();
+    assert_eq!(
+        model
+            .nonstandard_residue_types()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec!["UNL".to_string(), "UNX".to_string()]
+    );
+    assert!(model
+        .hetero_residues()
+        .all(|residue| residue.entity_type() == EntityType::Other));
+}
+
+#[test]
+fn unknown_atom_element() {
+    fs::write("dump/unknown_residues_input.pdb", FIXTURE).unwrap();
+    let (pdb, _) = read_pdb("dump/unknown_residues_input.pdb").unwrap();
+    let mut options = SaveOptions::new();
+    options.set_guess_missing_elements(true);
+    save_with_options(&pdb, "dump/unknown_residues_output.pdb", &options).unwrap();
+    let (saved, _) = read_pdb("dump/unknown_residues_output.pdb").unwrap();
+    let unx = saved
+        .hetero_atoms()
+        .find(|atom| atom.name() == "UNX")
+        .unwrap();
+    assert_eq!(unx.element(), "X");
+}
@@ -0,0 +1,117 @@
+use pdbtbx::*;
+use std::fs;
+
+const FIXTURE: &str = "\
+ATOM      1  N  AGLY A   1       0.000   0.000   0.000  0.60 10.00           N
+ATOM      2  N  BGLY A   1       0.100   0.000   0.000  0.60 10.00           N
+ATOM      3  CA  GLY A   1       1.458   0.000   0.000  1.00 10.00           C
+ATOM      3  C   GLY A   1       2.000   1.000   0.000  1.00 10.00           C
+TER       4      GLY A   1
+MASTER        0    0    0    0    0    0    0    0    9    0    0    0
+";
+
+fn parse_profile(profile: Option<ValidationProfile>) -> Vec<(String, ErrorLevel)> {
+    fs::write("dump/validation_profile.pdb", FIXTURE).unwrap();
+    let mut options = ReadOptions::new();
+    options.set_validation_profile(profile);
+    let (_, errors) = parse_with_options("dump/validation_profile.pdb", &options).unwrap();
+    errors
+        .iter()
+        .map(|e| (e.short_description().to_string(), e.level()))
+        .collect()
+}
+
+#[test]
+fn default() {
+    let errors = parse_profile(None);
+    assert_eq!(
+        errors,
+        vec![
+            (
+                "Atom serial number out of order".to_string(),
+                ErrorLevel::LooseWarning
+            ),
+            (
+                "MASTER checksum failed".to_string(),
+                ErrorLevel::StrictWarning
+            ),
+        ]
+    );
+}
+
+#[test]
+fn presets() {
+    let deposition = parse_profile(Some(ValidationProfile::preset(
+        ValidationPreset::Deposition,
+    )));
+    let names = deposition.iter().map(|e| e.0.as_str()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            "Atom serial number out of order",
+            "MASTER checksum failed",
+            "Missing END record",
+            "Conformer occupancies above one",
+            "Identical B-factors"
+        ]
+    );
+
+    let analysis = parse_profile(Some(ValidationProfile::preset(ValidationPreset::Analysis)));
+    assert_eq!(
+        analysis,
+        vec![
+            (
+                "Atom serial number out of order".to_string(),
+                ErrorLevel::GeneralWarning
+            ),
+            (
+                "Conformer occupancies above one".to_string(),
+                ErrorLevel::StrictWarning
+            ),
+        ]
+    );
+
+    let minimal = parse_profile(Some(ValidationProfile::preset(ValidationPreset::Minimal)));
+    assert!(minimal.is_empty(), "{:?}", minimal);
+}
+
+#[test]
+fn custom() {
+    let mut profile = ValidationProfile::new();
+    profile.add_check(ValidationCheck::MasterChecksum);
+    profile.set_level(ValidationCheck::MasterChecksum, ErrorLevel::LooseWarning);
+    assert!(profile.runs(ValidationCheck::MasterChecksum));
+    assert!(!profile.runs(ValidationCheck::SerialOrder));
+    assert_eq!(
+        parse_profile(Some(profile)),
+        vec![(
+            "MASTER checksum failed".to_string(),
+            ErrorLevel::LooseWarning
+        )]
+    );
+}
+
+#[test]
+fn validate_with_minimal() {
+    let (mut pdb, _) = read_pdb("example-pdbs/1ubq.pdb").unwrap();
+    let mut second = pdb.model(0).unwrap().clone();
+    second.set_serial_number(2);
+    second.remove_atoms_by(|atom| atom.serial_number() == 1);
+    pdb.add_model(second);
+    let minimal = ValidationProfile::preset(ValidationPreset::Minimal);
+    let descriptions = |errors: Vec<PDBError>| {
+        errors
+            .iter()
+            .map(|e| e.short_description().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        descriptions(validate_pdb(&pdb)),
+        vec!["Invalid Model".to_string()]
+    );
+    assert_eq!(
+        descriptions(validate_with(&pdb, &minimal)),
+        descriptions(validate_pdb(&pdb))
+    );
+    assert!(validate_with(&pdb, &ValidationProfile::new()).is_empty());
+}
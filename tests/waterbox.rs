@@ -1,3 +1,5 @@
+#![allow(clippy::clone_on_copy)]
+#![allow(deprecated)]
 use pdbtbx::*;
 use std::time::Instant;
 